@@ -1,20 +1,138 @@
-use crate::auth::Access;
-use crate::errors::{ApiError, AuthError};
-use dozer_cache::cache::expression::QueryExpression;
-use dozer_cache::cache::RecordWithId;
+use crate::auth::{authorize_filter, authorize_query, mask_record, Access};
+use crate::errors::ApiError;
+use dozer_cache::cache::expression::{FilterExpression, Operator, QueryExpression};
+use dozer_cache::cache::{index, RecordWithId};
+use dozer_cache::errors::CacheError;
 use dozer_cache::{AccessFilter, CacheReader};
-use dozer_types::types::Schema;
+use dozer_tracing::metrics::{record_request, RequestOperation};
+use dozer_types::types::{Field, Schema};
 
 pub fn get_record(
     cache_reader: &CacheReader,
+    endpoint_name: &str,
     key: &[u8],
     access: Option<Access>,
 ) -> Result<RecordWithId, ApiError> {
-    let access_filter = get_access_filter(access)?;
-    let record = cache_reader
-        .get(key, &access_filter)
-        .map_err(ApiError::NotFound)?;
-    Ok(record)
+    record_request(endpoint_name, RequestOperation::Get, || {
+        let access_filter = get_access_filter(access, endpoint_name)?;
+        let mut record = cache_reader
+            .get(key, &access_filter)
+            .map_err(ApiError::NotFound)?;
+        let schema = &cache_reader
+            .get_schema_and_indexes_by_name(endpoint_name)
+            .map_err(ApiError::SchemaNotFound)?
+            .0;
+        if !is_visible(cache_reader, endpoint_name, schema, &access_filter, &record)? {
+            return Err(ApiError::NotFound(CacheError::PrimaryKeyNotFound));
+        }
+        mask_record(&access_filter, schema, &mut record.record);
+        Ok(record)
+    })
+}
+
+/// Gets the records of `keys`, `None` for the keys that are not found or that `access` can't see.
+pub fn get_records_by_keys(
+    cache_reader: &CacheReader,
+    endpoint_name: &str,
+    keys: &[Vec<u8>],
+    access: Option<Access>,
+) -> Result<Vec<Option<RecordWithId>>, ApiError> {
+    record_request(endpoint_name, RequestOperation::GetMany, || {
+        let access_filter = get_access_filter(access, endpoint_name)?;
+        let records = cache_reader
+            .get_many(keys, &access_filter)
+            .map_err(ApiError::QueryFailed)?;
+        let schema = &cache_reader
+            .get_schema_and_indexes_by_name(endpoint_name)
+            .map_err(ApiError::SchemaNotFound)?
+            .0;
+        records
+            .into_iter()
+            .map(|record| {
+                let Some(mut record) = record else {
+                    return Ok(None);
+                };
+                if !is_visible(cache_reader, endpoint_name, schema, &access_filter, &record)? {
+                    return Ok(None);
+                }
+                mask_record(&access_filter, schema, &mut record.record);
+                Ok(Some(record))
+            })
+            .collect()
+    })
+}
+
+/// Looks up the records of `endpoint_name` whose primary key is the `key` field of `records`, which have `schema`.
+/// `None` where the key is null or there's no record that `access` can see.
+///
+/// All the keys are got in one `get_many`, so the records are of a single snapshot of the cache.
+pub fn lookup_records<'a>(
+    cache_reader: &'a CacheReader,
+    endpoint_name: &str,
+    schema: &Schema,
+    records: &[RecordWithId],
+    key: &str,
+    access: Option<Access>,
+) -> Result<(&'a Schema, Vec<Option<RecordWithId>>), ApiError> {
+    let key_index = schema
+        .fields
+        .iter()
+        .position(|field| field.name == key)
+        .ok_or_else(|| ApiError::InvalidLookup(format!("unknown field {key}")))?;
+    let lookup_schema = &cache_reader
+        .get_schema_and_indexes_by_name(endpoint_name)
+        .map_err(ApiError::SchemaNotFound)?
+        .0;
+    match lookup_schema.primary_index.len() {
+        0 => return Err(ApiError::NoPrimaryKey),
+        1 => {}
+        _ => return Err(ApiError::MultiIndexFetch(endpoint_name.to_string())),
+    }
+
+    let keys = records
+        .iter()
+        .map(|record| match record.record.values[key_index] {
+            Field::Null => None,
+            _ => Some(index::get_primary_key(&[key_index], &record.record.values)),
+        })
+        .collect::<Vec<_>>();
+    let batch = keys.iter().flatten().cloned().collect::<Vec<_>>();
+    let mut found = get_records_by_keys(cache_reader, endpoint_name, &batch, access)?.into_iter();
+    let records = keys
+        .iter()
+        .map(|key| key.as_ref().and_then(|_| found.next().flatten()))
+        .collect();
+    Ok((lookup_schema, records))
+}
+
+/// Whether `record` satisfies the filter of the access, which the cache evaluates.
+fn is_visible(
+    cache_reader: &CacheReader,
+    endpoint_name: &str,
+    schema: &Schema,
+    access_filter: &AccessFilter,
+    record: &RecordWithId,
+) -> Result<bool, ApiError> {
+    if access_filter.filter.is_none() {
+        return Ok(true);
+    }
+    let primary_key = schema
+        .primary_index
+        .iter()
+        .map(|index| {
+            FilterExpression::Simple(
+                schema.fields[*index].name.clone(),
+                Operator::EQ,
+                record.record.values[*index].to_json(),
+            )
+        })
+        .collect();
+    let mut exp = QueryExpression::with_no_limit();
+    exp.filter = Some(FilterExpression::And(primary_key));
+    let count = cache_reader
+        .count(endpoint_name, &mut exp, access_filter.clone())
+        .map_err(ApiError::CountFailed)?;
+    Ok(count > 0)
 }
 
 pub fn get_records_count(
@@ -23,10 +141,13 @@ pub fn get_records_count(
     exp: &mut QueryExpression,
     access: Option<Access>,
 ) -> Result<usize, ApiError> {
-    let access_filter = get_access_filter(access)?;
-    cache_reader
-        .count(endpoint_name, exp, access_filter)
-        .map_err(ApiError::CountFailed)
+    record_request(endpoint_name, RequestOperation::Count, || {
+        let access_filter = get_access_filter(access, endpoint_name)?;
+        authorize_query(&access_filter, exp)?;
+        cache_reader
+            .count(endpoint_name, exp, access_filter)
+            .map_err(ApiError::CountFailed)
+    })
 }
 
 /// Get multiple records
@@ -36,24 +157,50 @@ pub fn get_records<'a>(
     exp: &mut QueryExpression,
     access: Option<Access>,
 ) -> Result<(&'a Schema, Vec<RecordWithId>), ApiError> {
-    let access_filter = get_access_filter(access)?;
-    cache_reader
-        .query(endpoint_name, exp, access_filter)
-        .map_err(ApiError::QueryFailed)
-}
-
-fn get_access_filter(access: Option<Access>) -> Result<AccessFilter, ApiError> {
-    match access {
-        None | Some(Access::All) => Ok(AccessFilter {
-            filter: None,
-            fields: vec![],
-        }),
-        Some(Access::Custom(mut access_filters)) => {
-            if let Some(access_filter) = access_filters.remove("get_records") {
-                Ok(access_filter)
-            } else {
-                Err(ApiError::ApiAuthError(AuthError::InvalidToken))
-            }
+    record_request(endpoint_name, RequestOperation::Query, || {
+        let access_filter = get_access_filter(access, endpoint_name)?;
+        authorize_query(&access_filter, exp)?;
+        let (schema, mut records) = cache_reader
+            .query(endpoint_name, exp, access_filter.clone())
+            .map_err(ApiError::QueryFailed)?;
+        for record in &mut records {
+            mask_record(&access_filter, schema, &mut record.record);
         }
-    }
+        Ok((schema, records))
+    })
+}
+
+/// Authorizes a subscription to the events of `endpoint_name`, returning the filter of the events that `access` may see
+/// and the indexes of the fields it can't see.
+pub fn authorize_events(
+    schema: &Schema,
+    endpoint_name: &str,
+    filter: Option<FilterExpression>,
+    access: Option<Access>,
+) -> Result<(Option<FilterExpression>, Vec<usize>), ApiError> {
+    let access_filter = get_access_filter(access, endpoint_name)?;
+    authorize_filter(&access_filter, filter.as_ref())?;
+    let masked_fields = schema
+        .fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| access_filter.fields.contains(&field.name))
+        .map(|(index, _)| index)
+        .collect();
+    let filter = match (access_filter.filter, filter) {
+        (Some(access_filter), Some(filter)) => {
+            Some(FilterExpression::And(vec![access_filter, filter]))
+        }
+        (access_filter, filter) => access_filter.or(filter),
+    };
+    Ok((filter, masked_fields))
+}
+
+fn get_access_filter(
+    access: Option<Access>,
+    endpoint_name: &str,
+) -> Result<AccessFilter, ApiError> {
+    Ok(access
+        .unwrap_or(Access::All)
+        .into_access_filter(endpoint_name)?)
 }
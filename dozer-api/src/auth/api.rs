@@ -11,7 +11,7 @@ use dozer_types::{
 
 use crate::errors::{ApiError, AuthError};
 
-use super::{Access, Authorizer};
+use super::{authenticate, Access, Authorizer};
 
 pub async fn auth_route(
     access: Option<ReqData<Access>>,
@@ -44,6 +44,8 @@ fn get_secret(req: &HttpRequest) -> Result<&str, AuthError> {
 
     match api_security {
         ApiSecurity::Jwt(secret) => Ok(secret.as_str()),
+        // API keys are not tokens that can be generated.
+        ApiSecurity::ApiKeys(_) => Err(AuthError::Unauthorized),
     }
 }
 pub async fn validate(
@@ -53,21 +55,15 @@ pub async fn validate(
     let api_security = req
         .app_data::<ApiSecurity>()
         .expect("We only validate bearer tokens if ApiSecurity is set");
-    match api_security {
-        ApiSecurity::Jwt(secret) => {
-            let api_auth = Authorizer::new(secret, None, None);
-            let res = api_auth
-                .validate_token(credentials.token())
-                .map_err(|e| (Error::from(ApiError::ApiAuthError(e))));
+    let res = authenticate(api_security, credentials.token())
+        .map_err(|e| (Error::from(ApiError::ApiAuthError(e))));
 
-            match res {
-                Ok(claims) => {
-                    // Provide access to all
-                    req.extensions_mut().insert(claims.access);
-                    Ok(req)
-                }
-                Err(e) => Err((e, req)),
-            }
+    match res {
+        Ok(access) => {
+            // Provide the access of the token
+            req.extensions_mut().insert(access);
+            Ok(req)
         }
+        Err(e) => Err((e, req)),
     }
 }
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use dozer_cache::cache::expression::FilterExpression;
+use dozer_cache::AccessFilter;
+use dozer_types::models::api_security::{ApiKey, ApiKeys};
+use dozer_types::serde_json;
+
+use crate::errors::AuthError;
+
+use super::Access;
+
+/// Finds the key of `token` and returns its access.
+pub fn authenticate(api_keys: &ApiKeys, token: &str) -> Result<Access, AuthError> {
+    api_keys
+        .keys
+        .iter()
+        .find(|api_key| keys_equal(api_key.key.as_bytes(), token.as_bytes()))
+        .ok_or(AuthError::InvalidToken)
+        .and_then(access)
+}
+
+fn access(api_key: &ApiKey) -> Result<Access, AuthError> {
+    if api_key.endpoints.is_empty() {
+        return Ok(Access::All);
+    }
+    let access_filters = api_key
+        .endpoints
+        .iter()
+        .map(|endpoint| {
+            let filter = endpoint
+                .filter
+                .as_deref()
+                .map(serde_json::from_str::<FilterExpression>)
+                .transpose()
+                .map_err(|e| AuthError::InternalError(Box::new(e)))?;
+            let filter_fields =
+                (!endpoint.filter_fields.is_empty()).then(|| endpoint.filter_fields.clone());
+            Ok((
+                endpoint.name.clone(),
+                AccessFilter {
+                    filter,
+                    fields: endpoint.masked_fields.clone(),
+                    filter_fields,
+                },
+            ))
+        })
+        .collect::<Result<HashMap<_, _>, AuthError>>()?;
+    Ok(Access::Custom(access_filters))
+}
+
+/// Compares in constant time, so the time to reject a token doesn't tell how much of a key it guessed.
+fn keys_equal(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_cache::cache::expression::Operator;
+    use dozer_types::models::api_security::ApiKeyEndpoint;
+
+    use super::*;
+
+    #[test]
+    fn authenticate_api_keys() {
+        let api_keys = ApiKeys {
+            keys: vec![
+                ApiKey {
+                    key: "admin-key".to_string(),
+                    endpoints: vec![],
+                },
+                ApiKey {
+                    key: "films-key".to_string(),
+                    endpoints: vec![ApiKeyEndpoint {
+                        name: "films".to_string(),
+                        filter_fields: vec!["film_id".to_string()],
+                        filter: Some(r#"{"release_year": 2006}"#.to_string()),
+                        masked_fields: vec!["rental_rate".to_string()],
+                    }],
+                },
+            ],
+        };
+        assert_eq!(authenticate(&api_keys, "admin-key").unwrap(), Access::All);
+
+        let Access::Custom(access_filters) = authenticate(&api_keys, "films-key").unwrap() else {
+            panic!("Must be restricted to films");
+        };
+        assert_eq!(access_filters.len(), 1);
+        assert_eq!(
+            access_filters["films"],
+            AccessFilter {
+                filter: Some(FilterExpression::Simple(
+                    "release_year".to_string(),
+                    Operator::EQ,
+                    2006.into()
+                )),
+                fields: vec!["rental_rate".to_string()],
+                filter_fields: Some(vec!["film_id".to_string()]),
+            }
+        );
+
+        assert!(matches!(
+            authenticate(&api_keys, "films-kez"),
+            Err(AuthError::InvalidToken)
+        ));
+        assert!(authenticate(&api_keys, "").is_err());
+    }
+}
@@ -1,4 +1,3 @@
-use dozer_types::models::api_security::ApiSecurity;
 use jsonwebtoken::{
     decode, encode, errors::ErrorKind, Algorithm, DecodingKey, EncodingKey, Header, Validation,
 };
@@ -76,14 +75,6 @@ impl<'a> Authorizer<'a> {
         }
     }
 }
-
-impl<'a> From<&'a ApiSecurity> for Authorizer<'a> {
-    fn from(value: &'a ApiSecurity) -> Self {
-        match value {
-            ApiSecurity::Jwt(secret) => Authorizer::new(secret, None, None),
-        }
-    }
-}
 #[cfg(test)]
 mod tests {
     use super::Access;
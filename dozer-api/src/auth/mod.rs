@@ -1,9 +1,15 @@
 use std::collections::HashMap;
 
+use dozer_cache::cache::expression::{FilterExpression, QueryExpression};
 use dozer_cache::AccessFilter;
+use dozer_types::models::api_security::ApiSecurity;
 use dozer_types::serde;
+use dozer_types::types::{Field, Record, Schema};
 use serde::{Deserialize, Serialize};
+
+use crate::errors::AuthError;
 pub mod api;
+mod api_key;
 pub mod authorizer;
 pub use authorizer::Authorizer;
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
@@ -24,14 +30,102 @@ pub enum Access {
     Custom(HashMap<String, AccessFilter>),
 }
 
+impl Access {
+    /// The `AccessFilter` of `endpoint_name`, if this access covers it.
+    pub fn into_access_filter(self, endpoint_name: &str) -> Result<AccessFilter, AuthError> {
+        match self {
+            Access::All => Ok(AccessFilter {
+                filter: None,
+                fields: vec![],
+                filter_fields: None,
+            }),
+            Access::Custom(mut access_filters) => access_filters
+                .remove(endpoint_name)
+                .ok_or_else(|| AuthError::EndpointForbidden(endpoint_name.to_string())),
+        }
+    }
+}
+
+/// Validates a bearer token against `security`, returning the access it grants.
+pub fn authenticate(security: &ApiSecurity, token: &str) -> Result<Access, AuthError> {
+    match security {
+        ApiSecurity::Jwt(secret) => Authorizer::new(secret, None, None)
+            .validate_token(token)
+            .map(|claims| claims.access),
+        ApiSecurity::ApiKeys(api_keys) => api_key::authenticate(api_keys, token),
+    }
+}
+
+/// Checks that `filter` only filters on the `filter_fields` of `access_filter`, and not on its masked `fields`.
+pub fn authorize_filter(
+    access_filter: &AccessFilter,
+    filter: Option<&FilterExpression>,
+) -> Result<(), AuthError> {
+    match filter {
+        Some(filter) => check_filter_fields(filter, access_filter),
+        None => Ok(()),
+    }
+}
+
+/// Checks the filter of `query` like `authorize_filter`, and that it doesn't sort on masked fields.
+pub fn authorize_query(
+    access_filter: &AccessFilter,
+    query: &QueryExpression,
+) -> Result<(), AuthError> {
+    authorize_filter(access_filter, query.filter.as_ref())?;
+    match query
+        .order_by
+        .0
+        .iter()
+        .find(|sort_option| access_filter.fields.contains(&sort_option.field_name))
+    {
+        Some(sort_option) => Err(AuthError::FilterFieldForbidden(
+            sort_option.field_name.clone(),
+        )),
+        None => Ok(()),
+    }
+}
+
+fn check_filter_fields(
+    filter: &FilterExpression,
+    access_filter: &AccessFilter,
+) -> Result<(), AuthError> {
+    match filter {
+        FilterExpression::Simple(field_name, _, _) => {
+            let allowed = access_filter
+                .filter_fields
+                .as_ref()
+                .map_or(true, |filter_fields| filter_fields.contains(field_name));
+            if allowed && !access_filter.fields.contains(field_name) {
+                Ok(())
+            } else {
+                Err(AuthError::FilterFieldForbidden(field_name.clone()))
+            }
+        }
+        FilterExpression::And(filters) => filters
+            .iter()
+            .try_for_each(|filter| check_filter_fields(filter, access_filter)),
+    }
+}
+
+/// Masks the `fields` of `access_filter` in `record` as null.
+pub fn mask_record(access_filter: &AccessFilter, schema: &Schema, record: &mut Record) {
+    for (field_definition, value) in schema.fields.iter().zip(record.values.iter_mut()) {
+        if access_filter.fields.contains(&field_definition.name) {
+            *value = Field::Null;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
+    use dozer_cache::cache::expression::{Operator, SortDirection, SortOption};
     use dozer_cache::AccessFilter;
     use dozer_types::serde_json::json;
 
-    use super::Access;
+    use super::*;
 
     #[test]
     fn serialize_access() {
@@ -41,6 +135,7 @@ mod tests {
             AccessFilter {
                 filter: None,
                 fields: vec![],
+                filter_fields: None,
             },
         );
         let access = Access::Custom(access_map);
@@ -55,4 +150,73 @@ mod tests {
 
         assert_eq!(de_access.unwrap(), access, "are equal");
     }
+
+    #[test]
+    fn access_filter_of_endpoint() {
+        let access_filter = AccessFilter {
+            filter: None,
+            fields: vec![],
+            filter_fields: Some(vec!["film_id".to_string()]),
+        };
+        let access = Access::Custom(HashMap::from([(
+            "films".to_string(),
+            access_filter.clone(),
+        )]));
+        assert_eq!(
+            access.clone().into_access_filter("films").unwrap(),
+            access_filter
+        );
+        assert!(matches!(
+            access.into_access_filter("actors"),
+            Err(AuthError::EndpointForbidden(endpoint)) if endpoint == "actors"
+        ));
+        assert!(Access::All.into_access_filter("actors").is_ok());
+    }
+
+    #[test]
+    fn authorize_filter_fields() {
+        let access_filter = AccessFilter {
+            filter: None,
+            fields: vec![],
+            filter_fields: Some(vec!["film_id".to_string()]),
+        };
+        let simple = |field_name: &str| {
+            FilterExpression::Simple(field_name.to_string(), Operator::EQ, json!(1))
+        };
+        assert!(authorize_filter(&access_filter, None).is_ok());
+        assert!(authorize_filter(&access_filter, Some(&simple("film_id"))).is_ok());
+        assert!(matches!(
+            authorize_filter(
+                &access_filter,
+                Some(&FilterExpression::And(vec![simple("film_id"), simple("rental_rate")]))
+            ),
+            Err(AuthError::FilterFieldForbidden(field_name)) if field_name == "rental_rate"
+        ));
+
+        let all_fields = AccessFilter {
+            filter_fields: None,
+            ..access_filter
+        };
+        assert!(authorize_filter(&all_fields, Some(&simple("rental_rate"))).is_ok());
+
+        // Masked fields can't be filtered or sorted on.
+        let masked = AccessFilter {
+            fields: vec!["rental_rate".to_string()],
+            ..all_fields
+        };
+        assert!(authorize_filter(&masked, Some(&simple("rental_rate"))).is_err());
+        let query = QueryExpression::new(
+            None,
+            vec![SortOption::new(
+                "rental_rate".to_string(),
+                SortDirection::Descending,
+            )],
+            None,
+            Default::default(),
+        );
+        assert!(matches!(
+            authorize_query(&masked, &query),
+            Err(AuthError::FilterFieldForbidden(field_name)) if field_name == "rental_rate"
+        ));
+    }
 }
@@ -39,6 +39,12 @@ pub enum ApiError {
     TypeError(#[from] TypeError),
     #[error("Failed to bind to address {0}: {1}")]
     FailedToBindToAddress(String, #[source] std::io::Error),
+    #[error("Events are not enabled. This is currently an experimental feature. Enable it in the config.")]
+    EventsNotEnabled,
+    #[error("Too many requests: the {0} limit is reached")]
+    TooManyRequests(&'static str),
+    #[error("Invalid lookup: {0}")]
+    InvalidLookup(String),
 }
 
 impl ApiError {
@@ -77,7 +83,16 @@ impl From<GrpcError> for tonic::Status {
 
 impl From<ApiError> for tonic::Status {
     fn from(input: ApiError) -> Self {
-        tonic::Status::new(tonic::Code::Unknown, input.to_string())
+        let code = match input {
+            ApiError::ApiAuthError(
+                AuthError::EndpointForbidden(_) | AuthError::FilterFieldForbidden(_),
+            ) => tonic::Code::PermissionDenied,
+            ApiError::ApiAuthError(_) => tonic::Code::Unauthenticated,
+            ApiError::TooManyRequests(_) => tonic::Code::ResourceExhausted,
+            ApiError::InvalidLookup(_) => tonic::Code::InvalidArgument,
+            _ => tonic::Code::Unknown,
+        };
+        tonic::Status::new(code, input.to_string())
     }
 }
 
@@ -125,6 +140,10 @@ pub enum AuthError {
     InvalidToken,
     #[error("Issuer is invalid")]
     InvalidIssuer,
+    #[error("Cannot access endpoint {0}")]
+    EndpointForbidden(String),
+    #[error("Cannot filter on field {0}")]
+    FilterFieldForbidden(String),
     #[error("Internal error: {0}")]
     InternalError(#[from] BoxedError),
 }
@@ -138,7 +157,10 @@ impl actix_web::error::ResponseError for ApiError {
 
     fn status_code(&self) -> StatusCode {
         match *self {
-            ApiError::TypeError(_) => StatusCode::BAD_REQUEST,
+            ApiError::TypeError(_) | ApiError::InvalidLookup(_) => StatusCode::BAD_REQUEST,
+            ApiError::ApiAuthError(
+                AuthError::EndpointForbidden(_) | AuthError::FilterFieldForbidden(_),
+            ) => StatusCode::FORBIDDEN,
             ApiError::ApiAuthError(_) => StatusCode::UNAUTHORIZED,
             ApiError::NotFound(_) => StatusCode::NOT_FOUND,
             ApiError::SchemaNotFound(_) | ApiError::NoPrimaryKey | ApiError::MultiIndexFetch(_) => {
@@ -150,6 +172,8 @@ impl actix_web::error::ResponseError for ApiError {
             | ApiError::QueryFailed(_)
             | ApiError::CountFailed(_)
             | ApiError::FailedToBindToAddress(_, _) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::EventsNotEnabled => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
         }
     }
 }
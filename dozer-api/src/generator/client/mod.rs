@@ -0,0 +1,165 @@
+//! Typed clients of the REST API, generated from the schemas of the endpoints.
+
+use crate::errors::GenerationError;
+use dozer_types::serde::{self, Serialize};
+use dozer_types::types::{FieldType, Schema};
+use handlebars::Handlebars;
+use inflector::Inflector;
+
+#[cfg(test)]
+mod tests;
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientLanguage {
+    Rust,
+    TypeScript,
+}
+
+impl ClientLanguage {
+    fn template(&self) -> &'static str {
+        match self {
+            ClientLanguage::Rust => include_str!("template/rust.tmpl"),
+            ClientLanguage::TypeScript => include_str!("template/typescript.tmpl"),
+        }
+    }
+
+    /// The type of a value of `typ`, as `Field::to_json` writes it.
+    fn value_type(&self, typ: FieldType) -> &'static str {
+        match (self, typ) {
+            (ClientLanguage::Rust, FieldType::UInt) => "u64",
+            (ClientLanguage::Rust, FieldType::Int) => "i64",
+            (ClientLanguage::Rust, FieldType::Float) => "f64",
+            (ClientLanguage::Rust, FieldType::Boolean) => "bool",
+            (ClientLanguage::Rust, FieldType::Point) => "Point",
+            (ClientLanguage::Rust, _) => "String",
+            (ClientLanguage::TypeScript, FieldType::UInt | FieldType::Int | FieldType::Float) => {
+                "number"
+            }
+            (ClientLanguage::TypeScript, FieldType::Boolean) => "boolean",
+            (ClientLanguage::TypeScript, FieldType::Point) => "Point",
+            (ClientLanguage::TypeScript, _) => "string",
+        }
+    }
+
+    fn field_type(&self, typ: FieldType, nullable: bool) -> String {
+        let value_type = self.value_type(typ);
+        match (self, nullable) {
+            (_, false) => value_type.to_string(),
+            (ClientLanguage::Rust, true) => format!("Option<{value_type}>"),
+            (ClientLanguage::TypeScript, true) => format!("{value_type} | null"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "self::serde")]
+struct ClientMetadata {
+    endpoints: Vec<EndpointMetadata>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "self::serde")]
+struct EndpointMetadata {
+    name: String,
+    path: String,
+    pascal_name: String,
+    plural_pascal_name: String,
+    snake_name: String,
+    singular_snake_name: String,
+    fields: Vec<FieldMetadata>,
+    /// The field of the primary key, if it has exactly one, so that records can be got by it.
+    primary_key: Option<FieldMetadata>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "self::serde")]
+struct FieldMetadata {
+    name: String,
+    /// The name as a Rust identifier, for struct fields and builder methods.
+    ident: String,
+    renamed: bool,
+    typ: String,
+    value_type: String,
+}
+
+/// Generates a client of every endpoint added, in one source file.
+pub struct ClientGenerator<'a> {
+    handlebars: Handlebars<'a>,
+    language: ClientLanguage,
+    endpoints: Vec<EndpointMetadata>,
+}
+
+impl<'a> ClientGenerator<'a> {
+    pub fn new(language: ClientLanguage) -> Result<Self, GenerationError> {
+        let mut handlebars = Handlebars::new();
+        // The templates are source code, not HTML.
+        handlebars.register_escape_fn(handlebars::no_escape);
+        handlebars
+            .register_template_string("main", language.template())
+            .map_err(|e| GenerationError::HandlebarsTemplate(Box::new(e)))?;
+        Ok(Self {
+            handlebars,
+            language,
+            endpoints: vec![],
+        })
+    }
+
+    /// Adds the endpoint `name`, served at `path`, whose records have `schema`.
+    pub fn add_endpoint(&mut self, name: &str, path: &str, schema: &Schema) {
+        let fields = schema
+            .fields
+            .iter()
+            .map(|field| {
+                let ident = rust_ident(&field.name);
+                FieldMetadata {
+                    name: field.name.clone(),
+                    renamed: ident != field.name,
+                    ident,
+                    typ: self.language.field_type(field.typ, field.nullable),
+                    value_type: self.language.value_type(field.typ).to_string(),
+                }
+            })
+            .collect::<Vec<_>>();
+        let primary_key = match schema.primary_index.as_slice() {
+            [index] => Some(fields[*index].clone()),
+            _ => None,
+        };
+        let name_ident = name.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+        self.endpoints.push(EndpointMetadata {
+            name: name.to_string(),
+            path: path.to_string(),
+            pascal_name: name_ident.to_pascal_case().to_singular(),
+            plural_pascal_name: name_ident.to_pascal_case().to_plural(),
+            snake_name: name_ident.to_snake_case(),
+            singular_snake_name: name_ident.to_snake_case().to_singular(),
+            fields,
+            primary_key,
+        });
+    }
+
+    pub fn generate(&self) -> Result<String, GenerationError> {
+        let metadata = ClientMetadata {
+            endpoints: self.endpoints.clone(),
+        };
+        Ok(self.handlebars.render("main", &metadata)?)
+    }
+}
+
+/// `name` with the characters that can't be in an identifier replaced by `_`, and keywords suffixed with `_`.
+fn rust_ident(name: &str) -> String {
+    let mut ident = name.replace(|c: char| !c.is_ascii_alphanumeric() && c != '_', "_");
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    if RUST_KEYWORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    ident
+}
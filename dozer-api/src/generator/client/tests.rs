@@ -0,0 +1,53 @@
+use super::{rust_ident, ClientGenerator, ClientLanguage};
+use crate::test_utils;
+
+fn generate(language: ClientLanguage) -> String {
+    let endpoint = test_utils::get_endpoint();
+    let schema = test_utils::get_schema().0;
+    let mut generator = ClientGenerator::new(language).unwrap();
+    generator.add_endpoint(&endpoint.name, &endpoint.path, &schema);
+    generator.generate().unwrap()
+}
+
+#[test]
+fn test_generate_rust_client() {
+    let client = generate(ClientLanguage::Rust);
+    assert!(client.contains("pub struct Film {"));
+    assert!(client.contains("    pub film_id: u64,\n"));
+    assert!(client.contains("    pub description: Option<String>,\n"));
+    assert!(client.contains("    pub updated_at: Option<String>,\n"));
+    assert!(client.contains("pub struct FilmQuery(pub Query);"));
+    assert!(client.contains(
+        "pub fn filter_rental_rate(self, operator: Operator, value: impl Into<f64>) -> Self {"
+    ));
+    assert!(client.contains("Self(self.0.order_by(\"release_year\", direction))"));
+    assert!(client.contains("pub async fn query_films("));
+    assert!(client.contains("pub async fn count_films("));
+    assert!(client.contains("pub async fn get_film("));
+    assert!(client.contains("self.url(\"/films\", &[\"query\"])"));
+    // Nothing is HTML escaped.
+    assert!(!client.contains("&lt;"));
+}
+
+#[test]
+fn test_generate_typescript_client() {
+    let client = generate(ClientLanguage::TypeScript);
+    assert!(client.contains("export interface Film {"));
+    assert!(client.contains("  \"film_id\": number;\n"));
+    assert!(client.contains("  \"description\": string | null;\n"));
+    assert!(client.contains("export class FilmQuery extends Query<Film> {}"));
+    assert!(
+        client.contains("queryFilms(query: FilmQuery = new FilmQuery()): Promise<FilmRecord[]> {")
+    );
+    assert!(client.contains("countFilms("));
+    assert!(client.contains("getFilm(key: number): Promise<FilmRecord> {"));
+    assert!(client.contains("this.send(\"/films/query\""));
+}
+
+#[test]
+fn test_rust_ident() {
+    assert_eq!(rust_ident("film_id"), "film_id");
+    assert_eq!(rust_ident("film-id"), "film_id");
+    assert_eq!(rust_ident("type"), "type_");
+    assert_eq!(rust_ident("1st"), "_1st");
+}
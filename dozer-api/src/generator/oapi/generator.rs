@@ -37,11 +37,14 @@ impl<'a> OpenApiGenerator<'a> {
                     FieldType::String => Value::from("foo".to_string()),
                     FieldType::Binary
                     | FieldType::Decimal
+                    | FieldType::Decimal128
                     | FieldType::Timestamp
-                    | FieldType::Bson => Value::Null,
+                    | FieldType::Bson
+                    | FieldType::Geometry => Value::Null,
 
                     FieldType::Text => Value::from("lorem ipsum".to_string()),
                     FieldType::Date => Value::from("2022-11-24"),
+                    FieldType::Duration => Value::from("1h"),
                     FieldType::Point => {
                         let mut m = Map::new();
                         m.insert("x".to_string(), Value::from(3.3));
@@ -188,6 +191,16 @@ impl<'a> OpenApiGenerator<'a> {
                 "Documents can be queried based on a simple or a composite expression".to_owned(),
             ),
             operation_id: Some(format!("query-{}", self.endpoint.name)),
+            parameters: vec![
+                Self::boolean_query_parameter(
+                    "count",
+                    "Respond with the count of the documents instead",
+                ),
+                Self::boolean_query_parameter(
+                    "exists",
+                    "Respond with whether any document matches instead",
+                ),
+            ],
             request_body: Some(ReferenceOr::Item(request_body)),
             responses,
             ..Default::default()
@@ -198,16 +211,100 @@ impl<'a> OpenApiGenerator<'a> {
         })
     }
 
+    fn generate_batch_route(&self) -> ReferenceOr<PathItem> {
+        let request_body = RequestBody {
+            description: Some("Primary keys of the documents".to_owned()),
+            content: indexmap::indexmap! {
+                "application/json".to_owned() => MediaType { example: Some(json!([1, 2])), ..Default::default() }
+            },
+            required: true,
+            ..Default::default()
+        };
+        let response_schema = Schema {
+            schema_data: Default::default(),
+            schema_kind: SchemaKind::Type(Type::Object(ObjectType {
+                properties: indexmap::indexmap! {
+                    "found".to_owned() => ReferenceOr::Reference {
+                        reference: format!("#/components/schemas/{}", self.get_plural_name()),
+                    },
+                    "missing".to_owned() => ReferenceOr::Item(Box::new(Schema {
+                        schema_data: SchemaData {
+                            description: Some("Primary keys that are not found".to_owned()),
+                            ..Default::default()
+                        },
+                        schema_kind: SchemaKind::Type(Type::Array(ArrayType {
+                            items: None,
+                            min_items: None,
+                            max_items: None,
+                            unique_items: false,
+                        })),
+                    })),
+                },
+                required: vec!["found".to_owned(), "missing".to_owned()],
+                ..Default::default()
+            })),
+        };
+        let responses = Responses {
+            responses: indexmap::indexmap! {
+                StatusCode::Code(200) => ReferenceOr::Item(create_response(
+                    format!("The found {} and the missing keys", self.endpoint.name),
+                    response_schema,
+                ))
+            },
+            ..Default::default()
+        };
+        let operation = Some(Operation {
+            tags: vec![format!("{}", self.endpoint.name)],
+            summary: Some("Fetch multiple documents by primary key".to_owned()),
+            description: Some(
+                "Generated API to fetch the records of multiple primary keys in one request"
+                    .to_owned(),
+            ),
+            operation_id: Some(format!("{}-by-ids", self.endpoint.name)),
+            request_body: Some(ReferenceOr::Item(request_body)),
+            responses,
+            ..Default::default()
+        });
+        ReferenceOr::Item(PathItem {
+            post: operation,
+            ..Default::default()
+        })
+    }
+
+    fn boolean_query_parameter(name: &str, description: &str) -> ReferenceOr<Parameter> {
+        ReferenceOr::Item(Parameter::Query {
+            parameter_data: ParameterData {
+                name: name.to_owned(),
+                description: Some(description.to_owned()),
+                required: false,
+                format: ParameterSchemaOrContent::Schema(ReferenceOr::Item(Schema {
+                    schema_data: Default::default(),
+                    schema_kind: SchemaKind::Type(Type::Boolean {}),
+                })),
+                deprecated: None,
+                example: None,
+                examples: IndexMap::new(),
+                explode: None,
+                extensions: IndexMap::new(),
+            },
+            allow_reserved: false,
+            style: QueryStyle::Form,
+            allow_empty_value: None,
+        })
+    }
+
     fn _generate_available_paths(&self) -> Paths {
         let get_list = self.generate_list_route();
         let get_by_id_item = self.generate_get_route();
         let count_list = self.generate_count_route();
         let query_list = self.generate_query_route();
+        let batch_list = self.generate_batch_route();
         let path_items = indexmap::indexmap! {
             self.endpoint.path.to_owned() => get_list,
             format!("{}/{}", self.endpoint.path.to_owned(), "{id}") => get_by_id_item,
             format!("{}/count", self.endpoint.path.to_owned()) => count_list,
-            format!("{}/query", self.endpoint.path.to_owned()) => query_list
+            format!("{}/query", self.endpoint.path.to_owned()) => query_list,
+            format!("{}/batch", self.endpoint.path.to_owned()) => batch_list
         };
         Paths {
             paths: path_items,
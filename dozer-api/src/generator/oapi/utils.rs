@@ -3,10 +3,9 @@ use dozer_types::{
     types::{FieldType, DATE_FORMAT},
 };
 use openapiv3::{
-    ArrayType, Contact, IntegerFormat, IntegerType, MediaType, NumberFormat, NumberType,
-    ObjectType, Parameter, ParameterData, ParameterSchemaOrContent, PathStyle, ReferenceOr,
-    Response, Schema, SchemaData, SchemaKind, StringFormat, StringType, Type,
-    VariantOrUnknownOrEmpty,
+    Contact, IntegerFormat, IntegerType, MediaType, NumberFormat, NumberType, ObjectType,
+    Parameter, ParameterData, ParameterSchemaOrContent, PathStyle, ReferenceOr, Response, Schema,
+    SchemaData, SchemaKind, StringFormat, StringType, Type, VariantOrUnknownOrEmpty,
 };
 
 const CONTACT_NAME: &str = "Dozer Team";
@@ -78,11 +77,22 @@ pub fn convert_cache_to_oapi_schema(
         if !field.nullable {
             required_properties.push(field.name.to_owned());
         }
+        let mut schema_type = convert_cache_type_to_schema_type(field.typ);
+        // Binaries are base64 encoded, so their maximum length doesn't apply to the string.
+        if let (Type::String(string_type), FieldType::String | FieldType::Text, Some(max_length)) =
+            (&mut schema_type, field.typ, field.metadata.max_length)
+        {
+            string_type.max_length = Some(max_length as usize);
+        }
         properties.insert(
             field.name,
             ReferenceOr::boxed_item(Schema {
-                schema_data: Default::default(),
-                schema_kind: SchemaKind::Type(convert_cache_type_to_schema_type(field.typ)),
+                schema_data: SchemaData {
+                    description: field.metadata.description,
+                    default: field.metadata.default_value.map(|value| value.to_json()),
+                    ..Default::default()
+                },
+                schema_kind: SchemaKind::Type(schema_type),
             }),
         );
     }
@@ -100,7 +110,7 @@ pub fn convert_cache_to_oapi_schema(
     }
 }
 
-/// Should be consistent with `field_to_json_value`.
+/// Should be consistent with `Field::to_json`.
 fn convert_cache_type_to_schema_type(field_type: dozer_types::types::FieldType) -> Type {
     match field_type {
         FieldType::UInt | FieldType::Int => Type::Integer(IntegerType {
@@ -115,13 +125,13 @@ fn convert_cache_type_to_schema_type(field_type: dozer_types::types::FieldType)
         FieldType::String
         | FieldType::Text
         | FieldType::Decimal
+        | FieldType::Decimal128
         | FieldType::Timestamp
-        | FieldType::Date => {
+        | FieldType::Date
+        | FieldType::Duration
+        | FieldType::Geometry => {
             let (format, pattern) = if field_type == FieldType::Timestamp {
-                (
-                    VariantOrUnknownOrEmpty::Item(StringFormat::DateTime),
-                    Some("yyyy-MM-dd'T'HH:mm:ss.SSS'Z'".to_string()),
-                )
+                (VariantOrUnknownOrEmpty::Item(StringFormat::DateTime), None)
             } else if field_type == FieldType::Date {
                 (
                     VariantOrUnknownOrEmpty::Item(StringFormat::Date),
@@ -136,11 +146,9 @@ fn convert_cache_type_to_schema_type(field_type: dozer_types::types::FieldType)
                 ..Default::default()
             })
         }
-        FieldType::Binary | FieldType::Bson => Type::Array(ArrayType {
-            items: Some(ReferenceOr::Item(Box::new(u8_schema()))),
-            min_items: None,
-            max_items: None,
-            unique_items: false,
+        FieldType::Binary | FieldType::Bson => Type::String(StringType {
+            format: VariantOrUnknownOrEmpty::Item(StringFormat::Byte),
+            ..Default::default()
         }),
         FieldType::Point => {
             let mut properties: IndexMap<String, ReferenceOr<Box<Schema>>> = IndexMap::new();
@@ -168,13 +176,3 @@ fn convert_cache_type_to_schema_type(field_type: dozer_types::types::FieldType)
         }
     }
 }
-
-fn u8_schema() -> Schema {
-    Schema {
-        schema_data: Default::default(),
-        schema_kind: SchemaKind::Type(Type::Integer(IntegerType {
-            format: VariantOrUnknownOrEmpty::Item(IntegerFormat::Int32),
-            ..Default::default()
-        })),
-    }
-}
@@ -19,6 +19,7 @@ use super::{CountResponseDesc, QueryResponseDesc, RecordDesc, ServiceDesc};
 const POINT_TYPE_CLASS: &str = "dozer.types.PointType";
 const DECIMAL_TYPE_CLASS: &str = "dozer.types.RustDecimal";
 const TIMESTAMP_TYPE_CLASS: &str = "google.protobuf.Timestamp";
+const DURATION_TYPE_CLASS: &str = "google.protobuf.Duration";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "self::serde")]
@@ -87,7 +88,7 @@ impl<'a> ProtoGeneratorImpl<'a> {
     }
 
     fn libs_by_type(&self) -> Result<Vec<String>, GenerationError> {
-        let type_need_import_libs = [TIMESTAMP_TYPE_CLASS];
+        let type_need_import_libs = [TIMESTAMP_TYPE_CLASS, DURATION_TYPE_CLASS];
         let mut libs_import: Vec<String> = self
             .schema
             .fields
@@ -98,6 +99,7 @@ impl<'a> ProtoGeneratorImpl<'a> {
             })
             .map(|proto_type| match proto_type.as_str() {
                 TIMESTAMP_TYPE_CLASS => "google/protobuf/timestamp.proto".to_owned(),
+                DURATION_TYPE_CLASS => "google/protobuf/duration.proto".to_owned(),
                 _ => "".to_owned(),
             })
             .collect();
@@ -370,6 +372,9 @@ fn convert_dozer_type_to_proto_type(field_type: FieldType) -> Result<String, Gen
         FieldType::Timestamp => Ok(TIMESTAMP_TYPE_CLASS.to_owned()),
         FieldType::Date => Ok("string".to_owned()),
         FieldType::Bson => Ok("bytes".to_owned()),
+        FieldType::Geometry => Ok("bytes".to_owned()),
+        FieldType::Decimal128 => Ok("string".to_owned()),
         FieldType::Point => Ok(POINT_TYPE_CLASS.to_owned()),
+        FieldType::Duration => Ok(DURATION_TYPE_CLASS.to_owned()),
     }
 }
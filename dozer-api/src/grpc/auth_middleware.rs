@@ -9,7 +9,7 @@ use tonic::{
 };
 use tower::{Layer, Service};
 
-use crate::auth::Authorizer;
+use crate::auth::authenticate;
 
 #[derive(Debug, Clone, Default)]
 pub struct AuthMiddlewareLayer {
@@ -61,14 +61,13 @@ where
                     let auth_header = req.headers().get("authorization");
                     if let Some(auth_header) = auth_header {
                         let auth_header_str = auth_header.to_str().unwrap();
-                        let authorizer = Authorizer::from(&security);
                         if auth_header_str.starts_with("Bearer ") {
                             let token_array: Vec<&str> = auth_header_str.split(' ').collect();
-                            let token_data = authorizer.validate_token(token_array[1]);
-                            match token_data {
-                                Ok(claims) => {
+                            let access = authenticate(&security, token_array[1]);
+                            match access {
+                                Ok(access) => {
                                     let mut modified_request = req;
-                                    modified_request.extensions_mut().insert(claims.access);
+                                    modified_request.extensions_mut().insert(access);
                                     let response = inner.call(modified_request).await?;
                                     return Ok(response);
                                 }
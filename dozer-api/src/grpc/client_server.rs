@@ -5,7 +5,7 @@ use crate::{errors::GrpcError, generator::protoc::generator::ProtoGenerator, RoC
 use dozer_types::grpc_types::health::health_check_response::ServingStatus;
 use dozer_types::grpc_types::types::Operation;
 use dozer_types::grpc_types::{
-    common::common_grpc_service_server::CommonGrpcServiceServer,
+    self, common::common_grpc_service_server::CommonGrpcServiceServer,
     health::health_grpc_service_server::HealthGrpcServiceServer,
 };
 use dozer_types::tracing::Level;
@@ -49,6 +49,7 @@ impl ApiServer {
                 .as_ref()
                 .map_or("None".to_string(), |s| match s {
                     ApiSecurity::Jwt(_) => "JWT".to_string(),
+                    ApiSecurity::ApiKeys(_) => "API keys".to_string(),
                 })
         );
 
@@ -56,7 +57,10 @@ impl ApiServer {
 
         let descriptor_bytes = ProtoGenerator::read_descriptor_bytes(&descriptor_path)?;
 
+        // Reflect the common and health services too, so generic tools can discover every service.
         let inflection_service = tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(grpc_types::common::FILE_DESCRIPTOR_SET)
+            .register_encoded_file_descriptor_set(grpc_types::health::FILE_DESCRIPTOR_SET)
             .register_encoded_file_descriptor_set(&descriptor_bytes)
             .build()?;
 
@@ -5,16 +5,21 @@ use crate::auth::Access;
 
 use crate::grpc::shared_impl;
 use crate::grpc::types_helper::{map_field_definitions, map_record};
+use crate::limits::ReadPermit;
 use crate::RoCacheEndpoint;
 use dozer_types::grpc_types::common::common_grpc_service_server::CommonGrpcService;
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
 
+use dozer_types::grpc_types::common::index_definition::IndexType;
 use dozer_types::grpc_types::common::{
-    CountResponse, GetEndpointsRequest, GetEndpointsResponse, GetFieldsRequest, GetFieldsResponse,
-    OnEventRequest, QueryRequest, QueryResponse,
+    CountResponse, EndpointSchema, GetEndpointsRequest, GetEndpointsResponse, GetFieldsRequest,
+    GetFieldsResponse, GetManyRequest, GetManyResponse, GetSchemaRequest, GetSchemaResponse,
+    IndexDefinition, ListEndpointsRequest, ListEndpointsResponse, OnEventRequest, QueryRequest,
+    QueryResponse,
 };
 use dozer_types::grpc_types::types::Operation;
+use dozer_types::types;
 
 type EventResult<T> = Result<Response<T>, Status>;
 type ResponseStream = ReceiverStream<Result<Operation, tonic::Status>>;
@@ -44,17 +49,19 @@ impl CommonService {
     fn parse_request(
         &self,
         request: Request<QueryRequest>,
-    ) -> Result<(&RoCacheEndpoint, QueryRequest, Option<Access>), Status> {
-        let parts = request.into_parts();
-        let mut extensions = parts.1;
-        let query_request = parts.2;
-        let access = extensions.remove::<Access>();
-        let endpoint = &query_request.endpoint;
+    ) -> Result<(&RoCacheEndpoint, QueryRequest, Option<Access>, ReadPermit), Status> {
+        let endpoint = &request.get_ref().endpoint;
         let cache_endpoint = self
             .endpoint_map
             .get(endpoint)
             .map_or(Err(Status::invalid_argument(endpoint)), Ok)?;
-        Ok((cache_endpoint, query_request, access))
+        let permit = shared_impl::acquire_permit(cache_endpoint, &request)?;
+
+        let parts = request.into_parts();
+        let mut extensions = parts.1;
+        let query_request = parts.2;
+        let access = extensions.remove::<Access>();
+        Ok((cache_endpoint, query_request, access, permit))
     }
 }
 
@@ -64,7 +71,7 @@ impl CommonGrpcService for CommonService {
         &self,
         request: Request<QueryRequest>,
     ) -> Result<Response<CountResponse>, Status> {
-        let (cache_endpoint, query_request, access) = self.parse_request(request)?;
+        let (cache_endpoint, query_request, access, _permit) = self.parse_request(request)?;
 
         let count = shared_impl::count(
             &cache_endpoint.cache_reader(),
@@ -83,7 +90,7 @@ impl CommonGrpcService for CommonService {
         &self,
         request: Request<QueryRequest>,
     ) -> Result<Response<QueryResponse>, Status> {
-        let (cache_endpoint, query_request, access) = self.parse_request(request)?;
+        let (cache_endpoint, query_request, access, _permit) = self.parse_request(request)?;
 
         let cache_reader = cache_endpoint.cache_reader();
         let (schema, records) = shared_impl::query(
@@ -100,18 +107,57 @@ impl CommonGrpcService for CommonService {
         Ok(Response::new(reply))
     }
 
+    async fn get_many(
+        &self,
+        request: Request<GetManyRequest>,
+    ) -> Result<Response<GetManyResponse>, Status> {
+        let endpoint = &request.get_ref().endpoint;
+        let cache_endpoint = self
+            .endpoint_map
+            .get(endpoint)
+            .ok_or_else(|| Status::invalid_argument(endpoint))?;
+        let _permit = shared_impl::acquire_permit(cache_endpoint, &request)?;
+
+        let (_, extensions, get_many_request) = request.into_parts();
+        let access = extensions.get::<Access>().cloned();
+        let cache_reader = cache_endpoint.cache_reader();
+        let (schema, records) = shared_impl::get_many(
+            &cache_reader,
+            &cache_endpoint.endpoint.name,
+            &get_many_request.keys,
+            access,
+        )?;
+
+        let fields = map_field_definitions(schema.fields.clone());
+        let mut found = vec![];
+        let mut missing = vec![];
+        for (index, record) in records.into_iter().enumerate() {
+            match record {
+                Some(record) => found.push(map_record(record)),
+                None => missing.push(index as u32),
+            }
+        }
+        Ok(Response::new(GetManyResponse {
+            fields,
+            records: found,
+            missing,
+        }))
+    }
+
     type OnEventStream = ResponseStream;
 
     async fn on_event(&self, request: Request<OnEventRequest>) -> EventResult<Self::OnEventStream> {
-        let parts = request.into_parts();
-        let extensions = parts.1;
-        let query_request = parts.2;
-        let access = extensions.get::<Access>();
-        let endpoint = &query_request.endpoint;
+        let endpoint = &request.get_ref().endpoint;
         let cache_endpoint = self
             .endpoint_map
             .get(endpoint)
             .ok_or_else(|| Status::invalid_argument(endpoint))?;
+        let _permit = shared_impl::acquire_permit(cache_endpoint, &request)?;
+
+        let parts = request.into_parts();
+        let extensions = parts.1;
+        let query_request = parts.2;
+        let access = extensions.get::<Access>();
 
         shared_impl::on_event(
             &cache_endpoint.cache_reader(),
@@ -162,4 +208,61 @@ impl CommonGrpcService for CommonService {
             fields,
         }))
     }
+
+    async fn list_endpoints(
+        &self,
+        _: Request<ListEndpointsRequest>,
+    ) -> Result<Response<ListEndpointsResponse>, Status> {
+        let mut endpoints = self
+            .endpoint_map
+            .values()
+            .map(|cache_endpoint| endpoint_schema(cache_endpoint))
+            .collect::<Result<Vec<_>, _>>()?;
+        endpoints.sort_by(|a, b| a.endpoint.cmp(&b.endpoint));
+        Ok(Response::new(ListEndpointsResponse { endpoints }))
+    }
+
+    async fn get_schema(
+        &self,
+        request: Request<GetSchemaRequest>,
+    ) -> Result<Response<GetSchemaResponse>, Status> {
+        let endpoint = request.into_inner().endpoint;
+        let cache_endpoint = self
+            .endpoint_map
+            .get(&endpoint)
+            .ok_or_else(|| Status::invalid_argument(&endpoint))?;
+        Ok(Response::new(GetSchemaResponse {
+            schema: Some(endpoint_schema(cache_endpoint)?),
+        }))
+    }
+}
+
+fn endpoint_schema(cache_endpoint: &RoCacheEndpoint) -> Result<EndpointSchema, Status> {
+    let endpoint = &cache_endpoint.endpoint;
+    let cache_reader = cache_endpoint.cache_reader();
+    let (schema, secondary_indexes) = cache_reader
+        .get_schema_and_indexes_by_name(&endpoint.name)
+        .map_err(|_| Status::invalid_argument(&endpoint.name))?;
+
+    let field_name = |index: &usize| schema.fields[*index].name.clone();
+    let indexes = secondary_indexes
+        .iter()
+        .map(|index| match index {
+            types::IndexDefinition::SortedInverted(fields) => IndexDefinition {
+                typ: IndexType::SortedInverted as i32,
+                fields: fields.iter().map(field_name).collect(),
+            },
+            types::IndexDefinition::FullText(field) => IndexDefinition {
+                typ: IndexType::FullText as i32,
+                fields: vec![field_name(field)],
+            },
+        })
+        .collect();
+    Ok(EndpointSchema {
+        endpoint: endpoint.name.clone(),
+        path: endpoint.path.clone(),
+        fields: map_field_definitions(schema.fields.clone()),
+        primary_key: schema.primary_index.iter().map(field_name).collect(),
+        indexes,
+    })
 }
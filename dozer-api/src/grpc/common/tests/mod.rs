@@ -6,8 +6,9 @@ use crate::grpc::typed::tests::{
 
 use dozer_types::grpc_types::{
     common::{
-        common_grpc_service_server::CommonGrpcService, GetEndpointsRequest, GetFieldsRequest,
-        OnEventRequest, QueryRequest,
+        common_grpc_service_server::CommonGrpcService, index_definition::IndexType,
+        GetEndpointsRequest, GetFieldsRequest, GetManyRequest, GetSchemaRequest,
+        ListEndpointsRequest, OnEventRequest, QueryRequest,
     },
     types::{value, EventType, FieldDefinition, OperationType, RecordWithId, Type, Value},
 };
@@ -81,6 +82,30 @@ async fn test_grpc_common_count_and_query() {
     assert_eq!(records.len(), 11);
 }
 
+#[tokio::test]
+async fn test_grpc_common_get_many() {
+    let service = setup_common_service().await;
+    let key = |film_id| Value {
+        value: Some(value::Value::UintValue(film_id)),
+    };
+    let response = service
+        .get_many(Request::new(GetManyRequest {
+            endpoint: "films".to_string(),
+            keys: vec![key(524), key(100000), key(268)],
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.fields.len(), 5);
+    let film_ids = response
+        .records
+        .iter()
+        .map(|record| record.record.as_ref().unwrap().values[0].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(film_ids, vec![key(524), key(268)]);
+    assert_eq!(response.missing, vec![1]);
+}
+
 #[tokio::test]
 async fn test_grpc_common_get_endpoints() {
     let service = setup_common_service().await;
@@ -134,6 +159,42 @@ async fn test_grpc_common_get_fields() {
     );
 }
 
+#[tokio::test]
+async fn test_grpc_common_list_endpoints_and_get_schema() {
+    let service = setup_common_service().await;
+    let response = service
+        .list_endpoints(Request::new(ListEndpointsRequest {}))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.endpoints.len(), 1);
+    let schema = service
+        .get_schema(Request::new(GetSchemaRequest {
+            endpoint: "films".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .schema
+        .unwrap();
+    assert_eq!(response.endpoints[0], schema);
+
+    assert_eq!(schema.endpoint, "films");
+    assert_eq!(schema.path, "/films");
+    assert_eq!(schema.fields.len(), 5);
+    assert_eq!(schema.primary_key, vec!["film_id".to_string()]);
+    assert_eq!(schema.indexes.len(), 5);
+    assert_eq!(schema.indexes[1].typ, IndexType::SortedInverted as i32);
+    assert_eq!(schema.indexes[1].fields, vec!["description".to_string()]);
+
+    assert!(service
+        .get_schema(Request::new(GetSchemaRequest {
+            endpoint: "actors".to_string(),
+        }))
+        .await
+        .is_err());
+}
+
 #[tokio::test]
 async fn test_grpc_common_on_event() {
     // start fake internal pipeline
@@ -1,17 +1,21 @@
-use dozer_cache::cache::expression::{default_limit_for_query, QueryExpression};
-use dozer_cache::cache::RecordWithId;
+use dozer_cache::cache::expression::{default_limit_for_query, FilterExpression, QueryExpression};
+use dozer_cache::cache::{index, RecordWithId};
 use dozer_cache::CacheReader;
-use dozer_types::grpc_types::types::Operation;
+use dozer_types::grpc_types::types::{Operation, Value};
 use dozer_types::log::warn;
 use dozer_types::serde_json;
 use dozer_types::types::Schema;
 use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::broadcast::Receiver;
 use tokio_stream::wrappers::ReceiverStream;
-use tonic::{Code, Response, Status};
+use tonic::{Code, Request, Response, Status};
 
-use crate::api_helper::{get_records, get_records_count};
+use crate::api_helper::{authorize_events, get_records, get_records_by_keys, get_records_count};
 use crate::auth::Access;
+use crate::errors::ApiError;
+use crate::grpc::types_helper::map_prost_value;
+use crate::limits::{bearer_token, ReadPermit};
+use crate::RoCacheEndpoint;
 
 mod filter;
 
@@ -19,6 +23,21 @@ pub fn from_error(error: impl std::error::Error) -> Status {
     Status::new(Code::Internal, error.to_string())
 }
 
+/// Counts `request` against the limits of `cache_endpoint`, by the token of its `authorization` metadata.
+pub fn acquire_permit<T>(
+    cache_endpoint: &RoCacheEndpoint,
+    request: &Request<T>,
+) -> Result<ReadPermit, Status> {
+    let token = request
+        .metadata()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(bearer_token);
+    Ok(cache_endpoint
+        .limits()
+        .acquire(&cache_endpoint.endpoint().name, token)?)
+}
+
 fn parse_query(
     query: Option<&str>,
     default: impl FnOnce() -> QueryExpression,
@@ -64,21 +83,43 @@ pub fn query<'a>(
     Ok((schema, records))
 }
 
+/// Gets the records of `keys`, which are values of the single field of the primary key.
+pub fn get_many<'a>(
+    reader: &'a CacheReader,
+    endpoint_name: &str,
+    keys: &[Value],
+    access: Option<Access>,
+) -> Result<(&'a Schema, Vec<Option<RecordWithId>>), Status> {
+    let schema = &reader
+        .get_schema_and_indexes_by_name(endpoint_name)
+        .map_err(|_| Status::invalid_argument(endpoint_name))?
+        .0;
+    let field = match schema.primary_index.as_slice() {
+        [] => return Err(ApiError::NoPrimaryKey.into()),
+        [index] => &schema.fields[*index],
+        _ => return Err(ApiError::MultiIndexFetch(endpoint_name.to_string()).into()),
+    };
+    let keys = keys
+        .iter()
+        .map(|key| index::get_primary_key(&[0], &[map_prost_value(key, field.typ)]))
+        .collect::<Vec<_>>();
+    let records = get_records_by_keys(reader, endpoint_name, &keys, access)?;
+    Ok((schema, records))
+}
+
 pub fn on_event<T: Send + 'static>(
     reader: &CacheReader,
     endpoint_name: &str,
     filter: Option<&str>,
-    mut broadcast_receiver: Option<Receiver<Operation>>,
-    _access: Option<Access>,
+    broadcast_receiver: Option<Receiver<Operation>>,
+    access: Option<Access>,
     event_mapper: impl Fn(Operation) -> Option<T> + Send + Sync + 'static,
 ) -> Result<Response<ReceiverStream<T>>, Status> {
-    // TODO: Use access.
-
-    if broadcast_receiver.is_none() {
+    let Some(broadcast_receiver) = broadcast_receiver else {
         return Err(Status::unavailable(
             "on_event is not enabled. This is currently an experimental feature. Enable it in the config.",
         ));
-    }
+    };
 
     let filter = match filter {
         Some(filter) => {
@@ -95,34 +136,62 @@ pub fn on_event<T: Send + 'static>(
         .map_err(|_| Status::invalid_argument(endpoint_name))?
         .0
         .clone();
+    let (filter, masked_fields) = authorize_events(&schema, endpoint_name, filter, access)?;
+
+    Ok(Response::new(filter_events(
+        broadcast_receiver,
+        filter,
+        masked_fields,
+        schema,
+        event_mapper,
+    )))
+}
 
+/// Streams the operations of `broadcast_receiver` that satisfy `filter`, with the values of `masked_fields` as null,
+/// mapped by `event_mapper`.
+pub(crate) fn filter_events<T: Send + 'static>(
+    mut broadcast_receiver: Receiver<Operation>,
+    filter: Option<FilterExpression>,
+    masked_fields: Vec<usize>,
+    schema: Schema,
+    event_mapper: impl Fn(Operation) -> Option<T> + Send + Sync + 'static,
+) -> ReceiverStream<T> {
     let (tx, rx) = tokio::sync::mpsc::channel(1);
 
     tokio::spawn(async move {
         loop {
-            if let Some(broadcast_receiver) = broadcast_receiver.as_mut() {
-                let event = broadcast_receiver.recv().await;
-                match event {
-                    Ok(op) => {
-                        if filter::op_satisfies_filter(&op, filter.as_ref(), &schema) {
-                            if let Some(event) = event_mapper(op) {
-                                if (tx.send(event).await).is_err() {
-                                    // receiver dropped
-                                    break;
-                                }
+            let event = broadcast_receiver.recv().await;
+            match event {
+                Ok(mut op) => {
+                    if filter::op_satisfies_filter(&op, filter.as_ref(), &schema) {
+                        mask_operation(&mut op, &masked_fields);
+                        if let Some(event) = event_mapper(op) {
+                            if (tx.send(event).await).is_err() {
+                                // receiver dropped
+                                break;
                             }
                         }
                     }
-                    Err(e) => {
-                        warn!("Failed to receive event from broadcast channel: {}", e);
-                        if e == RecvError::Closed {
-                            break;
-                        }
+                }
+                Err(e) => {
+                    warn!("Failed to receive event from broadcast channel: {}", e);
+                    if e == RecvError::Closed {
+                        break;
                     }
                 }
             }
         }
     });
 
-    Ok(Response::new(ReceiverStream::new(rx)))
+    ReceiverStream::new(rx)
+}
+
+fn mask_operation(op: &mut Operation, masked_fields: &[usize]) {
+    for record in op.old.iter_mut().chain(op.new.iter_mut()) {
+        for index in masked_fields {
+            if let Some(value) = record.values.get_mut(*index) {
+                value.value = None;
+            }
+        }
+    }
 }
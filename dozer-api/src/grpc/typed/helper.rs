@@ -95,6 +95,7 @@ fn interval_value_to_pb(
             Value::Message(decimal)
         }
         GrpcTypes::value::Value::TimestampValue(ts) => Value::Message(ts.transcode_to_dynamic()),
+        GrpcTypes::value::Value::DurationValue(d) => Value::Message(d.transcode_to_dynamic()),
         GrpcTypes::value::Value::DateValue(d) => Value::String(d),
     })
 }
@@ -118,14 +118,17 @@ impl TypedService {
                 type Response = TypedResponse;
                 type Future = future::Ready<Result<Response<TypedResponse>, Status>>;
                 fn call(&mut self, request: Request<DynamicMessage>) -> Self::Future {
-                    let response = count(
-                        request,
-                        &self.cache_endpoint.cache_reader(),
-                        &self.cache_endpoint.endpoint.name,
-                        self.response_desc
-                            .take()
-                            .expect("This future shouldn't be polled twice"),
-                    );
+                    let response = shared_impl::acquire_permit(&self.cache_endpoint, &request)
+                        .and_then(|_permit| {
+                            count(
+                                request,
+                                &self.cache_endpoint.cache_reader(),
+                                &self.cache_endpoint.endpoint.name,
+                                self.response_desc
+                                    .take()
+                                    .expect("This future shouldn't be polled twice"),
+                            )
+                        });
                     future::ready(response)
                 }
             }
@@ -148,14 +151,17 @@ impl TypedService {
                 type Response = TypedResponse;
                 type Future = future::Ready<Result<Response<TypedResponse>, Status>>;
                 fn call(&mut self, request: Request<DynamicMessage>) -> Self::Future {
-                    let response = query(
-                        request,
-                        &self.cache_endpoint.cache_reader(),
-                        &self.cache_endpoint.endpoint.name,
-                        self.response_desc
-                            .take()
-                            .expect("This future shouldn't be polled twice"),
-                    );
+                    let response = shared_impl::acquire_permit(&self.cache_endpoint, &request)
+                        .and_then(|_permit| {
+                            query(
+                                request,
+                                &self.cache_endpoint.cache_reader(),
+                                &self.cache_endpoint.endpoint.name,
+                                self.response_desc
+                                    .take()
+                                    .expect("This future shouldn't be polled twice"),
+                            )
+                        });
                     future::ready(response)
                 }
             }
@@ -184,15 +190,21 @@ impl TypedService {
                     type Future =
                         future::Ready<Result<tonic::Response<Self::ResponseStream>, tonic::Status>>;
                     fn call(&mut self, request: tonic::Request<DynamicMessage>) -> Self::Future {
-                        future::ready(on_event(
-                            request,
-                            &self.cache_endpoint.cache_reader(),
-                            &self.cache_endpoint.endpoint.name,
-                            self.event_desc
-                                .take()
-                                .expect("This future shouldn't be polled twice"),
-                            self.event_notifier.take(),
-                        ))
+                        future::ready(
+                            shared_impl::acquire_permit(&self.cache_endpoint, &request).and_then(
+                                |_permit| {
+                                    on_event(
+                                        request,
+                                        &self.cache_endpoint.cache_reader(),
+                                        &self.cache_endpoint.endpoint.name,
+                                        self.event_desc
+                                            .take()
+                                            .expect("This future shouldn't be polled twice"),
+                                        self.event_notifier.take(),
+                                    )
+                                },
+                            ),
+                        )
                     }
                 }
 
@@ -372,14 +384,14 @@ fn token(
     security: Option<ApiSecurity>,
     response_desc: TokenResponseDesc,
 ) -> Result<Response<TypedResponse>, Status> {
-    if let Some(security) = security {
+    if let Some(ApiSecurity::Jwt(secret)) = security {
         let _parts = request.into_parts();
 
-        let auth = Authorizer::from(&security);
+        let auth = Authorizer::new(&secret, None, None);
         let token = auth.generate_token(Access::All, None).unwrap();
         let res = token_response(token, response_desc);
         Ok(Response::new(res))
     } else {
-        Err(Status::unavailable("security config unavailable"))
+        Err(Status::unavailable("JWT security config unavailable"))
     }
 }
@@ -1,8 +1,11 @@
 use dozer_cache::cache::RecordWithId as CacheRecordWithId;
+use dozer_types::chrono::{NaiveDate, TimeZone, Utc};
 use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::rust_decimal::Decimal;
-use dozer_types::types::{Field, FieldType, Record as DozerRecord, DATE_FORMAT};
-use prost_reflect::prost_types::Timestamp;
+use dozer_types::types::{
+    DozerDuration, DozerGeometry, DozerPoint, Field, FieldType, Record as DozerRecord, DATE_FORMAT,
+};
+use prost_reflect::prost_types::{Duration, Timestamp};
 
 use dozer_types::grpc_types::types::{
     value, Operation, OperationType, PointType, Record, RecordWithId, RustDecimal, Type, Value,
@@ -123,6 +126,64 @@ fn field_to_prost_value(f: Field) -> Value {
             )),
         },
         Field::Point(point) => map_x_y_to_prost_coord_map(point.0.x_y()),
+        Field::Duration(d) => Value {
+            value: Some(value::Value::DurationValue(Duration {
+                seconds: d.as_nanos() / 1_000_000_000,
+                nanos: (d.as_nanos() % 1_000_000_000) as i32,
+            })),
+        },
+        Field::Geometry(g) => Value {
+            value: Some(value::Value::BytesValue(g.as_bytes().to_vec())),
+        },
+        Field::Decimal128(d) => Value {
+            value: Some(value::Value::StringValue(d.to_string())),
+        },
+    }
+}
+
+/// Maps a value back to the field of type `typ` it was mapped from, the inverse of `field_to_prost_value`.
+///
+/// `Decimal` values lose their scale in `RustDecimal`, so they come back as integers.
+pub fn map_prost_value(value: &Value, typ: FieldType) -> Field {
+    let Some(value) = &value.value else {
+        return Field::Null;
+    };
+    match (value, typ) {
+        (value::Value::UintValue(n), _) => Field::UInt(*n),
+        (value::Value::IntValue(n), _) => Field::Int(*n),
+        (value::Value::FloatValue(n), _) => Field::Float(OrderedFloat(*n)),
+        (value::Value::BoolValue(b), _) => Field::Boolean(*b),
+        (value::Value::StringValue(s), FieldType::Text) => Field::Text(s.clone()),
+        (value::Value::StringValue(s), FieldType::Date) | (value::Value::DateValue(s), _) => {
+            NaiveDate::parse_from_str(s, DATE_FORMAT)
+                .map(Field::Date)
+                .unwrap_or_else(|_| Field::String(s.clone()))
+        }
+        (value::Value::StringValue(s), FieldType::Decimal128) => s
+            .parse()
+            .map(Field::Decimal128)
+            .unwrap_or_else(|_| Field::String(s.clone())),
+        (value::Value::StringValue(s), _) => Field::String(s.clone()),
+        (value::Value::BytesValue(b), FieldType::Bson) => Field::Bson(b.clone()),
+        (value::Value::BytesValue(b), FieldType::Geometry) => DozerGeometry::from_ewkb(b.clone())
+            .map(Field::Geometry)
+            .unwrap_or_else(|_| Field::Binary(b.clone())),
+        (value::Value::BytesValue(b), _) => Field::Binary(b.clone()),
+        (value::Value::DecimalValue(d), _) => {
+            Field::Decimal(Decimal::from_parts(d.lo, d.mid, d.hi, d.flags != 0, 0))
+        }
+        (value::Value::TimestampValue(ts), _) => Utc
+            .timestamp_opt(ts.seconds, ts.nanos as u32)
+            .single()
+            .map(|ts| Field::Timestamp(ts.into()))
+            .unwrap_or(Field::Null),
+        (value::Value::PointValue(p), _) => Field::Point(DozerPoint::from((p.x, p.y))),
+        (value::Value::DurationValue(d), _) => d
+            .seconds
+            .checked_mul(1_000_000_000)
+            .and_then(|nanos| nanos.checked_add(d.nanos.into()))
+            .map(|nanos| Field::Duration(DozerDuration::from_nanos(nanos)))
+            .unwrap_or(Field::Null),
     }
 }
 
@@ -153,5 +214,8 @@ fn field_type_to_internal_type(typ: FieldType) -> Type {
         FieldType::Bson => Type::Bson,
         FieldType::Date => Type::String,
         FieldType::Point => Type::Point,
+        FieldType::Duration => Type::Duration,
+        FieldType::Geometry => Type::Geometry,
+        FieldType::Decimal128 => Type::Decimal128,
     }
 }
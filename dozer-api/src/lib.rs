@@ -9,6 +9,7 @@ mod api_helper;
 pub struct RoCacheEndpoint {
     cache_reader: ArcSwap<CacheReader>,
     endpoint: ApiEndpoint,
+    limits: Arc<ApiLimits>,
 }
 
 impl RoCacheEndpoint {
@@ -17,9 +18,15 @@ impl RoCacheEndpoint {
         Ok(Self {
             cache_reader: ArcSwap::from_pointee(cache_reader),
             endpoint,
+            limits: Default::default(),
         })
     }
 
+    /// Limits the requests to this endpoint, with limits that may be shared by other endpoints.
+    pub fn with_limits(self, limits: Arc<ApiLimits>) -> Self {
+        Self { limits, ..self }
+    }
+
     pub fn cache_reader(&self) -> impl Deref<Target = Arc<CacheReader>> + '_ {
         self.cache_reader.load()
     }
@@ -28,6 +35,10 @@ impl RoCacheEndpoint {
         &self.endpoint
     }
 
+    pub fn limits(&self) -> &ApiLimits {
+        &self.limits
+    }
+
     pub fn redirect_cache(&self, cache_manager: &dyn CacheManager) -> Result<(), ApiError> {
         let cache_reader = open_cache_reader(cache_manager, &self.endpoint.name)?;
         self.cache_reader.store(Arc::new(cache_reader));
@@ -52,11 +63,13 @@ pub mod auth;
 pub mod errors;
 pub mod generator;
 pub mod grpc;
+pub mod limits;
 pub mod rest;
 // Re-exports
 pub use actix_web;
 pub use async_trait;
 use errors::ApiError;
+use limits::ApiLimits;
 pub use openapiv3;
 pub use tokio;
 pub use tonic;
@@ -0,0 +1,284 @@
+//! Rate limits and a cap on concurrent cache reads, so one client can't exhaust the API or the LMDB reader slots.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use dozer_types::models::api_config::RateLimitConfig;
+
+use crate::errors::ApiError;
+
+#[derive(Debug, Default)]
+pub struct ApiLimits {
+    per_token: Option<RateLimiter>,
+    per_endpoint: Option<RateLimiter>,
+    reads: Option<Arc<ReadLimiter>>,
+}
+
+impl ApiLimits {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            per_token: config.per_token.map(RateLimiter::new),
+            per_endpoint: config.per_endpoint.map(RateLimiter::new),
+            reads: config
+                .max_concurrent_reads
+                .map(|max| Arc::new(ReadLimiter::new(max as usize))),
+        }
+    }
+
+    /// Counts a request of `token` to `endpoint_name`, and returns a permit to read the cache until it's dropped.
+    ///
+    /// A rejected request isn't counted against any limit.
+    pub fn acquire(
+        &self,
+        endpoint_name: &str,
+        token: Option<&str>,
+    ) -> Result<ReadPermit, ApiError> {
+        let now = Instant::now();
+        // Both buckets stay locked until the request is counted. They're always locked in this order.
+        let mut per_token = self
+            .per_token
+            .as_ref()
+            .zip(token)
+            .map(|(per_token, token)| per_token.lock(token, now));
+        let mut per_endpoint = self
+            .per_endpoint
+            .as_ref()
+            .map(|per_endpoint| per_endpoint.lock(endpoint_name, now));
+        if per_token
+            .as_ref()
+            .map_or(false, |bucket| !bucket.has_request())
+        {
+            return Err(ApiError::TooManyRequests("token"));
+        }
+        if per_endpoint
+            .as_ref()
+            .map_or(false, |bucket| !bucket.has_request())
+        {
+            return Err(ApiError::TooManyRequests("endpoint"));
+        }
+        let permit = match &self.reads {
+            Some(reads) => reads
+                .try_acquire()
+                .ok_or(ApiError::TooManyRequests("concurrent reads"))?,
+            None => ReadPermit(None),
+        };
+        for bucket in per_token.iter_mut().chain(per_endpoint.iter_mut()) {
+            bucket.take();
+        }
+        Ok(permit)
+    }
+}
+
+/// A token bucket per key, which holds a second of requests and refills continuously.
+///
+/// A bucket that has been idle for a second is full, like a new one, so such buckets are dropped.
+#[derive(Debug)]
+struct RateLimiter {
+    requests_per_second: f64,
+    buckets: Mutex<Buckets>,
+}
+
+#[derive(Debug)]
+struct Buckets {
+    by_key: HashMap<String, Bucket>,
+    swept_at: Instant,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// How long a bucket takes to refill, and how often full buckets are dropped.
+const REFILL_TIME: Duration = Duration::from_secs(1);
+
+impl RateLimiter {
+    fn new(requests_per_second: u32) -> Self {
+        Self {
+            requests_per_second: requests_per_second as f64,
+            buckets: Mutex::new(Buckets {
+                by_key: HashMap::new(),
+                swept_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Refills the bucket of `key` and locks all buckets until the returned bucket is dropped.
+    fn lock<'a>(&'a self, key: &'a str, now: Instant) -> LockedBucket<'a> {
+        let mut buckets = self.buckets.lock().unwrap();
+        if now.saturating_duration_since(buckets.swept_at) >= REFILL_TIME {
+            buckets
+                .by_key
+                .retain(|_, bucket| now.saturating_duration_since(bucket.updated_at) < REFILL_TIME);
+            buckets.swept_at = now;
+        }
+
+        let bucket = buckets.by_key.entry(key.to_string()).or_insert(Bucket {
+            tokens: self.requests_per_second,
+            updated_at: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * self.requests_per_second)
+            .min(self.requests_per_second);
+        bucket.updated_at = now;
+        LockedBucket { buckets, key }
+    }
+}
+
+struct LockedBucket<'a> {
+    buckets: MutexGuard<'a, Buckets>,
+    key: &'a str,
+}
+
+impl LockedBucket<'_> {
+    fn has_request(&self) -> bool {
+        self.buckets.by_key[self.key].tokens >= 1.0
+    }
+
+    fn take(&mut self) {
+        if let Some(bucket) = self.buckets.by_key.get_mut(self.key) {
+            bucket.tokens -= 1.0;
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ReadLimiter {
+    max: usize,
+    in_flight: AtomicUsize,
+}
+
+impl ReadLimiter {
+    fn new(max: usize) -> Self {
+        Self {
+            max,
+            in_flight: AtomicUsize::new(0),
+        }
+    }
+
+    fn try_acquire(self: &Arc<Self>) -> Option<ReadPermit> {
+        self.in_flight
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |in_flight| {
+                (in_flight < self.max).then_some(in_flight + 1)
+            })
+            .ok()
+            .map(|_| ReadPermit(Some(self.clone())))
+    }
+}
+
+/// Releases its read when dropped.
+#[derive(Debug)]
+pub struct ReadPermit(Option<Arc<ReadLimiter>>);
+
+impl Drop for ReadPermit {
+    fn drop(&mut self) {
+        if let Some(reads) = &self.0 {
+            reads.in_flight.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+}
+
+/// The token of an `Authorization: Bearer` header.
+pub fn bearer_token(authorization: &str) -> Option<&str> {
+    authorization.strip_prefix("Bearer ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn try_acquire_at(limiter: &RateLimiter, key: &str, now: Instant) -> bool {
+        let mut bucket = limiter.lock(key, now);
+        let has_request = bucket.has_request();
+        if has_request {
+            bucket.take();
+        }
+        has_request
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter::new(2);
+        let start = Instant::now();
+        assert!(try_acquire_at(&limiter, "a", start));
+        assert!(try_acquire_at(&limiter, "a", start));
+        assert!(!try_acquire_at(&limiter, "a", start));
+        // Every key has its own bucket.
+        assert!(try_acquire_at(&limiter, "b", start));
+
+        // Half a second refills one request.
+        let later = start + Duration::from_millis(500);
+        assert!(try_acquire_at(&limiter, "a", later));
+        assert!(!try_acquire_at(&limiter, "a", later));
+    }
+
+    #[test]
+    fn test_idle_buckets_are_dropped() {
+        let limiter = RateLimiter::new(1);
+        let start = Instant::now();
+        assert!(try_acquire_at(&limiter, "a", start));
+        assert!(try_acquire_at(
+            &limiter,
+            "b",
+            start + Duration::from_millis(500)
+        ));
+        assert_eq!(limiter.buckets.lock().unwrap().by_key.len(), 2);
+
+        // "a" is full again and dropped, "b" isn't yet.
+        let later = start + Duration::from_millis(1200);
+        assert!(!try_acquire_at(&limiter, "b", later));
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.by_key.keys().collect::<Vec<_>>(), vec!["b"]);
+    }
+
+    #[test]
+    fn test_concurrent_reads() {
+        let limits = ApiLimits::new(&RateLimitConfig {
+            per_token: None,
+            per_endpoint: None,
+            max_concurrent_reads: Some(1),
+        });
+        let permit = limits.acquire("films", None).unwrap();
+        assert!(matches!(
+            limits.acquire("films", None),
+            Err(ApiError::TooManyRequests(_))
+        ));
+        drop(permit);
+        assert!(limits.acquire("films", None).is_ok());
+    }
+
+    #[test]
+    fn test_per_token_and_endpoint() {
+        let limits = ApiLimits::new(&RateLimitConfig {
+            per_token: Some(1),
+            per_endpoint: Some(2),
+            max_concurrent_reads: None,
+        });
+        assert!(limits.acquire("films", Some("a")).is_ok());
+        assert!(limits.acquire("films", Some("a")).is_err());
+        assert!(limits.acquire("films", Some("b")).is_ok());
+        // The endpoint used up its 2 requests.
+        assert!(limits.acquire("films", Some("c")).is_err());
+        assert!(limits.acquire("actors", Some("c")).is_ok());
+        assert!(ApiLimits::default().acquire("films", None).is_ok());
+    }
+
+    #[test]
+    fn test_rejected_request_is_not_counted() {
+        let limits = ApiLimits::new(&RateLimitConfig {
+            per_token: Some(1),
+            per_endpoint: Some(1),
+            max_concurrent_reads: None,
+        });
+        assert!(limits.acquire("films", Some("a")).is_ok());
+        // Rejected by the endpoint, so "b" keeps its request.
+        assert!(limits.acquire("films", Some("b")).is_err());
+        assert!(limits.acquire("actors", Some("b")).is_ok());
+        // Rejected by the token, so "directors" keeps its request.
+        assert!(limits.acquire("directors", Some("a")).is_err());
+        assert!(limits.acquire("directors", Some("c")).is_ok());
+    }
+}
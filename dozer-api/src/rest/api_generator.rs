@@ -1,26 +1,42 @@
+use std::convert::Infallible;
 use std::sync::Arc;
 
+use actix_web::http::header::{ETag, EntityTag, IfNoneMatch};
 use actix_web::web::ReqData;
-use actix_web::{web, HttpResponse};
-use dozer_cache::cache::expression::{default_limit_for_query, QueryExpression, Skip};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use dozer_cache::cache::expression::{
+    default_limit_for_query, FilterExpression, QueryExpression, Skip,
+};
 use dozer_cache::cache::{index, RecordWithId};
+use dozer_cache::errors::CacheError;
 use dozer_cache::CacheReader;
-use dozer_types::chrono::SecondsFormat;
+use dozer_types::chrono::{DateTime, TimeZone, Utc};
 use dozer_types::errors::types::TypeError;
+use dozer_types::helper::json_value_to_field;
 use dozer_types::indexmap::IndexMap;
 use dozer_types::log::info;
 use dozer_types::models::api_endpoint::ApiEndpoint;
-use dozer_types::ordered_float::OrderedFloat;
-use dozer_types::types::{Field, Schema, DATE_FORMAT};
+use dozer_types::types::{Field, Schema};
 use openapiv3::OpenAPI;
 
-use crate::api_helper::{get_record, get_records, get_records_count};
+use crate::api_helper::{
+    authorize_events, get_record, get_records, get_records_by_keys, get_records_count,
+    lookup_records,
+};
+use crate::generator::client::{ClientGenerator, ClientLanguage};
 use crate::generator::oapi::generator::OpenApiGenerator;
+use crate::grpc::shared_impl::filter_events;
+use crate::grpc::types_helper::map_prost_value;
 use crate::RoCacheEndpoint;
 use crate::{auth::Access, errors::ApiError};
+use dozer_tracing::health::telemetry_health;
 use dozer_types::grpc_types::health::health_check_response::ServingStatus;
+use dozer_types::grpc_types::types::{self as grpc_types, Operation, OperationType};
+use dozer_types::serde::Deserialize;
 use dozer_types::serde_json;
-use dozer_types::serde_json::{json, Map, Value};
+use dozer_types::serde_json::{json, Value};
+use futures_util::StreamExt;
+use tokio::sync::broadcast::Receiver;
 
 fn generate_oapi3(reader: &CacheReader, endpoint: ApiEndpoint) -> Result<OpenAPI, ApiError> {
     let (schema, secondary_indexes) = reader
@@ -48,13 +64,45 @@ pub async fn generate_oapi(
     .map(|result| HttpResponse::Ok().json(result))
 }
 
+/// The ETag of the records of `cache_reader`, which changes whenever the cache commits.
+///
+/// It's read before the records, so a commit in between only makes the client fetch them again.
+fn cache_etag(cache_reader: &CacheReader) -> Result<EntityTag, ApiError> {
+    let epoch = cache_reader
+        .get_commit_epoch()
+        .map_err(ApiError::QueryFailed)?;
+    Ok(EntityTag::new_strong(format!(
+        "{}-{}",
+        cache_reader.cache_name(),
+        epoch
+    )))
+}
+
+/// `304 Not Modified` if the `If-None-Match` of `req` has `etag`.
+fn not_modified(req: &HttpRequest, etag: &EntityTag) -> Option<HttpResponse> {
+    let matches = match req.get_header::<IfNoneMatch>()? {
+        IfNoneMatch::Any => true,
+        IfNoneMatch::Items(items) => items.iter().any(|item| item.weak_eq(etag)),
+    };
+    matches.then(|| {
+        HttpResponse::NotModified()
+            .insert_header(ETag(etag.clone()))
+            .finish()
+    })
+}
+
 // Generated Get function to return a single record in JSON format
 pub async fn get(
+    req: HttpRequest,
     access: Option<ReqData<Access>>,
     cache_endpoint: ReqData<Arc<RoCacheEndpoint>>,
     path: web::Path<String>,
 ) -> Result<HttpResponse, ApiError> {
     let cache_reader = &cache_endpoint.cache_reader();
+    let etag = cache_etag(cache_reader)?;
+    if let Some(response) = not_modified(&req, &etag) {
+        return Ok(response);
+    }
     let schema = &cache_reader
         .get_schema_and_indexes_by_name(&cache_endpoint.endpoint.name)
         .map_err(ApiError::SchemaNotFound)?
@@ -72,29 +120,80 @@ pub async fn get(
 
     let key = index::get_primary_key(&[0], &[key]);
     let record = get_record(
-        &cache_endpoint.cache_reader(),
+        cache_reader,
+        &cache_endpoint.endpoint.name,
         &key,
         access.map(|a| a.into_inner()),
     )?;
 
-    Ok(record_to_map(record, schema).map(|map| HttpResponse::Ok().json(map))?)
+    Ok(record_to_map(record, schema)
+        .map(|map| HttpResponse::Ok().insert_header(ETag(etag)).json(map))?)
+}
+
+// Generated batch function to return the records of multiple primary keys in JSON format
+pub async fn batch(
+    access: Option<ReqData<Access>>,
+    cache_endpoint: ReqData<Arc<RoCacheEndpoint>>,
+    keys: web::Json<Vec<Value>>,
+) -> Result<HttpResponse, ApiError> {
+    let cache_reader = &cache_endpoint.cache_reader();
+    let schema = &cache_reader
+        .get_schema_and_indexes_by_name(&cache_endpoint.endpoint.name)
+        .map_err(ApiError::SchemaNotFound)?
+        .0;
+
+    let keys = keys.into_inner();
+    let field = match schema.primary_index.as_slice() {
+        [] => return Err(ApiError::NoPrimaryKey),
+        [index] => &schema.fields[*index],
+        _ => return Err(ApiError::MultiIndexFetch(Value::from(keys).to_string())),
+    };
+    let encoded_keys = keys
+        .iter()
+        .map(|key| {
+            let key = json_value_to_field(key.clone(), field.typ, field.nullable)?;
+            Ok(index::get_primary_key(&[0], &[key]))
+        })
+        .collect::<Result<Vec<_>, TypeError>>()?;
+
+    let records = get_records_by_keys(
+        cache_reader,
+        &cache_endpoint.endpoint.name,
+        &encoded_keys,
+        access.map(|a| a.into_inner()),
+    )?;
+    let mut found = vec![];
+    let mut missing = vec![];
+    for (key, record) in keys.into_iter().zip(records) {
+        match record {
+            Some(record) => found.push(record_to_map(record, schema)?),
+            None => missing.push(key),
+        }
+    }
+    Ok(HttpResponse::Ok().json(json!({ "found": found, "missing": missing })))
 }
 
 // Generated list function for multiple records with a default query expression
 pub async fn list(
+    req: HttpRequest,
     access: Option<ReqData<Access>>,
     cache_endpoint: ReqData<Arc<RoCacheEndpoint>>,
 ) -> Result<HttpResponse, ApiError> {
+    let etag = cache_etag(&cache_endpoint.cache_reader())?;
+    if let Some(response) = not_modified(&req, &etag) {
+        return Ok(response);
+    }
+
     let mut exp = QueryExpression::new(None, vec![], Some(50), Skip::Skip(0));
     match get_records_map(access, cache_endpoint, &mut exp) {
-        Ok(maps) => Ok(HttpResponse::Ok().json(maps)),
+        Ok(maps) => Ok(HttpResponse::Ok().insert_header(ETag(etag)).json(maps)),
         Err(e) => match e {
             ApiError::QueryFailed(_) => {
                 let res: Vec<String> = vec![];
                 info!("No records found.");
-                Ok(HttpResponse::Ok().json(res))
+                Ok(HttpResponse::Ok().insert_header(ETag(etag)).json(res))
             }
-            _ => Err(ApiError::InternalError(Box::new(e))),
+            _ => Err(e),
         },
     }
 }
@@ -102,10 +201,124 @@ pub async fn list(
 // Generated get function for health check
 pub async fn health_route() -> Result<HttpResponse, ApiError> {
     let status = ServingStatus::Serving;
-    let resp = json!({ "status": status.as_str_name() }).to_string();
+    let resp = json!({
+        "status": status.as_str_name(),
+        "telemetry": telemetry_health(),
+    })
+    .to_string();
     Ok(HttpResponse::Ok().body(resp))
 }
 
+// Generated get function for readiness check, ready once every endpoint's cache has committed
+pub async fn ready_route(
+    cache_endpoints: web::Data<Vec<Arc<RoCacheEndpoint>>>,
+) -> Result<HttpResponse, ApiError> {
+    let mut endpoints = serde_json::Map::new();
+    for cache_endpoint in cache_endpoints.iter() {
+        let epoch = cache_endpoint
+            .cache_reader()
+            .get_commit_epoch()
+            .map_err(ApiError::QueryFailed)?;
+        endpoints.insert(cache_endpoint.endpoint.name.clone(), json!(epoch > 0));
+    }
+    let ready = endpoints
+        .values()
+        .all(|ready| ready.as_bool() == Some(true));
+    let resp = json!({ "ready": ready, "endpoints": endpoints });
+    Ok(if ready {
+        HttpResponse::Ok().json(resp)
+    } else {
+        HttpResponse::ServiceUnavailable().json(resp)
+    })
+}
+
+// Generated get function for the checkpoints of the endpoints' caches and how far they lag behind their sources
+pub async fn lag_route(
+    cache_endpoints: web::Data<Vec<Arc<RoCacheEndpoint>>>,
+) -> Result<HttpResponse, ApiError> {
+    let now = Utc::now();
+    let mut endpoints = serde_json::Map::new();
+    for cache_endpoint in cache_endpoints.iter() {
+        let cache_reader = cache_endpoint.cache_reader();
+        endpoints.insert(
+            cache_endpoint.endpoint.name.clone(),
+            cache_lag(&cache_reader, now).map_err(ApiError::QueryFailed)?,
+        );
+    }
+    Ok(HttpResponse::Ok().json(endpoints))
+}
+
+fn cache_lag(cache_reader: &CacheReader, now: DateTime<Utc>) -> Result<Value, CacheError> {
+    let last_commit_time = cache_reader.get_last_commit_time()?;
+    let mut sources = cache_reader
+        .get_checkpoint()?
+        .into_iter()
+        .map(|(source, op)| {
+            let transaction = op.transaction.unwrap_or_default();
+            let commit_time = transaction.commit_timestamp.and_then(|micros| {
+                let nanos = (micros.rem_euclid(1_000_000) * 1_000) as u32;
+                Utc.timestamp_opt(micros.div_euclid(1_000_000), nanos)
+                    .single()
+            });
+            json!({
+                "source": source.id,
+                "ns": source.ns,
+                "txid": op.txid,
+                "seq_in_tx": op.seq_in_tx,
+                "transaction_id": transaction.id,
+                "commit_lsn": transaction.commit_lsn,
+                "commit_time": commit_time.map(|time| time.to_rfc3339()),
+                // How long ago the source committed what the cache has committed.
+                "lag_ms": commit_time.map(|time| (now - time).num_milliseconds()),
+            })
+        })
+        .collect::<Vec<_>>();
+    sources.sort_by_key(|source| source["source"].to_string());
+    Ok(json!({
+        "cache": cache_reader.cache_name(),
+        "commit_epoch": cache_reader.get_commit_epoch()?,
+        "last_commit_time": last_commit_time.map(|time| time.to_rfc3339()),
+        "since_last_commit_ms": last_commit_time.map(|time| (now - time).num_milliseconds()),
+        "sources": sources,
+    }))
+}
+
+// Generated get function for a typed Rust client of all the endpoints
+pub async fn rust_client_route(
+    cache_endpoints: web::Data<Vec<Arc<RoCacheEndpoint>>>,
+) -> Result<HttpResponse, ApiError> {
+    generate_client(ClientLanguage::Rust, &cache_endpoints)
+}
+
+// Generated get function for a typed TypeScript client of all the endpoints
+pub async fn typescript_client_route(
+    cache_endpoints: web::Data<Vec<Arc<RoCacheEndpoint>>>,
+) -> Result<HttpResponse, ApiError> {
+    generate_client(ClientLanguage::TypeScript, &cache_endpoints)
+}
+
+fn generate_client(
+    language: ClientLanguage,
+    cache_endpoints: &[Arc<RoCacheEndpoint>],
+) -> Result<HttpResponse, ApiError> {
+    let mut generator = ClientGenerator::new(language)?;
+    for cache_endpoint in cache_endpoints {
+        let cache_reader = cache_endpoint.cache_reader();
+        let schema = &cache_reader
+            .get_schema_and_indexes_by_name(&cache_endpoint.endpoint.name)
+            .map_err(ApiError::SchemaNotFound)?
+            .0;
+        generator.add_endpoint(
+            &cache_endpoint.endpoint.name,
+            &cache_endpoint.endpoint.path,
+            schema,
+        );
+    }
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(generator.generate()?))
+}
+
 pub async fn count(
     access: Option<ReqData<Access>>,
     cache_endpoint: ReqData<Arc<RoCacheEndpoint>>,
@@ -126,23 +339,188 @@ pub async fn count(
     .map(|count| HttpResponse::Ok().json(count))
 }
 
+#[derive(Debug, Default, Deserialize)]
+#[serde(crate = "dozer_types::serde", default)]
+pub struct QueryMode {
+    /// Responds with the count of the matching records, like `count`.
+    count: bool,
+    /// Responds with whether any record matches, counting at most one. Takes precedence over `count`.
+    exists: bool,
+}
+
+/// A one-to-one lookup in `$lookup` of `query`, which adds to every record the record of `endpoint` whose primary key
+/// is its `key` field, or null if there's none.
+#[derive(Debug, Deserialize)]
+#[serde(crate = "dozer_types::serde", deny_unknown_fields)]
+pub struct Lookup {
+    endpoint: String,
+    key: String,
+    /// The fields of the looked up records to add, all of them if `None`.
+    #[serde(default)]
+    fields: Option<Vec<String>>,
+    /// The name of the added field, `endpoint` if `None`.
+    #[serde(rename = "as", default)]
+    alias: Option<String>,
+}
+
 // Generated query function for multiple records
 pub async fn query(
     access: Option<ReqData<Access>>,
     cache_endpoint: ReqData<Arc<RoCacheEndpoint>>,
+    cache_endpoints: web::Data<Vec<Arc<RoCacheEndpoint>>>,
     query_info: Option<web::Json<Value>>,
+    mode: web::Query<QueryMode>,
 ) -> Result<HttpResponse, ApiError> {
-    let mut query_expression = match query_info {
-        Some(query_info) => serde_json::from_value::<QueryExpression>(query_info.0)
-            .map_err(ApiError::map_deserialization_error)?,
-        None => QueryExpression::with_default_limit(),
+    let (mut query_expression, lookups) = match query_info {
+        Some(query_info) => {
+            let mut query_info = query_info.0;
+            let lookups = match query_info
+                .as_object_mut()
+                .and_then(|query_info| query_info.remove("$lookup"))
+            {
+                Some(lookups) => serde_json::from_value::<Vec<Lookup>>(lookups)
+                    .map_err(ApiError::map_deserialization_error)?,
+                None => vec![],
+            };
+            let query_expression = serde_json::from_value::<QueryExpression>(query_info)
+                .map_err(ApiError::map_deserialization_error)?;
+            (query_expression, lookups)
+        }
+        None => (QueryExpression::with_no_limit(), vec![]),
     };
+
+    if mode.count || mode.exists {
+        if mode.exists {
+            query_expression.limit = Some(1);
+        }
+        let count = get_records_count(
+            &cache_endpoint.cache_reader(),
+            &cache_endpoint.endpoint.name,
+            &mut query_expression,
+            access.map(|a| a.into_inner()),
+        )?;
+        return Ok(if mode.exists {
+            HttpResponse::Ok().json(count > 0)
+        } else {
+            HttpResponse::Ok().json(count)
+        });
+    }
+
     if query_expression.limit.is_none() {
         query_expression.limit = Some(default_limit_for_query());
     }
 
-    get_records_map(access, cache_endpoint, &mut query_expression)
-        .map(|maps| HttpResponse::Ok().json(maps))
+    let maps = if lookups.is_empty() {
+        get_records_map(access, cache_endpoint, &mut query_expression)?
+    } else {
+        get_records_map_with_lookups(
+            access.map(|a| a.into_inner()),
+            &cache_endpoint,
+            &cache_endpoints,
+            &mut query_expression,
+            &lookups,
+        )?
+    };
+    Ok(HttpResponse::Ok().json(maps))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct EventsQuery {
+    /// A JSON filter expression, like the `$filter` of `query`.
+    filter: Option<String>,
+}
+
+// Generated events function to stream the inserts, updates and deletes of the matching records as server-sent events
+pub async fn events(
+    access: Option<ReqData<Access>>,
+    cache_endpoint: ReqData<Arc<RoCacheEndpoint>>,
+    operations_receiver: Option<web::Data<Receiver<Operation>>>,
+    query: web::Query<EventsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let operations_receiver = operations_receiver.ok_or(ApiError::EventsNotEnabled)?;
+    let filter = match query.into_inner().filter {
+        Some(filter) if !filter.is_empty() => Some(
+            serde_json::from_str::<FilterExpression>(&filter)
+                .map_err(ApiError::map_deserialization_error)?,
+        ),
+        _ => None,
+    };
+    let schema = cache_endpoint
+        .cache_reader()
+        .get_schema_and_indexes_by_name(&cache_endpoint.endpoint.name)
+        .map_err(ApiError::SchemaNotFound)?
+        .0
+        .clone();
+    let (filter, masked_fields) = authorize_events(
+        &schema,
+        &cache_endpoint.endpoint.name,
+        filter,
+        access.map(|a| a.into_inner()),
+    )?;
+
+    let endpoint_name = cache_endpoint.endpoint.name.clone();
+    let event_schema = schema.clone();
+    let events = filter_events(
+        operations_receiver.resubscribe(),
+        filter,
+        masked_fields,
+        schema,
+        move |op| {
+            if op.endpoint_name == endpoint_name {
+                Some(operation_to_event(op, &event_schema))
+            } else {
+                None
+            }
+        },
+    );
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(events.map(|event| Ok::<_, Infallible>(web::Bytes::from(event)))))
+}
+
+/// Formats an operation as a server-sent event named after its type, with the old and new records as data.
+fn operation_to_event(op: Operation, schema: &Schema) -> String {
+    let typ = OperationType::from_i32(op.typ)
+        .map_or("unknown", |typ| typ.as_str_name())
+        .to_lowercase();
+    let mut data = serde_json::Map::new();
+    if let Some(old) = op.old {
+        data.insert("old".to_string(), grpc_record_to_json(old, None, schema));
+    }
+    if let Some(new) = op.new {
+        data.insert(
+            "new".to_string(),
+            grpc_record_to_json(new, op.new_id, schema),
+        );
+    }
+    format!("event: {typ}\ndata: {}\n\n", Value::Object(data))
+}
+
+/// Used in REST events, like `record_to_map`
+fn grpc_record_to_json(record: grpc_types::Record, id: Option<u64>, schema: &Schema) -> Value {
+    let mut map: serde_json::Map<String, Value> = schema
+        .fields
+        .iter()
+        .zip(&record.values)
+        .map(|(field_def, value)| {
+            (
+                field_def.name.clone(),
+                map_prost_value(value, field_def.typ).to_json(),
+            )
+        })
+        .collect();
+
+    if let Some(id) = id {
+        map.insert("__dozer_record_id".to_string(), Value::from(id));
+    }
+    map.insert(
+        "__dozer_record_version".to_string(),
+        Value::from(record.version),
+    );
+
+    Value::Object(map)
 }
 
 /// Get multiple records
@@ -166,17 +544,90 @@ fn get_records_map(
     Ok(maps)
 }
 
+/// Get multiple records, each with the records that `lookups` look up with one `get_many` per lookup
+fn get_records_map_with_lookups(
+    access: Option<Access>,
+    cache_endpoint: &RoCacheEndpoint,
+    cache_endpoints: &[Arc<RoCacheEndpoint>],
+    exp: &mut QueryExpression,
+    lookups: &[Lookup],
+) -> Result<Vec<IndexMap<String, Value>>, ApiError> {
+    let cache_reader = &cache_endpoint.cache_reader();
+    let (schema, records) = get_records(
+        cache_reader,
+        &cache_endpoint.endpoint.name,
+        exp,
+        access.clone(),
+    )?;
+
+    let mut lookup_values = vec![];
+    for lookup in lookups {
+        let alias = lookup.alias.as_ref().unwrap_or(&lookup.endpoint);
+        if schema.fields.iter().any(|field| &field.name == alias) {
+            return Err(ApiError::InvalidLookup(format!(
+                "{alias} is already a field"
+            )));
+        }
+        let lookup_endpoint = cache_endpoints
+            .iter()
+            .find(|cache_endpoint| cache_endpoint.endpoint.name == lookup.endpoint)
+            .ok_or_else(|| {
+                ApiError::InvalidLookup(format!("unknown endpoint {}", lookup.endpoint))
+            })?;
+        let lookup_reader = &lookup_endpoint.cache_reader();
+        let (lookup_schema, looked_up) = lookup_records(
+            lookup_reader,
+            &lookup.endpoint,
+            schema,
+            &records,
+            &lookup.key,
+            access.clone(),
+        )?;
+        if let Some(fields) = &lookup.fields {
+            if let Some(field) = fields
+                .iter()
+                .find(|field| !lookup_schema.fields.iter().any(|def| &def.name == *field))
+            {
+                return Err(ApiError::InvalidLookup(format!(
+                    "unknown field {field} of {}",
+                    lookup.endpoint
+                )));
+            }
+        }
+        let values = looked_up
+            .into_iter()
+            .map(|record| {
+                let Some(record) = record else {
+                    return Ok(Value::Null);
+                };
+                let mut map = record_to_map(record, lookup_schema)?;
+                if let Some(fields) = &lookup.fields {
+                    map.retain(|name, _| fields.contains(name));
+                }
+                Ok(Value::Object(map.into_iter().collect()))
+            })
+            .collect::<Result<Vec<_>, TypeError>>()?;
+        lookup_values.push((alias.clone(), values));
+    }
+
+    let mut maps = records
+        .into_iter()
+        .map(|record| record_to_map(record, schema))
+        .collect::<Result<Vec<_>, _>>()?;
+    for (alias, values) in lookup_values {
+        for (map, value) in maps.iter_mut().zip(values) {
+            map.insert(alias.clone(), value);
+        }
+    }
+    Ok(maps)
+}
+
 /// Used in REST APIs for converting to JSON
 fn record_to_map(
     record: RecordWithId,
     schema: &Schema,
 ) -> Result<IndexMap<String, Value>, TypeError> {
-    let mut map = IndexMap::new();
-
-    for (field_def, field) in schema.fields.iter().zip(record.record.values) {
-        let val = field_to_json_value(field);
-        map.insert(field_def.name.clone(), val);
-    }
+    let mut map = record.record.to_json(schema);
 
     map.insert("__dozer_record_id".to_string(), Value::from(record.id));
     map.insert(
@@ -186,90 +637,3 @@ fn record_to_map(
 
     Ok(map)
 }
-
-fn convert_x_y_to_object((x, y): &(OrderedFloat<f64>, OrderedFloat<f64>)) -> Value {
-    let mut m = Map::new();
-    m.insert("x".to_string(), Value::from(x.0));
-    m.insert("y".to_string(), Value::from(y.0));
-    Value::Object(m)
-}
-
-/// Used in REST APIs for converting raw value back and forth.
-///
-/// Should be consistent with `convert_cache_type_to_schema_type`.
-fn field_to_json_value(field: Field) -> Value {
-    match field {
-        Field::UInt(n) => Value::from(n),
-        Field::Int(n) => Value::from(n),
-        Field::Float(n) => Value::from(n.0),
-        Field::Boolean(b) => Value::from(b),
-        Field::String(s) => Value::from(s),
-        Field::Text(n) => Value::from(n),
-        Field::Binary(b) => Value::from(b),
-        Field::Decimal(n) => Value::String(n.to_string()),
-        Field::Timestamp(ts) => Value::String(ts.to_rfc3339_opts(SecondsFormat::Millis, true)),
-        Field::Date(n) => Value::String(n.format(DATE_FORMAT).to_string()),
-        Field::Bson(b) => Value::from(b),
-        Field::Point(point) => convert_x_y_to_object(&point.0.x_y()),
-        Field::Null => Value::Null,
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use dozer_types::{
-        chrono::{NaiveDate, Offset, TimeZone, Utc},
-        json_value_to_field,
-        ordered_float::OrderedFloat,
-        rust_decimal::Decimal,
-        types::{DozerPoint, Field, FieldType},
-    };
-
-    use super::*;
-
-    fn test_field_conversion(field_type: FieldType, field: Field) {
-        // Convert the field to a JSON value.
-        let value = field_to_json_value(field.clone());
-
-        // Convert the JSON value back to a Field.
-        let deserialized = json_value_to_field(value, field_type, true).unwrap();
-
-        assert_eq!(deserialized, field, "must be equal");
-    }
-
-    #[test]
-    fn test_field_types_json_conversion() {
-        let fields = vec![
-            (FieldType::Int, Field::Int(-1)),
-            (FieldType::UInt, Field::UInt(1)),
-            (FieldType::Float, Field::Float(OrderedFloat(1.1))),
-            (FieldType::Boolean, Field::Boolean(true)),
-            (FieldType::String, Field::String("a".to_string())),
-            (FieldType::Binary, Field::Binary(b"asdf".to_vec())),
-            (FieldType::Decimal, Field::Decimal(Decimal::new(202, 2))),
-            (
-                FieldType::Timestamp,
-                Field::Timestamp(Utc.fix().with_ymd_and_hms(2001, 1, 1, 0, 4, 0).unwrap()),
-            ),
-            (
-                FieldType::Date,
-                Field::Date(NaiveDate::from_ymd_opt(2022, 11, 24).unwrap()),
-            ),
-            (
-                FieldType::Bson,
-                Field::Bson(vec![
-                    // BSON representation of `{"abc":"foo"}`
-                    123, 34, 97, 98, 99, 34, 58, 34, 102, 111, 111, 34, 125,
-                ]),
-            ),
-            (FieldType::Text, Field::Text("lorem ipsum".to_string())),
-            (
-                FieldType::Point,
-                Field::Point(DozerPoint::from((3.234, 4.567))),
-            ),
-        ];
-        for (field_type, field) in fields {
-            test_field_conversion(field_type, field);
-        }
-    }
-}
@@ -2,24 +2,31 @@ use std::sync::Arc;
 
 // Exports
 use crate::errors::ApiError;
-use crate::rest::api_generator::health_route;
+use crate::rest::api_generator::{
+    health_route, lag_route, ready_route, rust_client_route, typescript_client_route,
+};
 use crate::{
     auth::api::{auth_route, validate},
+    limits::bearer_token,
     RoCacheEndpoint,
 };
 use actix_cors::Cors;
 use actix_web::{
     body::MessageBody,
     dev::{ServerHandle, Service, ServiceFactory, ServiceRequest, ServiceResponse},
+    http::header,
     middleware::{Condition, Logger},
     rt, web, App, HttpMessage, HttpServer,
 };
 use actix_web_httpauth::middleware::HttpAuthentication;
+use dozer_types::grpc_types::types::Operation;
 use dozer_types::{crossbeam::channel::Sender, log::info, models::api_config::RestApiOptions};
 use dozer_types::{
     models::api_security::ApiSecurity,
     serde::{self, Deserialize, Serialize},
 };
+use futures_util::future::{ready, Either};
+use tokio::sync::broadcast::Receiver;
 use tracing_actix_web::TracingLogger;
 
 mod api_generator;
@@ -76,6 +83,7 @@ impl ApiServer {
         security: Option<ApiSecurity>,
         cors: CorsOptions,
         cache_endpoints: Vec<Arc<RoCacheEndpoint>>,
+        operations_receiver: Option<Receiver<Operation>>,
     ) -> App<
         impl ServiceFactory<
             ServiceRequest,
@@ -96,6 +104,12 @@ impl ApiServer {
         } else {
             false
         };
+        // Injecting all the endpoints for `ready_route`, `lag_route` and the client routes
+        app = app.app_data(web::Data::new(cache_endpoints.clone()));
+        if let Some(operations_receiver) = operations_receiver {
+            // Injecting the operations that `events` streams
+            app = app.app_data(web::Data::new(operations_receiver));
+        }
         let auth_middleware =
             Condition::new(is_auth_configured, HttpAuthentication::bearer(validate));
 
@@ -110,12 +124,32 @@ impl ApiServer {
                     web::scope(scope)
                         // Inject cache_endpoint for generated functions
                         .wrap_fn(move |req, srv| {
+                            let token = req
+                                .headers()
+                                .get(header::AUTHORIZATION)
+                                .and_then(|value| value.to_str().ok())
+                                .and_then(bearer_token);
+                            let permit = match cache_endpoint
+                                .limits()
+                                .acquire(&cache_endpoint.endpoint().name, token)
+                            {
+                                Ok(permit) => permit,
+                                Err(e) => return Either::Left(ready(Err(e.into()))),
+                            };
                             req.extensions_mut().insert(cache_endpoint.clone());
-                            srv.call(req)
+                            let response = srv.call(req);
+                            Either::Right(async move {
+                                let response = response.await;
+                                drop(permit);
+                                response
+                            })
                         })
                         .route("/count", web::post().to(api_generator::count))
                         .route("/query", web::post().to(api_generator::query))
+                        .route("/batch", web::post().to(api_generator::batch))
                         .route("/oapi", web::post().to(api_generator::generate_oapi))
+                        .route("/oapi", web::get().to(api_generator::generate_oapi))
+                        .route("/events", web::get().to(api_generator::events))
                         .route("/{id}", web::get().to(api_generator::get))
                         .route("/", web::get().to(api_generator::list))
                         .route("", web::get().to(api_generator::list)),
@@ -125,6 +159,12 @@ impl ApiServer {
             .route("/auth/token", web::post().to(auth_route))
             // Attach health route
             .route("/health", web::get().to(health_route))
+            // Attach readiness and lag routes
+            .route("/ready", web::get().to(ready_route))
+            .route("/lag", web::get().to(lag_route))
+            // Attach typed client routes
+            .route("/sdk/rust", web::get().to(rust_client_route))
+            .route("/sdk/typescript", web::get().to(typescript_client_route))
             // Wrap Api Validator
             .wrap(auth_middleware)
             // Wrap CORS around api validator. Required to return the right headers.
@@ -134,6 +174,7 @@ impl ApiServer {
     pub async fn run(
         &self,
         cache_endpoints: Vec<Arc<RoCacheEndpoint>>,
+        operations_receiver: Option<Receiver<Operation>>,
         tx: Sender<ServerHandle>,
     ) -> Result<(), ApiError> {
         info!(
@@ -144,13 +185,21 @@ impl ApiServer {
                 .as_ref()
                 .map_or("None".to_string(), |s| match s {
                     ApiSecurity::Jwt(_) => "JWT".to_string(),
+                    ApiSecurity::ApiKeys(_) => "API keys".to_string(),
                 })
         );
         let cors = self.cors.clone();
         let security = self.security.clone();
         let address = format!("{}:{}", self.host, self.port);
+        // Every worker subscribes to the operations.
+        let operations_receiver = operations_receiver.map(Arc::new);
         let server = HttpServer::new(move || {
-            ApiServer::create_app_entry(security.clone(), cors.clone(), cache_endpoints.clone())
+            ApiServer::create_app_entry(
+                security.clone(),
+                cors.clone(),
+                cache_endpoints.clone(),
+                operations_receiver.as_ref().map(|r| r.resubscribe()),
+            )
         })
         .bind(&address)
         .map_err(|e| ApiError::FailedToBindToAddress(address, e))?
@@ -7,7 +7,7 @@ use crate::{
 };
 use actix_web::{body::MessageBody, dev::ServiceResponse};
 use dozer_types::{
-    models::api_security::ApiSecurity,
+    models::api_security::{ApiKey, ApiKeyEndpoint, ApiKeys, ApiSecurity},
     serde,
     serde::{Deserialize, Serialize},
     serde_json::{json, Value},
@@ -72,6 +72,7 @@ async fn check_status(
         vec![Arc::new(
             RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
         )],
+        None,
     );
     let app = actix_web::test::init_service(api_server).await;
 
@@ -100,6 +101,7 @@ async fn _call_auth_token_api(
         vec![Arc::new(
             RoCacheEndpoint::new(&*cache_manager, endpoint).unwrap(),
         )],
+        None,
     );
     let app = actix_web::test::init_service(api_server).await;
 
@@ -118,3 +120,144 @@ async fn _call_auth_token_api(
     let req = req.to_request();
     actix_web::test::call_service(&app, req).await
 }
+
+async fn query_with_token(
+    security: ApiSecurity,
+    token: &str,
+    query: Value,
+) -> ServiceResponse<impl MessageBody> {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let api_server = ApiServer::create_app_entry(
+        Some(security),
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
+        )],
+        None,
+    );
+    let app = actix_web::test::init_service(api_server).await;
+
+    let req = actix_web::test::TestRequest::post()
+        .uri(&format!("{}/query", endpoint.path))
+        .append_header(("Authorization", format!("Bearer {token}")))
+        .set_json(query)
+        .to_request();
+    actix_web::test::call_service(&app, req).await
+}
+
+#[actix_web::test]
+async fn api_keys_authorization_test() {
+    let security = ApiSecurity::ApiKeys(ApiKeys {
+        keys: vec![
+            ApiKey {
+                key: "films-key".to_string(),
+                endpoints: vec![ApiKeyEndpoint {
+                    name: "films".to_string(),
+                    filter_fields: vec!["film_id".to_string()],
+                    filter: None,
+                    masked_fields: vec![],
+                }],
+            },
+            ApiKey {
+                key: "actors-key".to_string(),
+                endpoints: vec![ApiKeyEndpoint {
+                    name: "actors".to_string(),
+                    filter_fields: vec![],
+                    filter: None,
+                    masked_fields: vec![],
+                }],
+            },
+        ],
+    });
+
+    let res = query_with_token(
+        security.clone(),
+        "films-key",
+        json!({"$filter": {"film_id": 268}}),
+    )
+    .await;
+    assert!(res.status().is_success());
+
+    // Unknown key
+    let res = query_with_token(security.clone(), "wrong-key", json!({})).await;
+    assert_eq!(res.status().as_u16(), 401, "Should be unauthorized.");
+
+    // Key of another endpoint
+    let res = query_with_token(security.clone(), "actors-key", json!({})).await;
+    assert_eq!(res.status().as_u16(), 403, "Should be forbidden.");
+
+    // Field that the key may not filter on
+    let res = query_with_token(
+        security,
+        "films-key",
+        json!({"$filter": {"release_year": 2006}}),
+    )
+    .await;
+    assert_eq!(res.status().as_u16(), 403, "Should be forbidden.");
+}
+
+#[actix_web::test]
+async fn row_and_column_security_test() {
+    let security = ApiSecurity::ApiKeys(ApiKeys {
+        keys: vec![ApiKey {
+            key: "tenant-key".to_string(),
+            endpoints: vec![ApiKeyEndpoint {
+                name: "films".to_string(),
+                filter_fields: vec![],
+                filter: Some(r#"{"film_id": 268}"#.to_string()),
+                masked_fields: vec!["rental_rate".to_string()],
+            }],
+        }],
+    });
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let api_server = ApiServer::create_app_entry(
+        Some(security),
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
+        )],
+        None,
+    );
+    let app = actix_web::test::init_service(api_server).await;
+    let request = |req: actix_web::test::TestRequest| {
+        req.append_header(("Authorization", "Bearer tenant-key"))
+            .to_request()
+    };
+
+    // Only the rows of the filter, with the masked fields as null
+    let req = request(
+        actix_web::test::TestRequest::post()
+            .uri(&format!("{}/query", endpoint.path))
+            .set_json(json!({})),
+    );
+    let res = actix_web::test::call_service(&app, req).await;
+    assert!(res.status().is_success());
+    let body: Value = actix_web::test::read_body_json(res).await;
+    let records = body.as_array().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["film_id"], json!(268));
+    assert_eq!(records[0]["rental_rate"], Value::Null);
+
+    let req =
+        request(actix_web::test::TestRequest::get().uri(&format!("{}/{}", endpoint.path, 268)));
+    let res = actix_web::test::call_service(&app, req).await;
+    assert!(res.status().is_success());
+    let body: Value = actix_web::test::read_body_json(res).await;
+    assert_eq!(body["rental_rate"], Value::Null);
+
+    let req =
+        request(actix_web::test::TestRequest::get().uri(&format!("{}/{}", endpoint.path, 524)));
+    let res = actix_web::test::call_service(&app, req).await;
+    assert_eq!(res.status().as_u16(), 404, "Should be hidden.");
+
+    // Masked fields can't be filtered on
+    let req = request(
+        actix_web::test::TestRequest::post()
+            .uri(&format!("{}/count", endpoint.path))
+            .set_json(json!({"$filter": {"rental_rate": 0.99}})),
+    );
+    let res = actix_web::test::call_service(&app, req).await;
+    assert_eq!(res.status().as_u16(), 403, "Should be forbidden.");
+}
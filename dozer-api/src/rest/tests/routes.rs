@@ -1,10 +1,18 @@
 use std::{fmt::Debug, sync::Arc};
 
 use super::super::{ApiServer, CorsOptions};
-use crate::{generator::oapi::generator::OpenApiGenerator, test_utils, RoCacheEndpoint};
-use actix_http::{body::MessageBody, Request};
+use crate::{
+    generator::oapi::generator::OpenApiGenerator, limits::ApiLimits, test_utils, RoCacheEndpoint,
+};
+use actix_http::{body::MessageBody, Request, StatusCode};
 use actix_web::dev::{Service, ServiceResponse};
+use actix_web::http::header;
+use dozer_cache::cache::{CacheManager, LmdbCacheManager};
+use dozer_types::grpc_types::types::{self as grpc_types, value, Operation, OperationType};
+use dozer_types::models::api_config::RateLimitConfig;
 use dozer_types::serde_json::{json, Value};
+use futures_util::future::poll_fn;
+use tokio::sync::broadcast;
 
 #[test]
 fn test_generate_oapi() {
@@ -19,7 +27,7 @@ fn test_generate_oapi() {
     );
     let generated = oapi_generator.generate_oas3();
 
-    assert_eq!(generated.paths.paths.len(), 4, " paths must be generated");
+    assert_eq!(generated.paths.paths.len(), 5, " paths must be generated");
 }
 
 #[actix_web::test]
@@ -32,6 +40,7 @@ async fn list_route() {
         vec![Arc::new(
             RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
         )],
+        None,
     );
     let app = actix_web::test::init_service(api_server).await;
 
@@ -91,6 +100,7 @@ async fn count_and_query_route() {
         vec![Arc::new(
             RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
         )],
+        None,
     );
     let app = actix_web::test::init_service(api_server).await;
 
@@ -136,6 +146,7 @@ async fn get_route() {
         vec![Arc::new(
             RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
         )],
+        None,
     );
     let app = actix_web::test::init_service(api_server).await;
     let req = actix_web::test::TestRequest::get()
@@ -154,3 +165,438 @@ async fn get_route() {
         "Must be equal"
     );
 }
+
+#[actix_web::test]
+async fn health_route() {
+    let api_server = ApiServer::create_app_entry(None, CorsOptions::Permissive, vec![], None);
+    let app = actix_web::test::init_service(api_server).await;
+    let req = actix_web::test::TestRequest::get()
+        .uri("/health")
+        .to_request();
+
+    let res = actix_web::test::call_service(&app, req).await;
+    assert!(res.status().is_success());
+
+    let body: Value = actix_web::test::read_body_json(res).await;
+    assert_eq!(body["status"], json!("SERVING"));
+    assert_eq!(body["telemetry"]["exporters"], json!([]));
+}
+
+#[actix_web::test]
+async fn ready_and_lag_routes() {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
+        )],
+        None,
+    );
+    let app = actix_web::test::init_service(api_server).await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/ready")
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert!(res.status().is_success());
+    let body: Value = actix_web::test::read_body_json(res).await;
+    assert_eq!(body, json!({"ready": true, "endpoints": {"films": true}}));
+
+    let req = actix_web::test::TestRequest::get().uri("/lag").to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert!(res.status().is_success());
+    let body: Value = actix_web::test::read_body_json(res).await;
+    assert_eq!(body["films"]["commit_epoch"], json!(1));
+    assert!(body["films"]["last_commit_time"].is_string());
+    assert_eq!(body["films"]["sources"], json!([]));
+}
+
+#[actix_web::test]
+async fn client_routes() {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            RoCacheEndpoint::new(&*cache_manager, endpoint).unwrap(),
+        )],
+        None,
+    );
+    let app = actix_web::test::init_service(api_server).await;
+
+    for (uri, expected) in [
+        ("/sdk/rust", "pub struct Film {"),
+        ("/sdk/typescript", "export interface Film {"),
+    ] {
+        let req = actix_web::test::TestRequest::get().uri(uri).to_request();
+        let res = actix_web::test::call_service(&app, req).await;
+        assert!(res.status().is_success());
+        let body = actix_web::test::read_body(res).await;
+        assert!(std::str::from_utf8(&body).unwrap().contains(expected));
+    }
+}
+
+#[actix_web::test]
+async fn ready_route_before_commit() {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = LmdbCacheManager::new(Default::default()).unwrap();
+    let (schema, secondary_indexes) = test_utils::get_schema();
+    let cache = cache_manager
+        .create_cache(vec![(endpoint.name.clone(), schema, secondary_indexes)])
+        .unwrap();
+    cache_manager
+        .create_alias(cache.name(), &endpoint.name)
+        .unwrap();
+    drop(cache);
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            RoCacheEndpoint::new(&cache_manager, endpoint.clone()).unwrap(),
+        )],
+        None,
+    );
+    let app = actix_web::test::init_service(api_server).await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri("/ready")
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body: Value = actix_web::test::read_body_json(res).await;
+    assert_eq!(body, json!({"ready": false, "endpoints": {"films": false}}));
+}
+
+#[actix_web::test]
+async fn oapi_route() {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
+        )],
+        None,
+    );
+    let app = actix_web::test::init_service(api_server).await;
+    let req = actix_web::test::TestRequest::get()
+        .uri(&format!("{}/oapi", endpoint.path))
+        .to_request();
+
+    let res = actix_web::test::call_service(&app, req).await;
+    assert!(res.status().is_success());
+
+    let body: Value = actix_web::test::read_body_json(res).await;
+    assert!(body["openapi"].as_str().unwrap().starts_with("3."));
+    assert_eq!(body["paths"].as_object().unwrap().len(), 5);
+}
+
+fn film_operation(endpoint_name: &str, film_id: u64) -> Operation {
+    let some = |value| grpc_types::Value { value: Some(value) };
+    Operation {
+        typ: OperationType::Insert as i32,
+        old: None,
+        new: Some(grpc_types::Record {
+            values: vec![
+                some(value::Value::UintValue(film_id)),
+                some(value::Value::StringValue("A film".to_string())),
+                some(value::Value::FloatValue(0.99)),
+                some(value::Value::UintValue(2006)),
+                grpc_types::Value { value: None },
+            ],
+            version: 1,
+        }),
+        new_id: Some(film_id),
+        endpoint_name: endpoint_name.to_string(),
+    }
+}
+
+#[actix_web::test]
+async fn events_route() {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let (sender, receiver) = broadcast::channel(16);
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
+        )],
+        Some(receiver),
+    );
+    let app = actix_web::test::init_service(api_server).await;
+    let req = actix_web::test::TestRequest::get()
+        .uri(&format!(
+            "{}/events?filter=%7B%22film_id%22%3A268%7D",
+            endpoint.path
+        ))
+        .to_request();
+
+    let res = actix_web::test::call_service(&app, req).await;
+    assert!(res.status().is_success());
+    assert_eq!(
+        res.headers().get("content-type").unwrap(),
+        "text/event-stream"
+    );
+
+    // Only the operations of the endpoint that satisfy the filter are streamed.
+    sender.send(film_operation("other", 268)).unwrap();
+    sender.send(film_operation(&endpoint.name, 1)).unwrap();
+    sender.send(film_operation(&endpoint.name, 268)).unwrap();
+    let mut body = Box::pin(res.into_body());
+    let Some(Ok(event)) = poll_fn(|cx| body.as_mut().poll_next(cx)).await else {
+        panic!("Must stream an event");
+    };
+    let event = std::str::from_utf8(&event).unwrap();
+    let data = event
+        .strip_prefix("event: insert\ndata: ")
+        .and_then(|data| data.strip_suffix("\n\n"))
+        .unwrap();
+    let data: Value = dozer_types::serde_json::from_str(data).unwrap();
+    assert_eq!(data["new"]["film_id"], json!(268));
+    assert_eq!(data["new"]["rental_rate"], json!(0.99));
+    assert_eq!(data["new"]["updated_at"], Value::Null);
+    assert_eq!(data["new"]["__dozer_record_id"], json!(268));
+    assert!(data.get("old").is_none());
+}
+
+#[actix_web::test]
+async fn events_route_not_enabled() {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
+        )],
+        None,
+    );
+    let app = actix_web::test::init_service(api_server).await;
+    let req = actix_web::test::TestRequest::get()
+        .uri(&format!("{}/events", endpoint.path))
+        .to_request();
+
+    let res = actix_web::test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[actix_web::test]
+async fn rate_limited_route() {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let limits = ApiLimits::new(&RateLimitConfig {
+        per_token: None,
+        per_endpoint: Some(1),
+        max_concurrent_reads: None,
+    });
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            RoCacheEndpoint::new(&*cache_manager, endpoint.clone())
+                .unwrap()
+                .with_limits(Arc::new(limits)),
+        )],
+        None,
+    );
+    let app = actix_web::test::init_service(api_server).await;
+
+    let req = actix_web::test::TestRequest::get()
+        .uri(&endpoint.path)
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert!(res.status().is_success());
+
+    let req = actix_web::test::TestRequest::get()
+        .uri(&endpoint.path)
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // Health is not limited.
+    let req = actix_web::test::TestRequest::get()
+        .uri("/health")
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert!(res.status().is_success());
+}
+
+#[actix_web::test]
+async fn etag_route() {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
+        )],
+        None,
+    );
+    let app = actix_web::test::init_service(api_server).await;
+
+    for path in [endpoint.path.clone(), format!("{}/268", endpoint.path)] {
+        let req = actix_web::test::TestRequest::get().uri(&path).to_request();
+        let res = actix_web::test::call_service(&app, req).await;
+        assert!(res.status().is_success());
+        let etag = res.headers().get(header::ETAG).unwrap().clone();
+
+        // The cache hasn't committed since.
+        let req = actix_web::test::TestRequest::get()
+            .uri(&path)
+            .insert_header((header::IF_NONE_MATCH, etag.clone()))
+            .to_request();
+        let res = actix_web::test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(res.headers().get(header::ETAG), Some(&etag));
+
+        let req = actix_web::test::TestRequest::get()
+            .uri(&path)
+            .insert_header((header::IF_NONE_MATCH, "\"other-0\""))
+            .to_request();
+        let res = actix_web::test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
+
+#[actix_web::test]
+async fn query_lookup_route() {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
+        )],
+        None,
+    );
+    let app = actix_web::test::init_service(api_server).await;
+
+    let query = |query: Value| {
+        actix_web::test::TestRequest::post()
+            .uri(&format!("{}/query", endpoint.path))
+            .set_json(query)
+            .to_request()
+    };
+
+    // Every film looks itself up.
+    let res = actix_web::test::call_service(
+        &app,
+        query(json!({
+            "$filter": {"film_id": 268},
+            "$lookup": [
+                {"endpoint": "films", "key": "film_id", "fields": ["description"], "as": "same"},
+                {"endpoint": "films", "key": "release_year", "as": "by_year"},
+            ],
+        })),
+    )
+    .await;
+    assert!(res.status().is_success());
+    let body: Value = actix_web::test::read_body_json(res).await;
+    let records = body.as_array().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(
+        records[0]["same"],
+        json!({"description": records[0]["description"]})
+    );
+    assert_eq!(records[0]["by_year"], Value::Null);
+
+    for lookup in [
+        json!({"endpoint": "actors", "key": "film_id"}),
+        json!({"endpoint": "films", "key": "actor_id"}),
+        json!({"endpoint": "films", "key": "film_id", "fields": ["name"]}),
+        json!({"endpoint": "films", "key": "film_id", "as": "description"}),
+    ] {
+        let res = actix_web::test::call_service(&app, query(json!({ "$lookup": [lookup] }))).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+}
+
+#[actix_web::test]
+async fn query_modes_route() {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
+        )],
+        None,
+    );
+    let app = actix_web::test::init_service(api_server).await;
+
+    let query_mode = |mode: &str, query: Value| {
+        actix_web::test::TestRequest::post()
+            .uri(&format!("{}/query?{mode}=true", endpoint.path))
+            .set_json(query)
+            .to_request()
+    };
+
+    let res = actix_web::test::call_service(&app, query_mode("count", json!({"$limit": 10}))).await;
+    assert!(res.status().is_success());
+    let body: Value = actix_web::test::read_body_json(res).await;
+    assert_eq!(body, json!(10));
+
+    // Unlike the records of `query`, the count has no default limit.
+    let res = actix_web::test::call_service(&app, query_mode("count", json!({}))).await;
+    let body: Value = actix_web::test::read_body_json(res).await;
+    assert_eq!(body, json!(52));
+
+    let res = actix_web::test::call_service(
+        &app,
+        query_mode("exists", json!({"$filter": {"film_id": 268}})),
+    )
+    .await;
+    let body: Value = actix_web::test::read_body_json(res).await;
+    assert_eq!(body, json!(true));
+
+    let res = actix_web::test::call_service(
+        &app,
+        query_mode("exists", json!({"$filter": {"film_id": 100000}})),
+    )
+    .await;
+    let body: Value = actix_web::test::read_body_json(res).await;
+    assert_eq!(body, json!(false));
+}
+
+#[actix_web::test]
+async fn batch_route() {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        vec![Arc::new(
+            RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
+        )],
+        None,
+    );
+    let app = actix_web::test::init_service(api_server).await;
+
+    let req = actix_web::test::TestRequest::post()
+        .uri(&format!("{}/batch", endpoint.path))
+        .set_json(json!([524, 100000, 268]))
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert!(res.status().is_success());
+    let body: Value = actix_web::test::read_body_json(res).await;
+    let found = body["found"].as_array().unwrap();
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0]["film_id"], json!(524));
+    assert_eq!(found[1]["film_id"], json!(268));
+    assert_eq!(body["missing"], json!([100000]));
+
+    let req = actix_web::test::TestRequest::post()
+        .uri(&format!("{}/batch", endpoint.path))
+        .set_json(json!(["not a film id"]))
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
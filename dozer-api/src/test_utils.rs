@@ -14,30 +14,35 @@ pub fn get_schema() -> (Schema, Vec<IndexDefinition>) {
             typ: FieldType::UInt,
             nullable: false,
             source: SourceDefinition::Dynamic,
+            metadata: Default::default(),
         },
         FieldDefinition {
             name: "description".to_string(),
             typ: FieldType::String,
             nullable: true,
             source: SourceDefinition::Dynamic,
+            metadata: Default::default(),
         },
         FieldDefinition {
             name: "rental_rate".to_string(),
             typ: FieldType::Float,
             nullable: true,
             source: SourceDefinition::Dynamic,
+            metadata: Default::default(),
         },
         FieldDefinition {
             name: "release_year".to_string(),
             typ: FieldType::UInt,
             nullable: true,
             source: SourceDefinition::Dynamic,
+            metadata: Default::default(),
         },
         FieldDefinition {
             name: "updated_at".to_string(),
             typ: FieldType::Timestamp,
             nullable: true,
             source: SourceDefinition::Dynamic,
+            metadata: Default::default(),
         },
     ];
     let secondary_indexes = fields
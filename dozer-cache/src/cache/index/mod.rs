@@ -9,22 +9,24 @@ pub trait CacheIndex {
     fn get_key(schema_id: u32, field_idx: &usize, field_val: &[u8]) -> Vec<u8>;
 }
 
+use dozer_storage::CompositeKey;
 use dozer_types::types::Field;
 
 use crate::errors::CompareError;
 
+/// Returns the primary key of a record with `values`, as a `CompositeKey` of the encoded primary key fields.
 pub fn get_primary_key(primary_index: &[usize], values: &[Field]) -> Vec<u8> {
     debug_assert!(
         !primary_index.is_empty(),
         "Primary key indexes cannot be empty"
     );
 
-    let key: Vec<Vec<u8>> = primary_index
-        .iter()
-        .map(|idx| values[*idx].encode())
-        .collect();
-
-    key.join("#".as_bytes())
+    let mut key = CompositeKey::new();
+    for idx in primary_index {
+        key.push(values[*idx].encode().as_slice())
+            .expect("Encoding bytes cannot fail");
+    }
+    key.into_bytes()
 }
 
 /// Returns the secondary index key for a given set of fields.
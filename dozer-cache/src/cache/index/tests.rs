@@ -1,8 +1,8 @@
-use dozer_types::types::field_test_cases;
+use dozer_types::types::{field_test_cases, Field};
 
 use crate::cache::index::{get_composite_secondary_index, CompositeSecondaryIndexKey};
 
-use super::get_full_text_secondary_index;
+use super::{get_full_text_secondary_index, get_primary_key};
 
 #[test]
 fn test_get_full_text_secondary_index() {
@@ -41,3 +41,15 @@ fn composite_key_encode_preserves_prefix() {
         }
     }
 }
+
+#[test]
+fn test_primary_key_distinguishes_separators() {
+    let key = |values: &[&str]| {
+        let values = values
+            .iter()
+            .map(|value| Field::String(value.to_string()))
+            .collect::<Vec<_>>();
+        get_primary_key(&[0, 1], &values)
+    };
+    assert_ne!(key(&["a#\u{4}b", "c"]), key(&["a", "b#\u{4}c"]));
+}
@@ -1,26 +1,34 @@
-use dozer_storage::{errors::StorageError, lmdb::RwTransaction, LmdbMap};
+use dozer_storage::{
+    backend::BackendMap, errors::StorageError, lmdb::RwTransaction,
+    lmdb_storage::LmdbEnvironmentManager, LmdbCounter,
+};
+
+/// The next record id, in the `counters` database.
+pub const RECORD_ID_COUNTER: &str = "record_id";
 
 pub fn get_or_generate_id(
-    map: LmdbMap<[u8], u64>,
+    map: BackendMap<LmdbEnvironmentManager, [u8], u64>,
+    counter: LmdbCounter,
     txn: &mut RwTransaction,
     key: Option<&[u8]>,
 ) -> Result<u64, StorageError> {
     if let Some(key) = key {
         match map.get(txn, key)? {
             Some(id) => Ok(id.into_owned()),
-            None => generate_id(map, txn, Some(key)),
+            None => generate_id(map, counter, txn, Some(key)),
         }
     } else {
-        generate_id(map, txn, None)
+        generate_id(map, counter, txn, None)
     }
 }
 
 fn generate_id(
-    map: LmdbMap<[u8], u64>,
+    map: BackendMap<LmdbEnvironmentManager, [u8], u64>,
+    counter: LmdbCounter,
     txn: &mut RwTransaction,
     key: Option<&[u8]>,
 ) -> Result<u64, StorageError> {
-    let id = map.count(txn)? as u64;
+    let id = counter.increment(txn, RECORD_ID_COUNTER)?;
 
     let id_bytes = id.to_be_bytes();
     let key = key.unwrap_or(&id_bytes);
@@ -42,15 +50,23 @@ mod tests {
     fn test_id_database() {
         let mut env = init_env(&CacheOptions::default()).unwrap().0;
         let name = Some("primary_index");
-        let writer = LmdbMap::new_from_env(&mut env, name, true).unwrap();
-        let reader = LmdbMap::<[u8], u64>::new_from_env(&mut env, name, false).unwrap();
+        let writer = BackendMap::new(&mut env, name, true).unwrap();
+        let reader = BackendMap::<_, [u8], u64>::new(&mut env, name, false).unwrap();
+        let counter = LmdbCounter::new_from_env(&mut env, Some("counters"), true).unwrap();
 
         let key = b"key";
 
         let txn = env.create_txn().unwrap();
         let mut txn = txn.write();
-        let id = get_or_generate_id(writer, txn.txn_mut(), Some(key)).unwrap();
-        get_or_generate_id(writer, txn.txn_mut(), None).unwrap();
+        let id = get_or_generate_id(writer, counter, txn.txn_mut(), Some(key)).unwrap();
+        assert_eq!(
+            get_or_generate_id(writer, counter, txn.txn_mut(), Some(key)).unwrap(),
+            id
+        );
+        assert_eq!(
+            get_or_generate_id(writer, counter, txn.txn_mut(), None).unwrap(),
+            id + 1
+        );
         txn.commit_and_renew().unwrap();
 
         assert_eq!(
@@ -0,0 +1,884 @@
+use dozer_storage::{
+    errors::StorageError, lmdb::Transaction, lmdb_storage::LmdbEnvironmentManager,
+    migration::Migration, LmdbCounter, LmdbMap, LmdbMultimap,
+};
+use dozer_types::bincode;
+use dozer_types::types::{
+    legacy::deserialize_schema_with, Field, FieldType, IndexDefinition, Record, Schema,
+};
+
+use crate::cache::{
+    index::get_primary_key,
+    lmdb::{comparator, indexer::Indexer},
+};
+
+use super::{id_database::RECORD_ID_COUNTER, secondary_index_database::database_name};
+
+/// Type prefix of timestamps in `Field::encode`.
+const TIMESTAMP_TYPE_PREFIX: u8 = 8;
+/// Type prefix of BSON in `Field::encode`.
+const BSON_TYPE_PREFIX: u8 = 10;
+
+/// Layout version 1 encodes timestamps in keys with nanoseconds and their offset, instead of milliseconds since the epoch.
+///
+/// Records are stored with all their precision, so the primary keys and sorted inverted indexes on timestamp fields are
+/// rebuilt from them. Primary keys of deleted records keep the legacy encoding, so reinserting such a record assigns it a
+/// new id.
+pub struct TimestampEncodingMigration;
+
+impl Migration for TimestampEncodingMigration {
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn migrate(&self, env: &mut LmdbEnvironmentManager) -> Result<(), StorageError> {
+        rebuild_keys(env, FieldType::Timestamp, legacy_timestamp_encoding)
+    }
+}
+
+/// Layout version 3 encodes BSON in keys in its canonical form, so documents with their keys in another order are equal.
+///
+/// The keys are rebuilt from the records as in `TimestampEncodingMigration`.
+pub struct BsonEncodingMigration;
+
+impl Migration for BsonEncodingMigration {
+    fn version(&self) -> u32 {
+        3
+    }
+
+    fn migrate(&self, env: &mut LmdbEnvironmentManager) -> Result<(), StorageError> {
+        rebuild_keys(env, FieldType::Bson, legacy_bson_encoding)
+    }
+}
+
+/// Layout version 4 generates record ids from a counter instead of the number of primary keys, so the counter is set to
+/// that number.
+///
+/// Every generated id has an entry in `primary_index`, which is never removed, so the ids after it are unused. The
+/// counter is only ever raised, so rerunning the migration or migrating a cache that already has the counter is safe.
+pub struct RecordIdCounterMigration;
+
+impl Migration for RecordIdCounterMigration {
+    fn version(&self) -> u32 {
+        4
+    }
+
+    fn migrate(&self, env: &mut LmdbEnvironmentManager) -> Result<(), StorageError> {
+        if !env
+            .list_databases()?
+            .iter()
+            .any(|name| name == "primary_index")
+        {
+            return Ok(());
+        }
+        let primary_key_to_record_id =
+            LmdbMap::<[u8], u64>::new_from_env(env, Some("primary_index"), false)?;
+        let counters = LmdbCounter::new_from_env(env, Some("counters"), true)?;
+        let mut txn = env.begin_rw_txn()?;
+        let next_id = primary_key_to_record_id.count(&txn)? as u64;
+        if counters.get(&txn, RECORD_ID_COUNTER)? < next_id {
+            counters.set(&mut txn, RECORD_ID_COUNTER, next_id)?;
+        }
+        txn.commit()?;
+        Ok(())
+    }
+}
+
+/// Layout version 5 encodes primary keys as a `CompositeKey` of their fields, instead of joining the fields with `#`, which
+/// gives the same key to different values whose strings contain `#`.
+///
+/// The primary keys are rebuilt from the records as in `TimestampEncodingMigration`. A joined key is only removed if it
+/// maps to the record, so rerunning the migration can't remove the key of another record.
+pub struct CompositePrimaryKeyMigration;
+
+impl Migration for CompositePrimaryKeyMigration {
+    fn version(&self) -> u32 {
+        5
+    }
+
+    fn migrate(&self, env: &mut LmdbEnvironmentManager) -> Result<(), StorageError> {
+        if !env.list_databases()?.iter().any(|name| name == "records") {
+            return Ok(());
+        }
+        let record_id_to_record =
+            LmdbMap::<u64, Record>::new_from_env(env, Some("records"), false)?;
+        let primary_key_to_record_id =
+            LmdbMap::<[u8], u64>::new_from_env(env, Some("primary_index"), false)?;
+        let schemas = read_schemas(env)?;
+
+        let mut txn = env.begin_rw_txn()?;
+        let mut primary_keys = vec![];
+        for result in record_id_to_record.iter(&txn)? {
+            let (id, record) = result?;
+            let Some(schema_id) = record.schema_id else {
+                continue;
+            };
+            let Some((_, schema, _)) = schemas
+                .iter()
+                .find(|(_, schema, _)| schema.identifier == Some(schema_id))
+            else {
+                continue;
+            };
+            if schema.primary_index.is_empty() {
+                continue;
+            }
+            primary_keys.push((
+                joined_primary_key(&schema.primary_index, &record.values),
+                get_primary_key(&schema.primary_index, &record.values),
+                id.into_owned(),
+            ));
+        }
+
+        for (joined_key, key, id) in primary_keys {
+            if primary_key_to_record_id
+                .get(&txn, &joined_key)?
+                .map_or(false, |joined_id| joined_id.into_owned() == id)
+            {
+                primary_key_to_record_id.remove(&mut txn, &joined_key)?;
+            }
+            primary_key_to_record_id.insert(&mut txn, &key, &id)?;
+        }
+        txn.commit()?;
+        Ok(())
+    }
+}
+
+/// Layout version 6 recreates the databases created with `INTEGER_KEY` or `INTEGER_DUP`, which LMDB keeps, so record ids
+/// in `records` and in the secondary indexes are compared byte-wise like in new caches.
+///
+/// Databases that compare byte-wise already are left as they are, so rerunning the migration is safe.
+pub struct ByteWiseOrderMigration;
+
+impl Migration for ByteWiseOrderMigration {
+    fn version(&self) -> u32 {
+        6
+    }
+
+    fn migrate(&self, env: &mut LmdbEnvironmentManager) -> Result<(), StorageError> {
+        let databases = env.list_databases()?;
+        for name in ["records", "primary_index"] {
+            if databases.iter().any(|database| database == name) {
+                env.recreate_with_byte_wise_order(name, None)?;
+            }
+        }
+        if !databases.iter().any(|name| name == SCHEMAS_DATABASE_NAME) {
+            return Ok(());
+        }
+        for (_, schema, secondary_indexes) in read_schemas(env)? {
+            let Some(schema_id) = schema.identifier else {
+                continue;
+            };
+            for (index, index_definition) in secondary_indexes.iter().enumerate() {
+                let comparator = match index_definition {
+                    IndexDefinition::SortedInverted(fields) => {
+                        comparator::sorted_inverted_comparator(fields)
+                    }
+                    _ => None,
+                };
+                env.recreate_with_byte_wise_order(&database_name(&schema_id, index), comparator)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rebuilds the primary keys and sorted inverted indexes on fields of type `typ` from the records.
+///
+/// `legacy_encode` is `Field::encode` of such fields before the migration. The primary keys are rebuilt in the layout
+/// before version 5, which converts them.
+fn rebuild_keys(
+    env: &mut LmdbEnvironmentManager,
+    typ: FieldType,
+    legacy_encode: fn(&Field) -> Vec<u8>,
+) -> Result<(), StorageError> {
+    if !env.list_databases()?.iter().any(|name| name == "records") {
+        return Ok(());
+    }
+    let record_id_to_record = LmdbMap::<u64, Record>::new_from_env(env, Some("records"), false)?;
+    let primary_key_to_record_id =
+        LmdbMap::<[u8], u64>::new_from_env(env, Some("primary_index"), false)?;
+    let schemas = read_schemas(env)?
+        .into_iter()
+        .map(|(_, schema, indexes)| (schema, indexes))
+        .collect::<Vec<_>>();
+
+    // Open the affected indexes with their comparators, as `LmdbCacheCommon` does.
+    let mut indexes = vec![];
+    for (schema, secondary_indexes) in &schemas {
+        let Some(schema_id) = schema.identifier else {
+            continue;
+        };
+        for (index, index_definition) in secondary_indexes.iter().enumerate() {
+            let IndexDefinition::SortedInverted(fields) = index_definition else {
+                continue;
+            };
+            if !has_type(schema, fields, typ) {
+                continue;
+            }
+            let name = database_name(&schema_id, index);
+            let db = LmdbMultimap::<[u8], u64>::new_from_env(env, Some(&name), false)?;
+            let txn = env.begin_ro_txn()?;
+            comparator::set_sorted_inverted_comparator(&txn, db.database(), fields)?;
+            txn.commit()?;
+            indexes.push((schema_id, fields.as_slice(), db));
+        }
+    }
+
+    let mut txn = env.begin_rw_txn()?;
+    for (_, _, db) in &indexes {
+        txn.clear_db(db.database())?;
+    }
+
+    let mut primary_keys = vec![];
+    let mut secondary_keys = vec![];
+    for result in record_id_to_record.iter(&txn)? {
+        let (id, record) = result?;
+        let id = id.into_owned();
+        let Some(schema_id) = record.schema_id else {
+            continue;
+        };
+        let Some((schema, _)) = schemas
+            .iter()
+            .find(|(schema, _)| schema.identifier == Some(schema_id))
+        else {
+            continue;
+        };
+
+        if has_type(schema, &schema.primary_index, typ) {
+            primary_keys.push((
+                legacy_primary_key(&schema.primary_index, &record.values, typ, legacy_encode),
+                joined_primary_key(&schema.primary_index, &record.values),
+                id,
+            ));
+        }
+        for (index_schema_id, fields, db) in &indexes {
+            if *index_schema_id == schema_id {
+                let key = Indexer::_build_index_sorted_inverted(fields, &record.values);
+                secondary_keys.push((*db, key, id));
+            }
+        }
+    }
+
+    for (legacy_key, key, id) in primary_keys {
+        primary_key_to_record_id.remove(&mut txn, &legacy_key)?;
+        primary_key_to_record_id.insert(&mut txn, &key, &id)?;
+    }
+    for (db, key, id) in secondary_keys {
+        db.insert(&mut txn, &key, &id)?;
+    }
+    txn.commit()?;
+    Ok(())
+}
+
+/// Layout version 2 adds `FieldDefinition::metadata`, so the schemas are rewritten with empty metadata.
+pub struct FieldMetadataMigration;
+
+impl Migration for FieldMetadataMigration {
+    fn version(&self) -> u32 {
+        2
+    }
+
+    fn migrate(&self, env: &mut LmdbEnvironmentManager) -> Result<(), StorageError> {
+        if !env
+            .list_databases()?
+            .iter()
+            .any(|name| name == SCHEMAS_DATABASE_NAME)
+        {
+            return Ok(());
+        }
+        let schemas = read_schemas(env)?;
+        // Written as bytes, because the map can't decode the legacy values it replaces.
+        let schema_db =
+            LmdbMap::<str, [u8]>::new_from_env(env, Some(SCHEMAS_DATABASE_NAME), false)?;
+        let mut txn = env.begin_rw_txn()?;
+        for (name, schema, indexes) in schemas {
+            let bytes = bincode::serialize(&(schema, indexes)).map_err(|e| {
+                StorageError::SerializationError {
+                    typ: "(Schema, Vec<IndexDefinition>)",
+                    reason: Box::new(e),
+                }
+            })?;
+            schema_db.update(&mut txn, &name, |_| Some(bytes))?;
+        }
+        txn.commit()?;
+        Ok(())
+    }
+}
+
+const SCHEMAS_DATABASE_NAME: &str = "schemas";
+
+/// Reads the schemas and their secondary indexes, whether their fields have metadata or not.
+fn read_schemas(
+    env: &mut LmdbEnvironmentManager,
+) -> Result<Vec<(String, Schema, Vec<IndexDefinition>)>, StorageError> {
+    let schema_db = LmdbMap::<str, [u8]>::new_from_env(env, Some(SCHEMAS_DATABASE_NAME), false)?;
+    let txn = env.begin_ro_txn()?;
+    let mut schemas = vec![];
+    for result in schema_db.iter(&txn)? {
+        let (name, bytes) = result?;
+        let (schema, indexes) =
+            deserialize_schema_with::<Vec<IndexDefinition>>(&bytes).map_err(|e| {
+                StorageError::DeserializationError {
+                    typ: "(Schema, Vec<IndexDefinition>)",
+                    reason: Box::new(e),
+                }
+            })?;
+        schemas.push((name.into_owned(), schema, indexes));
+    }
+    txn.commit()?;
+    Ok(schemas)
+}
+
+fn has_type(schema: &Schema, fields: &[usize], typ: FieldType) -> bool {
+    fields.iter().any(|index| {
+        schema
+            .fields
+            .get(*index)
+            .map_or(false, |field| field.typ == typ)
+    })
+}
+
+/// `Field::encode` of timestamps before layout version 1.
+fn legacy_timestamp_encoding(field: &Field) -> Vec<u8> {
+    let mut bytes = vec![TIMESTAMP_TYPE_PREFIX];
+    if let Field::Timestamp(timestamp) = field {
+        bytes.extend_from_slice(&timestamp.timestamp_millis().to_be_bytes());
+    }
+    bytes
+}
+
+/// `Field::encode` of BSON before layout version 3.
+fn legacy_bson_encoding(field: &Field) -> Vec<u8> {
+    let mut bytes = vec![BSON_TYPE_PREFIX];
+    if let Field::Bson(bson) = field {
+        bytes.extend_from_slice(bson);
+    }
+    bytes
+}
+
+/// `get_primary_key` before layout version 5.
+fn joined_primary_key(primary_index: &[usize], values: &[Field]) -> Vec<u8> {
+    let key: Vec<Vec<u8>> = primary_index
+        .iter()
+        .map(|idx| values[*idx].encode())
+        .collect();
+
+    key.join("#".as_bytes())
+}
+
+/// `joined_primary_key` with `legacy_encode` for fields of type `typ`.
+fn legacy_primary_key(
+    primary_index: &[usize],
+    values: &[Field],
+    typ: FieldType,
+    legacy_encode: fn(&Field) -> Vec<u8>,
+) -> Vec<u8> {
+    let key: Vec<Vec<u8>> = primary_index
+        .iter()
+        .map(|idx| match &values[*idx] {
+            field if field.get_type() == Some(typ) => legacy_encode(field),
+            field => field.encode(),
+        })
+        .collect();
+
+    key.join("#".as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_storage::{
+        lmdb::{Cursor, DatabaseFlags, WriteFlags},
+        lmdb_storage::LmdbEnvironmentOptions,
+    };
+    use dozer_types::{
+        chrono::DateTime,
+        serde_json::Value,
+        types::{
+            legacy::{LegacyFieldDefinition, LegacySchema},
+            FieldDefinition, SchemaIdentifier, SourceDefinition,
+        },
+    };
+    use tempdir::TempDir;
+
+    use crate::cache::{
+        expression::{FilterExpression, Operator, QueryExpression},
+        lmdb::cache::{CacheCommonOptions, CacheWriteOptions, LmdbRwCache},
+        test_utils::query_from_filter,
+        RoCache, RwCache,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_timestamp_encoding_migration() {
+        let temp_dir = TempDir::new("test_timestamp_encoding_migration").unwrap();
+        let common_options = CacheCommonOptions {
+            path: Some((temp_dir.path().to_path_buf(), "cache".to_string())),
+            ..Default::default()
+        };
+        let schema = Schema {
+            identifier: Some(SchemaIdentifier { id: 0, version: 1 }),
+            fields: vec![FieldDefinition {
+                name: "ts".to_string(),
+                typ: FieldType::Timestamp,
+                nullable: false,
+                source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
+            }],
+            primary_index: vec![0],
+        };
+        let timestamp_str = "2023-03-01T12:34:56.123456789+08:00";
+        let timestamp = Field::Timestamp(DateTime::parse_from_rfc3339(timestamp_str).unwrap());
+        let mut record = Record::new(schema.identifier, vec![timestamp.clone()], None);
+
+        let cache = LmdbRwCache::create(
+            [(
+                "ts".to_string(),
+                schema,
+                vec![IndexDefinition::SortedInverted(vec![0])],
+            )],
+            common_options.clone(),
+            CacheWriteOptions::default(),
+        )
+        .unwrap();
+        let id = cache.insert(&mut record).unwrap();
+        cache.commit(&Default::default()).unwrap();
+        drop(cache);
+
+        // Bring the cache back to layout version 0.
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "cache",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        env.drop_database(dozer_storage::migration::META_DATABASE_NAME)
+            .unwrap();
+        let primary_key_to_record_id =
+            LmdbMap::<[u8], u64>::new_from_env(&mut env, Some("primary_index"), false).unwrap();
+        let mut txn = env.begin_rw_txn().unwrap();
+        let values = [timestamp.clone()];
+        primary_key_to_record_id
+            .remove(&mut txn, &get_primary_key(&[0], &values))
+            .unwrap();
+        primary_key_to_record_id
+            .insert(
+                &mut txn,
+                &legacy_primary_key(
+                    &[0],
+                    &values,
+                    FieldType::Timestamp,
+                    legacy_timestamp_encoding,
+                ),
+                &id,
+            )
+            .unwrap();
+        txn.commit().unwrap();
+        drop(env);
+
+        let cache = LmdbRwCache::open(common_options, CacheWriteOptions::default()).unwrap();
+        assert_eq!(cache.get(&get_primary_key(&[0], &values)).unwrap().id, id);
+        let query = query_from_filter(FilterExpression::Simple(
+            "ts".to_string(),
+            Operator::EQ,
+            Value::String(timestamp_str.to_string()),
+        ));
+        assert_eq!(cache.count("ts", &query).unwrap(), 1);
+        assert_eq!(
+            cache
+                .count("ts", &QueryExpression::with_no_limit())
+                .unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_bson_encoding_migration() {
+        let temp_dir = TempDir::new("test_bson_encoding_migration").unwrap();
+        let common_options = CacheCommonOptions {
+            path: Some((temp_dir.path().to_path_buf(), "cache".to_string())),
+            ..Default::default()
+        };
+        let schema = Schema {
+            identifier: Some(SchemaIdentifier { id: 0, version: 1 }),
+            fields: vec![FieldDefinition {
+                name: "doc".to_string(),
+                typ: FieldType::Bson,
+                nullable: false,
+                source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
+            }],
+            primary_index: vec![0],
+        };
+        let document = Field::Bson(br#"{"b": 1.0, "a": [2]}"#.to_vec());
+        let mut record = Record::new(schema.identifier, vec![document.clone()], None);
+
+        let cache = LmdbRwCache::create(
+            [(
+                "docs".to_string(),
+                schema,
+                vec![IndexDefinition::SortedInverted(vec![0])],
+            )],
+            common_options.clone(),
+            CacheWriteOptions::default(),
+        )
+        .unwrap();
+        let id = cache.insert(&mut record).unwrap();
+        cache.commit(&Default::default()).unwrap();
+        drop(cache);
+
+        // Bring the cache back to layout version 0.
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "cache",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        env.drop_database(dozer_storage::migration::META_DATABASE_NAME)
+            .unwrap();
+        let primary_key_to_record_id =
+            LmdbMap::<[u8], u64>::new_from_env(&mut env, Some("primary_index"), false).unwrap();
+        let mut txn = env.begin_rw_txn().unwrap();
+        let values = [document];
+        primary_key_to_record_id
+            .remove(&mut txn, &get_primary_key(&[0], &values))
+            .unwrap();
+        primary_key_to_record_id
+            .insert(
+                &mut txn,
+                &legacy_primary_key(&[0], &values, FieldType::Bson, legacy_bson_encoding),
+                &id,
+            )
+            .unwrap();
+        txn.commit().unwrap();
+        drop(env);
+
+        let cache = LmdbRwCache::open(common_options, CacheWriteOptions::default()).unwrap();
+        let reordered = [Field::Bson(br#"{"a":[2],"b":1}"#.to_vec())];
+        assert_eq!(
+            cache.get(&get_primary_key(&[0], &reordered)).unwrap().id,
+            id
+        );
+        assert_eq!(
+            cache
+                .count("docs", &QueryExpression::with_no_limit())
+                .unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_field_metadata_migration() {
+        let temp_dir = TempDir::new("test_field_metadata_migration").unwrap();
+        let common_options = CacheCommonOptions {
+            path: Some((temp_dir.path().to_path_buf(), "cache".to_string())),
+            ..Default::default()
+        };
+        let schema = Schema {
+            identifier: Some(SchemaIdentifier { id: 0, version: 1 }),
+            fields: vec![FieldDefinition::new(
+                "id".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            )],
+            primary_index: vec![0],
+        };
+        let indexes = vec![IndexDefinition::SortedInverted(vec![0])];
+        let cache = LmdbRwCache::create(
+            [("ints".to_string(), schema.clone(), indexes.clone())],
+            common_options.clone(),
+            CacheWriteOptions::default(),
+        )
+        .unwrap();
+        drop(cache);
+
+        // Bring the cache back to layout version 1, with schemas serialized before field metadata.
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "cache",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let meta = LmdbMap::<str, u32>::new_from_env(
+            &mut env,
+            Some(dozer_storage::migration::META_DATABASE_NAME),
+            false,
+        )
+        .unwrap();
+        let schema_db =
+            LmdbMap::<str, [u8]>::new_from_env(&mut env, Some(SCHEMAS_DATABASE_NAME), false)
+                .unwrap();
+        let legacy_schema = LegacySchema {
+            identifier: schema.identifier,
+            fields: vec![LegacyFieldDefinition {
+                name: "id".to_string(),
+                typ: FieldType::Int,
+                nullable: false,
+                source: SourceDefinition::Dynamic,
+            }],
+            primary_index: vec![0],
+        };
+        let legacy_bytes = bincode::serialize(&(legacy_schema, indexes.clone())).unwrap();
+        let mut txn = env.begin_rw_txn().unwrap();
+        meta.update(&mut txn, "layout_version", |_| Some(1))
+            .unwrap();
+        schema_db
+            .update(&mut txn, "ints", |_| Some(legacy_bytes))
+            .unwrap();
+        txn.commit().unwrap();
+        drop(env);
+
+        let cache = LmdbRwCache::open(common_options, CacheWriteOptions::default()).unwrap();
+        assert_eq!(
+            cache.get_schema_and_indexes_by_name("ints").unwrap(),
+            &(schema, indexes)
+        );
+    }
+
+    #[test]
+    fn test_record_id_counter_migration() {
+        let temp_dir = TempDir::new("test_record_id_counter_migration").unwrap();
+        let common_options = CacheCommonOptions {
+            path: Some((temp_dir.path().to_path_buf(), "cache".to_string())),
+            ..Default::default()
+        };
+        let schema = |id, primary_index| Schema {
+            identifier: Some(SchemaIdentifier { id, version: 1 }),
+            fields: vec![FieldDefinition::new(
+                "id".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            )],
+            primary_index,
+        };
+        let events = schema(0, vec![]);
+        let users = schema(1, vec![0]);
+        let cache = LmdbRwCache::create(
+            [
+                ("events".to_string(), events.clone(), vec![]),
+                ("users".to_string(), users.clone(), vec![]),
+            ],
+            common_options.clone(),
+            CacheWriteOptions::default(),
+        )
+        .unwrap();
+        let insert = |schema: &Schema, value| {
+            let mut record = Record::new(schema.identifier, vec![Field::Int(value)], None);
+            cache.insert(&mut record).unwrap()
+        };
+        assert_eq!(
+            [insert(&events, 1), insert(&users, 1), insert(&events, 2)],
+            [0, 1, 2]
+        );
+        cache.commit(&Default::default()).unwrap();
+        drop(cache);
+
+        // Bring the cache back to before versioning, when ids were the number of primary keys.
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "cache",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        env.drop_database(dozer_storage::migration::META_DATABASE_NAME)
+            .unwrap();
+        env.drop_database("counters").unwrap();
+        drop(env);
+
+        let cache = LmdbRwCache::open(common_options, CacheWriteOptions::default()).unwrap();
+        let mut events_record = Record::new(events.identifier, vec![Field::Int(3)], None);
+        assert_eq!(cache.insert(&mut events_record).unwrap(), 3);
+        let mut users_record = Record::new(users.identifier, vec![Field::Int(2)], None);
+        assert_eq!(cache.insert(&mut users_record).unwrap(), 4);
+        assert_eq!(
+            cache
+                .count("events", &QueryExpression::with_no_limit())
+                .unwrap(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_composite_primary_key_migration() {
+        let temp_dir = TempDir::new("test_composite_primary_key_migration").unwrap();
+        let common_options = CacheCommonOptions {
+            path: Some((temp_dir.path().to_path_buf(), "cache".to_string())),
+            ..Default::default()
+        };
+        let field = |name: &str| {
+            FieldDefinition::new(
+                name.to_string(),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            )
+        };
+        let schema = Schema {
+            identifier: Some(SchemaIdentifier { id: 0, version: 1 }),
+            fields: vec![field("a"), field("b")],
+            primary_index: vec![0, 1],
+        };
+        let values = vec![
+            Field::String("a#b".to_string()),
+            Field::String("c".to_string()),
+        ];
+        let mut record = Record::new(schema.identifier, values.clone(), None);
+
+        let cache = LmdbRwCache::create(
+            [("strings".to_string(), schema, vec![])],
+            common_options.clone(),
+            CacheWriteOptions::default(),
+        )
+        .unwrap();
+        let id = cache.insert(&mut record).unwrap();
+        cache.commit(&Default::default()).unwrap();
+        drop(cache);
+
+        // Bring the cache back to layout version 4, with the fields of primary keys joined with `#`.
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "cache",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let meta = LmdbMap::<str, u32>::new_from_env(
+            &mut env,
+            Some(dozer_storage::migration::META_DATABASE_NAME),
+            false,
+        )
+        .unwrap();
+        let primary_key_to_record_id =
+            LmdbMap::<[u8], u64>::new_from_env(&mut env, Some("primary_index"), false).unwrap();
+        let mut txn = env.begin_rw_txn().unwrap();
+        meta.update(&mut txn, "layout_version", |_| Some(4))
+            .unwrap();
+        primary_key_to_record_id
+            .remove(&mut txn, &get_primary_key(&[0, 1], &values))
+            .unwrap();
+        primary_key_to_record_id
+            .insert(&mut txn, &joined_primary_key(&[0, 1], &values), &id)
+            .unwrap();
+        txn.commit().unwrap();
+        drop(env);
+
+        let cache = LmdbRwCache::open(common_options, CacheWriteOptions::default()).unwrap();
+        assert_eq!(
+            cache.get(&get_primary_key(&[0, 1], &values)).unwrap().id,
+            id
+        );
+    }
+
+    #[test]
+    fn test_byte_wise_order_migration() {
+        let temp_dir = TempDir::new("test_byte_wise_order_migration").unwrap();
+        let common_options = CacheCommonOptions {
+            path: Some((temp_dir.path().to_path_buf(), "cache".to_string())),
+            ..Default::default()
+        };
+        let schema = Schema {
+            identifier: Some(SchemaIdentifier { id: 0, version: 1 }),
+            fields: vec![FieldDefinition::new(
+                "a".to_string(),
+                FieldType::UInt,
+                false,
+                SourceDefinition::Dynamic,
+            )],
+            primary_index: vec![0],
+        };
+        let index_database_name = database_name(&schema.identifier.unwrap(), 0);
+
+        let cache = LmdbRwCache::create(
+            [(
+                "uints".to_string(),
+                schema.clone(),
+                vec![IndexDefinition::SortedInverted(vec![0])],
+            )],
+            common_options.clone(),
+            CacheWriteOptions::default(),
+        )
+        .unwrap();
+        let mut ids = vec![];
+        for value in 0..300 {
+            let mut record = Record::new(schema.identifier, vec![Field::UInt(value)], None);
+            ids.push(cache.insert(&mut record).unwrap());
+        }
+        cache.commit(&Default::default()).unwrap();
+        drop(cache);
+
+        // Bring the cache back to layout version 5, with the databases created with integer flags.
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "cache",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        recreate_with_flags(&mut env, "records", DatabaseFlags::INTEGER_KEY);
+        recreate_with_flags(
+            &mut env,
+            &index_database_name,
+            DatabaseFlags::DUP_SORT | DatabaseFlags::DUP_FIXED | DatabaseFlags::INTEGER_DUP,
+        );
+        let meta = LmdbMap::<str, u32>::new_from_env(
+            &mut env,
+            Some(dozer_storage::migration::META_DATABASE_NAME),
+            false,
+        )
+        .unwrap();
+        let mut txn = env.begin_rw_txn().unwrap();
+        meta.update(&mut txn, "layout_version", |_| Some(5))
+            .unwrap();
+        txn.commit().unwrap();
+        drop(env);
+
+        let cache = LmdbRwCache::open(common_options, CacheWriteOptions::default()).unwrap();
+        let values = [Field::UInt(256)];
+        assert_eq!(
+            cache.get(&get_primary_key(&[0], &values)).unwrap().id,
+            ids[256]
+        );
+        let query = query_from_filter(FilterExpression::Simple(
+            "a".to_string(),
+            Operator::GTE,
+            Value::from(256),
+        ));
+        assert_eq!(cache.count("uints", &query).unwrap(), 44);
+        drop(cache);
+
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "cache",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        assert!(!env.recreate_with_byte_wise_order("records", None).unwrap());
+        assert!(!env
+            .recreate_with_byte_wise_order(&index_database_name, None)
+            .unwrap());
+    }
+
+    /// Copies the database `name` into a database created with `flags`.
+    fn recreate_with_flags(env: &mut LmdbEnvironmentManager, name: &str, flags: DatabaseFlags) {
+        let db = env.create_database(Some(name), None).unwrap();
+        let txn = env.begin_ro_txn().unwrap();
+        let entries = txn
+            .open_ro_cursor(db)
+            .unwrap()
+            .iter_start()
+            .map(|item| {
+                let (key, value) = item.unwrap();
+                (key.to_vec(), value.to_vec())
+            })
+            .collect::<Vec<_>>();
+        txn.commit().unwrap();
+
+        env.drop_database(name).unwrap();
+        let db = env.create_database(Some(name), Some(flags)).unwrap();
+        let mut txn = env.begin_rw_txn().unwrap();
+        for (key, value) in entries {
+            txn.put(db, &key, &value, WriteFlags::empty()).unwrap();
+        }
+        txn.commit().unwrap();
+    }
+}
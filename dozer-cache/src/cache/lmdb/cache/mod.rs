@@ -2,14 +2,17 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::path::PathBuf;
 
-use dozer_storage::lmdb::{RoTransaction, RwTransaction, Transaction};
+use dozer_storage::backend::{self, BackendMap};
+use dozer_storage::errors::StorageError;
+use dozer_storage::lmdb::{self, RoTransaction, RwTransaction, Transaction};
 use dozer_storage::lmdb_storage::{
-    LmdbEnvironmentManager, LmdbExclusiveTransaction, SharedTransaction,
+    LmdbEnvironmentManager, LmdbEnvironmentStats, LmdbExclusiveTransaction, SharedTransaction,
 };
-use dozer_storage::{LmdbMap, LmdbMultimap};
+use dozer_storage::{LmdbCounter, LmdbMultimap};
 
+use dozer_types::chrono::{DateTime, TimeZone, Utc};
 use dozer_types::node::{NodeHandle, OpIdentifier, SourceStates};
-use dozer_types::parking_lot::RwLockReadGuard;
+use dozer_types::parking_lot::{Mutex, RwLockReadGuard};
 
 use dozer_types::types::{Field, FieldType, IndexDefinition, Record};
 use dozer_types::types::{Schema, SchemaIdentifier};
@@ -31,12 +34,21 @@ use query::LmdbQueryHandler;
 
 mod helper;
 mod id_database;
+mod migration;
 mod query;
 mod schema_database;
 mod secondary_index_database;
 
+pub use migration::{
+    BsonEncodingMigration, ByteWiseOrderMigration, CompositePrimaryKeyMigration,
+    FieldMetadataMigration, RecordIdCounterMigration, TimestampEncodingMigration,
+};
 use schema_database::SchemaDatabase;
 
+const COMMIT_EPOCH_COUNTER: &str = "commit_epoch";
+/// Milliseconds since the Unix epoch of the last commit, `0` before the first.
+const LAST_COMMIT_TIME_COUNTER: &str = "last_commit_time";
+
 pub type SecondaryIndexDatabases = HashMap<(SchemaIdentifier, usize), LmdbMultimap<[u8], u64>>;
 
 #[derive(Clone, Debug)]
@@ -52,6 +64,10 @@ pub struct CacheCommonOptions {
     /// Provide a path where db will be created. If nothing is provided, will default to a temp location.
     /// Db path will be `PathBuf.join(String)`.
     pub path: Option<(PathBuf, String)>,
+
+    /// Open the cache with a lock file, so it can be read by other processes while it's written.
+    /// The writer and all readers must set this.
+    pub multi_process: bool,
 }
 
 impl Default for CacheCommonOptions {
@@ -61,6 +77,7 @@ impl Default for CacheCommonOptions {
             max_db_size: 1000,
             intersection_chunk_size: 100,
             path: None,
+            multi_process: false,
         }
     }
 }
@@ -87,12 +104,25 @@ pub struct CacheWriteOptions {
     // Total size allocated for data in a memory mapped file.
     // This size is allocated at initialization.
     pub max_size: usize,
+
+    /// Don't flush to disk on commit. A system crash may lose the last commits, but the cache is never corrupted.
+    pub no_sync: bool,
+
+    /// Use a writeable memory map. Faster writes, but bugs can corrupt the cache through stray pointer writes.
+    pub write_map: bool,
+
+    /// If set, the map size is multiplied by this factor when it's almost full, or when a transaction doesn't fit in it.
+    /// Writes since the last commit are then kept in memory, to replay them after growing the map.
+    pub map_growth_factor: Option<f64>,
 }
 
 impl Default for CacheWriteOptions {
     fn default() -> Self {
         Self {
             max_size: 1024 * 1024 * 1024 * 1024,
+            no_sync: false,
+            write_map: false,
+            map_growth_factor: None,
         }
     }
 }
@@ -100,8 +130,15 @@ impl Default for CacheWriteOptions {
 #[derive(Debug)]
 pub struct LmdbRwCache {
     common: LmdbCacheCommon,
-    checkpoint_db: LmdbMap<NodeHandle, OpIdentifier>,
     txn: SharedTransaction,
+    /// Writes since the last commit, replayed if the transaction doesn't fit in the map. `None` if the map can't grow.
+    uncommitted: Option<Mutex<Vec<UncommittedWrite>>>,
+}
+
+#[derive(Debug)]
+enum UncommittedWrite {
+    Insert(Record),
+    Delete(Vec<u8>),
 }
 
 impl LmdbRwCache {
@@ -129,17 +166,17 @@ impl LmdbRwCache {
         common_options: CacheCommonOptions,
         write_options: CacheWriteOptions,
     ) -> Result<Self, CacheError> {
+        let uncommitted = write_options.map_growth_factor.map(|_| Mutex::new(vec![]));
         let (mut env, name) = utils::init_env(&CacheOptions {
             common: common_options.clone(),
             kind: CacheOptionsKind::Write(write_options),
         })?;
         let common = LmdbCacheCommon::new(&mut env, common_options, name, true)?;
-        let checkpoint_db = LmdbMap::new_from_env(&mut env, Some("checkpoint"), true)?;
         let txn = env.create_txn()?;
         Ok(Self {
             common,
-            checkpoint_db,
             txn,
+            uncommitted,
         })
     }
 }
@@ -149,6 +186,10 @@ impl<C: LmdbCache> RoCache for C {
         &self.common().name
     }
 
+    #[cfg_attr(
+        feature = "instrument",
+        tracing::instrument(level = "debug", skip_all, fields(cache = self.name(), id))
+    )]
     fn get(&self, key: &[u8]) -> Result<RecordWithId, CacheError> {
         let txn = self.begin_txn()?;
         let txn = txn.as_txn();
@@ -164,9 +205,56 @@ impl<C: LmdbCache> RoCache for C {
             .get(txn, &id)?
             .ok_or(CacheError::PrimaryKeyNotFound)?
             .into_owned();
+        #[cfg(feature = "instrument")]
+        tracing::Span::current().record("id", id);
         Ok(RecordWithId::new(id, record))
     }
 
+    #[cfg_attr(
+        feature = "instrument",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(cache = self.name(), keys = keys.len(), records)
+        )
+    )]
+    fn get_many(&self, keys: &[Vec<u8>]) -> Result<Vec<Option<RecordWithId>>, CacheError> {
+        let txn = self.begin_txn()?;
+        let txn = txn.as_txn();
+        let records = keys
+            .iter()
+            .map(|key| {
+                let Some(id) = self.common().primary_key_to_record_id.get(txn, key)? else {
+                    return Ok(None);
+                };
+                let id = id.into_owned();
+                let record = self
+                    .common()
+                    .record_id_to_record
+                    .get(txn, &id)?
+                    .ok_or(CacheError::PrimaryKeyNotFound)?
+                    .into_owned();
+                Ok(Some(RecordWithId::new(id, record)))
+            })
+            .collect::<Result<Vec<_>, CacheError>>()?;
+        #[cfg(feature = "instrument")]
+        tracing::Span::current().record("records", records.iter().flatten().count());
+        Ok(records)
+    }
+
+    #[cfg_attr(
+        feature = "instrument",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                cache = self.name(),
+                schema = schema_name,
+                query = %dozer_types::serde_json::to_string(query).unwrap_or_default(),
+                count
+            )
+        )
+    )]
     fn count(&self, schema_name: &str, query: &QueryExpression) -> Result<usize, CacheError> {
         let txn = self.begin_txn()?;
         let txn = txn.as_txn();
@@ -176,9 +264,25 @@ impl<C: LmdbCache> RoCache for C {
             .get_schema_from_name(schema_name)
             .ok_or_else(|| CacheError::SchemaNotFound(schema_name.to_string()))?;
         let handler = LmdbQueryHandler::new(self.common(), txn, schema, secondary_indexes, query);
-        handler.count()
+        let count = handler.count()?;
+        #[cfg(feature = "instrument")]
+        tracing::Span::current().record("count", count);
+        Ok(count)
     }
 
+    #[cfg_attr(
+        feature = "instrument",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                cache = self.name(),
+                schema = schema_name,
+                query = %dozer_types::serde_json::to_string(query).unwrap_or_default(),
+                records
+            )
+        )
+    )]
     fn query(
         &self,
         schema_name: &str,
@@ -193,6 +297,8 @@ impl<C: LmdbCache> RoCache for C {
             .ok_or_else(|| CacheError::SchemaNotFound(schema_name.to_string()))?;
         let handler = LmdbQueryHandler::new(self.common(), txn, schema, secondary_indexes, query);
         let records = handler.query()?;
+        #[cfg(feature = "instrument")]
+        tracing::Span::current().record("records", records.len());
         Ok((schema, records))
     }
 
@@ -215,51 +321,170 @@ impl<C: LmdbCache> RoCache for C {
             .map(|(schema, _)| schema)
             .ok_or(CacheError::SchemaIdentifierNotFound(schema_identifier))
     }
+
+    fn get_commit_epoch(&self) -> Result<u64, CacheError> {
+        let txn = self.begin_txn()?;
+        Ok(self
+            .common()
+            .counters
+            .get(txn.as_txn(), COMMIT_EPOCH_COUNTER)?)
+    }
+
+    fn get_last_commit_time(&self) -> Result<Option<DateTime<Utc>>, CacheError> {
+        let txn = self.begin_txn()?;
+        let millis = self
+            .common()
+            .counters
+            .get(txn.as_txn(), LAST_COMMIT_TIME_COUNTER)?;
+        if millis == 0 {
+            return Ok(None);
+        }
+        Ok(Utc.timestamp_millis_opt(millis as i64).single())
+    }
+
+    fn get_checkpoint(&self) -> Result<SourceStates, CacheError> {
+        let txn = self.begin_txn()?;
+        let result = self
+            .common()
+            .checkpoint_db
+            .iter(txn.as_txn())?
+            .map(|result| {
+                result
+                    .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                    .map_err(CacheError::Storage)
+            })
+            .collect();
+        result
+    }
+
+    fn environment_stats(&self) -> Result<LmdbEnvironmentStats, CacheError> {
+        LmdbCache::environment_stats(self)
+    }
 }
 
 impl RwCache for LmdbRwCache {
+    #[cfg_attr(
+        feature = "instrument",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(cache = self.name(), schema_id = ?record.schema_id, id)
+        )
+    )]
     fn insert(&self, record: &mut Record) -> Result<u64, CacheError> {
         let (schema, secondary_indexes) = self.get_schema_and_indexes_from_record(record)?;
+        record.fill_omitted(schema);
+        record.apply_timestamp_policies(schema);
         record.version = Some(INITIAL_RECORD_VERSION);
-        self.insert_impl(record, schema, secondary_indexes)
+        let id = self.with_map_growth(|| self.insert_impl(record, schema, secondary_indexes))?;
+        self.log_uncommitted(|| UncommittedWrite::Insert(record.clone()));
+        #[cfg(feature = "instrument")]
+        tracing::Span::current().record("id", id);
+        Ok(id)
     }
 
     fn delete(&self, key: &[u8]) -> Result<u32, CacheError> {
-        let (_, _, version) = self.delete_impl(key)?;
+        let (_, _, version) = self.with_map_growth(|| self.delete_impl(key))?;
+        self.log_uncommitted(|| UncommittedWrite::Delete(key.to_vec()));
         Ok(version)
     }
 
     fn update(&self, key: &[u8], record: &mut Record) -> Result<u32, CacheError> {
-        let (schema, secondary_indexes, old_version) = self.delete_impl(key)?;
+        let (schema, secondary_indexes, old_version) =
+            self.with_map_growth(|| self.delete_impl(key))?;
+        self.log_uncommitted(|| UncommittedWrite::Delete(key.to_vec()));
+        record.apply_timestamp_policies(schema);
         record.version = Some(old_version + 1);
-        self.insert_impl(record, schema, secondary_indexes)?;
+        self.with_map_growth(|| self.insert_impl(record, schema, secondary_indexes))?;
+        self.log_uncommitted(|| UncommittedWrite::Insert(record.clone()));
         Ok(old_version)
     }
 
+    #[cfg_attr(
+        feature = "instrument",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(cache = self.name(), sources = checkpoint.len())
+        )
+    )]
     fn commit(&self, checkpoint: &SourceStates) -> Result<(), CacheError> {
-        let mut txn = self.txn.write();
-        self.checkpoint_db.clear(txn.txn_mut())?;
-        self.checkpoint_db.extend(txn.txn_mut(), checkpoint)?;
-        txn.commit_and_renew()?;
+        self.with_map_growth(|| {
+            let mut txn = self.txn.write();
+            self.common.checkpoint_db.clear(txn.txn_mut())?;
+            for (node, op) in checkpoint {
+                self.common.checkpoint_db.insert(txn.txn_mut(), node, op)?;
+            }
+            self.common
+                .counters
+                .increment(txn.txn_mut(), COMMIT_EPOCH_COUNTER)?;
+            self.common.counters.set(
+                txn.txn_mut(),
+                LAST_COMMIT_TIME_COUNTER,
+                Utc::now().timestamp_millis() as u64,
+            )?;
+            txn.commit_and_renew()?;
+            Ok(())
+        })?;
+        if let Some(uncommitted) = &self.uncommitted {
+            uncommitted.lock().clear();
+        }
         Ok(())
     }
-
-    fn get_checkpoint(&self) -> Result<SourceStates, CacheError> {
-        let txn = self.txn.read();
-        let result = self
-            .checkpoint_db
-            .iter(txn.txn())?
-            .map(|result| {
-                result
-                    .map(|(key, value)| (key.into_owned(), value.into_owned()))
-                    .map_err(CacheError::Storage)
-            })
-            .collect();
-        result
-    }
 }
 
 impl LmdbRwCache {
+    /// Runs `write`, and if the transaction doesn't fit in the map, grows the map, replays the uncommitted writes
+    /// and runs `write` again.
+    fn with_map_growth<T>(
+        &self,
+        mut write: impl FnMut() -> Result<T, CacheError>,
+    ) -> Result<T, CacheError> {
+        loop {
+            match write() {
+                Err(e) if is_map_full(&e) && self.uncommitted.is_some() => {
+                    self.grow_and_replay()?
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn grow_and_replay(&self) -> Result<(), CacheError> {
+        let uncommitted = self
+            .uncommitted
+            .as_ref()
+            .expect("Only called if the map can grow")
+            .lock();
+        loop {
+            self.txn.write().abort_grow_and_renew()?;
+            match uncommitted.iter().try_for_each(|write| self.replay(write)) {
+                Err(e) if is_map_full(&e) => continue,
+                result => return result,
+            }
+        }
+    }
+
+    fn replay(&self, write: &UncommittedWrite) -> Result<(), CacheError> {
+        match write {
+            UncommittedWrite::Insert(record) => {
+                let (schema, secondary_indexes) =
+                    self.get_schema_and_indexes_from_record(record)?;
+                self.insert_impl(record, schema, secondary_indexes)?;
+            }
+            UncommittedWrite::Delete(key) => {
+                self.delete_impl(key)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn log_uncommitted(&self, write: impl FnOnce() -> UncommittedWrite) {
+        if let Some(uncommitted) = &self.uncommitted {
+            uncommitted.lock().push(write());
+        }
+    }
+
     fn delete_impl(&self, key: &[u8]) -> Result<(&Schema, &[IndexDefinition], u32), CacheError> {
         let record = self.get(key)?;
         let (schema, secondary_indexes) =
@@ -293,11 +518,17 @@ impl LmdbRwCache {
         let txn = txn.txn_mut();
 
         let id = if schema.primary_index.is_empty() {
-            get_or_generate_id(self.common.primary_key_to_record_id, txn, None)?
+            get_or_generate_id(
+                self.common.primary_key_to_record_id,
+                self.common.counters,
+                txn,
+                None,
+            )?
         } else {
             let primary_key = get_primary_key(&schema.primary_index, &record.values);
             get_or_generate_id(
                 self.common.primary_key_to_record_id,
+                self.common.counters,
                 txn,
                 Some(&primary_key),
             )?
@@ -316,9 +547,16 @@ impl LmdbRwCache {
     }
 }
 
+fn is_map_full(error: &CacheError) -> bool {
+    matches!(
+        error,
+        CacheError::Storage(StorageError::Lmdb(lmdb::Error::MapFull))
+    )
+}
+
 /// This trait abstracts the behavior of getting a transaction from a `LmdbExclusiveTransaction` or a `lmdb::Transaction`.
 trait AsTransaction {
-    type Transaction<'a>: Transaction
+    type Transaction<'a>: Transaction + backend::ReadTransaction<Database = lmdb::Database>
     where
         Self: 'a;
 
@@ -350,6 +588,7 @@ trait LmdbCache: Send + Sync + Debug {
 
     fn common(&self) -> &LmdbCacheCommon;
     fn begin_txn(&self) -> Result<Self::AsTransaction<'_>, CacheError>;
+    fn environment_stats(&self) -> Result<LmdbEnvironmentStats, CacheError>;
 
     fn get_schema_and_indexes_from_record(
         &self,
@@ -378,6 +617,10 @@ impl LmdbCache for LmdbRoCache {
     fn begin_txn(&self) -> Result<Self::AsTransaction<'_>, CacheError> {
         Ok(self.env.begin_ro_txn()?)
     }
+
+    fn environment_stats(&self) -> Result<LmdbEnvironmentStats, CacheError> {
+        Ok(self.env.stats()?)
+    }
 }
 
 impl LmdbCache for LmdbRwCache {
@@ -390,6 +633,10 @@ impl LmdbCache for LmdbRwCache {
     fn begin_txn(&self) -> Result<Self::AsTransaction<'_>, CacheError> {
         Ok(self.txn.read())
     }
+
+    fn environment_stats(&self) -> Result<LmdbEnvironmentStats, CacheError> {
+        Ok(self.txn.read().stats()?)
+    }
 }
 
 fn debug_check_schema_record_consistency(schema: &Schema, record: &Record) {
@@ -418,6 +665,9 @@ fn debug_check_schema_record_consistency(schema: &Schema, record: &Record) {
             FieldType::Date => debug_assert!(value.as_date().is_some()),
             FieldType::Bson => debug_assert!(value.as_bson().is_some()),
             FieldType::Point => debug_assert!(value.as_point().is_some()),
+            FieldType::Duration => debug_assert!(value.as_duration().is_some()),
+            FieldType::Geometry => debug_assert!(value.as_geometry().is_some()),
+            FieldType::Decimal128 => debug_assert!(value.as_decimal128().is_some()),
         }
     }
 }
@@ -426,8 +676,10 @@ const INITIAL_RECORD_VERSION: u32 = 1_u32;
 
 #[derive(Debug)]
 pub struct LmdbCacheCommon {
-    record_id_to_record: LmdbMap<u64, Record>,
-    primary_key_to_record_id: LmdbMap<[u8], u64>,
+    record_id_to_record: BackendMap<LmdbEnvironmentManager, u64, Record>,
+    primary_key_to_record_id: BackendMap<LmdbEnvironmentManager, [u8], u64>,
+    counters: LmdbCounter,
+    checkpoint_db: BackendMap<LmdbEnvironmentManager, NodeHandle, OpIdentifier>,
     secondary_indexes: SecondaryIndexDatabases,
     schema_db: SchemaDatabase,
     cache_options: CacheCommonOptions,
@@ -443,10 +695,11 @@ impl LmdbCacheCommon {
         create_db_if_not_exist: bool,
     ) -> Result<Self, CacheError> {
         // Create or open must have databases.
-        let record_id_to_record =
-            LmdbMap::new_from_env(env, Some("records"), create_db_if_not_exist)?;
+        let record_id_to_record = BackendMap::new(env, Some("records"), create_db_if_not_exist)?;
         let primary_key_to_record_id =
-            LmdbMap::new_from_env(env, Some("primary_index"), create_db_if_not_exist)?;
+            BackendMap::new(env, Some("primary_index"), create_db_if_not_exist)?;
+        let counters = LmdbCounter::new_from_env(env, Some("counters"), create_db_if_not_exist)?;
+        let checkpoint_db = BackendMap::new(env, Some("checkpoint"), create_db_if_not_exist)?;
         let schema_db = SchemaDatabase::new(env, create_db_if_not_exist)?;
 
         // Open existing secondary index databases.
@@ -468,6 +721,8 @@ impl LmdbCacheCommon {
         Ok(Self {
             record_id_to_record,
             primary_key_to_record_id,
+            counters,
+            checkpoint_db,
             secondary_indexes: secondary_indexe_databases,
             schema_db,
             cache_options: options,
@@ -511,5 +766,12 @@ mod tests {
         ) -> (&SharedTransaction, &SecondaryIndexDatabases) {
             (&self.txn, &self.common.secondary_indexes)
         }
+
+        /// The bytes that the record of `id` is stored as.
+        pub fn get_stored_record(&self, id: u64) -> Vec<u8> {
+            let txn = self.txn.read();
+            let db = self.common.record_id_to_record.database();
+            txn.txn().get(db, &id.to_be_bytes()).unwrap().to_vec()
+        }
     }
 }
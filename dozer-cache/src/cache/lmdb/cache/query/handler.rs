@@ -12,18 +12,20 @@ use crate::cache::{
     plan::{IndexScan, IndexScanKind, Plan, QueryPlanner, SortedInvertedRangeQuery},
 };
 use crate::errors::{CacheError, IndexError};
-use dozer_storage::lmdb::Transaction;
+use dozer_storage::backend::ReadTransaction;
+use dozer_storage::lmdb::{Database, Transaction};
+use dozer_storage::LmdbMultimap;
 use dozer_types::types::{Field, IndexDefinition, Schema};
 use itertools::Either;
 
-pub struct LmdbQueryHandler<'a, T: Transaction> {
+pub struct LmdbQueryHandler<'a, T: Transaction + ReadTransaction<Database = Database>> {
     common: &'a LmdbCacheCommon,
     txn: &'a T,
     schema: &'a Schema,
     secondary_indexes: &'a [IndexDefinition],
     query: &'a QueryExpression,
 }
-impl<'a, T: Transaction> LmdbQueryHandler<'a, T> {
+impl<'a, T: Transaction + ReadTransaction<Database = Database>> LmdbQueryHandler<'a, T> {
     pub fn new(
         common: &'a LmdbCacheCommon,
         txn: &'a T,
@@ -44,7 +46,17 @@ impl<'a, T: Transaction> LmdbQueryHandler<'a, T> {
         let planner = QueryPlanner::new(self.schema, self.secondary_indexes, self.query);
         let execution = planner.plan()?;
         match execution {
-            Plan::IndexScans(index_scans) => Ok(self.build_index_scan(index_scans)?.count()),
+            Plan::IndexScans(index_scans) => {
+                if let (Skip::Skip(skip), [index_scan]) = (self.query.skip, index_scans.as_slice())
+                {
+                    if let Some(count) = self.count_single_key(index_scan)? {
+                        return Ok(count
+                            .saturating_sub(skip)
+                            .min(self.query.limit.unwrap_or(usize::MAX)));
+                    }
+                }
+                Ok(self.build_index_scan(index_scans)?.count())
+            }
             Plan::SeqScan(_) => Ok(match self.query.skip {
                 Skip::Skip(skip) => self
                     .common
@@ -110,32 +122,71 @@ impl<'a, T: Transaction> LmdbQueryHandler<'a, T> {
         Ok(skip(full_scan, self.query.skip).take(self.query.limit.unwrap_or(usize::MAX)))
     }
 
-    fn query_with_secondary_index(
-        &'a self,
-        index_scan: &IndexScan,
-    ) -> Result<impl Iterator<Item = Result<u64, CacheError>> + 'a, CacheError> {
+    /// If `index_scan` scans a single key, such as an equality filter, returns the number of ids of that key without iterating them.
+    fn count_single_key(&self, index_scan: &IndexScan) -> Result<Option<usize>, CacheError> {
+        let RangeSpec { start, end, .. } =
+            get_range_spec(&index_scan.kind, index_scan.is_single_field_sorted_inverted)?;
+        match (start, end) {
+            (Some(KeyEndpoint::Including(start)), Some(KeyEndpoint::Including(end)))
+                if start == end =>
+            {
+                let index_db = self.secondary_index_database(index_scan.index_id)?;
+                Ok(Some(index_db.value_count(self.txn, &start)?))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn secondary_index_database(
+        &self,
+        index_id: usize,
+    ) -> Result<LmdbMultimap<[u8], u64>, CacheError> {
         let schema_id = self
             .schema
             .identifier
             .ok_or(CacheError::SchemaHasNoIdentifier)?;
-        let index_db = *self
-            .common
+        self.common
             .secondary_indexes
-            .get(&(schema_id, index_scan.index_id))
-            .ok_or(CacheError::SecondaryIndexDatabaseNotFound)?;
+            .get(&(schema_id, index_id))
+            .copied()
+            .ok_or(CacheError::SecondaryIndexDatabaseNotFound)
+    }
+
+    fn query_with_secondary_index(
+        &'a self,
+        index_scan: &IndexScan,
+    ) -> Result<impl Iterator<Item = Result<u64, CacheError>> + 'a, CacheError> {
+        let index_db = self.secondary_index_database(index_scan.index_id)?;
 
         let RangeSpec {
             start,
             end,
             direction,
         } = get_range_spec(&index_scan.kind, index_scan.is_single_field_sorted_inverted)?;
+
+        // A single key, such as an equality filter, is read without comparing the keys of the index.
+        if let (Some(KeyEndpoint::Including(start)), Some(KeyEndpoint::Including(end))) =
+            (&start, &end)
+        {
+            if start == end && direction == SortDirection::Ascending {
+                let ids = index_db
+                    .range_dup(self.txn, start, (Bound::Unbounded, Bound::Unbounded))?
+                    .map(|result| {
+                        result
+                            .map(|id| id.into_owned())
+                            .map_err(CacheError::Storage)
+                    });
+                return Ok(Either::Left(ids));
+            }
+        }
+
         let start = match &start {
             Some(KeyEndpoint::Including(key)) => Bound::Included(key.as_slice()),
             Some(KeyEndpoint::Excluding(key)) => Bound::Excluded(key.as_slice()),
             None => Bound::Unbounded,
         };
 
-        Ok(index_db
+        let ids = index_db
             .range(self.txn, start, direction == SortDirection::Ascending)?
             .take_while(move |result| match result {
                 Ok((key, _)) => {
@@ -155,7 +206,8 @@ impl<'a, T: Transaction> LmdbQueryHandler<'a, T> {
                 result
                     .map(|(_, id)| id.into_owned())
                     .map_err(CacheError::Storage)
-            }))
+            });
+        Ok(Either::Right(ids))
     }
 
     fn collect_records(
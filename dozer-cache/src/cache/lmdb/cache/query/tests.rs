@@ -152,6 +152,20 @@ fn query_secondary_vars() {
         schema_name,
     );
 
+    test_query(
+        json!({"$filter":{ "b": "james"}, "$skip": 1, "$limit": 1}),
+        1,
+        &cache,
+        schema_name,
+    );
+
+    test_query(
+        json!({"$filter":{ "b": "james"}, "$skip": 5}),
+        0,
+        &cache,
+        schema_name,
+    );
+
     test_query(
         json!({"$filter":{ "a": 1, "b": "yuri".to_string()}}),
         1,
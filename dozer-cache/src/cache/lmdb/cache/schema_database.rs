@@ -142,6 +142,7 @@ mod tests {
                 typ: FieldType::UInt,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             }],
             primary_index: vec![0],
         };
@@ -47,6 +47,6 @@ pub fn new_secondary_index_database_from_txn(
     Ok(result)
 }
 
-fn database_name(schema_id: &SchemaIdentifier, index: usize) -> String {
+pub fn database_name(schema_id: &SchemaIdentifier, index: usize) -> String {
     format!("index_#{}_#{}_#{}", schema_id.id, schema_id.version, index)
 }
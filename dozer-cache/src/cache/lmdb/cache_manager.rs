@@ -29,6 +29,9 @@ pub struct CacheManagerOptions {
     // This size is allocated at initialization.
     pub max_size: usize,
 
+    /// See `CacheWriteOptions::map_growth_factor`.
+    pub map_growth_factor: Option<f64>,
+
     /// Provide a path where db will be created. If nothing is provided, will default to a temp directory.
     pub path: Option<PathBuf>,
 }
@@ -42,6 +45,7 @@ impl Default for CacheManagerOptions {
             max_db_size: cache_common_options.max_db_size,
             intersection_chunk_size: cache_common_options.intersection_chunk_size,
             max_size: cache_write_options.max_size,
+            map_growth_factor: cache_write_options.map_growth_factor,
             path: None,
         }
     }
@@ -152,12 +156,15 @@ impl LmdbCacheManager {
             max_readers: self.options.max_readers,
             intersection_chunk_size: self.options.intersection_chunk_size,
             path: Some((self.base_path.clone(), name)),
+            multi_process: false,
         }
     }
 
     fn cache_write_options(&self) -> CacheWriteOptions {
         CacheWriteOptions {
             max_size: self.options.max_size,
+            map_growth_factor: self.options.map_growth_factor,
+            ..Default::default()
         }
     }
 
@@ -1,6 +1,7 @@
+use dozer_storage::comparator::{set_comparator, KeyComparator};
 use dozer_storage::errors::StorageError;
 use dozer_storage::lmdb::{Database, Transaction};
-use dozer_storage::lmdb_sys::{mdb_set_compare, MDB_cmp_func, MDB_val, MDB_SUCCESS};
+use dozer_storage::lmdb_sys::MDB_val;
 
 use crate::cache::index::compare_composite_secondary_index;
 
@@ -9,21 +10,19 @@ pub fn set_sorted_inverted_comparator<T: Transaction>(
     db: Database,
     fields: &[usize],
 ) -> Result<(), StorageError> {
-    let comparator: MDB_cmp_func = if fields.len() == 1 {
+    match sorted_inverted_comparator(fields) {
+        Some(comparator) => set_comparator(txn, db, comparator),
+        None => Ok(()),
+    }
+}
+
+/// The key comparator of a sorted inverted index on `fields`, or `None` if keys are compared byte-wise.
+pub fn sorted_inverted_comparator(fields: &[usize]) -> Option<KeyComparator> {
+    if fields.len() == 1 {
         None
     } else {
         Some(compare_composite_key)
-    };
-
-    if let Some(comparator) = comparator {
-        unsafe {
-            assert_eq!(
-                mdb_set_compare(txn.txn(), db.dbi(), Some(comparator)),
-                MDB_SUCCESS
-            );
-        }
     }
-    Ok(())
 }
 
 unsafe fn mdb_val_to_slice(val: &MDB_val) -> &[u8] {
@@ -85,7 +85,7 @@ impl<'a> Indexer<'a> {
         Ok(())
     }
 
-    fn _build_index_sorted_inverted(fields: &[usize], values: &[Field]) -> Vec<u8> {
+    pub(crate) fn _build_index_sorted_inverted(fields: &[usize], values: &[Field]) -> Vec<u8> {
         let values = fields
             .iter()
             .copied()
@@ -1,13 +1,14 @@
 use crate::cache::{
     expression::{self, FilterExpression, QueryExpression, Skip},
     index,
-    lmdb::cache::LmdbRwCache,
+    lmdb::cache::{CacheWriteOptions, LmdbRwCache},
     test_utils::{self, query_from_filter},
     RoCache, RwCache,
 };
 use dozer_types::{
+    node::{NodeHandle, OpIdentifier, SourceStates, SourceTransaction},
     serde_json::Value,
-    types::{Field, Record, Schema},
+    types::{Field, Record, RecordEnvelope, Schema, FORMAT_VERSION_COMPACT},
 };
 
 use super::utils::create_cache;
@@ -91,6 +92,70 @@ fn insert_get_and_delete_record() {
     );
 }
 
+#[test]
+fn insert_stores_record_encoding() {
+    let (cache, schema, _) = _setup();
+    let mut record = Record::new(schema.identifier, vec![Field::String("foo".into())], None);
+    let id = cache.insert(&mut record).unwrap();
+
+    let stored = cache.get_stored_record(id);
+    assert!(RecordEnvelope::read(&stored).unwrap().is_some());
+    assert_eq!(stored, record.encode_stored());
+}
+
+#[test]
+fn insert_stores_compact_records() {
+    let (cache, schema, _) = _setup();
+    let mut record = Record::new(schema.identifier, vec![Field::String("foo".into())], None);
+    let id = cache.insert(&mut record).unwrap();
+
+    let stored = cache.get_stored_record(id);
+    let (_, payload) = RecordEnvelope::read(&stored).unwrap().unwrap();
+    assert_eq!(payload, record.encode_compact());
+    assert!(payload.len() < dozer_types::bincode::serialize(&record).unwrap().len());
+    let key = index::get_primary_key(&schema.primary_index, &record.values);
+    assert_eq!(cache.get(&key).unwrap().record, record);
+}
+
+#[test]
+fn insert_and_update_store_record_envelope() {
+    let (cache, schema, _) = _setup();
+    let mut record = Record::new(schema.identifier, vec![Field::String("foo".into())], None);
+    cache.insert(&mut record).unwrap();
+    let key = index::get_primary_key(&schema.primary_index, &record.values);
+    cache.update(&key, &mut record).unwrap();
+    let id = cache.get(&key).unwrap().id;
+
+    let stored = cache.get_stored_record(id);
+    let (envelope, _) = RecordEnvelope::read(&stored).unwrap().unwrap();
+    assert_eq!(envelope.format_version, FORMAT_VERSION_COMPACT);
+    assert_eq!(
+        envelope.schema_version,
+        schema.identifier.unwrap().version as u8
+    );
+}
+
+#[test]
+fn get_many_records() {
+    let (cache, schema, _) = _setup();
+    let mut records = vec![];
+    for val in ["foo", "bar"] {
+        let mut record = Record::new(schema.identifier, vec![Field::String(val.into())], None);
+        let id = cache.insert(&mut record).unwrap();
+        records.push((id, record));
+    }
+
+    let keys = ["bar", "missing", "foo"]
+        .map(|val| index::get_primary_key(&[0], &[Field::String(val.into())]));
+    let found = cache.get_many(&keys).unwrap();
+    assert_eq!(found.len(), 3);
+    assert_eq!(found[0].as_ref().unwrap().id, records[1].0);
+    assert_eq!(found[0].as_ref().unwrap().record, records[1].1);
+    assert!(found[1].is_none());
+    assert_eq!(found[2].as_ref().unwrap().record, records[0].1);
+    assert_eq!(cache.get_many(&[]).unwrap(), vec![]);
+}
+
 #[test]
 fn insert_and_update_record() {
     let (cache, schema, _) = _setup();
@@ -114,6 +179,69 @@ fn insert_and_update_record() {
     assert_eq!(foo.version.unwrap(), old_version + 1);
 }
 
+#[test]
+fn insert_fills_omitted_values() {
+    let (cache, _, _) = create_cache("doc", || {
+        let (mut schema, secondary_indexes) = test_utils::schema_1();
+        schema.fields[1].metadata.default_value = Some(Field::String("none".to_string()));
+        (schema, secondary_indexes)
+    });
+    let schema = cache
+        .get_schema_and_indexes_by_name("doc")
+        .unwrap()
+        .0
+        .clone();
+
+    let mut record = Record::new(
+        schema.identifier,
+        vec![Field::Int(1), Field::Null, Field::Null],
+        None,
+    );
+    cache.insert(&mut record).unwrap();
+    assert_eq!(
+        record.values,
+        vec![
+            Field::Int(1),
+            Field::String("none".to_string()),
+            Field::Null
+        ]
+    );
+
+    let key = index::get_primary_key(&schema.primary_index, &record.values);
+    assert_eq!(cache.get(&key).unwrap().record, record);
+}
+
+#[test]
+fn commit_and_get_checkpoint_with_transaction() {
+    let (cache, _, _) = _setup();
+    let checkpoint: SourceStates = [
+        (
+            NodeHandle::new(None, "postgres".to_string()),
+            OpIdentifier::new(100, 2).with_transaction(SourceTransaction {
+                id: Some(731),
+                commit_lsn: Some(120),
+                commit_timestamp: Some(1_680_000_000_000_000),
+            }),
+        ),
+        (
+            NodeHandle::new(Some(1), "kafka".to_string()),
+            OpIdentifier::new(5, 0),
+        ),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(cache.get_commit_epoch().unwrap(), 0);
+    assert_eq!(cache.get_last_commit_time().unwrap(), None);
+    cache.commit(&checkpoint).unwrap();
+    assert_eq!(cache.get_commit_epoch().unwrap(), 1);
+    assert!(cache.get_last_commit_time().unwrap().is_some());
+
+    let stored = cache.get_checkpoint().unwrap();
+    assert_eq!(stored, checkpoint);
+    let handle = NodeHandle::new(None, "postgres".to_string());
+    assert_eq!(stored[&handle].transaction.unwrap().commit_lsn, Some(120));
+}
+
 fn insert_and_query_record_impl(cache: LmdbRwCache, schema: Schema, schema_name: &str) {
     let val = "bar".to_string();
     let mut record = Record::new(schema.identifier, vec![Field::String(val)], None);
@@ -145,3 +273,72 @@ fn insert_and_query_record() {
     let (cache, schema, schema_name) = _setup_empty_primary_index();
     insert_and_query_record_impl(cache, schema, schema_name);
 }
+
+#[test]
+fn environment_stats() {
+    let (cache, schema, _) = _setup();
+    let entries = |cache: &LmdbRwCache| -> usize {
+        cache
+            .environment_stats()
+            .unwrap()
+            .database_entries
+            .iter()
+            .map(|(_, entries)| entries)
+            .sum()
+    };
+    let before = entries(&cache);
+
+    let mut record = Record::new(schema.identifier, vec![Field::String("foo".into())], None);
+    cache.insert(&mut record).unwrap();
+    let stats = cache.environment_stats().unwrap();
+    assert!(stats
+        .database_entries
+        .iter()
+        .any(|(name, entries)| name == "checkpoint" && *entries == 0));
+    assert!(entries(&cache) > before);
+    assert!(stats.info.map_used_ratio() > 0.0);
+}
+
+#[test]
+fn insert_grows_full_map() {
+    let schema_name = "doc";
+    let (schema, secondary_indexes) = test_utils::schema_0();
+    let map_size = 2 * 1024 * 1024;
+    let cache = LmdbRwCache::create(
+        [(schema_name.to_string(), schema.clone(), secondary_indexes)],
+        Default::default(),
+        CacheWriteOptions {
+            max_size: map_size,
+            map_growth_factor: Some(2.0),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // Write more than the map can hold in one transaction, so it's replayed in a grown map.
+    let count = 10000;
+    for i in 0..count {
+        let mut record = Record::new(
+            schema.identifier,
+            vec![Field::String(format!("{i:0200}"))],
+            None,
+        );
+        cache.insert(&mut record).unwrap();
+    }
+    cache
+        .delete(&Field::String(format!("{:0200}", 0)).encode())
+        .unwrap();
+    cache.commit(&Default::default()).unwrap();
+
+    assert!(cache.environment_stats().unwrap().info.map_size > map_size);
+    assert_eq!(
+        cache
+            .count(schema_name, &QueryExpression::with_no_limit())
+            .unwrap(),
+        count - 1
+    );
+    let record = cache
+        .get(&Field::String(format!("{:0200}", count - 1)).encode())
+        .unwrap();
+    assert_eq!(record.id, count as u64 - 1);
+}
@@ -20,9 +20,11 @@ fn read_and_write() {
             max_db_size: 100,
             path: Some(path.clone()),
             intersection_chunk_size: 1,
+            multi_process: false,
         },
         CacheWriteOptions {
             max_size: 1024 * 1024,
+            ..Default::default()
         },
     )
     .unwrap();
@@ -44,6 +46,9 @@ fn read_and_write() {
         ..Default::default()
     };
     let cache_reader = LmdbRoCache::new(read_options).unwrap();
+    assert_eq!(cache_reader.get_commit_epoch().unwrap(), 1);
+    assert!(cache_reader.get_last_commit_time().unwrap().is_some());
+    assert_eq!(cache_reader.get_checkpoint().unwrap(), Default::default());
     for (a, b, c) in items {
         let rec = cache_reader.get(&Field::Int(a).encode()).unwrap();
         let values = vec![
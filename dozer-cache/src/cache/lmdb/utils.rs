@@ -4,10 +4,15 @@ use crate::errors::CacheError;
 use dozer_storage::{
     lmdb::EnvironmentFlags,
     lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions},
+    migration::{self, Migration},
 };
 use tempdir::TempDir;
 
-use super::cache::{CacheCommonOptions, CacheWriteOptions};
+use super::cache::{
+    BsonEncodingMigration, ByteWiseOrderMigration, CacheCommonOptions, CacheWriteOptions,
+    CompositePrimaryKeyMigration, FieldMetadataMigration, RecordIdCounterMigration,
+    TimestampEncodingMigration,
+};
 
 #[derive(Clone, Debug, Default)]
 pub struct CacheOptions {
@@ -33,6 +38,16 @@ impl Default for CacheOptionsKind {
     }
 }
 
+/// Migrations of the cache storage layout, in version order.
+const MIGRATIONS: &[&dyn Migration] = &[
+    &TimestampEncodingMigration,
+    &FieldMetadataMigration,
+    &BsonEncodingMigration,
+    &RecordIdCounterMigration,
+    &CompositePrimaryKeyMigration,
+    &ByteWiseOrderMigration,
+];
+
 pub fn init_env(options: &CacheOptions) -> Result<(LmdbEnvironmentManager, String), CacheError> {
     match &options.kind {
         CacheOptionsKind::Write(write_options) => {
@@ -51,17 +66,20 @@ pub fn init_env(options: &CacheOptions) -> Result<(LmdbEnvironmentManager, Strin
                 }
             };
 
-            let options = LmdbEnvironmentOptions::new(
-                options.common.max_db_size,
-                options.common.max_readers,
-                write_options.max_size,
-                EnvironmentFlags::empty(),
-            );
-
-            Ok((
-                LmdbEnvironmentManager::create(&base_path, name, options)?,
-                name.to_string(),
-            ))
+            let options = LmdbEnvironmentOptions {
+                max_dbs: options.common.max_db_size,
+                max_readers: options.common.max_readers,
+                max_map_sz: write_options.max_size,
+                no_sync: write_options.no_sync,
+                write_map: write_options.write_map,
+                map_growth_factor: write_options.map_growth_factor,
+                multi_process: options.common.multi_process,
+                ..Default::default()
+            };
+
+            let mut env = LmdbEnvironmentManager::create(&base_path, name, options)?;
+            migration::migrate(&mut env, MIGRATIONS)?;
+            Ok((env, name.to_string()))
         }
         CacheOptionsKind::ReadOnly(_) => {
             let (base_path, name) = options
@@ -77,10 +95,13 @@ pub fn init_env(options: &CacheOptions) -> Result<(LmdbEnvironmentManager, Strin
                 ..Default::default()
             };
 
-            Ok((
-                LmdbEnvironmentManager::create(base_path, name, env_options)?,
-                name.to_string(),
-            ))
+            let mut env = if options.common.multi_process {
+                LmdbEnvironmentManager::open_shared_reader(base_path, name, env_options)?
+            } else {
+                LmdbEnvironmentManager::create(base_path, name, env_options)?
+            };
+            migration::check_layout_version(&mut env, MIGRATIONS)?;
+            Ok((env, name.to_string()))
         }
     }
 }
@@ -3,7 +3,9 @@ use std::fmt::Debug;
 
 use self::expression::QueryExpression;
 use crate::errors::CacheError;
+use dozer_storage::lmdb_storage::LmdbEnvironmentStats;
 use dozer_types::{
+    chrono::{DateTime, Utc},
     node::SourceStates,
     serde::{Deserialize, Serialize},
     types::{IndexDefinition, Record, Schema, SchemaIdentifier},
@@ -67,12 +69,25 @@ pub trait RoCache: Send + Sync + Debug {
 
     // Record Operations
     fn get(&self, key: &[u8]) -> Result<RecordWithId, CacheError>;
+    /// Gets the records of `keys` in one transaction, `None` for the keys that are not found.
+    fn get_many(&self, keys: &[Vec<u8>]) -> Result<Vec<Option<RecordWithId>>, CacheError>;
     fn count(&self, schema_name: &str, query: &QueryExpression) -> Result<usize, CacheError>;
     fn query(
         &self,
         schema_name: &str,
         query: &QueryExpression,
     ) -> Result<(&Schema, Vec<RecordWithId>), CacheError>;
+
+    /// Returns the number of commits of the cache, which advances whenever its records may have changed.
+    fn get_commit_epoch(&self) -> Result<u64, CacheError>;
+    /// Returns the time of the last commit, `None` if the cache has never committed.
+    fn get_last_commit_time(&self) -> Result<Option<DateTime<Utc>>, CacheError>;
+    /// Get the current checkpoint.
+    fn get_checkpoint(&self) -> Result<SourceStates, CacheError>;
+
+    // Telemetry
+    /// Statistics of the underlying LMDB environment, such as how full its map is.
+    fn environment_stats(&self) -> Result<LmdbEnvironmentStats, CacheError>;
 }
 
 pub trait RwCache: RoCache {
@@ -85,6 +100,4 @@ pub trait RwCache: RoCache {
     fn update(&self, key: &[u8], record: &mut Record) -> Result<u32, CacheError>;
     /// Commits the current transaction.
     fn commit(&self, checkpoint: &SourceStates) -> Result<(), CacheError>;
-    /// Get the current checkpoint.
-    fn get_checkpoint(&self) -> Result<SourceStates, CacheError>;
 }
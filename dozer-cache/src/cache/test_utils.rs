@@ -13,6 +13,7 @@ pub fn schema_0() -> (Schema, Vec<IndexDefinition>) {
                 typ: dozer_types::types::FieldType::String,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             }],
             primary_index: vec![0],
         },
@@ -30,18 +31,21 @@ pub fn schema_1() -> (Schema, Vec<IndexDefinition>) {
                     typ: dozer_types::types::FieldType::Int,
                     nullable: true,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 },
                 FieldDefinition {
                     name: "b".to_string(),
                     typ: dozer_types::types::FieldType::String,
                     nullable: true,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 },
                 FieldDefinition {
                     name: "c".to_string(),
                     typ: dozer_types::types::FieldType::Int,
                     nullable: true,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 },
             ],
             primary_index: vec![0],
@@ -66,12 +70,14 @@ pub fn schema_full_text() -> (Schema, Vec<IndexDefinition>) {
                     typ: dozer_types::types::FieldType::String,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 },
                 FieldDefinition {
                     name: "bar".to_string(),
                     typ: dozer_types::types::FieldType::Text,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 },
             ],
             primary_index: vec![0],
@@ -90,6 +96,7 @@ pub fn schema_empty_primary_index() -> (Schema, Vec<IndexDefinition>) {
                 typ: dozer_types::types::FieldType::String,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             }],
             primary_index: vec![],
         },
@@ -107,12 +114,14 @@ pub fn schema_multi_indices() -> (Schema, Vec<IndexDefinition>) {
                     typ: dozer_types::types::FieldType::Int,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 },
                 FieldDefinition {
                     name: "text".to_string(),
                     typ: dozer_types::types::FieldType::String,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 },
             ],
             primary_index: vec![0],
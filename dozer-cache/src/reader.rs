@@ -2,7 +2,10 @@ use crate::cache::{expression::QueryExpression, RecordWithId, RoCache};
 
 use super::cache::expression::FilterExpression;
 use crate::errors::CacheError;
+use dozer_storage::lmdb_storage::LmdbEnvironmentStats;
 use dozer_types::{
+    chrono::{DateTime, Utc},
+    node::SourceStates,
     serde,
     types::{IndexDefinition, Record, Schema},
 };
@@ -16,8 +19,12 @@ pub struct AccessFilter {
     /// FilterExpression to evaluate access
     pub filter: Option<FilterExpression>,
 
-    /// Fields to be restricted
+    /// Fields to be restricted, masked as null by the API
     pub fields: Vec<String>,
+
+    /// Fields that queries may filter on, all of them if `None`. Checked by the API before the query gets here.
+    #[serde(default)]
+    pub filter_fields: Option<Vec<String>>,
 }
 
 #[derive(Debug)]
@@ -55,6 +62,19 @@ impl CacheReader {
         }
     }
 
+    /// Gets the records of `keys`, `None` for the keys that are not found.
+    pub fn get_many(
+        &self,
+        keys: &[Vec<u8>],
+        access_filter: &AccessFilter,
+    ) -> Result<Vec<Option<RecordWithId>>, CacheError> {
+        let records = self.cache.get_many(keys)?;
+        for record in records.iter().flatten() {
+            self.check_access(&record.record, access_filter)?;
+        }
+        Ok(records)
+    }
+
     pub fn query(
         &self,
         schema_name: &str,
@@ -65,6 +85,31 @@ impl CacheReader {
         self.cache.query(schema_name, query)
     }
 
+    /// See `RoCache::name`.
+    pub fn cache_name(&self) -> &str {
+        self.cache.name()
+    }
+
+    /// See `RoCache::get_commit_epoch`.
+    pub fn get_commit_epoch(&self) -> Result<u64, CacheError> {
+        self.cache.get_commit_epoch()
+    }
+
+    /// See `RoCache::get_last_commit_time`.
+    pub fn get_last_commit_time(&self) -> Result<Option<DateTime<Utc>>, CacheError> {
+        self.cache.get_last_commit_time()
+    }
+
+    /// See `RoCache::get_checkpoint`.
+    pub fn get_checkpoint(&self) -> Result<SourceStates, CacheError> {
+        self.cache.get_checkpoint()
+    }
+
+    /// See `RoCache::environment_stats`.
+    pub fn environment_stats(&self) -> Result<LmdbEnvironmentStats, CacheError> {
+        self.cache.environment_stats()
+    }
+
     pub fn count(
         &self,
         schema_name: &str,
@@ -15,7 +15,7 @@ use dozer_storage::lmdb_storage::{
 use dozer_types::bincode;
 use dozer_types::log::debug;
 use dozer_types::node::{NodeHandle, OpIdentifier, SourceStates};
-use dozer_types::types::Schema;
+use dozer_types::types::{legacy, Schema};
 use std::collections::HashMap;
 
 use std::path::PathBuf;
@@ -304,7 +304,8 @@ fn deserialize_schema(key: &[u8], value: &[u8]) -> Result<(PortHandle, Schema),
             .try_into()
             .map_err(|_e| ExecutionError::InvalidPortHandle(0))?,
     );
-    let schema: Schema = bincode::deserialize(value).map_err(|e| DeserializationError {
+    // Pipelines that ran before `FieldDefinition::metadata` was added stored the legacy layout.
+    let schema = legacy::deserialize_schema(value).map_err(|e| DeserializationError {
         typ: "Schema",
         reason: Box::new(e),
     })?;
@@ -324,11 +325,11 @@ pub fn write_source_metadata<'a>(
     Ok(())
 }
 
-fn serialize_source_metadata(node_handle: &NodeHandle, op_id: OpIdentifier) -> (Vec<u8>, [u8; 16]) {
+fn serialize_source_metadata(node_handle: &NodeHandle, op_id: OpIdentifier) -> (Vec<u8>, Vec<u8>) {
     let mut key: Vec<u8> = vec![SOURCE_ID_IDENTIFIER];
     key.extend(node_handle.to_bytes());
 
-    let value = op_id.to_bytes();
+    let value = op_id.to_bytes_with_transaction();
 
     (key, value)
 }
@@ -337,7 +338,7 @@ fn deserialize_source_metadata(key: &[u8], value: &[u8]) -> (NodeHandle, OpIdent
     debug_assert!(key[0] == SOURCE_ID_IDENTIFIER);
     let source = NodeHandle::from_bytes(&key[1..]);
 
-    let op_id = OpIdentifier::from_bytes(value.try_into().unwrap());
+    let op_id = OpIdentifier::from_bytes_with_transaction(value).unwrap();
     (source, op_id)
 }
 
@@ -11,7 +11,7 @@ use crossbeam::channel::Sender;
 use dozer_storage::lmdb_storage::SharedTransaction;
 use dozer_types::ingestion_types::{IngestionMessage, IngestionMessageKind};
 use dozer_types::log::debug;
-use dozer_types::node::NodeHandle;
+use dozer_types::node::{NodeHandle, OpIdentifier, SourceTransaction};
 use dozer_types::types::Operation;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -148,6 +148,7 @@ pub(crate) struct SourceChannelManager {
     manager: ChannelManager,
     curr_txid: u64,
     curr_seq_in_tx: u64,
+    curr_transaction: Option<SourceTransaction>,
     commit_sz: u32,
     num_uncommitted_ops: u32,
     max_duration_between_commits: Duration,
@@ -171,6 +172,7 @@ impl SourceChannelManager {
             // FIXME: Read curr_txid and curr_seq_in_tx from persisted state.
             curr_txid: 0,
             curr_seq_in_tx: 0,
+            curr_transaction: None,
             source_handle: owner,
             commit_sz,
             num_uncommitted_ops: 0,
@@ -190,11 +192,14 @@ impl SourceChannelManager {
             .epoch_manager
             .wait_for_epoch_close(request_termination, self.num_uncommitted_ops > 0);
         if let Some(epoch_id) = epoch {
-            self.manager.store_and_send_commit(&Epoch::from(
+            let op_id = OpIdentifier {
+                txid: self.curr_txid,
+                seq_in_tx: self.curr_seq_in_tx,
+                transaction: self.curr_transaction,
+            };
+            self.manager.store_and_send_commit(&Epoch::new(
                 epoch_id,
-                self.source_handle.clone(),
-                self.curr_txid,
-                self.curr_seq_in_tx,
+                [(self.source_handle.clone(), op_id)].into_iter().collect(),
             ))?;
         }
         self.num_uncommitted_ops = 0;
@@ -222,6 +227,7 @@ impl SourceChannelManager {
         //
         self.curr_txid = message.identifier.txid;
         self.curr_seq_in_tx = message.identifier.seq_in_tx;
+        self.curr_transaction = message.identifier.transaction;
         match message.kind {
             IngestionMessageKind::OperationEvent(op) => {
                 self.manager.send_op(op, port)?;
@@ -12,7 +12,7 @@ use tempdir::TempDir;
 
 #[test]
 fn test_checkpoint_consistency_ns() {
-    // dozer_tracing::init_telemetry(false).unwrap();
+    // dozer_tracing::init_telemetry(Default::default()).unwrap();
 
     const MESSAGES_COUNT: u64 = 25_000;
 
@@ -84,7 +84,7 @@ impl Processor for NoopProcessor {
 
 #[test]
 fn test_run_dag() {
-    // dozer_tracing::init_telemetry(false).unwrap();
+    // dozer_tracing::init_telemetry(Default::default()).unwrap();
 
     let count: u64 = 1_000;
 
@@ -41,6 +41,7 @@ pub fn get_contract_event_schemas(
                     },
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 });
             }
 
@@ -245,72 +246,84 @@ pub fn get_eth_schema() -> Schema {
                 typ: FieldType::UInt,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "address".to_string(),
                 typ: FieldType::String,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "topics".to_string(),
                 typ: FieldType::String,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "data".to_string(),
                 typ: FieldType::Binary,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "block_hash".to_string(),
                 typ: FieldType::String,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "block_number".to_string(),
                 typ: FieldType::UInt,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "transaction_hash".to_string(),
                 typ: FieldType::String,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "transaction_index".to_string(),
                 typ: FieldType::Int,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "log_index".to_string(),
                 typ: FieldType::Int,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "transaction_log_index".to_string(),
                 typ: FieldType::Int,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "log_type".to_string(),
                 typ: FieldType::String,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "removed".to_string(),
                 typ: FieldType::Boolean,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
         ],
 
@@ -88,7 +88,7 @@ pub fn get_eth_producer(
 }
 
 pub fn run_eth_sample(wss_url: String, my_account: H160) -> (Contract<WebSocket>, Vec<Operation>) {
-    dozer_tracing::init_telemetry(false).unwrap();
+    dozer_tracing::init_telemetry(Default::default()).unwrap();
     let orig_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic_info| {
         // invoke the default handler and exit the process
@@ -99,48 +99,56 @@ pub fn get_trace_schema() -> Schema {
                 typ: FieldType::String,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "from".to_string(),
                 typ: FieldType::String,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "to".to_string(),
                 typ: FieldType::String,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "value".to_string(),
                 typ: FieldType::UInt,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "gas".to_string(),
                 typ: FieldType::UInt,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "gas_used".to_string(),
                 typ: FieldType::UInt,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "input".to_string(),
                 typ: FieldType::Text,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "output".to_string(),
                 typ: FieldType::Text,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
         ],
         primary_index: vec![],
@@ -136,6 +136,27 @@ fn map_record(rec: grpc_types::types::Record, schema: &Schema) -> Result<Record,
                 grpc_types::types::value::Value::BytesValue(a),
                 dozer_types::types::FieldType::Bson,
             ) => Ok(dozer_types::types::Field::Bson(a.clone())),
+            (
+                grpc_types::types::value::Value::BytesValue(a),
+                dozer_types::types::FieldType::Geometry,
+            ) => dozer_types::types::DozerGeometry::from_ewkb(a.clone())
+                .map(dozer_types::types::Field::Geometry)
+                .map_err(|e| {
+                    ConnectorError::InitializationError(format!(
+                        "data is not valid at index: {idx}, {e}"
+                    ))
+                }),
+            (
+                grpc_types::types::value::Value::StringValue(a),
+                dozer_types::types::FieldType::Decimal128,
+            ) => a
+                .parse::<dozer_types::types::DozerDecimal128>()
+                .map(dozer_types::types::Field::Decimal128)
+                .map_err(|e| {
+                    ConnectorError::InitializationError(format!(
+                        "data is not valid at index: {idx}, {e}"
+                    ))
+                }),
             (
                 grpc_types::types::value::Value::TimestampValue(a),
                 dozer_types::types::FieldType::Timestamp,
@@ -148,6 +169,19 @@ fn map_record(rec: grpc_types::types::Record, schema: &Schema) -> Result<Record,
                     })
                     .unwrap_or(dozer_types::types::Field::Null),
             ),
+            (
+                grpc_types::types::value::Value::DurationValue(a),
+                dozer_types::types::FieldType::Duration,
+            ) => Ok(a
+                .seconds
+                .checked_mul(1_000_000_000)
+                .and_then(|nanos| nanos.checked_add(a.nanos.into()))
+                .map(|nanos| {
+                    dozer_types::types::Field::Duration(
+                        dozer_types::types::DozerDuration::from_nanos(nanos),
+                    )
+                })
+                .unwrap_or(dozer_types::types::Field::Null)),
             (
                 grpc_types::types::value::Value::DecimalValue(_),
                 dozer_types::types::FieldType::Decimal,
@@ -321,24 +321,28 @@ mod tests {
                     typ: FieldType::Int,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 },
                 FieldDefinition {
                     name: "name".to_string(),
                     typ: FieldType::String,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 },
                 FieldDefinition {
                     name: "description".to_string(),
                     typ: FieldType::String,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 },
                 FieldDefinition {
                     name: "weight".to_string(),
                     typ: FieldType::Float,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 },
             ],
             primary_index: vec![],
@@ -411,12 +415,14 @@ mod tests {
                     typ: FieldType::Int,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 },
                 FieldDefinition {
                     name: "name".to_string(),
                     typ: FieldType::String,
                     nullable: true,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 },
             ],
             primary_index: vec![],
@@ -78,6 +78,7 @@ pub fn map_schema<'a>(
                                 typ,
                                 nullable: f.optional.map_or(false, |o| o),
                                 source: SourceDefinition::Dynamic,
+                                metadata: Default::default(),
                             })
                         })
                         .collect(),
@@ -201,12 +202,14 @@ mod tests {
                     typ: FieldType::Int,
                     nullable: false,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 },
                 FieldDefinition {
                     name: "name".to_string(),
                     typ: FieldType::String,
                     nullable: true,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 },
             ],
             primary_index: vec![0],
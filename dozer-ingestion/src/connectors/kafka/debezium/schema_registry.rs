@@ -129,6 +129,7 @@ impl SchemaRegistry {
                                                 typ,
                                                 nullable,
                                                 source: SourceDefinition::Dynamic,
+                                                metadata: Default::default(),
                                             })
                                         })
                                         .collect();
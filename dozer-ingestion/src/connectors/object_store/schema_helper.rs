@@ -160,6 +160,7 @@ pub fn map_schema_to_dozer<'a, I: Iterator<Item = &'a Field>>(
                 typ: mapped_field_type,
                 nullable: field.is_nullable(),
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             })
         })
         .collect()
@@ -1,7 +1,7 @@
 use crate::connectors::postgres::xlog_mapper::TableColumn;
 use crate::errors::PostgresSchemaError::{
-    ColumnTypeNotFound, ColumnTypeNotSupported, CustomTypeNotSupported, JSONBParseError,
-    PointParseError, StringParseError, ValueConversionError,
+    ColumnTypeNotFound, ColumnTypeNotSupported, CustomTypeNotSupported, GeometryParseError,
+    JSONBParseError, PointParseError, StringParseError, ValueConversionError,
 };
 use crate::errors::{ConnectorError, PostgresSchemaError};
 use dozer_types::bytes::Bytes;
@@ -9,7 +9,7 @@ use dozer_types::chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Offse
 use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::{rust_decimal, serde_json, types::*};
 use postgres::{Column, Row};
-use postgres_types::{Type, WasNull};
+use postgres_types::{FromSql, Kind, Type, WasNull};
 use rust_decimal::prelude::FromPrimitive;
 use rust_decimal::Decimal;
 use std::error::Error;
@@ -17,6 +17,31 @@ use std::vec;
 
 use dozer_types::geo::Point as GeoPoint;
 
+/// Name of the PostGIS geometry type, which has no fixed oid.
+pub const GEOMETRY_TYPE_NAME: &str = "geometry";
+
+fn is_geometry(typ: &Type) -> bool {
+    typ.name() == GEOMETRY_TYPE_NAME
+}
+
+/// The type of a column whose oid `Type::from_oid` doesn't know, such as a PostGIS geometry.
+pub fn custom_type(oid: u32, name: &str, schema: &str) -> Type {
+    Type::new(name.to_string(), oid, Kind::Simple, schema.to_string())
+}
+
+/// The EWKB that PostGIS sends geometries as in the binary format.
+struct Ewkb(Vec<u8>);
+
+impl<'a> FromSql<'a> for Ewkb {
+    fn from_sql(_ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        Ok(Ewkb(raw.to_vec()))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        is_geometry(ty)
+    }
+}
+
 pub fn postgres_type_to_field(
     value: Option<&Bytes>,
     column: &TableColumn,
@@ -80,6 +105,13 @@ pub fn postgres_type_to_field(
                         .parse::<DozerPoint>()
                         .map_err(|_| PointParseError)?,
                 )),
+                // The text format of geometries is hexadecimal EWKB.
+                _ if is_geometry(&column_type) => Ok(Field::Geometry(
+                    String::from_utf8(v.to_vec())
+                        .map_err(StringParseError)?
+                        .parse::<DozerGeometry>()
+                        .map_err(GeometryParseError)?,
+                )),
                 _ => Err(ColumnTypeNotSupported(column_type.name().to_string())),
             })
     })
@@ -97,6 +129,7 @@ pub fn postgres_type_to_dozer_type(column_type: Type) -> Result<FieldType, Postg
         Type::JSONB => Ok(FieldType::Bson),
         Type::DATE => Ok(FieldType::Date),
         Type::POINT => Ok(FieldType::Point),
+        _ if is_geometry(&column_type) => Ok(FieldType::Geometry),
         _ => Err(ColumnTypeNotSupported(column_type.name().to_string())),
     }
 }
@@ -153,6 +186,14 @@ pub fn value_to_field(
             })
         }
         &Type::POINT => convert_row_value_to_field!(row, idx, GeoPoint),
+        _ if is_geometry(col_type) => {
+            let value: Result<Ewkb, _> = row.try_get(idx);
+            value.map_or_else(handle_error, |v| {
+                DozerGeometry::from_ewkb(v.0)
+                    .map(Field::Geometry)
+                    .map_err(GeometryParseError)
+            })
+        }
         _ => {
             if col_type.schema() == "pg_catalog" {
                 Err(ColumnTypeNotSupported(col_type.name().to_string()))
@@ -209,6 +250,7 @@ pub fn convert_column_to_field(column: &Column) -> Result<FieldDefinition, Postg
         typ,
         nullable: true,
         source: SourceDefinition::Dynamic,
+        metadata: Default::default(),
     })
 }
 
@@ -294,6 +336,13 @@ mod tests {
             Type::POINT,
             Field::Point(DozerPoint::from((1.234, 2.456)))
         );
+
+        let hex = "0101000020E6100000000000000000F03F0000000000000040";
+        test_conversion!(
+            hex,
+            custom_type(16_394, GEOMETRY_TYPE_NAME, "public"),
+            Field::Geometry(hex.parse().unwrap())
+        );
     }
 
     #[test]
@@ -308,6 +357,10 @@ mod tests {
         test_type_mapping!(Type::JSONB, FieldType::Bson);
         test_type_mapping!(Type::BOOL, FieldType::Boolean);
         test_type_mapping!(Type::POINT, FieldType::Point);
+        test_type_mapping!(
+            custom_type(16_394, GEOMETRY_TYPE_NAME, "public"),
+            FieldType::Geometry
+        );
     }
 
     #[test]
@@ -218,6 +218,7 @@ impl<'a> PostgresIteratorHandler<'a> {
                 connector_id: self.connector_id,
                 seq_no: 0,
                 name: self.details.name.clone(),
+                transaction: None,
             };
             replicator.start(tables).await
         })
@@ -10,6 +10,7 @@ use dozer_types::bytes;
 use dozer_types::chrono::{TimeZone, Utc};
 use dozer_types::ingestion_types::IngestionMessage;
 use dozer_types::log::{error, info};
+use dozer_types::node::SourceTransaction;
 use futures::StreamExt;
 use postgres_protocol::message::backend::ReplicationMessage::*;
 use postgres_protocol::message::backend::{LogicalReplicationMessage, ReplicationMessage};
@@ -38,6 +39,8 @@ pub struct CDCHandler<'a> {
 
     pub offset: u64,
     pub seq_no: u64,
+    /// The transaction being replicated, from its `Begin` message.
+    pub transaction: Option<SourceTransaction>,
 }
 
 impl<'a> CDCHandler<'a> {
@@ -124,19 +127,19 @@ impl<'a> CDCHandler<'a> {
                     Some(MappedReplicationMessage::Commit(commit)) => {
                         self.last_commit_lsn = commit.txid;
                     }
-                    Some(MappedReplicationMessage::Begin) => {
+                    Some(MappedReplicationMessage::Begin(transaction)) => {
                         self.begin_lsn = lsn;
                         self.seq_no = 0;
+                        self.transaction = Some(transaction);
                     }
                     Some(MappedReplicationMessage::Operation(op)) => {
                         self.seq_no += 1;
                         if self.begin_lsn != self.offset_lsn || self.offset < self.seq_no {
+                            let mut message =
+                                IngestionMessage::new_op(self.begin_lsn, self.seq_no, op);
+                            message.identifier.transaction = self.transaction;
                             self.ingestor
-                                .handle_message(IngestionMessage::new_op(
-                                    self.begin_lsn,
-                                    self.seq_no,
-                                    op,
-                                ))
+                                .handle_message(message)
                                 .map_err(ConnectorError::IngestorError)?;
                         }
                     }
@@ -9,7 +9,7 @@ use dozer_types::types::{
 use crate::connectors::{ColumnInfo, TableInfo, ValidationResults};
 
 use crate::connectors::postgres::connection::helper;
-use crate::connectors::postgres::helper::postgres_type_to_dozer_type;
+use crate::connectors::postgres::helper::{custom_type, postgres_type_to_dozer_type};
 use crate::errors::PostgresSchemaError::{
     InvalidColumnType, PrimaryKeyIsMissingInSchema, ValueConversionError,
 };
@@ -372,7 +372,9 @@ impl SchemaHelper {
         };
         let replication_type_int: i8 = row.get(5);
         let type_oid: u32 = row.get(6);
-        let typ = Type::from_oid(type_oid);
+        let type_name: Option<String> = row.get(8);
+        let typ = Type::from_oid(type_oid)
+            .or_else(|| type_name.map(|name| custom_type(type_oid, &name, "public")));
 
         let typ = typ.map_or(Err(InvalidColumnType), postgres_type_to_dozer_type)?;
 
@@ -403,7 +405,8 @@ SELECT table_info.table_name,
        pc.oid,
        pc.relreplident,
        pt.oid                                                           AS type_oid,
-       t.table_type
+       t.table_type,
+       pt.typname                                                       AS type_name
 FROM information_schema.columns table_info
          LEFT JOIN information_schema.tables t ON t.table_name = table_info.table_name
          LEFT JOIN pg_class pc ON t.table_name = pc.relname
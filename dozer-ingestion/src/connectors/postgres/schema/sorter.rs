@@ -92,6 +92,7 @@ mod tests {
                 typ: FieldType::UInt,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             false,
         );
@@ -101,6 +102,7 @@ mod tests {
                 typ: FieldType::UInt,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             true,
         );
@@ -110,6 +112,7 @@ mod tests {
                 typ: FieldType::UInt,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             false,
         );
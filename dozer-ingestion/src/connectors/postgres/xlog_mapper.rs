@@ -1,11 +1,11 @@
 use crate::connectors::postgres::helper;
 use crate::connectors::ColumnInfo;
 use crate::errors::{PostgresConnectorError, PostgresSchemaError};
-use dozer_types::node::OpIdentifier;
+use dozer_types::node::{OpIdentifier, SourceTransaction};
 use dozer_types::types::{Field, FieldDefinition, Operation, Record, Schema, SourceDefinition};
 use helper::postgres_type_to_dozer_type;
 use postgres_protocol::message::backend::LogicalReplicationMessage::{
-    Begin, Commit, Delete, Insert, Relation, Update,
+    Begin, Commit, Delete, Insert, Relation, Type as TypeMessage, Update,
 };
 use postgres_protocol::message::backend::{
     LogicalReplicationMessage, RelationBody, ReplicaIdentity, TupleData, UpdateBody, XLogDataBody,
@@ -55,9 +55,12 @@ impl Hash for MessageBody<'_> {
     }
 }
 
+/// Microseconds from the Unix epoch to the PostgreSQL epoch, 2000-01-01, which commit timestamps count from.
+const POSTGRES_EPOCH_MICROS: i64 = 946_684_800_000_000;
+
 #[derive(Debug, Clone)]
 pub enum MappedReplicationMessage {
-    Begin,
+    Begin(SourceTransaction),
     Commit(OpIdentifier),
     Operation(Operation),
 }
@@ -65,6 +68,8 @@ pub enum MappedReplicationMessage {
 pub struct XlogMapper {
     relations_map: HashMap<u32, Table>,
     tables_columns: HashMap<u32, Vec<ColumnInfo>>,
+    /// Types that `Type::from_oid` doesn't know, announced before the relations using them.
+    custom_types: HashMap<u32, Type>,
 }
 
 impl Default for XlogMapper {
@@ -78,6 +83,7 @@ impl XlogMapper {
         XlogMapper {
             relations_map: HashMap::<u32, Table>::new(),
             tables_columns,
+            custom_types: HashMap::new(),
         }
     }
 
@@ -104,14 +110,24 @@ impl XlogMapper {
                     }
                 }
             }
+            TypeMessage(typ) => {
+                self.custom_types.insert(
+                    typ.id(),
+                    helper::custom_type(typ.id(), typ.name().unwrap(), typ.namespace().unwrap()),
+                );
+            }
             Commit(commit) => {
                 return Ok(Some(MappedReplicationMessage::Commit(OpIdentifier::new(
                     commit.end_lsn(),
                     0,
                 ))));
             }
-            Begin(_begin) => {
-                return Ok(Some(MappedReplicationMessage::Begin));
+            Begin(begin) => {
+                return Ok(Some(MappedReplicationMessage::Begin(SourceTransaction {
+                    id: Some(begin.xid() as u64),
+                    commit_lsn: Some(begin.final_lsn()),
+                    commit_timestamp: Some(begin.timestamp() + POSTGRES_EPOCH_MICROS),
+                })));
             }
             Insert(insert) => {
                 let table = self.relations_map.get(&insert.rel_id()).unwrap();
@@ -211,7 +227,8 @@ impl XlogMapper {
                 name: String::from(column.name().unwrap()),
                 type_id: column.type_id(),
                 flags: column.flags(),
-                r#type: Type::from_oid(column.type_id() as u32),
+                r#type: Type::from_oid(column.type_id() as u32)
+                    .or_else(|| self.custom_types.get(&(column.type_id() as u32)).cloned()),
                 idx,
             })
             .collect();
@@ -245,6 +262,7 @@ impl XlogMapper {
                 typ,
                 nullable: true,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             });
         }
 
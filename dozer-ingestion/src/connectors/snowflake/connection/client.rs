@@ -3,12 +3,12 @@ use dozer_types::log::debug;
 
 use crate::errors::{ConnectorError, SnowflakeError, SnowflakeSchemaError};
 
-use crate::connectors::snowflake::schema_helper::SchemaHelper;
+use crate::connectors::snowflake::schema_helper::{SchemaHelper, MAX_DECIMAL_PRECISION};
 use crate::connectors::TableInfo;
 use crate::errors::SnowflakeError::{QueryError, SnowflakeStreamError};
 use crate::errors::SnowflakeSchemaError::SchemaConversionError;
 use crate::errors::SnowflakeSchemaError::{
-    DecimalConvertError, InvalidDateError, InvalidTimeError,
+    Decimal128ConvertError, DecimalConvertError, InvalidDateError, InvalidTimeError,
 };
 use crate::errors::SnowflakeStreamError::TimeTravelNotAvailableError;
 use dozer_types::chrono::{NaiveDate, NaiveDateTime, NaiveTime};
@@ -71,6 +71,19 @@ pub fn convert_data(
                     Some(value) => Ok(Field::from(value)),
                 }
             }
+            // Numbers with more digits than `Decimal` are read as text.
+            Some(_) if column_descriptor.column_size > Some(MAX_DECIMAL_PRECISION as u64) => {
+                match cursor
+                    .get_data::<String>(i)
+                    .map_err(|e| SnowflakeSchemaError::ValueConversionError(Box::new(e)))?
+                {
+                    None => Ok(Field::Null),
+                    Some(value) => value
+                        .parse::<DozerDecimal128>()
+                        .map(Field::Decimal128)
+                        .map_err(Decimal128ConvertError),
+                }
+            }
             Some(digits) => {
                 match cursor
                     .get_data::<&[u8]>(i)
@@ -406,7 +419,7 @@ impl Client {
         });
 
         let query = format!(
-            "SELECT TABLE_SCHEMA, TABLE_NAME, COLUMN_NAME, DATA_TYPE, IS_NULLABLE, NUMERIC_SCALE
+            "SELECT TABLE_SCHEMA, TABLE_NAME, COLUMN_NAME, DATA_TYPE, IS_NULLABLE, NUMERIC_SCALE, NUMERIC_PRECISION
             FROM INFORMATION_SCHEMA.COLUMNS
             WHERE TABLE_SCHEMA = 'PUBLIC' {tables_condition}
             ORDER BY TABLE_NAME, ORDINAL_POSITION"
@@ -472,6 +485,11 @@ impl Client {
                     } else {
                         None
                     };
+                    let precision = if let Field::Int(precision) = &row_data.get(6).unwrap() {
+                        Some(*precision)
+                    } else {
+                        None
+                    };
 
                     let schema_id = *tables_indexes.get(&table_name.clone()).unwrap();
 
@@ -488,9 +506,10 @@ impl Client {
                         .fields
                         .push(FieldDefinition {
                             name: field_name.clone(),
-                            typ: SchemaHelper::map_schema_type(type_name, scale)?,
+                            typ: SchemaHelper::map_schema_type(type_name, scale, precision)?,
                             nullable: *nullable,
                             source: SourceDefinition::Dynamic,
+                            metadata: Default::default(),
                         })
                 }
 
@@ -9,6 +9,9 @@ use crate::errors::ConnectorError::TableNotFound;
 use crate::errors::SnowflakeError::ConnectionError;
 use dozer_types::types::{FieldType, SourceSchema};
 
+/// The most digits of a `Decimal`, beyond which numbers are `Decimal128`.
+pub const MAX_DECIMAL_PRECISION: i64 = 28;
+
 pub struct SchemaHelper {}
 
 impl SchemaHelper {
@@ -43,10 +46,15 @@ impl SchemaHelper {
     pub fn map_schema_type(
         type_name: &str,
         scale: Option<i64>,
+        precision: Option<i64>,
     ) -> Result<FieldType, SnowflakeSchemaError> {
         match type_name {
             "NUMBER" => scale.map_or(Ok(FieldType::Int), |scale| {
-                if scale > 0 {
+                if scale > 0
+                    && precision.map_or(false, |precision| precision > MAX_DECIMAL_PRECISION)
+                {
+                    Ok(FieldType::Decimal128)
+                } else if scale > 0 {
                     Ok(FieldType::Decimal)
                 } else {
                     Ok(FieldType::Int)
@@ -236,6 +236,9 @@ pub enum PostgresSchemaError {
     #[error("Point parse failed")]
     PointParseError,
 
+    #[error("Geometry parse failed: {0}")]
+    GeometryParseError(#[source] TypeError),
+
     #[error("Unsupported replication type - '{0}'")]
     UnsupportedReplicationType(String),
 
@@ -288,6 +291,9 @@ pub enum SnowflakeSchemaError {
 
     #[error("Decimal convert error")]
     DecimalConvertError(#[source] rust_decimal::Error),
+
+    #[error("Decimal128 convert error")]
+    Decimal128ConvertError(#[source] TypeError),
 }
 
 #[derive(Error, Debug)]
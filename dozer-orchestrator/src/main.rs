@@ -1,16 +1,15 @@
 use clap::Parser;
 use dozer_orchestrator::cli::generate_config_repl;
 use dozer_orchestrator::cli::types::{ApiCommands, AppCommands, Cli, Commands, ConnectorCommands};
-use dozer_orchestrator::cli::{configure, init_dozer, list_sources, LOGO};
+use dozer_orchestrator::cli::{configure, init_dozer, list_sources, load_config, LOGO};
 use dozer_orchestrator::errors::OrchestrationError;
 use dozer_orchestrator::{set_ctrl_handler, set_panic_hook, Orchestrator};
 
 use dozer_types::log::{error, info};
 
+use std::process;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
-use std::time::Duration;
-use std::{process, thread};
 use tokio::runtime::Runtime;
 
 fn main() {
@@ -29,19 +28,28 @@ fn render_logo() {
 }
 
 fn run() -> Result<(), OrchestrationError> {
-    let _tracing_thread = thread::spawn(|| {
-        let rt = Runtime::new().unwrap();
-        rt.block_on(async {
-            dozer_tracing::init_telemetry(false).unwrap();
-        });
+    let cli = Cli::parse();
+    // Errors in the config are reported when the command loads it.
+    let telemetry_config = load_config(cli.config_path.clone())
+        .ok()
+        .and_then(|config| config.telemetry)
+        .unwrap_or_default();
+    // Spans may be exported on this runtime, so it lives until telemetry is shut down.
+    let telemetry_runtime = Runtime::new().unwrap();
+    telemetry_runtime.block_on(async move {
+        dozer_tracing::init_telemetry(telemetry_config).unwrap();
     });
-    thread::sleep(Duration::from_millis(50));
 
     set_panic_hook();
 
-    let cli = Cli::parse();
     let running = Arc::new(AtomicBool::new(true));
     set_ctrl_handler(running.clone());
+    let result = run_command(cli, running);
+    dozer_tracing::shutdown_telemetry();
+    result
+}
+
+fn run_command(cli: Cli, running: Arc<AtomicBool>) -> Result<(), OrchestrationError> {
     if let Some(cmd) = cli.cmd {
         // run individual servers
         match cmd {
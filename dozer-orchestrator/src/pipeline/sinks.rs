@@ -137,9 +137,11 @@ impl CacheSinkFactory {
                 | FieldType::Float
                 | FieldType::Boolean
                 | FieldType::Decimal
+                | FieldType::Decimal128
                 | FieldType::Timestamp
                 | FieldType::Date
-                | FieldType::Point => vec![IndexDefinition::SortedInverted(vec![idx])],
+                | FieldType::Point
+                | FieldType::Duration => vec![IndexDefinition::SortedInverted(vec![idx])],
 
                 // Create sorted inverted and full text indexes for string fields.
                 FieldType::String => vec![
@@ -152,7 +154,7 @@ impl CacheSinkFactory {
                 FieldType::Text => vec![],
 
                 // Skip creating indexes
-                FieldType::Binary | FieldType::Bson => vec![],
+                FieldType::Binary | FieldType::Bson | FieldType::Geometry => vec![],
             })
             .collect();
         Ok((schema, secondary_indexes))
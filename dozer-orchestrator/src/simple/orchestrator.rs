@@ -4,7 +4,7 @@ use crate::errors::OrchestrationError;
 use crate::pipeline::{CacheSinkSettings, PipelineBuilder};
 use crate::simple::helper::validate_config;
 use crate::utils::{
-    get_api_dir, get_api_security_config, get_app_grpc_config, get_cache_dir,
+    get_api_config, get_api_dir, get_api_security_config, get_app_grpc_config, get_cache_dir,
     get_cache_manager_options, get_executor_options, get_flags, get_grpc_config, get_pipeline_dir,
     get_rest_config,
 };
@@ -15,9 +15,11 @@ use dozer_api::grpc::internal::internal_pipeline_client::InternalPipelineClient;
 use dozer_api::{
     actix_web::dev::ServerHandle,
     grpc::{self, internal::internal_pipeline_server::start_internal_pipeline_server},
+    limits::ApiLimits,
     rest, RoCacheEndpoint,
 };
 use dozer_cache::cache::{CacheManager, LmdbCacheManager};
+use dozer_cache::errors::CacheError;
 use dozer_core::app::AppPipeline;
 use dozer_core::dag_schemas::{DagHaveSchemas, DagSchemas};
 use dozer_core::errors::ExecutionError::InternalError;
@@ -26,6 +28,7 @@ use dozer_core::petgraph::Direction;
 use dozer_core::NodeKind;
 use dozer_sql::pipeline::builder::statement_to_pipeline;
 use dozer_sql::pipeline::errors::PipelineError;
+use dozer_tracing::gauges::{spawn_lmdb_gauges, LmdbGaugeValues};
 use dozer_types::crossbeam::channel::{self, unbounded, Sender};
 use dozer_types::grpc_types::internal::AliasRedirected;
 use dozer_types::log::{info, warn};
@@ -38,6 +41,7 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use std::{sync::Arc, thread};
 use tokio::sync::broadcast::Receiver;
 use tokio::sync::oneshot;
@@ -80,15 +84,50 @@ impl Orchestrator for SimpleOrchestrator {
             // Open `RoCacheEndpoint`s.
             let cache_manager = LmdbCacheManager::new(get_cache_manager_options(&self.config))
                 .map_err(OrchestrationError::CacheInitFailed)?;
+            // The limits are shared by all endpoints.
+            let limits = Arc::new(
+                get_api_config(self.config.clone())
+                    .rate_limit
+                    .as_ref()
+                    .map(ApiLimits::new)
+                    .unwrap_or_default(),
+            );
             let cache_endpoints = self
                 .config
                 .endpoints
                 .iter()
                 .map(|endpoint| {
-                    RoCacheEndpoint::new(&cache_manager, endpoint.clone()).map(Arc::new)
+                    RoCacheEndpoint::new(&cache_manager, endpoint.clone())
+                        .map(|cache_endpoint| Arc::new(cache_endpoint.with_limits(limits.clone())))
                 })
                 .collect::<Result<Vec<_>, _>>()?;
 
+            // Report cache capacity gauges.
+            let lmdb_gauges_interval_secs = self
+                .config
+                .telemetry
+                .clone()
+                .unwrap_or_default()
+                .lmdb_gauges_interval_secs;
+            if lmdb_gauges_interval_secs > 0 {
+                for cache_endpoint in &cache_endpoints {
+                    let cache_endpoint = cache_endpoint.clone();
+                    spawn_lmdb_gauges(
+                        cache_endpoint.endpoint().name.clone(),
+                        Duration::from_secs(lmdb_gauges_interval_secs),
+                        move || {
+                            let stats = cache_endpoint.cache_reader().environment_stats()?;
+                            Ok::<_, CacheError>(LmdbGaugeValues {
+                                map_used_ratio: stats.info.map_used_ratio(),
+                                readers: stats.info.num_readers,
+                                max_readers: stats.info.max_readers,
+                                database_entries: stats.database_entries,
+                            })
+                        },
+                    );
+                }
+            }
+
             // Listen to endpoint redirect events.
             tokio::spawn(redirect_cache_endpoints(
                 Box::new(cache_manager),
@@ -96,18 +135,6 @@ impl Orchestrator for SimpleOrchestrator {
                 alias_redirected_receiver,
             ));
 
-            // Initialize API Server
-            let rest_config = get_rest_config(self.config.to_owned());
-            let security = get_api_security_config(self.config.to_owned());
-            let cache_endpoints_for_rest = cache_endpoints.clone();
-            let rest_handle = tokio::spawn(async move {
-                let api_server = rest::ApiServer::new(rest_config, security);
-                api_server
-                    .run(cache_endpoints_for_rest, tx)
-                    .await
-                    .map_err(OrchestrationError::ApiServerFailed)
-            });
-
             // Initialize `PipelineResponse` events.
             let flags = self.config.flags.clone().unwrap_or_default();
             let operation_receiver = if flags.dynamic {
@@ -121,6 +148,19 @@ impl Orchestrator for SimpleOrchestrator {
                 None
             };
 
+            // Initialize API Server
+            let rest_config = get_rest_config(self.config.to_owned());
+            let security = get_api_security_config(self.config.to_owned());
+            let cache_endpoints_for_rest = cache_endpoints.clone();
+            let operation_receiver_for_rest = operation_receiver.as_ref().map(|r| r.resubscribe());
+            let rest_handle = tokio::spawn(async move {
+                let api_server = rest::ApiServer::new(rest_config, security);
+                api_server
+                    .run(cache_endpoints_for_rest, operation_receiver_for_rest, tx)
+                    .await
+                    .map_err(OrchestrationError::ApiServerFailed)
+            });
+
             // Initialize gRPC Server
             let api_dir = get_api_dir(&self.config);
             let grpc_config = get_grpc_config(self.config.to_owned());
@@ -215,6 +255,11 @@ impl Orchestrator for SimpleOrchestrator {
                         })?;
                         return Ok(token);
                     }
+                    dozer_types::models::api_security::ApiSecurity::ApiKeys(_) => {
+                        return Err(OrchestrationError::GenerateTokenFailed(
+                            "API keys are configured, not generated".to_owned(),
+                        ));
+                    }
                 }
             }
         }
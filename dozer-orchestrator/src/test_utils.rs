@@ -16,12 +16,14 @@ pub fn get_schema() -> Schema {
                 typ: FieldType::Int,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
             FieldDefinition {
                 name: "film_name".to_string(),
                 typ: FieldType::String,
                 nullable: false,
                 source: SourceDefinition::Dynamic,
+                metadata: Default::default(),
             },
         ],
         primary_index: vec![0],
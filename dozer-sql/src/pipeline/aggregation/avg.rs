@@ -1,12 +1,10 @@
 use crate::pipeline::aggregation::aggregator::{update_map, Aggregator};
 use crate::pipeline::errors::PipelineError;
 use crate::pipeline::expression::aggregate::AggregateFunctionType::Avg;
-use crate::{calculate_err_field, calculate_err_type};
 use dozer_core::errors::ExecutionError::InvalidType;
 use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::rust_decimal::Decimal;
 use dozer_types::types::{Field, FieldType};
-use num_traits::FromPrimitive;
 use std::collections::BTreeMap;
 
 #[derive(Debug)]
@@ -49,89 +47,38 @@ fn get_average(
     field_map: &BTreeMap<Field, u64>,
     return_type: Option<FieldType>,
 ) -> Result<Field, PipelineError> {
-    match return_type {
-        Some(FieldType::UInt) => {
-            if field_map.is_empty() {
-                Ok(Field::Decimal(calculate_err_type!(
-                    Decimal::from_f64(0_f64),
-                    Avg,
-                    FieldType::Decimal
-                )))
-            } else {
-                let mut sum =
-                    calculate_err_type!(Decimal::from_f64(0_f64), Avg, FieldType::Decimal);
-                let mut count =
-                    calculate_err_type!(Decimal::from_f64(0_f64), Avg, FieldType::Decimal);
-                for (field, cnt) in field_map {
-                    let cnt = calculate_err_field!(Decimal::from_u64(*cnt), Avg, field);
-                    sum += calculate_err_field!(field.to_decimal(), Avg, field) * cnt;
-                    count += cnt;
-                }
-                Ok(Field::Decimal(sum / count))
-            }
-        }
-        Some(FieldType::Int) => {
-            if field_map.is_empty() {
-                Ok(Field::Decimal(calculate_err_type!(
-                    Decimal::from_f64(0_f64),
-                    Avg,
-                    FieldType::Decimal
-                )))
-            } else {
-                let mut sum =
-                    calculate_err_type!(Decimal::from_f64(0_f64), Avg, FieldType::Decimal);
-                let mut count =
-                    calculate_err_type!(Decimal::from_f64(0_f64), Avg, FieldType::Decimal);
-                for (field, cnt) in field_map {
-                    let cnt = calculate_err_field!(Decimal::from_u64(*cnt), Avg, field);
-                    sum += calculate_err_field!(field.to_decimal(), Avg, field) * cnt;
-                    count += cnt;
-                }
-                Ok(Field::Decimal(sum / count))
-            }
-        }
-        Some(FieldType::Float) => {
-            if field_map.is_empty() {
-                Ok(Field::Float(OrderedFloat::from(0_f64)))
-            } else {
-                let mut sum = 0_f64;
-                let mut count = 0_f64;
-                for (field, cnt) in field_map {
-                    sum += calculate_err_field!(field.to_float(), Avg, field) * (*cnt as f64);
-                    count += *cnt as f64;
-                }
-                Ok(Field::Float(OrderedFloat::from(sum / count)))
-            }
-        }
-        Some(FieldType::Decimal) => {
-            if field_map.is_empty() {
-                Ok(Field::Decimal(calculate_err_type!(
-                    Decimal::from_f64(0_f64),
-                    Avg,
-                    FieldType::Decimal
-                )))
-            } else {
-                let mut sum =
-                    calculate_err_type!(Decimal::from_f64(0_f64), Avg, FieldType::Decimal);
-                let mut count =
-                    calculate_err_type!(Decimal::from_f64(0_f64), Avg, FieldType::Decimal);
-                for (field, cnt) in field_map {
-                    let cnt = calculate_err_field!(Decimal::from_u64(*cnt), Avg, field);
-                    sum += calculate_err_field!(field.to_decimal(), Avg, field) * cnt;
-                    count += cnt;
-                }
-                Ok(Field::Decimal(sum / count))
-            }
+    let zero = match return_type {
+        Some(FieldType::UInt | FieldType::Int | FieldType::Decimal) => {
+            Field::Decimal(Decimal::ZERO)
         }
+        Some(FieldType::Float) => Field::Float(OrderedFloat(0.0)),
         Some(not_supported_return_type) => {
-            Err(PipelineError::InternalExecutionError(InvalidType(format!(
+            return Err(PipelineError::InternalExecutionError(InvalidType(format!(
                 "Not supported return type {} for {}",
                 not_supported_return_type, Avg
             ))))
         }
-        None => Err(PipelineError::InternalExecutionError(InvalidType(format!(
-            "Not supported None return type for {}",
-            Avg
-        )))),
+        None => {
+            return Err(PipelineError::InternalExecutionError(InvalidType(format!(
+                "Not supported None return type for {}",
+                Avg
+            ))))
+        }
+    };
+    if field_map.is_empty() {
+        return Ok(zero);
+    }
+
+    let mut sum = zero.clone();
+    let mut count = zero.clone();
+    for (field, cnt) in field_map {
+        // The count has the type of the average, so integers are multiplied as decimals.
+        let cnt = zero.checked_add(&Field::UInt(*cnt))?;
+        // `Null`s count as zero.
+        if *field != Field::Null {
+            sum = sum.checked_add(&field.checked_mul(&cnt)?)?;
+        }
+        count = count.checked_add(&cnt)?;
     }
+    Ok(sum.checked_div(&count)?)
 }
@@ -1,11 +1,9 @@
 use crate::pipeline::aggregation::aggregator::{update_map, Aggregator};
 use crate::pipeline::errors::PipelineError;
 use crate::pipeline::expression::aggregate::AggregateFunctionType::Max;
-use crate::{calculate_err, calculate_err_field};
 use dozer_core::errors::ExecutionError::InvalidType;
-
-use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::types::{Field, FieldType};
+use std::cmp::Ordering;
 use std::collections::BTreeMap;
 
 #[derive(Debug)]
@@ -48,41 +46,37 @@ fn get_max(
     field_map: &BTreeMap<Field, u64>,
     return_type: Option<FieldType>,
 ) -> Result<Field, PipelineError> {
-    if field_map.is_empty() {
-        Ok(Field::Null)
-    } else {
-        let val = calculate_err!(field_map.keys().max(), Max).clone();
-        match return_type {
-            Some(FieldType::UInt) => Ok(Field::UInt(calculate_err_field!(val.to_uint(), Max, val))),
-            Some(FieldType::Int) => Ok(Field::Int(calculate_err_field!(val.to_int(), Max, val))),
-            Some(FieldType::Float) => Ok(Field::Float(OrderedFloat::from(calculate_err_field!(
-                val.to_float(),
-                Max,
-                val
-            )))),
-            Some(FieldType::Decimal) => Ok(Field::Decimal(calculate_err_field!(
-                val.to_decimal(),
-                Max,
-                val
-            ))),
-            Some(FieldType::Timestamp) => Ok(Field::Timestamp(calculate_err_field!(
-                val.to_timestamp()?,
-                Max,
-                val
-            ))),
-            Some(FieldType::Date) => {
-                Ok(Field::Date(calculate_err_field!(val.to_date()?, Max, val)))
-            }
-            Some(not_supported_return_type) => {
-                Err(PipelineError::InternalExecutionError(InvalidType(format!(
-                    "Not supported return type {} for {}",
-                    not_supported_return_type, Max
-                ))))
+    match return_type {
+        Some(
+            FieldType::UInt
+            | FieldType::Int
+            | FieldType::Float
+            | FieldType::Decimal
+            | FieldType::Timestamp
+            | FieldType::Date
+            | FieldType::Duration,
+        ) => {
+            // The map is ordered by the derived `Ord`, which doesn't compare numbers of different types by value.
+            let mut max: Option<&Field> = None;
+            for field in field_map.keys().filter(|field| **field != Field::Null) {
+                if let Some(current) = max {
+                    if field.checked_cmp(current)? != Some(Ordering::Greater) {
+                        continue;
+                    }
+                }
+                max = Some(field);
             }
-            None => Err(PipelineError::InternalExecutionError(InvalidType(format!(
-                "Not supported None return type for {}",
-                Max
-            )))),
+            Ok(max.cloned().unwrap_or(Field::Null))
+        }
+        Some(not_supported_return_type) => {
+            Err(PipelineError::InternalExecutionError(InvalidType(format!(
+                "Not supported return type {} for {}",
+                not_supported_return_type, Max
+            ))))
         }
+        None => Err(PipelineError::InternalExecutionError(InvalidType(format!(
+            "Not supported None return type for {}",
+            Max
+        )))),
     }
 }
@@ -1,4 +1,3 @@
-use crate::calculate_err_field;
 use crate::pipeline::aggregation::aggregator::Aggregator;
 use crate::pipeline::errors::PipelineError;
 use crate::pipeline::expression::aggregate::AggregateFunctionType::Sum;
@@ -6,31 +5,17 @@ use dozer_core::errors::ExecutionError::InvalidType;
 use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::rust_decimal::Decimal;
 use dozer_types::types::{Field, FieldType};
-use num_traits::FromPrimitive;
 
 #[derive(Debug)]
 pub struct SumAggregator {
-    current_state: SumAggregatorState,
+    current_state: Field,
     return_type: Option<FieldType>,
 }
 
-#[derive(Debug)]
-struct SumAggregatorState {
-    int_state: i64,
-    uint_state: u64,
-    float_state: f64,
-    decimal_state: Decimal,
-}
-
 impl SumAggregator {
     pub fn new() -> Self {
         Self {
-            current_state: SumAggregatorState {
-                int_state: 0_i64,
-                uint_state: 0_u64,
-                float_state: 0_f64,
-                decimal_state: Decimal::from_f64(0_f64).unwrap(),
-            },
+            current_state: Field::Null,
             return_type: None,
         }
     }
@@ -38,6 +23,13 @@ impl SumAggregator {
 
 impl Aggregator for SumAggregator {
     fn init(&mut self, return_type: FieldType) {
+        self.current_state = match return_type {
+            FieldType::UInt => Field::UInt(0),
+            FieldType::Int => Field::Int(0),
+            FieldType::Float => Field::Float(OrderedFloat(0.0)),
+            FieldType::Decimal => Field::Decimal(Decimal::ZERO),
+            _ => Field::Null,
+        };
         self.return_type = Some(return_type);
     }
 
@@ -57,66 +49,21 @@ impl Aggregator for SumAggregator {
 
 fn get_sum(
     fields: &[Field],
-    current_state: &mut SumAggregatorState,
+    current_state: &mut Field,
     return_type: Option<FieldType>,
     decr: bool,
 ) -> Result<Field, PipelineError> {
     match return_type {
-        Some(FieldType::UInt) => {
-            if decr {
-                for field in fields {
-                    let val = calculate_err_field!(field.to_uint(), Sum, field);
-                    current_state.uint_state -= val;
-                }
-            } else {
-                for field in fields {
-                    let val = calculate_err_field!(field.to_uint(), Sum, field);
-                    current_state.uint_state += val;
-                }
-            }
-            Ok(Field::UInt(current_state.uint_state))
-        }
-        Some(FieldType::Int) => {
-            if decr {
-                for field in fields {
-                    let val = calculate_err_field!(field.to_int(), Sum, field);
-                    current_state.int_state -= val;
-                }
-            } else {
-                for field in fields {
-                    let val = calculate_err_field!(field.to_int(), Sum, field);
-                    current_state.int_state += val;
-                }
-            }
-            Ok(Field::Int(current_state.int_state))
-        }
-        Some(FieldType::Float) => {
-            if decr {
-                for field in fields {
-                    let val = calculate_err_field!(field.to_float(), Sum, field);
-                    current_state.float_state -= val;
-                }
-            } else {
-                for field in fields {
-                    let val = calculate_err_field!(field.to_float(), Sum, field);
-                    current_state.float_state += val;
-                }
-            }
-            Ok(Field::Float(OrderedFloat::from(current_state.float_state)))
-        }
-        Some(FieldType::Decimal) => {
-            if decr {
-                for field in fields {
-                    let val = calculate_err_field!(field.to_decimal(), Sum, field);
-                    current_state.decimal_state -= val;
-                }
-            } else {
-                for field in fields {
-                    let val = calculate_err_field!(field.to_decimal(), Sum, field);
-                    current_state.decimal_state += val;
-                }
+        Some(FieldType::UInt | FieldType::Int | FieldType::Float | FieldType::Decimal) => {
+            // `Null`s count as zero.
+            for field in fields.iter().filter(|field| **field != Field::Null) {
+                *current_state = if decr {
+                    current_state.checked_sub(field)?
+                } else {
+                    current_state.checked_add(field)?
+                };
             }
-            Ok(Field::Decimal(current_state.decimal_state))
+            Ok(current_state.clone())
         }
         Some(not_supported_return_type) => {
             Err(PipelineError::InternalExecutionError(InvalidType(format!(
@@ -7,7 +7,10 @@ use crate::pipeline::aggregation::tests::aggregation_tests_utils::{
     FIELD_350_INT, FIELD_350_UINT, FIELD_50_FLOAT, FIELD_50_INT, FIELD_50_UINT, FIELD_NULL, ITALY,
     SINGAPORE,
 };
+use crate::pipeline::errors::PipelineError;
 use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::errors::types::ArithmeticError;
+use dozer_types::types::Field;
 use dozer_types::types::FieldType::{Decimal, Float, Int, UInt};
 use std::collections::HashMap;
 
@@ -716,3 +719,24 @@ fn test_sum_aggregation_decimal_null() {
     exp = vec![delete_exp(ITALY, &get_decimal_field(0))];
     assert_eq!(out, exp);
 }
+
+#[test]
+fn test_sum_aggregation_uint_overflow() {
+    let schema = init_input_schema(UInt, "SUM");
+    let mut processor = init_processor(
+        "SELECT Country, SUM(Salary) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    let max = Field::UInt(u64::MAX);
+    let out = output!(processor, insert_field(ITALY, &max));
+    assert_eq!(out, vec![insert_exp(ITALY, &max)]);
+
+    assert!(matches!(
+        processor.aggregate(insert_field(ITALY, FIELD_100_UINT)),
+        Err(PipelineError::Arithmetic(ArithmeticError::Overflow { .. }))
+    ));
+}
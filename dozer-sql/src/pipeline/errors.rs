@@ -3,7 +3,7 @@
 use dozer_core::errors::ExecutionError;
 use dozer_core::storage::errors::StorageError;
 use dozer_types::errors::internal::BoxedError;
-use dozer_types::errors::types::{DeserializationError, TypeError};
+use dozer_types::errors::types::{ArithmeticError, DeserializationError, TypeError};
 use dozer_types::thiserror;
 use dozer_types::thiserror::Error;
 use dozer_types::types::{Field, FieldType, Record};
@@ -45,6 +45,8 @@ pub enum PipelineError {
     InvalidTypeComparison(Field, Field, String),
     #[error("Invalid types on {0} for {1} operand")]
     InvalidType(Field, String),
+    #[error(transparent)]
+    Arithmetic(#[from] ArithmeticError),
     #[error("Invalid value: {0}")]
     InvalidValue(String),
     #[error("Invalid query: {0}")]
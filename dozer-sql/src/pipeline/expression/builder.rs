@@ -1,10 +1,11 @@
 use dozer_types::{
     ordered_float::OrderedFloat,
-    types::{Field, FieldDefinition, Schema, SourceDefinition},
+    types::{DozerDuration, Field, FieldDefinition, Schema, SourceDefinition},
 };
 use sqlparser::ast::{
-    BinaryOperator as SqlBinaryOperator, DataType, Expr as SqlExpr, Expr, Function, FunctionArg,
-    FunctionArgExpr, Ident, TrimWhereField, UnaryOperator as SqlUnaryOperator, Value as SqlValue,
+    BinaryOperator as SqlBinaryOperator, DataType, DateTimeField, Expr as SqlExpr, Expr, Function,
+    FunctionArg, FunctionArgExpr, Ident, TrimWhereField, UnaryOperator as SqlUnaryOperator,
+    Value as SqlValue,
 };
 
 use crate::pipeline::errors::PipelineError::{
@@ -108,6 +109,11 @@ impl ExpressionBuilder {
             SqlExpr::Cast { expr, data_type } => {
                 self.parse_sql_cast_operator(parse_aggregations, expr, data_type, schema)
             }
+            SqlExpr::Interval {
+                value,
+                leading_field,
+                ..
+            } => Self::parse_sql_interval(value, leading_field),
             _ => Err(InvalidExpression(format!("{expression:?}"))),
         }
     }
@@ -451,6 +457,7 @@ impl ExpressionBuilder {
             DataType::Boolean => CastOperatorType::Boolean,
             DataType::Date => CastOperatorType::Date,
             DataType::Timestamp(..) => CastOperatorType::Timestamp,
+            DataType::Interval => CastOperatorType::Duration,
             DataType::Text => CastOperatorType::Text,
             DataType::String => CastOperatorType::String,
             DataType::Custom(name, ..) => {
@@ -472,6 +479,36 @@ impl ExpressionBuilder {
         })
     }
 
+    /// Parses `INTERVAL '1 hour 30 minutes'` and `INTERVAL '90' MINUTE` into duration literals.
+    fn parse_sql_interval(
+        value: &Expr,
+        leading_field: &Option<DateTimeField>,
+    ) -> Result<Expression, PipelineError> {
+        let value = match value {
+            SqlExpr::Value(SqlValue::SingleQuotedString(s) | SqlValue::Number(s, _)) => s,
+            _ => return Err(InvalidExpression(format!("INTERVAL {value}"))),
+        };
+        let value = match leading_field {
+            None => value.clone(),
+            Some(
+                field @ (DateTimeField::Week
+                | DateTimeField::Day
+                | DateTimeField::Hour
+                | DateTimeField::Minute
+                | DateTimeField::Second),
+            ) => format!("{value} {field}"),
+            Some(field) => {
+                return Err(InvalidExpression(format!(
+                    "INTERVAL {value} {field} is not a fixed duration"
+                )))
+            }
+        };
+        value
+            .parse::<DozerDuration>()
+            .map(|duration| Expression::Literal(Field::Duration(duration)))
+            .map_err(|_| InvalidValue(value))
+    }
+
     fn parse_sql_string(s: &str) -> Result<Expression, PipelineError> {
         Ok(Expression::Literal(Field::String(s.to_owned())))
     }
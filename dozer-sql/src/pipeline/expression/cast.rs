@@ -21,6 +21,7 @@ pub enum CastOperatorType {
     Decimal,
     Timestamp,
     Date,
+    Duration,
     Bson,
 }
 
@@ -37,6 +38,7 @@ impl Display for CastOperatorType {
             CastOperatorType::Decimal => f.write_str("CAST AS DECIMAL"),
             CastOperatorType::Timestamp => f.write_str("CAST AS TIMESTAMP"),
             CastOperatorType::Date => f.write_str("CAST AS DATE"),
+            CastOperatorType::Duration => f.write_str("CAST AS INTERVAL"),
             CastOperatorType::Bson => f.write_str("CAST AS BSON"),
         }
     }
@@ -151,6 +153,16 @@ impl CastOperatorType {
                     })
                 }
             }
+            CastOperatorType::Duration => {
+                if let Some(value) = field.to_duration()? {
+                    Ok(Field::Duration(value))
+                } else {
+                    Err(PipelineError::InvalidCast {
+                        from: field,
+                        to: FieldType::Duration,
+                    })
+                }
+            }
             CastOperatorType::Bson => {
                 if let Some(value) = field.to_bson() {
                     Ok(Field::Bson(value.to_vec()))
@@ -209,6 +221,7 @@ impl CastOperatorType {
                     FieldType::String,
                     FieldType::Text,
                     FieldType::Timestamp,
+                    FieldType::Duration,
                     FieldType::UInt,
                 ],
                 FieldType::String,
@@ -224,6 +237,7 @@ impl CastOperatorType {
                     FieldType::String,
                     FieldType::Text,
                     FieldType::Timestamp,
+                    FieldType::Duration,
                     FieldType::UInt,
                 ],
                 FieldType::Text,
@@ -244,6 +258,10 @@ impl CastOperatorType {
                 FieldType::Timestamp,
             ),
             CastOperatorType::Date => (vec![FieldType::Date, FieldType::String], FieldType::Date),
+            CastOperatorType::Duration => (
+                vec![FieldType::Duration, FieldType::String],
+                FieldType::Duration,
+            ),
             CastOperatorType::Bson => (vec![FieldType::Bson], FieldType::Bson),
         };
 
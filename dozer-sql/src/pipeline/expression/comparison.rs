@@ -1,11 +1,7 @@
 use crate::pipeline::errors::PipelineError;
-use dozer_types::rust_decimal::Decimal;
 use dozer_types::types::Schema;
-use dozer_types::{
-    ordered_float::OrderedFloat,
-    types::{Field, Record},
-};
-use num_traits::cast::*;
+use dozer_types::types::{Field, Record};
+use std::cmp::Ordering;
 
 use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
 
@@ -20,358 +16,38 @@ macro_rules! define_comparison {
             let left_p = left.evaluate(&record, schema)?;
             let right_p = right.evaluate(&record, schema)?;
 
-            match left_p {
-                Field::Null => match right_p {
-                    // left: Null, right: Null
-                    Field::Null => Ok(Field::Boolean(true)),
-                    _ => Ok(Field::Boolean(false)),
-                },
-                Field::Boolean(left_v) => match right_p {
-                    // left: Bool, right: Bool
-                    Field::Boolean(right_v) => Ok(Field::Boolean($function(left_v, right_v))),
-                    _ => Ok(Field::Boolean(false)),
-                },
-                Field::Int(left_v) => match right_p {
-                    // left: Int, right: Int
-                    Field::Int(right_v) => Ok(Field::Boolean($function(left_v, right_v))),
-                    // left: Int, right: UInt
-                    Field::UInt(right_v) => Ok(Field::Boolean($function(
-                        left_v,
-                        right_v.to_i64().ok_or(PipelineError::UnableToCast(
-                            format!("{}", right_v),
-                            "i64".to_string(),
-                        ))?,
-                    ))),
-                    // left: Int, right: Float
-                    Field::Float(right_v) => {
-                        let left_v_f = OrderedFloat::<f64>::from_i64(left_v).unwrap();
-                        Ok(Field::Boolean($function(left_v_f, right_v)))
-                    }
-                    // left: Int, right: Decimal
-                    Field::Decimal(right_v) => {
-                        let left_v_d =
-                            Decimal::from_i64(left_v).ok_or(PipelineError::UnableToCast(
-                                format!("{}", left_v),
-                                "Decimal".to_string(),
-                            ))?;
-                        Ok(Field::Boolean($function(left_v_d, right_v)))
-                    }
-                    // left: Int, right: Null
-                    Field::Null => Ok(Field::Boolean(false)),
-                    _ => Err(PipelineError::InvalidTypeComparison(
-                        left_p,
-                        right_p,
-                        $op.to_string(),
-                    )),
-                },
-                Field::UInt(left_v) => match right_p {
-                    // left: UInt, right: Int
-                    Field::Int(right_v) => Ok(Field::Boolean($function(
-                        left_v.to_i64().ok_or(PipelineError::UnableToCast(
-                            format!("{}", left_v),
-                            "i64".to_string(),
-                        ))?,
-                        right_v,
-                    ))),
-                    // left: UInt, right: UInt
-                    Field::UInt(right_v) => Ok(Field::Boolean($function(left_v, right_v))),
-                    // left: UInt, right: Float
-                    Field::Float(right_v) => {
-                        let left_v_f = OrderedFloat::<f64>::from_i64(left_v.to_i64().ok_or(
-                            PipelineError::UnableToCast(format!("{}", left_v), "i64".to_string()),
-                        )?)
-                        .unwrap();
-                        Ok(Field::Boolean($function(left_v_f, right_v)))
-                    }
-                    // left: UInt, right: Decimal
-                    Field::Decimal(right_v) => {
-                        let left_v_d = Decimal::from_i64(left_v.to_i64().ok_or(
-                            PipelineError::UnableToCast(format!("{}", left_v), "i64".to_string()),
-                        )?)
-                        .ok_or(PipelineError::UnableToCast(
-                            format!("{}", left_v),
-                            "Decimal".to_string(),
-                        ))?;
-                        Ok(Field::Boolean($function(left_v_d, right_v)))
-                    }
-                    // left: UInt, right: Null
-                    Field::Null => Ok(Field::Boolean(false)),
-                    _ => Err(PipelineError::InvalidTypeComparison(
-                        left_p,
-                        right_p,
-                        $op.to_string(),
-                    )),
-                },
-                Field::Float(left_v) => match right_p {
-                    // left: Float, right: Float
-                    Field::Float(right_v) => Ok(Field::Boolean($function(left_v, right_v))),
-                    // left: Float, right: Int
-                    Field::UInt(right_v) => {
-                        let right_v_f = OrderedFloat::<f64>::from_i64(right_v.to_i64().ok_or(
-                            PipelineError::UnableToCast(
-                                format!("{}", right_v),
-                                "Decimal".to_string(),
-                            ),
-                        )?)
-                        .unwrap();
-                        Ok(Field::Boolean($function(left_v, right_v_f)))
-                    }
-                    // left: Float, right: Int
-                    Field::Int(right_v) => {
-                        let right_v_f = OrderedFloat::<f64>::from_i64(right_v).unwrap();
-                        Ok(Field::Boolean($function(left_v, right_v_f)))
-                    }
-                    // left: Float, right: Decimal
-                    Field::Decimal(right_v) => {
-                        let left_v_d = Decimal::from_f64(left_v.to_f64().ok_or(
-                            PipelineError::UnableToCast(format!("{}", left_v), "i64".to_string()),
-                        )?)
-                        .ok_or(PipelineError::UnableToCast(
-                            format!("{}", left_v),
-                            "Decimal".to_string(),
-                        ))?;
-                        Ok(Field::Boolean($function(left_v_d, right_v)))
-                    }
-                    // left: Float, right: Null
-                    Field::Null => Ok(Field::Boolean(false)),
-                    _ => Err(PipelineError::InvalidTypeComparison(
-                        left_p,
-                        right_p,
-                        $op.to_string(),
-                    )),
-                },
-                Field::Decimal(left_v) => match right_p {
-                    // left: Decimal, right: Float
-                    Field::Float(right_v) => {
-                        let right_v_d = Decimal::from_f64(right_v.to_f64().ok_or(
-                            PipelineError::UnableToCast(format!("{}", right_v), "f64".to_string()),
-                        )?)
-                        .ok_or(PipelineError::UnableToCast(
-                            format!("{}", right_v),
-                            "Decimal".to_string(),
-                        ))?;
-                        Ok(Field::Boolean($function(left_v, right_v_d)))
-                    }
-                    // left: Decimal, right: Int
-                    Field::Int(right_v) => {
-                        let right_v_d =
-                            Decimal::from_i64(right_v).ok_or(PipelineError::UnableToCast(
-                                format!("{}", right_v),
-                                "Decimal".to_string(),
-                            ))?;
-                        Ok(Field::Boolean($function(left_v, right_v_d)))
-                    }
-                    // left: Decimal, right: UInt
-                    Field::UInt(right_v) => {
-                        let right_v_d = Decimal::from_i64(right_v.to_i64().ok_or(
-                            PipelineError::UnableToCast(format!("{}", right_v), "i64".to_string()),
-                        )?)
-                        .ok_or(PipelineError::UnableToCast(
-                            format!("{}", right_v),
-                            "Decimal".to_string(),
-                        ))?;
-                        Ok(Field::Boolean($function(left_v, right_v_d)))
-                    }
-                    // left: Decimal, right: Decimal
-                    Field::Decimal(right_v) => Ok(Field::Boolean($function(left_v, right_v))),
-                    // left: Decimal, right: Null
-                    Field::Null => Ok(Field::Boolean(false)),
-                    _ => Err(PipelineError::InvalidTypeComparison(
+            match (&left_p, &right_p) {
+                (Field::Null, Field::Null) => Ok(Field::Boolean(true)),
+                (Field::Null, _) | (_, Field::Null) => Ok(Field::Boolean(false)),
+                (Field::Boolean(_), right) if !matches!(right, Field::Boolean(_)) => {
+                    Ok(Field::Boolean(false))
+                }
+                _ => match left_p.checked_cmp(&right_p) {
+                    Ok(Some(ordering)) => Ok(Field::Boolean($function(ordering, Ordering::Equal))),
+                    Ok(None) | Err(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
                         $op.to_string(),
                     )),
                 },
-                Field::String(ref left_v) => match right_p {
-                    Field::String(ref right_v) => Ok(Field::Boolean($function(left_v, right_v))),
-                    Field::Null => Ok(Field::Boolean(false)),
-                    _ => Err(PipelineError::InvalidTypeComparison(
-                        left_p,
-                        right_p,
-                        $op.to_string(),
-                    )),
-                },
-                Field::Timestamp(left_v) => match right_p {
-                    Field::Timestamp(right_v) => Ok(Field::Boolean($function(left_v, right_v))),
-                    Field::Null => Ok(Field::Boolean(false)),
-                    _ => Err(PipelineError::InvalidTypeComparison(
-                        left_p,
-                        right_p,
-                        $op.to_string(),
-                    )),
-                },
-                Field::Binary(ref _left_v) => Err(PipelineError::InvalidTypeComparison(
-                    left_p,
-                    right_p,
-                    $op.to_string(),
-                )),
-                _ => Err(PipelineError::InvalidTypeComparison(
-                    left_p,
-                    right_p,
-                    $op.to_string(),
-                )),
             }
         }
     };
 }
 
-pub fn evaluate_lt(
-    schema: &Schema,
-    left: &Expression,
-    right: &Expression,
-    record: &Record,
-) -> Result<Field, PipelineError> {
-    let left_p = left.evaluate(record, schema)?;
-    let right_p = right.evaluate(record, schema)?;
-
-    match left_p {
-        Field::Null => match right_p {
-            Field::Null => Ok(Field::Boolean(true)),
-            _ => Ok(Field::Boolean(false)),
-        },
-        Field::Boolean(left_v) => match right_p {
-            Field::Boolean(right_v) => Ok(Field::Boolean(!left_v & right_v)),
-            _ => Ok(Field::Boolean(false)),
-        },
-        Field::Int(left_v) => match right_p {
-            Field::Int(right_v) => Ok(Field::Boolean(left_v < right_v)),
-            Field::Float(right_v) => {
-                let left_v_f = OrderedFloat::<f64>::from_i64(left_v).unwrap();
-                Ok(Field::Boolean(left_v_f < right_v))
-            }
-            Field::Null => Ok(Field::Boolean(false)),
-            _ => Err(PipelineError::InvalidTypeComparison(
-                left_p,
-                right_p,
-                "<".to_string(),
-            )),
-        },
-        Field::Float(left_v) => match right_p {
-            Field::Float(right_v) => Ok(Field::Boolean(left_v < right_v)),
-            Field::Int(right_v) => {
-                let right_v_f = OrderedFloat::<f64>::from_i64(right_v).unwrap();
-                Ok(Field::Boolean(left_v < right_v_f))
-            }
-            Field::Null => Ok(Field::Boolean(false)),
-            _ => Err(PipelineError::InvalidTypeComparison(
-                left_p,
-                right_p,
-                "<".to_string(),
-            )),
-        },
-        Field::String(ref left_v) => match right_p {
-            Field::String(ref right_v) => Ok(Field::Boolean(left_v < right_v)),
-            Field::Null => Ok(Field::Boolean(false)),
-            _ => Err(PipelineError::InvalidTypeComparison(
-                left_p,
-                right_p,
-                "<".to_string(),
-            )),
-        },
-        Field::Timestamp(left_v) => match right_p {
-            Field::Timestamp(right_v) => Ok(Field::Boolean(left_v < right_v)),
-            Field::Null => Ok(Field::Boolean(false)),
-            _ => Err(PipelineError::InvalidTypeComparison(
-                left_p,
-                right_p,
-                "<".to_string(),
-            )),
-        },
-        Field::Binary(ref _left_v) => Err(PipelineError::InvalidTypeComparison(
-            left_p,
-            right_p,
-            "<".to_string(),
-        )),
-        _ => Err(PipelineError::InvalidTypeComparison(
-            left_p,
-            right_p,
-            "<".to_string(),
-        )),
-    }
-}
-
-pub fn evaluate_gt(
-    schema: &Schema,
-    left: &Expression,
-    right: &Expression,
-    record: &Record,
-) -> Result<Field, PipelineError> {
-    let left_p = left.evaluate(record, schema)?;
-    let right_p = right.evaluate(record, schema)?;
-
-    match left_p {
-        Field::Null => match right_p {
-            Field::Null => Ok(Field::Boolean(true)),
-            _ => Ok(Field::Boolean(false)),
-        },
-        Field::Boolean(left_v) => match right_p {
-            Field::Boolean(right_v) => Ok(Field::Boolean(left_v & !right_v)),
-            _ => Ok(Field::Boolean(false)),
-        },
-        Field::Int(left_v) => match right_p {
-            Field::Int(right_v) => Ok(Field::Boolean(left_v > right_v)),
-            Field::Float(right_v) => {
-                let left_v_f = OrderedFloat::<f64>::from_i64(left_v).unwrap();
-                Ok(Field::Boolean(left_v_f > right_v))
-            }
-            Field::Null => Ok(Field::Boolean(false)),
-            _ => Err(PipelineError::InvalidTypeComparison(
-                left_p,
-                right_p,
-                ">".to_string(),
-            )),
-        },
-        Field::Float(left_v) => match right_p {
-            Field::Float(right_v) => Ok(Field::Boolean(left_v > right_v)),
-            Field::Int(right_v) => {
-                let right_v_f = OrderedFloat::<f64>::from_i64(right_v).unwrap();
-                Ok(Field::Boolean(left_v > right_v_f))
-            }
-            Field::Null => Ok(Field::Boolean(false)),
-            _ => Err(PipelineError::InvalidTypeComparison(
-                left_p,
-                right_p,
-                ">".to_string(),
-            )),
-        },
-        Field::String(ref left_v) => match right_p {
-            Field::String(ref right_v) => Ok(Field::Boolean(left_v > right_v)),
-            Field::Null => Ok(Field::Boolean(false)),
-            _ => Err(PipelineError::InvalidTypeComparison(
-                left_p.clone(),
-                right_p,
-                ">".to_string(),
-            )),
-        },
-        Field::Timestamp(left_v) => match right_p {
-            Field::Timestamp(right_v) => Ok(Field::Boolean(left_v > right_v)),
-            Field::Null => Ok(Field::Boolean(false)),
-            _ => Err(PipelineError::InvalidTypeComparison(
-                left_p,
-                right_p,
-                ">".to_string(),
-            )),
-        },
-        Field::Binary(ref _left_v) => Err(PipelineError::InvalidTypeComparison(
-            left_p.clone(),
-            right_p,
-            ">".to_string(),
-        )),
-        _ => Err(PipelineError::InvalidTypeComparison(
-            left_p,
-            right_p,
-            ">".to_string(),
-        )),
-    }
-}
-
 define_comparison!(evaluate_eq, "=", |l, r| { l == r });
 define_comparison!(evaluate_ne, "!=", |l, r| { l != r });
+define_comparison!(evaluate_lt, "<", |l, r| { l < r });
+define_comparison!(evaluate_gt, ">", |l, r| { l > r });
 define_comparison!(evaluate_lte, "<=", |l, r| { l <= r });
 define_comparison!(evaluate_gte, ">=", |l, r| { l >= r });
 
 #[cfg(test)]
 use crate::pipeline::expression::execution::Expression::Literal;
+#[cfg(test)]
+use dozer_types::{ordered_float::OrderedFloat, rust_decimal::Decimal};
+#[cfg(test)]
+use num_traits::FromPrimitive;
 
 #[test]
 fn test_float_float_eq() {
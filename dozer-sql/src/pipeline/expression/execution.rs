@@ -322,6 +322,9 @@ fn get_field_type(field: &Field) -> Option<FieldType> {
         Field::Text(_) => Some(FieldType::Text),
         Field::Date(_) => Some(FieldType::Date),
         Field::Point(_) => Some(FieldType::Point),
+        Field::Duration(_) => Some(FieldType::Duration),
+        Field::Geometry(_) => Some(FieldType::Geometry),
+        Field::Decimal128(_) => Some(FieldType::Decimal128),
     }
 }
 
@@ -406,6 +409,19 @@ fn get_binary_operator_type(
                     SourceDefinition::Dynamic,
                     false,
                 )),
+                (FieldType::Timestamp, FieldType::Duration)
+                | (FieldType::Duration, FieldType::Timestamp) => Ok(ExpressionType::new(
+                    FieldType::Timestamp,
+                    false,
+                    SourceDefinition::Dynamic,
+                    false,
+                )),
+                (FieldType::Duration, FieldType::Duration) => Ok(ExpressionType::new(
+                    FieldType::Duration,
+                    false,
+                    SourceDefinition::Dynamic,
+                    false,
+                )),
                 (FieldType::Int, FieldType::Float)
                 | (FieldType::Float, FieldType::Int)
                 | (FieldType::Float, FieldType::Float) => Ok(ExpressionType::new(
@@ -11,7 +11,6 @@ use dozer_types::types::{Field, FieldType, Record, Schema};
 use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
 use crate::pipeline::expression::geo::common::GeoFunctionType;
 use dozer_types::geo::GeodesicDistance;
-use dozer_types::geo::HaversineDistance;
 use dozer_types::geo::VincentyDistance;
 
 use dozer_types::ordered_float::OrderedFloat;
@@ -106,7 +105,7 @@ pub(crate) fn evaluate_distance(
 
         let distance: OrderedFloat<f64> = match calculation_type {
             Algorithm::Geodesic => Ok(from.geodesic_distance(to)),
-            Algorithm::Haversine => Ok(from.0.haversine_distance(&to.0)),
+            Algorithm::Haversine => Ok(OrderedFloat(from.haversine_distance(to))),
             Algorithm::Vincenty => from
                 .0
                 .vincenty_distance(&to.0)
@@ -55,9 +55,12 @@ pub fn evaluate_py_udf(
             FieldType::Text => Field::Text(res.extract::<String>()?),
             FieldType::Binary => Field::Binary(res.extract::<Vec<u8>>()?),
             FieldType::Decimal
+            | FieldType::Decimal128
             | FieldType::Date
             | FieldType::Timestamp
             | FieldType::Point
+            | FieldType::Duration
+            | FieldType::Geometry
             | FieldType::Bson => {
                 return Err(UnsupportedSqlError(GenericError(
                     "Unsupported return type for python udf".to_string(),
@@ -1,6 +1,8 @@
 use crate::pipeline::expression::scalar::tests::scalar_common::run_scalar_fct;
 use dozer_types::chrono::{DateTime, NaiveDate};
-use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+use dozer_types::types::{
+    DozerDuration, Field, FieldDefinition, FieldType, Schema, SourceDefinition,
+};
 
 #[test]
 fn test_date() {
@@ -76,6 +78,64 @@ fn test_timestamp_diff() {
     assert_eq!(f, Field::Int(1000));
 }
 
+#[test]
+fn test_timestamp_add_interval() {
+    let schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("ts"),
+                FieldType::Timestamp,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+    let ts = Field::Timestamp(DateTime::parse_from_rfc3339("2023-01-02T00:12:10Z").unwrap());
+
+    let f = run_scalar_fct(
+        "SELECT ts + INTERVAL '1 day 2 hours' FROM users",
+        schema.clone(),
+        vec![ts.clone()],
+    );
+    assert_eq!(
+        f,
+        Field::Timestamp(DateTime::parse_from_rfc3339("2023-01-03T02:12:10Z").unwrap())
+    );
+
+    let f = run_scalar_fct(
+        "SELECT ts - INTERVAL '10' SECOND FROM users",
+        schema,
+        vec![ts],
+    );
+    assert_eq!(
+        f,
+        Field::Timestamp(DateTime::parse_from_rfc3339("2023-01-02T00:12:00Z").unwrap())
+    );
+}
+
+#[test]
+fn test_interval_arithmetic() {
+    let schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("d"),
+                FieldType::Duration,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+
+    let f = run_scalar_fct(
+        "SELECT d + INTERVAL '30' MINUTE FROM users",
+        schema,
+        vec![Field::Duration(DozerDuration::from_secs(3600).unwrap())],
+    );
+    assert_eq!(f, Field::Duration(DozerDuration::from_secs(5400).unwrap()));
+}
+
 // #[test]
 // fn test_timestamp_add() {
 //     let f = run_scalar_fct(
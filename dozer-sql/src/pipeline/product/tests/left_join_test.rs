@@ -512,7 +512,7 @@ impl Sink for TestSink {
 #[test]
 #[ignore]
 fn test_pipeline_builder() {
-    dozer_tracing::init_telemetry(false).unwrap();
+    dozer_tracing::init_telemetry(Default::default()).unwrap();
 
     let mut pipeline = AppPipeline::new();
 
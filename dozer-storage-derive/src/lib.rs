@@ -0,0 +1,105 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, DeriveInput, Generics, Meta, NestedMeta, WherePredicate,
+};
+
+/// Derives bincode backed `Encode` and `Decode` for a type that implements `Serialize`, `Deserialize` and `Clone`.
+///
+/// With `#[lmdb_val(key)]`, `LmdbKey` is derived too, with `LmdbValType::VariableSize`.
+/// Note that bincode encoding doesn't preserve order, so such keys are only useful for exact lookups.
+///
+/// The generated code refers to `dozer_types::bincode`, so the deriving crate must depend on `dozer-types`.
+#[proc_macro_derive(LmdbVal, attributes(lmdb_val))]
+pub fn derive_lmdb_val(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match lmdb_val(input) {
+        Ok(output) => output.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn lmdb_val(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let is_key = is_key(&input)?;
+
+    let name = &input.ident;
+    let type_name = name.to_string();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let encode_generics = with_predicate(
+        &input.generics,
+        parse_quote!(Self: ::dozer_types::serde::Serialize),
+    );
+    let (_, _, encode_where_clause) = encode_generics.split_for_impl();
+    let decode_generics = with_predicate(
+        &input.generics,
+        parse_quote!(Self: ::std::clone::Clone + ::dozer_types::serde::de::DeserializeOwned),
+    );
+    let (_, _, decode_where_clause) = decode_generics.split_for_impl();
+
+    let mut output = quote! {
+        impl #impl_generics ::dozer_storage::Encode for #name #ty_generics #encode_where_clause {
+            fn encode(&self) -> Result<::dozer_storage::Encoded, ::dozer_storage::errors::StorageError> {
+                ::dozer_types::bincode::serialize(self)
+                    .map(::dozer_storage::Encoded::Vec)
+                    .map_err(|e| ::dozer_storage::errors::StorageError::SerializationError {
+                        typ: #type_name,
+                        reason: Box::new(e),
+                    })
+            }
+        }
+
+        impl #impl_generics ::dozer_storage::Decode for #name #ty_generics #decode_where_clause {
+            fn decode(bytes: &[u8]) -> Result<::std::borrow::Cow<Self>, ::dozer_storage::errors::StorageError> {
+                ::dozer_types::bincode::deserialize(bytes)
+                    .map(::std::borrow::Cow::Owned)
+                    .map_err(|e| ::dozer_storage::errors::StorageError::DeserializationError {
+                        typ: #type_name,
+                        reason: Box::new(e),
+                    })
+            }
+        }
+    };
+
+    if is_key {
+        output.extend(quote! {
+            unsafe impl #impl_generics ::dozer_storage::LmdbKey for #name #ty_generics #where_clause {
+                const TYPE: ::dozer_storage::LmdbValType = ::dozer_storage::LmdbValType::VariableSize;
+            }
+        });
+    }
+
+    Ok(output)
+}
+
+fn with_predicate(generics: &Generics, predicate: WherePredicate) -> Generics {
+    let mut generics = generics.clone();
+    generics.make_where_clause().predicates.push(predicate);
+    generics
+}
+
+fn is_key(input: &DeriveInput) -> syn::Result<bool> {
+    let mut is_key = false;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("lmdb_val"))
+    {
+        let Meta::List(list) = attr.parse_meta()? else {
+            return Err(syn::Error::new_spanned(attr, "expected `#[lmdb_val(...)]`"));
+        };
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("key") => is_key = true,
+                nested => {
+                    return Err(syn::Error::new_spanned(
+                        nested,
+                        "unknown `lmdb_val` option, expected `key`",
+                    ))
+                }
+            }
+        }
+    }
+    Ok(is_key)
+}
@@ -0,0 +1,110 @@
+use std::{borrow::Borrow, sync::Arc};
+
+use lmdb::RoTransaction;
+
+use crate::{
+    errors::StorageError, lmdb_storage::LmdbEnvironmentManager, LmdbKey, LmdbMap, LmdbValue,
+};
+
+/// An async facade over a `LmdbMap` for use from tokio tasks.
+///
+/// Every read is executed in a fresh read transaction on tokio's blocking thread pool,
+/// so page faults on cold pages don't block the runtime's worker threads.
+#[derive(Debug)]
+pub struct AsyncLmdbMap<K: ?Sized, V: ?Sized> {
+    env: Arc<LmdbEnvironmentManager>,
+    map: LmdbMap<K, V>,
+}
+
+impl<K: ?Sized, V: ?Sized> Clone for AsyncLmdbMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            env: self.env.clone(),
+            map: self.map,
+        }
+    }
+}
+
+impl<K: LmdbKey + ?Sized + 'static, V: LmdbValue + ?Sized + 'static> AsyncLmdbMap<K, V> {
+    pub fn new(env: Arc<LmdbEnvironmentManager>, map: LmdbMap<K, V>) -> Self {
+        Self { env, map }
+    }
+
+    pub fn map(&self) -> LmdbMap<K, V> {
+        self.map
+    }
+
+    /// Runs `f` with a read transaction on the blocking thread pool.
+    ///
+    /// Panics in `f` are propagated to the caller.
+    pub async fn read<R: Send + 'static>(
+        &self,
+        f: impl FnOnce(&RoTransaction, LmdbMap<K, V>) -> Result<R, StorageError> + Send + 'static,
+    ) -> Result<R, StorageError> {
+        let env = self.env.clone();
+        let map = self.map;
+        let handle = tokio::task::spawn_blocking(move || {
+            let txn = env.begin_ro_txn()?;
+            f(&txn, map)
+        });
+        match handle.await {
+            Ok(result) => result,
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+
+    pub async fn count(&self) -> Result<usize, StorageError> {
+        self.read(|txn, map| map.count(txn)).await
+    }
+
+    pub async fn get(&self, key: K::Owned) -> Result<Option<V::Owned>, StorageError>
+    where
+        K: ToOwned,
+        K::Owned: Send + 'static,
+        V::Owned: Send + 'static,
+    {
+        self.read(move |txn, map| Ok(map.get(txn, key.borrow())?.map(|value| value.into_owned())))
+            .await
+    }
+
+    pub async fn contains_key(&self, key: K::Owned) -> Result<bool, StorageError>
+    where
+        K: ToOwned,
+        K::Owned: Send + 'static,
+    {
+        self.read(move |txn, map| map.contains(txn, key.borrow()))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lmdb::Transaction;
+    use tempdir::TempDir;
+
+    use crate::lmdb_storage::LmdbEnvironmentOptions;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_async_lmdb_map() {
+        let temp_dir = TempDir::new("test_async_lmdb_map").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let map = LmdbMap::<str, u64>::new_from_env(&mut env, None, true).unwrap();
+        let mut txn = env.begin_rw_txn().unwrap();
+        map.extend(&mut txn, [("a", &1), ("b", &2)]).unwrap();
+        txn.commit().unwrap();
+
+        let map = AsyncLmdbMap::new(Arc::new(env), map);
+        assert_eq!(map.count().await.unwrap(), 2);
+        assert_eq!(map.get("a".to_string()).await.unwrap(), Some(1));
+        assert_eq!(map.get("c".to_string()).await.unwrap(), None);
+        assert!(map.contains_key("b".to_string()).await.unwrap());
+        assert!(!map.contains_key("c".to_string()).await.unwrap());
+    }
+}
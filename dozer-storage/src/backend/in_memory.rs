@@ -0,0 +1,296 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap},
+};
+
+use crate::errors::StorageError;
+
+use super::{
+    DatabaseLayout, DupIterator, EntryIterator, ReadTransaction, StorageBackend, WriteTransaction,
+};
+
+/// A `StorageBackend` that keeps all data in `BTreeMap`s.
+///
+/// Write transactions change the tables in place and record how to undo every change, which is replayed in reverse
+/// if the transaction is dropped without committing.
+#[derive(Debug, Default)]
+pub struct InMemoryBackend {
+    names: HashMap<Option<String>, InMemoryDatabase>,
+    tables: Vec<Table>,
+}
+
+impl InMemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InMemoryDatabase(usize);
+
+impl StorageBackend for InMemoryBackend {
+    type Database = InMemoryDatabase;
+    type ReadTransaction<'a> = InMemoryReadTransaction<'a>;
+    type WriteTransaction<'a> = InMemoryWriteTransaction<'a>;
+
+    fn create_database(
+        &mut self,
+        name: Option<&str>,
+        layout: Option<DatabaseLayout>,
+    ) -> Result<InMemoryDatabase, StorageError> {
+        let name = name.map(ToString::to_string);
+        if let Some(db) = self.names.get(&name) {
+            return Ok(*db);
+        }
+        let Some(layout) = layout else {
+            return Err(StorageError::DatabaseNotFound(name));
+        };
+        let db = InMemoryDatabase(self.tables.len());
+        self.tables.push(Table::new(layout));
+        self.names.insert(name, db);
+        Ok(db)
+    }
+
+    fn begin_read(&self) -> Result<InMemoryReadTransaction, StorageError> {
+        Ok(InMemoryReadTransaction { backend: self })
+    }
+
+    fn begin_write(&mut self) -> Result<InMemoryWriteTransaction, StorageError> {
+        Ok(InMemoryWriteTransaction {
+            backend: self,
+            undo_log: vec![],
+            committed: false,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct InMemoryReadTransaction<'a> {
+    backend: &'a InMemoryBackend,
+}
+
+impl<'a> ReadTransaction for InMemoryReadTransaction<'a> {
+    type Database = InMemoryDatabase;
+
+    fn get(&self, db: InMemoryDatabase, key: &[u8]) -> Result<Option<Cow<[u8]>>, StorageError> {
+        Ok(self.backend.tables[db.0].get(key).map(Cow::Borrowed))
+    }
+
+    fn count(&self, db: InMemoryDatabase) -> Result<usize, StorageError> {
+        Ok(self.backend.tables[db.0].count())
+    }
+
+    fn iter(&self, db: InMemoryDatabase) -> Result<EntryIterator, StorageError> {
+        Ok(self.backend.tables[db.0].iter())
+    }
+
+    fn iter_dup(&self, db: InMemoryDatabase, key: &[u8]) -> Result<DupIterator, StorageError> {
+        Ok(self.backend.tables[db.0].iter_dup(key))
+    }
+}
+
+#[derive(Debug)]
+pub struct InMemoryWriteTransaction<'a> {
+    backend: &'a mut InMemoryBackend,
+    undo_log: Vec<Undo>,
+    committed: bool,
+}
+
+/// The reverse of one change made by a write transaction.
+#[derive(Debug)]
+enum Undo {
+    /// Remove a key-value pair that was inserted.
+    Remove {
+        db: InMemoryDatabase,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    /// Restore the values of a key that were removed.
+    Restore {
+        db: InMemoryDatabase,
+        key: Vec<u8>,
+        values: BTreeSet<Vec<u8>>,
+    },
+    /// Restore the entries of a database that was cleared.
+    RestoreAll {
+        db: InMemoryDatabase,
+        entries: BTreeMap<Vec<u8>, BTreeSet<Vec<u8>>>,
+    },
+}
+
+impl<'a> InMemoryWriteTransaction<'a> {
+    fn table(&self, db: InMemoryDatabase) -> &Table {
+        &self.backend.tables[db.0]
+    }
+}
+
+impl<'a> Drop for InMemoryWriteTransaction<'a> {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+        for undo in self.undo_log.drain(..).rev() {
+            match undo {
+                Undo::Remove { db, key, value } => {
+                    self.backend.tables[db.0].remove(&key, Some(&value));
+                }
+                Undo::Restore { db, key, values } => {
+                    self.backend.tables[db.0]
+                        .entries
+                        .entry(key)
+                        .or_default()
+                        .extend(values);
+                }
+                Undo::RestoreAll { db, entries } => {
+                    self.backend.tables[db.0].entries = entries;
+                }
+            }
+        }
+    }
+}
+
+impl<'a> ReadTransaction for InMemoryWriteTransaction<'a> {
+    type Database = InMemoryDatabase;
+
+    fn get(&self, db: InMemoryDatabase, key: &[u8]) -> Result<Option<Cow<[u8]>>, StorageError> {
+        Ok(self.table(db).get(key).map(Cow::Borrowed))
+    }
+
+    fn count(&self, db: InMemoryDatabase) -> Result<usize, StorageError> {
+        Ok(self.table(db).count())
+    }
+
+    fn iter(&self, db: InMemoryDatabase) -> Result<EntryIterator, StorageError> {
+        Ok(self.table(db).iter())
+    }
+
+    fn iter_dup(&self, db: InMemoryDatabase, key: &[u8]) -> Result<DupIterator, StorageError> {
+        Ok(self.table(db).iter_dup(key))
+    }
+}
+
+impl<'a> WriteTransaction for InMemoryWriteTransaction<'a> {
+    fn insert(
+        &mut self,
+        db: InMemoryDatabase,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<bool, StorageError> {
+        let inserted = self.backend.tables[db.0].insert(key, value);
+        if inserted {
+            self.undo_log.push(Undo::Remove {
+                db,
+                key: key.to_vec(),
+                value: value.to_vec(),
+            });
+        }
+        Ok(inserted)
+    }
+
+    fn remove(
+        &mut self,
+        db: InMemoryDatabase,
+        key: &[u8],
+        value: Option<&[u8]>,
+    ) -> Result<bool, StorageError> {
+        let values = match value {
+            Some(value) => self.backend.tables[db.0]
+                .remove(key, Some(value))
+                .then(|| BTreeSet::from([value.to_vec()])),
+            None => self.backend.tables[db.0].entries.remove(key),
+        };
+        let Some(values) = values else {
+            return Ok(false);
+        };
+        self.undo_log.push(Undo::Restore {
+            db,
+            key: key.to_vec(),
+            values,
+        });
+        Ok(true)
+    }
+
+    fn clear(&mut self, db: InMemoryDatabase) -> Result<(), StorageError> {
+        let entries = std::mem::take(&mut self.backend.tables[db.0].entries);
+        self.undo_log.push(Undo::RestoreAll { db, entries });
+        Ok(())
+    }
+
+    fn commit(mut self) -> Result<(), StorageError> {
+        self.committed = true;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct Table {
+    layout: DatabaseLayout,
+    /// Values of a database without duplicate keys are single element sets.
+    ///
+    /// Byte-wise order is the order of LMDB, see `LmdbKey`.
+    entries: BTreeMap<Vec<u8>, BTreeSet<Vec<u8>>>,
+}
+
+impl Table {
+    fn new(layout: DatabaseLayout) -> Self {
+        Self {
+            layout,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.entries
+            .get(key)
+            .and_then(|values| values.first())
+            .map(Vec::as_slice)
+    }
+
+    fn count(&self) -> usize {
+        self.entries.values().map(BTreeSet::len).sum()
+    }
+
+    fn iter(&self) -> EntryIterator {
+        Box::new(self.entries.iter().flat_map(|(key, values)| {
+            values.iter().map(move |value| {
+                Ok((
+                    Cow::Borrowed(key.as_slice()),
+                    Cow::Borrowed(value.as_slice()),
+                ))
+            })
+        }))
+    }
+
+    fn iter_dup(&self, key: &[u8]) -> DupIterator {
+        Box::new(
+            self.entries
+                .get(key)
+                .into_iter()
+                .flatten()
+                .map(|value| Ok(Cow::Borrowed(value.as_slice()))),
+        )
+    }
+
+    fn insert(&mut self, key: &[u8], value: &[u8]) -> bool {
+        let is_dup = self.layout.dup_value.is_some();
+        let values = self.entries.entry(key.to_vec()).or_default();
+        if !is_dup && !values.is_empty() {
+            return false;
+        }
+        values.insert(value.to_vec())
+    }
+
+    fn remove(&mut self, key: &[u8], value: Option<&[u8]>) -> bool {
+        let Some(value) = value else {
+            return self.entries.remove(key).is_some();
+        };
+        let Some(values) = self.entries.get_mut(key) else {
+            return false;
+        };
+        let removed = values.remove(value);
+        if values.is_empty() {
+            self.entries.remove(key);
+        }
+        removed
+    }
+}
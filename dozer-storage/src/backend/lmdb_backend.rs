@@ -0,0 +1,169 @@
+use std::{borrow::Cow, ops::Bound};
+
+use lmdb::{Database, DatabaseFlags, RoTransaction, RwTransaction, Transaction, WriteFlags};
+
+use crate::{
+    errors::StorageError,
+    lmdb_database::RawIterator,
+    lmdb_map::lmdb_stat,
+    lmdb_storage::{self, LmdbEnvironmentManager},
+    DupValueIterator, LmdbValType,
+};
+
+use super::{
+    DatabaseLayout, DupIterator, EntryIterator, ReadTransaction, StorageBackend, WriteTransaction,
+};
+
+impl StorageBackend for LmdbEnvironmentManager {
+    type Database = Database;
+    type ReadTransaction<'a> = RoTransaction<'a>;
+    type WriteTransaction<'a> = RwTransaction<'a>;
+
+    fn create_database(
+        &mut self,
+        name: Option<&str>,
+        layout: Option<DatabaseLayout>,
+    ) -> Result<Database, StorageError> {
+        LmdbEnvironmentManager::create_database(self, name, layout.map(database_flags))
+    }
+
+    fn begin_read(&self) -> Result<RoTransaction, StorageError> {
+        self.begin_ro_txn()
+    }
+
+    fn begin_write(&mut self) -> Result<RwTransaction, StorageError> {
+        self.begin_rw_txn()
+    }
+}
+
+impl<'env> ReadTransaction for RoTransaction<'env> {
+    type Database = Database;
+
+    fn get(&self, db: Database, key: &[u8]) -> Result<Option<Cow<[u8]>>, StorageError> {
+        get(self, db, key)
+    }
+
+    fn count(&self, db: Database) -> Result<usize, StorageError> {
+        count(self, db)
+    }
+
+    fn iter(&self, db: Database) -> Result<EntryIterator, StorageError> {
+        iter(self, db)
+    }
+
+    fn iter_dup(&self, db: Database, key: &[u8]) -> Result<DupIterator, StorageError> {
+        iter_dup(self, db, key)
+    }
+}
+
+impl<'env> ReadTransaction for RwTransaction<'env> {
+    type Database = Database;
+
+    fn get(&self, db: Database, key: &[u8]) -> Result<Option<Cow<[u8]>>, StorageError> {
+        get(self, db, key)
+    }
+
+    fn count(&self, db: Database) -> Result<usize, StorageError> {
+        count(self, db)
+    }
+
+    fn iter(&self, db: Database) -> Result<EntryIterator, StorageError> {
+        iter(self, db)
+    }
+
+    fn iter_dup(&self, db: Database, key: &[u8]) -> Result<DupIterator, StorageError> {
+        iter_dup(self, db, key)
+    }
+}
+
+impl<'env> WriteTransaction for RwTransaction<'env> {
+    fn insert(&mut self, db: Database, key: &[u8], value: &[u8]) -> Result<bool, StorageError> {
+        let flags = if is_dup_sort(self, db)? {
+            WriteFlags::NO_DUP_DATA
+        } else {
+            WriteFlags::NO_OVERWRITE
+        };
+        match self.put(db, &key, &value, flags) {
+            Ok(()) => Ok(true),
+            Err(lmdb::Error::KeyExist) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn remove(
+        &mut self,
+        db: Database,
+        key: &[u8],
+        value: Option<&[u8]>,
+    ) -> Result<bool, StorageError> {
+        match self.del(db, &key, value) {
+            Ok(()) => Ok(true),
+            Err(lmdb::Error::NotFound) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn clear(&mut self, db: Database) -> Result<(), StorageError> {
+        self.clear_db(db).map_err(Into::into)
+    }
+
+    fn commit(self) -> Result<(), StorageError> {
+        Transaction::commit(self).map_err(Into::into)
+    }
+}
+
+fn get<'txn, T: Transaction>(
+    txn: &'txn T,
+    db: Database,
+    key: &[u8],
+) -> Result<Option<Cow<'txn, [u8]>>, StorageError> {
+    match Transaction::get(txn, db, &key) {
+        Ok(value) => Ok(Some(Cow::Borrowed(value))),
+        Err(lmdb::Error::NotFound) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn count<T: Transaction>(txn: &T, db: Database) -> Result<usize, StorageError> {
+    Ok(lmdb_stat(txn, db).map(|stat| stat.ms_entries)?)
+}
+
+fn iter<T: Transaction>(txn: &T, db: Database) -> Result<EntryIterator, StorageError> {
+    let cursor = txn.open_ro_cursor(db)?;
+    let iter = RawIterator::new(cursor, Bound::Unbounded, true)?;
+    Ok(Box::new(iter.map(|result| {
+        result
+            .map(|(key, value)| (Cow::Borrowed(key), Cow::Borrowed(value)))
+            .map_err(Into::into)
+    })))
+}
+
+fn iter_dup<'txn, T: Transaction>(
+    txn: &'txn T,
+    db: Database,
+    key: &[u8],
+) -> Result<DupIterator<'txn>, StorageError> {
+    let cursor = txn.open_ro_cursor(db)?;
+    let iter = DupValueIterator::<_, [u8]>::new(
+        txn,
+        db,
+        cursor,
+        key,
+        (Bound::Unbounded, Bound::Unbounded),
+    )?;
+    Ok(Box::new(iter))
+}
+
+fn is_dup_sort<T: Transaction>(txn: &T, db: Database) -> Result<bool, StorageError> {
+    Ok(lmdb_storage::database_flags(txn, db)?.contains(DatabaseFlags::DUP_SORT))
+}
+
+/// Keys and values are compared byte-wise, see `LmdbKey`, so new databases are created without `INTEGER_KEY` and
+/// `INTEGER_DUP`. Existing databases keep the flags they were created with.
+fn database_flags(layout: DatabaseLayout) -> DatabaseFlags {
+    match layout.dup_value {
+        None => DatabaseFlags::empty(),
+        Some(LmdbValType::VariableSize) => DatabaseFlags::DUP_SORT,
+        Some(_) => DatabaseFlags::DUP_SORT | DatabaseFlags::DUP_FIXED,
+    }
+}
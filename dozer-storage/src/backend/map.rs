@@ -0,0 +1,356 @@
+use std::borrow::Cow;
+
+use crate::{errors::StorageError, Decode, LmdbDupValue, LmdbKey, LmdbValue};
+
+use super::{DatabaseLayout, ReadTransaction, StorageBackend, WriteTransaction};
+
+/// A typed map on top of any `StorageBackend`, with the same encoding and ordering as `LmdbMap`.
+#[derive(Debug)]
+pub struct BackendMap<B: StorageBackend, K: ?Sized, V: ?Sized> {
+    db: B::Database,
+    _key: std::marker::PhantomData<*const K>,
+    _value: std::marker::PhantomData<*const V>,
+}
+
+impl<B: StorageBackend, K: ?Sized, V: ?Sized> Clone for BackendMap<B, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db,
+            _key: std::marker::PhantomData,
+            _value: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<B: StorageBackend, K: ?Sized, V: ?Sized> Copy for BackendMap<B, K, V> {}
+
+unsafe impl<B: StorageBackend, K: ?Sized, V: ?Sized> Send for BackendMap<B, K, V> where
+    B::Database: Send
+{
+}
+unsafe impl<B: StorageBackend, K: ?Sized, V: ?Sized> Sync for BackendMap<B, K, V> where
+    B::Database: Sync
+{
+}
+
+impl<B: StorageBackend, K: LmdbKey + ?Sized, V: LmdbValue + ?Sized> BackendMap<B, K, V> {
+    pub fn new(
+        backend: &mut B,
+        name: Option<&str>,
+        create_if_not_exist: bool,
+    ) -> Result<Self, StorageError> {
+        let layout = create_if_not_exist.then(DatabaseLayout::map::<K>);
+        let db = backend.create_database(name, layout)?;
+        Ok(Self {
+            db,
+            _key: std::marker::PhantomData,
+            _value: std::marker::PhantomData,
+        })
+    }
+
+    pub fn database(&self) -> B::Database {
+        self.db
+    }
+
+    pub fn count<T: ReadTransaction<Database = B::Database>>(
+        &self,
+        txn: &T,
+    ) -> Result<usize, StorageError> {
+        txn.count(self.db)
+    }
+
+    pub fn get<'a, T: ReadTransaction<Database = B::Database>>(
+        &self,
+        txn: &'a T,
+        key: &K,
+    ) -> Result<Option<Cow<'a, V>>, StorageError> {
+        let key = key.encode()?;
+        match txn.get(self.db, key.as_ref())? {
+            Some(value) => Ok(Some(decode(value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns if the key was actually inserted.
+    pub fn insert<T: WriteTransaction<Database = B::Database>>(
+        &self,
+        txn: &mut T,
+        key: &K,
+        value: &V,
+    ) -> Result<bool, StorageError> {
+        let key = key.encode()?;
+        let value = value.encode()?;
+        txn.insert(self.db, key.as_ref(), value.as_ref())
+    }
+
+    /// Returns if the key was actually removed.
+    pub fn remove<T: WriteTransaction<Database = B::Database>>(
+        &self,
+        txn: &mut T,
+        key: &K,
+    ) -> Result<bool, StorageError> {
+        let key = key.encode()?;
+        txn.remove(self.db, key.as_ref(), None)
+    }
+
+    pub fn clear<T: WriteTransaction<Database = B::Database>>(
+        &self,
+        txn: &mut T,
+    ) -> Result<(), StorageError> {
+        txn.clear(self.db)
+    }
+}
+
+impl<B: StorageBackend, K: LmdbKey + Decode + ?Sized, V: LmdbValue + ?Sized> BackendMap<B, K, V> {
+    pub fn keys<'a, T: ReadTransaction<Database = B::Database>>(
+        &self,
+        txn: &'a T,
+    ) -> Result<impl std::iter::Iterator<Item = Result<Cow<'a, K>, StorageError>> + 'a, StorageError>
+    where
+        K: 'a,
+    {
+        Ok(txn
+            .iter(self.db)?
+            .map(|result| result.and_then(|(key, _)| decode(key))))
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn iter<'a, T: ReadTransaction<Database = B::Database>>(
+        &self,
+        txn: &'a T,
+    ) -> Result<
+        impl std::iter::Iterator<Item = Result<(Cow<'a, K>, Cow<'a, V>), StorageError>> + 'a,
+        StorageError,
+    >
+    where
+        K: 'a,
+        V: 'a,
+    {
+        Ok(txn
+            .iter(self.db)?
+            .map(|result| result.and_then(|(key, value)| Ok((decode(key)?, decode(value)?)))))
+    }
+}
+
+/// A typed multimap on top of any `StorageBackend`, with the same encoding and ordering as `LmdbMultimap`.
+#[derive(Debug)]
+pub struct BackendMultimap<B: StorageBackend, K: ?Sized, V: ?Sized> {
+    db: B::Database,
+    _key: std::marker::PhantomData<*const K>,
+    _value: std::marker::PhantomData<*const V>,
+}
+
+impl<B: StorageBackend, K: ?Sized, V: ?Sized> Clone for BackendMultimap<B, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db,
+            _key: std::marker::PhantomData,
+            _value: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<B: StorageBackend, K: ?Sized, V: ?Sized> Copy for BackendMultimap<B, K, V> {}
+
+unsafe impl<B: StorageBackend, K: ?Sized, V: ?Sized> Send for BackendMultimap<B, K, V> where
+    B::Database: Send
+{
+}
+unsafe impl<B: StorageBackend, K: ?Sized, V: ?Sized> Sync for BackendMultimap<B, K, V> where
+    B::Database: Sync
+{
+}
+
+impl<B: StorageBackend, K: LmdbKey + ?Sized, V: LmdbDupValue + ?Sized> BackendMultimap<B, K, V> {
+    pub fn new(
+        backend: &mut B,
+        name: Option<&str>,
+        create_if_not_exist: bool,
+    ) -> Result<Self, StorageError> {
+        let layout = create_if_not_exist.then(DatabaseLayout::multimap::<K, V>);
+        let db = backend.create_database(name, layout)?;
+        Ok(Self {
+            db,
+            _key: std::marker::PhantomData,
+            _value: std::marker::PhantomData,
+        })
+    }
+
+    pub fn database(&self) -> B::Database {
+        self.db
+    }
+
+    /// Returns the total number of key-value pairs.
+    pub fn count<T: ReadTransaction<Database = B::Database>>(
+        &self,
+        txn: &T,
+    ) -> Result<usize, StorageError> {
+        txn.count(self.db)
+    }
+
+    /// Returns if the key-value pair was actually inserted.
+    pub fn insert<T: WriteTransaction<Database = B::Database>>(
+        &self,
+        txn: &mut T,
+        key: &K,
+        value: &V,
+    ) -> Result<bool, StorageError> {
+        let key = key.encode()?;
+        let value = value.encode()?;
+        txn.insert(self.db, key.as_ref(), value.as_ref())
+    }
+
+    /// Returns if the key-value pair was actually removed.
+    pub fn remove<T: WriteTransaction<Database = B::Database>>(
+        &self,
+        txn: &mut T,
+        key: &K,
+        value: &V,
+    ) -> Result<bool, StorageError> {
+        let key = key.encode()?;
+        let value = value.encode()?;
+        txn.remove(self.db, key.as_ref(), Some(value.as_ref()))
+    }
+
+    /// Iterates the values of `key` in ascending order.
+    pub fn get_dup<'a, T: ReadTransaction<Database = B::Database>>(
+        &self,
+        txn: &'a T,
+        key: &K,
+    ) -> Result<impl std::iter::Iterator<Item = Result<Cow<'a, V>, StorageError>> + 'a, StorageError>
+    where
+        V: 'a,
+    {
+        let key = key.encode()?;
+        Ok(txn
+            .iter_dup(self.db, key.as_ref())?
+            .map(|result| result.and_then(decode)))
+    }
+}
+
+fn decode<T: Decode + ?Sized>(bytes: Cow<[u8]>) -> Result<Cow<T>, StorageError> {
+    match bytes {
+        Cow::Borrowed(bytes) => T::decode(bytes),
+        Cow::Owned(bytes) => T::decode(&bytes).map(|value| Cow::Owned(value.into_owned())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use crate::{
+        backend::InMemoryBackend,
+        lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions},
+    };
+
+    use super::*;
+
+    fn run_map_and_multimap<B: StorageBackend>(backend: &mut B) -> (Vec<(u64, String)>, Vec<u64>) {
+        let map = BackendMap::<B, u64, str>::new(backend, Some("map"), true).unwrap();
+        let multimap =
+            BackendMultimap::<B, str, u64>::new(backend, Some("multimap"), true).unwrap();
+
+        let mut txn = backend.begin_write().unwrap();
+        assert!(map.insert(&mut txn, &256, "b").unwrap());
+        assert!(map.insert(&mut txn, &1, "a").unwrap());
+        assert!(!map.insert(&mut txn, &1, "c").unwrap());
+        for value in [3, 256, 1, 3] {
+            multimap.insert(&mut txn, "key", &value).unwrap();
+        }
+        txn.commit().unwrap();
+
+        let mut txn = backend.begin_write().unwrap();
+        assert!(map.remove(&mut txn, &256).unwrap());
+        assert!(multimap.remove(&mut txn, "key", &256).unwrap());
+        assert!(multimap.insert(&mut txn, "other", &4).unwrap());
+        map.clear(&mut txn).unwrap();
+        // Aborted.
+        drop(txn);
+
+        let mut txn = backend.begin_write().unwrap();
+        assert!(map.insert(&mut txn, &2, "c").unwrap());
+        assert!(!map.remove(&mut txn, &3).unwrap());
+        txn.commit().unwrap();
+
+        let txn = backend.begin_read().unwrap();
+        assert_eq!(map.count(&txn).unwrap(), 3);
+        assert_eq!(map.get(&txn, &1).unwrap().unwrap(), "a");
+        assert!(map.get(&txn, &3).unwrap().is_none());
+        assert_eq!(multimap.count(&txn).unwrap(), 3);
+        let entries = map
+            .iter(&txn)
+            .unwrap()
+            .map(|result| result.map(|(key, value)| (key.into_owned(), value.into_owned())))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let values = multimap
+            .get_dup(&txn, "key")
+            .unwrap()
+            .map(|result| result.map(Cow::into_owned))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        (entries, values)
+    }
+
+    #[test]
+    fn test_backends_agree() {
+        let mut in_memory = InMemoryBackend::new();
+        let in_memory_result = run_map_and_multimap(&mut in_memory);
+
+        let temp_dir = TempDir::new("test_backends_agree").unwrap();
+        let mut lmdb = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let lmdb_result = run_map_and_multimap(&mut lmdb);
+
+        assert_eq!(in_memory_result, lmdb_result);
+        let (entries, values) = in_memory_result;
+        assert_eq!(
+            entries.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+            vec![1, 2, 256]
+        );
+        assert_eq!(values, vec![1, 3, 256]);
+    }
+
+    #[cfg(feature = "rocksdb")]
+    #[test]
+    fn test_rocksdb_backend_agrees() {
+        use crate::backend::RocksdbBackend;
+
+        let mut in_memory = InMemoryBackend::new();
+        let in_memory_result = run_map_and_multimap(&mut in_memory);
+
+        let temp_dir = TempDir::new("test_rocksdb_backend_agrees").unwrap();
+        let path = temp_dir.path().join("rocksdb");
+        let rocksdb_result = {
+            let mut rocksdb = RocksdbBackend::open(&path).unwrap();
+            run_map_and_multimap(&mut rocksdb)
+        };
+        assert_eq!(in_memory_result, rocksdb_result);
+
+        // Reopening restores the databases and their ordering.
+        let mut rocksdb = RocksdbBackend::open(&path).unwrap();
+        let map =
+            BackendMap::<RocksdbBackend, u64, str>::new(&mut rocksdb, Some("map"), false).unwrap();
+        let txn = rocksdb.begin_read().unwrap();
+        assert_eq!(map.count(&txn).unwrap(), in_memory_result.0.len());
+        let keys = map
+            .iter(&txn)
+            .unwrap()
+            .map(|result| result.map(|(key, _)| key.into_owned()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            keys,
+            in_memory_result
+                .0
+                .iter()
+                .map(|(key, _)| *key)
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Byte-level storage abstraction, so the same logic can run on LMDB or in memory.
+//!
+//! Keys and values are ordered as LMDB orders them, determined by the database's `DatabaseLayout`.
+
+use std::{borrow::Cow, fmt::Debug};
+
+use crate::{errors::StorageError, LmdbKey, LmdbValType};
+
+mod in_memory;
+mod lmdb_backend;
+mod map;
+#[cfg(feature = "rocksdb")]
+mod rocksdb_backend;
+
+pub use in_memory::{
+    InMemoryBackend, InMemoryDatabase, InMemoryReadTransaction, InMemoryWriteTransaction,
+};
+pub use map::{BackendMap, BackendMultimap};
+#[cfg(feature = "rocksdb")]
+pub use rocksdb_backend::{
+    RocksdbBackend, RocksdbDatabase, RocksdbReadTransaction, RocksdbWriteTransaction,
+};
+
+/// The key type of a database, and the value type if the database allows duplicate keys.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DatabaseLayout {
+    pub key: LmdbValType,
+    pub dup_value: Option<LmdbValType>,
+}
+
+impl DatabaseLayout {
+    pub fn map<K: LmdbKey + ?Sized>() -> Self {
+        Self {
+            key: K::TYPE,
+            dup_value: None,
+        }
+    }
+
+    pub fn multimap<K: LmdbKey + ?Sized, V: LmdbKey + ?Sized>() -> Self {
+        Self {
+            key: K::TYPE,
+            dup_value: Some(V::TYPE),
+        }
+    }
+}
+
+pub type EntryIterator<'a> =
+    Box<dyn std::iter::Iterator<Item = Result<(Cow<'a, [u8]>, Cow<'a, [u8]>), StorageError>> + 'a>;
+
+pub type DupIterator<'a> =
+    Box<dyn std::iter::Iterator<Item = Result<Cow<'a, [u8]>, StorageError>> + 'a>;
+
+pub trait ReadTransaction {
+    type Database: Debug + Clone + Copy;
+
+    /// Returns the first value of `key`.
+    ///
+    /// Backends that can't hand out references into their storage return owned bytes.
+    fn get(&self, db: Self::Database, key: &[u8]) -> Result<Option<Cow<[u8]>>, StorageError>;
+
+    /// Returns the number of entries. Every duplicate value counts as an entry.
+    fn count(&self, db: Self::Database) -> Result<usize, StorageError>;
+
+    /// Iterates all entries in ascending order.
+    fn iter(&self, db: Self::Database) -> Result<EntryIterator, StorageError>;
+
+    /// Iterates the values of `key` in ascending order.
+    fn iter_dup(&self, db: Self::Database, key: &[u8]) -> Result<DupIterator, StorageError>;
+}
+
+pub trait WriteTransaction: ReadTransaction {
+    /// Returns if the entry was actually inserted.
+    ///
+    /// An existing key, or key-value pair if the database allows duplicate keys, is never overwritten.
+    fn insert(
+        &mut self,
+        db: Self::Database,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<bool, StorageError>;
+
+    /// Removes all values of `key`, or only `value` if given. Returns if anything was actually removed.
+    fn remove(
+        &mut self,
+        db: Self::Database,
+        key: &[u8],
+        value: Option<&[u8]>,
+    ) -> Result<bool, StorageError>;
+
+    fn clear(&mut self, db: Self::Database) -> Result<(), StorageError>;
+
+    /// Dropping a write transaction without committing aborts it.
+    fn commit(self) -> Result<(), StorageError>
+    where
+        Self: Sized;
+}
+
+pub trait StorageBackend {
+    type Database: Debug + Clone + Copy;
+    type ReadTransaction<'a>: ReadTransaction<Database = Self::Database>
+    where
+        Self: 'a;
+    type WriteTransaction<'a>: WriteTransaction<Database = Self::Database>
+    where
+        Self: 'a;
+
+    /// Opens database `name`. If it doesn't exist, it's created with `layout`, or an error is returned if `layout` is `None`.
+    fn create_database(
+        &mut self,
+        name: Option<&str>,
+        layout: Option<DatabaseLayout>,
+    ) -> Result<Self::Database, StorageError>;
+
+    fn begin_read(&self) -> Result<Self::ReadTransaction<'_>, StorageError>;
+
+    fn begin_write(&mut self) -> Result<Self::WriteTransaction<'_>, StorageError>;
+}
@@ -0,0 +1,417 @@
+use std::{borrow::Cow, cmp::Ordering, collections::HashMap, path::Path};
+
+use rocksdb::{
+    ColumnFamily, ColumnFamilyDescriptor, Direction, IteratorMode, Options, SnapshotWithThreadMode,
+    Transaction, TransactionDB, TransactionDBOptions, DB, DEFAULT_COLUMN_FAMILY_NAME,
+};
+
+use crate::{errors::StorageError, Decode, LmdbValType};
+
+use super::{
+    DatabaseLayout, DupIterator, EntryIterator, ReadTransaction, StorageBackend, WriteTransaction,
+};
+
+/// A `StorageBackend` on top of a RocksDB `TransactionDB`, for working sets that don't fit in memory.
+///
+/// Every database is a column family, whose name records the `DatabaseLayout` so the right comparator can be set on reopening.
+/// Keys of databases with duplicate keys are stored as `key length (u32, BE) | key | value`, with empty RocksDB values.
+///
+/// RocksDB can only estimate the number of keys, so the number of entries of every database is kept in the default
+/// column family, keyed by the database's column family name, and updated by the write transactions.
+pub struct RocksdbBackend {
+    db: TransactionDB,
+    names: HashMap<Option<String>, RocksdbDatabase>,
+    databases: Vec<(String, DatabaseLayout)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RocksdbDatabase(usize);
+
+impl std::fmt::Debug for RocksdbBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RocksdbBackend")
+            .field("path", &self.db.path())
+            .field("databases", &self.databases)
+            .finish()
+    }
+}
+
+impl RocksdbBackend {
+    pub fn open(path: &Path) -> Result<Self, StorageError> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+
+        let cf_names = if path.exists() {
+            DB::list_cf(&options, path)?
+        } else {
+            vec![]
+        };
+
+        let mut names = HashMap::new();
+        let mut databases = vec![];
+        let mut descriptors = vec![];
+        for cf_name in cf_names {
+            if cf_name == DEFAULT_COLUMN_FAMILY_NAME {
+                continue;
+            }
+            let (name, layout) = parse_cf_name(&cf_name).ok_or_else(|| {
+                StorageError::OpenOrCreateError(format!("Unknown column family {cf_name}"))
+            })?;
+            descriptors.push(ColumnFamilyDescriptor::new(&cf_name, cf_options(layout)));
+            names.insert(name, RocksdbDatabase(databases.len()));
+            databases.push((cf_name, layout));
+        }
+
+        let db = TransactionDB::open_cf_descriptors(
+            &options,
+            &TransactionDBOptions::default(),
+            path,
+            descriptors,
+        )?;
+        Ok(Self {
+            db,
+            names,
+            databases,
+        })
+    }
+
+    fn cf(&self, db: RocksdbDatabase) -> (&ColumnFamily, DatabaseLayout) {
+        let (cf_name, layout) = &self.databases[db.0];
+        let cf = self
+            .db
+            .cf_handle(cf_name)
+            .expect("Column family of a created database must exist");
+        (cf, *layout)
+    }
+
+    fn count_key(&self, db: RocksdbDatabase) -> &[u8] {
+        self.databases[db.0].0.as_bytes()
+    }
+}
+
+impl StorageBackend for RocksdbBackend {
+    type Database = RocksdbDatabase;
+    type ReadTransaction<'a> = RocksdbReadTransaction<'a>;
+    type WriteTransaction<'a> = RocksdbWriteTransaction<'a>;
+
+    fn create_database(
+        &mut self,
+        name: Option<&str>,
+        layout: Option<DatabaseLayout>,
+    ) -> Result<RocksdbDatabase, StorageError> {
+        let name = name.map(ToString::to_string);
+        if let Some(db) = self.names.get(&name) {
+            return Ok(*db);
+        }
+        let Some(layout) = layout else {
+            return Err(StorageError::DatabaseNotFound(name));
+        };
+        let cf_name = cf_name(name.as_deref(), layout);
+        self.db.create_cf(&cf_name, &cf_options(layout))?;
+        let db = RocksdbDatabase(self.databases.len());
+        self.databases.push((cf_name, layout));
+        self.names.insert(name, db);
+        Ok(db)
+    }
+
+    fn begin_read(&self) -> Result<RocksdbReadTransaction, StorageError> {
+        Ok(RocksdbReadTransaction {
+            backend: self,
+            snapshot: self.db.snapshot(),
+        })
+    }
+
+    fn begin_write(&mut self) -> Result<RocksdbWriteTransaction, StorageError> {
+        let backend: &RocksdbBackend = self;
+        Ok(RocksdbWriteTransaction {
+            backend,
+            txn: backend.db.transaction(),
+            count_deltas: HashMap::new(),
+        })
+    }
+}
+
+pub struct RocksdbReadTransaction<'a> {
+    backend: &'a RocksdbBackend,
+    snapshot: SnapshotWithThreadMode<'a, TransactionDB>,
+}
+
+impl<'a> ReadTransaction for RocksdbReadTransaction<'a> {
+    type Database = RocksdbDatabase;
+
+    fn get(&self, db: RocksdbDatabase, key: &[u8]) -> Result<Option<Cow<[u8]>>, StorageError> {
+        let (cf, layout) = self.backend.cf(db);
+        if layout.dup_value.is_some() {
+            return Ok(self.iter_dup(db, key)?.next().transpose()?);
+        }
+        Ok(self.snapshot.get_cf(cf, key)?.map(Cow::Owned))
+    }
+
+    fn count(&self, db: RocksdbDatabase) -> Result<usize, StorageError> {
+        decode_count(self.snapshot.get(self.backend.count_key(db))?)
+    }
+
+    fn iter(&self, db: RocksdbDatabase) -> Result<EntryIterator, StorageError> {
+        let (cf, layout) = self.backend.cf(db);
+        let iter = self.snapshot.iterator_cf(cf, IteratorMode::Start);
+        Ok(Box::new(iter.map(move |entry| {
+            entry
+                .map(|entry| decode_entry(layout, entry))
+                .map_err(StorageError::from)
+        })))
+    }
+
+    fn iter_dup(&self, db: RocksdbDatabase, key: &[u8]) -> Result<DupIterator, StorageError> {
+        let (cf, layout) = self.backend.cf(db);
+        let start = dup_start(layout, key);
+        let iter = self
+            .snapshot
+            .iterator_cf(cf, IteratorMode::From(&start, Direction::Forward));
+        Ok(dup_values(key, iter))
+    }
+}
+
+pub struct RocksdbWriteTransaction<'a> {
+    backend: &'a RocksdbBackend,
+    txn: Transaction<'a, TransactionDB>,
+    /// Changes of the entry counts, written on commit.
+    count_deltas: HashMap<RocksdbDatabase, i64>,
+}
+
+impl<'a> RocksdbWriteTransaction<'a> {
+    fn stored_count(&self, db: RocksdbDatabase) -> Result<usize, StorageError> {
+        decode_count(self.txn.get_for_update(self.backend.count_key(db), true)?)
+    }
+}
+
+impl<'a> ReadTransaction for RocksdbWriteTransaction<'a> {
+    type Database = RocksdbDatabase;
+
+    fn get(&self, db: RocksdbDatabase, key: &[u8]) -> Result<Option<Cow<[u8]>>, StorageError> {
+        let (cf, layout) = self.backend.cf(db);
+        if layout.dup_value.is_some() {
+            return Ok(self.iter_dup(db, key)?.next().transpose()?);
+        }
+        Ok(self.txn.get_cf(cf, key)?.map(Cow::Owned))
+    }
+
+    fn count(&self, db: RocksdbDatabase) -> Result<usize, StorageError> {
+        let delta = self.count_deltas.get(&db).copied().unwrap_or_default();
+        Ok((self.stored_count(db)? as i64 + delta) as usize)
+    }
+
+    fn iter(&self, db: RocksdbDatabase) -> Result<EntryIterator, StorageError> {
+        let (cf, layout) = self.backend.cf(db);
+        let iter = self.txn.iterator_cf(cf, IteratorMode::Start);
+        Ok(Box::new(iter.map(move |entry| {
+            entry
+                .map(|entry| decode_entry(layout, entry))
+                .map_err(StorageError::from)
+        })))
+    }
+
+    fn iter_dup(&self, db: RocksdbDatabase, key: &[u8]) -> Result<DupIterator, StorageError> {
+        let (cf, layout) = self.backend.cf(db);
+        let start = dup_start(layout, key);
+        let iter = self
+            .txn
+            .iterator_cf(cf, IteratorMode::From(&start, Direction::Forward));
+        Ok(dup_values(key, iter))
+    }
+}
+
+impl<'a> WriteTransaction for RocksdbWriteTransaction<'a> {
+    fn insert(
+        &mut self,
+        db: RocksdbDatabase,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<bool, StorageError> {
+        let (cf, layout) = self.backend.cf(db);
+        let (key, value) = if layout.dup_value.is_some() {
+            (dup_key(key, value), &[][..])
+        } else {
+            (key.to_vec(), value)
+        };
+        if self.txn.get_for_update_cf(cf, &key, true)?.is_some() {
+            return Ok(false);
+        }
+        self.txn.put_cf(cf, key, value)?;
+        *self.count_deltas.entry(db).or_default() += 1;
+        Ok(true)
+    }
+
+    fn remove(
+        &mut self,
+        db: RocksdbDatabase,
+        key: &[u8],
+        value: Option<&[u8]>,
+    ) -> Result<bool, StorageError> {
+        let (cf, layout) = self.backend.cf(db);
+        let keys = match (layout.dup_value, value) {
+            (Some(_), Some(value)) => vec![dup_key(key, value)],
+            (Some(_), None) => self
+                .iter_dup(db, key)?
+                .map(|value| value.map(|value| dup_key(key, &value)))
+                .collect::<Result<_, _>>()?,
+            (None, _) => vec![key.to_vec()],
+        };
+
+        let mut removed = 0;
+        for key in keys {
+            if self.txn.get_for_update_cf(cf, &key, true)?.is_some() {
+                self.txn.delete_cf(cf, key)?;
+                removed += 1;
+            }
+        }
+        *self.count_deltas.entry(db).or_default() -= removed;
+        Ok(removed > 0)
+    }
+
+    fn clear(&mut self, db: RocksdbDatabase) -> Result<(), StorageError> {
+        let (cf, _) = self.backend.cf(db);
+        let keys = self
+            .txn
+            .iterator_cf(cf, IteratorMode::Start)
+            .map(|entry| entry.map(|(key, _)| key))
+            .collect::<Result<Vec<_>, _>>()?;
+        for key in keys {
+            self.txn.delete_cf(cf, key)?;
+        }
+        let count = self.count(db)? as i64;
+        *self.count_deltas.entry(db).or_default() -= count;
+        Ok(())
+    }
+
+    fn commit(self) -> Result<(), StorageError> {
+        for (&db, &delta) in &self.count_deltas {
+            if delta != 0 {
+                let count = (self.stored_count(db)? as i64 + delta) as u64;
+                self.txn
+                    .put(self.backend.count_key(db), count.to_be_bytes())?;
+            }
+        }
+        self.txn.commit().map_err(Into::into)
+    }
+}
+
+type RocksdbEntry = (Box<[u8]>, Box<[u8]>);
+
+fn decode_count(bytes: Option<Vec<u8>>) -> Result<usize, StorageError> {
+    match bytes {
+        Some(bytes) => Ok(u64::decode(&bytes)?.into_owned() as usize),
+        None => Ok(0),
+    }
+}
+
+fn decode_entry(
+    layout: DatabaseLayout,
+    (key, value): RocksdbEntry,
+) -> (Cow<'static, [u8]>, Cow<'static, [u8]>) {
+    if layout.dup_value.is_some() {
+        let (key, value) = split_dup_key(&key);
+        (Cow::Owned(key.to_vec()), Cow::Owned(value.to_vec()))
+    } else {
+        (Cow::Owned(key.into_vec()), Cow::Owned(value.into_vec()))
+    }
+}
+
+fn dup_values<'a>(
+    key: &[u8],
+    iter: impl std::iter::Iterator<Item = Result<RocksdbEntry, rocksdb::Error>> + 'a,
+) -> DupIterator<'a> {
+    let key = key.to_vec();
+    Box::new(
+        iter.map(|entry| entry.map_err(StorageError::from))
+            .take_while(move |entry| match entry {
+                Ok((dup_key, _)) => split_dup_key(dup_key).0 == key.as_slice(),
+                Err(_) => true,
+            })
+            .map(|entry| entry.map(|(key, _)| Cow::Owned(split_dup_key(&key).1.to_vec()))),
+    )
+}
+
+/// The smallest stored key of `key` in a database with duplicate keys.
+fn dup_start(layout: DatabaseLayout, key: &[u8]) -> Vec<u8> {
+    debug_assert!(layout.dup_value.is_some());
+    dup_key(key, &[])
+}
+
+fn dup_key(key: &[u8], value: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(4 + key.len() + value.len());
+    result.extend_from_slice(&(key.len() as u32).to_be_bytes());
+    result.extend_from_slice(key);
+    result.extend_from_slice(value);
+    result
+}
+
+fn split_dup_key(dup_key: &[u8]) -> (&[u8], &[u8]) {
+    let key_len = u32::from_be_bytes(dup_key[..4].try_into().unwrap()) as usize;
+    dup_key[4..].split_at(key_len)
+}
+
+fn cf_options(layout: DatabaseLayout) -> Options {
+    let mut options = Options::default();
+    options.set_comparator(
+        &format!("dozer.{}", layout_code(layout)),
+        Box::new(move |a, b| compare(layout, a, b)),
+    );
+    options
+}
+
+/// Orders keys the way LMDB would order them for a database of `layout`, byte-wise, see `LmdbKey`.
+///
+/// Keys of a database with duplicate keys are compared by their key first, then by their value.
+fn compare(layout: DatabaseLayout, a: &[u8], b: &[u8]) -> Ordering {
+    if layout.dup_value.is_none() {
+        return a.cmp(b);
+    }
+    let (a_key, a_value) = split_dup_key(a);
+    let (b_key, b_value) = split_dup_key(b);
+    a_key.cmp(b_key).then_with(|| a_value.cmp(b_value))
+}
+
+fn cf_name(name: Option<&str>, layout: DatabaseLayout) -> String {
+    format!("{}:{}", layout_code(layout), name.unwrap_or_default())
+}
+
+fn parse_cf_name(cf_name: &str) -> Option<(Option<String>, DatabaseLayout)> {
+    let (code, name) = cf_name.split_once(':')?;
+    let mut code = code.chars();
+    let key = parse_val_type(code.next()?)?;
+    let dup_value = match code.next()? {
+        '-' => None,
+        c => Some(parse_val_type(c)?),
+    };
+    let name = (!name.is_empty()).then(|| name.to_string());
+    Some((name, DatabaseLayout { key, dup_value }))
+}
+
+fn layout_code(layout: DatabaseLayout) -> String {
+    let mut code = String::with_capacity(2);
+    code.push(val_type_code(layout.key));
+    code.push(layout.dup_value.map_or('-', val_type_code));
+    code
+}
+
+fn val_type_code(ty: LmdbValType) -> char {
+    match ty {
+        LmdbValType::U32 => '4',
+        #[cfg(target_pointer_width = "64")]
+        LmdbValType::U64 => '8',
+        LmdbValType::FixedSizeOtherThanU32OrUsize => 'f',
+        LmdbValType::VariableSize => 'v',
+    }
+}
+
+fn parse_val_type(code: char) -> Option<LmdbValType> {
+    match code {
+        '4' => Some(LmdbValType::U32),
+        #[cfg(target_pointer_width = "64")]
+        '8' => Some(LmdbValType::U64),
+        'f' => Some(LmdbValType::FixedSizeOtherThanU32OrUsize),
+        'v' => Some(LmdbValType::VariableSize),
+        _ => None,
+    }
+}
@@ -0,0 +1,160 @@
+//! Custom key orders for databases, see `LmdbEnvironmentManager::create_database_with_comparator`.
+//!
+//! A database must be opened with the same comparator every time, by every process, otherwise it will be corrupted.
+
+use std::{cmp::Ordering, ffi::c_int};
+
+use lmdb::{Database, Transaction};
+use lmdb_sys::MDB_val;
+
+use crate::errors::StorageError;
+
+/// A key comparison function, returning a negative number, zero or a positive number if `a` is less than, equal to or greater than `b`.
+pub type KeyComparator = unsafe extern "C" fn(a: *const MDB_val, b: *const MDB_val) -> c_int;
+
+/// Sets the key comparator of `db`. Must be called before any data of `db` is accessed.
+pub fn set_comparator<T: Transaction>(
+    txn: &T,
+    db: Database,
+    comparator: KeyComparator,
+) -> Result<(), StorageError> {
+    // SAFETY: `txn` and `db` are valid.
+    let code = unsafe { lmdb_sys::mdb_set_compare(txn.txn(), db.dbi(), Some(comparator)) };
+    if code == lmdb_sys::MDB_SUCCESS {
+        Ok(())
+    } else {
+        Err(lmdb::Error::from_err_code(code).into())
+    }
+}
+
+/// Orders keys lexicographically in descending order.
+///
+/// # Safety
+///
+/// `a` and `b` must be valid.
+pub unsafe extern "C" fn reverse_lexicographic(a: *const MDB_val, b: *const MDB_val) -> c_int {
+    to_c_int(as_slice(b).cmp(as_slice(a)))
+}
+
+/// Orders keys as big-endian unsigned integers of any length, so differently sized integers compare numerically.
+///
+/// # Safety
+///
+/// `a` and `b` must be valid.
+pub unsafe extern "C" fn big_endian_unsigned(a: *const MDB_val, b: *const MDB_val) -> c_int {
+    to_c_int(compare_big_endian_unsigned(as_slice(a), as_slice(b)))
+}
+
+/// Orders UTF-8 keys ignoring ASCII case, falling back to byte order for keys that only differ in case.
+///
+/// # Safety
+///
+/// `a` and `b` must be valid.
+pub unsafe extern "C" fn ascii_case_insensitive(a: *const MDB_val, b: *const MDB_val) -> c_int {
+    to_c_int(compare_ascii_case_insensitive(as_slice(a), as_slice(b)))
+}
+
+fn compare_big_endian_unsigned(a: &[u8], b: &[u8]) -> Ordering {
+    let strip = |bytes: &[u8]| {
+        let leading_zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+        &bytes[leading_zeros..]
+    };
+    let (a, b) = (strip(a), strip(b));
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+fn compare_ascii_case_insensitive(a: &[u8], b: &[u8]) -> Ordering {
+    a.iter()
+        .map(u8::to_ascii_lowercase)
+        .cmp(b.iter().map(u8::to_ascii_lowercase))
+        .then_with(|| a.cmp(b))
+}
+
+unsafe fn as_slice<'a>(val: *const MDB_val) -> &'a [u8] {
+    let val = &*val;
+    if val.mv_size == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(val.mv_data as *const u8, val.mv_size)
+    }
+}
+
+fn to_c_int(ordering: Ordering) -> c_int {
+    ordering as c_int
+}
+
+#[cfg(test)]
+mod tests {
+    use lmdb::{DatabaseFlags, WriteFlags};
+    use tempdir::TempDir;
+
+    use crate::{
+        lmdb_database::RawIterator,
+        lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_compare_big_endian_unsigned() {
+        assert_eq!(
+            compare_big_endian_unsigned(&[0, 0, 1], &[2]),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_big_endian_unsigned(&[1, 0], &[0, 0, 0, 255]),
+            Ordering::Greater
+        );
+        assert_eq!(compare_big_endian_unsigned(&[0, 7], &[7]), Ordering::Equal);
+        assert_eq!(compare_big_endian_unsigned(&[], &[0]), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_compare_ascii_case_insensitive() {
+        assert_eq!(
+            compare_ascii_case_insensitive(b"apple", b"Banana"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_ascii_case_insensitive(b"Apple", b"apple"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_ascii_case_insensitive(b"apple", b"apple"),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_create_database_with_comparator() {
+        let temp_dir = TempDir::new("test_create_database_with_comparator").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let db = env
+            .create_database_with_comparator(
+                Some("db"),
+                Some(DatabaseFlags::empty()),
+                reverse_lexicographic,
+            )
+            .unwrap();
+
+        let mut txn = env.begin_rw_txn().unwrap();
+        for key in [b"b", b"a", b"c"] {
+            txn.put(db, key, b"", WriteFlags::empty()).unwrap();
+        }
+        let keys = RawIterator::new(
+            txn.open_ro_cursor(db).unwrap(),
+            std::ops::Bound::Unbounded,
+            true,
+        )
+        .unwrap()
+        .map(|item| item.map(|(key, _)| key))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+        assert_eq!(keys, vec![b"c", b"b", b"a"]);
+    }
+}
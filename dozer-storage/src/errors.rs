@@ -1,4 +1,6 @@
 #![allow(clippy::enum_variant_names)]
+use std::path::PathBuf;
+
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::thiserror;
 use dozer_types::thiserror::Error;
@@ -21,10 +23,33 @@ pub enum StorageError {
     InvalidDatasetIdentifier(String),
     #[error("Invalid key: {0}")]
     InvalidKey(String),
+    #[error("Checksum mismatch: expected {expected:#010x}, actual {actual:#010x}")]
+    ChecksumMismatch { expected: u32, actual: u32 },
     #[error("Invalid record")]
     InvalidRecord,
+    #[error("Invalid path: {0:?}")]
+    InvalidPath(PathBuf),
+    #[error("Invalid environment options: {0}")]
+    InvalidEnvironmentOptions(String),
+    #[error("Database not found: {0:?}")]
+    DatabaseNotFound(Option<String>),
+    #[error("Database already exists: {0}")]
+    DatabaseAlreadyExists(String),
+    #[error("Unsupported storage layout version {version}, latest version is {latest}")]
+    UnsupportedLayoutVersion { version: u32, latest: u32 },
+    #[error("Invalid export: {0}")]
+    InvalidExport(String),
+    #[error("Transaction of a multi-process environment can only be committed on the thread that created it")]
+    TransactionThreadMismatch,
+    #[error("Counter {0} overflowed")]
+    CounterOverflow(String),
 
     // Error forwarding
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
     #[error("Lmdb error: {0}")]
     Lmdb(#[from] lmdb::Error),
+    #[cfg(feature = "rocksdb")]
+    #[error("RocksDB error: {0}")]
+    Rocksdb(#[from] rocksdb::Error),
 }
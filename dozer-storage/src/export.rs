@@ -0,0 +1,262 @@
+//! A portable flat format for the contents of a database.
+//!
+//! Raw LMDB files depend on the page size and endianness of the machine that wrote them. Exported databases only contain the
+//! encoded keys and values, so they can be imported anywhere.
+//!
+//! The format is an 8 byte magic, a version byte, a compression byte, and then the possibly compressed records.
+//! Every record is a big-endian `u32` key length, the key, a big-endian `u32` value length and the value.
+//! The records end with a key length of `u32::MAX`, so truncated exports are detected.
+
+use std::io::{BufReader, BufWriter, Read, Write};
+
+use lmdb::{Database, RwTransaction, Transaction, WriteFlags};
+
+use crate::{errors::StorageError, lmdb_database::RawIterator};
+
+const MAGIC: &[u8; 8] = b"DOZEREXP";
+const VERSION: u8 = 1;
+const END: u32 = u32::MAX;
+
+const NO_COMPRESSION: u8 = 0;
+const ZSTD_COMPRESSION: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    #[default]
+    None,
+    #[cfg(feature = "zstd")]
+    Zstd { level: i32 },
+}
+
+impl Compression {
+    fn tag(&self) -> u8 {
+        match self {
+            Compression::None => NO_COMPRESSION,
+            #[cfg(feature = "zstd")]
+            Compression::Zstd { .. } => ZSTD_COMPRESSION,
+        }
+    }
+}
+
+/// Writes all pairs of `db` to `writer` and returns the number of pairs written.
+pub fn export_database<T: Transaction>(
+    txn: &T,
+    db: Database,
+    mut writer: impl Write,
+    compression: Compression,
+) -> Result<u64, StorageError> {
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[VERSION, compression.tag()])?;
+    match compression {
+        Compression::None => write_records(txn, db, writer),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd { level } => {
+            let mut encoder = zstd::Encoder::new(writer, level)?;
+            let count = write_records(txn, db, &mut encoder)?;
+            encoder.finish()?;
+            Ok(count)
+        }
+    }
+}
+
+/// Returns the number of bytes `export_database` writes without compression.
+pub(crate) fn exported_len<T: Transaction>(txn: &T, db: Database) -> Result<u64, StorageError> {
+    let mut len = (MAGIC.len() + 2 + std::mem::size_of::<u32>()) as u64;
+    for item in RawIterator::new(txn.open_ro_cursor(db)?, std::ops::Bound::Unbounded, true)? {
+        let (key, value) = item?;
+        len += (2 * std::mem::size_of::<u32>() + key.len() + value.len()) as u64;
+    }
+    Ok(len)
+}
+
+/// Puts all pairs read from `reader` into `db`, overwriting existing keys, and returns the number of pairs read.
+pub fn import_database(
+    txn: &mut RwTransaction,
+    db: Database,
+    mut reader: impl Read,
+) -> Result<u64, StorageError> {
+    let mut magic = [0; MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(StorageError::InvalidExport("invalid magic".to_string()));
+    }
+    let mut header = [0; 2];
+    reader.read_exact(&mut header)?;
+    let [version, compression] = header;
+    if version != VERSION {
+        return Err(StorageError::InvalidExport(format!(
+            "unsupported version {version}"
+        )));
+    }
+    match compression {
+        NO_COMPRESSION => read_records(txn, db, reader),
+        #[cfg(feature = "zstd")]
+        ZSTD_COMPRESSION => read_records(txn, db, zstd::Decoder::new(reader)?),
+        #[cfg(not(feature = "zstd"))]
+        ZSTD_COMPRESSION => Err(StorageError::InvalidExport(
+            "zstd compressed exports require the `zstd` feature".to_string(),
+        )),
+        other => Err(StorageError::InvalidExport(format!(
+            "unknown compression {other}"
+        ))),
+    }
+}
+
+fn write_records<T: Transaction>(
+    txn: &T,
+    db: Database,
+    writer: impl Write,
+) -> Result<u64, StorageError> {
+    let mut writer = BufWriter::new(writer);
+    let mut count = 0;
+    for item in RawIterator::new(txn.open_ro_cursor(db)?, std::ops::Bound::Unbounded, true)? {
+        let (key, value) = item?;
+        write_bytes(&mut writer, key)?;
+        write_bytes(&mut writer, value)?;
+        count += 1;
+    }
+    writer.write_all(&END.to_be_bytes())?;
+    writer.flush()?;
+    Ok(count)
+}
+
+fn write_bytes(writer: &mut impl Write, bytes: &[u8]) -> Result<(), StorageError> {
+    let len = u32::try_from(bytes.len())
+        .ok()
+        .filter(|len| *len != END)
+        .ok_or_else(|| StorageError::InvalidExport(format!("{} bytes is too long", bytes.len())))?;
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(bytes)?;
+    Ok(())
+}
+
+fn read_records(
+    txn: &mut RwTransaction,
+    db: Database,
+    reader: impl Read,
+) -> Result<u64, StorageError> {
+    let mut reader = BufReader::new(reader);
+    let mut count = 0;
+    let (mut key, mut value) = (vec![], vec![]);
+    loop {
+        let key_len = read_len(&mut reader)?;
+        if key_len == END {
+            return Ok(count);
+        }
+        read_bytes(&mut reader, key_len, &mut key)?;
+        let value_len = read_len(&mut reader)?;
+        read_bytes(&mut reader, value_len, &mut value)?;
+        txn.put(db, &key, &value, WriteFlags::empty())?;
+        count += 1;
+    }
+}
+
+fn read_len(reader: &mut impl Read) -> Result<u32, StorageError> {
+    let mut len = [0; 4];
+    reader.read_exact(&mut len)?;
+    Ok(u32::from_be_bytes(len))
+}
+
+/// Reads `len` bytes into `buf`. The buffer only grows as bytes are read, so a corrupted length can't allocate more than the input.
+fn read_bytes(reader: &mut impl Read, len: u32, buf: &mut Vec<u8>) -> Result<(), StorageError> {
+    buf.clear();
+    let read = reader.take(len as u64).read_to_end(buf)?;
+    if read != len as usize {
+        return Err(StorageError::InvalidExport(format!(
+            "expected {len} bytes, but the input ended after {read}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use crate::{
+        lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions},
+        LmdbMap,
+    };
+
+    use super::*;
+
+    fn export_and_import(compression: Compression) {
+        let temp_dir = TempDir::new("test_export_import").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let from = LmdbMap::<u64, str>::new_from_env(&mut env, Some("from"), true).unwrap();
+        let to = LmdbMap::<u64, str>::new_from_env(&mut env, Some("to"), true).unwrap();
+
+        let mut txn = env.begin_rw_txn().unwrap();
+        from.extend(&mut txn, [(&1, "a"), (&2, ""), (&3, "ccc")])
+            .unwrap();
+        let mut exported = vec![];
+        assert_eq!(from.export(&txn, &mut exported, compression).unwrap(), 3);
+
+        assert!(to.insert(&mut txn, &1, "old").unwrap());
+        assert_eq!(to.import(&mut txn, exported.as_slice()).unwrap(), 3);
+        assert_eq!(to.count(&txn).unwrap(), 3);
+        assert_eq!(to.get(&txn, &1).unwrap().unwrap(), "a");
+        assert_eq!(to.get(&txn, &2).unwrap().unwrap(), "");
+        assert_eq!(to.get(&txn, &3).unwrap().unwrap(), "ccc");
+
+        exported.truncate(exported.len() - 1);
+        assert!(matches!(
+            to.import(&mut txn, exported.as_slice()),
+            Err(StorageError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_export_import() {
+        export_and_import(Compression::None);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_export_import_zstd() {
+        export_and_import(Compression::Zstd { level: 3 });
+    }
+
+    #[test]
+    fn test_import_invalid_magic() {
+        let temp_dir = TempDir::new("test_import_invalid_magic").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let map = LmdbMap::<u64, str>::new_from_env(&mut env, None, true).unwrap();
+        let mut txn = env.begin_rw_txn().unwrap();
+        assert!(matches!(
+            map.import(&mut txn, b"NOTDOZER\x01\x00".as_slice()),
+            Err(StorageError::InvalidExport(_))
+        ));
+    }
+
+    #[test]
+    fn test_import_truncated_length() {
+        let temp_dir = TempDir::new("test_import_truncated_length").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let map = LmdbMap::<u64, str>::new_from_env(&mut env, None, true).unwrap();
+        let mut txn = env.begin_rw_txn().unwrap();
+        let mut input = b"DOZEREXP\x01\x00".to_vec();
+        // A key declared almost 4 GB long, followed by only 3 bytes.
+        input.extend_from_slice(&(u32::MAX - 1).to_be_bytes());
+        input.extend_from_slice(b"key");
+        assert!(matches!(
+            map.import(&mut txn, input.as_slice()),
+            Err(StorageError::InvalidExport(_))
+        ));
+    }
+}
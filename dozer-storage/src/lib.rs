@@ -1,20 +1,49 @@
+pub mod backend;
 pub mod common;
+pub mod comparator;
 pub mod errors;
+pub mod export;
 pub mod lmdb_storage;
+pub mod migration;
 pub mod prefix_transaction;
+pub mod retry;
+pub mod verify;
 
 mod lmdb_database;
+mod observer;
 pub use lmdb_database::{
-    Decode, Encode, Encoded, Iterator, KeyIterator, LmdbDupValue, LmdbKey, LmdbValType, LmdbValue,
-    ValueIterator,
+    join_iter, verify_checksum, BorrowDecode, Checksummed, CompositeKey, CompositeKeyComponents,
+    Decode, DupValueIterator, Encode, Encoded, Iterator, JoinIterator, Joined, KeyIterator,
+    LmdbCursor, LmdbDupValue, LmdbKey, LmdbValType, LmdbValue, ValueIterator,
 };
+pub use observer::StorageObserver;
+mod async_lmdb_map;
+pub use async_lmdb_map::AsyncLmdbMap;
+mod lmdb_counter;
+pub use lmdb_counter::LmdbCounter;
 mod lmdb_map;
-pub use lmdb_map::LmdbMap;
+pub use lmdb_map::{LmdbMap, ObservedLmdbMap};
 mod lmdb_multimap;
 pub use lmdb_multimap::LmdbMultimap;
+mod lmdb_overflow_map;
+pub use lmdb_overflow_map::LmdbOverflowMap;
+mod lmdb_queue;
+pub use lmdb_queue::LmdbQueue;
+mod lmdb_set;
+pub use lmdb_set::LmdbSet;
+mod lmdb_ttl_map;
+pub use lmdb_ttl_map::LmdbTtlMap;
+mod read_txn_pool;
+mod snapshot;
+pub use read_txn_pool::PooledRoTransaction;
+mod write_batch;
+pub use write_batch::WriteBatch;
 
 #[cfg(test)]
 mod tests;
 
+extern crate self as dozer_storage;
+pub use dozer_storage_derive::LmdbVal;
+
 pub use lmdb;
 pub use lmdb_sys;
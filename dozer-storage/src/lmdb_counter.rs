@@ -0,0 +1,127 @@
+use lmdb::{Database, RwTransaction, Transaction, WriteFlags};
+
+use crate::{
+    errors::StorageError,
+    lmdb_storage::{LmdbEnvironmentManager, LmdbExclusiveTransaction},
+    Encode, LmdbMap,
+};
+
+/// A database of named `u64` counters.
+///
+/// Counters that have never been set read as `0`.
+#[derive(Debug, Clone, Copy)]
+pub struct LmdbCounter {
+    map: LmdbMap<str, u64>,
+}
+
+impl LmdbCounter {
+    pub fn new_from_env(
+        env: &mut LmdbEnvironmentManager,
+        name: Option<&str>,
+        create_if_not_exist: bool,
+    ) -> Result<Self, StorageError> {
+        let map = LmdbMap::new_from_env(env, name, create_if_not_exist)?;
+        Ok(Self { map })
+    }
+
+    pub fn new_from_txn(
+        txn: &mut LmdbExclusiveTransaction,
+        name: Option<&str>,
+        create_if_not_exist: bool,
+    ) -> Result<Self, StorageError> {
+        let map = LmdbMap::new_from_txn(txn, name, create_if_not_exist)?;
+        Ok(Self { map })
+    }
+
+    pub fn database(&self) -> Database {
+        self.map.database()
+    }
+
+    pub fn get<T: Transaction>(&self, txn: &T, counter: &str) -> Result<u64, StorageError> {
+        Ok(self
+            .map
+            .get(txn, counter)?
+            .map(|value| value.into_owned())
+            .unwrap_or(0))
+    }
+
+    pub fn set(
+        &self,
+        txn: &mut RwTransaction,
+        counter: &str,
+        value: u64,
+    ) -> Result<(), StorageError> {
+        let key = counter.encode()?;
+        let value = value.encode()?;
+        txn.put(self.map.database(), &key, &value, WriteFlags::empty())
+            .map_err(Into::into)
+    }
+
+    /// Increments the counter by one and returns the value before incrementing.
+    ///
+    /// Fails with `CounterOverflow` if the counter is `u64::MAX`, leaving it unchanged.
+    pub fn increment(&self, txn: &mut RwTransaction, counter: &str) -> Result<u64, StorageError> {
+        let value = self.get(txn, counter)?;
+        let next = value
+            .checked_add(1)
+            .ok_or_else(|| StorageError::CounterOverflow(counter.to_string()))?;
+        self.set(txn, counter, next)?;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions};
+
+    use super::*;
+
+    use tempdir::TempDir;
+
+    #[test]
+    fn test_lmdb_counter() {
+        let temp_dir = TempDir::new("test_lmdb_counter").unwrap();
+        let env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+
+        let counter = LmdbCounter::new_from_txn(&mut txn, None, true).unwrap();
+        assert_eq!(counter.get(txn.txn(), "a").unwrap(), 0);
+        assert_eq!(counter.increment(txn.txn_mut(), "a").unwrap(), 0);
+        assert_eq!(counter.increment(txn.txn_mut(), "a").unwrap(), 1);
+        assert_eq!(counter.get(txn.txn(), "a").unwrap(), 2);
+        assert_eq!(counter.get(txn.txn(), "b").unwrap(), 0);
+
+        counter.set(txn.txn_mut(), "b", 10).unwrap();
+        assert_eq!(counter.increment(txn.txn_mut(), "b").unwrap(), 10);
+        assert_eq!(counter.get(txn.txn(), "b").unwrap(), 11);
+        assert_eq!(counter.get(txn.txn(), "a").unwrap(), 2);
+    }
+
+    #[test]
+    fn test_lmdb_counter_overflow() {
+        let temp_dir = TempDir::new("test_lmdb_counter_overflow").unwrap();
+        let env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+
+        let counter = LmdbCounter::new_from_txn(&mut txn, None, true).unwrap();
+        counter.set(txn.txn_mut(), "a", u64::MAX - 1).unwrap();
+        assert_eq!(counter.increment(txn.txn_mut(), "a").unwrap(), u64::MAX - 1);
+        assert!(matches!(
+            counter.increment(txn.txn_mut(), "a"),
+            Err(StorageError::CounterOverflow(name)) if name == "a"
+        ));
+        assert_eq!(counter.get(txn.txn(), "a").unwrap(), u64::MAX);
+    }
+}
@@ -0,0 +1,89 @@
+use std::borrow::{Borrow, Cow};
+
+use crate::errors::StorageError;
+
+use super::{Decode, Encode, Encoded};
+
+const CHECKSUM_SIZE: usize = 4;
+
+/// A value stored with a CRC32 checksum of its encoding appended, which is verified on decode.
+///
+/// Use `Checksummed<V>` instead of `V` as the value type of a map to detect corrupted values, at the cost of 4 bytes per value
+/// and an allocation per decode.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Checksummed<V: ToOwned + ?Sized>(pub V::Owned);
+
+impl<V: ToOwned + ?Sized> Clone for Checksummed<V>
+where
+    V::Owned: Clone,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<V: Encode + ToOwned + ?Sized> Encode for Checksummed<V> {
+    fn encode(&self) -> Result<Encoded, StorageError> {
+        let value: &V = self.0.borrow();
+        let encoded = value.encode()?;
+        let mut bytes = Vec::with_capacity(encoded.as_ref().len() + CHECKSUM_SIZE);
+        bytes.extend_from_slice(encoded.as_ref());
+        bytes.extend_from_slice(&crc32fast::hash(encoded.as_ref()).to_be_bytes());
+        Ok(Encoded::Vec(bytes))
+    }
+}
+
+impl<V: Decode + ?Sized> Decode for Checksummed<V>
+where
+    V::Owned: Clone,
+{
+    fn decode(bytes: &[u8]) -> Result<Cow<Self>, StorageError> {
+        let payload = verify_checksum(bytes)?;
+        Ok(Cow::Owned(Self(V::decode(payload)?.into_owned())))
+    }
+}
+
+/// Verifies the checksum appended by `Checksummed` and returns the bytes before it.
+pub fn verify_checksum(bytes: &[u8]) -> Result<&[u8], StorageError> {
+    if bytes.len() < CHECKSUM_SIZE {
+        return Err(StorageError::DeserializationError {
+            typ: "Checksummed",
+            reason: format!(
+                "value of {} bytes is too short to hold a checksum",
+                bytes.len()
+            )
+            .into(),
+        });
+    }
+    let (payload, checksum) = bytes.split_at(bytes.len() - CHECKSUM_SIZE);
+    let expected = u32::from_be_bytes(checksum.try_into().unwrap());
+    let actual = crc32fast::hash(payload);
+    if expected == actual {
+        Ok(payload)
+    } else {
+        Err(StorageError::ChecksumMismatch { expected, actual })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksummed() {
+        let value = Checksummed::<str>("value".to_string());
+        let mut encoded = value.encode().unwrap().as_ref().to_vec();
+        assert_eq!(encoded.len(), "value".len() + CHECKSUM_SIZE);
+        assert_eq!(
+            Checksummed::<str>::decode(&encoded).unwrap().into_owned(),
+            value
+        );
+
+        encoded[0] ^= 1;
+        assert!(matches!(
+            Checksummed::<str>::decode(&encoded),
+            Err(StorageError::ChecksumMismatch { .. })
+        ));
+        assert!(Checksummed::<str>::decode(&[0; 3]).is_err());
+    }
+}
@@ -0,0 +1,184 @@
+use std::borrow::Cow;
+
+use crate::errors::StorageError;
+
+use super::{Decode, Encode, Encoded, LmdbKey, LmdbValType};
+
+const ESCAPE: u8 = 0x00;
+const ESCAPED_ZERO: u8 = 0xFF;
+const TERMINATOR: u8 = 0x01;
+
+/// A key made of multiple components, whose byte-wise order equals the tuple order of the components.
+///
+/// Every component is encoded with its `Encode` implementation, so components must use order-preserving encodings
+/// (e.g. big-endian integers, strings and bytes). In the encoded component, `0x00` is escaped as `0x00 0xFF`,
+/// and the component is terminated by `0x00 0x01`, which sorts before any continuation of the component.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CompositeKey {
+    bytes: Vec<u8>,
+}
+
+impl CompositeKey {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps bytes previously returned by `as_bytes` or `into_bytes`.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self { bytes }
+    }
+
+    /// Appends a component.
+    pub fn push<K: LmdbKey + ?Sized>(&mut self, component: &K) -> Result<(), StorageError> {
+        let component = component.encode()?;
+        for byte in component.as_ref() {
+            if *byte == ESCAPE {
+                self.bytes.extend_from_slice(&[ESCAPE, ESCAPED_ZERO]);
+            } else {
+                self.bytes.push(*byte);
+            }
+        }
+        self.bytes.extend_from_slice(&[ESCAPE, TERMINATOR]);
+        Ok(())
+    }
+
+    /// Appends a component and returns `self`, for chaining.
+    pub fn with<K: LmdbKey + ?Sized>(mut self, component: &K) -> Result<Self, StorageError> {
+        self.push(component)?;
+        Ok(self)
+    }
+
+    /// Returns a key that all keys starting with the components of this key are greater than or equal to.
+    ///
+    /// This is the key itself. It's the lower bound of a prefix scan with `LmdbCursor::seek_prefix`.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Iterates the encoded components.
+    pub fn components(&self) -> CompositeKeyComponents {
+        CompositeKeyComponents { bytes: &self.bytes }
+    }
+
+    /// Decodes the component at `index`.
+    pub fn get<K: Decode + ?Sized>(&self, index: usize) -> Result<Option<K::Owned>, StorageError> {
+        let Some(component) = self.components().nth(index) else {
+            return Ok(None);
+        };
+        let component = component?;
+        Ok(Some(K::decode(&component)?.into_owned()))
+    }
+}
+
+pub struct CompositeKeyComponents<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> std::iter::Iterator for CompositeKeyComponents<'a> {
+    type Item = Result<Cow<'a, [u8]>, StorageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+
+        let mut component = Cow::Borrowed(&[][..]);
+        let mut index = 0;
+        loop {
+            let Some(escape) = self.bytes[index..].iter().position(|byte| *byte == ESCAPE) else {
+                self.bytes = &[];
+                return Some(Err(invalid_composite_key("missing terminator")));
+            };
+            let escape = index + escape;
+            let chunk = &self.bytes[index..escape];
+            match self.bytes.get(escape + 1) {
+                Some(&TERMINATOR) => {
+                    if index == 0 {
+                        component = Cow::Borrowed(chunk);
+                    } else {
+                        component.to_mut().extend_from_slice(chunk);
+                    }
+                    self.bytes = &self.bytes[escape + 2..];
+                    return Some(Ok(component));
+                }
+                Some(&ESCAPED_ZERO) => {
+                    let component = component.to_mut();
+                    component.extend_from_slice(chunk);
+                    component.push(ESCAPE);
+                    index = escape + 2;
+                }
+                _ => {
+                    self.bytes = &[];
+                    return Some(Err(invalid_composite_key("invalid escape sequence")));
+                }
+            }
+        }
+    }
+}
+
+fn invalid_composite_key(reason: &str) -> StorageError {
+    StorageError::InvalidKey(format!("Invalid composite key: {reason}"))
+}
+
+impl Encode for CompositeKey {
+    fn encode(&self) -> Result<Encoded, StorageError> {
+        Ok(Encoded::Borrowed(&self.bytes))
+    }
+}
+
+impl Decode for CompositeKey {
+    fn decode(bytes: &[u8]) -> Result<Cow<Self>, StorageError> {
+        Ok(Cow::Owned(Self::from_bytes(bytes.to_vec())))
+    }
+}
+
+unsafe impl LmdbKey for CompositeKey {
+    const TYPE: LmdbValType = LmdbValType::VariableSize;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(first: &str, second: i64) -> CompositeKey {
+        CompositeKey::new()
+            .with(first)
+            .unwrap()
+            .with(&second)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_composite_key_order() {
+        let keys = [
+            key("", i64::MIN),
+            key("", 0),
+            key("\0", -1),
+            key("\0\0", -1),
+            key("a", 1),
+            key("a\0", 0),
+            key("a\0b", 0),
+            key("ab", -1),
+            key("b", 0),
+        ];
+        for window in keys.windows(2) {
+            assert!(window[0].as_bytes() < window[1].as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_composite_key_components() {
+        let key = key("a\0b", -2);
+        assert_eq!(key.get::<str>(0).unwrap().unwrap(), "a\0b");
+        assert_eq!(key.get::<i64>(1).unwrap(), Some(-2));
+        assert_eq!(key.get::<i64>(2).unwrap(), None);
+        assert_eq!(key.components().count(), 2);
+
+        let key = CompositeKey::from_bytes(vec![b'a', 0x00]);
+        assert!(key.components().next().unwrap().is_err());
+    }
+}
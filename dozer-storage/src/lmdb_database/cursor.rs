@@ -0,0 +1,160 @@
+use std::borrow::Cow;
+
+use lmdb::Cursor;
+use lmdb_sys::{MDB_FIRST, MDB_GET_CURRENT, MDB_LAST, MDB_NEXT, MDB_PREV, MDB_SET, MDB_SET_RANGE};
+
+use crate::{errors::StorageError, Decode, Encode};
+
+use super::iterator::{decode_key, decode_value};
+
+/// A typed wrapper around a LMDB cursor.
+///
+/// All positioning methods return if the cursor is positioned at an entry after the operation.
+pub struct LmdbCursor<'txn, C: Cursor<'txn>, K: ?Sized, V: ?Sized> {
+    inner: C,
+    _txn: std::marker::PhantomData<&'txn ()>,
+    _key: std::marker::PhantomData<*const K>,
+    _value: std::marker::PhantomData<*const V>,
+}
+
+impl<'txn, C: Cursor<'txn>, K: ?Sized, V: ?Sized> LmdbCursor<'txn, C, K, V> {
+    pub fn new(cursor: C) -> Self {
+        Self {
+            inner: cursor,
+            _txn: std::marker::PhantomData,
+            _key: std::marker::PhantomData,
+            _value: std::marker::PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    pub fn first(&self) -> Result<bool, StorageError> {
+        self.position(None, MDB_FIRST)
+    }
+
+    pub fn last(&self) -> Result<bool, StorageError> {
+        self.position(None, MDB_LAST)
+    }
+
+    pub fn next(&self) -> Result<bool, StorageError> {
+        self.position(None, MDB_NEXT)
+    }
+
+    pub fn prev(&self) -> Result<bool, StorageError> {
+        self.position(None, MDB_PREV)
+    }
+
+    /// Positions the cursor at the first key that starts with `prefix`.
+    ///
+    /// The prefix is matched against the encoded key, so this is only meaningful for databases that compare keys byte-wise.
+    pub fn seek_prefix(&self, prefix: &[u8]) -> Result<bool, StorageError> {
+        match self.inner.get(Some(prefix), None, MDB_SET_RANGE) {
+            Ok((key, _)) => Ok(key
+                .expect("Key should always be `Some` unless `op` is `MDB_SET`")
+                .starts_with(prefix)),
+            Err(lmdb::Error::NotFound) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn position(&self, key: Option<&[u8]>, op: u32) -> Result<bool, StorageError> {
+        match self.inner.get(key, None, op) {
+            Ok(_) => Ok(true),
+            Err(lmdb::Error::NotFound) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl<'txn, C: Cursor<'txn>, K: Encode + ?Sized, V: ?Sized> LmdbCursor<'txn, C, K, V> {
+    /// Positions the cursor at `key`.
+    pub fn seek_exact(&self, key: &K) -> Result<bool, StorageError> {
+        let key = key.encode()?;
+        self.position(Some(key.as_ref()), MDB_SET)
+    }
+
+    /// Positions the cursor at the first key greater than or equal to `key`.
+    pub fn seek_ge(&self, key: &K) -> Result<bool, StorageError> {
+        let key = key.encode()?;
+        self.position(Some(key.as_ref()), MDB_SET_RANGE)
+    }
+}
+
+impl<'txn, C: Cursor<'txn>, K: Decode + 'txn + ?Sized, V: Decode + 'txn + ?Sized>
+    LmdbCursor<'txn, C, K, V>
+{
+    /// Returns the entry at current cursor position.
+    ///
+    /// The cursor must have been positioned by a successful positioning method.
+    #[allow(clippy::type_complexity)]
+    pub fn read(&self) -> Result<Option<(Cow<'txn, K>, Cow<'txn, V>)>, StorageError> {
+        match self.inner.get(None, None, MDB_GET_CURRENT) {
+            Ok((key, value)) => {
+                let key = key.expect("MDB_GET_CURRENT should always return some data when found");
+                Ok(Some((decode_key(key)?, decode_value(value)?)))
+            }
+            Err(lmdb::Error::NotFound) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use crate::{
+        lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions},
+        LmdbMap,
+    };
+
+    #[test]
+    fn test_lmdb_cursor() {
+        let temp_dir = TempDir::new("test_lmdb_cursor").unwrap();
+        let env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+
+        let map = LmdbMap::<str, u64>::new_from_txn(&mut txn, None, true).unwrap();
+        map.extend(txn.txn_mut(), [("aa", &1), ("ab", &2), ("ba", &3)])
+            .unwrap();
+
+        let cursor = map.cursor(txn.txn()).unwrap();
+        let read = || {
+            cursor
+                .read()
+                .unwrap()
+                .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        };
+        assert!(cursor.seek_exact("ab").unwrap());
+        assert_eq!(read(), Some(("ab".to_string(), 2)));
+        assert!(!cursor.seek_exact("b").unwrap());
+
+        assert!(cursor.seek_ge("b").unwrap());
+        assert_eq!(read(), Some(("ba".to_string(), 3)));
+        assert!(!cursor.seek_ge("c").unwrap());
+
+        assert!(cursor.seek_prefix(b"a").unwrap());
+        assert_eq!(read(), Some(("aa".to_string(), 1)));
+        assert!(cursor.next().unwrap());
+        assert_eq!(read(), Some(("ab".to_string(), 2)));
+        assert!(cursor.prev().unwrap());
+        assert_eq!(read(), Some(("aa".to_string(), 1)));
+        assert!(!cursor.prev().unwrap());
+        assert!(!cursor.seek_prefix(b"bb").unwrap());
+
+        assert!(cursor.last().unwrap());
+        assert_eq!(read(), Some(("ba".to_string(), 3)));
+        assert!(!cursor.next().unwrap());
+        assert!(cursor.first().unwrap());
+        assert_eq!(read(), Some(("aa".to_string(), 1)));
+    }
+}
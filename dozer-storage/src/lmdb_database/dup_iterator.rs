@@ -0,0 +1,132 @@
+use std::{borrow::Cow, ffi::c_void, ops::Bound};
+
+use lmdb::{Cursor, Database, Transaction};
+use lmdb_sys::{MDB_GET_BOTH_RANGE, MDB_NEXT_DUP, MDB_SET};
+
+use crate::{errors::StorageError, Decode, Encode};
+
+use super::iterator::decode_value;
+
+/// Iterates the values of a single key in a `DUP_SORT` database, in ascending order.
+pub struct DupValueIterator<'txn, C: Cursor<'txn>, V: ?Sized> {
+    cursor: C,
+    txn: *mut lmdb_sys::MDB_txn,
+    db: Database,
+    first: Option<&'txn [u8]>,
+    started: bool,
+    done: bool,
+    end: Bound<Vec<u8>>,
+    _value: std::marker::PhantomData<*const V>,
+}
+
+impl<'txn, C: Cursor<'txn>, V: Encode + ?Sized> DupValueIterator<'txn, C, V> {
+    pub fn new<T: Transaction>(
+        txn: &'txn T,
+        db: Database,
+        cursor: C,
+        key: &[u8],
+        value_bounds: (Bound<&V>, Bound<&V>),
+    ) -> Result<Self, StorageError> {
+        let (start, end) = value_bounds;
+        let mut iter = Self {
+            cursor,
+            txn: txn.txn(),
+            db,
+            first: None,
+            started: false,
+            done: false,
+            end: match end {
+                Bound::Included(value) => Bound::Included(value.encode()?.as_ref().to_vec()),
+                Bound::Excluded(value) => Bound::Excluded(value.encode()?.as_ref().to_vec()),
+                Bound::Unbounded => Bound::Unbounded,
+            },
+            _value: std::marker::PhantomData,
+        };
+
+        iter.first = match start {
+            Bound::Included(value) => {
+                let value = value.encode()?;
+                iter.get(Some(key), Some(value.as_ref()), MDB_GET_BOTH_RANGE)?
+            }
+            Bound::Excluded(value) => {
+                let value = value.encode()?;
+                match iter.get(Some(key), Some(value.as_ref()), MDB_GET_BOTH_RANGE)? {
+                    Some(hit) if iter.compare(hit, value.as_ref()).is_eq() => {
+                        // Hit equal value, get next.
+                        iter.get(None, None, MDB_NEXT_DUP)?
+                    }
+                    hit => hit,
+                }
+            }
+            Bound::Unbounded => iter.get(Some(key), None, MDB_SET)?,
+        };
+        Ok(iter)
+    }
+}
+
+impl<'txn, C: Cursor<'txn>, V: ?Sized> DupValueIterator<'txn, C, V> {
+    fn get(
+        &self,
+        key: Option<&[u8]>,
+        value: Option<&[u8]>,
+        op: u32,
+    ) -> Result<Option<&'txn [u8]>, lmdb::Error> {
+        match self.cursor.get(key, value, op) {
+            Ok((_, value)) => Ok(Some(value)),
+            Err(lmdb::Error::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn compare(&self, a: &[u8], b: &[u8]) -> std::cmp::Ordering {
+        let a = lmdb_sys::MDB_val {
+            mv_size: a.len(),
+            mv_data: a.as_ptr() as *mut c_void,
+        };
+        let b = lmdb_sys::MDB_val {
+            mv_size: b.len(),
+            mv_data: b.as_ptr() as *mut c_void,
+        };
+        // SAFETY: `self.txn` is alive for `'txn`.
+        let result = unsafe { lmdb_sys::mdb_dcmp(self.txn, self.db.dbi(), &a, &b) };
+        result.cmp(&0)
+    }
+
+    fn in_range(&self, value: &[u8]) -> bool {
+        match &self.end {
+            Bound::Included(end) => self.compare(value, end).is_le(),
+            Bound::Excluded(end) => self.compare(value, end).is_lt(),
+            Bound::Unbounded => true,
+        }
+    }
+}
+
+impl<'txn, C: Cursor<'txn>, V: Decode + 'txn + ?Sized> std::iter::Iterator
+    for DupValueIterator<'txn, C, V>
+{
+    type Item = Result<Cow<'txn, V>, StorageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let value = if self.started {
+            match self.get(None, None, MDB_NEXT_DUP) {
+                Ok(value) => value,
+                Err(e) => return Some(Err(e.into())),
+            }
+        } else {
+            self.started = true;
+            self.first.take()
+        };
+
+        match value {
+            Some(value) if self.in_range(value) => Some(decode_value(value)),
+            _ => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
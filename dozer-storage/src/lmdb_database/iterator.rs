@@ -1,3 +1,10 @@
+//! Iterators over LMDB databases.
+//!
+//! Items borrow from the memory map for the lifetime of the transaction, not from the cursor, so they stay valid after
+//! the next call. That allows peeking ahead, as `join_iter` does, and the `std::iter::Iterator` adapters, which a
+//! lending iterator tied to the cursor would rule out. Keys and values that decode by borrowing, like `[u8]` and `str`,
+//! are not copied.
+
 use std::{borrow::Cow, ops::Bound};
 
 use lmdb::Cursor;
@@ -6,6 +13,7 @@ use crate::{errors::StorageError, Decode, Encode, Encoded};
 
 use super::raw_iterator::RawIterator;
 
+/// Iterates the keys of a database.
 pub struct KeyIterator<'txn, C: Cursor<'txn>, K: ?Sized> {
     inner: RawIterator<'txn, C>,
     _key: std::marker::PhantomData<*const K>,
@@ -38,7 +46,9 @@ impl<'txn, C: Cursor<'txn>, K: Encode + ?Sized> KeyIterator<'txn, C, K> {
     }
 }
 
-fn decode_key<'a, K: Decode + 'a + ?Sized>(key: &'a [u8]) -> Result<Cow<'a, K>, StorageError> {
+pub(super) fn decode_key<'a, K: Decode + 'a + ?Sized>(
+    key: &'a [u8],
+) -> Result<Cow<'a, K>, StorageError> {
     let key = K::decode(key).map_err(|e| StorageError::DeserializationError {
         typ: "Iterator::K",
         reason: Box::new(e),
@@ -59,6 +69,7 @@ impl<'txn, C: Cursor<'txn>, K: Decode + 'txn + ?Sized> std::iter::Iterator
     }
 }
 
+/// Iterates the values of a database. Items borrow from the transaction like in `KeyIterator`.
 pub struct ValueIterator<'txn, C: Cursor<'txn>, V: ?Sized> {
     inner: RawIterator<'txn, C>,
     _value: std::marker::PhantomData<*const V>,
@@ -79,7 +90,9 @@ impl<'txn, C: Cursor<'txn>, V: ?Sized> ValueIterator<'txn, C, V> {
     }
 }
 
-fn decode_value<'a, V: Decode + 'a + ?Sized>(value: &'a [u8]) -> Result<Cow<'a, V>, StorageError> {
+pub(super) fn decode_value<'a, V: Decode + 'a + ?Sized>(
+    value: &'a [u8],
+) -> Result<Cow<'a, V>, StorageError> {
     let value = V::decode(value).map_err(|e| StorageError::DeserializationError {
         typ: "Iterator::V",
         reason: Box::new(e),
@@ -100,6 +113,7 @@ impl<'txn, C: Cursor<'txn>, V: Decode + 'txn + ?Sized> std::iter::Iterator
     }
 }
 
+/// Iterates the key-value pairs of a database. Items borrow from the transaction like in `KeyIterator`.
 pub struct Iterator<'txn, C: Cursor<'txn>, K: ?Sized, V: ?Sized> {
     inner: RawIterator<'txn, C>,
     _key: std::marker::PhantomData<*const K>,
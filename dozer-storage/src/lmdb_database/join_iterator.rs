@@ -0,0 +1,192 @@
+use std::{cmp::Ordering, ops::Bound};
+
+use lmdb::{Database, RoCursor, Transaction};
+
+use crate::{errors::StorageError, verify};
+
+use super::raw_iterator::RawIterator;
+
+type KeyValuePair<'txn> = (&'txn [u8], &'txn [u8]);
+
+/// An item of `JoinIterator`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Joined<'txn> {
+    /// The key only exists in the left database.
+    Left { key: &'txn [u8], value: &'txn [u8] },
+    /// The key only exists in the right database.
+    Right { key: &'txn [u8], value: &'txn [u8] },
+    /// The key exists in both databases.
+    Both {
+        key: &'txn [u8],
+        left: &'txn [u8],
+        right: &'txn [u8],
+    },
+}
+
+impl<'txn> Joined<'txn> {
+    pub fn key(&self) -> &'txn [u8] {
+        match self {
+            Joined::Left { key, .. } | Joined::Right { key, .. } | Joined::Both { key, .. } => *key,
+        }
+    }
+}
+
+/// Merge-iterates two databases by key in ascending order, see `join_iter`.
+pub struct JoinIterator<'txn, T: Transaction> {
+    txn: &'txn T,
+    db: Database,
+    left: RawIterator<'txn, RoCursor<'txn>>,
+    right: RawIterator<'txn, RoCursor<'txn>>,
+    left_item: Option<KeyValuePair<'txn>>,
+    right_item: Option<KeyValuePair<'txn>>,
+}
+
+/// Merge-iterates `left` and `right` by key in one transaction, yielding keys that exist in either or both of them.
+///
+/// Keys are compared with the comparison function of `left`, so both databases must order keys in the same way.
+/// `DUP_SORT` databases are not supported.
+pub fn join_iter<T: Transaction>(
+    txn: &T,
+    left: Database,
+    right: Database,
+) -> Result<JoinIterator<'_, T>, StorageError> {
+    let mut left_iter = RawIterator::new(txn.open_ro_cursor(left)?, Bound::Unbounded, true)?;
+    let mut right_iter = RawIterator::new(txn.open_ro_cursor(right)?, Bound::Unbounded, true)?;
+    Ok(JoinIterator {
+        txn,
+        db: left,
+        left_item: left_iter.next().transpose()?,
+        right_item: right_iter.next().transpose()?,
+        left: left_iter,
+        right: right_iter,
+    })
+}
+
+impl<'txn, T: Transaction> JoinIterator<'txn, T> {
+    fn next_item(&mut self) -> Result<Option<Joined<'txn>>, StorageError> {
+        let item = match (self.left_item, self.right_item) {
+            (None, None) => return Ok(None),
+            (Some((key, value)), None) => {
+                self.left_item = self.left.next().transpose()?;
+                Joined::Left { key, value }
+            }
+            (None, Some((key, value))) => {
+                self.right_item = self.right.next().transpose()?;
+                Joined::Right { key, value }
+            }
+            (Some((left_key, left)), Some((right_key, right))) => {
+                match verify::compare(self.txn, self.db, left_key, right_key, lmdb_sys::mdb_cmp) {
+                    Ordering::Less => {
+                        self.left_item = self.left.next().transpose()?;
+                        Joined::Left {
+                            key: left_key,
+                            value: left,
+                        }
+                    }
+                    Ordering::Greater => {
+                        self.right_item = self.right.next().transpose()?;
+                        Joined::Right {
+                            key: right_key,
+                            value: right,
+                        }
+                    }
+                    Ordering::Equal => {
+                        self.left_item = self.left.next().transpose()?;
+                        self.right_item = self.right.next().transpose()?;
+                        Joined::Both {
+                            key: left_key,
+                            left,
+                            right,
+                        }
+                    }
+                }
+            }
+        };
+        Ok(Some(item))
+    }
+}
+
+impl<'txn, T: Transaction> std::iter::Iterator for JoinIterator<'txn, T> {
+    type Item = Result<Joined<'txn>, StorageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_item() {
+            Ok(item) => item.map(Ok),
+            Err(e) => {
+                self.left_item = None;
+                self.right_item = None;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lmdb::{DatabaseFlags, WriteFlags};
+    use tempdir::TempDir;
+
+    use crate::lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions};
+
+    use super::*;
+
+    #[test]
+    fn test_join_iter() {
+        let temp_dir = TempDir::new("test_join_iter").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let left = env
+            .create_database(Some("left"), Some(DatabaseFlags::empty()))
+            .unwrap();
+        let right = env
+            .create_database(Some("right"), Some(DatabaseFlags::empty()))
+            .unwrap();
+        let empty = env
+            .create_database(Some("empty"), Some(DatabaseFlags::empty()))
+            .unwrap();
+
+        let mut txn = env.begin_rw_txn().unwrap();
+        for (db, key, value) in [
+            (left, b"a", b"1"),
+            (left, b"b", b"2"),
+            (right, b"b", b"3"),
+            (right, b"c", b"4"),
+            (left, b"d", b"5"),
+        ] {
+            txn.put(db, key, value, WriteFlags::empty()).unwrap();
+        }
+
+        let joined = join_iter(&txn, left, right)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            joined,
+            vec![
+                Joined::Left {
+                    key: b"a",
+                    value: b"1"
+                },
+                Joined::Both {
+                    key: b"b",
+                    left: b"2",
+                    right: b"3"
+                },
+                Joined::Right {
+                    key: b"c",
+                    value: b"4"
+                },
+                Joined::Left {
+                    key: b"d",
+                    value: b"5"
+                },
+            ]
+        );
+
+        assert_eq!(join_iter(&txn, empty, empty).unwrap().count(), 0);
+    }
+}
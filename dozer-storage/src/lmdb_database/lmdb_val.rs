@@ -2,13 +2,14 @@ use std::borrow::Cow;
 
 use dozer_types::{
     node::{NodeHandle, OpIdentifier},
-    types::{IndexDefinition, Record, Schema},
+    types::{legacy, IndexDefinition, Record, RecordBorrow, Schema},
 };
 
 use crate::errors::StorageError;
 
 pub enum Encoded<'a> {
     U8([u8; 1]),
+    U8x2([u8; 2]),
     U8x4([u8; 4]),
     U8x8([u8; 8]),
     U8x16([u8; 16]),
@@ -20,6 +21,7 @@ impl<'a> AsRef<[u8]> for Encoded<'a> {
     fn as_ref(&self) -> &[u8] {
         match self {
             Self::U8(v) => v.as_slice(),
+            Self::U8x2(v) => v.as_slice(),
             Self::U8x4(v) => v.as_slice(),
             Self::U8x8(v) => v.as_slice(),
             Self::U8x16(v) => v.as_slice(),
@@ -37,6 +39,13 @@ pub trait Decode: ToOwned {
     fn decode(bytes: &[u8]) -> Result<Cow<Self>, StorageError>;
 }
 
+/// Types that can be decoded into a view borrowing from the stored bytes, to avoid allocating for large values.
+pub trait BorrowDecode {
+    type Borrowed<'a>;
+
+    fn decode_borrow(bytes: &[u8]) -> Result<Self::Borrowed<'_>, StorageError>;
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum LmdbValType {
     U32,
@@ -54,7 +63,10 @@ pub enum LmdbValType {
 ///
 /// # Note
 ///
-/// The implementation for `u32` and `u64` has a caveat: The values are encoded in big-endian but compared in native-endian.
+/// Keys are compared byte-wise. Integers are encoded in big-endian, so they are ordered numerically. New databases are not
+/// created with `INTEGER_KEY` or `INTEGER_DUP`, which would compare them in native endian. LMDB keeps the flags a
+/// database was created with, so databases that have them must be rebuilt with
+/// `LmdbEnvironmentManager::recreate_with_byte_wise_order` before they are used with byte-wise keys.
 pub unsafe trait LmdbKey: Encode {
     const TYPE: LmdbValType;
 }
@@ -83,6 +95,22 @@ unsafe impl LmdbKey for u8 {
     const TYPE: LmdbValType = LmdbValType::FixedSizeOtherThanU32OrUsize;
 }
 
+impl Encode for u16 {
+    fn encode(&self) -> Result<Encoded, StorageError> {
+        Ok(Encoded::U8x2(self.to_be_bytes()))
+    }
+}
+
+impl Decode for u16 {
+    fn decode(bytes: &[u8]) -> Result<Cow<Self>, StorageError> {
+        Ok(Cow::Owned(u16::from_be_bytes(bytes.try_into().unwrap())))
+    }
+}
+
+unsafe impl LmdbKey for u16 {
+    const TYPE: LmdbValType = LmdbValType::FixedSizeOtherThanU32OrUsize;
+}
+
 impl Encode for u32 {
     fn encode(&self) -> Result<Encoded, StorageError> {
         Ok(Encoded::U8x4(self.to_be_bytes()))
@@ -99,6 +127,24 @@ unsafe impl LmdbKey for u32 {
     const TYPE: LmdbValType = LmdbValType::U32;
 }
 
+/// Signed integers are encoded in big-endian with the sign bit flipped, so byte-wise comparison matches numeric order.
+impl Encode for i32 {
+    fn encode(&self) -> Result<Encoded, StorageError> {
+        Ok(Encoded::U8x4(((*self as u32) ^ (1 << 31)).to_be_bytes()))
+    }
+}
+
+impl Decode for i32 {
+    fn decode(bytes: &[u8]) -> Result<Cow<Self>, StorageError> {
+        let value = u32::from_be_bytes(bytes.try_into().unwrap()) ^ (1 << 31);
+        Ok(Cow::Owned(value as i32))
+    }
+}
+
+unsafe impl LmdbKey for i32 {
+    const TYPE: LmdbValType = LmdbValType::FixedSizeOtherThanU32OrUsize;
+}
+
 impl Encode for u64 {
     fn encode(&self) -> Result<Encoded, StorageError> {
         Ok(Encoded::U8x8(self.to_be_bytes()))
@@ -118,6 +164,60 @@ unsafe impl LmdbKey for u64 {
     const TYPE: LmdbValType = LmdbValType::FixedSizeOtherThanU32OrUsize;
 }
 
+impl Encode for i64 {
+    fn encode(&self) -> Result<Encoded, StorageError> {
+        Ok(Encoded::U8x8(((*self as u64) ^ (1 << 63)).to_be_bytes()))
+    }
+}
+
+impl Decode for i64 {
+    fn decode(bytes: &[u8]) -> Result<Cow<Self>, StorageError> {
+        let value = u64::from_be_bytes(bytes.try_into().unwrap()) ^ (1 << 63);
+        Ok(Cow::Owned(value as i64))
+    }
+}
+
+unsafe impl LmdbKey for i64 {
+    const TYPE: LmdbValType = LmdbValType::FixedSizeOtherThanU32OrUsize;
+}
+
+impl Encode for u128 {
+    fn encode(&self) -> Result<Encoded, StorageError> {
+        Ok(Encoded::U8x16(self.to_be_bytes()))
+    }
+}
+
+impl Decode for u128 {
+    fn decode(bytes: &[u8]) -> Result<Cow<Self>, StorageError> {
+        Ok(Cow::Owned(u128::from_be_bytes(bytes.try_into().unwrap())))
+    }
+}
+
+unsafe impl LmdbKey for u128 {
+    const TYPE: LmdbValType = LmdbValType::FixedSizeOtherThanU32OrUsize;
+}
+
+impl Encode for (u64, u64) {
+    fn encode(&self) -> Result<Encoded, StorageError> {
+        let mut result = [0; 16];
+        result[..8].copy_from_slice(&self.0.to_be_bytes());
+        result[8..].copy_from_slice(&self.1.to_be_bytes());
+        Ok(Encoded::U8x16(result))
+    }
+}
+
+impl Decode for (u64, u64) {
+    fn decode(bytes: &[u8]) -> Result<Cow<Self>, StorageError> {
+        let first = u64::from_be_bytes(bytes[..8].try_into().unwrap());
+        let second = u64::from_be_bytes(bytes[8..].try_into().unwrap());
+        Ok(Cow::Owned((first, second)))
+    }
+}
+
+unsafe impl LmdbKey for (u64, u64) {
+    const TYPE: LmdbValType = LmdbValType::FixedSizeOtherThanU32OrUsize;
+}
+
 impl Encode for [u8] {
     fn encode(&self) -> Result<Encoded, StorageError> {
         Ok(Encoded::Borrowed(self))
@@ -130,10 +230,34 @@ impl Decode for [u8] {
     }
 }
 
+impl BorrowDecode for [u8] {
+    type Borrowed<'a> = &'a [u8];
+
+    fn decode_borrow(bytes: &[u8]) -> Result<&[u8], StorageError> {
+        Ok(bytes)
+    }
+}
+
 unsafe impl LmdbKey for [u8] {
     const TYPE: LmdbValType = LmdbValType::VariableSize;
 }
 
+impl<const N: usize> Encode for [u8; N] {
+    fn encode(&self) -> Result<Encoded, StorageError> {
+        Ok(Encoded::Borrowed(self))
+    }
+}
+
+impl<const N: usize> Decode for [u8; N] {
+    fn decode(bytes: &[u8]) -> Result<Cow<Self>, StorageError> {
+        Ok(Cow::Borrowed(bytes.try_into().unwrap()))
+    }
+}
+
+unsafe impl<const N: usize> LmdbKey for [u8; N] {
+    const TYPE: LmdbValType = LmdbValType::FixedSizeOtherThanU32OrUsize;
+}
+
 impl Encode for str {
     fn encode(&self) -> Result<Encoded, StorageError> {
         Ok(Encoded::Borrowed(self.as_bytes()))
@@ -146,29 +270,43 @@ impl Decode for str {
     }
 }
 
+impl BorrowDecode for str {
+    type Borrowed<'a> = &'a str;
+
+    fn decode_borrow(bytes: &[u8]) -> Result<&str, StorageError> {
+        Ok(std::str::from_utf8(bytes).unwrap())
+    }
+}
+
 unsafe impl LmdbKey for str {
     const TYPE: LmdbValType = LmdbValType::VariableSize;
 }
 
 impl Encode for Record {
     fn encode(&self) -> Result<Encoded, StorageError> {
-        dozer_types::bincode::serialize(self)
-            .map(Encoded::Vec)
-            .map_err(|e| StorageError::SerializationError {
-                typ: "Record",
-                reason: Box::new(e),
-            })
+        Ok(Encoded::Vec(self.encode_stored()))
     }
 }
 
 impl Decode for Record {
     fn decode(bytes: &[u8]) -> Result<Cow<Self>, StorageError> {
-        dozer_types::bincode::deserialize(bytes)
-            .map(Cow::Owned)
-            .map_err(|e| StorageError::DeserializationError {
+        Record::decode_stored(bytes).map(Cow::Owned).map_err(|e| {
+            StorageError::DeserializationError {
                 typ: "Record",
                 reason: Box::new(e),
-            })
+            }
+        })
+    }
+}
+
+impl BorrowDecode for Record {
+    type Borrowed<'a> = RecordBorrow<'a>;
+
+    fn decode_borrow(bytes: &[u8]) -> Result<RecordBorrow, StorageError> {
+        Record::decode_stored_borrow(bytes).map_err(|e| StorageError::DeserializationError {
+            typ: "RecordBorrow",
+            reason: Box::new(e),
+        })
     }
 }
 
@@ -187,9 +325,10 @@ impl Encode for (Schema, Vec<IndexDefinition>) {
     }
 }
 
+/// Schemas serialized by earlier versions are decoded too, see `legacy::deserialize_schema`.
 impl Decode for (Schema, Vec<IndexDefinition>) {
     fn decode(bytes: &[u8]) -> Result<Cow<Self>, StorageError> {
-        dozer_types::bincode::deserialize(bytes)
+        legacy::deserialize_schema_with(bytes)
             .map(Cow::Owned)
             .map_err(|e| StorageError::DeserializationError {
                 typ: "(Schema, Vec<IndexDefinition>)",
@@ -216,24 +355,33 @@ unsafe impl LmdbKey for NodeHandle {
 
 impl Encode for OpIdentifier {
     fn encode(&self) -> Result<Encoded, StorageError> {
-        Ok(Encoded::U8x16(self.to_bytes()))
+        Ok(Encoded::Vec(self.to_bytes_with_transaction()))
     }
 }
 
 impl Decode for OpIdentifier {
     fn decode(bytes: &[u8]) -> Result<Cow<Self>, StorageError> {
-        Ok(Cow::Owned(OpIdentifier::from_bytes(
-            bytes.try_into().unwrap(),
-        )))
+        OpIdentifier::from_bytes_with_transaction(bytes)
+            .map(Cow::Owned)
+            .map_err(|e| StorageError::DeserializationError {
+                typ: "OpIdentifier",
+                reason: Box::new(e),
+            })
     }
 }
 
 unsafe impl LmdbKey for OpIdentifier {
-    const TYPE: LmdbValType = LmdbValType::FixedSizeOtherThanU32OrUsize;
+    // Identifiers with a transaction are longer.
+    const TYPE: LmdbValType = LmdbValType::VariableSize;
 }
 
 #[cfg(test)]
 mod tests {
+    use dozer_types::types::{
+        legacy::{LegacyFieldDefinition, LegacySchema},
+        Field, FieldDefinition, FieldType, SchemaIdentifier, SourceDefinition,
+    };
+
     use super::*;
 
     #[test]
@@ -242,5 +390,85 @@ mod tests {
         assert_eq!(u32::TYPE, LmdbValType::U32);
         assert_eq!(u64::TYPE, LmdbValType::U64);
         assert_eq!(<[u8]>::TYPE, LmdbValType::VariableSize);
+        assert_eq!(i32::TYPE, LmdbValType::FixedSizeOtherThanU32OrUsize);
+        assert_eq!(i64::TYPE, LmdbValType::FixedSizeOtherThanU32OrUsize);
+        assert_eq!(<[u8; 4]>::TYPE, LmdbValType::FixedSizeOtherThanU32OrUsize);
+    }
+
+    fn assert_order_preserving<T: Encode + Decode + Ord + std::fmt::Debug + ?Sized>(values: &[&T]) {
+        for window in values.windows(2) {
+            assert!(window[0] < window[1]);
+            assert!(window[0].encode().unwrap().as_ref() < window[1].encode().unwrap().as_ref());
+        }
+        for value in values {
+            let encoded = value.encode().unwrap();
+            assert_eq!(&*T::decode(encoded.as_ref()).unwrap(), *value);
+        }
+    }
+
+    #[test]
+    fn test_order_preserving_encoding() {
+        assert_order_preserving::<u16>(&[&0, &1, &255, &256, &u16::MAX]);
+        assert_order_preserving::<i32>(&[&i32::MIN, &-256, &-1, &0, &1, &256, &i32::MAX]);
+        assert_order_preserving::<i64>(&[&i64::MIN, &-256, &-1, &0, &1, &256, &i64::MAX]);
+        assert_order_preserving::<u128>(&[&0, &1, &256, &(u64::MAX as u128 + 1), &u128::MAX]);
+        assert_order_preserving::<(u64, u64)>(&[&(0, 0), &(0, u64::MAX), &(1, 0), &(256, 1)]);
+        assert_order_preserving::<[u8; 2]>(&[&[0, 1], &[0, 2], &[1, 0]]);
+    }
+
+    #[test]
+    fn test_record_decode_borrow() {
+        let record = Record::new(
+            None,
+            vec![
+                Field::UInt(1),
+                Field::String("string".to_string()),
+                Field::Binary(vec![1, 2, 3]),
+                Field::Null,
+            ],
+            Some(1),
+        );
+        let encoded = record.encode().unwrap();
+        let borrowed = Record::decode_borrow(encoded.as_ref()).unwrap();
+        assert_eq!(borrowed, record.borrow());
+        assert_eq!(borrowed.into_owned(), record);
+    }
+
+    #[test]
+    fn test_schema_and_indexes_decode_legacy_layout() {
+        let identifier = Some(SchemaIdentifier { id: 1, version: 1 });
+        let indexes = vec![
+            IndexDefinition::SortedInverted(vec![0]),
+            IndexDefinition::FullText(0),
+        ];
+        let legacy_schema = LegacySchema {
+            identifier,
+            fields: vec![LegacyFieldDefinition {
+                name: "name".to_string(),
+                typ: FieldType::String,
+                nullable: false,
+                source: SourceDefinition::Dynamic,
+            }],
+            primary_index: vec![0],
+        };
+        let bytes = dozer_types::bincode::serialize(&(legacy_schema, indexes.clone())).unwrap();
+
+        let schema = Schema {
+            identifier,
+            fields: vec![FieldDefinition::new(
+                "name".to_string(),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            )],
+            primary_index: vec![0],
+        };
+        let decoded = <(Schema, Vec<IndexDefinition>)>::decode(&bytes).unwrap();
+        assert_eq!(decoded.into_owned(), (schema.clone(), indexes.clone()));
+
+        let value = (schema, indexes);
+        let encoded = value.encode().unwrap();
+        let decoded = <(Schema, Vec<IndexDefinition>)>::decode(encoded.as_ref()).unwrap();
+        assert_eq!(decoded.into_owned(), value);
     }
 }
@@ -1,6 +1,19 @@
+mod checksummed;
+mod composite_key;
+mod cursor;
+mod dup_iterator;
 mod iterator;
+mod join_iterator;
 mod lmdb_val;
 mod raw_iterator;
 
+pub use checksummed::{verify_checksum, Checksummed};
+pub use composite_key::{CompositeKey, CompositeKeyComponents};
+pub use cursor::LmdbCursor;
+pub use dup_iterator::DupValueIterator;
 pub use iterator::{Iterator, KeyIterator, ValueIterator};
-pub use lmdb_val::{Decode, Encode, Encoded, LmdbDupValue, LmdbKey, LmdbValType, LmdbValue};
+pub use join_iterator::{join_iter, JoinIterator, Joined};
+pub use lmdb_val::{
+    BorrowDecode, Decode, Encode, Encoded, LmdbDupValue, LmdbKey, LmdbValType, LmdbValue,
+};
+pub(crate) use raw_iterator::RawIterator;
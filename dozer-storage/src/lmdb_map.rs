@@ -1,11 +1,18 @@
-use std::{borrow::Cow, ops::Bound};
+use std::{
+    borrow::{Borrow, Cow},
+    io::{Read, Write},
+    ops::Bound,
+    sync::Arc,
+};
 
 use lmdb::{Database, DatabaseFlags, RoCursor, RwTransaction, Transaction, WriteFlags};
 
 use crate::{
     errors::StorageError,
+    export::{self, Compression},
     lmdb_storage::{LmdbEnvironmentManager, LmdbExclusiveTransaction},
-    Iterator, KeyIterator, LmdbKey, LmdbValType, LmdbValue, ValueIterator,
+    observer::{self, StorageObserver},
+    BorrowDecode, Iterator, KeyIterator, LmdbCursor, LmdbKey, LmdbValue, ValueIterator,
 };
 
 #[derive(Debug)]
@@ -38,7 +45,7 @@ impl<K: LmdbKey + ?Sized, V: LmdbValue + ?Sized> LmdbMap<K, V> {
         create_if_not_exist: bool,
     ) -> Result<Self, StorageError> {
         let create_flags = if create_if_not_exist {
-            Some(database_key_flag::<K>())
+            Some(DatabaseFlags::empty())
         } else {
             None
         };
@@ -58,7 +65,7 @@ impl<K: LmdbKey + ?Sized, V: LmdbValue + ?Sized> LmdbMap<K, V> {
         create_if_not_exist: bool,
     ) -> Result<Self, StorageError> {
         let create_flags = if create_if_not_exist {
-            Some(database_key_flag::<K>())
+            Some(DatabaseFlags::empty())
         } else {
             None
         };
@@ -72,6 +79,14 @@ impl<K: LmdbKey + ?Sized, V: LmdbValue + ?Sized> LmdbMap<K, V> {
         })
     }
 
+    /// Returns a view of this map that reports reads and writes to `observer`.
+    pub fn with_observer(self, observer: Arc<dyn StorageObserver>) -> ObservedLmdbMap<K, V> {
+        ObservedLmdbMap {
+            map: self,
+            observer,
+        }
+    }
+
     pub fn database(&self) -> Database {
         self.db
     }
@@ -84,45 +99,309 @@ impl<K: LmdbKey + ?Sized, V: LmdbValue + ?Sized> LmdbMap<K, V> {
         &self,
         txn: &'a T,
         key: &K,
+    ) -> Result<Option<Cow<'a, V>>, StorageError> {
+        self.get_observed(txn, key, None)
+    }
+
+    fn get_observed<'a, T: Transaction>(
+        &self,
+        txn: &'a T,
+        key: &K,
+        observer: Option<&dyn StorageObserver>,
     ) -> Result<Option<Cow<'a, V>>, StorageError> {
         let key = key.encode()?;
-        match txn.get(self.db, &key) {
-            Ok(value) => Ok(Some(V::decode(value)?)),
-            Err(lmdb::Error::NotFound) => Ok(None),
-            Err(e) => Err(e.into()),
+        match self.raw_get(txn, key.as_ref(), observer)? {
+            Some(value) => Ok(Some(V::decode(value)?)),
+            None => Ok(None),
         }
     }
 
+    /// Like `get`, but returns a view borrowing from the database instead of a possibly owned value.
+    pub fn get_borrowed<'a, T: Transaction>(
+        &self,
+        txn: &'a T,
+        key: &K,
+    ) -> Result<Option<V::Borrowed<'a>>, StorageError>
+    where
+        V: BorrowDecode,
+    {
+        let key = key.encode()?;
+        match self.raw_get(txn, key.as_ref(), None)? {
+            Some(value) => Ok(Some(V::decode_borrow(value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns if `key` exists, without decoding its value.
+    pub fn contains<T: Transaction>(&self, txn: &T, key: &K) -> Result<bool, StorageError> {
+        self.contains_observed(txn, key, None)
+    }
+
+    fn contains_observed<T: Transaction>(
+        &self,
+        txn: &T,
+        key: &K,
+        observer: Option<&dyn StorageObserver>,
+    ) -> Result<bool, StorageError> {
+        let key = key.encode()?;
+        Ok(self.raw_get(txn, key.as_ref(), observer)?.is_some())
+    }
+
+    /// Returns if each of `keys` exists, in the same order.
+    pub fn contains_many<'k, T: Transaction>(
+        &self,
+        txn: &T,
+        keys: impl IntoIterator<Item = &'k K>,
+    ) -> Result<Vec<bool>, StorageError>
+    where
+        K: 'k,
+    {
+        keys.into_iter()
+            .map(|key| self.contains(txn, key))
+            .collect()
+    }
+
+    fn raw_get<'a, T: Transaction>(
+        &self,
+        txn: &'a T,
+        key: &[u8],
+        observer: Option<&dyn StorageObserver>,
+    ) -> Result<Option<&'a [u8]>, StorageError> {
+        let start = observer::start(observer);
+        let value = match txn.get(self.db, &key) {
+            Ok(value) => Some(value),
+            Err(lmdb::Error::NotFound) => None,
+            Err(e) => return Err(e.into()),
+        };
+        if let (Some(observer), Some(start)) = (observer, start) {
+            observer.on_get(self.db, key.len(), value.map(<[u8]>::len), start.elapsed());
+        }
+        Ok(value)
+    }
+
     /// Returns if the key was actually inserted.
     pub fn insert(
         &self,
         txn: &mut RwTransaction,
         key: &K,
         value: &V,
+    ) -> Result<bool, StorageError> {
+        self.insert_observed(txn, key, value, None)
+    }
+
+    fn insert_observed(
+        &self,
+        txn: &mut RwTransaction,
+        key: &K,
+        value: &V,
+        observer: Option<&dyn StorageObserver>,
     ) -> Result<bool, StorageError> {
         let key = key.encode()?;
         let value = value.encode()?;
-        match txn.put(self.db, &key, &value, WriteFlags::NO_OVERWRITE) {
-            Ok(()) => Ok(true),
-            Err(lmdb::Error::KeyExist) => Ok(false),
-            Err(e) => Err(e.into()),
+        let start = observer::start(observer);
+        let inserted = match txn.put(self.db, &key, &value, WriteFlags::NO_OVERWRITE) {
+            Ok(()) => true,
+            Err(lmdb::Error::KeyExist) => false,
+            Err(e) => return Err(e.into()),
+        };
+        if let (Some(observer), Some(start)) = (observer, start) {
+            observer.on_put(
+                self.db,
+                key.as_ref().len(),
+                value.as_ref().len(),
+                start.elapsed(),
+            );
         }
+        Ok(inserted)
+    }
+
+    /// Reserves `len` bytes for the value of `key` and returns them, so the caller can encode the value in place
+    /// instead of copying an encoded buffer.
+    ///
+    /// Returns `None` if the key exists. The reserved bytes are uninitialized and must all be written.
+    pub fn insert_reserve<'txn>(
+        &self,
+        txn: &'txn mut RwTransaction,
+        key: &K,
+        len: usize,
+    ) -> Result<Option<&'txn mut [u8]>, StorageError> {
+        self.insert_reserve_observed(txn, key, len, None)
+    }
+
+    fn insert_reserve_observed<'txn>(
+        &self,
+        txn: &'txn mut RwTransaction,
+        key: &K,
+        len: usize,
+        observer: Option<&dyn StorageObserver>,
+    ) -> Result<Option<&'txn mut [u8]>, StorageError> {
+        let key = key.encode()?;
+        let start = observer::start(observer);
+        let reserved = match txn.reserve(self.db, &key, len, WriteFlags::NO_OVERWRITE) {
+            Ok(reserved) => Some(reserved),
+            Err(lmdb::Error::KeyExist) => None,
+            Err(e) => return Err(e.into()),
+        };
+        if let (Some(observer), Some(start)) = (observer, start) {
+            observer.on_put(self.db, key.as_ref().len(), len, start.elapsed());
+        }
+        Ok(reserved)
     }
 
     /// Returns if the key was actually removed.
     pub fn remove(&self, txn: &mut RwTransaction, key: &K) -> Result<bool, StorageError> {
+        self.remove_observed(txn, key, None)
+    }
+
+    fn remove_observed(
+        &self,
+        txn: &mut RwTransaction,
+        key: &K,
+        observer: Option<&dyn StorageObserver>,
+    ) -> Result<bool, StorageError> {
         let key = key.encode()?;
-        match txn.del(self.db, &key, None) {
-            Ok(()) => Ok(true),
-            Err(lmdb::Error::NotFound) => Ok(false),
-            Err(e) => Err(e.into()),
+        let start = observer::start(observer);
+        let removed = match txn.del(self.db, &key, None) {
+            Ok(()) => true,
+            Err(lmdb::Error::NotFound) => false,
+            Err(e) => return Err(e.into()),
+        };
+        if let (Some(observer), Some(start)) = (observer, start) {
+            observer.on_del(self.db, key.as_ref().len(), removed, start.elapsed());
         }
+        Ok(removed)
+    }
+
+    /// Applies `f` to the current value of `key` and writes back the result, removing the key if `f` returns `None`.
+    ///
+    /// Returns if the stored value changed.
+    pub fn update(
+        &self,
+        txn: &mut RwTransaction,
+        key: &K,
+        f: impl FnOnce(Option<&V>) -> Option<V::Owned>,
+    ) -> Result<bool, StorageError> {
+        self.update_observed(txn, key, f, None)
+    }
+
+    fn update_observed(
+        &self,
+        txn: &mut RwTransaction,
+        key: &K,
+        f: impl FnOnce(Option<&V>) -> Option<V::Owned>,
+        observer: Option<&dyn StorageObserver>,
+    ) -> Result<bool, StorageError> {
+        let key = key.encode()?;
+        let old_value = self.raw_get(txn, key.as_ref(), observer)?;
+        let new_value = f(old_value.map(V::decode).transpose()?.as_deref());
+        let new_value = new_value
+            .as_ref()
+            .map(|value| Borrow::<V>::borrow(value).encode())
+            .transpose()?;
+
+        let changed = match (old_value, &new_value) {
+            (Some(old_value), Some(new_value)) => old_value != new_value.as_ref(),
+            (None, None) => false,
+            _ => true,
+        };
+        if changed {
+            let start = observer::start(observer);
+            match &new_value {
+                Some(new_value) => txn.put(self.db, &key, new_value, WriteFlags::empty())?,
+                None => txn.del(self.db, &key, None)?,
+            }
+            if let (Some(observer), Some(start)) = (observer, start) {
+                match &new_value {
+                    Some(new_value) => observer.on_put(
+                        self.db,
+                        key.as_ref().len(),
+                        new_value.as_ref().len(),
+                        start.elapsed(),
+                    ),
+                    None => observer.on_del(self.db, key.as_ref().len(), true, start.elapsed()),
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Returns the value of `key`, inserting the result of `default` if the key doesn't exist.
+    ///
+    /// The returned `bool` is if the value was actually inserted.
+    pub fn get_or_insert_with(
+        &self,
+        txn: &mut RwTransaction,
+        key: &K,
+        default: impl FnOnce() -> V::Owned,
+    ) -> Result<(V::Owned, bool), StorageError> {
+        self.get_or_insert_with_observed(txn, key, default, None)
+    }
+
+    fn get_or_insert_with_observed(
+        &self,
+        txn: &mut RwTransaction,
+        key: &K,
+        default: impl FnOnce() -> V::Owned,
+        observer: Option<&dyn StorageObserver>,
+    ) -> Result<(V::Owned, bool), StorageError> {
+        if let Some(value) = self.get_observed(txn, key, observer)? {
+            return Ok((value.into_owned(), false));
+        }
+        let value = default();
+        if !self.insert_observed(txn, key, Borrow::<V>::borrow(&value), observer)? {
+            panic!("We just checked that the key doesn't exist");
+        }
+        Ok((value, true))
     }
 
     pub fn clear(&self, txn: &mut RwTransaction) -> Result<(), StorageError> {
         txn.clear_db(self.db).map_err(Into::into)
     }
 
+    /// Writes all pairs to `writer` in the portable format of the `export` module and returns the number of pairs written.
+    pub fn export<T: Transaction>(
+        &self,
+        txn: &T,
+        writer: impl Write,
+        compression: Compression,
+    ) -> Result<u64, StorageError> {
+        export::export_database(txn, self.db, writer, compression)
+    }
+
+    /// Puts all pairs written by `export` into the map, overwriting existing keys, and returns the number of pairs read.
+    pub fn import(&self, txn: &mut RwTransaction, reader: impl Read) -> Result<u64, StorageError> {
+        export::import_database(txn, self.db, reader)
+    }
+
+    pub fn cursor<'txn, T: Transaction>(
+        &self,
+        txn: &'txn T,
+    ) -> Result<LmdbCursor<'txn, RoCursor<'txn>, K, V>, StorageError> {
+        let cursor = txn.open_ro_cursor(self.db)?;
+        Ok(LmdbCursor::new(cursor))
+    }
+
+    /// Returns the pair with the smallest key.
+    #[allow(clippy::type_complexity)]
+    pub fn first<'txn, T: Transaction>(
+        &self,
+        txn: &'txn T,
+    ) -> Result<Option<(Cow<'txn, K>, Cow<'txn, V>)>, StorageError> {
+        self.iter(txn)?.next().transpose()
+    }
+
+    /// Returns the pair with the largest key.
+    #[allow(clippy::type_complexity)]
+    pub fn last<'txn, T: Transaction>(
+        &self,
+        txn: &'txn T,
+    ) -> Result<Option<(Cow<'txn, K>, Cow<'txn, V>)>, StorageError> {
+        let cursor = txn.open_ro_cursor(self.db)?;
+        Iterator::new(cursor, Bound::Unbounded, false)?
+            .next()
+            .transpose()
+    }
+
     pub fn iter<'txn, T: Transaction>(
         &self,
         txn: &'txn T,
@@ -157,25 +436,163 @@ impl<'a, K: LmdbKey + 'a + ?Sized, V: LmdbValue + 'a + ?Sized> LmdbMap<K, V> {
         txn: &mut RwTransaction,
         iter: impl IntoIterator<Item = (&'a K, &'a V)>,
     ) -> Result<(), StorageError> {
+        self.insert_many(txn, iter).map(|_| ())
+    }
+
+    /// Inserts the contents of an iterator using a single write cursor and returns the number of pairs actually inserted.
+    ///
+    /// While the keys are in ascending order and greater than all existing keys, they're appended with `MDB_APPEND`,
+    /// which skips cursor positioning. Once that doesn't hold, remaining pairs fall back to normal puts.
+    ///
+    /// Keys that exist in the map before insertion are ignored.
+    pub fn insert_many(
+        &self,
+        txn: &mut RwTransaction,
+        iter: impl IntoIterator<Item = (&'a K, &'a V)>,
+    ) -> Result<usize, StorageError> {
+        self.insert_many_observed(txn, iter, None)
+    }
+
+    fn insert_many_observed(
+        &self,
+        txn: &mut RwTransaction,
+        iter: impl IntoIterator<Item = (&'a K, &'a V)>,
+        observer: Option<&dyn StorageObserver>,
+    ) -> Result<usize, StorageError> {
+        let mut cursor = txn.open_rw_cursor(self.db)?;
+        let mut append = true;
+        let mut inserted = 0;
         for (key, value) in iter {
-            self.insert(txn, key, value)?;
+            let key = key.encode()?;
+            let value = value.encode()?;
+            let start = observer::start(observer);
+            let mut result = Err(lmdb::Error::KeyExist);
+            if append {
+                result = cursor.put(&key, &value, WriteFlags::APPEND | WriteFlags::NO_OVERWRITE);
+            }
+            if let Err(lmdb::Error::KeyExist) = result {
+                // Key is not greater than the last key, or already exists.
+                append = false;
+                result = cursor.put(&key, &value, WriteFlags::NO_OVERWRITE);
+            }
+            match result {
+                Ok(()) => inserted += 1,
+                Err(lmdb::Error::KeyExist) => (),
+                Err(e) => return Err(e.into()),
+            }
+            if let (Some(observer), Some(start)) = (observer, start) {
+                observer.on_put(
+                    self.db,
+                    key.as_ref().len(),
+                    value.as_ref().len(),
+                    start.elapsed(),
+                );
+            }
         }
-        Ok(())
+        Ok(inserted)
     }
 }
 
-pub fn database_key_flag<K: LmdbKey + ?Sized>() -> DatabaseFlags {
-    match K::TYPE {
-        LmdbValType::U32 => DatabaseFlags::INTEGER_KEY,
-        #[cfg(target_pointer_width = "64")]
-        LmdbValType::U64 => DatabaseFlags::INTEGER_KEY,
-        LmdbValType::FixedSizeOtherThanU32OrUsize | LmdbValType::VariableSize => {
-            DatabaseFlags::empty()
+/// An `LmdbMap` that reports reads and writes to a `StorageObserver`. See `LmdbMap::with_observer`.
+///
+/// Iteration, `clear` and `import` are not reported. Use `map` for them and for other unreported operations.
+#[derive(Debug)]
+pub struct ObservedLmdbMap<K: ?Sized, V: ?Sized> {
+    map: LmdbMap<K, V>,
+    observer: Arc<dyn StorageObserver>,
+}
+
+impl<K: ?Sized, V: ?Sized> Clone for ObservedLmdbMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map,
+            observer: self.observer.clone(),
         }
     }
 }
 
-fn lmdb_stat<T: Transaction>(txn: &T, db: Database) -> Result<lmdb_sys::MDB_stat, lmdb::Error> {
+impl<K: LmdbKey + ?Sized, V: LmdbValue + ?Sized> ObservedLmdbMap<K, V> {
+    pub fn map(&self) -> LmdbMap<K, V> {
+        self.map
+    }
+
+    pub fn get<'a, T: Transaction>(
+        &self,
+        txn: &'a T,
+        key: &K,
+    ) -> Result<Option<Cow<'a, V>>, StorageError> {
+        self.map.get_observed(txn, key, Some(&*self.observer))
+    }
+
+    pub fn contains<T: Transaction>(&self, txn: &T, key: &K) -> Result<bool, StorageError> {
+        self.map.contains_observed(txn, key, Some(&*self.observer))
+    }
+
+    /// See `LmdbMap::insert`.
+    pub fn insert(
+        &self,
+        txn: &mut RwTransaction,
+        key: &K,
+        value: &V,
+    ) -> Result<bool, StorageError> {
+        self.map
+            .insert_observed(txn, key, value, Some(&*self.observer))
+    }
+
+    /// See `LmdbMap::insert_reserve`.
+    pub fn insert_reserve<'txn>(
+        &self,
+        txn: &'txn mut RwTransaction,
+        key: &K,
+        len: usize,
+    ) -> Result<Option<&'txn mut [u8]>, StorageError> {
+        self.map
+            .insert_reserve_observed(txn, key, len, Some(&*self.observer))
+    }
+
+    /// See `LmdbMap::remove`.
+    pub fn remove(&self, txn: &mut RwTransaction, key: &K) -> Result<bool, StorageError> {
+        self.map.remove_observed(txn, key, Some(&*self.observer))
+    }
+
+    /// See `LmdbMap::update`.
+    pub fn update(
+        &self,
+        txn: &mut RwTransaction,
+        key: &K,
+        f: impl FnOnce(Option<&V>) -> Option<V::Owned>,
+    ) -> Result<bool, StorageError> {
+        self.map.update_observed(txn, key, f, Some(&*self.observer))
+    }
+
+    /// See `LmdbMap::get_or_insert_with`.
+    pub fn get_or_insert_with(
+        &self,
+        txn: &mut RwTransaction,
+        key: &K,
+        default: impl FnOnce() -> V::Owned,
+    ) -> Result<(V::Owned, bool), StorageError> {
+        self.map
+            .get_or_insert_with_observed(txn, key, default, Some(&*self.observer))
+    }
+}
+
+impl<'a, K: LmdbKey + 'a + ?Sized, V: LmdbValue + 'a + ?Sized> ObservedLmdbMap<K, V> {
+    /// See `LmdbMap::insert_many`.
+    pub fn insert_many(
+        &self,
+        txn: &mut RwTransaction,
+        iter: impl IntoIterator<Item = (&'a K, &'a V)>,
+    ) -> Result<usize, StorageError> {
+        self.map
+            .insert_many_observed(txn, iter, Some(&*self.observer))
+    }
+}
+
+pub(crate) fn lmdb_stat<T: Transaction>(
+    txn: &T,
+    db: Database,
+) -> Result<lmdb_sys::MDB_stat, lmdb::Error> {
     let mut stat = lmdb_sys::MDB_stat {
         ms_psize: 0,
         ms_depth: 0,
@@ -194,7 +611,10 @@ fn lmdb_stat<T: Transaction>(txn: &T, db: Database) -> Result<lmdb_sys::MDB_stat
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
     use crate::lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions};
+    use crate::observer::tests::CountingObserver;
 
     use super::*;
 
@@ -239,4 +659,229 @@ mod tests {
         assert!(map.remove(txn.txn_mut(), [1u8].as_slice()).unwrap());
         assert_eq!(map.count(txn.txn()).unwrap(), 0);
     }
+
+    #[test]
+    fn test_lmdb_map_insert_reserve() {
+        let temp_dir = TempDir::new("test_lmdb_map").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let map = LmdbMap::<u64, [u8]>::new_from_env(&mut env, None, true).unwrap();
+
+        let mut txn = env.begin_rw_txn().unwrap();
+        map.insert_reserve(&mut txn, &1, 3)
+            .unwrap()
+            .unwrap()
+            .copy_from_slice(&[1, 2, 3]);
+        assert!(map.insert_reserve(&mut txn, &1, 3).unwrap().is_none());
+        assert_eq!(map.get(&txn, &1).unwrap().unwrap().as_ref(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_lmdb_map_contains() {
+        let temp_dir = TempDir::new("test_lmdb_map").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let map = LmdbMap::<u64, str>::new_from_env(&mut env, None, true).unwrap();
+
+        let mut txn = env.begin_rw_txn().unwrap();
+        map.extend(&mut txn, [(&1, "a"), (&3, "c")]).unwrap();
+        assert!(map.contains(&txn, &1).unwrap());
+        assert!(!map.contains(&txn, &2).unwrap());
+        assert_eq!(
+            map.contains_many(&txn, &[3, 2, 1]).unwrap(),
+            vec![true, false, true]
+        );
+    }
+
+    #[test]
+    fn test_lmdb_map_first_last() {
+        let temp_dir = TempDir::new("test_lmdb_map").unwrap();
+        let env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+
+        let map = LmdbMap::<str, u64>::new_from_txn(&mut txn, None, true).unwrap();
+        assert!(map.first(txn.txn()).unwrap().is_none());
+        assert!(map.last(txn.txn()).unwrap().is_none());
+
+        map.extend(txn.txn_mut(), [("b", &2), ("a", &1), ("c", &3)])
+            .unwrap();
+        let (key, value) = map.first(txn.txn()).unwrap().unwrap();
+        assert_eq!((key.as_ref(), value.into_owned()), ("a", 1));
+        let (key, value) = map.last(txn.txn()).unwrap().unwrap();
+        assert_eq!((key.as_ref(), value.into_owned()), ("c", 3));
+    }
+
+    #[test]
+    fn test_lmdb_map_first_last_integer_keys() {
+        let temp_dir = TempDir::new("test_lmdb_map").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let map = LmdbMap::<u64, u64>::new_from_env(&mut env, None, true).unwrap();
+
+        let mut txn = env.begin_rw_txn().unwrap();
+        for key in [256, 1, u64::MAX, 255, 1 << 40] {
+            assert!(map.insert(&mut txn, &key, &key).unwrap());
+        }
+        let (key, _) = map.first(&txn).unwrap().unwrap();
+        assert_eq!(key.into_owned(), 1);
+        let (key, _) = map.last(&txn).unwrap().unwrap();
+        assert_eq!(key.into_owned(), u64::MAX);
+
+        assert!(map.remove(&mut txn, &u64::MAX).unwrap());
+        let (key, _) = map.last(&txn).unwrap().unwrap();
+        assert_eq!(key.into_owned(), 1 << 40);
+        assert_eq!(
+            map.keys(&txn)
+                .unwrap()
+                .map(|key| key.map(|key| key.into_owned()))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            vec![1, 255, 256, 1 << 40]
+        );
+    }
+
+    #[test]
+    fn test_lmdb_map_update() {
+        let temp_dir = TempDir::new("test_lmdb_map").unwrap();
+        let env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+
+        let map = LmdbMap::<str, u64>::new_from_txn(&mut txn, None, true).unwrap();
+
+        let bump = |value: Option<&u64>| Some(value.copied().unwrap_or(0) + 1);
+        assert!(map.update(txn.txn_mut(), "a", bump).unwrap());
+        assert!(map.update(txn.txn_mut(), "a", bump).unwrap());
+        assert_eq!(map.get(txn.txn(), "a").unwrap().unwrap().into_owned(), 2);
+
+        assert!(!map
+            .update(txn.txn_mut(), "a", |value| value.copied())
+            .unwrap());
+        assert!(!map.update(txn.txn_mut(), "b", |_| None).unwrap());
+        assert!(map.update(txn.txn_mut(), "a", |_| None).unwrap());
+        assert!(map.get(txn.txn(), "a").unwrap().is_none());
+
+        assert_eq!(
+            map.get_or_insert_with(txn.txn_mut(), "c", || 3).unwrap(),
+            (3, true)
+        );
+        assert_eq!(
+            map.get_or_insert_with(txn.txn_mut(), "c", || 4).unwrap(),
+            (3, false)
+        );
+    }
+
+    #[test]
+    fn test_lmdb_map_insert_many() {
+        let temp_dir = TempDir::new("test_lmdb_map").unwrap();
+        let env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+
+        let map = LmdbMap::<str, u64>::new_from_txn(&mut txn, None, true).unwrap();
+
+        // Sorted input is appended.
+        assert_eq!(
+            map.insert_many(txn.txn_mut(), [("b", &1), ("d", &2)])
+                .unwrap(),
+            2
+        );
+        // Unsorted input and existing keys fall back to normal puts.
+        assert_eq!(
+            map.insert_many(txn.txn_mut(), [("e", &3), ("a", &4), ("d", &5), ("c", &6)])
+                .unwrap(),
+            3
+        );
+
+        let items = map
+            .iter(txn.txn())
+            .unwrap()
+            .map(|result| result.map(|(key, value)| (key.into_owned(), value.into_owned())))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            items,
+            vec![
+                ("a".to_string(), 4),
+                ("b".to_string(), 1),
+                ("c".to_string(), 6),
+                ("d".to_string(), 2),
+                ("e".to_string(), 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lmdb_map_observer() {
+        let temp_dir = TempDir::new("test_lmdb_map_observer").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let observer = Arc::new(CountingObserver::default());
+        let map = LmdbMap::<u64, str>::new_from_env(&mut env, None, true)
+            .unwrap()
+            .with_observer(observer.clone());
+
+        let mut txn = env.begin_rw_txn().unwrap();
+        assert!(map.insert(&mut txn, &1, "value").unwrap());
+        assert!(map.get(&txn, &1).unwrap().is_some());
+        assert!(map.get(&txn, &2).unwrap().is_none());
+        assert!(map.remove(&mut txn, &1).unwrap());
+
+        let count = |counter: &AtomicUsize| counter.load(Ordering::Relaxed);
+        assert_eq!(count(&observer.puts), 1);
+        assert_eq!(count(&observer.put_bytes), 13);
+        assert_eq!(count(&observer.gets), 2);
+        assert_eq!(count(&observer.misses), 1);
+        assert_eq!(count(&observer.dels), 1);
+
+        // 2 puts.
+        assert_eq!(
+            map.insert_many(&mut txn, [(&2, "a"), (&3, "b")]).unwrap(),
+            2
+        );
+        // A get and a put.
+        assert!(map
+            .update(&mut txn, &2, |value| value.map(|value| value.repeat(2)))
+            .unwrap());
+        // A get and a del.
+        assert!(map.update(&mut txn, &3, |_| None).unwrap());
+        // Only a get, because nothing changes.
+        assert!(!map.update(&mut txn, &4, |_| None).unwrap());
+        assert_eq!(count(&observer.puts), 4);
+        assert_eq!(count(&observer.gets), 5);
+        assert_eq!(count(&observer.misses), 2);
+        assert_eq!(count(&observer.dels), 2);
+    }
 }
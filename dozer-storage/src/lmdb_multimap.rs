@@ -1,12 +1,12 @@
 use std::ops::Bound;
 
-use lmdb::{Database, DatabaseFlags, RoCursor, RwTransaction, Transaction, WriteFlags};
+use lmdb::{Cursor, Database, DatabaseFlags, RoCursor, RwTransaction, Transaction, WriteFlags};
+use lmdb_sys::MDB_SET;
 
 use crate::{
     errors::StorageError,
-    lmdb_map::database_key_flag,
     lmdb_storage::{LmdbEnvironmentManager, LmdbExclusiveTransaction},
-    Iterator, LmdbDupValue, LmdbKey, LmdbValType,
+    DupValueIterator, Iterator, LmdbCursor, LmdbDupValue, LmdbKey, LmdbValType,
 };
 
 #[derive(Debug)]
@@ -39,7 +39,7 @@ impl<K: LmdbKey + ?Sized, V: LmdbDupValue + ?Sized> LmdbMultimap<K, V> {
         create_if_not_exist: bool,
     ) -> Result<Self, StorageError> {
         let create_flags = if create_if_not_exist {
-            Some(database_flag::<K, V>())
+            Some(database_flag::<V>())
         } else {
             None
         };
@@ -59,7 +59,7 @@ impl<K: LmdbKey + ?Sized, V: LmdbDupValue + ?Sized> LmdbMultimap<K, V> {
         create_if_not_exist: bool,
     ) -> Result<Self, StorageError> {
         let create_flags = if create_if_not_exist {
-            Some(database_flag::<K, V>())
+            Some(database_flag::<V>())
         } else {
             None
         };
@@ -109,6 +109,60 @@ impl<K: LmdbKey + ?Sized, V: LmdbDupValue + ?Sized> LmdbMultimap<K, V> {
         }
     }
 
+    /// Returns the number of values of `key`.
+    pub fn value_count<T: Transaction>(&self, txn: &T, key: &K) -> Result<usize, StorageError> {
+        let key = key.encode()?;
+        let cursor = txn.open_ro_cursor(self.db)?;
+        match cursor.get(Some(key.as_ref()), None, MDB_SET) {
+            Ok(_) => (),
+            Err(lmdb::Error::NotFound) => return Ok(0),
+            Err(e) => return Err(e.into()),
+        }
+        let mut count = 0;
+        // SAFETY: `cursor` is positioned at `key`.
+        let code = unsafe { lmdb_sys::mdb_cursor_count(cursor.cursor(), &mut count) };
+        if code == lmdb_sys::MDB_SUCCESS {
+            Ok(count)
+        } else {
+            Err(lmdb::Error::from_err_code(code).into())
+        }
+    }
+
+    /// Alias of `value_count`, named after `MDB_DUPSORT` like `range_dup`.
+    pub fn count_dup<T: Transaction>(&self, txn: &T, key: &K) -> Result<usize, StorageError> {
+        self.value_count(txn, key)
+    }
+
+    /// Returns if any value of `key` was actually removed.
+    pub fn remove_all(&self, txn: &mut RwTransaction, key: &K) -> Result<bool, StorageError> {
+        let key = key.encode()?;
+        match txn.del(self.db, &key, None) {
+            Ok(()) => Ok(true),
+            Err(lmdb::Error::NotFound) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Iterates the values of `key` that fall in `value_bounds`, in ascending order.
+    pub fn range_dup<'txn, T: Transaction>(
+        &self,
+        txn: &'txn T,
+        key: &K,
+        value_bounds: (Bound<&V>, Bound<&V>),
+    ) -> Result<DupValueIterator<'txn, RoCursor<'txn>, V>, StorageError> {
+        let key = key.encode()?;
+        let cursor = txn.open_ro_cursor(self.db)?;
+        DupValueIterator::new(txn, self.db, cursor, key.as_ref(), value_bounds)
+    }
+
+    pub fn cursor<'txn, T: Transaction>(
+        &self,
+        txn: &'txn T,
+    ) -> Result<LmdbCursor<'txn, RoCursor<'txn>, K, V>, StorageError> {
+        let cursor = txn.open_ro_cursor(self.db)?;
+        Ok(LmdbCursor::new(cursor))
+    }
+
     pub fn iter<'txn, T: Transaction>(
         &self,
         txn: &'txn T,
@@ -128,17 +182,16 @@ impl<K: LmdbKey + ?Sized, V: LmdbDupValue + ?Sized> LmdbMultimap<K, V> {
     }
 }
 
-fn database_flag<K: LmdbKey + ?Sized, V: LmdbKey + ?Sized>() -> DatabaseFlags {
-    let mut flags = database_key_flag::<K>();
-    flags |= DatabaseFlags::DUP_SORT;
+/// Values are compared byte-wise like keys, see `LmdbKey`, so new databases are created without `INTEGER_DUP`.
+fn database_flag<V: LmdbKey + ?Sized>() -> DatabaseFlags {
     match V::TYPE {
-        LmdbValType::U32 => flags |= DatabaseFlags::DUP_FIXED | DatabaseFlags::INTEGER_DUP,
         #[cfg(target_pointer_width = "64")]
-        LmdbValType::U64 => flags |= DatabaseFlags::DUP_FIXED | DatabaseFlags::INTEGER_DUP,
-        LmdbValType::FixedSizeOtherThanU32OrUsize => flags |= DatabaseFlags::DUP_FIXED,
-        LmdbValType::VariableSize => (),
-    };
-    flags
+        LmdbValType::U64 => DatabaseFlags::DUP_SORT | DatabaseFlags::DUP_FIXED,
+        LmdbValType::U32 | LmdbValType::FixedSizeOtherThanU32OrUsize => {
+            DatabaseFlags::DUP_SORT | DatabaseFlags::DUP_FIXED
+        }
+        LmdbValType::VariableSize => DatabaseFlags::DUP_SORT,
+    }
 }
 
 #[cfg(test)]
@@ -168,4 +221,90 @@ mod tests {
         assert!(map.remove(txn.txn_mut(), &1u64, &2u64).unwrap());
         assert!(!map.remove(txn.txn_mut(), &1u64, &2u64).unwrap());
     }
+
+    #[test]
+    fn test_lmdb_multimap_dup_operations() {
+        let temp_dir = TempDir::new("test_lmdb_map").unwrap();
+        let env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+
+        let map = LmdbMultimap::<[u8], [u8]>::new_from_txn(&mut txn, None, true).unwrap();
+        for value in [b"1", b"2", b"3", b"4"] {
+            assert!(map.insert(txn.txn_mut(), b"a", value).unwrap());
+        }
+        assert!(map.insert(txn.txn_mut(), b"b", b"1").unwrap());
+
+        assert_eq!(map.value_count(txn.txn(), b"a").unwrap(), 4);
+        assert_eq!(map.value_count(txn.txn(), b"b").unwrap(), 1);
+        assert_eq!(map.value_count(txn.txn(), b"c").unwrap(), 0);
+        assert_eq!(map.count_dup(txn.txn(), b"a").unwrap(), 4);
+
+        let range_dup = |value_bounds: (Bound<&[u8]>, Bound<&[u8]>)| {
+            map.range_dup(txn.txn(), b"a", value_bounds)
+                .unwrap()
+                .map(|value| value.map(|value| value.into_owned()))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        };
+        assert_eq!(
+            range_dup((Bound::Unbounded, Bound::Unbounded)),
+            vec![b"1", b"2", b"3", b"4"]
+        );
+        assert_eq!(
+            range_dup((Bound::Included(b"2"), Bound::Excluded(b"4"))),
+            vec![b"2", b"3"]
+        );
+        assert_eq!(
+            range_dup((Bound::Excluded(b"2"), Bound::Included(b"4"))),
+            vec![b"3", b"4"]
+        );
+        assert_eq!(
+            range_dup((Bound::Excluded(b"4"), Bound::Unbounded)),
+            Vec::<Vec<u8>>::new()
+        );
+
+        assert!(map.remove_all(txn.txn_mut(), b"a").unwrap());
+        assert!(!map.remove_all(txn.txn_mut(), b"a").unwrap());
+        assert_eq!(map.value_count(txn.txn(), b"a").unwrap(), 0);
+        assert_eq!(map.value_count(txn.txn(), b"b").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_lmdb_multimap_integer_order() {
+        let temp_dir = TempDir::new("test_lmdb_map").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let map = LmdbMultimap::<u64, u64>::new_from_env(&mut env, None, true).unwrap();
+
+        let mut txn = env.begin_rw_txn().unwrap();
+        for key in [65536, 1, 256, 255] {
+            for value in [1 << 40, 256, 1, 255] {
+                assert!(map.insert(&mut txn, &key, &value).unwrap());
+            }
+        }
+
+        let entries = map
+            .iter(&txn)
+            .unwrap()
+            .map(|result| result.map(|(key, value)| (key.into_owned(), value.into_owned())))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let mut expected = vec![];
+        for key in [1, 255, 256, 65536] {
+            for value in [1, 255, 256, 1 << 40] {
+                expected.push((key, value));
+            }
+        }
+        assert_eq!(entries, expected);
+    }
 }
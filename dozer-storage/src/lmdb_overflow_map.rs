@@ -0,0 +1,266 @@
+use std::borrow::Cow;
+
+use lmdb::{RwTransaction, Transaction};
+
+use crate::{
+    errors::StorageError,
+    lmdb_storage::{LmdbEnvironmentManager, LmdbExclusiveTransaction},
+    LmdbKey, LmdbMap, LmdbValue,
+};
+
+const INLINE: u8 = 0;
+const OVERFLOW: u8 = 1;
+
+/// A map that stores values larger than `threshold` bytes in a separate blob database, keeping only the blob id in the map.
+///
+/// Large values stored inline spread over LMDB overflow pages, which fragments the map. Moving them out keeps the map compact
+/// and iterating its keys cheap.
+///
+/// The blob database is named `{name}__overflow`.
+#[derive(Debug)]
+pub struct LmdbOverflowMap<K: ?Sized, V: ?Sized> {
+    map: LmdbMap<K, [u8]>,
+    blobs: LmdbMap<u64, [u8]>,
+    threshold: usize,
+    _value: std::marker::PhantomData<*const V>,
+}
+
+impl<K: ?Sized, V: ?Sized> Clone for LmdbOverflowMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map,
+            blobs: self.blobs,
+            threshold: self.threshold,
+            _value: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<K: ?Sized, V: ?Sized> Copy for LmdbOverflowMap<K, V> {}
+
+// Safety: `Database` is `Send` and `Sync`.
+unsafe impl<K: ?Sized, V: ?Sized> Send for LmdbOverflowMap<K, V> {}
+unsafe impl<K: ?Sized, V: ?Sized> Sync for LmdbOverflowMap<K, V> {}
+
+impl<K: LmdbKey + ?Sized, V: LmdbValue + ?Sized> LmdbOverflowMap<K, V> {
+    pub fn new_from_env(
+        env: &mut LmdbEnvironmentManager,
+        name: &str,
+        threshold: usize,
+        create_if_not_exist: bool,
+    ) -> Result<Self, StorageError> {
+        let map = LmdbMap::new_from_env(env, Some(name), create_if_not_exist)?;
+        let blobs = LmdbMap::new_from_env(
+            env,
+            Some(blob_database_name(name).as_str()),
+            create_if_not_exist,
+        )?;
+        Ok(Self::new(map, blobs, threshold))
+    }
+
+    pub fn new_from_txn(
+        txn: &mut LmdbExclusiveTransaction,
+        name: &str,
+        threshold: usize,
+        create_if_not_exist: bool,
+    ) -> Result<Self, StorageError> {
+        let map = LmdbMap::new_from_txn(txn, Some(name), create_if_not_exist)?;
+        let blobs = LmdbMap::new_from_txn(
+            txn,
+            Some(blob_database_name(name).as_str()),
+            create_if_not_exist,
+        )?;
+        Ok(Self::new(map, blobs, threshold))
+    }
+
+    fn new(map: LmdbMap<K, [u8]>, blobs: LmdbMap<u64, [u8]>, threshold: usize) -> Self {
+        Self {
+            map,
+            blobs,
+            threshold,
+            _value: std::marker::PhantomData,
+        }
+    }
+
+    pub fn map(&self) -> LmdbMap<K, [u8]> {
+        self.map
+    }
+
+    pub fn blobs(&self) -> LmdbMap<u64, [u8]> {
+        self.blobs
+    }
+
+    pub fn count<T: Transaction>(&self, txn: &T) -> Result<usize, StorageError> {
+        self.map.count(txn)
+    }
+
+    pub fn get<'a, T: Transaction>(
+        &self,
+        txn: &'a T,
+        key: &K,
+    ) -> Result<Option<Cow<'a, V>>, StorageError> {
+        let Some(stored) = self.map.get_borrowed(txn, key)? else {
+            return Ok(None);
+        };
+        let bytes = match parse_stored(stored)? {
+            Stored::Inline(bytes) => bytes,
+            Stored::Overflow(id) => self
+                .blobs
+                .get_borrowed(txn, &id)?
+                .ok_or_else(|| missing_blob(id))?,
+        };
+        V::decode(bytes).map(Some)
+    }
+
+    /// Returns if the key was actually inserted.
+    pub fn insert(
+        &self,
+        txn: &mut RwTransaction,
+        key: &K,
+        value: &V,
+    ) -> Result<bool, StorageError> {
+        if self.map.contains(txn, key)? {
+            return Ok(false);
+        }
+
+        let value = value.encode()?;
+        let value = value.as_ref();
+        let mut stored = Vec::with_capacity(1 + value.len().min(self.threshold));
+        if value.len() > self.threshold {
+            // Keys are ordered numerically, see `LmdbKey`, so the last blob has the largest id.
+            let id = match self.blobs.last(txn)? {
+                Some((id, _)) => id.into_owned() + 1,
+                None => 0,
+            };
+            if !self.blobs.insert(txn, &id, value)? {
+                panic!("The blob id should be new");
+            }
+            stored.push(OVERFLOW);
+            stored.extend_from_slice(&id.to_be_bytes());
+        } else {
+            stored.push(INLINE);
+            stored.extend_from_slice(value);
+        }
+        self.map.insert(txn, key, &stored)
+    }
+
+    /// Returns if the key was actually removed.
+    pub fn remove(&self, txn: &mut RwTransaction, key: &K) -> Result<bool, StorageError> {
+        let overflow_id = match self.map.get_borrowed(txn, key)? {
+            Some(stored) => match parse_stored(stored)? {
+                Stored::Inline(_) => None,
+                Stored::Overflow(id) => Some(id),
+            },
+            None => return Ok(false),
+        };
+        if let Some(id) = overflow_id {
+            self.blobs.remove(txn, &id)?;
+        }
+        self.map.remove(txn, key)
+    }
+
+    pub fn clear(&self, txn: &mut RwTransaction) -> Result<(), StorageError> {
+        self.map.clear(txn)?;
+        self.blobs.clear(txn)
+    }
+}
+
+fn blob_database_name(name: &str) -> String {
+    format!("{name}__overflow")
+}
+
+enum Stored<'a> {
+    Inline(&'a [u8]),
+    Overflow(u64),
+}
+
+fn parse_stored(stored: &[u8]) -> Result<Stored, StorageError> {
+    match stored.split_first() {
+        Some((&INLINE, bytes)) => Ok(Stored::Inline(bytes)),
+        Some((&OVERFLOW, id)) => id
+            .try_into()
+            .map(|id| Stored::Overflow(u64::from_be_bytes(id)))
+            .map_err(|_| invalid_stored_value("invalid blob id")),
+        _ => Err(invalid_stored_value("invalid tag")),
+    }
+}
+
+fn invalid_stored_value(reason: &str) -> StorageError {
+    StorageError::DeserializationError {
+        typ: "LmdbOverflowMap value",
+        reason: reason.into(),
+    }
+}
+
+fn missing_blob(id: u64) -> StorageError {
+    invalid_stored_value(&format!("blob {id} not found"))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use crate::lmdb_storage::LmdbEnvironmentOptions;
+
+    use super::*;
+
+    #[test]
+    fn test_lmdb_overflow_map() {
+        let temp_dir = TempDir::new("test_lmdb_overflow_map").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let map = LmdbOverflowMap::<u64, [u8]>::new_from_env(&mut env, "map", 4, true).unwrap();
+
+        let small = vec![1; 4];
+        let large = vec![2; 1024 * 1024];
+        let mut txn = env.begin_rw_txn().unwrap();
+        assert!(map.insert(&mut txn, &0, &small).unwrap());
+        assert!(map.insert(&mut txn, &1, &large).unwrap());
+        assert!(map.insert(&mut txn, &2, &large).unwrap());
+        assert!(!map.insert(&mut txn, &1, &small).unwrap());
+
+        assert_eq!(map.count(&txn).unwrap(), 3);
+        assert_eq!(map.blobs().count(&txn).unwrap(), 2);
+        assert_eq!(map.get(&txn, &0).unwrap().unwrap().as_ref(), small);
+        assert_eq!(map.get(&txn, &1).unwrap().unwrap().as_ref(), large);
+        assert_eq!(map.get(&txn, &2).unwrap().unwrap().as_ref(), large);
+        assert!(map.get(&txn, &3).unwrap().is_none());
+
+        assert!(map.remove(&mut txn, &1).unwrap());
+        assert!(!map.remove(&mut txn, &1).unwrap());
+        assert_eq!(map.blobs().count(&txn).unwrap(), 1);
+        assert!(map.get(&txn, &1).unwrap().is_none());
+
+        assert!(map.insert(&mut txn, &1, &large).unwrap());
+        assert_eq!(map.get(&txn, &1).unwrap().unwrap().as_ref(), large);
+        map.clear(&mut txn).unwrap();
+        assert_eq!(map.count(&txn).unwrap(), 0);
+        assert_eq!(map.blobs().count(&txn).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_lmdb_overflow_map_many_blobs() {
+        let temp_dir = TempDir::new("test_lmdb_overflow_map").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let map = LmdbOverflowMap::<u64, [u8]>::new_from_env(&mut env, "map", 4, true).unwrap();
+
+        let value = |key: u64| key.to_be_bytes().repeat(2);
+        let mut txn = env.begin_rw_txn().unwrap();
+        for key in 0..300 {
+            assert!(map.insert(&mut txn, &key, &value(key)).unwrap());
+        }
+        assert_eq!(map.blobs().count(&txn).unwrap(), 300);
+        for key in 0..300 {
+            assert_eq!(map.get(&txn, &key).unwrap().unwrap().as_ref(), value(key));
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use std::borrow::Cow;
+
+use lmdb::{Database, RwTransaction, Transaction};
+
+use crate::{
+    errors::StorageError,
+    lmdb_storage::{LmdbEnvironmentManager, LmdbExclusiveTransaction},
+    LmdbCounter, LmdbMap, LmdbValue,
+};
+
+const NEXT_ID: &str = "next_id";
+
+/// A FIFO queue. Elements are keyed by a monotonically increasing `u64`, which is never reused, even after the queue
+/// is emptied or cleared.
+///
+/// The keys are stored as big-endian bytes without `INTEGER_KEY`, so LMDB's byte-wise comparison matches numeric order.
+/// The next id is kept in a counter database named `{name}__next_id`.
+#[derive(Debug)]
+pub struct LmdbQueue<V: ?Sized> {
+    map: LmdbMap<[u8], V>,
+    next_id: LmdbCounter,
+}
+
+impl<V: ?Sized> Clone for LmdbQueue<V> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map,
+            next_id: self.next_id,
+        }
+    }
+}
+
+impl<V: ?Sized> Copy for LmdbQueue<V> {}
+
+impl<V: LmdbValue + ?Sized> LmdbQueue<V> {
+    pub fn new_from_env(
+        env: &mut LmdbEnvironmentManager,
+        name: &str,
+        create_if_not_exist: bool,
+    ) -> Result<Self, StorageError> {
+        let map = LmdbMap::new_from_env(env, Some(name), create_if_not_exist)?;
+        let next_id = LmdbCounter::new_from_env(
+            env,
+            Some(next_id_database_name(name).as_str()),
+            create_if_not_exist,
+        )?;
+        Ok(Self { map, next_id })
+    }
+
+    pub fn new_from_txn(
+        txn: &mut LmdbExclusiveTransaction,
+        name: &str,
+        create_if_not_exist: bool,
+    ) -> Result<Self, StorageError> {
+        let map = LmdbMap::new_from_txn(txn, Some(name), create_if_not_exist)?;
+        let next_id = LmdbCounter::new_from_txn(
+            txn,
+            Some(next_id_database_name(name).as_str()),
+            create_if_not_exist,
+        )?;
+        Ok(Self { map, next_id })
+    }
+
+    pub fn database(&self) -> Database {
+        self.map.database()
+    }
+
+    pub fn len<T: Transaction>(&self, txn: &T) -> Result<usize, StorageError> {
+        self.map.count(txn)
+    }
+
+    pub fn is_empty<T: Transaction>(&self, txn: &T) -> Result<bool, StorageError> {
+        self.len(txn).map(|len| len == 0)
+    }
+
+    /// Appends `value` to the back of the queue and returns its id.
+    pub fn push_back(&self, txn: &mut RwTransaction, value: &V) -> Result<u64, StorageError> {
+        // Queues written before the counter existed have no next id stored.
+        let id = match self.back_id(txn)? {
+            Some(back_id) => self.next_id.get(txn, NEXT_ID)?.max(back_id + 1),
+            None => self.next_id.get(txn, NEXT_ID)?,
+        };
+        if !self.map.insert(txn, &id.to_be_bytes(), value)? {
+            panic!("Queue id {id} should not exist");
+        }
+        self.next_id.set(txn, NEXT_ID, id + 1)?;
+        Ok(id)
+    }
+
+    /// Returns the element at the front of the queue without removing it.
+    pub fn peek<'a, T: Transaction>(
+        &self,
+        txn: &'a T,
+    ) -> Result<Option<(u64, Cow<'a, V>)>, StorageError> {
+        match self.map.first(txn)? {
+            Some((key, value)) => Ok(Some((decode_id(&key), value))),
+            None => Ok(None),
+        }
+    }
+
+    /// Removes and returns the element at the front of the queue.
+    pub fn pop_front(
+        &self,
+        txn: &mut RwTransaction,
+    ) -> Result<Option<(u64, V::Owned)>, StorageError> {
+        let Some((id, value)) = self.peek(txn)?.map(|(id, value)| (id, value.into_owned())) else {
+            return Ok(None);
+        };
+        if !self.map.remove(txn, &id.to_be_bytes())? {
+            panic!("We just got this key from the queue");
+        }
+        Ok(Some((id, value)))
+    }
+
+    /// Removes all elements. Ids of elements pushed later continue after the removed ones.
+    pub fn clear(&self, txn: &mut RwTransaction) -> Result<(), StorageError> {
+        self.map.clear(txn)
+    }
+
+    fn back_id<T: Transaction>(&self, txn: &T) -> Result<Option<u64>, StorageError> {
+        match self.map.last(txn)? {
+            Some((key, _)) => Ok(Some(decode_id(&key))),
+            None => Ok(None),
+        }
+    }
+}
+
+fn next_id_database_name(name: &str) -> String {
+    format!("{name}__next_id")
+}
+
+fn decode_id(key: &[u8]) -> u64 {
+    u64::from_be_bytes(key.try_into().expect("Queue key should be 8 bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions};
+
+    use super::*;
+
+    use tempdir::TempDir;
+
+    #[test]
+    fn test_lmdb_queue() {
+        let temp_dir = TempDir::new("test_lmdb_queue").unwrap();
+        let env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+
+        let queue = LmdbQueue::<str>::new_from_txn(&mut txn, "queue", true).unwrap();
+        assert!(queue.is_empty(txn.txn()).unwrap());
+        assert!(queue.peek(txn.txn()).unwrap().is_none());
+        assert!(queue.pop_front(txn.txn_mut()).unwrap().is_none());
+
+        assert_eq!(queue.push_back(txn.txn_mut(), "a").unwrap(), 0);
+        assert_eq!(queue.push_back(txn.txn_mut(), "b").unwrap(), 1);
+        assert_eq!(queue.len(txn.txn()).unwrap(), 2);
+
+        let (id, value) = queue.peek(txn.txn()).unwrap().unwrap();
+        assert_eq!((id, value.as_ref()), (0, "a"));
+        assert_eq!(
+            queue.pop_front(txn.txn_mut()).unwrap(),
+            Some((0, "a".to_string()))
+        );
+        assert_eq!(queue.push_back(txn.txn_mut(), "c").unwrap(), 2);
+        assert_eq!(
+            queue.pop_front(txn.txn_mut()).unwrap(),
+            Some((1, "b".to_string()))
+        );
+        assert_eq!(
+            queue.pop_front(txn.txn_mut()).unwrap(),
+            Some((2, "c".to_string()))
+        );
+        assert!(queue.is_empty(txn.txn()).unwrap());
+    }
+
+    #[test]
+    fn test_lmdb_queue_order_across_byte_boundary() {
+        let temp_dir = TempDir::new("test_lmdb_queue").unwrap();
+        let env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+
+        let queue = LmdbQueue::<u64>::new_from_txn(&mut txn, "queue", true).unwrap();
+        for i in 0..300u64 {
+            assert_eq!(queue.push_back(txn.txn_mut(), &i).unwrap(), i);
+        }
+        for i in 0..300u64 {
+            assert_eq!(queue.pop_front(txn.txn_mut()).unwrap(), Some((i, i)));
+        }
+    }
+
+    #[test]
+    fn test_lmdb_queue_ids_are_not_reused() {
+        let temp_dir = TempDir::new("test_lmdb_queue").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let queue = LmdbQueue::<str>::new_from_env(&mut env, "queue", true).unwrap();
+
+        let mut txn = env.begin_rw_txn().unwrap();
+        assert_eq!(queue.push_back(&mut txn, "a").unwrap(), 0);
+        assert_eq!(queue.push_back(&mut txn, "b").unwrap(), 1);
+        queue.pop_front(&mut txn).unwrap();
+        queue.pop_front(&mut txn).unwrap();
+        assert!(queue.is_empty(&txn).unwrap());
+        assert_eq!(queue.push_back(&mut txn, "c").unwrap(), 2);
+
+        queue.clear(&mut txn).unwrap();
+        assert_eq!(queue.push_back(&mut txn, "d").unwrap(), 3);
+        txn.commit().unwrap();
+
+        // The next id is persisted.
+        drop(env);
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let queue = LmdbQueue::<str>::new_from_env(&mut env, "queue", false).unwrap();
+        let mut txn = env.begin_rw_txn().unwrap();
+        assert_eq!(
+            queue.pop_front(&mut txn).unwrap(),
+            Some((3, "d".to_string()))
+        );
+        assert_eq!(queue.push_back(&mut txn, "e").unwrap(), 4);
+    }
+}
@@ -0,0 +1,182 @@
+use std::ops::Bound;
+
+use lmdb::{Database, DatabaseFlags, RoCursor, RwTransaction, Transaction, WriteFlags};
+
+use crate::{
+    errors::StorageError,
+    lmdb_map::lmdb_stat,
+    lmdb_storage::{LmdbEnvironmentManager, LmdbExclusiveTransaction},
+    KeyIterator, LmdbKey,
+};
+
+/// A key-only database. Every key is stored with an empty value.
+#[derive(Debug)]
+pub struct LmdbSet<K: ?Sized> {
+    db: Database,
+    _key: std::marker::PhantomData<*const K>,
+}
+
+impl<K: ?Sized> Clone for LmdbSet<K> {
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db,
+            _key: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<K: ?Sized> Copy for LmdbSet<K> {}
+
+// Safety: `Database` is `Send` and `Sync`.
+unsafe impl<K: ?Sized> Send for LmdbSet<K> {}
+unsafe impl<K: ?Sized> Sync for LmdbSet<K> {}
+
+impl<K: LmdbKey + ?Sized> LmdbSet<K> {
+    pub fn new_from_env(
+        env: &mut LmdbEnvironmentManager,
+        name: Option<&str>,
+        create_if_not_exist: bool,
+    ) -> Result<Self, StorageError> {
+        let create_flags = if create_if_not_exist {
+            Some(DatabaseFlags::empty())
+        } else {
+            None
+        };
+
+        let db = env.create_database(name, create_flags)?;
+
+        Ok(Self {
+            db,
+            _key: std::marker::PhantomData,
+        })
+    }
+
+    pub fn new_from_txn(
+        txn: &mut LmdbExclusiveTransaction,
+        name: Option<&str>,
+        create_if_not_exist: bool,
+    ) -> Result<Self, StorageError> {
+        let create_flags = if create_if_not_exist {
+            Some(DatabaseFlags::empty())
+        } else {
+            None
+        };
+
+        let db = txn.create_database(name, create_flags)?;
+
+        Ok(Self {
+            db,
+            _key: std::marker::PhantomData,
+        })
+    }
+
+    pub fn database(&self) -> Database {
+        self.db
+    }
+
+    pub fn count<T: Transaction>(&self, txn: &T) -> Result<usize, StorageError> {
+        Ok(lmdb_stat(txn, self.db).map(|stat| stat.ms_entries)?)
+    }
+
+    pub fn contains<T: Transaction>(&self, txn: &T, key: &K) -> Result<bool, StorageError> {
+        let key = key.encode()?;
+        match txn.get(self.db, &key) {
+            Ok(_) => Ok(true),
+            Err(lmdb::Error::NotFound) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Returns if the key was actually inserted.
+    pub fn insert(&self, txn: &mut RwTransaction, key: &K) -> Result<bool, StorageError> {
+        let key = key.encode()?;
+        match txn.put(self.db, &key, &[], WriteFlags::NO_OVERWRITE) {
+            Ok(()) => Ok(true),
+            Err(lmdb::Error::KeyExist) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Returns if the key was actually removed.
+    pub fn remove(&self, txn: &mut RwTransaction, key: &K) -> Result<bool, StorageError> {
+        let key = key.encode()?;
+        match txn.del(self.db, &key, None) {
+            Ok(()) => Ok(true),
+            Err(lmdb::Error::NotFound) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn clear(&self, txn: &mut RwTransaction) -> Result<(), StorageError> {
+        txn.clear_db(self.db).map_err(Into::into)
+    }
+
+    pub fn iter<'txn, T: Transaction>(
+        &self,
+        txn: &'txn T,
+    ) -> Result<KeyIterator<'txn, RoCursor<'txn>, K>, StorageError> {
+        let cursor = txn.open_ro_cursor(self.db)?;
+        KeyIterator::new(cursor, Bound::Unbounded, true)
+    }
+}
+
+impl<'a, K: LmdbKey + 'a + ?Sized> LmdbSet<K> {
+    /// Extend the set with the contents of an iterator.
+    ///
+    /// Keys that exist in the set before insertion are ignored.
+    pub fn extend(
+        &self,
+        txn: &mut RwTransaction,
+        iter: impl IntoIterator<Item = &'a K>,
+    ) -> Result<(), StorageError> {
+        for key in iter {
+            self.insert(txn, key)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions};
+
+    use super::*;
+
+    use tempdir::TempDir;
+
+    #[test]
+    fn test_lmdb_set() {
+        let temp_dir = TempDir::new("test_lmdb_set").unwrap();
+        let env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+
+        let set = LmdbSet::<u64>::new_from_txn(&mut txn, None, true).unwrap();
+        assert_eq!(set.count(txn.txn()).unwrap(), 0);
+
+        assert!(set.insert(txn.txn_mut(), &1).unwrap());
+        assert!(!set.insert(txn.txn_mut(), &1).unwrap());
+        assert!(set.contains(txn.txn(), &1).unwrap());
+        assert!(!set.contains(txn.txn(), &2).unwrap());
+
+        set.extend(txn.txn_mut(), [&2, &3, &1]).unwrap();
+        assert_eq!(set.count(txn.txn()).unwrap(), 3);
+        assert_eq!(
+            set.iter(txn.txn())
+                .unwrap()
+                .map(|key| key.map(|key| key.into_owned()))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            vec![1, 2, 3]
+        );
+
+        assert!(set.remove(txn.txn_mut(), &2).unwrap());
+        assert!(!set.remove(txn.txn_mut(), &2).unwrap());
+        assert_eq!(set.count(txn.txn()).unwrap(), 2);
+    }
+}
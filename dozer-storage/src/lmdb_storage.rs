@@ -1,16 +1,32 @@
+use crate::comparator::{set_comparator, KeyComparator};
 use crate::errors::StorageError;
+use crate::lmdb_database::RawIterator;
+use crate::lmdb_map::lmdb_stat;
+use crate::observer::{self, StorageObserver};
+use crate::read_txn_pool::{PooledRoTransaction, ReadTransactionPool};
+use crate::snapshot;
+use crate::verify::{self, VerifyOptions, VerifyReport};
+use dozer_types::log::{debug, info, warn};
 use dozer_types::parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use lmdb::{
     Database, DatabaseFlags, Environment, EnvironmentFlags, RoCursor, RoTransaction, RwCursor,
     RwTransaction, Transaction, WriteFlags,
 };
+use std::ffi::CString;
 use std::fs;
+use std::io::{Read, Write};
+use std::ops::{Bound, Deref, DerefMut};
+use std::panic::Location;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
+use std::thread::{self, JoinHandle, ThreadId};
+use std::time::{Duration, Instant};
 
 const DEFAULT_MAX_DBS: u32 = 256;
 const DEFAULT_MAX_READERS: u32 = 256;
 const DEFAULT_MAX_MAP_SZ: usize = 1024 * 1024 * 1024 * 1024;
+/// The map is grown when the used ratio exceeds this threshold, if auto growing is enabled.
+const MAP_GROWTH_THRESHOLD: f64 = 0.8;
 
 #[derive(Debug, Clone, Copy)]
 pub struct LmdbEnvironmentOptions {
@@ -18,6 +34,29 @@ pub struct LmdbEnvironmentOptions {
     pub max_readers: u32,
     pub max_map_sz: usize,
     pub flags: lmdb::EnvironmentFlags,
+    /// `MDB_NOSYNC`: Don't flush system buffers to disk when committing. A system crash may lose the last commits.
+    pub no_sync: bool,
+    /// `MDB_NOMETASYNC`: Flush system buffers to disk only once per transaction, omitting the metadata flush.
+    pub no_meta_sync: bool,
+    /// `MDB_MAPASYNC`: Use asynchronous flushes to disk. Requires `write_map`.
+    pub map_async: bool,
+    /// `MDB_WRITEMAP`: Use a writeable memory map.
+    pub write_map: bool,
+    /// `MDB_NORDAHEAD`: Turn off readahead. Can improve random read performance when the database is larger than RAM.
+    pub no_read_ahead: bool,
+    /// If set, the map size is multiplied by this factor when it's almost full.
+    ///
+    /// The map is checked after every commit, so a single transaction that fills the map still fails with `MDB_MAP_FULL`.
+    /// Writers can then grow the map and replay the transaction, see `LmdbExclusiveTransaction::abort_grow_and_renew`.
+    pub map_growth_factor: Option<f64>,
+    /// Use the lock file `{name}-lock` to coordinate with other processes opening the same environment.
+    ///
+    /// Without it, the environment is opened with `NO_LOCK` and must not be opened by more than one process at a time.
+    /// LMDB write transactions then hold a lock that is bound to the thread that began them, so the `SharedTransaction`
+    /// of such an environment can only be committed on the thread that created it.
+    pub multi_process: bool,
+    /// If set, a warning with the acquiring call site is logged when a `SharedTransaction` write lock is held longer than this.
+    pub slow_write_lock_threshold: Option<Duration>,
 }
 
 impl LmdbEnvironmentOptions {
@@ -32,8 +71,62 @@ impl LmdbEnvironmentOptions {
             max_readers,
             max_map_sz,
             flags,
+            no_sync: false,
+            no_meta_sync: false,
+            map_async: false,
+            write_map: false,
+            no_read_ahead: false,
+            map_growth_factor: None,
+            multi_process: false,
+            slow_write_lock_threshold: None,
         }
     }
+
+    /// Validates the options and returns the flags to open the environment with.
+    pub fn environment_flags(&self) -> Result<EnvironmentFlags, StorageError> {
+        let read_only = self.flags.contains(EnvironmentFlags::READ_ONLY);
+        if read_only {
+            for (enabled, name) in [
+                (self.no_sync, "no_sync"),
+                (self.no_meta_sync, "no_meta_sync"),
+                (self.map_async, "map_async"),
+                (self.write_map, "write_map"),
+                (self.map_growth_factor.is_some(), "map_growth_factor"),
+            ] {
+                if enabled {
+                    return Err(StorageError::InvalidEnvironmentOptions(format!(
+                        "`{name}` cannot be used with a read only environment"
+                    )));
+                }
+            }
+        }
+        if self.map_async && !self.write_map {
+            return Err(StorageError::InvalidEnvironmentOptions(
+                "`map_async` requires `write_map`".to_string(),
+            ));
+        }
+        if let Some(factor) = self.map_growth_factor {
+            if factor <= 1.0 {
+                return Err(StorageError::InvalidEnvironmentOptions(format!(
+                    "`map_growth_factor` must be greater than 1, got {factor}"
+                )));
+            }
+        }
+
+        let mut flags = self.flags;
+        for (enabled, flag) in [
+            (self.no_sync, EnvironmentFlags::NO_SYNC),
+            (self.no_meta_sync, EnvironmentFlags::NO_META_SYNC),
+            (self.map_async, EnvironmentFlags::MAP_ASYNC),
+            (self.write_map, EnvironmentFlags::WRITE_MAP),
+            (self.no_read_ahead, EnvironmentFlags::NO_READAHEAD),
+        ] {
+            if enabled {
+                flags |= flag;
+            }
+        }
+        Ok(flags)
+    }
 }
 
 impl Default for LmdbEnvironmentOptions {
@@ -43,16 +136,73 @@ impl Default for LmdbEnvironmentOptions {
             max_readers: DEFAULT_MAX_READERS,
             max_map_sz: DEFAULT_MAX_MAP_SZ,
             flags: EnvironmentFlags::empty(),
+            no_sync: false,
+            no_meta_sync: false,
+            map_async: false,
+            write_map: false,
+            no_read_ahead: false,
+            map_growth_factor: None,
+            multi_process: false,
+            slow_write_lock_threshold: None,
         }
     }
 }
 
+/// Capacity and usage statistics of an environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LmdbEnvironmentInfo {
+    /// Size of the memory map in bytes.
+    pub map_size: usize,
+    pub page_size: u32,
+    /// Number of pages ever used, including free pages.
+    pub used_pages: usize,
+    /// Number of pages in the freelist, which can be reused by later writes.
+    pub free_pages: usize,
+    pub max_readers: u32,
+    /// Number of reader slots in use. Always 0 for environments opened with `NO_LOCK`.
+    pub num_readers: u32,
+    /// Id of the last committed transaction.
+    pub last_txn_id: usize,
+}
+
+impl LmdbEnvironmentInfo {
+    /// Number of bytes that can still be written before the map is full.
+    pub fn available_size(&self) -> usize {
+        self.map_size.saturating_sub(self.used_size())
+    }
+
+    /// Fraction of the map in use, between 0 and 1. Writes fail with `MDB_MAP_FULL` at 1.
+    pub fn map_used_ratio(&self) -> f64 {
+        if self.map_size == 0 {
+            return 0.0;
+        }
+        self.used_size() as f64 / self.map_size as f64
+    }
+
+    fn used_size(&self) -> usize {
+        (self.used_pages - self.free_pages) * self.page_size as usize
+    }
+}
+
+/// Statistics of an environment and its named databases, for exporting telemetry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LmdbEnvironmentStats {
+    pub info: LmdbEnvironmentInfo,
+    /// Number of entries of every named database, by name.
+    pub database_entries: Vec<(String, usize)>,
+}
+
 #[derive(Debug)]
-/// This is a safe wrapper around `lmdb::Environment` that is opened with `NO_TLS` and `NO_LOCK`.
+/// This is a safe wrapper around `lmdb::Environment` that is opened with `NO_TLS`, and `NO_LOCK` unless `multi_process` is set.
 ///
 /// All write related methods that use `Environment` take `&mut self` to avoid race between transactions.
 pub struct LmdbEnvironmentManager {
+    // Declared before `inner` so pooled transactions are dropped before the environment.
+    read_txn_pool: ReadTransactionPool,
     inner: Environment,
+    map_growth_factor: Option<f64>,
+    multi_process: bool,
+    slow_write_lock_threshold: Option<Duration>,
 }
 
 impl LmdbEnvironmentManager {
@@ -61,9 +211,11 @@ impl LmdbEnvironmentManager {
         Path::exists(full_path.as_path())
     }
 
+    /// Removes the environment file and its lock file, if any.
     pub fn remove(path: &Path, name: &str) {
         let full_path = path.join(Path::new(name));
         let _ = fs::remove_file(full_path);
+        let _ = fs::remove_file(path.join(lock_file_name(name)));
     }
 
     pub fn create(
@@ -72,26 +224,54 @@ impl LmdbEnvironmentManager {
         options: LmdbEnvironmentOptions,
     ) -> Result<Self, StorageError> {
         let full_path = base_path.join(Path::new(name));
+        let flags = options.environment_flags()?;
 
         let mut builder = Environment::new();
         builder.set_max_dbs(options.max_dbs);
         builder.set_map_size(options.max_map_sz);
         builder.set_max_readers(options.max_readers);
-        builder.set_flags(
-            options.flags
-                | EnvironmentFlags::NO_SUB_DIR
-                | EnvironmentFlags::NO_TLS
-                | EnvironmentFlags::NO_LOCK,
-        );
+        let mut flags = flags | EnvironmentFlags::NO_SUB_DIR | EnvironmentFlags::NO_TLS;
+        if !options.multi_process {
+            flags |= EnvironmentFlags::NO_LOCK;
+        }
+        builder.set_flags(flags);
 
         let env = builder.open(&full_path)?;
-        Ok(LmdbEnvironmentManager { inner: env })
+        Ok(LmdbEnvironmentManager {
+            read_txn_pool: ReadTransactionPool::default(),
+            inner: env,
+            map_growth_factor: options.map_growth_factor,
+            multi_process: options.multi_process,
+            slow_write_lock_threshold: options.slow_write_lock_threshold,
+        })
+    }
+
+    /// Opens an environment that is being written by another process, for reading.
+    ///
+    /// The writing process must open the environment with `multi_process` too.
+    /// Transactions fail with `MDB_MAP_RESIZED` after the writer grows the map beyond `max_map_sz`, so it should be large.
+    pub fn open_shared_reader(
+        base_path: &Path,
+        name: &str,
+        options: LmdbEnvironmentOptions,
+    ) -> Result<Self, StorageError> {
+        Self::create(
+            base_path,
+            name,
+            LmdbEnvironmentOptions {
+                flags: options.flags | EnvironmentFlags::READ_ONLY,
+                multi_process: true,
+                ..options
+            },
+        )
     }
 
+    /// For environments opened with `multi_process`, the transaction can only be committed on the calling thread,
+    /// and the last clone must be dropped on it too. Otherwise the write lock stays held until the calling thread exits.
     pub fn create_txn(self) -> Result<SharedTransaction, StorageError> {
-        Ok(SharedTransaction(Arc::new(RwLock::new(
-            LmdbExclusiveTransaction::new(self.inner)?,
-        ))))
+        let mut txn = LmdbExclusiveTransaction::new(self.inner, self.map_growth_factor)?;
+        txn.slow_write_lock_threshold = self.slow_write_lock_threshold;
+        Ok(SharedTransaction(Arc::new(RwLock::new(txn))))
     }
 
     pub fn create_database(
@@ -100,12 +280,83 @@ impl LmdbEnvironmentManager {
         create_flags: Option<DatabaseFlags>,
     ) -> Result<Database, StorageError> {
         if let Some(flags) = create_flags {
-            Ok(self.inner.create_db(name, flags)?)
+            match self.inner.create_db(name, flags) {
+                Ok(db) => Ok(db),
+                Err(lmdb::Error::MapFull) if self.map_growth_factor.is_some() => {
+                    // The failed transaction only contained this database creation, so we can grow and retry.
+                    self.grow_map(self.map_growth_factor.unwrap())?;
+                    Ok(self.inner.create_db(name, flags)?)
+                }
+                Err(e) => Err(e.into()),
+            }
         } else {
             Ok(self.inner.open_db(name)?)
         }
     }
 
+    /// Like `create_database`, but orders keys with `comparator`.
+    ///
+    /// The database must be opened with the same comparator every time, see the `comparator` module.
+    pub fn create_database_with_comparator(
+        &mut self,
+        name: Option<&str>,
+        create_flags: Option<DatabaseFlags>,
+        comparator: KeyComparator,
+    ) -> Result<Database, StorageError> {
+        let db = self.create_database(name, create_flags)?;
+        // The comparator is stored in the environment, so setting it in any transaction is enough.
+        let txn = self.inner.begin_ro_txn()?;
+        set_comparator(&txn, db, comparator)?;
+        txn.commit()?;
+        Ok(db)
+    }
+
+    pub fn info(&self) -> Result<LmdbEnvironmentInfo, StorageError> {
+        environment_info(&self.inner)
+    }
+
+    /// Reads `info` and the number of entries of every named database in one read transaction.
+    pub fn stats(&self) -> Result<LmdbEnvironmentStats, StorageError> {
+        Ok(LmdbEnvironmentStats {
+            info: self.info()?,
+            database_entries: database_entries(&self.inner.begin_ro_txn()?)?,
+        })
+    }
+
+    /// Clears reader slots left by dead processes and returns the number of cleared slots.
+    ///
+    /// Only environments opened with `multi_process` have reader slots, so this fails for others.
+    pub fn clear_stale_readers(&self) -> Result<usize, StorageError> {
+        clear_stale_readers(&self.inner)
+    }
+
+    pub fn is_multi_process(&self) -> bool {
+        self.multi_process
+    }
+
+    /// Copies the environment to a new file at `path`. Can be used while transactions are active.
+    ///
+    /// If `compact` is true, free pages are omitted and pages are renumbered sequentially, making the copy smaller but slower.
+    pub fn copy_to(&self, path: &Path, compact: bool) -> Result<(), StorageError> {
+        copy_environment(&self.inner, path, compact)
+    }
+
+    /// Writes all named databases to `writer` as a tar archive, taken in one read transaction so it's consistent.
+    ///
+    /// Databases are stored in the portable format of the `export` module. Data in the unnamed database and custom comparators are not included.
+    pub fn export_tar(&self, writer: impl Write) -> Result<(), StorageError> {
+        let txn = self.begin_ro_txn()?;
+        snapshot::export_tar(&txn, writer)
+    }
+
+    /// Restores the databases of an archive written by `export_tar` in one write transaction, overwriting existing keys.
+    pub fn import_tar(&mut self, reader: impl Read) -> Result<(), StorageError> {
+        let mut txn = self.begin_rw_txn()?;
+        snapshot::import_tar(&mut txn, reader)?;
+        txn.commit()?;
+        Ok(())
+    }
+
     pub fn begin_ro_txn(&self) -> Result<RoTransaction, StorageError> {
         Ok(self.inner.begin_ro_txn()?)
     }
@@ -113,6 +364,98 @@ impl LmdbEnvironmentManager {
     pub fn begin_rw_txn(&mut self) -> Result<RwTransaction, StorageError> {
         Ok(self.inner.begin_rw_txn()?)
     }
+
+    /// Like `begin_ro_txn`, but renews a transaction reset by a previously dropped `PooledRoTransaction` if there's one.
+    ///
+    /// Prefer this for short, frequent reads.
+    pub fn begin_pooled_ro_txn(&self) -> Result<PooledRoTransaction, StorageError> {
+        // SAFETY: All transactions in the pool are from `self.inner`, which is dropped after the pool.
+        unsafe { self.read_txn_pool.begin(&self.inner) }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn pooled_ro_txns(&self) -> usize {
+        self.read_txn_pool.len()
+    }
+
+    /// Tries to fix the cause of a failed write, so it can be retried. Returns `false` if `error` can't be fixed.
+    pub(crate) fn recover_from_write_error(
+        &mut self,
+        error: &lmdb::Error,
+    ) -> Result<bool, StorageError> {
+        match error {
+            lmdb::Error::MapFull => match self.map_growth_factor {
+                Some(map_growth_factor) => {
+                    self.grow_map(map_growth_factor)?;
+                    Ok(true)
+                }
+                None => Ok(false),
+            },
+            lmdb::Error::MapResized => {
+                // SAFETY: `&mut self` guarantees that there's no active transaction. Size 0 adopts the size of the file.
+                lmdb_result(unsafe { lmdb_sys::mdb_env_set_mapsize(self.inner.env(), 0) })?;
+                Ok(true)
+            }
+            lmdb::Error::ReadersFull if self.multi_process => {
+                Ok(clear_stale_readers(&self.inner)? > 0)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn grow_map(&mut self, map_growth_factor: f64) -> Result<(), StorageError> {
+        // `&mut self` guarantees that no transaction is open, but pooled transactions still hold their reader slots.
+        self.read_txn_pool.clear();
+        grow_map(&self.inner, map_growth_factor)
+    }
+
+    /// Returns the names of all named databases in the environment.
+    pub fn list_databases(&self) -> Result<Vec<String>, StorageError> {
+        list_databases(&self.inner.begin_ro_txn()?)
+    }
+
+    /// Walks every database in key order, checking that entries are sorted and, for databases registered in `options`,
+    /// that keys and values decode. Problems found are returned in the report, not as errors.
+    pub fn verify(&self, options: &VerifyOptions) -> Result<VerifyReport, StorageError> {
+        let txn = self.inner.begin_ro_txn()?;
+        let names = list_databases(&txn)?;
+        verify::verify_databases(&txn, names, options)
+    }
+
+    /// Deletes the database `name` and its data. Returns `false` if the database doesn't exist.
+    ///
+    /// Handles of the dropped database must not be used afterwards.
+    pub fn drop_database(&mut self, name: &str) -> Result<bool, StorageError> {
+        let mut txn = self.inner.begin_rw_txn()?;
+        let dropped = drop_database(&mut txn, name)?;
+        txn.commit()?;
+        Ok(dropped)
+    }
+
+    /// Renames the database `from` to `to`. Returns `false` if `from` doesn't exist.
+    ///
+    /// See `rename_database` for details.
+    pub fn rename_database(&mut self, from: &str, to: &str) -> Result<bool, StorageError> {
+        let mut txn = self.inner.begin_rw_txn()?;
+        let renamed = rename_database(&mut txn, from, to)?;
+        txn.commit()?;
+        Ok(renamed)
+    }
+
+    /// Recreates the database `name` without `INTEGER_KEY` and `INTEGER_DUP`, so its keys and values are compared
+    /// byte-wise, see `LmdbKey`. Returns `false` if the database doesn't exist or has neither flag.
+    ///
+    /// See `recreate_with_byte_wise_order` for details.
+    pub fn recreate_with_byte_wise_order(
+        &mut self,
+        name: &str,
+        comparator: Option<KeyComparator>,
+    ) -> Result<bool, StorageError> {
+        let mut txn = self.inner.begin_rw_txn()?;
+        let recreated = recreate_with_byte_wise_order(&mut txn, name, comparator)?;
+        txn.commit()?;
+        Ok(recreated)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -125,18 +468,99 @@ impl SharedTransaction {
             .map_err(Self)
     }
 
-    pub fn write(&self) -> RwLockWriteGuard<LmdbExclusiveTransaction> {
-        self.0.write()
+    /// If `slow_write_lock_threshold` is set, the caller of this method is reported when the returned guard is held for too long.
+    #[track_caller]
+    pub fn write(&self) -> SharedTransactionWriteGuard {
+        let location = Location::caller();
+        let guard = self.0.write();
+        let acquired_at = guard
+            .slow_write_lock_threshold
+            .map(|threshold| (Instant::now(), threshold));
+        SharedTransactionWriteGuard {
+            guard,
+            acquired_at,
+            location,
+        }
     }
 
     pub fn read(&self) -> RwLockReadGuard<LmdbExclusiveTransaction> {
         self.0.read()
     }
+
+    /// Spawns a thread that clears stale reader slots every `interval`.
+    ///
+    /// The thread exits after all clones of this `SharedTransaction` are dropped.
+    /// Fails if the environment is not opened with `multi_process`, see `LmdbEnvironmentManager::clear_stale_readers`.
+    pub fn spawn_stale_reader_cleaner(
+        &self,
+        interval: Duration,
+    ) -> Result<JoinHandle<()>, StorageError> {
+        check_uses_lock_file(&self.read().env)?;
+        let txn = WeakSharedTransaction(Arc::downgrade(&self.0));
+        Ok(thread::spawn(move || loop {
+            thread::sleep(interval);
+            let Some(txn) = txn.upgrade() else {
+                break;
+            };
+            match txn.read().clear_stale_readers() {
+                Ok(0) => (),
+                Ok(count) => info!("Cleared {} stale LMDB reader slots", count),
+                Err(e) => warn!("Failed to clear stale LMDB reader slots: {}", e),
+            }
+        }))
+    }
+}
+
+/// Write guard of a `SharedTransaction`, see `SharedTransaction::write`.
+pub struct SharedTransactionWriteGuard<'a> {
+    guard: RwLockWriteGuard<'a, LmdbExclusiveTransaction>,
+    acquired_at: Option<(Instant, Duration)>,
+    location: &'static Location<'static>,
+}
+
+impl<'a> Deref for SharedTransactionWriteGuard<'a> {
+    type Target = LmdbExclusiveTransaction;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<'a> DerefMut for SharedTransactionWriteGuard<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+impl<'a> Drop for SharedTransactionWriteGuard<'a> {
+    fn drop(&mut self) {
+        if let Some((acquired_at, threshold)) = self.acquired_at {
+            let held = acquired_at.elapsed();
+            if held > threshold {
+                warn!(
+                    "Write lock of shared transaction acquired at {} was held for {:?}",
+                    self.location, held
+                );
+            }
+        }
+    }
 }
 
+struct WeakSharedTransaction(Weak<RwLock<LmdbExclusiveTransaction>>);
+
+impl WeakSharedTransaction {
+    fn upgrade(&self) -> Option<Arc<RwLock<LmdbExclusiveTransaction>>> {
+        self.0.upgrade()
+    }
+}
+
+// SAFETY: Same as `SharedTransaction`. `WeakSharedTransaction` only uses the environment, never the inner transaction.
+unsafe impl Send for WeakSharedTransaction {}
+
 // SAFETY:
 // - `SharedTransaction` can only be created from `LmdbEnvironmentManager::create_txn`.
-// - `LmdbEnvironmentManager` is opened with `NO_TLS` and `NO_LOCK`.
+// - Environments are opened with `NO_TLS`, and unless they're opened with `NO_LOCK`, `commit_and_renew` refuses
+//   to release the write lock on a thread that doesn't own it.
 // - Inner `lmdb::RwTransaction` is protected by `RwLock`.
 unsafe impl Send for SharedTransaction {}
 unsafe impl Sync for SharedTransaction {}
@@ -144,13 +568,19 @@ unsafe impl Sync for SharedTransaction {}
 pub struct LmdbExclusiveTransaction {
     inner: Option<RwTransaction<'static>>,
     env: Environment,
+    map_growth_factor: Option<f64>,
+    observer: Option<Arc<dyn StorageObserver>>,
+    slow_write_lock_threshold: Option<Duration>,
+    /// The thread holding the write lock of a `multi_process` environment, which is the only one that can commit.
+    owner_thread: Option<ThreadId>,
 }
 
 const PANIC_MESSAGE: &str =
     "LmdbExclusiveTransaction cannot be used after `commit_and_renew` fails.";
 
 impl LmdbExclusiveTransaction {
-    pub fn new(env: Environment) -> Result<Self, StorageError> {
+    pub fn new(env: Environment, map_growth_factor: Option<f64>) -> Result<Self, StorageError> {
+        let owner_thread = uses_lock_file(&env)?.then(|| thread::current().id());
         let inner = env.begin_rw_txn()?;
         // SAFETY:
         // - `inner` does not reference data in `env`, it only has to be outlived by `env`.
@@ -161,12 +591,67 @@ impl LmdbExclusiveTransaction {
         Ok(Self {
             inner: Some(inner),
             env,
+            map_growth_factor,
+            observer: None,
+            slow_write_lock_threshold: None,
+            owner_thread,
         })
     }
 
-    /// If this method fails, following calls to `self` will panic.
+    /// Reports `put`, `del`, `get` and `commit_and_renew` calls to `observer`.
+    pub fn set_observer(&mut self, observer: Option<Arc<dyn StorageObserver>>) {
+        self.observer = observer;
+    }
+
+    /// If this method fails, following calls to `self` will panic, unless it fails with `TransactionThreadMismatch`.
+    #[cfg_attr(
+        feature = "instrument",
+        tracing::instrument(level = "debug", skip_all, name = "lmdb_commit")
+    )]
     pub fn commit_and_renew(&mut self) -> Result<(), StorageError> {
+        self.check_owner_thread()?;
+        let start = observer::start(self.observer.as_deref());
         self.inner.take().expect(PANIC_MESSAGE).commit()?;
+        if let (Some(observer), Some(start)) = (&self.observer, start) {
+            observer.on_commit(start.elapsed());
+        }
+        if let Some(map_growth_factor) = self.map_growth_factor {
+            // No transaction is active now, so it's safe to resize the map.
+            grow_map_if_needed(&self.env, map_growth_factor)?;
+        }
+        self.renew()
+    }
+
+    /// Aborts the transaction, grows the map by `map_growth_factor` and begins a new transaction.
+    ///
+    /// Use this to recover from `MDB_MAP_FULL` when a transaction doesn't fit in the map.
+    /// All writes since the last commit are lost, so the caller has to replay them.
+    /// Fails if `map_growth_factor` is not set. Can be called after `commit_and_renew` fails.
+    pub fn abort_grow_and_renew(&mut self) -> Result<(), StorageError> {
+        let Some(map_growth_factor) = self.map_growth_factor else {
+            return Err(StorageError::InvalidEnvironmentOptions(
+                "the map can't grow without `map_growth_factor`".to_string(),
+            ));
+        };
+        self.check_owner_thread()?;
+        if let Some(inner) = self.inner.take() {
+            inner.abort();
+        }
+        // No transaction is active now. The environment is owned by `self`, so no other transaction can be open.
+        grow_map(&self.env, map_growth_factor)?;
+        self.renew()
+    }
+
+    fn check_owner_thread(&self) -> Result<(), StorageError> {
+        match self.owner_thread {
+            Some(owner_thread) if thread::current().id() != owner_thread => {
+                Err(StorageError::TransactionThreadMismatch)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn renew(&mut self) -> Result<(), StorageError> {
         let inner = self.env.begin_rw_txn()?;
         // SAFETY: Same as `new`.
         let inner =
@@ -194,6 +679,69 @@ impl LmdbExclusiveTransaction {
         Ok(db)
     }
 
+    /// Like `create_database`, but orders keys with `comparator`.
+    ///
+    /// The database must be opened with the same comparator every time, see the `comparator` module.
+    pub fn create_database_with_comparator(
+        &mut self,
+        name: Option<&str>,
+        create_flags: Option<DatabaseFlags>,
+        comparator: KeyComparator,
+    ) -> Result<Database, StorageError> {
+        let db = self.create_database(name, create_flags)?;
+        set_comparator(self.txn(), db, comparator)?;
+        Ok(db)
+    }
+
+    /// Returns the names of all named databases, including the ones created in this transaction.
+    pub fn list_databases(&self) -> Result<Vec<String>, StorageError> {
+        list_databases(self.txn())
+    }
+
+    /// Deletes the database `name` and its data. Returns `false` if the database doesn't exist.
+    ///
+    /// Handles of the dropped database must not be used afterwards.
+    /// If this method fails, following calls to `self` will panic.
+    pub fn drop_database(&mut self, name: &str) -> Result<bool, StorageError> {
+        let dropped = drop_database(self.txn_mut(), name)?;
+        self.commit_and_renew()?;
+        Ok(dropped)
+    }
+
+    /// Renames the database `from` to `to`. Returns `false` if `from` doesn't exist.
+    ///
+    /// See `rename_database` for details.
+    /// If this method fails, following calls to `self` will panic.
+    pub fn rename_database(&mut self, from: &str, to: &str) -> Result<bool, StorageError> {
+        let renamed = rename_database(self.txn_mut(), from, to)?;
+        self.commit_and_renew()?;
+        Ok(renamed)
+    }
+
+    pub fn info(&self) -> Result<LmdbEnvironmentInfo, StorageError> {
+        environment_info(&self.env)
+    }
+
+    /// See `LmdbEnvironmentManager::stats`. Entries written in this transaction are counted.
+    pub fn stats(&self) -> Result<LmdbEnvironmentStats, StorageError> {
+        Ok(LmdbEnvironmentStats {
+            info: self.info()?,
+            database_entries: database_entries(self.txn())?,
+        })
+    }
+
+    /// See `LmdbEnvironmentManager::clear_stale_readers`.
+    pub fn clear_stale_readers(&self) -> Result<usize, StorageError> {
+        clear_stale_readers(&self.env)
+    }
+
+    /// Copies the environment to a new file at `path`. Only committed data is copied.
+    ///
+    /// See `LmdbEnvironmentManager::copy_to`.
+    pub fn copy_to(&self, path: &Path, compact: bool) -> Result<(), StorageError> {
+        copy_environment(&self.env, path, compact)
+    }
+
     pub fn txn(&self) -> &RwTransaction {
         self.inner.as_ref().expect(PANIC_MESSAGE)
     }
@@ -209,13 +757,35 @@ impl LmdbExclusiveTransaction {
         }
     }
 
+    /// Begins a child transaction that can be committed into, or aborted without affecting, this transaction.
+    ///
+    /// Dropping the child transaction without committing aborts it. Not supported for environments opened with `write_map`.
+    pub fn begin_nested_txn(&mut self) -> Result<RwTransaction, StorageError> {
+        Ok(self.txn_mut().begin_nested_txn()?)
+    }
+
+    /// Runs `f` in a child transaction, which is committed if `f` returns `Ok` and aborted otherwise.
+    pub fn nested<T, E: From<StorageError>>(
+        &mut self,
+        f: impl FnOnce(&mut RwTransaction) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let mut txn = self.begin_nested_txn()?;
+        let result = f(&mut txn)?;
+        txn.commit().map_err(StorageError::Lmdb)?;
+        Ok(result)
+    }
+
     #[inline]
     pub fn put(&mut self, db: Database, key: &[u8], value: &[u8]) -> Result<(), StorageError> {
+        let start = observer::start(self.observer.as_deref());
         self.inner
             .as_mut()
             .expect(PANIC_MESSAGE)
-            .put(db, &key, &value, WriteFlags::default())
-            .map_err(Into::into)
+            .put(db, &key, &value, WriteFlags::default())?;
+        if let (Some(observer), Some(start)) = (&self.observer, start) {
+            observer.on_put(db, key.len(), value.len(), start.elapsed());
+        }
+        Ok(())
     }
 
     #[inline]
@@ -225,16 +795,21 @@ impl LmdbExclusiveTransaction {
         key: &[u8],
         value: Option<&[u8]>,
     ) -> Result<bool, StorageError> {
-        match self
+        let start = observer::start(self.observer.as_deref());
+        let found = match self
             .inner
             .as_mut()
             .expect(PANIC_MESSAGE)
             .del(db, &key, value)
         {
-            Ok(()) => Ok(true),
-            Err(lmdb::Error::NotFound) => Ok(false),
-            Err(err) => Err(err.into()),
+            Ok(()) => true,
+            Err(lmdb::Error::NotFound) => false,
+            Err(err) => return Err(err.into()),
+        };
+        if let (Some(observer), Some(start)) = (&self.observer, start) {
+            observer.on_del(db, key.len(), found, start.elapsed());
         }
+        Ok(found)
     }
 
     #[inline]
@@ -249,11 +824,16 @@ impl LmdbExclusiveTransaction {
 
     #[inline]
     pub fn get(&self, db: Database, key: &[u8]) -> Result<Option<&[u8]>, StorageError> {
-        match self.inner.as_ref().expect(PANIC_MESSAGE).get(db, &key) {
-            Ok(value) => Ok(Some(value)),
-            Err(lmdb::Error::NotFound) => Ok(None),
-            Err(err) => Err(err.into()),
+        let start = observer::start(self.observer.as_deref());
+        let value = match self.inner.as_ref().expect(PANIC_MESSAGE).get(db, &key) {
+            Ok(value) => Some(value),
+            Err(lmdb::Error::NotFound) => None,
+            Err(err) => return Err(err.into()),
+        };
+        if let (Some(observer), Some(start)) = (&self.observer, start) {
+            observer.on_get(db, key.len(), value.map(<[u8]>::len), start.elapsed());
         }
+        Ok(value)
     }
 
     #[inline]
@@ -266,3 +846,840 @@ impl LmdbExclusiveTransaction {
         Ok(cursor)
     }
 }
+
+/// Named databases are stored as records of the unnamed database, so we list the keys of the unnamed database,
+/// skipping the ones that are ordinary records.
+pub(crate) fn list_databases<T: Transaction>(txn: &T) -> Result<Vec<String>, StorageError> {
+    // SAFETY: The unnamed database always exists and the handle is not kept.
+    let main_db = unsafe { txn.open_db(None)? };
+    let keys = RawIterator::new(txn.open_ro_cursor(main_db)?, Bound::Unbounded, true)?
+        .map(|item| item.map(|(key, _)| key.to_vec()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut names = vec![];
+    for key in keys {
+        let Ok(name) = String::from_utf8(key) else {
+            continue;
+        };
+        if name.contains('\0') {
+            continue;
+        }
+        // SAFETY: The handle is not kept.
+        match unsafe { txn.open_db(Some(&name)) } {
+            Ok(_) => names.push(name),
+            Err(lmdb::Error::Incompatible) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(names)
+}
+
+fn database_entries<T: Transaction>(txn: &T) -> Result<Vec<(String, usize)>, StorageError> {
+    list_databases(txn)?
+        .into_iter()
+        .map(|name| {
+            // SAFETY: The handle is not kept.
+            let db = unsafe { txn.open_db(Some(&name))? };
+            let entries = lmdb_stat(txn, db)?.ms_entries;
+            Ok((name, entries))
+        })
+        .collect()
+}
+
+fn drop_database(txn: &mut RwTransaction, name: &str) -> Result<bool, StorageError> {
+    let Some(db) = open_database_if_exists(txn, name)? else {
+        return Ok(false);
+    };
+    // SAFETY: Callers are responsible for not using the handle after the database is dropped.
+    unsafe { txn.drop_db(db)? };
+    Ok(true)
+}
+
+/// LMDB has no rename, so the database is copied to a new database with the same flags, and then dropped.
+///
+/// All entries of `from` are buffered in memory during the copy.
+/// Fails with `DatabaseAlreadyExists` if `to` exists.
+fn rename_database(txn: &mut RwTransaction, from: &str, to: &str) -> Result<bool, StorageError> {
+    let Some(from_db) = open_database_if_exists(txn, from)? else {
+        return Ok(false);
+    };
+    if open_database_if_exists(txn, to)?.is_some() {
+        return Err(StorageError::DatabaseAlreadyExists(to.to_string()));
+    }
+
+    let flags = database_flags(txn, from_db)?;
+    let entries = RawIterator::new(txn.open_ro_cursor(from_db)?, Bound::Unbounded, true)?
+        .map(|item| item.map(|(key, value)| (key.to_vec(), value.to_vec())))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // SAFETY: The handle of `to` is returned to no one, and callers are responsible for not using the handle of `from`.
+    unsafe {
+        let to_db = txn.create_db(Some(to), flags)?;
+        for (key, value) in entries {
+            txn.put(to_db, &key, &value, WriteFlags::empty())?;
+        }
+        txn.drop_db(from_db)?;
+    }
+    Ok(true)
+}
+
+/// LMDB keeps the flags a database was created with, so databases created with `INTEGER_KEY` or `INTEGER_DUP` keep
+/// comparing integers in native endian until they are recreated.
+///
+/// The entries are buffered in memory, the database is dropped and created again with its other flags, and the entries
+/// are written back. `comparator` must be the key comparator the database is opened with, if any.
+fn recreate_with_byte_wise_order(
+    txn: &mut RwTransaction,
+    name: &str,
+    comparator: Option<KeyComparator>,
+) -> Result<bool, StorageError> {
+    let Some(db) = open_database_if_exists(txn, name)? else {
+        return Ok(false);
+    };
+    let integer_flags = DatabaseFlags::INTEGER_KEY | DatabaseFlags::INTEGER_DUP;
+    let flags = database_flags(txn, db)?;
+    if !flags.intersects(integer_flags) {
+        return Ok(false);
+    }
+
+    let entries = RawIterator::new(txn.open_ro_cursor(db)?, Bound::Unbounded, true)?
+        .map(|item| item.map(|(key, value)| (key.to_vec(), value.to_vec())))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // SAFETY: Callers are responsible for not using the handle of the dropped database.
+    unsafe {
+        txn.drop_db(db)?;
+        let db = txn.create_db(Some(name), flags - integer_flags)?;
+        if let Some(comparator) = comparator {
+            set_comparator(txn, db, comparator)?;
+        }
+        for (key, value) in entries {
+            txn.put(db, &key, &value, WriteFlags::empty())?;
+        }
+    }
+    Ok(true)
+}
+
+fn open_database_if_exists(
+    txn: &RwTransaction,
+    name: &str,
+) -> Result<Option<Database>, StorageError> {
+    // SAFETY: The handle is only used in this transaction.
+    match unsafe { txn.open_db(Some(name)) } {
+        Ok(db) => Ok(Some(db)),
+        Err(lmdb::Error::NotFound) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Returns the flags `db` was created with.
+pub(crate) fn database_flags<T: Transaction>(
+    txn: &T,
+    db: Database,
+) -> Result<DatabaseFlags, StorageError> {
+    let mut flags = 0;
+    // SAFETY: `txn` and `db` are valid.
+    let code = unsafe { lmdb_sys::mdb_dbi_flags(txn.txn(), db.dbi(), &mut flags) };
+    lmdb_result(code).map(|()| DatabaseFlags::from_bits_truncate(flags))
+}
+
+/// LMDB names the lock file of a `NO_SUB_DIR` environment by appending `-lock` to the path.
+fn lock_file_name(name: &str) -> String {
+    format!("{name}-lock")
+}
+
+/// Whether `env` was opened without `NO_LOCK`.
+fn uses_lock_file(env: &Environment) -> Result<bool, StorageError> {
+    let mut flags = 0;
+    // SAFETY: The environment is valid.
+    let code = unsafe { lmdb_sys::mdb_env_get_flags(env.env(), &mut flags) };
+    lmdb_result(code).map(|()| flags & EnvironmentFlags::NO_LOCK.bits() == 0)
+}
+
+/// Environments opened with `NO_LOCK` have no reader table, so there's nothing to check.
+fn check_uses_lock_file(env: &Environment) -> Result<(), StorageError> {
+    if uses_lock_file(env)? {
+        Ok(())
+    } else {
+        Err(StorageError::InvalidEnvironmentOptions(
+            "stale readers can only be cleared in a `multi_process` environment".to_string(),
+        ))
+    }
+}
+
+fn clear_stale_readers(env: &Environment) -> Result<usize, StorageError> {
+    check_uses_lock_file(env)?;
+    let mut dead = 0;
+    // SAFETY: The environment is valid.
+    let code = unsafe { lmdb_sys::mdb_reader_check(env.env(), &mut dead) };
+    lmdb_result(code).map(|()| dead as usize)
+}
+
+fn copy_environment(env: &Environment, path: &Path, compact: bool) -> Result<(), StorageError> {
+    let c_path = path
+        .to_str()
+        .and_then(|path| CString::new(path).ok())
+        .ok_or_else(|| StorageError::InvalidPath(path.to_path_buf()))?;
+    let flags = if compact { lmdb_sys::MDB_CP_COMPACT } else { 0 };
+    // SAFETY: The environment is valid and `c_path` is a nul terminated string.
+    let code = unsafe { lmdb_sys::mdb_env_copy2(env.env(), c_path.as_ptr(), flags) };
+    lmdb_result(code)
+}
+
+/// Grows the map if the used ratio exceeds `MAP_GROWTH_THRESHOLD`.
+///
+/// Must not be called when there's an active transaction.
+fn grow_map_if_needed(env: &Environment, growth_factor: f64) -> Result<(), StorageError> {
+    let info = env_info(env)?;
+    let stat = env_stat(env)?;
+    let used_size = (info.me_last_pgno + 1) * stat.ms_psize as usize;
+    if (used_size as f64) < info.me_mapsize as f64 * MAP_GROWTH_THRESHOLD {
+        return Ok(());
+    }
+    grow_map(env, growth_factor)
+}
+
+/// Must not be called when there's an active transaction.
+fn grow_map(env: &Environment, growth_factor: f64) -> Result<(), StorageError> {
+    let map_size = env_info(env)?.me_mapsize;
+    let new_map_size = (map_size as f64 * growth_factor) as usize;
+    debug!(
+        "Growing LMDB map from {} to {} bytes",
+        map_size, new_map_size
+    );
+    // SAFETY: The environment is valid and there's no active transaction.
+    let code = unsafe { lmdb_sys::mdb_env_set_mapsize(env.env(), new_map_size) };
+    lmdb_result(code)
+}
+
+fn environment_info(env: &Environment) -> Result<LmdbEnvironmentInfo, StorageError> {
+    let info = env_info(env)?;
+    let stat = env_stat(env)?;
+    Ok(LmdbEnvironmentInfo {
+        map_size: info.me_mapsize,
+        page_size: stat.ms_psize,
+        used_pages: info.me_last_pgno + 1,
+        free_pages: free_pages(env)?,
+        max_readers: info.me_maxreaders,
+        num_readers: info.me_numreaders,
+        last_txn_id: info.me_last_txnid,
+    })
+}
+
+/// Counts the pages recorded in the freelist database.
+fn free_pages(env: &Environment) -> Result<usize, StorageError> {
+    let txn = env.begin_ro_txn()?;
+    let mut cursor: *mut lmdb_sys::MDB_cursor = std::ptr::null_mut();
+    // SAFETY: The freelist database always has dbi 0.
+    lmdb_result(unsafe { lmdb_sys::mdb_cursor_open(txn.txn(), 0, &mut cursor) })?;
+
+    let mut key = lmdb_sys::MDB_val {
+        mv_size: 0,
+        mv_data: std::ptr::null_mut(),
+    };
+    let mut data = lmdb_sys::MDB_val {
+        mv_size: 0,
+        mv_data: std::ptr::null_mut(),
+    };
+    let mut free_pages = 0;
+    let result = loop {
+        // SAFETY: `cursor` is open.
+        match unsafe { lmdb_sys::mdb_cursor_get(cursor, &mut key, &mut data, lmdb_sys::MDB_NEXT) } {
+            lmdb_sys::MDB_SUCCESS => {
+                // Every value is a list of page numbers, prefixed by its length.
+                // SAFETY: The value is at least one `size_t` long.
+                free_pages += unsafe { std::ptr::read_unaligned(data.mv_data as *const usize) };
+            }
+            lmdb_sys::MDB_NOTFOUND => break Ok(free_pages),
+            code => break Err(lmdb::Error::from_err_code(code).into()),
+        }
+    };
+    // SAFETY: `cursor` is open and its transaction is still alive.
+    unsafe { lmdb_sys::mdb_cursor_close(cursor) };
+    result
+}
+
+fn env_info(env: &Environment) -> Result<lmdb_sys::MDB_envinfo, StorageError> {
+    let mut info = lmdb_sys::MDB_envinfo {
+        me_mapaddr: std::ptr::null_mut(),
+        me_mapsize: 0,
+        me_last_pgno: 0,
+        me_last_txnid: 0,
+        me_maxreaders: 0,
+        me_numreaders: 0,
+    };
+    let code = unsafe { lmdb_sys::mdb_env_info(env.env(), &mut info) };
+    lmdb_result(code).map(|()| info)
+}
+
+fn env_stat(env: &Environment) -> Result<lmdb_sys::MDB_stat, StorageError> {
+    let mut stat = lmdb_sys::MDB_stat {
+        ms_psize: 0,
+        ms_depth: 0,
+        ms_branch_pages: 0,
+        ms_leaf_pages: 0,
+        ms_overflow_pages: 0,
+        ms_entries: 0,
+    };
+    let code = unsafe { lmdb_sys::mdb_env_stat(env.env(), &mut stat) };
+    lmdb_result(code).map(|()| stat)
+}
+
+fn lmdb_result(code: std::ffi::c_int) -> Result<(), StorageError> {
+    if code == lmdb_sys::MDB_SUCCESS {
+        Ok(())
+    } else {
+        Err(lmdb::Error::from_err_code(code).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    use tempdir::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_map_auto_growth() {
+        let temp_dir = TempDir::new("test_map_auto_growth").unwrap();
+        let map_size = 1024 * 1024;
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions {
+                max_map_sz: map_size,
+                map_growth_factor: Some(2.0),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let db = env
+            .create_database(None, Some(DatabaseFlags::empty()))
+            .unwrap();
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+
+        // Write more than the initial map size, committing in small batches.
+        let value = vec![0u8; 1024];
+        for i in 0..4096u32 {
+            txn.put(db, &i.to_be_bytes(), &value).unwrap();
+            if i % 64 == 0 {
+                txn.commit_and_renew().unwrap();
+            }
+        }
+        txn.commit_and_renew().unwrap();
+
+        assert!(env_info(&txn.env).unwrap().me_mapsize > map_size);
+    }
+
+    #[test]
+    fn test_abort_grow_and_renew() {
+        let temp_dir = TempDir::new("test_abort_grow_and_renew").unwrap();
+        let map_size = 1024 * 1024;
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions {
+                max_map_sz: map_size,
+                map_growth_factor: Some(2.0),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let db = env
+            .create_database(None, Some(DatabaseFlags::empty()))
+            .unwrap();
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+        txn.put(db, b"committed", b"value").unwrap();
+        txn.commit_and_renew().unwrap();
+
+        // Write more than the map can hold in one transaction.
+        let value = vec![0u8; 1024];
+        let error = (0..4096u32)
+            .find_map(|i| txn.put(db, &i.to_be_bytes(), &value).err())
+            .unwrap();
+        assert!(matches!(error, StorageError::Lmdb(lmdb::Error::MapFull)));
+
+        txn.abort_grow_and_renew().unwrap();
+        assert_eq!(txn.info().unwrap().map_size, 2 * map_size);
+        assert!(txn.get(db, b"committed").unwrap().is_some());
+        assert!(txn.get(db, &0u32.to_be_bytes()).unwrap().is_none());
+
+        // Replaying a part of the failed transaction fits now.
+        for i in 0..512u32 {
+            txn.put(db, &i.to_be_bytes(), &value).unwrap();
+        }
+        txn.commit_and_renew().unwrap();
+    }
+
+    #[test]
+    fn test_environment_flags() {
+        let options = LmdbEnvironmentOptions {
+            no_sync: true,
+            write_map: true,
+            map_async: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            options.environment_flags().unwrap(),
+            EnvironmentFlags::NO_SYNC | EnvironmentFlags::WRITE_MAP | EnvironmentFlags::MAP_ASYNC
+        );
+
+        let options = LmdbEnvironmentOptions {
+            map_async: true,
+            ..Default::default()
+        };
+        assert!(options.environment_flags().is_err());
+
+        let options = LmdbEnvironmentOptions {
+            flags: EnvironmentFlags::READ_ONLY,
+            no_read_ahead: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            options.environment_flags().unwrap(),
+            EnvironmentFlags::READ_ONLY | EnvironmentFlags::NO_READAHEAD
+        );
+
+        let options = LmdbEnvironmentOptions {
+            flags: EnvironmentFlags::READ_ONLY,
+            no_sync: true,
+            ..Default::default()
+        };
+        assert!(options.environment_flags().is_err());
+    }
+
+    #[test]
+    fn test_copy_to() {
+        let temp_dir = TempDir::new("test_copy_to").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let db = env
+            .create_database(Some("db"), Some(DatabaseFlags::empty()))
+            .unwrap();
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+        txn.put(db, b"key", b"value").unwrap();
+        txn.commit_and_renew().unwrap();
+        // Uncommitted data is not copied.
+        txn.put(db, b"uncommitted", b"value").unwrap();
+
+        for (name, compact) in [("copy", false), ("compact_copy", true)] {
+            txn.copy_to(&temp_dir.path().join(name), compact).unwrap();
+
+            let mut copy =
+                LmdbEnvironmentManager::create(temp_dir.path(), name, Default::default()).unwrap();
+            let db = copy.create_database(Some("db"), None).unwrap();
+            let copy_txn = copy.begin_ro_txn().unwrap();
+            assert_eq!(copy_txn.get(db, b"key").unwrap(), b"value");
+            assert_eq!(
+                copy_txn.get(db, b"uncommitted").unwrap_err(),
+                lmdb::Error::NotFound
+            );
+        }
+    }
+
+    #[test]
+    fn test_nested_transaction() {
+        let temp_dir = TempDir::new("test_nested_transaction").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let db = env
+            .create_database(None, Some(DatabaseFlags::empty()))
+            .unwrap();
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+        txn.put(db, b"parent", b"value").unwrap();
+
+        txn.nested(|txn| {
+            txn.put(db, b"committed", b"value", WriteFlags::empty())?;
+            Ok::<_, StorageError>(())
+        })
+        .unwrap();
+        txn.nested(|txn| {
+            txn.put(db, b"aborted", b"value", WriteFlags::empty())?;
+            Err::<(), _>(StorageError::InvalidRecord)
+        })
+        .unwrap_err();
+        drop(txn.begin_nested_txn().unwrap());
+
+        assert!(txn.get(db, b"parent").unwrap().is_some());
+        assert!(txn.get(db, b"committed").unwrap().is_some());
+        assert!(txn.get(db, b"aborted").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_clear_stale_readers() {
+        let temp_dir = TempDir::new("test_clear_stale_readers").unwrap();
+        let options = LmdbEnvironmentOptions {
+            multi_process: true,
+            ..Default::default()
+        };
+        let env = LmdbEnvironmentManager::create(temp_dir.path(), "env", options).unwrap();
+        assert_eq!(env.clear_stale_readers().unwrap(), 0);
+
+        assert!(run_child_test("exit_with_open_reader", temp_dir.path()));
+        assert_eq!(env.clear_stale_readers().unwrap(), 1);
+        assert_eq!(env.clear_stale_readers().unwrap(), 0);
+
+        let txn = env.create_txn().unwrap();
+        assert!(run_child_test("exit_with_open_reader", temp_dir.path()));
+        assert_eq!(txn.read().clear_stale_readers().unwrap(), 1);
+        let cleaner = txn
+            .spawn_stale_reader_cleaner(Duration::from_millis(1))
+            .unwrap();
+        drop(txn);
+        cleaner.join().unwrap();
+    }
+
+    /// Run by `test_clear_stale_readers`. Exits without releasing the reader slot, like a crashed process.
+    #[test]
+    fn exit_with_open_reader() {
+        let Some(dir) = child_env_dir() else {
+            return;
+        };
+        let reader =
+            LmdbEnvironmentManager::open_shared_reader(&dir, "env", Default::default()).unwrap();
+        std::mem::forget(reader.begin_ro_txn().unwrap());
+        // Tells `run_child_test` that the test passed, because the test harness doesn't get to print the result.
+        println!("1 passed");
+        std::process::exit(0);
+    }
+
+    #[test]
+    fn test_clear_stale_readers_requires_multi_process() {
+        let temp_dir = TempDir::new("test_clear_stale_readers_requires_multi_process").unwrap();
+        let env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        assert!(matches!(
+            env.clear_stale_readers(),
+            Err(StorageError::InvalidEnvironmentOptions(_))
+        ));
+
+        let txn = env.create_txn().unwrap();
+        assert!(matches!(
+            txn.spawn_stale_reader_cleaner(Duration::from_millis(1)),
+            Err(StorageError::InvalidEnvironmentOptions(_))
+        ));
+    }
+
+    #[test]
+    fn test_environment_info() {
+        let temp_dir = TempDir::new("test_environment_info").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let db = env
+            .create_database(None, Some(DatabaseFlags::empty()))
+            .unwrap();
+        let info = env.info().unwrap();
+        assert_eq!(info.map_size, DEFAULT_MAX_MAP_SZ);
+        assert_eq!(info.max_readers, DEFAULT_MAX_READERS);
+
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+        for i in 0..1024u32 {
+            txn.put(db, &i.to_be_bytes(), &[0; 128]).unwrap();
+        }
+        txn.commit_and_renew().unwrap();
+        for i in 0..1024u32 {
+            txn.del(db, &i.to_be_bytes(), None).unwrap();
+        }
+        txn.commit_and_renew().unwrap();
+
+        let new_info = txn.info().unwrap();
+        assert!(new_info.last_txn_id > info.last_txn_id);
+        assert!(new_info.used_pages > info.used_pages);
+        assert!(new_info.free_pages > 0);
+        assert!(new_info.available_size() <= new_info.map_size);
+        assert!(new_info.map_used_ratio() > 0.0 && new_info.map_used_ratio() < 1.0);
+    }
+
+    #[test]
+    fn test_environment_stats() {
+        let temp_dir = TempDir::new("test_environment_stats").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let users = env
+            .create_database(Some("users"), Some(DatabaseFlags::empty()))
+            .unwrap();
+        env.create_database(Some("orders"), Some(DatabaseFlags::empty()))
+            .unwrap();
+        let mut stats = env.stats().unwrap();
+        stats.database_entries.sort();
+        assert_eq!(
+            stats.database_entries,
+            vec![("orders".to_string(), 0), ("users".to_string(), 0)]
+        );
+
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+        for i in 0..10u32 {
+            txn.put(users, &i.to_be_bytes(), &[0; 16]).unwrap();
+        }
+        let mut stats = txn.stats().unwrap();
+        stats.database_entries.sort();
+        assert_eq!(
+            stats.database_entries,
+            vec![("orders".to_string(), 0), ("users".to_string(), 10)]
+        );
+        assert_eq!(stats.info, txn.info().unwrap());
+    }
+
+    #[test]
+    fn test_list_drop_rename_databases() {
+        let temp_dir = TempDir::new("test_list_drop_rename_databases").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let main_db = env
+            .create_database(None, Some(DatabaseFlags::empty()))
+            .unwrap();
+        let a = env
+            .create_database(Some("a"), Some(DatabaseFlags::DUP_SORT))
+            .unwrap();
+        env.create_database(Some("b"), Some(DatabaseFlags::empty()))
+            .unwrap();
+        assert_eq!(env.list_databases().unwrap(), vec!["a", "b"]);
+        assert!(env.drop_database("b").unwrap());
+        assert!(!env.drop_database("b").unwrap());
+        assert_eq!(env.list_databases().unwrap(), vec!["a"]);
+
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+        txn.put(main_db, b"record", b"value").unwrap();
+        txn.put(a, b"key", b"value1").unwrap();
+        txn.put(a, b"key", b"value2").unwrap();
+        txn.commit_and_renew().unwrap();
+        assert_eq!(txn.list_databases().unwrap(), vec!["a"]);
+
+        assert!(txn.rename_database("a", "c").unwrap());
+        assert!(!txn.rename_database("a", "c").unwrap());
+        assert_eq!(txn.list_databases().unwrap(), vec!["c"]);
+        let c = txn.create_database(Some("c"), None).unwrap();
+        let values = RawIterator::new(txn.open_ro_cursor(c).unwrap(), Bound::Unbounded, true)
+            .unwrap()
+            .map(|item| item.unwrap().1)
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![b"value1", b"value2"]);
+        assert!(database_flags(txn.txn(), c)
+            .unwrap()
+            .contains(DatabaseFlags::DUP_SORT));
+
+        txn.create_database(Some("d"), Some(DatabaseFlags::empty()))
+            .unwrap();
+        assert!(matches!(
+            txn.rename_database("c", "d"),
+            Err(StorageError::DatabaseAlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    fn test_recreate_with_byte_wise_order() {
+        let temp_dir = TempDir::new("test_recreate_with_byte_wise_order").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let flags = DatabaseFlags::INTEGER_KEY
+            | DatabaseFlags::DUP_SORT
+            | DatabaseFlags::DUP_FIXED
+            | DatabaseFlags::INTEGER_DUP;
+        let db = env.create_database(Some("ints"), Some(flags)).unwrap();
+        let mut txn = env.begin_rw_txn().unwrap();
+        for (key, value) in [(1_u64, 256_u64), (256, 1), (256, 256)] {
+            txn.put(
+                db,
+                &key.to_be_bytes(),
+                &value.to_be_bytes(),
+                WriteFlags::empty(),
+            )
+            .unwrap();
+        }
+        txn.commit().unwrap();
+
+        assert!(env.recreate_with_byte_wise_order("ints", None).unwrap());
+        assert!(!env.recreate_with_byte_wise_order("ints", None).unwrap());
+        assert!(!env.recreate_with_byte_wise_order("missing", None).unwrap());
+
+        let db = env.create_database(Some("ints"), None).unwrap();
+        let txn = env.begin_ro_txn().unwrap();
+        assert_eq!(
+            database_flags(&txn, db).unwrap(),
+            DatabaseFlags::DUP_SORT | DatabaseFlags::DUP_FIXED
+        );
+        let entries = RawIterator::new(txn.open_ro_cursor(db).unwrap(), Bound::Unbounded, true)
+            .unwrap()
+            .map(|item| {
+                let (key, value) = item.unwrap();
+                (
+                    u64::from_be_bytes(key.try_into().unwrap()),
+                    u64::from_be_bytes(value.try_into().unwrap()),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(entries, vec![(1, 256), (256, 1), (256, 256)]);
+    }
+
+    /// Set for tests run by `run_child_test`, to the directory of the environment the test should open.
+    const CHILD_ENV_DIR: &str = "DOZER_STORAGE_CHILD_ENV_DIR";
+
+    /// Runs the test `name` of this module in a child process and returns whether it passed.
+    ///
+    /// Environments with a lock file must not be opened twice in one process, so that's how we get a second one.
+    fn run_child_test(name: &str, dir: &Path) -> bool {
+        let test = format!("lmdb_storage::tests::{name}");
+        let output = Command::new(std::env::current_exe().unwrap())
+            .args([test.as_str(), "--exact", "--nocapture"])
+            .env(CHILD_ENV_DIR, dir)
+            .output()
+            .unwrap();
+        output.status.success() && String::from_utf8_lossy(&output.stdout).contains("1 passed")
+    }
+
+    /// The directory passed to `run_child_test`, or `None` if the test is not run by it.
+    fn child_env_dir() -> Option<PathBuf> {
+        std::env::var_os(CHILD_ENV_DIR).map(PathBuf::from)
+    }
+
+    #[test]
+    fn test_multi_process_environment() {
+        let temp_dir = TempDir::new("test_multi_process_environment").unwrap();
+        let options = LmdbEnvironmentOptions {
+            multi_process: true,
+            ..Default::default()
+        };
+        let mut env = LmdbEnvironmentManager::create(temp_dir.path(), "env", options).unwrap();
+        assert!(temp_dir.path().join(lock_file_name("env")).exists());
+
+        let db = env
+            .create_database(None, Some(DatabaseFlags::empty()))
+            .unwrap();
+        let shared = env.create_txn().unwrap();
+        let mut txn = shared.write();
+        txn.put(db, b"key", b"value").unwrap();
+        txn.commit_and_renew().unwrap();
+        txn.put(db, b"uncommitted", b"value").unwrap();
+        drop(txn);
+
+        assert!(run_child_test("read_shared_environment", temp_dir.path()));
+
+        let other = shared.clone();
+        let result = thread::spawn(move || other.write().commit_and_renew())
+            .join()
+            .unwrap();
+        assert!(matches!(
+            result,
+            Err(StorageError::TransactionThreadMismatch)
+        ));
+        shared.write().commit_and_renew().unwrap();
+        drop(shared);
+
+        LmdbEnvironmentManager::remove(temp_dir.path(), "env");
+        assert!(!LmdbEnvironmentManager::exists(temp_dir.path(), "env"));
+        assert!(!temp_dir.path().join(lock_file_name("env")).exists());
+    }
+
+    /// Run by `test_multi_process_environment` while the other process holds a write transaction.
+    #[test]
+    fn read_shared_environment() {
+        let Some(dir) = child_env_dir() else {
+            return;
+        };
+        let mut reader =
+            LmdbEnvironmentManager::open_shared_reader(&dir, "env", Default::default()).unwrap();
+        let db = reader.create_database(None, None).unwrap();
+        let txn = reader.begin_ro_txn().unwrap();
+        assert_eq!(txn.get(db, b"key").unwrap(), b"value");
+        assert!(matches!(
+            txn.get(db, b"uncommitted"),
+            Err(lmdb::Error::NotFound)
+        ));
+        drop(txn);
+        assert!(reader.begin_rw_txn().is_err());
+    }
+
+    #[test]
+    fn test_transaction_observer() {
+        let temp_dir = TempDir::new("test_transaction_observer").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let db = env
+            .create_database(None, Some(DatabaseFlags::empty()))
+            .unwrap();
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+
+        let observer = Arc::new(crate::observer::tests::CountingObserver::default());
+        txn.set_observer(Some(observer.clone()));
+        txn.put(db, b"key", b"value").unwrap();
+        txn.get(db, b"key").unwrap();
+        txn.get(db, b"missing").unwrap();
+        txn.del(db, b"key", None).unwrap();
+        txn.commit_and_renew().unwrap();
+
+        let count = |counter: &std::sync::atomic::AtomicUsize| {
+            counter.load(std::sync::atomic::Ordering::Relaxed)
+        };
+        assert_eq!(count(&observer.puts), 1);
+        assert_eq!(count(&observer.put_bytes), 8);
+        assert_eq!(count(&observer.gets), 2);
+        assert_eq!(count(&observer.misses), 1);
+        assert_eq!(count(&observer.dels), 1);
+        assert_eq!(count(&observer.commits), 1);
+    }
+
+    #[test]
+    fn test_slow_write_lock_tracking() {
+        let temp_dir = TempDir::new("test_slow_write_lock_tracking").unwrap();
+        let options = LmdbEnvironmentOptions {
+            slow_write_lock_threshold: Some(Duration::ZERO),
+            ..Default::default()
+        };
+        let mut env = LmdbEnvironmentManager::create(temp_dir.path(), "env", options).unwrap();
+        let db = env
+            .create_database(None, Some(DatabaseFlags::empty()))
+            .unwrap();
+        let txn = env.create_txn().unwrap();
+
+        let mut guard = txn.write();
+        assert_eq!(guard.location.file(), file!());
+        assert_eq!(guard.acquired_at.unwrap().1, Duration::ZERO);
+        guard.put(db, b"key", b"value").unwrap();
+        drop(guard);
+
+        assert!(txn.read().get(db, b"key").unwrap().is_some());
+    }
+}
@@ -0,0 +1,293 @@
+use std::borrow::Cow;
+
+use lmdb::{RwTransaction, Transaction};
+
+use crate::{
+    errors::StorageError,
+    lmdb_storage::{LmdbEnvironmentManager, LmdbExclusiveTransaction},
+    LmdbKey, LmdbMap, LmdbMultimap, LmdbValue,
+};
+
+const EXPIRY_LEN: usize = std::mem::size_of::<u64>();
+
+/// A map whose entries expire at a given time, with an expiry-ordered database for evicting expired entries in bulk.
+///
+/// Times are `u64`s in a unit chosen by the caller, for example milliseconds since the Unix epoch.
+/// An entry is expired once `now >= expires_at`.
+///
+/// The expiry database is named `{name}__expiry` and maps expiry times to encoded keys.
+#[derive(Debug)]
+pub struct LmdbTtlMap<K: ?Sized, V: ?Sized> {
+    map: LmdbMap<K, [u8]>,
+    expiries: LmdbMultimap<u64, [u8]>,
+    _value: std::marker::PhantomData<*const V>,
+}
+
+impl<K: ?Sized, V: ?Sized> Clone for LmdbTtlMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map,
+            expiries: self.expiries,
+            _value: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<K: ?Sized, V: ?Sized> Copy for LmdbTtlMap<K, V> {}
+
+// Safety: `Database` is `Send` and `Sync`.
+unsafe impl<K: ?Sized, V: ?Sized> Send for LmdbTtlMap<K, V> {}
+unsafe impl<K: ?Sized, V: ?Sized> Sync for LmdbTtlMap<K, V> {}
+
+impl<K: LmdbKey + ?Sized, V: LmdbValue + ?Sized> LmdbTtlMap<K, V> {
+    pub fn new_from_env(
+        env: &mut LmdbEnvironmentManager,
+        name: &str,
+        create_if_not_exist: bool,
+    ) -> Result<Self, StorageError> {
+        let map = LmdbMap::new_from_env(env, Some(name), create_if_not_exist)?;
+        let expiries = LmdbMultimap::new_from_env(
+            env,
+            Some(expiry_database_name(name).as_str()),
+            create_if_not_exist,
+        )?;
+        Ok(Self::new(map, expiries))
+    }
+
+    pub fn new_from_txn(
+        txn: &mut LmdbExclusiveTransaction,
+        name: &str,
+        create_if_not_exist: bool,
+    ) -> Result<Self, StorageError> {
+        let map = LmdbMap::new_from_txn(txn, Some(name), create_if_not_exist)?;
+        let expiries = LmdbMultimap::new_from_txn(
+            txn,
+            Some(expiry_database_name(name).as_str()),
+            create_if_not_exist,
+        )?;
+        Ok(Self::new(map, expiries))
+    }
+
+    fn new(map: LmdbMap<K, [u8]>, expiries: LmdbMultimap<u64, [u8]>) -> Self {
+        Self {
+            map,
+            expiries,
+            _value: std::marker::PhantomData,
+        }
+    }
+
+    pub fn map(&self) -> LmdbMap<K, [u8]> {
+        self.map
+    }
+
+    pub fn expiries(&self) -> LmdbMultimap<u64, [u8]> {
+        self.expiries
+    }
+
+    /// Returns the number of entries, including expired ones that are not evicted yet.
+    pub fn count<T: Transaction>(&self, txn: &T) -> Result<usize, StorageError> {
+        self.map.count(txn)
+    }
+
+    /// Returns the value of `key` if it exists and is not expired at `now`.
+    pub fn get<'a, T: Transaction>(
+        &self,
+        txn: &'a T,
+        key: &K,
+        now: u64,
+    ) -> Result<Option<Cow<'a, V>>, StorageError> {
+        match self.get_stored(txn, key)? {
+            Some((expires_at, value)) if now < expires_at => V::decode(value).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the expiry time of `key`, even if it's expired.
+    pub fn expires_at<T: Transaction>(
+        &self,
+        txn: &T,
+        key: &K,
+    ) -> Result<Option<u64>, StorageError> {
+        Ok(self.get_stored(txn, key)?.map(|(expires_at, _)| expires_at))
+    }
+
+    /// Sets `key` to `value`, expiring at `expires_at`, replacing the existing value and expiry.
+    pub fn insert(
+        &self,
+        txn: &mut RwTransaction,
+        key: &K,
+        value: &V,
+        expires_at: u64,
+    ) -> Result<(), StorageError> {
+        self.remove(txn, key)?;
+
+        let value = value.encode()?;
+        let value = value.as_ref();
+        let mut stored = Vec::with_capacity(EXPIRY_LEN + value.len());
+        stored.extend_from_slice(&expires_at.to_be_bytes());
+        stored.extend_from_slice(value);
+        if !self.map.insert(txn, key, &stored)? {
+            panic!("We just removed the key");
+        }
+        self.expiries
+            .insert(txn, &expires_at, key.encode()?.as_ref())?;
+        Ok(())
+    }
+
+    /// Returns if the key was actually removed.
+    pub fn remove(&self, txn: &mut RwTransaction, key: &K) -> Result<bool, StorageError> {
+        let Some(expires_at) = self.expires_at(txn, key)? else {
+            return Ok(false);
+        };
+        self.expiries
+            .remove(txn, &expires_at, key.encode()?.as_ref())?;
+        self.map.remove(txn, key)
+    }
+
+    /// Removes all entries that are expired at `now` and returns the number of removed entries.
+    ///
+    /// Expiry times are keys of `expiries`, which are ordered numerically, see `LmdbKey`, so iteration stops at the first
+    /// unexpired entry.
+    pub fn evict_expired(&self, txn: &mut RwTransaction, now: u64) -> Result<usize, StorageError> {
+        let mut expired = vec![];
+        for result in self.expiries.iter(txn)? {
+            let (expires_at, key) = result?;
+            let expires_at = expires_at.into_owned();
+            if expires_at > now {
+                break;
+            }
+            expired.push((expires_at, key.into_owned()));
+        }
+
+        for (expires_at, key) in &expired {
+            self.expiries.remove(txn, expires_at, key)?;
+            txn.del(self.map.database(), key, None)?;
+        }
+        Ok(expired.len())
+    }
+
+    pub fn clear(&self, txn: &mut RwTransaction) -> Result<(), StorageError> {
+        self.map.clear(txn)?;
+        txn.clear_db(self.expiries.database()).map_err(Into::into)
+    }
+
+    fn get_stored<'a, T: Transaction>(
+        &self,
+        txn: &'a T,
+        key: &K,
+    ) -> Result<Option<(u64, &'a [u8])>, StorageError> {
+        let Some(stored) = self.map.get_borrowed(txn, key)? else {
+            return Ok(None);
+        };
+        if stored.len() < EXPIRY_LEN {
+            return Err(StorageError::DeserializationError {
+                typ: "LmdbTtlMap value",
+                reason: "missing expiry".into(),
+            });
+        }
+        let (expires_at, value) = stored.split_at(EXPIRY_LEN);
+        let expires_at = u64::from_be_bytes(expires_at.try_into().expect("We checked the length"));
+        Ok(Some((expires_at, value)))
+    }
+}
+
+fn expiry_database_name(name: &str) -> String {
+    format!("{name}__expiry")
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use crate::lmdb_storage::LmdbEnvironmentOptions;
+
+    use super::*;
+
+    #[test]
+    fn test_lmdb_ttl_map() {
+        let temp_dir = TempDir::new("test_lmdb_ttl_map").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let map = LmdbTtlMap::<str, u64>::new_from_env(&mut env, "map", true).unwrap();
+
+        let mut txn = env.begin_rw_txn().unwrap();
+        map.insert(&mut txn, "a", &1, 10).unwrap();
+        map.insert(&mut txn, "b", &2, 20).unwrap();
+        map.insert(&mut txn, "c", &3, 20).unwrap();
+        map.insert(&mut txn, "d", &4, 30).unwrap();
+
+        assert_eq!(map.get(&txn, "a", 9).unwrap().unwrap().into_owned(), 1);
+        assert!(map.get(&txn, "a", 10).unwrap().is_none());
+        assert_eq!(map.expires_at(&txn, "a").unwrap(), Some(10));
+
+        // Replacing moves the expiry.
+        map.insert(&mut txn, "a", &5, 40).unwrap();
+        assert_eq!(map.get(&txn, "a", 10).unwrap().unwrap().into_owned(), 5);
+
+        assert_eq!(map.evict_expired(&mut txn, 20).unwrap(), 2);
+        assert_eq!(map.count(&txn).unwrap(), 2);
+        assert!(map.expires_at(&txn, "b").unwrap().is_none());
+        assert!(map.expires_at(&txn, "c").unwrap().is_none());
+        assert_eq!(map.evict_expired(&mut txn, 20).unwrap(), 0);
+
+        assert!(map.remove(&mut txn, "d").unwrap());
+        assert!(!map.remove(&mut txn, "d").unwrap());
+        assert_eq!(map.evict_expired(&mut txn, 100).unwrap(), 1);
+        assert_eq!(map.count(&txn).unwrap(), 0);
+        assert_eq!(map.expiries().iter(&txn).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_lmdb_ttl_map_epoch_millis() {
+        let temp_dir = TempDir::new("test_lmdb_ttl_map").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let map = LmdbTtlMap::<str, u64>::new_from_env(&mut env, "map", true).unwrap();
+
+        let now = 1_760_000_000_000;
+        let mut txn = env.begin_rw_txn().unwrap();
+        map.insert(&mut txn, "minute_ago", &1, now - 60_000)
+            .unwrap();
+        map.insert(&mut txn, "in_a_second", &2, now + 1_000)
+            .unwrap();
+        map.insert(&mut txn, "now", &3, now).unwrap();
+        map.insert(&mut txn, "in_a_day", &4, now + 86_400_000)
+            .unwrap();
+        map.insert(&mut txn, "ms_ago", &5, now - 1).unwrap();
+
+        assert_eq!(
+            map.expiries()
+                .iter(&txn)
+                .unwrap()
+                .map(|result| result.map(|(expires_at, _)| expires_at.into_owned()))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            vec![now - 60_000, now - 1, now, now + 1_000, now + 86_400_000]
+        );
+
+        assert_eq!(map.evict_expired(&mut txn, now).unwrap(), 3);
+        assert_eq!(map.count(&txn).unwrap(), 2);
+        assert_eq!(
+            map.get(&txn, "in_a_second", now)
+                .unwrap()
+                .unwrap()
+                .into_owned(),
+            2
+        );
+        assert_eq!(map.evict_expired(&mut txn, now + 1_000).unwrap(), 1);
+        assert_eq!(
+            map.expires_at(&txn, "in_a_day").unwrap(),
+            Some(now + 86_400_000)
+        );
+        assert_eq!(map.evict_expired(&mut txn, u64::MAX).unwrap(), 1);
+        assert_eq!(map.count(&txn).unwrap(), 0);
+    }
+}
@@ -0,0 +1,198 @@
+use dozer_types::log::info;
+use lmdb::Transaction;
+
+use crate::{
+    errors::StorageError, lmdb_map::lmdb_stat, lmdb_storage::LmdbEnvironmentManager, LmdbMap,
+};
+
+/// Name of the database reserved for storage metadata, such as the layout version.
+pub const META_DATABASE_NAME: &str = "__meta";
+const LAYOUT_VERSION_KEY: &str = "layout_version";
+
+/// Upgrades an environment from layout version `version() - 1` to `version()`.
+///
+/// A crash between the migration and the version update causes the migration to run again, so migrations should be
+/// safe to rerun.
+pub trait Migration {
+    fn version(&self) -> u32;
+
+    fn migrate(&self, env: &mut LmdbEnvironmentManager) -> Result<(), StorageError>;
+}
+
+/// Brings the layout of `env` to the latest version, running the migrations it hasn't applied yet, in order.
+///
+/// `migrations` must have consecutive versions starting from 1. The latest version is the version of the last migration,
+/// or 0 if there's none.
+///
+/// Empty environments are stamped with the latest version. Non-empty environments without a layout version
+/// predate versioning and have version 0. Returns the layout version of `env`.
+pub fn migrate(
+    env: &mut LmdbEnvironmentManager,
+    migrations: &[&dyn Migration],
+) -> Result<u32, StorageError> {
+    let latest = latest_version(migrations);
+
+    let stored = layout_version(env)?;
+    let mut version = match stored {
+        Some(version) => version,
+        None if is_empty(env)? => latest,
+        None => 0,
+    };
+    if version > latest {
+        return Err(StorageError::UnsupportedLayoutVersion { version, latest });
+    }
+
+    for migration in &migrations[version as usize..] {
+        info!(
+            "Migrating storage layout from version {} to {}",
+            version,
+            migration.version()
+        );
+        migration.migrate(env)?;
+        version = migration.version();
+        write_layout_version(env, version)?;
+    }
+
+    if stored.is_none() {
+        write_layout_version(env, version)?;
+    }
+    Ok(version)
+}
+
+/// Checks that `env` is at the latest layout version, without migrating it. Suitable for read only environments.
+pub fn check_layout_version(
+    env: &mut LmdbEnvironmentManager,
+    migrations: &[&dyn Migration],
+) -> Result<(), StorageError> {
+    let latest = latest_version(migrations);
+    let version = layout_version(env)?.unwrap_or(0);
+    if version == latest {
+        Ok(())
+    } else {
+        Err(StorageError::UnsupportedLayoutVersion { version, latest })
+    }
+}
+
+/// Returns the layout version stored in `env`, or `None` if `env` has never been versioned.
+pub fn layout_version(env: &mut LmdbEnvironmentManager) -> Result<Option<u32>, StorageError> {
+    if !env
+        .list_databases()?
+        .iter()
+        .any(|name| name == META_DATABASE_NAME)
+    {
+        return Ok(None);
+    }
+    let meta = LmdbMap::<str, u32>::new_from_env(env, Some(META_DATABASE_NAME), false)?;
+    let txn = env.begin_ro_txn()?;
+    let version = meta
+        .get(&txn, LAYOUT_VERSION_KEY)?
+        .map(|version| version.into_owned());
+    Ok(version)
+}
+
+fn write_layout_version(
+    env: &mut LmdbEnvironmentManager,
+    version: u32,
+) -> Result<(), StorageError> {
+    let meta = LmdbMap::<str, u32>::new_from_env(env, Some(META_DATABASE_NAME), true)?;
+    let mut txn = env.begin_rw_txn()?;
+    meta.update(&mut txn, LAYOUT_VERSION_KEY, |_| Some(version))?;
+    txn.commit()?;
+    Ok(())
+}
+
+fn latest_version(migrations: &[&dyn Migration]) -> u32 {
+    for (index, migration) in migrations.iter().enumerate() {
+        assert_eq!(
+            migration.version() as usize,
+            index + 1,
+            "Migrations must have consecutive versions starting from 1"
+        );
+    }
+    migrations.len() as u32
+}
+
+/// An environment is empty if its unnamed database, which also holds the named databases, has no entry.
+fn is_empty(env: &mut LmdbEnvironmentManager) -> Result<bool, StorageError> {
+    let main_db = env.create_database(None, None)?;
+    let txn = env.begin_ro_txn()?;
+    Ok(lmdb_stat(&txn, main_db)?.ms_entries == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use lmdb::DatabaseFlags;
+    use tempdir::TempDir;
+
+    use crate::lmdb_storage::LmdbEnvironmentOptions;
+
+    use super::*;
+
+    struct CountingMigration {
+        version: u32,
+        runs: Cell<usize>,
+    }
+
+    impl CountingMigration {
+        fn new(version: u32) -> Self {
+            Self {
+                version,
+                runs: Cell::new(0),
+            }
+        }
+    }
+
+    impl Migration for CountingMigration {
+        fn version(&self) -> u32 {
+            self.version
+        }
+
+        fn migrate(&self, _env: &mut LmdbEnvironmentManager) -> Result<(), StorageError> {
+            self.runs.set(self.runs.get() + 1);
+            Ok(())
+        }
+    }
+
+    fn create_env(temp_dir: &TempDir) -> LmdbEnvironmentManager {
+        LmdbEnvironmentManager::create(temp_dir.path(), "env", LmdbEnvironmentOptions::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_empty_environment_is_stamped_with_latest_version() {
+        let temp_dir =
+            TempDir::new("test_empty_environment_is_stamped_with_latest_version").unwrap();
+        let mut env = create_env(&temp_dir);
+        let first = CountingMigration::new(1);
+        assert_eq!(migrate(&mut env, &[&first]).unwrap(), 1);
+        assert_eq!(first.runs.get(), 0);
+        assert_eq!(layout_version(&mut env).unwrap(), Some(1));
+        check_layout_version(&mut env, &[&first]).unwrap();
+    }
+
+    #[test]
+    fn test_unversioned_environment_is_migrated() {
+        let temp_dir = TempDir::new("test_unversioned_environment_is_migrated").unwrap();
+        let mut env = create_env(&temp_dir);
+        env.create_database(Some("data"), Some(DatabaseFlags::empty()))
+            .unwrap();
+        assert_eq!(layout_version(&mut env).unwrap(), None);
+
+        let first = CountingMigration::new(1);
+        let second = CountingMigration::new(2);
+        assert_eq!(migrate(&mut env, &[&first]).unwrap(), 1);
+        assert!(check_layout_version(&mut env, &[&first, &second]).is_err());
+        assert_eq!(migrate(&mut env, &[&first, &second]).unwrap(), 2);
+        assert_eq!((first.runs.get(), second.runs.get()), (1, 1));
+
+        assert!(matches!(
+            migrate(&mut env, &[&first]),
+            Err(StorageError::UnsupportedLayoutVersion {
+                version: 2,
+                latest: 1
+            })
+        ));
+    }
+}
@@ -0,0 +1,76 @@
+use std::time::{Duration, Instant};
+
+use lmdb::Database;
+
+/// Receives the sizes and durations of storage operations, for exporting telemetry.
+///
+/// All methods default to doing nothing. Observers are called synchronously on the hot path, so they should be cheap.
+pub trait StorageObserver: std::fmt::Debug + Send + Sync {
+    /// `value_bytes` is `None` if the key was not found.
+    fn on_get(
+        &self,
+        _db: Database,
+        _key_bytes: usize,
+        _value_bytes: Option<usize>,
+        _duration: Duration,
+    ) {
+    }
+
+    fn on_put(&self, _db: Database, _key_bytes: usize, _value_bytes: usize, _duration: Duration) {}
+
+    /// `found` is if anything was deleted.
+    fn on_del(&self, _db: Database, _key_bytes: usize, _found: bool, _duration: Duration) {}
+
+    fn on_commit(&self, _duration: Duration) {}
+}
+
+/// Starts timing an operation if there's an observer.
+pub(crate) fn start(observer: Option<&dyn StorageObserver>) -> Option<Instant> {
+    observer.map(|_| Instant::now())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct CountingObserver {
+        pub gets: AtomicUsize,
+        pub misses: AtomicUsize,
+        pub puts: AtomicUsize,
+        pub put_bytes: AtomicUsize,
+        pub dels: AtomicUsize,
+        pub commits: AtomicUsize,
+    }
+
+    impl StorageObserver for CountingObserver {
+        fn on_get(
+            &self,
+            _db: Database,
+            _key_bytes: usize,
+            value_bytes: Option<usize>,
+            _duration: Duration,
+        ) {
+            self.gets.fetch_add(1, Ordering::Relaxed);
+            if value_bytes.is_none() {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        fn on_put(&self, _db: Database, key_bytes: usize, value_bytes: usize, _duration: Duration) {
+            self.puts.fetch_add(1, Ordering::Relaxed);
+            self.put_bytes
+                .fetch_add(key_bytes + value_bytes, Ordering::Relaxed);
+        }
+
+        fn on_del(&self, _db: Database, _key_bytes: usize, _found: bool, _duration: Duration) {
+            self.dels.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn on_commit(&self, _duration: Duration) {
+            self.commits.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
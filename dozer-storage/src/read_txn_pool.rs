@@ -0,0 +1,131 @@
+use dozer_types::parking_lot::Mutex;
+use lmdb::{Environment, InactiveTransaction, RoTransaction, Transaction};
+
+use crate::errors::StorageError;
+
+/// Maximum number of reset transactions kept for reuse.
+const MAX_POOLED_TRANSACTIONS: usize = 64;
+
+struct PooledTransaction(InactiveTransaction<'static>);
+
+// SAFETY: Environments are opened with `NO_TLS`, so read transactions are not bound to the thread that created them.
+unsafe impl Send for PooledTransaction {}
+
+/// Reset read transactions, which are renewed instead of beginning a new transaction, saving the reader table lock.
+#[derive(Default)]
+pub(crate) struct ReadTransactionPool {
+    transactions: Mutex<Vec<PooledTransaction>>,
+}
+
+impl std::fmt::Debug for ReadTransactionPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadTransactionPool")
+            .field("len", &self.transactions.lock().len())
+            .finish()
+    }
+}
+
+impl ReadTransactionPool {
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.transactions.lock().len()
+    }
+
+    /// Drops all pooled transactions, releasing their reader slots.
+    pub(crate) fn clear(&self) {
+        self.transactions.lock().clear();
+    }
+
+    /// # Safety
+    ///
+    /// All transactions of the pool must be from `env`, and `env` must outlive the pool.
+    pub(crate) unsafe fn begin<'a>(
+        &'a self,
+        env: &'a Environment,
+    ) -> Result<PooledRoTransaction<'a>, StorageError> {
+        let pooled = self.transactions.lock().pop();
+        let txn = match pooled.map(|PooledTransaction(txn)| txn.renew()) {
+            Some(Ok(txn)) => txn,
+            // Renewing can fail for example if the map was resized. Fall back to a new transaction.
+            Some(Err(_)) | None => env.begin_ro_txn()?,
+        };
+        Ok(PooledRoTransaction {
+            txn: Some(txn),
+            pool: self,
+        })
+    }
+}
+
+/// A read transaction that is reset and returned to the pool of its environment when dropped.
+///
+/// See `LmdbEnvironmentManager::begin_pooled_ro_txn`.
+pub struct PooledRoTransaction<'a> {
+    txn: Option<RoTransaction<'a>>,
+    pool: &'a ReadTransactionPool,
+}
+
+impl<'a> Transaction for PooledRoTransaction<'a> {
+    fn txn(&self) -> *mut lmdb_sys::MDB_txn {
+        self.txn
+            .as_ref()
+            .expect("Transaction is only taken when dropped")
+            .txn()
+    }
+}
+
+impl<'a> Drop for PooledRoTransaction<'a> {
+    fn drop(&mut self) {
+        let Some(txn) = self.txn.take() else {
+            return;
+        };
+        let mut transactions = self.pool.transactions.lock();
+        if transactions.len() < MAX_POOLED_TRANSACTIONS {
+            // SAFETY: Only the lifetime is transmuted. The environment outlives the pool, guaranteed by the caller of `begin`.
+            let txn = unsafe {
+                std::mem::transmute::<InactiveTransaction<'a>, InactiveTransaction<'static>>(
+                    txn.reset(),
+                )
+            };
+            transactions.push(PooledTransaction(txn));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lmdb::{DatabaseFlags, Transaction, WriteFlags};
+    use tempdir::TempDir;
+
+    use crate::lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions};
+
+    #[test]
+    fn test_pooled_read_transactions_see_latest_commit() {
+        let temp_dir = TempDir::new("test_pooled_read_transactions_see_latest_commit").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let db = env
+            .create_database(None, Some(DatabaseFlags::empty()))
+            .unwrap();
+
+        for i in 0..3u32 {
+            let mut txn = env.begin_rw_txn().unwrap();
+            txn.put(db, &i.to_be_bytes(), b"value", WriteFlags::empty())
+                .unwrap();
+            txn.commit().unwrap();
+
+            let first = env.begin_pooled_ro_txn().unwrap();
+            let second = env.begin_pooled_ro_txn().unwrap();
+            for txn in [&first, &second] {
+                for j in 0..=i {
+                    assert!(txn.get(db, &j.to_be_bytes()).is_ok());
+                }
+                assert!(txn.get(db, &(i + 1).to_be_bytes()).is_err());
+            }
+        }
+        assert_eq!(env.pooled_ro_txns(), 2);
+    }
+}
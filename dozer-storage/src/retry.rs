@@ -0,0 +1,219 @@
+use std::time::Duration;
+
+use dozer_types::log::debug;
+use lmdb::{RoTransaction, RwTransaction, Transaction};
+
+use crate::{errors::StorageError, lmdb_storage::LmdbEnvironmentManager};
+
+/// How transactions failing with transient errors are retried.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryOptions {
+    /// Including the first attempt.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    /// The backoff doubles after every failed attempt, up to this value.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryOptions {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(100),
+        }
+    }
+}
+
+impl RetryOptions {
+    /// Runs `f` in a new write transaction and commits it, retrying the whole transaction on transient errors.
+    ///
+    /// Transient errors are a full map, if the environment grows automatically, a map resized by another process,
+    /// and a full reader table, if stale readers can be cleared.
+    pub fn write<T>(
+        &self,
+        env: &mut LmdbEnvironmentManager,
+        mut f: impl FnMut(&mut RwTransaction) -> Result<T, StorageError>,
+    ) -> Result<T, StorageError> {
+        let mut backoff = Backoff::new(*self);
+        loop {
+            let error = match write_once(env, &mut f) {
+                Ok(result) => return Ok(result),
+                Err(StorageError::Lmdb(error)) => error,
+                Err(e) => return Err(e),
+            };
+            if !backoff.can_retry() || !env.recover_from_write_error(&error)? {
+                return Err(error.into());
+            }
+            debug!("Retrying write transaction after error: {}", error);
+            backoff.wait();
+        }
+    }
+
+    /// Runs `f` in a read transaction, renewing the transaction and retrying on transient errors.
+    ///
+    /// Transient errors are a full reader table and an invalid reader slot. In `multi_process` environments,
+    /// stale readers are cleared before retrying.
+    pub fn read<T>(
+        &self,
+        env: &LmdbEnvironmentManager,
+        mut f: impl FnMut(&RoTransaction) -> Result<T, StorageError>,
+    ) -> Result<T, StorageError> {
+        let mut backoff = Backoff::new(*self);
+        let mut txn = loop {
+            match env.begin_ro_txn() {
+                Ok(txn) => break txn,
+                Err(StorageError::Lmdb(error))
+                    if is_transient_read_error(&error) && backoff.can_retry() =>
+                {
+                    debug!("Retrying beginning read transaction after error: {}", error);
+                    if env.is_multi_process() {
+                        env.clear_stale_readers()?;
+                    }
+                    backoff.wait();
+                }
+                Err(e) => return Err(e),
+            }
+        };
+
+        loop {
+            match f(&txn) {
+                Ok(result) => return Ok(result),
+                Err(StorageError::Lmdb(error))
+                    if is_transient_read_error(&error) && backoff.can_retry() =>
+                {
+                    debug!("Retrying read transaction after error: {}", error);
+                    if env.is_multi_process() {
+                        env.clear_stale_readers()?;
+                    }
+                    backoff.wait();
+                    txn = txn.reset().renew()?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// `RetryOptions::write` with default options.
+pub fn with_write_txn<T>(
+    env: &mut LmdbEnvironmentManager,
+    f: impl FnMut(&mut RwTransaction) -> Result<T, StorageError>,
+) -> Result<T, StorageError> {
+    RetryOptions::default().write(env, f)
+}
+
+/// `RetryOptions::read` with default options.
+pub fn with_read_txn<T>(
+    env: &LmdbEnvironmentManager,
+    f: impl FnMut(&RoTransaction) -> Result<T, StorageError>,
+) -> Result<T, StorageError> {
+    RetryOptions::default().read(env, f)
+}
+
+fn write_once<T>(
+    env: &mut LmdbEnvironmentManager,
+    f: &mut impl FnMut(&mut RwTransaction) -> Result<T, StorageError>,
+) -> Result<T, StorageError> {
+    let mut txn = env.begin_rw_txn()?;
+    let result = f(&mut txn)?;
+    txn.commit()?;
+    Ok(result)
+}
+
+fn is_transient_read_error(error: &lmdb::Error) -> bool {
+    matches!(error, lmdb::Error::ReadersFull | lmdb::Error::BadRslot)
+}
+
+struct Backoff {
+    options: RetryOptions,
+    attempts: u32,
+    backoff: Duration,
+}
+
+impl Backoff {
+    fn new(options: RetryOptions) -> Self {
+        Self {
+            options,
+            attempts: 1,
+            backoff: options.initial_backoff,
+        }
+    }
+
+    fn can_retry(&self) -> bool {
+        self.attempts < self.options.max_attempts
+    }
+
+    fn wait(&mut self) {
+        std::thread::sleep(self.backoff);
+        self.attempts += 1;
+        self.backoff = (self.backoff * 2).min(self.options.max_backoff);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lmdb::{DatabaseFlags, WriteFlags};
+    use tempdir::TempDir;
+
+    use crate::lmdb_storage::LmdbEnvironmentOptions;
+
+    use super::*;
+
+    fn create_env(temp_dir: &TempDir, map_growth_factor: Option<f64>) -> LmdbEnvironmentManager {
+        LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions {
+                max_map_sz: 1024 * 1024,
+                map_growth_factor,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    }
+
+    fn write_large_transaction(
+        env: &mut LmdbEnvironmentManager,
+        attempts: &mut u32,
+    ) -> Result<(), StorageError> {
+        let db = env.create_database(None, Some(DatabaseFlags::empty()))?;
+        let value = vec![0u8; 1024];
+        with_write_txn(env, |txn| {
+            *attempts += 1;
+            for i in 0..1536u32 {
+                txn.put(db, &i.to_be_bytes(), &value, WriteFlags::empty())?;
+            }
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_write_retries_after_growing_map() {
+        let temp_dir = TempDir::new("test_write_retries_after_growing_map").unwrap();
+        let mut env = create_env(&temp_dir, Some(2.0));
+        let mut attempts = 0;
+        write_large_transaction(&mut env, &mut attempts).unwrap();
+        assert!(attempts > 1);
+
+        let count = with_read_txn(&env, |txn| {
+            // SAFETY: The handle is only used in this transaction.
+            let db = unsafe { txn.open_db(None)? };
+            Ok(crate::lmdb_map::lmdb_stat(txn, db)?.ms_entries)
+        })
+        .unwrap();
+        assert_eq!(count, 1536);
+    }
+
+    #[test]
+    fn test_write_does_not_retry_without_growth() {
+        let temp_dir = TempDir::new("test_write_does_not_retry_without_growth").unwrap();
+        let mut env = create_env(&temp_dir, None);
+        let mut attempts = 0;
+        assert!(matches!(
+            write_large_transaction(&mut env, &mut attempts),
+            Err(StorageError::Lmdb(lmdb::Error::MapFull))
+        ));
+        assert_eq!(attempts, 1);
+    }
+}
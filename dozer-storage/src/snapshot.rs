@@ -0,0 +1,252 @@
+//! Snapshots of whole environments as tar archives, see `LmdbEnvironmentManager::export_tar`.
+//!
+//! The archive starts with a `manifest.json` entry listing the databases and their flags, followed by one `databases/{index}` entry
+//! per database in the portable format of the `export` module.
+
+use std::io::{self, Read, Write};
+
+use dozer_types::serde::{Deserialize, Serialize};
+use lmdb::{DatabaseFlags, RwTransaction, Transaction};
+
+use crate::{
+    errors::StorageError,
+    export::{self, Compression},
+    lmdb_storage::{database_flags, list_databases},
+};
+
+const MANIFEST_PATH: &str = "manifest.json";
+const BLOCK_SIZE: usize = 512;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+struct Manifest {
+    databases: Vec<ManifestDatabase>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+struct ManifestDatabase {
+    name: String,
+    flags: u32,
+}
+
+/// Writes all named databases visible to `txn` to `writer` as a tar archive.
+pub(crate) fn export_tar<T: Transaction>(
+    txn: &T,
+    mut writer: impl Write,
+) -> Result<(), StorageError> {
+    let mut databases = vec![];
+    let mut manifest = Manifest { databases: vec![] };
+    for name in list_databases(txn)? {
+        // SAFETY: The handle is only used in this transaction.
+        let db = unsafe { txn.open_db(Some(&name))? };
+        manifest.databases.push(ManifestDatabase {
+            name,
+            flags: database_flags(txn, db)?.bits(),
+        });
+        databases.push(db);
+    }
+
+    let manifest = dozer_types::serde_json::to_vec(&manifest).map_err(|e| {
+        StorageError::SerializationError {
+            typ: "snapshot manifest",
+            reason: Box::new(e),
+        }
+    })?;
+    write_header(&mut writer, MANIFEST_PATH, manifest.len() as u64)?;
+    writer.write_all(&manifest)?;
+    write_padding(&mut writer, manifest.len() as u64)?;
+
+    for (index, db) in databases.into_iter().enumerate() {
+        let len = export::exported_len(txn, db)?;
+        write_header(&mut writer, &database_path(index), len)?;
+        export::export_database(txn, db, &mut writer, Compression::None)?;
+        write_padding(&mut writer, len)?;
+    }
+
+    // The end of archive marker is two empty blocks.
+    writer.write_all(&[0; 2 * BLOCK_SIZE])?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Creates the databases in a tar archive written by `export_tar` and imports their contents, overwriting existing keys.
+pub(crate) fn import_tar(
+    txn: &mut RwTransaction,
+    mut reader: impl Read,
+) -> Result<(), StorageError> {
+    let manifest = read_entry(&mut reader, MANIFEST_PATH, |entry| {
+        let mut bytes = vec![];
+        entry.read_to_end(&mut bytes)?;
+        dozer_types::serde_json::from_slice::<Manifest>(&bytes).map_err(|e| {
+            StorageError::DeserializationError {
+                typ: "snapshot manifest",
+                reason: Box::new(e),
+            }
+        })
+    })?;
+
+    for (index, database) in manifest.databases.iter().enumerate() {
+        let flags = DatabaseFlags::from_bits(database.flags).ok_or_else(|| {
+            invalid_snapshot(format!("invalid flags of database {}", database.name))
+        })?;
+        // SAFETY: The handle is only used in this transaction.
+        let db = unsafe { txn.create_db(Some(&database.name), flags)? };
+        read_entry(&mut reader, &database_path(index), |entry| {
+            export::import_database(txn, db, entry).map(|_| ())
+        })?;
+    }
+    Ok(())
+}
+
+fn database_path(index: usize) -> String {
+    format!("databases/{index}")
+}
+
+fn invalid_snapshot(reason: String) -> StorageError {
+    StorageError::InvalidExport(format!("invalid snapshot: {reason}"))
+}
+
+/// Writes a ustar header of a regular file.
+fn write_header(writer: &mut impl Write, path: &str, size: u64) -> Result<(), StorageError> {
+    let mut header = [0; BLOCK_SIZE];
+    header[..path.len()].copy_from_slice(path.as_bytes());
+    write_octal(&mut header[100..108], 0o644);
+    write_octal(&mut header[108..116], 0);
+    write_octal(&mut header[116..124], 0);
+    write_size(&mut header[124..136], size);
+    write_octal(&mut header[136..148], 0);
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+
+    // The checksum is computed with the checksum field filled with spaces.
+    header[148..156].fill(b' ');
+    let checksum = header.iter().map(|byte| *byte as u64).sum();
+    write_octal(&mut header[148..155], checksum);
+
+    writer.write_all(&header)?;
+    Ok(())
+}
+
+/// Writes `value` as zero padded octal digits followed by a NUL.
+fn write_octal(field: &mut [u8], value: u64) {
+    let digits = format!("{value:0width$o}", width = field.len() - 1);
+    field[..digits.len()].copy_from_slice(digits.as_bytes());
+    field[digits.len()] = 0;
+}
+
+/// Writes sizes that don't fit in octal in base-256, as GNU tar does.
+fn write_size(field: &mut [u8], size: u64) {
+    if size < 8u64.pow(field.len() as u32 - 1) {
+        write_octal(field, size);
+    } else {
+        field.fill(0);
+        let start = field.len() - 8;
+        field[start..].copy_from_slice(&size.to_be_bytes());
+        field[0] = 0x80;
+    }
+}
+
+fn write_padding(writer: &mut impl Write, len: u64) -> Result<(), StorageError> {
+    let padding = padding_len(len);
+    writer.write_all(&[0; BLOCK_SIZE][..padding])?;
+    Ok(())
+}
+
+fn padding_len(len: u64) -> usize {
+    (BLOCK_SIZE - (len % BLOCK_SIZE as u64) as usize) % BLOCK_SIZE
+}
+
+/// Reads the next entry, which must be at `path`, with `f`, and skips what `f` didn't read.
+fn read_entry<R: Read, T>(
+    reader: &mut R,
+    path: &str,
+    f: impl FnOnce(&mut io::Take<&mut R>) -> Result<T, StorageError>,
+) -> Result<T, StorageError> {
+    let mut header = [0; BLOCK_SIZE];
+    reader.read_exact(&mut header)?;
+    let name_len = header[..100]
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(100);
+    if &header[..name_len] != path.as_bytes() {
+        return Err(invalid_snapshot(format!("expected entry {path}")));
+    }
+    let size = read_size(&header[124..136])
+        .ok_or_else(|| invalid_snapshot(format!("invalid size of entry {path}")))?;
+
+    let mut entry = (&mut *reader).take(size);
+    let result = f(&mut entry)?;
+    io::copy(&mut entry, &mut io::sink())?;
+    io::copy(
+        &mut (&mut *reader).take(padding_len(size) as u64),
+        &mut io::sink(),
+    )?;
+    Ok(result)
+}
+
+fn read_size(field: &[u8]) -> Option<u64> {
+    if field[0] & 0x80 == 0 {
+        return read_octal(field);
+    }
+    let (high, low) = field.split_at(field.len() - 8);
+    if high[0] != 0x80 || high[1..].iter().any(|byte| *byte != 0) {
+        return None;
+    }
+    Some(u64::from_be_bytes(low.try_into().ok()?))
+}
+
+fn read_octal(field: &[u8]) -> Option<u64> {
+    let digits = std::str::from_utf8(field).ok()?;
+    let digits = digits.trim_matches(|c: char| c == '\0' || c == ' ');
+    u64::from_str_radix(digits, 8).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use crate::{
+        lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions},
+        LmdbMap, LmdbMultimap,
+    };
+
+    #[test]
+    fn test_export_import_tar() {
+        let temp_dir = TempDir::new("test_export_import_tar").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "from",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let map = LmdbMap::<u64, str>::new_from_env(&mut env, Some("map"), true).unwrap();
+        let multimap =
+            LmdbMultimap::<u64, u64>::new_from_env(&mut env, Some("multimap"), true).unwrap();
+        let mut txn = env.begin_rw_txn().unwrap();
+        map.extend(&mut txn, [(&1, "a"), (&2, "b")]).unwrap();
+        multimap.insert(&mut txn, &1, &1).unwrap();
+        multimap.insert(&mut txn, &1, &2).unwrap();
+        lmdb::Transaction::commit(txn).unwrap();
+
+        let mut archive = vec![];
+        env.export_tar(&mut archive).unwrap();
+        assert_eq!(archive.len() % super::BLOCK_SIZE, 0);
+
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "to",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        env.import_tar(archive.as_slice()).unwrap();
+        let map = LmdbMap::<u64, str>::new_from_env(&mut env, Some("map"), false).unwrap();
+        let multimap =
+            LmdbMultimap::<u64, u64>::new_from_env(&mut env, Some("multimap"), false).unwrap();
+        let txn = env.begin_ro_txn().unwrap();
+        assert_eq!(map.get(&txn, &1).unwrap().unwrap(), "a");
+        assert_eq!(map.get(&txn, &2).unwrap().unwrap(), "b");
+        assert_eq!(multimap.value_count(&txn, &1).unwrap(), 2);
+    }
+}
@@ -1,4 +1,6 @@
 #[cfg(test)]
+mod derive;
+#[cfg(test)]
 mod lmdb_sys;
 #[cfg(test)]
 mod prefix_transaction;
@@ -0,0 +1,62 @@
+use std::borrow::Cow;
+
+use dozer_types::serde::{Deserialize, Serialize};
+use tempdir::TempDir;
+
+use crate::{
+    lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions},
+    Decode, Encode, LmdbMap, LmdbVal,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, LmdbVal)]
+#[serde(crate = "dozer_types::serde")]
+struct Value {
+    id: u64,
+    name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, LmdbVal)]
+#[serde(crate = "dozer_types::serde")]
+#[lmdb_val(key)]
+struct Key<T> {
+    parts: Vec<T>,
+}
+
+#[test]
+fn test_derive_lmdb_val() {
+    let value = Value {
+        id: 1,
+        name: "name".to_string(),
+    };
+    let encoded = value.encode().unwrap();
+    assert_eq!(Value::decode(encoded.as_ref()).unwrap(), Cow::Owned(value));
+
+    let temp_dir = TempDir::new("test_derive_lmdb_val").unwrap();
+    let env =
+        LmdbEnvironmentManager::create(temp_dir.path(), "env", LmdbEnvironmentOptions::default())
+            .unwrap();
+    let txn = env.create_txn().unwrap();
+    let mut txn = txn.write();
+
+    let map = LmdbMap::<Key<u32>, Value>::new_from_txn(&mut txn, None, true).unwrap();
+    let key = Key { parts: vec![1, 2] };
+    let value = Value {
+        id: 2,
+        name: "other".to_string(),
+    };
+    assert!(map.insert(txn.txn_mut(), &key, &value).unwrap());
+    assert_eq!(
+        map.get(txn.txn(), &key).unwrap().unwrap().into_owned(),
+        value
+    );
+    assert!(map
+        .get(txn.txn(), &Key { parts: vec![2, 1] })
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_decode_error_names_type() {
+    let error = Value::decode(&[]).unwrap_err();
+    assert!(error.to_string().contains("Value"));
+}
@@ -0,0 +1,263 @@
+use std::{cmp::Ordering, collections::HashMap, ffi::c_void, ops::Bound};
+
+use lmdb::{Database, DatabaseFlags, Transaction};
+
+use crate::{
+    errors::StorageError, lmdb_database::RawIterator, lmdb_storage::database_flags, Decode,
+};
+
+type Validator = Box<dyn Fn(&[u8]) -> Result<(), StorageError> + Send + Sync>;
+
+struct Validators {
+    key: Validator,
+    value: Validator,
+}
+
+/// Registers the key and value types of databases, so `LmdbEnvironmentManager::verify` can check that entries decode.
+///
+/// Databases that are not registered are only checked for order.
+#[derive(Default)]
+pub struct VerifyOptions {
+    validators: HashMap<Option<String>, Validators>,
+}
+
+impl VerifyOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks that every key of database `name` decodes as `K` and every value as `V`.
+    pub fn register<K: Decode + ?Sized + 'static, V: Decode + ?Sized + 'static>(
+        mut self,
+        name: Option<&str>,
+    ) -> Self {
+        self.validators.insert(
+            name.map(ToString::to_string),
+            Validators {
+                key: Box::new(|bytes| K::decode(bytes).map(drop)),
+                value: Box::new(|bytes| V::decode(bytes).map(drop)),
+            },
+        );
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyIssue {
+    /// The entry at `index` is not greater than the previous entry, according to the database's comparators.
+    OutOfOrder {
+        index: usize,
+    },
+    InvalidKey {
+        index: usize,
+        reason: String,
+    },
+    InvalidValue {
+        index: usize,
+        reason: String,
+    },
+    /// Reading the entry at `index` failed, so the rest of the database was not checked.
+    ReadFailed {
+        index: usize,
+        reason: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseReport {
+    /// `None` for the unnamed database.
+    pub name: Option<String>,
+    pub entries: usize,
+    /// If the entries were decoded with registered types.
+    pub decoded: bool,
+    pub issues: Vec<VerifyIssue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyReport {
+    pub databases: Vec<DatabaseReport>,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.databases
+            .iter()
+            .all(|database| database.issues.is_empty())
+    }
+}
+
+/// Walks the unnamed database and the named databases `names`, in the order of their B-trees.
+pub(crate) fn verify_databases<T: Transaction>(
+    txn: &T,
+    names: Vec<String>,
+    options: &VerifyOptions,
+) -> Result<VerifyReport, StorageError> {
+    let mut databases = vec![];
+    for name in std::iter::once(None).chain(names.into_iter().map(Some)) {
+        // SAFETY: The handle is only used in this transaction.
+        let db = unsafe { txn.open_db(name.as_deref())? };
+        let validators = options.validators.get(&name);
+        databases.push(verify_database(txn, db, name, validators)?);
+    }
+    Ok(VerifyReport { databases })
+}
+
+fn verify_database<T: Transaction>(
+    txn: &T,
+    db: Database,
+    name: Option<String>,
+    validators: Option<&Validators>,
+) -> Result<DatabaseReport, StorageError> {
+    let dup_sort = database_flags(txn, db)?.contains(DatabaseFlags::DUP_SORT);
+    let mut issues = vec![];
+    let mut entries = 0;
+    let mut previous: Option<(&[u8], &[u8])> = None;
+
+    for (index, item) in
+        RawIterator::new(txn.open_ro_cursor(db)?, Bound::Unbounded, true)?.enumerate()
+    {
+        let (key, value) = match item {
+            Ok(item) => item,
+            Err(e) => {
+                issues.push(VerifyIssue::ReadFailed {
+                    index,
+                    reason: e.to_string(),
+                });
+                break;
+            }
+        };
+        entries += 1;
+
+        if let Some((previous_key, previous_value)) = previous {
+            let in_order = match compare(txn, db, previous_key, key, lmdb_sys::mdb_cmp) {
+                Ordering::Less => true,
+                Ordering::Equal => {
+                    dup_sort && compare(txn, db, previous_value, value, lmdb_sys::mdb_dcmp).is_lt()
+                }
+                Ordering::Greater => false,
+            };
+            if !in_order {
+                issues.push(VerifyIssue::OutOfOrder { index });
+            }
+        }
+        previous = Some((key, value));
+
+        if let Some(validators) = validators {
+            if let Err(e) = (validators.key)(key) {
+                issues.push(VerifyIssue::InvalidKey {
+                    index,
+                    reason: e.to_string(),
+                });
+            }
+            if let Err(e) = (validators.value)(value) {
+                issues.push(VerifyIssue::InvalidValue {
+                    index,
+                    reason: e.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(DatabaseReport {
+        name,
+        entries,
+        decoded: validators.is_some(),
+        issues,
+    })
+}
+
+pub(crate) type CompareFn = unsafe extern "C" fn(
+    *mut lmdb_sys::MDB_txn,
+    lmdb_sys::MDB_dbi,
+    *const lmdb_sys::MDB_val,
+    *const lmdb_sys::MDB_val,
+) -> std::ffi::c_int;
+
+pub(crate) fn compare<T: Transaction>(
+    txn: &T,
+    db: Database,
+    a: &[u8],
+    b: &[u8],
+    cmp: CompareFn,
+) -> Ordering {
+    let a = lmdb_sys::MDB_val {
+        mv_size: a.len(),
+        mv_data: a.as_ptr() as *mut c_void,
+    };
+    let b = lmdb_sys::MDB_val {
+        mv_size: b.len(),
+        mv_data: b.as_ptr() as *mut c_void,
+    };
+    // SAFETY: `txn` and `db` are valid.
+    let result = unsafe { cmp(txn.txn(), db.dbi(), &a, &b) };
+    result.cmp(&0)
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::types::Record;
+    use lmdb::WriteFlags;
+    use tempdir::TempDir;
+
+    use crate::{
+        lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions},
+        LmdbMap, LmdbMultimap,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_verify() {
+        let temp_dir = TempDir::new("test_verify").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let map = LmdbMap::<u32, str>::new_from_env(&mut env, Some("map"), true).unwrap();
+        let multimap =
+            LmdbMultimap::<u64, u64>::new_from_env(&mut env, Some("multimap"), true).unwrap();
+        let records = env
+            .create_database(Some("records"), Some(DatabaseFlags::empty()))
+            .unwrap();
+
+        let mut txn = env.begin_rw_txn().unwrap();
+        for i in 0..300u32 {
+            map.insert(&mut txn, &i, "value").unwrap();
+        }
+        for i in 0..10u64 {
+            multimap.insert(&mut txn, &1, &i).unwrap();
+        }
+        txn.put(records, b"key", b"not a record", WriteFlags::empty())
+            .unwrap();
+        txn.commit().unwrap();
+
+        let options = VerifyOptions::new()
+            .register::<u32, str>(Some("map"))
+            .register::<u64, u64>(Some("multimap"))
+            .register::<[u8], Record>(Some("records"));
+        let report = env.verify(&options).unwrap();
+        assert!(!report.is_ok());
+
+        let database = |name: &str| {
+            report
+                .databases
+                .iter()
+                .find(|database| database.name.as_deref() == Some(name))
+                .unwrap()
+        };
+        assert_eq!(report.databases[0].name, None);
+        assert_eq!(report.databases[0].entries, 3);
+        assert!(!report.databases[0].decoded);
+        assert!(report.databases[0].issues.is_empty());
+        assert_eq!(database("map").entries, 300);
+        assert!(database("map").issues.is_empty());
+        assert_eq!(database("multimap").entries, 10);
+        assert!(database("multimap").issues.is_empty());
+        assert!(matches!(
+            database("records").issues.as_slice(),
+            [VerifyIssue::InvalidValue { index: 0, .. }]
+        ));
+    }
+}
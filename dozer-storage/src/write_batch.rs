@@ -0,0 +1,182 @@
+use lmdb::{Database, RwTransaction, WriteFlags};
+
+use crate::{
+    errors::StorageError, lmdb_storage::SharedTransaction, LmdbDupValue, LmdbKey, LmdbMap,
+    LmdbMultimap, LmdbValue,
+};
+
+#[derive(Debug, Clone)]
+enum Operation {
+    Put {
+        db: Database,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        flags: WriteFlags,
+    },
+    Delete {
+        db: Database,
+        key: Vec<u8>,
+        value: Option<Vec<u8>>,
+    },
+}
+
+/// Buffers writes to multiple maps, to be applied in one transaction in the order they were added.
+///
+/// Keys and values are encoded when they're added, so encoding errors are reported early and applying can't fail because of them.
+#[derive(Debug, Clone, Default)]
+pub struct WriteBatch {
+    operations: Vec<Operation>,
+}
+
+impl WriteBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Sets `key` to `value` in `map`, overwriting the existing value.
+    pub fn put<K: LmdbKey + ?Sized, V: LmdbValue + ?Sized>(
+        &mut self,
+        map: &LmdbMap<K, V>,
+        key: &K,
+        value: &V,
+    ) -> Result<(), StorageError> {
+        self.operations.push(Operation::Put {
+            db: map.database(),
+            key: key.encode()?.as_ref().to_vec(),
+            value: value.encode()?.as_ref().to_vec(),
+            flags: WriteFlags::empty(),
+        });
+        Ok(())
+    }
+
+    /// Removes `key` from `map`. Removing a key that doesn't exist is not an error.
+    pub fn remove<K: LmdbKey + ?Sized, V: LmdbValue + ?Sized>(
+        &mut self,
+        map: &LmdbMap<K, V>,
+        key: &K,
+    ) -> Result<(), StorageError> {
+        self.operations.push(Operation::Delete {
+            db: map.database(),
+            key: key.encode()?.as_ref().to_vec(),
+            value: None,
+        });
+        Ok(())
+    }
+
+    /// Adds the key-value pair to `multimap`. Inserting an existing pair is not an error.
+    pub fn insert_dup<K: LmdbKey + ?Sized, V: LmdbDupValue + ?Sized>(
+        &mut self,
+        multimap: &LmdbMultimap<K, V>,
+        key: &K,
+        value: &V,
+    ) -> Result<(), StorageError> {
+        self.operations.push(Operation::Put {
+            db: multimap.database(),
+            key: key.encode()?.as_ref().to_vec(),
+            value: value.encode()?.as_ref().to_vec(),
+            flags: WriteFlags::NO_DUP_DATA,
+        });
+        Ok(())
+    }
+
+    /// Removes the key-value pair from `multimap`. Removing a pair that doesn't exist is not an error.
+    pub fn remove_dup<K: LmdbKey + ?Sized, V: LmdbDupValue + ?Sized>(
+        &mut self,
+        multimap: &LmdbMultimap<K, V>,
+        key: &K,
+        value: &V,
+    ) -> Result<(), StorageError> {
+        self.operations.push(Operation::Delete {
+            db: multimap.database(),
+            key: key.encode()?.as_ref().to_vec(),
+            value: Some(value.encode()?.as_ref().to_vec()),
+        });
+        Ok(())
+    }
+
+    /// Applies the buffered writes to `txn` in order and clears the batch.
+    ///
+    /// If this fails, some writes may have been applied, so `txn` should be aborted.
+    pub fn apply(&mut self, txn: &mut RwTransaction) -> Result<(), StorageError> {
+        for operation in self.operations.drain(..) {
+            match operation {
+                Operation::Put {
+                    db,
+                    key,
+                    value,
+                    flags,
+                } => match txn.put(db, &key, &value, flags) {
+                    Ok(()) | Err(lmdb::Error::KeyExist) => (),
+                    Err(e) => return Err(e.into()),
+                },
+                Operation::Delete { db, key, value } => match txn.del(db, &key, value.as_deref()) {
+                    Ok(()) | Err(lmdb::Error::NotFound) => (),
+                    Err(e) => return Err(e.into()),
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the buffered writes and commits, holding the lock of `txn` only once.
+    pub fn commit(mut self, txn: &SharedTransaction) -> Result<(), StorageError> {
+        let mut txn = txn.write();
+        self.apply(txn.txn_mut())?;
+        txn.commit_and_renew()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use crate::lmdb_storage::{LmdbEnvironmentManager, LmdbEnvironmentOptions};
+
+    use super::*;
+
+    #[test]
+    fn test_write_batch() {
+        let temp_dir = TempDir::new("test_write_batch").unwrap();
+        let mut env = LmdbEnvironmentManager::create(
+            temp_dir.path(),
+            "env",
+            LmdbEnvironmentOptions::default(),
+        )
+        .unwrap();
+        let map = LmdbMap::<u64, str>::new_from_env(&mut env, Some("map"), true).unwrap();
+        let multimap =
+            LmdbMultimap::<u64, u64>::new_from_env(&mut env, Some("multimap"), true).unwrap();
+        let txn = env.create_txn().unwrap();
+
+        let mut batch = WriteBatch::new();
+        batch.put(&map, &1, "a").unwrap();
+        batch.put(&map, &1, "b").unwrap();
+        batch.put(&map, &2, "c").unwrap();
+        batch.remove(&map, &2).unwrap();
+        batch.remove(&map, &3).unwrap();
+        batch.insert_dup(&multimap, &1, &1).unwrap();
+        batch.insert_dup(&multimap, &1, &1).unwrap();
+        batch.insert_dup(&multimap, &1, &2).unwrap();
+        batch.remove_dup(&multimap, &1, &2).unwrap();
+        assert_eq!(batch.len(), 9);
+
+        {
+            let txn = txn.read();
+            assert_eq!(map.count(txn.txn()).unwrap(), 0);
+        }
+        batch.commit(&txn).unwrap();
+
+        let txn = txn.read();
+        assert_eq!(map.get(txn.txn(), &1).unwrap().unwrap(), "b");
+        assert!(map.get(txn.txn(), &2).unwrap().is_none());
+        assert_eq!(multimap.value_count(txn.txn(), &1).unwrap(), 1);
+    }
+}
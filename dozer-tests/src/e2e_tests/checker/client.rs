@@ -1,5 +1,5 @@
 use dozer_api::{
-    openapiv3::{OpenAPI, ReferenceOr, SchemaKind, StringFormat, VariantOrUnknownOrEmpty},
+    openapiv3::{OpenAPI, SchemaKind, StringFormat, VariantOrUnknownOrEmpty},
     tonic::transport::{Channel, Endpoint},
 };
 use dozer_types::{
@@ -329,11 +329,14 @@ fn grpc_type_matches(grpc_type: i32, field_type: FieldType) -> bool {
         FieldType::Date => grpc_type == Type::Date as i32,
         FieldType::Bson => grpc_type == Type::Bson as i32,
         FieldType::Point => grpc_type == Type::Point as i32,
+        FieldType::Duration => grpc_type == Type::Duration as i32,
+        FieldType::Geometry => grpc_type == Type::Geometry as i32,
+        FieldType::Decimal128 => grpc_type == Type::Decimal128 as i32,
     }
 }
 
 fn oapi_type_matches(oapi_type: &dozer_api::openapiv3::Type, field_type: FieldType) -> bool {
-    use dozer_api::openapiv3::Type::{Array, Boolean, Integer, Number, String};
+    use dozer_api::openapiv3::Type::{Boolean, Integer, Number, String};
 
     match (oapi_type, field_type) {
         (Integer(_), FieldType::UInt | FieldType::Int) => true,
@@ -344,12 +347,14 @@ fn oapi_type_matches(oapi_type: &dozer_api::openapiv3::Type, field_type: FieldTy
             FieldType::String
             | FieldType::Text
             | FieldType::Decimal
+            | FieldType::Decimal128
             | FieldType::Timestamp
-            | FieldType::Date,
+            | FieldType::Date
+            | FieldType::Duration
+            | FieldType::Geometry,
         ) => {
             if field_type == FieldType::Timestamp {
                 string_type.format == VariantOrUnknownOrEmpty::Item(StringFormat::DateTime)
-                    && string_type.pattern == Some("yyyy-MM-dd'T'HH:mm:ss.SSS'Z'".to_string())
             } else if field_type == FieldType::Date {
                 string_type.format == VariantOrUnknownOrEmpty::Item(StringFormat::Date)
                     && string_type.pattern == Some(DATE_FORMAT.to_string())
@@ -357,11 +362,8 @@ fn oapi_type_matches(oapi_type: &dozer_api::openapiv3::Type, field_type: FieldTy
                 true
             }
         }
-        (Array(array_type), FieldType::Binary | FieldType::Bson) => {
-            let Some(ReferenceOr::Item(schema)) = array_type.items.as_ref() else {
-                return false;
-            };
-            matches!(schema.schema_kind, SchemaKind::Type(Integer(_)))
+        (String(string_type), FieldType::Binary | FieldType::Bson) => {
+            string_type.format == VariantOrUnknownOrEmpty::Item(StringFormat::Byte)
         }
         _ => false,
     }
@@ -150,7 +150,7 @@ pub fn map_sqlite_to_record(
                 Field::Decimal(Decimal::from_str(&val).expect("decimal parse error"))
             },
             FieldType::Date =>  convert_type!(Field::String, f, row, idx),
-            FieldType::Bson | FieldType::Point => {
+            FieldType::Bson | FieldType::Point | FieldType::Duration | FieldType::Geometry | FieldType::Decimal128 => {
                 panic!("type not supported : {:?}", f.typ.to_owned())
             }
         };
@@ -226,6 +226,9 @@ pub fn map_field_to_string(f: &Field) -> String {
         Field::Decimal(i) => i.to_string(),
         Field::Null => "null".to_string(),
         Field::Point(p) => format!("'{:?}'", p.0.x_y()),
+        Field::Duration(d) => format!("'{d}'"),
+        Field::Geometry(g) => format!("'{g}'"),
+        Field::Decimal128(d) => d.to_string(),
     }
 }
 
@@ -254,6 +257,7 @@ pub fn get_schema(columns: &[rusqlite::Column]) -> Schema {
                     },
                     nullable: true,
                     source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 }
             })
             .collect(),
@@ -180,7 +180,8 @@ fn test_null_inserts() {
                     name: "actor_id".to_string(),
                     typ: dozer_types::types::FieldType::Int,
                     nullable: false,
-                    source: SourceDefinition::Dynamic
+                    source: SourceDefinition::Dynamic,
+                    metadata: Default::default(),
                 }],
                 primary_index: vec![0],
             }
@@ -205,25 +206,29 @@ fn test_null_inserts() {
                         name: "actor_id".to_string(),
                         typ: dozer_types::types::FieldType::Int,
                         nullable: false,
-                        source: SourceDefinition::Dynamic
+                        source: SourceDefinition::Dynamic,
+                        metadata: Default::default(),
                     },
                     FieldDefinition {
                         name: "first_name".to_string(),
                         typ: dozer_types::types::FieldType::String,
                         nullable: false,
-                        source: SourceDefinition::Dynamic
+                        source: SourceDefinition::Dynamic,
+                        metadata: Default::default(),
                     },
                     FieldDefinition {
                         name: "last_name".to_string(),
                         typ: dozer_types::types::FieldType::String,
                         nullable: true,
-                        source: SourceDefinition::Dynamic
+                        source: SourceDefinition::Dynamic,
+                        metadata: Default::default(),
                     },
                     FieldDefinition {
                         name: "last_update".to_string(),
                         typ: dozer_types::types::FieldType::String,
                         nullable: true,
-                        source: SourceDefinition::Dynamic
+                        source: SourceDefinition::Dynamic,
+                        metadata: Default::default(),
                     }
                 ],
                 primary_index: vec![0],
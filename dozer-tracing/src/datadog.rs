@@ -0,0 +1,92 @@
+//! Exports spans to the Datadog agent, tagged with the `env` and `version` of Datadog's unified service tagging.
+
+use dozer_types::models::telemetry::{BatchExportConfig, DatadogConfig};
+use opentelemetry::sdk::export::trace::SpanData;
+use opentelemetry::sdk::trace::{self, Span, SpanProcessor, TraceRuntime};
+use opentelemetry::trace::{Span as _, TraceError, TraceResult};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_datadog::ApiVersion;
+
+use crate::health::MonitoredExporter;
+use crate::redaction::Redactor;
+use crate::{batch_processor, with_processor};
+
+/// Exports spans in batches on `runtime`, so ending a span never waits on the agent.
+pub fn with_datadog_batch<R: TraceRuntime>(
+    provider: trace::Builder,
+    service_name: &str,
+    datadog: &DatadogConfig,
+    batch_export: &BatchExportConfig,
+    redactor: &Redactor,
+    trace_config: &dyn Fn() -> trace::Config,
+    runtime: R,
+) -> Result<trace::Builder, TraceError> {
+    let mut pipeline = opentelemetry_datadog::new_pipeline()
+        .with_service_name(service_name)
+        .with_version(ApiVersion::Version05)
+        .with_trace_config(trace_config());
+    if let Some(agent_endpoint) = &datadog.agent_endpoint {
+        pipeline = pipeline.with_agent_endpoint(agent_endpoint);
+    }
+    let exporter = MonitoredExporter::new("datadog", pipeline.build_exporter()?);
+
+    let processor = UnifiedServiceTags {
+        inner: batch_processor(exporter, batch_export, runtime),
+        tags: unified_service_tags(datadog),
+    };
+    Ok(with_processor(provider, processor, redactor))
+}
+
+/// The exporter sends span attributes, not resource attributes, to the agent, so the tags are set on every span.
+fn unified_service_tags(datadog: &DatadogConfig) -> Vec<KeyValue> {
+    [("env", &datadog.env), ("version", &datadog.version)]
+        .into_iter()
+        .filter_map(|(key, value)| value.clone().map(|value| KeyValue::new(key, value)))
+        .collect()
+}
+
+#[derive(Debug)]
+struct UnifiedServiceTags<P> {
+    inner: P,
+    tags: Vec<KeyValue>,
+}
+
+impl<P: SpanProcessor> SpanProcessor for UnifiedServiceTags<P> {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        for tag in &self.tags {
+            span.set_attribute(tag.clone());
+        }
+        self.inner.on_start(span, cx);
+    }
+
+    fn on_end(&self, span: SpanData) {
+        self.inner.on_end(span);
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        self.inner.force_flush()
+    }
+
+    fn shutdown(&mut self) -> TraceResult<()> {
+        self.inner.shutdown()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_service_tags() {
+        let datadog = DatadogConfig {
+            agent_endpoint: None,
+            env: Some("production".to_string()),
+            version: None,
+        };
+        assert_eq!(
+            unified_service_tags(&datadog),
+            vec![KeyValue::new("env", "production")]
+        );
+        assert!(unified_service_tags(&DatadogConfig::default()).is_empty());
+    }
+}
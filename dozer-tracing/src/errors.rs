@@ -0,0 +1,14 @@
+use dozer_types::thiserror;
+use dozer_types::thiserror::Error;
+use tracing_subscriber::filter::ParseError;
+use tracing_subscriber::reload;
+
+#[derive(Error, Debug)]
+pub enum FilterError {
+    #[error("Telemetry is not initialized")]
+    NotInitialized,
+    #[error("Invalid filter: {0}")]
+    Parse(#[from] ParseError),
+    #[error("Failed to reload filter: {0}")]
+    Reload(#[from] reload::Error),
+}
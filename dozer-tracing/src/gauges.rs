@@ -0,0 +1,97 @@
+//! Gauges of LMDB environments, reported to the global meter provider.
+
+use std::fmt::Display;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use dozer_types::log::warn;
+use opentelemetry::{global, metrics, KeyValue};
+use tokio::task::JoinHandle;
+
+/// The values of the gauges of one LMDB environment, read by the caller from the environment's statistics.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LmdbGaugeValues {
+    /// Fraction of the map in use, between 0 and 1.
+    pub map_used_ratio: f64,
+    /// Number of reader slots in use.
+    pub readers: u32,
+    pub max_readers: u32,
+    /// Number of entries of every named database, by name.
+    pub database_entries: Vec<(String, usize)>,
+}
+
+/// Reads the gauge values of the environment `name` with `read_stats` every `interval` and reports them as gauges, so
+/// capacity alerts can fire before writes fail with `MDB_MAP_FULL`.
+///
+/// | Gauge | Attributes |
+/// |---|---|
+/// | `lmdb.map_used_ratio` | `environment` |
+/// | `lmdb.readers` | `environment` |
+/// | `lmdb.max_readers` | `environment` |
+/// | `lmdb.database_entries` | `environment`, `database` |
+///
+/// Must be called on a Tokio runtime. The gauges stop reporting when the returned task is aborted.
+pub fn spawn_lmdb_gauges<F, E>(name: String, interval: Duration, read_stats: F) -> JoinHandle<()>
+where
+    F: Fn() -> Result<LmdbGaugeValues, E> + Send + 'static,
+    E: Display,
+{
+    let latest = Arc::new(Mutex::new(None));
+    if let Err(e) = register_gauges(name.clone(), Arc::downgrade(&latest)) {
+        warn!("Failed to register gauges of LMDB environment {name}: {e}");
+    }
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            match read_stats() {
+                Ok(values) => *latest.lock().unwrap() = Some(values),
+                Err(e) => warn!("Failed to read statistics of LMDB environment {name}: {e}"),
+            }
+        }
+    })
+}
+
+fn register_gauges(
+    name: String,
+    latest: Weak<Mutex<Option<LmdbGaugeValues>>>,
+) -> metrics::Result<()> {
+    let meter = global::meter("dozer");
+    let map_used_ratio = meter
+        .f64_observable_gauge("lmdb.map_used_ratio")
+        .with_description("Fraction of the LMDB map in use, writes fail at 1")
+        .init();
+    let readers = meter
+        .u64_observable_gauge("lmdb.readers")
+        .with_description("LMDB reader slots in use")
+        .init();
+    let max_readers = meter
+        .u64_observable_gauge("lmdb.max_readers")
+        .with_description("LMDB reader slots")
+        .init();
+    let database_entries = meter
+        .u64_observable_gauge("lmdb.database_entries")
+        .with_description("Entries of an LMDB database")
+        .init();
+
+    meter.register_callback(move |cx| {
+        let Some(latest) = latest.upgrade() else {
+            return;
+        };
+        let Some(values) = latest.lock().unwrap().clone() else {
+            return;
+        };
+        let environment = KeyValue::new("environment", name.clone());
+        let attributes = [environment.clone()];
+        map_used_ratio.observe(cx, values.map_used_ratio, &attributes);
+        readers.observe(cx, values.readers as u64, &attributes);
+        max_readers.observe(cx, values.max_readers as u64, &attributes);
+        for (database, entries) in values.database_entries {
+            database_entries.observe(
+                cx,
+                entries as u64,
+                &[environment.clone(), KeyValue::new("database", database)],
+            );
+        }
+    })
+}
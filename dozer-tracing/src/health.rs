@@ -0,0 +1,171 @@
+//! Health of the telemetry pipeline, so an exporter that silently fails shows up in the health endpoint.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+
+use dozer_types::serde::Serialize;
+use opentelemetry::global;
+use opentelemetry::sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct TelemetryHealth {
+    /// The exporters of traces, in the order of the config.
+    pub exporters: Vec<ExporterHealth>,
+    /// Errors that the trace SDK reported, such as spans dropped because the export queue was full.
+    pub trace_errors: u64,
+    /// Errors that the metrics SDK reported.
+    pub metric_errors: u64,
+}
+
+impl TelemetryHealth {
+    pub fn is_healthy(&self) -> bool {
+        self.exporters
+            .iter()
+            .all(|exporter| !matches!(exporter.status, ExporterStatus::Failing(_)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct ExporterHealth {
+    pub name: &'static str,
+    pub status: ExporterStatus,
+    pub exported_spans: u64,
+    /// Spans of exports that failed.
+    pub dropped_spans: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub enum ExporterStatus {
+    /// Nothing was exported yet.
+    Unknown,
+    /// The last export succeeded.
+    Connected,
+    /// The last export failed with this error.
+    Failing(String),
+}
+
+static TELEMETRY_HEALTH: Mutex<TelemetryHealth> = Mutex::new(TelemetryHealth {
+    exporters: Vec::new(),
+    trace_errors: 0,
+    metric_errors: 0,
+});
+
+/// The health of the telemetry pipeline since `init_telemetry`.
+pub fn telemetry_health() -> TelemetryHealth {
+    TELEMETRY_HEALTH.lock().unwrap().clone()
+}
+
+/// Counts the errors of the OpenTelemetry SDK, and prints them like the default error handler.
+pub(crate) fn handle_error(error: global::Error) {
+    {
+        let mut health = TELEMETRY_HEALTH.lock().unwrap();
+        match &error {
+            global::Error::Trace(_) => health.trace_errors += 1,
+            global::Error::Metric(_) => health.metric_errors += 1,
+            _ => {}
+        }
+    }
+    eprintln!("OpenTelemetry error occurred. {error}");
+}
+
+fn record_export(index: usize, spans: u64, result: &ExportResult) {
+    let mut health = TELEMETRY_HEALTH.lock().unwrap();
+    let health = &mut health.exporters[index];
+    match result {
+        Ok(()) => {
+            health.status = ExporterStatus::Connected;
+            health.exported_spans += spans;
+        }
+        Err(e) => {
+            health.status = ExporterStatus::Failing(e.to_string());
+            health.dropped_spans += spans;
+        }
+    }
+}
+
+/// Records the results of the exports of `inner` in `telemetry_health`.
+#[derive(Debug)]
+pub(crate) struct MonitoredExporter<E> {
+    inner: E,
+    index: usize,
+}
+
+impl<E> MonitoredExporter<E> {
+    pub(crate) fn new(name: &'static str, inner: E) -> Self {
+        let mut health = TELEMETRY_HEALTH.lock().unwrap();
+        health.exporters.push(ExporterHealth {
+            name,
+            status: ExporterStatus::Unknown,
+            exported_spans: 0,
+            dropped_spans: 0,
+        });
+        Self {
+            inner,
+            index: health.exporters.len() - 1,
+        }
+    }
+}
+
+impl<E: SpanExporter> SpanExporter for MonitoredExporter<E> {
+    fn export(
+        &mut self,
+        batch: Vec<SpanData>,
+    ) -> Pin<Box<dyn Future<Output = ExportResult> + Send + 'static>> {
+        let spans = batch.len() as u64;
+        let index = self.index;
+        let export = self.inner.export(batch);
+        Box::pin(async move {
+            let result = export.await;
+            record_export(index, spans, &result);
+            result
+        })
+    }
+
+    fn shutdown(&mut self) {
+        self.inner.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::TraceError;
+
+    #[test]
+    fn test_telemetry_health() {
+        assert!(telemetry_health().is_healthy());
+        let jaeger = MonitoredExporter::new("jaeger", ()).index;
+        let stdout = MonitoredExporter::new("stdout", ()).index;
+        let exporter = |index: usize| telemetry_health().exporters[index].clone();
+        assert_eq!(exporter(jaeger).status, ExporterStatus::Unknown);
+
+        record_export(jaeger, 3, &Ok(()));
+        record_export(stdout, 1, &Ok(()));
+        assert_eq!(exporter(jaeger).status, ExporterStatus::Connected);
+        assert_eq!(exporter(jaeger).exported_spans, 3);
+
+        record_export(jaeger, 2, &Err(TraceError::from("agent unreachable")));
+        handle_error(global::Error::Trace(TraceError::from("queue full")));
+        let health = telemetry_health();
+        assert!(!health.is_healthy());
+        assert_eq!(
+            health.exporters[jaeger],
+            ExporterHealth {
+                name: "jaeger",
+                status: ExporterStatus::Failing("agent unreachable".to_string()),
+                exported_spans: 3,
+                dropped_spans: 2,
+            }
+        );
+        assert_eq!(health.exporters[stdout].status, ExporterStatus::Connected);
+        assert_eq!(health.trace_errors, 1);
+        assert_eq!(health.metric_errors, 0);
+
+        record_export(jaeger, 1, &Ok(()));
+        assert!(telemetry_health().is_healthy());
+    }
+}
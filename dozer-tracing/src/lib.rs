@@ -1,34 +1,418 @@
-use opentelemetry::{global, sdk::propagation::TraceContextPropagator};
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[cfg(not(feature = "tokio-console"))]
+use dozer_types::log::warn;
+#[cfg(feature = "tokio-console")]
+use dozer_types::models::telemetry::TokioConsoleConfig;
+use dozer_types::models::telemetry::{
+    BatchExportConfig, BatchExportRuntime, JaegerConfig, LogFormat, TelemetryConfig,
+    TelemetryExporter,
+};
+use dozer_types::tracing::Subscriber;
+use errors::FilterError;
+use health::MonitoredExporter;
+use opentelemetry::sdk::export::trace::{stdout, SpanExporter};
+use opentelemetry::sdk::trace::{
+    self, BatchSpanProcessor, SimpleSpanProcessor, SpanProcessor, TraceRuntime, TracerProvider,
+};
+use opentelemetry::sdk::Resource;
+use opentelemetry::trace::{TraceError, TracerProvider as _};
+use opentelemetry::KeyValue;
+use opentelemetry::{global, runtime, sdk::propagation::TraceContextPropagator};
+use opentelemetry_jaeger::JaegerTraceRuntime;
+use redaction::{RedactingFields, RedactingMakeWriter, RedactingSpanProcessor, Redactor};
+use rolling_file::RollingFileWriter;
+use sentry::ClientInitGuard;
+use slow_query::{SlowQueryLayer, SlowQuerySpans};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::filter::ParseError;
+use tracing_subscriber::fmt::MakeWriter;
 use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::util::SubscriberInitExt;
-use tracing_subscriber::{fmt, EnvFilter};
+use tracing_subscriber::{fmt, reload, EnvFilter, Layer, Registry};
+
+mod datadog;
+pub mod errors;
+pub mod gauges;
+pub mod health;
+pub mod metrics;
+pub mod propagation;
+pub mod redaction;
+mod rolling_file;
+mod sampler;
+mod sentry_capture;
+mod slow_query;
+
+/// Changes the log filter of a target, see `set_filter`.
+type FilterHandle = reload::Handle<EnvFilter, Registry>;
 
-pub fn init_telemetry(enable_opel: bool) -> Result<(), Box<dyn ::std::error::Error>> {
-    let app_name = "dozer";
+/// The filters of the targets that follow `log_filter`, `None` before telemetry is initialized.
+static FILTER_HANDLES: Mutex<Option<Vec<FilterHandle>>> = Mutex::new(None);
 
+/// Flushes the logs waiting to be written to the log file when dropped.
+static FILE_LOG_GUARD: Mutex<Option<WorkerGuard>> = Mutex::new(None);
+
+/// Flushes the events waiting to be sent to Sentry when dropped.
+static SENTRY_GUARD: Mutex<Option<ClientInitGuard>> = Mutex::new(None);
+
+pub fn init_telemetry(config: TelemetryConfig) -> Result<(), Box<dyn ::std::error::Error>> {
     global::set_text_map_propagator(TraceContextPropagator::new());
-    let tracer = opentelemetry_jaeger::new_agent_pipeline()
-        .with_service_name(app_name)
-        // .install_batch(opentelemetry::runtime::TokioCurrentThread)
-        .install_simple()
-        .expect("Failed to install OpenTelemetry tracer.");
+    global::set_error_handler(health::handle_error)?;
+
+    let resource = Resource::new(
+        std::iter::once(KeyValue::new("service.name", config.service_name.clone())).chain(
+            config
+                .resource_attributes
+                .iter()
+                .map(|(key, value)| KeyValue::new(key.clone(), value.clone())),
+        ),
+    );
+    let redactor = Redactor::new(&config.redacted_fields);
+    let trace_config = || {
+        trace::config()
+            .with_sampler(sampler::sampler(&config))
+            .with_resource(resource.clone())
+    };
+
+    let exporters = config
+        .exporter
+        .iter()
+        .chain(
+            config
+                .exporters
+                .iter()
+                .filter_map(|exporter| exporter.exporter.as_ref()),
+        )
+        .collect::<Vec<_>>();
+    // All exporters share a provider, so they export the same spans.
+    let tracer = if exporters.is_empty() {
+        None
+    } else {
+        let batch_export = config.batch_export.clone().unwrap_or_default();
+        let mut provider = TracerProvider::builder().with_config(trace_config());
+        for exporter in exporters {
+            provider = with_exporter(
+                provider,
+                exporter,
+                &config.service_name,
+                &batch_export,
+                &redactor,
+                &trace_config,
+            )?;
+        }
+        let provider = provider.build();
+        let tracer = provider.tracer("dozer");
+        let _ = global::set_tracer_provider(provider);
+        Some(tracer)
+    };
+
+    // Every target has a filter of its own, the ones of the targets without directives in the config follow
+    // `log_filter` and `set_filter`.
+    let mut filter_handles = vec![];
+    let mut filter = |directives: Option<&String>| {
+        let (filter, handle) =
+            reload::Layer::new(env_filter(directives.unwrap_or(&config.log_filter))?);
+        if directives.is_none() {
+            filter_handles.push(handle);
+        }
+        Ok::<_, ParseError>(filter)
+    };
 
-    let fmt_layer = fmt::layer().with_target(false);
-    let filter_layer = EnvFilter::try_from_default_env()
-        .or_else(|_| EnvFilter::try_new("info"))
-        .unwrap();
+    let stdout_layer = fmt_layer(config.log_format.as_ref(), &redactor, std::io::stdout, true)
+        .with_filter(filter(config.stdout_log_filter.as_ref())?);
+    let file_layer = match &config.file {
+        Some(file) => {
+            let (writer, guard) = tracing_appender::non_blocking(RollingFileWriter::new(file)?);
+            *FILE_LOG_GUARD.lock().unwrap() = Some(guard);
+            Some(
+                fmt_layer(config.log_format.as_ref(), &redactor, writer, false)
+                    .with_filter(filter(file.log_filter.as_ref())?),
+            )
+        }
+        None => None,
+    };
 
     // Enable Open Telemetry
-    let telemetry = match enable_opel {
-        true => Some(tracing_opentelemetry::layer().with_tracer(tracer)),
-        false => None,
+    let telemetry = match tracer {
+        Some(tracer) => Some(
+            tracing_opentelemetry::layer()
+                .with_tracer(tracer)
+                .with_filter(filter(config.trace_filter.as_ref())?),
+        ),
+        None => None,
+    };
+
+    let sentry_layer = match &config.sentry {
+        Some(sentry) => {
+            *SENTRY_GUARD.lock().unwrap() = Some(sentry_capture::init_sentry(sentry, &redactor));
+            Some(sentry_capture::sentry_layer().with_filter(filter(None)?))
+        }
+        None => None,
     };
+    *FILTER_HANDLES.lock().unwrap() = Some(filter_handles);
+
+    let slow_query_layer = config.slow_query_threshold_ms.map(|threshold| {
+        SlowQueryLayer::new(Duration::from_millis(threshold)).with_filter(SlowQuerySpans)
+    });
+
+    // tokio-console needs the trace level spans of tokio, so it has no filter.
+    #[cfg(feature = "tokio-console")]
+    let console_layer = config
+        .tokio_console
+        .as_ref()
+        .map(console_layer)
+        .transpose()?;
+    #[cfg(not(feature = "tokio-console"))]
+    let console_layer: Option<tracing_subscriber::layer::Identity> = None;
 
     tracing_subscriber::registry()
-        .with(filter_layer)
-        .with(fmt_layer)
-        .with(telemetry)
+        .with(
+            stdout_layer
+                .and_then(file_layer)
+                .and_then(telemetry)
+                .and_then(sentry_layer)
+                .and_then(slow_query_layer),
+        )
+        .with(console_layer)
         .init();
 
+    #[cfg(not(feature = "tokio-console"))]
+    if config.tokio_console.is_some() {
+        warn!("`tokio_console` is ignored because dozer was built without the `tokio-console` feature");
+    }
+
+    Ok(())
+}
+
+/// Serves tokio-console at `server_addr`. The tasks of a runtime only show up in tokio-console if dozer was built with
+/// `RUSTFLAGS="--cfg tokio_unstable"`.
+#[cfg(feature = "tokio-console")]
+fn console_layer(
+    config: &TokioConsoleConfig,
+) -> Result<console_subscriber::ConsoleLayer, std::net::AddrParseError> {
+    let mut builder = console_subscriber::ConsoleLayer::builder().with_default_env();
+    if let Some(server_addr) = &config.server_addr {
+        builder = builder.server_addr(server_addr.parse::<std::net::SocketAddr>()?);
+    }
+    Ok(builder.spawn())
+}
+
+/// Replaces the log filter of the running process with `directives`, in the format of `RUST_LOG`, such as
+/// `set_filter("dozer_cache=debug")`. Targets with filters of their own in the config keep them.
+pub fn set_filter(directives: &str) -> Result<(), FilterError> {
+    EnvFilter::try_new(directives)?;
+    let handles = FILTER_HANDLES.lock().unwrap();
+    for handle in handles.as_ref().ok_or(FilterError::NotInitialized)? {
+        // `EnvFilter` is not `Clone`, and `directives` is known to parse.
+        handle.reload(EnvFilter::new(directives))?;
+    }
     Ok(())
 }
+
+/// The directives of the current log filter, `None` before telemetry is initialized or if every target has a filter
+/// of its own.
+pub fn current_filter() -> Option<String> {
+    FILTER_HANDLES
+        .lock()
+        .unwrap()
+        .as_ref()?
+        .first()?
+        .with_current(|filter| filter.to_string())
+        .ok()
+}
+
+/// Flushes the spans waiting for export, the logs waiting to be written and the events waiting to be sent to Sentry,
+/// and shuts the exporter down. Call before the process exits.
+pub fn shutdown_telemetry() {
+    global::shutdown_tracer_provider();
+    FILE_LOG_GUARD.lock().unwrap().take();
+    SENTRY_GUARD.lock().unwrap().take();
+}
+
+/// `log_filter` with the directives of `RUST_LOG` after it, so they win for the same targets. An invalid `RUST_LOG` is
+/// ignored.
+fn env_filter(log_filter: &str) -> Result<EnvFilter, ParseError> {
+    match std::env::var(EnvFilter::DEFAULT_ENV) {
+        Ok(env) if !env.is_empty() => EnvFilter::try_new(format!("{log_filter},{env}"))
+            .or_else(|_| EnvFilter::try_new(log_filter)),
+        _ => EnvFilter::try_new(log_filter),
+    }
+}
+
+/// Redacts the fields of `redactor` in text logs when they are formatted, and in JSON logs when they are written,
+/// because the JSON format doesn't format the fields of events with `fmt_fields`.
+fn fmt_layer<S, W>(
+    log_format: Option<&LogFormat>,
+    redactor: &Redactor,
+    writer: W,
+    ansi: bool,
+) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let layer = fmt::layer().with_ansi(ansi);
+    match log_format {
+        Some(LogFormat::Compact(_)) => layer
+            .with_writer(writer)
+            .fmt_fields(RedactingFields::new(redactor.clone()))
+            .with_target(false)
+            .compact()
+            .boxed(),
+        Some(LogFormat::Json(_)) => layer
+            .with_writer(RedactingMakeWriter::new(writer, redactor.clone()))
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(true)
+            .boxed(),
+        Some(LogFormat::Full(_)) | None => layer
+            .with_writer(writer)
+            .fmt_fields(RedactingFields::new(redactor.clone()))
+            .with_target(false)
+            .boxed(),
+    }
+}
+
+/// Adds a span processor that exports to `exporter` to `provider`.
+fn with_exporter(
+    provider: trace::Builder,
+    exporter: &TelemetryExporter,
+    service_name: &str,
+    batch_export: &BatchExportConfig,
+    redactor: &Redactor,
+    trace_config: &dyn Fn() -> trace::Config,
+) -> Result<trace::Builder, TraceError> {
+    match exporter {
+        TelemetryExporter::Jaeger(jaeger) => match batch_export.runtime {
+            Some(BatchExportRuntime::Tokio(_)) => with_jaeger_batch(
+                provider,
+                service_name,
+                jaeger,
+                batch_export,
+                redactor,
+                trace_config,
+                runtime::Tokio,
+            ),
+            Some(BatchExportRuntime::TokioCurrentThread(_)) | None => with_jaeger_batch(
+                provider,
+                service_name,
+                jaeger,
+                batch_export,
+                redactor,
+                trace_config,
+                runtime::TokioCurrentThread,
+            ),
+        },
+        TelemetryExporter::Datadog(datadog) => match batch_export.runtime {
+            Some(BatchExportRuntime::Tokio(_)) => datadog::with_datadog_batch(
+                provider,
+                service_name,
+                datadog,
+                batch_export,
+                redactor,
+                trace_config,
+                runtime::Tokio,
+            ),
+            Some(BatchExportRuntime::TokioCurrentThread(_)) | None => datadog::with_datadog_batch(
+                provider,
+                service_name,
+                datadog,
+                batch_export,
+                redactor,
+                trace_config,
+                runtime::TokioCurrentThread,
+            ),
+        },
+        TelemetryExporter::Stdout(_) => {
+            let exporter =
+                MonitoredExporter::new("stdout", stdout::Exporter::new(std::io::stdout(), false));
+            Ok(with_processor(
+                provider,
+                SimpleSpanProcessor::new(Box::new(exporter)),
+                redactor,
+            ))
+        }
+    }
+}
+
+/// Exports spans in batches on `runtime`, so ending a span never waits on the agent.
+fn with_jaeger_batch<R: JaegerTraceRuntime>(
+    provider: trace::Builder,
+    service_name: &str,
+    jaeger: &JaegerConfig,
+    batch_export: &BatchExportConfig,
+    redactor: &Redactor,
+    trace_config: &dyn Fn() -> trace::Config,
+    runtime: R,
+) -> Result<trace::Builder, TraceError> {
+    let mut pipeline = opentelemetry_jaeger::new_agent_pipeline()
+        .with_service_name(service_name)
+        .with_trace_config(trace_config());
+    if let Some(endpoint) = &jaeger.endpoint {
+        pipeline = pipeline.with_endpoint(endpoint);
+    }
+    let exporter = MonitoredExporter::new(
+        "jaeger",
+        pipeline.build_async_agent_exporter(runtime.clone())?,
+    );
+    let processor = batch_processor(exporter, batch_export, runtime);
+    Ok(with_processor(provider, processor, redactor))
+}
+
+fn batch_processor<E: SpanExporter + 'static, R: TraceRuntime>(
+    exporter: E,
+    batch_export: &BatchExportConfig,
+    runtime: R,
+) -> BatchSpanProcessor<R> {
+    BatchSpanProcessor::builder(exporter, runtime)
+        .with_max_queue_size(batch_export.max_queue_size as usize)
+        .with_scheduled_delay(Duration::from_millis(batch_export.scheduled_delay_ms))
+        .with_max_export_batch_size(batch_export.max_export_batch_size as usize)
+        .build()
+}
+
+/// Adds `processor` to `provider`, with the fields of `redactor` redacted before `processor` exports spans.
+fn with_processor<P: SpanProcessor + 'static>(
+    provider: trace::Builder,
+    processor: P,
+    redactor: &Redactor,
+) -> trace::Builder {
+    provider.with_span_processor(RedactingSpanProcessor::new(processor, redactor.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_filter_before_init() {
+        assert!(matches!(
+            set_filter("dozer_cache=debug"),
+            Err(FilterError::NotInitialized)
+        ));
+        assert!(matches!(
+            set_filter("dozer_cache=loud"),
+            Err(FilterError::Parse(_))
+        ));
+        assert_eq!(current_filter(), None);
+    }
+
+    #[test]
+    fn test_env_filter() {
+        std::env::set_var(EnvFilter::DEFAULT_ENV, "dozer_cache=trace");
+        let filter = env_filter("info,dozer_storage=warn").unwrap().to_string();
+        assert!(filter.contains("dozer_storage=warn"));
+        assert!(filter.contains("dozer_cache=trace"));
+
+        std::env::set_var(EnvFilter::DEFAULT_ENV, "dozer_cache=loud");
+        let filter = env_filter("info,dozer_storage=warn").unwrap().to_string();
+        assert!(filter.contains("dozer_storage=warn"));
+        assert!(!filter.contains("dozer_cache"));
+
+        std::env::remove_var(EnvFilter::DEFAULT_ENV);
+        assert!(env_filter("dozer_storage=loud").is_err());
+    }
+}
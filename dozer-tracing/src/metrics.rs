@@ -0,0 +1,110 @@
+//! Metrics of API requests, reported to the global meter provider.
+
+use std::sync::Mutex;
+use std::time::Instant;
+
+use opentelemetry::metrics::{Counter, Histogram};
+use opentelemetry::{global, Context, KeyValue};
+
+/// The operation of an API request, the `operation` attribute of its metrics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestOperation {
+    Get,
+    GetMany,
+    Count,
+    Query,
+}
+
+impl RequestOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RequestOperation::Get => "get",
+            RequestOperation::GetMany => "get_many",
+            RequestOperation::Count => "count",
+            RequestOperation::Query => "query",
+        }
+    }
+}
+
+#[derive(Clone)]
+struct RequestInstruments {
+    requests: Counter<u64>,
+    duration: Histogram<f64>,
+    errors: Counter<u64>,
+}
+
+/// Created on the first request, which comes after `init_telemetry` sets the meter provider.
+static REQUEST_INSTRUMENTS: Mutex<Option<RequestInstruments>> = Mutex::new(None);
+
+fn request_instruments() -> RequestInstruments {
+    REQUEST_INSTRUMENTS
+        .lock()
+        .unwrap()
+        .get_or_insert_with(|| {
+            let meter = global::meter("dozer");
+            RequestInstruments {
+                requests: meter
+                    .u64_counter("dozer.api.requests")
+                    .with_description("API requests")
+                    .init(),
+                duration: meter
+                    .f64_histogram("dozer.api.request.duration")
+                    .with_description("Duration of API requests, in seconds")
+                    .init(),
+                errors: meter
+                    .u64_counter("dozer.api.request.errors")
+                    .with_description("API requests that failed")
+                    .init(),
+            }
+        })
+        .clone()
+}
+
+/// Runs `request` and reports its metrics, so every API serves the same metrics for every endpoint.
+///
+/// | Metric | Kind | Attributes |
+/// |---|---|---|
+/// | `dozer.api.requests` | counter | `endpoint`, `operation` |
+/// | `dozer.api.request.duration` | histogram, in seconds | `endpoint`, `operation` |
+/// | `dozer.api.request.errors` | counter | `endpoint`, `operation` |
+pub fn record_request<T, E>(
+    endpoint: &str,
+    operation: RequestOperation,
+    request: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> {
+    let start = Instant::now();
+    let result = request();
+    let duration = start.elapsed().as_secs_f64();
+
+    let instruments = request_instruments();
+    let cx = Context::current();
+    let attributes = [
+        KeyValue::new("endpoint", endpoint.to_string()),
+        KeyValue::new("operation", operation.as_str()),
+    ];
+    instruments.requests.add(&cx, 1, &attributes);
+    instruments.duration.record(&cx, duration, &attributes);
+    if result.is_err() {
+        instruments.errors.add(&cx, 1, &attributes);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_request() {
+        assert_eq!(
+            record_request("films", RequestOperation::Count, || Ok::<_, ()>(3)),
+            Ok(3)
+        );
+        assert_eq!(
+            record_request("films", RequestOperation::Query, || Err::<usize, _>(
+                "failed"
+            )),
+            Err("failed")
+        );
+    }
+}
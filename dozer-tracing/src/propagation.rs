@@ -0,0 +1,176 @@
+//! Carries W3C trace context across tonic requests and HTTP requests, with the propagator that `init_telemetry` sets.
+
+use dozer_types::tracing::Span;
+use http::header::{HeaderName, HeaderValue};
+use http::HeaderMap;
+use opentelemetry::global;
+use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::Context;
+use tonic::metadata::{KeyRef, MetadataKey, MetadataMap, MetadataValue};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// The trace context of the caller of a tonic request.
+pub fn extract_tonic_context(metadata: &MetadataMap) -> Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(&MetadataExtractor(metadata)))
+}
+
+/// Adds the trace context `cx` to the metadata of a tonic request.
+pub fn inject_tonic_context(cx: &Context, metadata: &mut MetadataMap) {
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(cx, &mut MetadataInjector(metadata))
+    })
+}
+
+/// The trace context of the caller of an HTTP request.
+pub fn extract_http_context(headers: &HeaderMap) -> Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)))
+}
+
+/// Adds the trace context `cx` to the headers of an HTTP request.
+pub fn inject_http_context(cx: &Context, headers: &mut HeaderMap) {
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(cx, &mut HeaderInjector(headers))
+    })
+}
+
+/// Makes `span` a child of the caller's span in the metadata of a tonic request, so the two traces link.
+pub fn set_parent_from_tonic(span: &Span, metadata: &MetadataMap) {
+    span.set_parent(extract_tonic_context(metadata));
+}
+
+/// Makes `span` a child of the caller's span in the headers of an HTTP request, so the two traces link.
+pub fn set_parent_from_http(span: &Span, headers: &HeaderMap) {
+    span.set_parent(extract_http_context(headers));
+}
+
+/// Adds the trace context of the current span to the metadata of an outgoing tonic request.
+pub fn inject_current_tonic_context(metadata: &mut MetadataMap) {
+    inject_tonic_context(&Span::current().context(), metadata);
+}
+
+/// Adds the trace context of the current span to the headers of an outgoing HTTP request.
+pub fn inject_current_http_context(headers: &mut HeaderMap) {
+    inject_http_context(&Span::current().context(), headers);
+}
+
+struct MetadataExtractor<'a>(&'a MetadataMap);
+
+impl<'a> Extractor for MetadataExtractor<'a> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0
+            .keys()
+            .filter_map(|key| match key {
+                KeyRef::Ascii(key) => Some(key.as_str()),
+                KeyRef::Binary(_) => None,
+            })
+            .collect()
+    }
+}
+
+struct MetadataInjector<'a>(&'a mut MetadataMap);
+
+impl<'a> Injector for MetadataInjector<'a> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(key), Ok(value)) = (
+            MetadataKey::from_bytes(key.as_bytes()),
+            MetadataValue::try_from(value),
+        ) {
+            self.0.insert(key, value);
+        }
+    }
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl<'a> Extractor for HeaderExtractor<'a> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(HeaderName::as_str).collect()
+    }
+}
+
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl<'a> Injector for HeaderInjector<'a> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(key), Ok(value)) = (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::try_from(value),
+        ) {
+            self.0.insert(key, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::sdk::propagation::TraceContextPropagator;
+    use opentelemetry::trace::{
+        SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+    };
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+    const SPAN_ID: &str = "00f067aa0ba902b7";
+
+    fn remote_context() -> Context {
+        Context::new().with_remote_span_context(SpanContext::new(
+            TraceId::from_hex(TRACE_ID).unwrap(),
+            SpanId::from_hex(SPAN_ID).unwrap(),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        ))
+    }
+
+    fn assert_remote_context(cx: Context) {
+        let span = cx.span();
+        let span_context = span.span_context();
+        assert_eq!(
+            span_context.trace_id(),
+            TraceId::from_hex(TRACE_ID).unwrap()
+        );
+        assert_eq!(span_context.span_id(), SpanId::from_hex(SPAN_ID).unwrap());
+        assert!(span_context.is_sampled());
+        assert!(span_context.is_remote());
+    }
+
+    #[test]
+    fn test_tonic_context() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+
+        let mut metadata = MetadataMap::new();
+        inject_tonic_context(&remote_context(), &mut metadata);
+        assert_eq!(
+            metadata.get("traceparent").unwrap().to_str().unwrap(),
+            TRACEPARENT
+        );
+        assert_remote_context(extract_tonic_context(&metadata));
+        assert!(!extract_tonic_context(&MetadataMap::new())
+            .span()
+            .span_context()
+            .is_valid());
+    }
+
+    #[test]
+    fn test_http_context() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+
+        let mut headers = HeaderMap::new();
+        inject_http_context(&remote_context(), &mut headers);
+        assert_eq!(
+            headers.get("traceparent").unwrap().to_str().unwrap(),
+            TRACEPARENT
+        );
+        assert_remote_context(extract_http_context(&headers));
+    }
+}
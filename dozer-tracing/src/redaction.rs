@@ -0,0 +1,326 @@
+//! Masks the values of configured field names, such as `password`, in logs and exported spans.
+//!
+//! Text logs are redacted when their fields are formatted, JSON logs when their lines are written, and spans before
+//! they are exported.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use dozer_types::serde_json::{self, Value};
+use dozer_types::tracing::field::{Field, Visit};
+use opentelemetry::sdk::export::trace::SpanData;
+use opentelemetry::sdk::trace::{EvictedQueue, Span, SpanProcessor};
+use opentelemetry::trace::TraceResult;
+use opentelemetry::{Context, KeyValue};
+use tracing_subscriber::field::{MakeVisitor, RecordFields, VisitFmt, VisitOutput};
+use tracing_subscriber::fmt::format::{DefaultFields, DefaultVisitor, Writer};
+use tracing_subscriber::fmt::{FormatFields, MakeWriter};
+
+pub const REDACTED: &str = "[REDACTED]";
+
+/// The field names to redact, compared case-insensitively.
+#[derive(Debug, Clone, Default)]
+pub struct Redactor(Arc<HashSet<String>>);
+
+impl Redactor {
+    pub fn new(names: &[String]) -> Self {
+        Self(Arc::new(
+            names.iter().map(|name| name.to_ascii_lowercase()).collect(),
+        ))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn is_redacted(&self, name: &str) -> bool {
+        !self.is_empty() && self.0.contains(&name.to_ascii_lowercase())
+    }
+
+    fn redact_key_values(&self, key_values: &mut [KeyValue]) {
+        for key_value in key_values {
+            if self.is_redacted(key_value.key.as_str()) {
+                *key_value = KeyValue::new(key_value.key.clone(), REDACTED);
+            }
+        }
+    }
+
+    /// Redacts the members of every object in `value`.
+    fn redact_json(&self, value: &mut Value) {
+        match value {
+            Value::Object(object) => self.redact_members(object.iter_mut()),
+            Value::Array(array) => array.iter_mut().for_each(|value| self.redact_json(value)),
+            _ => {}
+        }
+    }
+
+    /// Redacts the values of redacted keys, and the members of every object in the other values.
+    pub(crate) fn redact_members<'a>(
+        &self,
+        members: impl Iterator<Item = (&'a String, &'a mut Value)>,
+    ) {
+        for (key, value) in members {
+            if self.is_redacted(key) {
+                *value = Value::String(REDACTED.to_string());
+            } else {
+                self.redact_json(value);
+            }
+        }
+    }
+}
+
+/// Formats fields like `DefaultFields`, with the values of redacted fields masked.
+#[derive(Debug)]
+pub struct RedactingFields {
+    redactor: Redactor,
+}
+
+impl RedactingFields {
+    pub fn new(redactor: Redactor) -> Self {
+        Self { redactor }
+    }
+}
+
+impl<'writer> FormatFields<'writer> for RedactingFields {
+    fn format_fields<R: RecordFields>(&self, writer: Writer<'writer>, fields: R) -> fmt::Result {
+        if self.redactor.is_empty() {
+            return DefaultFields::new().format_fields(writer, fields);
+        }
+        let mut visitor = RedactingVisitor {
+            inner: DefaultFields::new().make_visitor(writer),
+            redactor: &self.redactor,
+        };
+        fields.record(&mut visitor);
+        visitor.inner.finish()
+    }
+}
+
+struct RedactingVisitor<'a, 'writer> {
+    inner: DefaultVisitor<'writer>,
+    redactor: &'a Redactor,
+}
+
+impl<'a, 'writer> Visit for RedactingVisitor<'a, 'writer> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.record(field, |inner| inner.record_f64(field, value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.record(field, |inner| inner.record_i64(field, value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.record(field, |inner| inner.record_u64(field, value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.record(field, |inner| inner.record_bool(field, value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, |inner| inner.record_str(field, value));
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.record(field, |inner| inner.record_error(field, value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record(field, |inner| inner.record_debug(field, value));
+    }
+}
+
+impl<'a, 'writer> RedactingVisitor<'a, 'writer> {
+    fn record(&mut self, field: &Field, record: impl FnOnce(&mut DefaultVisitor<'writer>)) {
+        if self.redactor.is_redacted(field.name()) {
+            self.inner
+                .record_debug(field, &format_args!("{}", REDACTED));
+        } else {
+            record(&mut self.inner);
+        }
+    }
+}
+
+impl<'a, 'writer> VisitOutput<fmt::Result> for RedactingVisitor<'a, 'writer> {
+    fn finish(self) -> fmt::Result {
+        self.inner.finish()
+    }
+}
+
+impl<'a, 'writer> VisitFmt for RedactingVisitor<'a, 'writer> {
+    fn writer(&mut self) -> &mut dyn fmt::Write {
+        self.inner.writer()
+    }
+}
+
+/// Redacts JSON log lines before writing them. The fmt layer writes every event in one `write`.
+#[derive(Debug)]
+pub struct RedactingMakeWriter<M> {
+    inner: M,
+    redactor: Redactor,
+}
+
+impl<M> RedactingMakeWriter<M> {
+    pub fn new(inner: M, redactor: Redactor) -> Self {
+        Self { inner, redactor }
+    }
+}
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactingMakeWriter<M> {
+    type Writer = RedactingWriter<'a, M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter {
+            inner: self.inner.make_writer(),
+            redactor: &self.redactor,
+        }
+    }
+}
+
+pub struct RedactingWriter<'a, W> {
+    inner: W,
+    redactor: &'a Redactor,
+}
+
+impl<'a, W: Write> Write for RedactingWriter<'a, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.redactor.is_empty() {
+            return self.inner.write(buf);
+        }
+        // Lines that are not JSON are written as they are.
+        let Ok(mut value) = serde_json::from_slice::<Value>(buf) else {
+            return self.inner.write(buf);
+        };
+        self.redactor.redact_json(&mut value);
+        let mut line = serde_json::to_vec(&value)?;
+        line.push(b'\n');
+        self.inner.write_all(&line)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Redacts the attributes of spans and their events before `inner` exports them.
+#[derive(Debug)]
+pub struct RedactingSpanProcessor<P> {
+    inner: P,
+    redactor: Redactor,
+}
+
+impl<P> RedactingSpanProcessor<P> {
+    pub fn new(inner: P, redactor: Redactor) -> Self {
+        Self { inner, redactor }
+    }
+}
+
+impl<P: SpanProcessor> SpanProcessor for RedactingSpanProcessor<P> {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        self.inner.on_start(span, cx);
+    }
+
+    fn on_end(&self, mut span: SpanData) {
+        if !self.redactor.is_empty() {
+            let redacted = span
+                .attributes
+                .iter()
+                .filter(|(key, _)| self.redactor.is_redacted(key.as_str()))
+                .map(|(key, _)| key.clone())
+                .collect::<Vec<_>>();
+            for key in redacted {
+                span.attributes.insert(KeyValue::new(key, REDACTED));
+            }
+
+            let mut events = std::mem::replace(&mut span.events, EvictedQueue::new(u32::MAX))
+                .into_iter()
+                .map(|mut event| {
+                    self.redactor.redact_key_values(&mut event.attributes);
+                    event
+                })
+                .collect::<Vec<_>>();
+            span.events.append_vec(&mut events);
+        }
+        self.inner.on_end(span);
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        self.inner.force_flush()
+    }
+
+    fn shutdown(&mut self) -> TraceResult<()> {
+        self.inner.shutdown()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dozer_types::serde_json::json;
+
+    #[test]
+    fn test_redactor() {
+        let redactor = Redactor::new(&["password".to_string(), "SSN".to_string()]);
+        assert!(redactor.is_redacted("password"));
+        assert!(redactor.is_redacted("Password"));
+        assert!(redactor.is_redacted("ssn"));
+        assert!(!redactor.is_redacted("user"));
+        assert!(!Redactor::default().is_redacted("password"));
+    }
+
+    #[test]
+    fn test_redact_json() {
+        let redactor = Redactor::new(&["password".to_string()]);
+        let mut value = json!({
+            "level": "INFO",
+            "password": "hunter2",
+            "span": { "name": "connect", "password": "hunter2" },
+            "spans": [{ "name": "connect", "Password": 1 }],
+        });
+        redactor.redact_json(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "level": "INFO",
+                "password": REDACTED,
+                "span": { "name": "connect", "password": REDACTED },
+                "spans": [{ "name": "connect", "Password": REDACTED }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_redacting_writer() {
+        let redactor = Redactor::new(&["password".to_string()]);
+        let mut output = vec![];
+        let mut writer = RedactingWriter {
+            inner: &mut output,
+            redactor: &redactor,
+        };
+        writer
+            .write_all(b"{\"password\":\"hunter2\",\"user\":\"admin\"}\n")
+            .unwrap();
+        writer.write_all(b"not json\n").unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!("{{\"password\":\"{REDACTED}\",\"user\":\"admin\"}}\nnot json\n")
+        );
+    }
+
+    #[test]
+    fn test_redact_key_values() {
+        let redactor = Redactor::new(&["ssn".to_string()]);
+        let mut key_values = vec![KeyValue::new("ssn", "123"), KeyValue::new("user", "admin")];
+        redactor.redact_key_values(&mut key_values);
+        assert_eq!(
+            key_values,
+            vec![
+                KeyValue::new("ssn", REDACTED),
+                KeyValue::new("user", "admin")
+            ]
+        );
+    }
+}
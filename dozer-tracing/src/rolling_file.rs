@@ -0,0 +1,188 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use dozer_types::chrono::{DateTime, Duration, DurationRound, Utc};
+use dozer_types::models::telemetry::{FileLogConfig, LogRotationPeriod};
+
+const ROTATED_SUFFIX_FORMAT: &str = "%Y%m%dT%H%M%S%.6fZ";
+
+/// Writes logs to `file_name` in `directory`, renaming it with a timestamp suffix when a rotation period starts or
+/// it would grow over the size limit, and keeping only the `max_files` latest rotated files.
+pub struct RollingFileWriter {
+    directory: PathBuf,
+    file_name: String,
+    rotation_period: Option<Duration>,
+    max_file_size: Option<u64>,
+    max_files: usize,
+    file: File,
+    size: u64,
+    next_rotation: Option<DateTime<Utc>>,
+}
+
+impl RollingFileWriter {
+    pub fn new(config: &FileLogConfig) -> io::Result<Self> {
+        let directory = PathBuf::from(&config.directory);
+        fs::create_dir_all(&directory)?;
+        let rotation_period = config.rotation_period.as_ref().map(|period| match period {
+            LogRotationPeriod::Hourly(_) => Duration::hours(1),
+            LogRotationPeriod::Daily(_) => Duration::days(1),
+        });
+        let file = open(&directory.join(&config.file_name))?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            directory,
+            file_name: config.file_name.clone(),
+            rotation_period,
+            max_file_size: config
+                .max_file_size_mb
+                .map(|size| size.saturating_mul(1024 * 1024)),
+            max_files: config.max_files as usize,
+            file,
+            size,
+            next_rotation: next_rotation(Utc::now(), rotation_period),
+        })
+    }
+
+    fn should_rotate(&self, now: DateTime<Utc>, len: usize) -> bool {
+        if self.next_rotation.map_or(false, |next| now >= next) {
+            return true;
+        }
+        // A write larger than the limit still goes to a file of its own.
+        self.max_file_size
+            .map_or(false, |max| self.size > 0 && self.size + len as u64 > max)
+    }
+
+    fn rotate(&mut self, now: DateTime<Utc>) -> io::Result<()> {
+        self.file.flush()?;
+        let path = self.directory.join(&self.file_name);
+        fs::rename(&path, self.rotated_path(now))?;
+        self.file = open(&path)?;
+        self.size = 0;
+        self.next_rotation = next_rotation(now, self.rotation_period);
+        self.remove_old_files()
+    }
+
+    fn rotated_path(&self, now: DateTime<Utc>) -> PathBuf {
+        self.directory.join(format!(
+            "{}.{}",
+            self.file_name,
+            now.format(ROTATED_SUFFIX_FORMAT)
+        ))
+    }
+
+    /// Rotated files sort by age because of the format of their suffix.
+    fn remove_old_files(&self) -> io::Result<()> {
+        let prefix = format!("{}.", self.file_name);
+        let mut rotated = fs::read_dir(&self.directory)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+            .map(|entry| entry.path())
+            .collect::<Vec<_>>();
+        rotated.sort();
+        let excess = rotated.len().saturating_sub(self.max_files);
+        for path in &rotated[..excess] {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for RollingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let now = Utc::now();
+        if self.should_rotate(now, buf.len()) {
+            self.rotate(now)?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// The start of the period after the one `now` is in.
+fn next_rotation(now: DateTime<Utc>, period: Option<Duration>) -> Option<DateTime<Utc>> {
+    let period = period?;
+    let start = now.duration_trunc(period).unwrap_or(now);
+    Some(start + period)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dozer_types::models::telemetry::HourlyRotation;
+    use tempdir::TempDir;
+
+    fn file_names(directory: &TempDir) -> Vec<String> {
+        let mut names = fs::read_dir(directory.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_rotate_by_size() {
+        let directory = TempDir::new("test_rotate_by_size").unwrap();
+        let mut writer = RollingFileWriter::new(&FileLogConfig {
+            directory: directory.path().to_string_lossy().into_owned(),
+            max_file_size_mb: Some(1),
+            max_files: 2,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let line = vec![b'a'; 600 * 1024];
+        for _ in 0..5 {
+            writer.write_all(&line).unwrap();
+        }
+        writer.flush().unwrap();
+
+        let names = file_names(&directory);
+        assert_eq!(names.len(), 3);
+        assert_eq!(names[0], "dozer.log");
+        assert!(names[1..].iter().all(|name| name.starts_with("dozer.log.")));
+        let current = fs::metadata(directory.path().join("dozer.log")).unwrap();
+        assert_eq!(current.len(), line.len() as u64);
+    }
+
+    #[test]
+    fn test_rotate_by_time() {
+        let directory = TempDir::new("test_rotate_by_time").unwrap();
+        let mut writer = RollingFileWriter::new(&FileLogConfig {
+            directory: directory.path().to_string_lossy().into_owned(),
+            rotation_period: Some(LogRotationPeriod::Hourly(HourlyRotation {})),
+            ..Default::default()
+        })
+        .unwrap();
+        writer.write_all(b"first\n").unwrap();
+
+        let now = Utc::now();
+        assert!(writer.next_rotation.unwrap() > now);
+        assert!(writer.next_rotation.unwrap() <= now + Duration::hours(1));
+        assert!(writer.should_rotate(now + Duration::hours(1), 0));
+        writer.rotate(now + Duration::hours(1)).unwrap();
+        writer.write_all(b"second\n").unwrap();
+        writer.flush().unwrap();
+
+        let names = file_names(&directory);
+        assert_eq!(names.len(), 2);
+        assert_eq!(
+            fs::read_to_string(directory.path().join("dozer.log")).unwrap(),
+            "second\n"
+        );
+        assert_eq!(
+            fs::read_to_string(directory.path().join(&names[1])).unwrap(),
+            "first\n"
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use dozer_types::models::telemetry::{TelemetryConfig, TelemetrySampler};
+use opentelemetry::sdk::trace::{Sampler, ShouldSample};
+use opentelemetry::trace::{
+    Link, SamplingDecision, SamplingResult, SpanKind, TraceContextExt, TraceId, TraceState,
+};
+use opentelemetry::{Context, InstrumentationLibrary, Key, OrderMap, Value};
+
+/// The sampler of `config`.
+pub fn sampler(config: &TelemetryConfig) -> ConfiguredSampler {
+    let root = match &config.sampler {
+        Some(TelemetrySampler::AlwaysOn(_)) => Sampler::AlwaysOn,
+        Some(TelemetrySampler::AlwaysOff(_)) => Sampler::AlwaysOff,
+        Some(TelemetrySampler::Ratio(ratio)) => Sampler::TraceIdRatioBased(ratio.ratio),
+        Some(TelemetrySampler::RateLimited(rate_limited)) => {
+            return ConfiguredSampler::RateLimited(RateLimitedSampler::new(
+                rate_limited.traces_per_second,
+                config.parent_based,
+            ))
+        }
+        None => Sampler::TraceIdRatioBased(config.sampling_ratio),
+    };
+    if config.parent_based {
+        ConfiguredSampler::Sdk(Sampler::ParentBased(Box::new(root)))
+    } else {
+        ConfiguredSampler::Sdk(root)
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfiguredSampler {
+    Sdk(Sampler),
+    RateLimited(RateLimitedSampler),
+}
+
+impl ShouldSample for ConfiguredSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &OrderMap<Key, Value>,
+        links: &[Link],
+        instrumentation_library: &InstrumentationLibrary,
+    ) -> SamplingResult {
+        let sampler: &dyn ShouldSample = match self {
+            ConfiguredSampler::Sdk(sampler) => sampler,
+            ConfiguredSampler::RateLimited(sampler) => sampler,
+        };
+        sampler.should_sample(
+            parent_context,
+            trace_id,
+            name,
+            span_kind,
+            attributes,
+            links,
+            instrumentation_library,
+        )
+    }
+}
+
+/// Samples up to `traces_per_second` for every span name, with bursts of up to one second of traces.
+#[derive(Debug)]
+pub struct RateLimitedSampler {
+    traces_per_second: f64,
+    parent_based: bool,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl RateLimitedSampler {
+    pub fn new(traces_per_second: f64, parent_based: bool) -> Self {
+        Self {
+            traces_per_second,
+            parent_based,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn try_acquire(&self, name: &str, now: Instant) -> bool {
+        let capacity = self.traces_per_second.max(1.0);
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(name.to_string()).or_insert(TokenBucket {
+            tokens: capacity,
+            last_refill: now,
+        });
+        bucket.tokens = (bucket.tokens
+            + now.duration_since(bucket.last_refill).as_secs_f64() * self.traces_per_second)
+            .min(capacity);
+        bucket.last_refill = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl ShouldSample for RateLimitedSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        _trace_id: TraceId,
+        name: &str,
+        _span_kind: &SpanKind,
+        _attributes: &OrderMap<Key, Value>,
+        _links: &[Link],
+        _instrumentation_library: &InstrumentationLibrary,
+    ) -> SamplingResult {
+        let parent = parent_context.filter(|cx| cx.has_active_span());
+        let sampled = match parent {
+            Some(cx) if self.parent_based => cx.span().span_context().is_sampled(),
+            _ => self.try_acquire(name, Instant::now()),
+        };
+        SamplingResult {
+            decision: if sampled {
+                SamplingDecision::RecordAndSample
+            } else {
+                SamplingDecision::Drop
+            },
+            attributes: Vec::new(),
+            trace_state: parent.map_or_else(TraceState::default, |cx| {
+                cx.span().span_context().trace_state().clone()
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_rate_limited_sampler() {
+        let sampler = RateLimitedSampler::new(2.0, true);
+        let start = Instant::now();
+        assert!(sampler.try_acquire("query", start));
+        assert!(sampler.try_acquire("query", start));
+        assert!(!sampler.try_acquire("query", start));
+        // Every span name has its own limit.
+        assert!(sampler.try_acquire("count", start));
+
+        assert!(!sampler.try_acquire("query", start + Duration::from_millis(100)));
+        assert!(sampler.try_acquire("query", start + Duration::from_millis(600)));
+        assert!(!sampler.try_acquire("query", start + Duration::from_millis(600)));
+
+        // Bursts are limited to one second of traces.
+        let later = start + Duration::from_secs(60);
+        assert!(sampler.try_acquire("query", later));
+        assert!(sampler.try_acquire("query", later));
+        assert!(!sampler.try_acquire("query", later));
+    }
+
+    #[test]
+    fn test_slow_rate_limited_sampler() {
+        let sampler = RateLimitedSampler::new(0.5, true);
+        let start = Instant::now();
+        assert!(sampler.try_acquire("query", start));
+        assert!(!sampler.try_acquire("query", start + Duration::from_secs(1)));
+        assert!(sampler.try_acquire("query", start + Duration::from_secs(2)));
+    }
+}
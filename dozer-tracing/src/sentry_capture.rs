@@ -0,0 +1,84 @@
+//! Sends error logs, with the warning and info logs before them as breadcrumbs, and panics to Sentry.
+
+use std::sync::Arc;
+
+use dozer_types::models::telemetry::SentryConfig;
+use dozer_types::tracing::Subscriber;
+use sentry::protocol::{Context, Event};
+use sentry::{ClientInitGuard, ClientOptions};
+use sentry_tracing::SentryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::redaction::Redactor;
+
+/// Initializes the Sentry client. The events waiting to be sent are flushed when the guard is dropped.
+pub fn init_sentry(config: &SentryConfig, redactor: &Redactor) -> ClientInitGuard {
+    let redactor = redactor.clone();
+    sentry::init((
+        config.dsn.as_str(),
+        ClientOptions {
+            environment: config.environment.clone().map(Into::into),
+            release: Some(
+                config
+                    .release
+                    .clone()
+                    .unwrap_or_else(|| format!("dozer@{}", env!("CARGO_PKG_VERSION")))
+                    .into(),
+            ),
+            attach_stacktrace: true,
+            before_send: Some(Arc::new(move |event| Some(redact_event(&redactor, event)))),
+            ..Default::default()
+        },
+    ))
+}
+
+/// Captures error events, with the fields of their spans, and records warning and info events as breadcrumbs.
+pub fn sentry_layer<S>() -> SentryLayer<S>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    sentry_tracing::layer()
+}
+
+/// The fields of events and spans end up in the extra data and contexts of the Sentry event.
+fn redact_event(redactor: &Redactor, mut event: Event<'static>) -> Event<'static> {
+    if redactor.is_empty() {
+        return event;
+    }
+    redactor.redact_members(event.extra.iter_mut());
+    for context in event.contexts.values_mut() {
+        if let Context::Other(fields) = context {
+            redactor.redact_members(fields.iter_mut());
+        }
+    }
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redaction::REDACTED;
+    use dozer_types::serde_json::Value;
+
+    #[test]
+    fn test_redact_event() {
+        let redactor = Redactor::new(&["password".to_string()]);
+        let mut event = Event::new();
+        event
+            .extra
+            .insert("password".to_string(), Value::from("hunter2"));
+        event.extra.insert("user".to_string(), Value::from("admin"));
+        event.contexts.insert(
+            "Rust Tracing Fields".to_string(),
+            Context::Other([("password".to_string(), Value::from("hunter2"))].into()),
+        );
+
+        let event = redact_event(&redactor, event);
+        assert_eq!(event.extra["password"], Value::from(REDACTED));
+        assert_eq!(event.extra["user"], Value::from("admin"));
+        let Context::Other(fields) = &event.contexts["Rust Tracing Fields"] else {
+            panic!("the context should have been kept");
+        };
+        assert_eq!(fields["password"], Value::from(REDACTED));
+    }
+}
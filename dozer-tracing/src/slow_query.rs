@@ -0,0 +1,164 @@
+//! Logs the cache reads and commits that take longer than a threshold at warn, from the spans of dozer-cache's
+//! `instrument` feature.
+
+use std::fmt::{self, Write};
+use std::time::{Duration, Instant};
+
+use dozer_types::tracing::field::{Field, Visit};
+use dozer_types::tracing::span::{Attributes, Id, Record};
+use dozer_types::tracing::{self, Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Filter};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// The spans of `RoCache` and `RwCache` that are timed.
+const SLOW_QUERY_SPANS: [&str; 5] = ["get", "get_many", "count", "query", "commit"];
+
+/// Logs the spans of `SlowQuerySpans` that last `threshold` or longer, with their fields, such as the query.
+pub struct SlowQueryLayer {
+    threshold: Duration,
+}
+
+impl SlowQueryLayer {
+    pub fn new(threshold: Duration) -> Self {
+        Self { threshold }
+    }
+}
+
+struct SlowQueryTiming {
+    start: Instant,
+    fields: String,
+}
+
+impl<S> Layer<S> for SlowQueryLayer
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut fields = String::new();
+        attrs.record(&mut FieldsVisitor(&mut fields));
+        span.extensions_mut().insert(SlowQueryTiming {
+            start: Instant::now(),
+            fields,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        if let Some(timing) = span.extensions_mut().get_mut::<SlowQueryTiming>() {
+            values.record(&mut FieldsVisitor(&mut timing.fields));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(timing) = span.extensions_mut().remove::<SlowQueryTiming>() else {
+            return;
+        };
+        let duration = timing.start.elapsed();
+        if duration >= self.threshold {
+            tracing::warn!(
+                target: "dozer::slow_query",
+                operation = span.name(),
+                duration_ms = duration.as_millis() as u64,
+                "Slow cache {}: {}",
+                span.name(),
+                timing.fields
+            );
+        }
+    }
+}
+
+/// Selects the spans of `SLOW_QUERY_SPANS`, whatever the log filter, so they are timed even if they are not logged.
+pub struct SlowQuerySpans;
+
+impl<S> Filter<S> for SlowQuerySpans {
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: &Context<'_, S>) -> bool {
+        metadata.is_span()
+            && metadata.target().starts_with("dozer_cache")
+            && SLOW_QUERY_SPANS.contains(&metadata.name())
+    }
+}
+
+/// Appends fields as `name=value`, separated by spaces.
+struct FieldsVisitor<'a>(&'a mut String);
+
+impl<'a> Visit for FieldsVisitor<'a> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_debug(field, &format_args!("{value}"));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        let _ = write!(self.0, "{}={:?}", field.name(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dozer_types::tracing::Event;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    /// Collects the messages of the events of `dozer::slow_query`.
+    struct Messages(Arc<Mutex<Vec<String>>>);
+
+    impl<S: Subscriber> Layer<S> for Messages {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            if event.metadata().target() != "dozer::slow_query" {
+                return;
+            }
+            let mut fields = String::new();
+            event.record(&mut FieldsVisitor(&mut fields));
+            self.0.lock().unwrap().push(fields);
+        }
+    }
+
+    fn slow_queries(threshold: Duration, f: impl FnOnce()) -> Vec<String> {
+        let messages = Arc::new(Mutex::new(vec![]));
+        let subscriber = tracing_subscriber::registry()
+            .with(SlowQueryLayer::new(threshold).with_filter(SlowQuerySpans))
+            .with(Messages(messages.clone()));
+        tracing::subscriber::with_default(subscriber, f);
+        let messages = messages.lock().unwrap().clone();
+        messages
+    }
+
+    #[test]
+    fn test_slow_query_layer() {
+        let messages = slow_queries(Duration::ZERO, || {
+            let span = tracing::debug_span!(
+                target: "dozer_cache::cache::lmdb::cache",
+                "query",
+                schema = "films",
+                query = r#"{"$limit":50}"#,
+                records = tracing::field::Empty
+            );
+            span.record("records", 3);
+            drop(span);
+            // Only the cache spans are timed.
+            drop(tracing::debug_span!(target: "dozer_cache::cache::lmdb::cache", "insert"));
+            drop(tracing::debug_span!(target: "dozer_api", "query"));
+        });
+        assert_eq!(messages.len(), 1);
+        assert!(
+            messages[0].contains(r#"Slow cache query: schema=films query={"$limit":50} records=3"#)
+        );
+        assert!(messages[0].contains("operation=query"));
+
+        let messages = slow_queries(Duration::from_secs(60), || {
+            drop(tracing::debug_span!(target: "dozer_cache::cache::lmdb::cache", "commit"));
+        });
+        assert!(messages.is_empty());
+    }
+}
@@ -12,9 +12,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .compile(&["protos/types.proto"], &["protos"])?;
     tonic_build::configure()
         .protoc_arg("--experimental_allow_proto3_optional")
+        .file_descriptor_set_path(out_dir.join("common.bin"))
         .compile(&["protos/common.proto"], &["protos"])?;
     tonic_build::configure()
         .protoc_arg("--experimental_allow_proto3_optional")
+        .file_descriptor_set_path(out_dir.join("health.bin"))
         .compile(&["protos/health.proto"], &["protos"])?;
     tonic_build::configure()
         .protoc_arg("--experimental_allow_proto3_optional")
@@ -32,6 +32,15 @@ pub enum FromArrowError {
     #[error("Duration conversion failed")]
     DurationConversionError,
 
+    #[error("Decimal conversion failed")]
+    DecimalConversionError,
+
+    #[error("Point conversion failed")]
+    PointConversionError,
+
+    #[error("Geometry conversion failed")]
+    GeometryConversionError,
+
     #[error("Schema has {0} fields, but batch has {1}")]
     SchemaMismatchError(usize, usize),
 
@@ -1,13 +1,17 @@
 use super::errors::FromArrowError;
 use super::errors::FromArrowError::DateConversionError;
 use super::errors::FromArrowError::DateTimeConversionError;
+use super::errors::FromArrowError::DecimalConversionError;
 use super::errors::FromArrowError::DurationConversionError;
 use super::errors::FromArrowError::FieldTypeNotSupported;
+use super::errors::FromArrowError::GeometryConversionError;
+use super::errors::FromArrowError::PointConversionError;
 use super::errors::FromArrowError::TimeConversionError;
 use super::to_arrow;
 use crate::types::Record;
 use crate::types::{
-    Field as DozerField, FieldDefinition, FieldType, Schema as DozerSchema, SourceDefinition,
+    DozerDecimal128, DozerDuration, DozerGeometry, DozerPoint, Field as DozerField,
+    FieldDefinition, FieldType, Schema as DozerSchema, SourceDefinition,
 };
 use arrow::array;
 use arrow::array::{Array, ArrayRef};
@@ -15,6 +19,8 @@ use arrow::datatypes::{DataType, TimeUnit};
 use arrow::ipc::writer::StreamWriter;
 use arrow::record_batch::RecordBatch;
 use arrow::row::SortField;
+use rust_decimal::Decimal;
+use std::str::FromStr;
 
 macro_rules! make_from {
     ($array_type:ty, $column: ident, $row: ident) => {{
@@ -52,6 +58,33 @@ macro_rules! make_binary {
     }};
 }
 
+macro_rules! make_decimal {
+    ($array_type:ty, $column: ident, $row: ident) => {{
+        let array = $column.as_any().downcast_ref::<$array_type>();
+
+        if let Some(r) = array {
+            if r.is_null($row.clone()) {
+                Ok(DozerField::Null)
+            } else {
+                let value = r.value_as_string($row.clone());
+                // Arrow pads the scale with zeros, which may not fit in a `Decimal`.
+                let value = if value.contains('.') {
+                    value.trim_end_matches('0').trim_end_matches('.')
+                } else {
+                    value.as_str()
+                };
+                // Decimals beyond `Decimal` are `Decimal128`s.
+                Decimal::from_str(value)
+                    .map(DozerField::from)
+                    .or_else(|_| DozerDecimal128::from_str(value).map(DozerField::from))
+                    .map_err(|_| DecimalConversionError)
+            }
+        } else {
+            Ok(DozerField::Null)
+        }
+    }};
+}
+
 macro_rules! make_timestamp {
     ($array_type:ty, $column: ident, $row: ident) => {{
         let array = $column.as_any().downcast_ref::<$array_type>();
@@ -112,10 +145,12 @@ macro_rules! make_duration {
             if r.is_null($row.clone()) {
                 Ok(DozerField::Null)
             } else {
-                r.value_as_duration($row.clone()).map_or_else(
-                    || Err(DurationConversionError),
-                    |v| Ok(DozerField::from(v.num_nanoseconds().unwrap())),
-                )
+                r.value_as_duration($row.clone())
+                    .and_then(DozerDuration::from_chrono)
+                    .map_or_else(
+                        || Err(DurationConversionError),
+                        |v| Ok(DozerField::Duration(v)),
+                    )
             }
         } else {
             Ok(DozerField::Null)
@@ -128,13 +163,22 @@ pub fn map_schema_to_dozer(
 ) -> Result<DozerSchema, FromArrowError> {
     let mut fields = vec![];
     for field in schema.fields() {
-        let typ = map_arrow_to_dozer_type(field.data_type())?;
+        // Bson, points and geometries are binaries in Arrow, and `Decimal128`s are decimals like `Decimal`s, see
+        // `to_arrow::map_field_type`.
+        let typ = match field.metadata().get("logical_type").map(String::as_str) {
+            Some("Bson") => FieldType::Bson,
+            Some("Point") => FieldType::Point,
+            Some("Geometry") => FieldType::Geometry,
+            Some("Decimal128") => FieldType::Decimal128,
+            _ => map_arrow_to_dozer_type(field.data_type())?,
+        };
 
         fields.push(FieldDefinition {
             name: field.name().clone(),
             typ,
             nullable: field.is_nullable(),
             source: SourceDefinition::Dynamic,
+            metadata: Default::default(),
         });
     }
 
@@ -150,7 +194,6 @@ pub fn map_arrow_to_dozer_type(dt: &DataType) -> Result<FieldType, FromArrowErro
         DataType::Boolean => Ok(FieldType::Boolean),
         DataType::Time32(_)
         | DataType::Time64(_)
-        | DataType::Duration(_)
         | DataType::Interval(_)
         | DataType::Int8
         | DataType::Int16
@@ -161,20 +204,20 @@ pub fn map_arrow_to_dozer_type(dt: &DataType) -> Result<FieldType, FromArrowErro
         }
         DataType::Float16 | DataType::Float32 | DataType::Float64 => Ok(FieldType::Float),
         DataType::Timestamp(_, _) => Ok(FieldType::Timestamp),
+        DataType::Duration(_) => Ok(FieldType::Duration),
         DataType::Date32 | DataType::Date64 => Ok(FieldType::Date),
         DataType::Binary | DataType::FixedSizeBinary(_) | DataType::LargeBinary => {
             Ok(FieldType::Binary)
         }
         DataType::Utf8 => Ok(FieldType::String),
         DataType::LargeUtf8 => Ok(FieldType::Text),
+        DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => Ok(FieldType::Decimal),
         // DataType::List(_) => {}
         // DataType::FixedSizeList(_, _) => {}
         // DataType::LargeList(_) => {}
         // DataType::Struct(_) => {}
         // DataType::Union(_, _, _) => {}
         // DataType::Dictionary(_, _) => {}
-        // DataType::Decimal128(_, _) => {}
-        // DataType::Decimal256(_, _) => {}
         // DataType::Map(_, _) => {}
         _ => Err(FieldTypeNotSupported(format!("{dt:?}"))),
     }
@@ -239,7 +282,9 @@ pub fn map_value_to_dozer_field(
         DataType::FixedSizeBinary(_) => make_binary!(array::FixedSizeBinaryArray, column, row),
         DataType::LargeBinary => make_binary!(array::LargeBinaryArray, column, row),
         DataType::Utf8 => make_from!(array::StringArray, column, row),
-        DataType::LargeUtf8 => make_from!(array::StringArray, column, row),
+        DataType::LargeUtf8 => make_from!(array::LargeStringArray, column, row),
+        DataType::Decimal128(_, _) => make_decimal!(array::Decimal128Array, column, row),
+        DataType::Decimal256(_, _) => make_decimal!(array::Decimal256Array, column, row),
         // DataType::Interval(TimeUnit::) => make_from!(array::BooleanArray, x, x0),
         // DataType::List(_) => {}
         // DataType::FixedSizeList(_, _) => {}
@@ -247,8 +292,6 @@ pub fn map_value_to_dozer_field(
         // DataType::Struct(_) => {}
         // DataType::Union(_, _, _) => {}
         // DataType::Dictionary(_, _) => {}
-        // DataType::Decimal128(_, _) => {}
-        // DataType::Decimal256(_, _) => {}
         // DataType::Map(_, _) => {}
         _ => Err(FieldTypeNotSupported(column_name.to_string())),
     }
@@ -278,7 +321,7 @@ pub fn map_record_batch_to_dozer_records(
         for (c, x) in columns.iter().enumerate() {
             let field = schema.fields.get(c).unwrap();
             let value = map_value_to_dozer_field(x, &r, &field.name)?;
-            values.push(value);
+            values.push(map_to_logical_type(value, field.typ)?);
         }
         records.push(Record {
             schema_id: schema.identifier,
@@ -290,6 +333,24 @@ pub fn map_record_batch_to_dozer_records(
     Ok(records)
 }
 
+/// Recovers the Dozer types that share an Arrow type with others.
+fn map_to_logical_type(value: DozerField, typ: FieldType) -> Result<DozerField, FromArrowError> {
+    match (value, typ) {
+        (DozerField::String(v), FieldType::Text) => Ok(DozerField::Text(v)),
+        (DozerField::Binary(v), FieldType::Bson) => Ok(DozerField::Bson(v)),
+        (DozerField::Binary(v), FieldType::Point) if v.len() == 16 => DozerPoint::from_bytes(&v)
+            .map(DozerField::Point)
+            .map_err(|_| PointConversionError),
+        (DozerField::Binary(_), FieldType::Point) => Err(PointConversionError),
+        (DozerField::Binary(v), FieldType::Geometry) => DozerGeometry::from_ewkb(v)
+            .map(DozerField::Geometry)
+            .map_err(|_| GeometryConversionError),
+        (DozerField::Decimal(v), FieldType::Decimal128) => Ok(DozerField::Decimal128(v.into())),
+        (DozerField::Decimal128(_), FieldType::Decimal) => Err(DecimalConversionError),
+        (value, _) => Ok(value),
+    }
+}
+
 pub fn serialize_record_batch(record: &RecordBatch) -> Vec<u8> {
     let buffer: Vec<u8> = Vec::new();
     let mut stream_writer = StreamWriter::try_new(buffer, &record.schema()).unwrap();
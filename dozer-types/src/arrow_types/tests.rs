@@ -1,7 +1,15 @@
 use arrow::datatypes::{self as arrow_types};
+use chrono::{DateTime, NaiveDate};
+use rust_decimal::Decimal;
 use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::arrow_types::from_arrow::{map_record_batch_to_dozer_records, map_schema_to_dozer};
+use crate::arrow_types::to_arrow::map_records_to_arrow;
+use crate::types::{
+    DozerPoint, Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition,
+};
 
-use crate::arrow_types::from_arrow::map_schema_to_dozer;
 #[test]
 fn can_convert_from_arrow_to_dozer() {
     let field_a = arrow_types::Field::new("a", arrow_types::DataType::Int64, false);
@@ -18,3 +26,128 @@ fn can_convert_from_arrow_to_dozer() {
         field_a.name().to_string().as_str()
     );
 }
+
+#[test]
+fn can_convert_records_to_arrow_and_back() {
+    let schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "decimal".to_string(),
+                FieldType::Decimal,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "timestamp".to_string(),
+                FieldType::Timestamp,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "date".to_string(),
+                FieldType::Date,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "point".to_string(),
+                FieldType::Point,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "text".to_string(),
+                FieldType::Text,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+    let records = vec![
+        Record::new(
+            None,
+            vec![
+                Field::Decimal(Decimal::from_str("-12.345").unwrap()),
+                Field::Timestamp(
+                    DateTime::parse_from_rfc3339("2023-03-01T12:34:56.123456789Z").unwrap(),
+                ),
+                Field::Date(NaiveDate::from_ymd_opt(2023, 3, 1).unwrap()),
+                Field::Point(DozerPoint::from((1.5, -2.0))),
+                Field::Text("text".to_string()),
+            ],
+            None,
+        ),
+        Record::new(
+            None,
+            vec![
+                Field::Decimal(Decimal::MAX),
+                Field::Null,
+                Field::Null,
+                Field::Null,
+                Field::Null,
+            ],
+            None,
+        ),
+    ];
+
+    let batch = map_records_to_arrow(&records, &schema).unwrap();
+    assert_eq!(batch.num_rows(), 2);
+
+    let arrow_schema = batch.schema();
+    let dozer_schema = map_schema_to_dozer(&arrow_schema).unwrap();
+    assert_eq!(
+        dozer_schema
+            .fields
+            .iter()
+            .map(|field| field.typ)
+            .collect::<Vec<_>>(),
+        schema
+            .fields
+            .iter()
+            .map(|field| field.typ)
+            .collect::<Vec<_>>()
+    );
+
+    let values = map_record_batch_to_dozer_records(batch, &schema)
+        .unwrap()
+        .into_iter()
+        .map(|record| record.values)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        records
+            .into_iter()
+            .map(|record| record.values)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn converting_mismatched_record_to_arrow_fails() {
+    let schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "int".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+    let record = Record::new(None, vec![Field::String("1".to_string())], None);
+    assert!(map_records_to_arrow(&[record], &schema).is_err());
+}
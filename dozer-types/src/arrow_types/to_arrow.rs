@@ -1,18 +1,21 @@
 use std::{collections::HashMap, sync::Arc};
 
-use crate::types::{Field, FieldDefinition, FieldType, Record, Schema};
+use crate::types::{DozerDecimal128, Field, FieldDefinition, FieldType, Record, Schema};
 use arrow::datatypes::{self as arrow_types, DataType};
+use chrono::{DateTime, FixedOffset};
+use rust_decimal::Decimal;
 
 use arrow::{
     array::{self as arrow_array, ArrayRef},
     datatypes::i256,
+    error::ArrowError,
     record_batch::RecordBatch,
 };
 
 // Maps a Dozer Schema to an Arrow Schema
 pub fn map_to_arrow_schema(
     schema: crate::types::Schema,
-) -> Result<arrow_types::Schema, arrow::error::ArrowError> {
+) -> Result<arrow_types::Schema, ArrowError> {
     let mut fields = vec![];
     for fd in schema.fields {
         let field = arrow_types::Field::from(fd);
@@ -24,101 +27,158 @@ pub fn map_to_arrow_schema(
     })
 }
 
+/// Precision and scale of Arrow decimals, enough for every `Decimal` without rounding.
+pub const DECIMAL_PRECISION: u8 = 76;
+pub const DECIMAL_SCALE: i8 = 28;
+/// Scale of Arrow decimals of `Decimal128`, with the same precision.
+pub const DECIMAL128_SCALE: i8 = DozerDecimal128::MAX_PRECISION as i8;
+
+/// Time zone of Arrow timestamps. Arrow has one time zone per column, so timestamps are converted to UTC.
+pub const TIMESTAMP_TIMEZONE: &str = "UTC";
+
+macro_rules! make_array {
+    ($array_type:ty, $values:ident, $invalid:ident, $pattern:pat => $value:expr) => {
+        Arc::new(
+            $values
+                .map(|field| match field {
+                    $pattern => Ok(Some($value)),
+                    Field::Null => Ok(None),
+                    field => Err($invalid(field)),
+                })
+                .collect::<Result<$array_type, ArrowError>>()?,
+        ) as ArrayRef
+    };
+}
+
 // Maps a Dozer Record to an Arrow RecordBatch of size 1
-// TODO: We can extend this as we build our micro batching support.
-// In a micro batch we can send a record batch that is of size > 1
-pub fn map_record_to_arrow(
-    rec: Record,
-    schema: Schema,
-) -> Result<RecordBatch, arrow::error::ArrowError> {
-    let mut rows = vec![];
-
-    for (idx, f) in rec.values.iter().enumerate() {
-        let fd = schema.fields.get(idx).unwrap();
-        let r = match (f, fd.typ) {
-            (Field::UInt(v), FieldType::UInt) => {
-                Arc::new(arrow_array::UInt64Array::from_iter_values([*v])) as ArrayRef
-            }
-            (Field::Null, FieldType::UInt) => {
-                Arc::new(arrow_array::UInt64Array::from(vec![None as Option<u64>])) as ArrayRef
-            }
-            (Field::Int(v), FieldType::Int) => {
-                Arc::new(arrow_array::Int64Array::from_iter_values([*v])) as ArrayRef
-            }
-            (Field::Null, FieldType::Int) => {
-                Arc::new(arrow_array::Int64Array::from(vec![None as Option<i64>])) as ArrayRef
-            }
-            (Field::Float(v), FieldType::Float) => {
-                Arc::new(arrow_array::Float64Array::from_iter_values([**v])) as ArrayRef
-            }
-            (Field::Null, FieldType::Float) => {
-                Arc::new(arrow_array::Float64Array::from(vec![None as Option<f64>])) as ArrayRef
-            }
-            (Field::Boolean(v), FieldType::Boolean) => {
-                Arc::new(arrow_array::BooleanArray::from(vec![*v])) as ArrayRef
-            }
-            (Field::Null, FieldType::Boolean) => {
-                Arc::new(arrow_array::BooleanArray::from(vec![None as Option<bool>])) as ArrayRef
-            }
-            (Field::String(v), FieldType::String) => {
-                Arc::new(arrow_array::StringArray::from_iter_values([v])) as ArrayRef
-            }
-            (Field::Null, FieldType::String) => {
-                Arc::new(arrow_array::StringArray::from(vec![None as Option<String>])) as ArrayRef
-            }
-            (Field::Text(v), FieldType::Text) => {
-                Arc::new(arrow_array::LargeStringArray::from_iter_values([v])) as ArrayRef
-            }
-            (Field::Null, FieldType::Text) => Arc::new(arrow_array::LargeStringArray::from(vec![
-                None as Option<String>,
-            ])) as ArrayRef,
-            (Field::Decimal(v), FieldType::Decimal) => Arc::new(arrow_array::Decimal256Array::from(
-                i256::from_string(&v.to_string())
-                    .map_or(vec![None as Option<i256>], |f| vec![Some(f)]),
-            )) as ArrayRef,
-            (Field::Null, FieldType::Decimal) => Arc::new(arrow_array::Decimal256Array::from(vec![
-                None as Option<i256>,
-            ])) as ArrayRef,
-            (Field::Timestamp(v), FieldType::Timestamp) => {
-                Arc::new(arrow_array::TimestampMillisecondArray::from_iter_values([
-                    v.timestamp_millis(),
-                ])) as ArrayRef
-            }
-            (Field::Null, FieldType::Timestamp) => {
-                Arc::new(arrow_array::TimestampMillisecondArray::from(vec![
-                    None as Option<i64>,
-                ])) as ArrayRef
-            }
-            (Field::Date(v), FieldType::Date) => {
-                let d = v.and_hms_milli_opt(0, 0, 0, 0).unwrap();
-                Arc::new(arrow_array::Date64Array::from_iter_values([
-                    d.timestamp_millis()
-                ])) as ArrayRef
-            }
-            (Field::Null, FieldType::Date) => {
-                Arc::new(arrow_array::Date64Array::from(vec![None as Option<i64>])) as ArrayRef
-            }
-            (Field::Binary(v), FieldType::Binary) => {
-                Arc::new(arrow_array::BinaryArray::from_iter_values([v])) as ArrayRef
-            }
-            (Field::Bson(v), FieldType::Bson) => {
-                Arc::new(arrow_array::BinaryArray::from_iter_values([v])) as ArrayRef
-            }
-            (Field::Point(v), FieldType::Point) => {
-                Arc::new(arrow_array::BinaryArray::from_iter_values([v.to_bytes()])) as ArrayRef
-            }
-            (Field::Null, FieldType::Point) => {
-                Arc::new(arrow_array::BinaryArray::from_opt_vec(vec![
-                    None as Option<&[u8]>,
-                ])) as ArrayRef
-            }
-            (a, b) => Err(arrow::error::ArrowError::InvalidArgumentError(format!(
-                "Invalid field type {b:?} for the field: {a:?}",
-            )))?,
-        };
-        rows.push(r);
+pub fn map_record_to_arrow(rec: Record, schema: Schema) -> Result<RecordBatch, ArrowError> {
+    map_records_to_arrow(&[rec], &schema)
+}
+
+/// Maps Dozer records of `schema` to an Arrow RecordBatch with a row per record.
+pub fn map_records_to_arrow(
+    records: &[Record],
+    schema: &Schema,
+) -> Result<RecordBatch, ArrowError> {
+    let mut columns = vec![];
+    for (idx, fd) in schema.fields.iter().enumerate() {
+        let values = records
+            .iter()
+            .map(|rec| rec.values.get(idx).unwrap_or(&Field::Null));
+        columns.push(map_column_to_arrow(values, fd.typ)?);
     }
-    RecordBatch::try_new(Arc::new(map_to_arrow_schema(schema).unwrap()), rows)
+    RecordBatch::try_new(Arc::new(map_to_arrow_schema(schema.clone())?), columns)
+}
+
+fn map_column_to_arrow<'a>(
+    values: impl Iterator<Item = &'a Field>,
+    typ: FieldType,
+) -> Result<ArrayRef, ArrowError> {
+    let invalid = |field: &Field| {
+        ArrowError::InvalidArgumentError(format!(
+            "Invalid field type {typ:?} for the field: {field:?}",
+        ))
+    };
+
+    Ok(match typ {
+        FieldType::UInt => {
+            make_array!(arrow_array::UInt64Array, values, invalid, Field::UInt(v) => *v)
+        }
+        FieldType::Int => {
+            make_array!(arrow_array::Int64Array, values, invalid, Field::Int(v) => *v)
+        }
+        FieldType::Float => {
+            make_array!(arrow_array::Float64Array, values, invalid, Field::Float(v) => v.0)
+        }
+        FieldType::Boolean => {
+            make_array!(arrow_array::BooleanArray, values, invalid, Field::Boolean(v) => *v)
+        }
+        FieldType::String => {
+            make_array!(arrow_array::StringArray, values, invalid, Field::String(v) => v)
+        }
+        FieldType::Text => {
+            make_array!(arrow_array::LargeStringArray, values, invalid, Field::Text(v) => v)
+        }
+        FieldType::Decimal => Arc::new(
+            values
+                .map(|field| match field {
+                    Field::Decimal(v) => Ok(Some(decimal_to_i256(v))),
+                    Field::Null => Ok(None),
+                    field => Err(invalid(field)),
+                })
+                .collect::<Result<arrow_array::Decimal256Array, ArrowError>>()?
+                .with_precision_and_scale(DECIMAL_PRECISION, DECIMAL_SCALE)?,
+        ) as ArrayRef,
+        FieldType::Decimal128 => Arc::new(
+            values
+                .map(|field| match field {
+                    Field::Decimal128(v) => Ok(Some(decimal128_to_i256(v))),
+                    Field::Null => Ok(None),
+                    field => Err(invalid(field)),
+                })
+                .collect::<Result<arrow_array::Decimal256Array, ArrowError>>()?
+                .with_precision_and_scale(DECIMAL_PRECISION, DECIMAL128_SCALE)?,
+        ) as ArrayRef,
+        FieldType::Timestamp => Arc::new(
+            values
+                .map(|field| match field {
+                    Field::Timestamp(v) => timestamp_nanos(v).map(Some),
+                    Field::Null => Ok(None),
+                    field => Err(invalid(field)),
+                })
+                .collect::<Result<arrow_array::TimestampNanosecondArray, ArrowError>>()?
+                .with_timezone(TIMESTAMP_TIMEZONE.to_string()),
+        ) as ArrayRef,
+        FieldType::Date => {
+            make_array!(arrow_array::Date64Array, values, invalid, Field::Date(v) => {
+                v.and_hms_milli_opt(0, 0, 0, 0)
+                    .expect("Midnight is valid")
+                    .timestamp_millis()
+            })
+        }
+        FieldType::Binary => {
+            make_array!(arrow_array::BinaryArray, values, invalid, Field::Binary(v) => v)
+        }
+        FieldType::Bson => {
+            make_array!(arrow_array::BinaryArray, values, invalid, Field::Bson(v) => v)
+        }
+        FieldType::Point => {
+            make_array!(arrow_array::BinaryArray, values, invalid, Field::Point(v) => v.to_bytes())
+        }
+        FieldType::Geometry => {
+            make_array!(arrow_array::BinaryArray, values, invalid, Field::Geometry(v) => v.as_bytes())
+        }
+        FieldType::Duration => make_array!(
+            arrow_array::DurationNanosecondArray,
+            values,
+            invalid,
+            Field::Duration(v) => v.as_nanos()
+        ),
+    })
+}
+
+/// Scales `decimal` to `DECIMAL_SCALE`, which never overflows as `Decimal` mantissas have 96 bits.
+fn decimal_to_i256(decimal: &Decimal) -> i256 {
+    let factor = 10_i128.pow(DECIMAL_SCALE as u32 - decimal.scale());
+    i256::from_i128(decimal.mantissa()).wrapping_mul(i256::from_i128(factor))
+}
+
+/// Scales `decimal` to `DECIMAL128_SCALE`, which never overflows as the result has at most 76 digits.
+fn decimal128_to_i256(decimal: &DozerDecimal128) -> i256 {
+    let factor = 10_i128.pow((DECIMAL128_SCALE as u8 - decimal.scale()) as u32);
+    i256::from_i128(decimal.mantissa()).wrapping_mul(i256::from_i128(factor))
+}
+
+fn timestamp_nanos(timestamp: &DateTime<FixedOffset>) -> Result<i64, ArrowError> {
+    timestamp
+        .timestamp()
+        .checked_mul(1_000_000_000)
+        .and_then(|nanos| nanos.checked_add(timestamp.timestamp_subsec_nanos() as i64))
+        .ok_or_else(|| {
+            ArrowError::InvalidArgumentError(format!(
+                "Timestamp {timestamp} is out of the range of nanosecond timestamps"
+            ))
+        })
 }
 
 // Maps the dozer field type to the arrow data type
@@ -132,9 +192,15 @@ pub fn map_field_type(typ: FieldType, metadata: Option<&mut HashMap<String, Stri
         FieldType::Boolean => DataType::Boolean,
         FieldType::String => DataType::Utf8,
         FieldType::Text => DataType::LargeUtf8,
-        // TODO: Map thsi correctly
-        FieldType::Decimal => DataType::Decimal256(10, 5),
-        FieldType::Timestamp => DataType::Timestamp(arrow_types::TimeUnit::Millisecond, None),
+        FieldType::Decimal => DataType::Decimal256(DECIMAL_PRECISION, DECIMAL_SCALE),
+        FieldType::Decimal128 => {
+            metadata.map(|m| m.insert("logical_type".to_string(), "Decimal128".to_string()));
+            DataType::Decimal256(DECIMAL_PRECISION, DECIMAL128_SCALE)
+        }
+        FieldType::Timestamp => DataType::Timestamp(
+            arrow_types::TimeUnit::Nanosecond,
+            Some(TIMESTAMP_TIMEZONE.to_string()),
+        ),
         FieldType::Date => DataType::Date64,
         FieldType::Binary => {
             metadata.map(|m| m.insert("logical_type".to_string(), "Binary".to_string()));
@@ -148,6 +214,11 @@ pub fn map_field_type(typ: FieldType, metadata: Option<&mut HashMap<String, Stri
             metadata.map(|m| m.insert("logical_type".to_string(), "Point".to_string()));
             DataType::Binary
         }
+        FieldType::Geometry => {
+            metadata.map(|m| m.insert("logical_type".to_string(), "Geometry".to_string()));
+            DataType::Binary
+        }
+        FieldType::Duration => DataType::Duration(arrow_types::TimeUnit::Nanosecond),
     }
 }
 
@@ -156,6 +227,6 @@ impl From<FieldDefinition> for arrow_types::Field {
         let mut metadata = HashMap::new();
         let dt = map_field_type(f.typ, Some(&mut metadata));
 
-        arrow_types::Field::new(f.name, dt, f.nullable)
+        arrow_types::Field::new(f.name, dt, f.nullable).with_metadata(metadata)
     }
 }
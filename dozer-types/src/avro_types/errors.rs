@@ -0,0 +1,43 @@
+use apache_avro::Error as AvroError;
+use thiserror::Error;
+
+use crate::types::{Field, FieldType};
+
+#[derive(Error, Debug)]
+pub enum ToAvroError {
+    #[error(transparent)]
+    AvroError(#[from] AvroError),
+
+    #[error("Schema has {0} fields, but record has {1}")]
+    SchemaMismatchError(usize, usize),
+
+    #[error("Invalid field type {0} for the field: {1}")]
+    InvalidFieldValue(FieldType, Field),
+
+    #[error("Field {0} is out of the range of its Avro type")]
+    OutOfRangeError(Field),
+}
+
+#[derive(Error, Debug)]
+pub enum FromAvroError {
+    #[error("Unsupported type of \"{0}\" field")]
+    FieldTypeNotSupported(String),
+
+    #[error("Schema is not a record")]
+    NotARecordError,
+
+    #[error("Value does not match the schema of \"{0}\" field")]
+    SchemaMismatchError(String),
+
+    #[error("Date time conversion failed")]
+    DateTimeConversionError,
+
+    #[error("Date conversion failed")]
+    DateConversionError,
+
+    #[error("Decimal conversion failed")]
+    DecimalConversionError,
+
+    #[error(transparent)]
+    AvroError(#[from] AvroError),
+}
@@ -0,0 +1,195 @@
+use std::str::FromStr;
+
+use apache_avro::schema::RecordField;
+use apache_avro::types::Value as AvroValue;
+use apache_avro::Schema as AvroSchema;
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use num_bigint::BigInt;
+use ordered_float::OrderedFloat;
+use rust_decimal::Decimal;
+
+use super::errors::FromAvroError;
+use super::errors::FromAvroError::{
+    DateConversionError, DateTimeConversionError, DecimalConversionError, FieldTypeNotSupported,
+    NotARecordError, SchemaMismatchError,
+};
+use super::to_avro::{DECIMAL_SCALE, POINT_RECORD_NAME};
+use crate::types::{
+    DozerDecimal128, DozerPoint, Field as DozerField, FieldDefinition, FieldType, Record,
+    Schema as DozerSchema, SourceDefinition,
+};
+
+fn record_fields(schema: &AvroSchema) -> Result<&[RecordField], FromAvroError> {
+    match schema {
+        AvroSchema::Record { fields, .. } => Ok(fields),
+        _ => Err(NotARecordError),
+    }
+}
+
+pub fn map_schema_to_dozer(schema: &AvroSchema) -> Result<DozerSchema, FromAvroError> {
+    let mut fields = vec![];
+    for field in record_fields(schema)? {
+        let (typ, nullable) = match &field.schema {
+            AvroSchema::Union(union) if union.is_nullable() => {
+                let mut variants = union
+                    .variants()
+                    .iter()
+                    .filter(|variant| **variant != AvroSchema::Null);
+                match (variants.next(), variants.next()) {
+                    (Some(variant), None) => (map_avro_to_dozer_type(variant, &field.name)?, true),
+                    _ => return Err(FieldTypeNotSupported(field.name.clone())),
+                }
+            }
+            schema => (map_avro_to_dozer_type(schema, &field.name)?, false),
+        };
+
+        fields.push(FieldDefinition {
+            name: field.name.clone(),
+            typ,
+            nullable,
+            source: SourceDefinition::Dynamic,
+            metadata: Default::default(),
+        });
+    }
+
+    Ok(DozerSchema {
+        identifier: None,
+        fields,
+        primary_index: vec![],
+    })
+}
+
+pub fn map_avro_to_dozer_type(
+    schema: &AvroSchema,
+    field_name: &str,
+) -> Result<FieldType, FromAvroError> {
+    match schema {
+        AvroSchema::Boolean => Ok(FieldType::Boolean),
+        AvroSchema::Int | AvroSchema::Long | AvroSchema::TimeMillis | AvroSchema::TimeMicros => {
+            Ok(FieldType::Int)
+        }
+        AvroSchema::Float | AvroSchema::Double => Ok(FieldType::Float),
+        AvroSchema::Bytes | AvroSchema::Fixed { .. } => Ok(FieldType::Binary),
+        AvroSchema::String | AvroSchema::Enum { .. } | AvroSchema::Uuid => Ok(FieldType::String),
+        // Scales beyond `Decimal` are from `Decimal128`, see `to_avro::map_field_type`.
+        AvroSchema::Decimal { scale, .. } if *scale > DECIMAL_SCALE as usize => {
+            Ok(FieldType::Decimal128)
+        }
+        AvroSchema::Decimal { .. } => Ok(FieldType::Decimal),
+        AvroSchema::Date => Ok(FieldType::Date),
+        AvroSchema::TimestampMillis | AvroSchema::TimestampMicros => Ok(FieldType::Timestamp),
+        AvroSchema::Record { name, .. } | AvroSchema::Ref { name }
+            if name.name == POINT_RECORD_NAME =>
+        {
+            Ok(FieldType::Point)
+        }
+        // AvroSchema::Array(_) => {}
+        // AvroSchema::Map(_) => {}
+        // AvroSchema::Record { .. } => {}
+        // AvroSchema::Duration => {}
+        _ => Err(FieldTypeNotSupported(field_name.to_string())),
+    }
+}
+
+// Maps an Avro record value of `schema` to a Dozer Record of the schema from `map_schema_to_dozer`
+pub fn map_avro_to_dozer_record(
+    value: &AvroValue,
+    schema: &AvroSchema,
+) -> Result<Record, FromAvroError> {
+    let fields = record_fields(schema)?;
+    let AvroValue::Record(values) = value else {
+        return Err(NotARecordError);
+    };
+    if fields.len() != values.len() {
+        return Err(SchemaMismatchError(format!(
+            "{} fields, but {} values",
+            fields.len(),
+            values.len()
+        )));
+    }
+
+    let mut result = vec![];
+    for (field, (_, value)) in fields.iter().zip(values) {
+        result.push(map_value_to_dozer_field(value, &field.schema, &field.name)?);
+    }
+    Ok(Record::new(None, result, None))
+}
+
+pub fn map_value_to_dozer_field(
+    value: &AvroValue,
+    schema: &AvroSchema,
+    field_name: &str,
+) -> Result<DozerField, FromAvroError> {
+    let mismatch = || SchemaMismatchError(field_name.to_string());
+    match (value, schema) {
+        (AvroValue::Null, _) => Ok(DozerField::Null),
+        (AvroValue::Union(index, value), AvroSchema::Union(union)) => {
+            let schema = union.variants().get(*index as usize).ok_or_else(mismatch)?;
+            map_value_to_dozer_field(value, schema, field_name)
+        }
+        (AvroValue::Boolean(v), _) => Ok(DozerField::Boolean(*v)),
+        (AvroValue::Int(v) | AvroValue::TimeMillis(v), _) => Ok(DozerField::Int(*v as i64)),
+        (AvroValue::Long(v) | AvroValue::TimeMicros(v), _) => Ok(DozerField::Int(*v)),
+        (AvroValue::Float(v), _) => Ok(DozerField::Float(OrderedFloat(*v as f64))),
+        (AvroValue::Double(v), _) => Ok(DozerField::Float(OrderedFloat(*v))),
+        (AvroValue::Bytes(v) | AvroValue::Fixed(_, v), _) => Ok(DozerField::Binary(v.clone())),
+        (AvroValue::String(v) | AvroValue::Enum(_, v), _) => Ok(DozerField::String(v.clone())),
+        (AvroValue::Uuid(v), _) => Ok(DozerField::String(v.to_string())),
+        (AvroValue::Decimal(v), AvroSchema::Decimal { scale, .. }) => {
+            let bytes = Vec::<u8>::try_from(v)?;
+            let value = decimal_bytes_to_string(&bytes, *scale);
+            if *scale > DECIMAL_SCALE as usize {
+                DozerDecimal128::from_str(&value)
+                    .map(DozerField::Decimal128)
+                    .map_err(|_| DecimalConversionError)
+            } else {
+                Decimal::from_str(&value)
+                    .map(DozerField::Decimal)
+                    .map_err(|_| DecimalConversionError)
+            }
+        }
+        (AvroValue::Date(days), _) => NaiveDate::from_ymd_opt(1970, 1, 1)
+            .and_then(|epoch| epoch.checked_add_signed(Duration::days(*days as i64)))
+            .map(DozerField::Date)
+            .ok_or(DateConversionError),
+        (AvroValue::TimestampMillis(millis), _) => NaiveDateTime::from_timestamp_opt(
+            millis.div_euclid(1_000),
+            (millis.rem_euclid(1_000) * 1_000_000) as u32,
+        )
+        .map(DozerField::from)
+        .ok_or(DateTimeConversionError),
+        (AvroValue::TimestampMicros(micros), _) => NaiveDateTime::from_timestamp_opt(
+            micros.div_euclid(1_000_000),
+            (micros.rem_euclid(1_000_000) * 1_000) as u32,
+        )
+        .map(DozerField::from)
+        .ok_or(DateTimeConversionError),
+        (AvroValue::Record(fields), _) => match fields.as_slice() {
+            [(x_name, AvroValue::Double(x)), (y_name, AvroValue::Double(y))]
+                if x_name == "x" && y_name == "y" =>
+            {
+                Ok(DozerField::Point(DozerPoint::from((*x, *y))))
+            }
+            _ => Err(mismatch()),
+        },
+        _ => Err(FieldTypeNotSupported(field_name.to_string())),
+    }
+}
+
+/// Converts the big-endian two's complement unscaled bytes of an Avro decimal to a decimal string.
+fn decimal_bytes_to_string(bytes: &[u8], scale: usize) -> String {
+    let unscaled = BigInt::from_signed_bytes_be(bytes).to_string();
+    let (sign, digits) = match unscaled.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", unscaled.as_str()),
+    };
+    let digits = format!("{digits:0>width$}", width = scale + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - scale);
+    // Trailing zeros of the scale may not fit in a `Decimal`.
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        format!("{sign}{integer}")
+    } else {
+        format!("{sign}{integer}.{fraction}")
+    }
+}
@@ -0,0 +1,6 @@
+pub mod errors;
+pub mod from_avro;
+pub mod to_avro;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,113 @@
+use std::str::FromStr;
+
+use apache_avro::{from_avro_datum, to_avro_datum};
+use chrono::{DateTime, NaiveDate};
+use rust_decimal::Decimal;
+
+use crate::avro_types::from_avro::{map_avro_to_dozer_record, map_schema_to_dozer};
+use crate::avro_types::to_avro::{map_record_to_avro, map_to_avro_schema};
+use crate::types::{
+    DozerPoint, Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition,
+};
+
+fn field(name: &str, typ: FieldType, nullable: bool) -> FieldDefinition {
+    FieldDefinition::new(name.to_string(), typ, nullable, SourceDefinition::Dynamic)
+}
+
+#[test]
+fn can_convert_records_to_avro_and_back() {
+    let schema = Schema::empty()
+        .field(field("id", FieldType::Int, false), true)
+        .field(field("name", FieldType::String, true), false)
+        .field(field("price", FieldType::Decimal, false), false)
+        .field(field("created_at", FieldType::Timestamp, true), false)
+        .field(field("day", FieldType::Date, false), false)
+        .field(field("from", FieldType::Point, false), false)
+        .field(field("to", FieldType::Point, true), false)
+        .clone();
+    let records = [
+        Record::new(
+            None,
+            vec![
+                Field::Int(1),
+                Field::String("a".to_string()),
+                Field::Decimal(Decimal::from_str("-12.345").unwrap()),
+                Field::Timestamp(DateTime::parse_from_rfc3339("1960-03-01T12:34:56.789Z").unwrap()),
+                Field::Date(NaiveDate::from_ymd_opt(1960, 3, 1).unwrap()),
+                Field::Point(DozerPoint::from((1.5, -2.0))),
+                Field::Point(DozerPoint::from((0.0, 0.0))),
+            ],
+            None,
+        ),
+        Record::new(
+            None,
+            vec![
+                Field::Int(2),
+                Field::Null,
+                Field::Decimal(Decimal::MAX),
+                Field::Null,
+                Field::Date(NaiveDate::from_ymd_opt(2023, 3, 1).unwrap()),
+                Field::Point(DozerPoint::from((1.5, -2.0))),
+                Field::Null,
+            ],
+            None,
+        ),
+    ];
+
+    let avro_schema = map_to_avro_schema(&schema, "record").unwrap();
+    let dozer_schema = map_schema_to_dozer(&avro_schema).unwrap();
+    assert_eq!(dozer_schema.fields, schema.fields);
+
+    for record in records {
+        let value = map_record_to_avro(&record, &schema).unwrap();
+        let bytes = to_avro_datum(&avro_schema, value).unwrap();
+        let value = from_avro_datum(&avro_schema, &mut bytes.as_slice(), None).unwrap();
+        let result = map_avro_to_dozer_record(&value, &avro_schema).unwrap();
+        assert_eq!(result.values, record.values);
+    }
+}
+
+#[test]
+fn converting_invalid_records_to_avro_fails() {
+    let schema = Schema::empty()
+        .field(field("id", FieldType::UInt, false), true)
+        .clone();
+    for value in [Field::Null, Field::Int(1), Field::UInt(u64::MAX)] {
+        let record = Record::new(None, vec![value], None);
+        assert!(map_record_to_avro(&record, &schema).is_err());
+    }
+}
+
+#[test]
+fn can_convert_avro_logical_types_to_dozer() {
+    let avro_schema = apache_avro::Schema::parse_str(
+        r#"{
+            "type": "record",
+            "name": "test",
+            "fields": [
+                {"name": "amount", "type": {"type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2}},
+                {"name": "at", "type": {"type": "long", "logicalType": "timestamp-micros"}}
+            ]
+        }"#,
+    )
+    .unwrap();
+    let value = apache_avro::types::Value::Record(vec![
+        (
+            "amount".to_string(),
+            apache_avro::types::Value::Decimal(apache_avro::Decimal::from(vec![0xff, 0x85])),
+        ),
+        (
+            "at".to_string(),
+            apache_avro::types::Value::TimestampMicros(1_500_001),
+        ),
+    ]);
+
+    let record = map_avro_to_dozer_record(&value, &avro_schema).unwrap();
+    assert_eq!(
+        record.values,
+        vec![
+            Field::Decimal(Decimal::from_str("-1.23").unwrap()),
+            Field::Timestamp(DateTime::parse_from_rfc3339("1970-01-01T00:00:01.500001Z").unwrap()),
+        ]
+    );
+}
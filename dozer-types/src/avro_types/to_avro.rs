@@ -0,0 +1,160 @@
+use apache_avro::types::Value as AvroValue;
+use apache_avro::Schema as AvroSchema;
+use chrono::NaiveDate;
+use num_bigint::BigInt;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+
+use super::errors::ToAvroError;
+use crate::types::{DozerDecimal128, Field, FieldDefinition, FieldType, Record, Schema};
+
+/// Precision and scale of Avro decimals, enough for every `Decimal` without rounding.
+pub const DECIMAL_PRECISION: u32 = 57;
+pub const DECIMAL_SCALE: u32 = 28;
+/// Precision and scale of Avro decimals of `Decimal128`.
+pub const DECIMAL128_PRECISION: u32 = 2 * DozerDecimal128::MAX_PRECISION as u32;
+pub const DECIMAL128_SCALE: u32 = DozerDecimal128::MAX_PRECISION as u32;
+
+/// Name of the Avro record that points are mapped to.
+pub const POINT_RECORD_NAME: &str = "DozerPoint";
+
+// Maps a Dozer Schema to an Avro record schema named `name`
+//
+// Avro has no unsigned integers, texts, BSON, geometries or nanosecond durations, so `UInt` and `Duration` (in
+// nanoseconds) become `long`, `Text` becomes `string` and `Bson` and `Geometry` (as EWKB) become `bytes`. Timestamps become `timestamp-millis`.
+// Nullable fields are unions of `null` and their type.
+pub fn map_to_avro_schema(schema: &Schema, name: &str) -> Result<AvroSchema, ToAvroError> {
+    let mut point_defined = false;
+    let fields: Vec<Value> = schema
+        .fields
+        .iter()
+        .map(|fd| {
+            let mut typ = map_field_type(fd.typ, &mut point_defined);
+            if fd.nullable {
+                typ = json!(["null", typ]);
+            }
+            json!({ "name": fd.name, "type": typ })
+        })
+        .collect();
+
+    let schema = json!({
+        "type": "record",
+        "name": name,
+        "fields": fields,
+    });
+    Ok(AvroSchema::parse(&schema)?)
+}
+
+// Maps the dozer field type to the JSON of the Avro type
+// Points are only defined the first time and referenced by name afterwards, as Avro requires
+fn map_field_type(typ: FieldType, point_defined: &mut bool) -> Value {
+    match typ {
+        FieldType::UInt | FieldType::Int | FieldType::Duration => json!("long"),
+        FieldType::Float => json!("double"),
+        FieldType::Boolean => json!("boolean"),
+        FieldType::String | FieldType::Text => json!("string"),
+        FieldType::Binary | FieldType::Bson | FieldType::Geometry => json!("bytes"),
+        FieldType::Decimal => json!({
+            "type": "bytes",
+            "logicalType": "decimal",
+            "precision": DECIMAL_PRECISION,
+            "scale": DECIMAL_SCALE,
+        }),
+        FieldType::Decimal128 => json!({
+            "type": "bytes",
+            "logicalType": "decimal",
+            "precision": DECIMAL128_PRECISION,
+            "scale": DECIMAL128_SCALE,
+        }),
+        FieldType::Timestamp => json!({ "type": "long", "logicalType": "timestamp-millis" }),
+        FieldType::Date => json!({ "type": "int", "logicalType": "date" }),
+        FieldType::Point if *point_defined => json!(POINT_RECORD_NAME),
+        FieldType::Point => {
+            *point_defined = true;
+            json!({
+                "type": "record",
+                "name": POINT_RECORD_NAME,
+                "fields": [
+                    { "name": "x", "type": "double" },
+                    { "name": "y", "type": "double" },
+                ],
+            })
+        }
+    }
+}
+
+// Maps a Dozer Record to an Avro record value of the schema from `map_to_avro_schema`
+pub fn map_record_to_avro(record: &Record, schema: &Schema) -> Result<AvroValue, ToAvroError> {
+    if schema.fields.len() != record.values.len() {
+        return Err(ToAvroError::SchemaMismatchError(
+            schema.fields.len(),
+            record.values.len(),
+        ));
+    }
+
+    let mut fields = vec![];
+    for (fd, field) in schema.fields.iter().zip(&record.values) {
+        fields.push((fd.name.clone(), map_field_to_avro(field, fd)?));
+    }
+    Ok(AvroValue::Record(fields))
+}
+
+fn map_field_to_avro(field: &Field, fd: &FieldDefinition) -> Result<AvroValue, ToAvroError> {
+    let out_of_range = || ToAvroError::OutOfRangeError(field.clone());
+    let value = match (field, fd.typ) {
+        (Field::Null, _) if fd.nullable => {
+            return Ok(AvroValue::Union(0, Box::new(AvroValue::Null)))
+        }
+        (Field::UInt(v), FieldType::UInt) => {
+            AvroValue::Long(i64::try_from(*v).map_err(|_| out_of_range())?)
+        }
+        (Field::Int(v), FieldType::Int) => AvroValue::Long(*v),
+        (Field::Float(v), FieldType::Float) => AvroValue::Double(v.0),
+        (Field::Boolean(v), FieldType::Boolean) => AvroValue::Boolean(*v),
+        (Field::String(v), FieldType::String) | (Field::Text(v), FieldType::Text) => {
+            AvroValue::String(v.clone())
+        }
+        (Field::Binary(v), FieldType::Binary) | (Field::Bson(v), FieldType::Bson) => {
+            AvroValue::Bytes(v.clone())
+        }
+        (Field::Decimal(v), FieldType::Decimal) => {
+            AvroValue::Decimal(apache_avro::Decimal::from(decimal_to_bytes(v)))
+        }
+        (Field::Decimal128(v), FieldType::Decimal128) => {
+            AvroValue::Decimal(apache_avro::Decimal::from(decimal128_to_bytes(v)))
+        }
+        (Field::Timestamp(v), FieldType::Timestamp) => {
+            AvroValue::TimestampMillis(v.timestamp_millis())
+        }
+        (Field::Date(v), FieldType::Date) => {
+            let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).expect("Epoch is valid");
+            let days = v.signed_duration_since(epoch).num_days();
+            AvroValue::Date(i32::try_from(days).map_err(|_| out_of_range())?)
+        }
+        (Field::Point(v), FieldType::Point) => AvroValue::Record(vec![
+            ("x".to_string(), AvroValue::Double(v.0.x().0)),
+            ("y".to_string(), AvroValue::Double(v.0.y().0)),
+        ]),
+        (Field::Duration(v), FieldType::Duration) => AvroValue::Long(v.as_nanos()),
+        (Field::Geometry(v), FieldType::Geometry) => AvroValue::Bytes(v.as_bytes().to_vec()),
+        (field, typ) => return Err(ToAvroError::InvalidFieldValue(typ, field.clone())),
+    };
+
+    Ok(if fd.nullable {
+        AvroValue::Union(1, Box::new(value))
+    } else {
+        value
+    })
+}
+
+/// The big-endian two's complement bytes of `decimal` scaled to `DECIMAL_SCALE`.
+fn decimal_to_bytes(decimal: &Decimal) -> Vec<u8> {
+    let factor = BigInt::from(10).pow(DECIMAL_SCALE - decimal.scale());
+    (BigInt::from(decimal.mantissa()) * factor).to_signed_bytes_be()
+}
+
+/// The big-endian two's complement bytes of `decimal` scaled to `DECIMAL128_SCALE`.
+fn decimal128_to_bytes(decimal: &DozerDecimal128) -> Vec<u8> {
+    let factor = BigInt::from(10).pow(DECIMAL128_SCALE - decimal.scale() as u32);
+    (BigInt::from(decimal.mantissa()) * factor).to_signed_bytes_be()
+}
@@ -1,7 +1,7 @@
 use geo::vincenty_distance::FailedToConvergeError;
 use thiserror::Error;
 
-use crate::types::FieldType;
+use crate::types::{ArithmeticOperator, Field, FieldType};
 
 use super::internal::BoxedError;
 
@@ -19,6 +19,8 @@ pub enum TypeError {
         nullable: bool,
         value: String,
     },
+    #[error("Missing value of field: {0}")]
+    MissingFieldValue(String),
     #[error("Invalid timestamp")]
     InvalidTimestamp,
     #[error("Ambiguous timestamp")]
@@ -27,10 +29,60 @@ pub enum TypeError {
     SerializationError(#[source] SerializationError),
     #[error("Failed to parse the field: {0}")]
     DeserializationError(#[source] DeserializationError),
+    #[error("Invalid geometry: {0}")]
+    InvalidGeometry(String),
+    #[error("Invalid decimal128: {0}")]
+    InvalidDecimal128(String),
     #[error("Failed to calculate distance: {0}")]
     DistanceCalculationError(#[source] FailedToConvergeError),
 }
 
+#[derive(Error, Debug)]
+pub enum CastError {
+    #[error("Cannot cast {from} to {to}")]
+    Unsupported { from: FieldType, to: FieldType },
+    #[error("Cannot cast {value:?} to {to}")]
+    InvalidValue { value: String, to: FieldType },
+    #[error("{value} is out of the range of {to}")]
+    OutOfRange { value: String, to: FieldType },
+}
+
+#[derive(Error, Debug)]
+pub enum ArithmeticError {
+    #[error("Cannot apply {op} to {left} and {right}")]
+    UnsupportedTypes {
+        op: ArithmeticOperator,
+        left: Field,
+        right: Field,
+    },
+    #[error("Cannot apply {op} to {operand}")]
+    UnsupportedType {
+        op: ArithmeticOperator,
+        operand: Field,
+    },
+    #[error("Overflow on {left} {op} {right}")]
+    Overflow {
+        op: ArithmeticOperator,
+        left: Field,
+        right: Field,
+    },
+    #[error("Overflow on {op}{operand}")]
+    UnaryOverflow {
+        op: ArithmeticOperator,
+        operand: Field,
+    },
+    #[error("Division by zero on {left} {op} {right}")]
+    DivisionByZero {
+        op: ArithmeticOperator,
+        left: Field,
+        right: Field,
+    },
+    #[error("{value} cannot be represented as {to}")]
+    PrecisionLoss { value: Field, to: FieldType },
+    #[error("Cannot compare {left} and {right}")]
+    Incomparable { left: Field, right: Field },
+}
+
 #[derive(Error, Debug)]
 pub enum SerializationError {
     #[error("json: {0}")]
@@ -55,6 +107,8 @@ pub enum DeserializationError {
     UnrecognisedFieldType(u8),
     #[error("Bad data length")]
     BadDataLength,
+    #[error("Unsupported record format version: {0}")]
+    UnsupportedFormatVersion(u8),
     #[error("Bad data format: {0}")]
     BadDateFormat(#[from] chrono::ParseError),
     #[error("utf8: {0}")]
@@ -1,4 +1,4 @@
-use crate::types::{DozerPoint, Field};
+use crate::types::{DozerDecimal128, DozerPoint, Field};
 use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, Timelike, Utc};
 use geo::Point;
 use ordered_float::OrderedFloat;
@@ -88,6 +88,12 @@ impl From<Decimal> for Field {
     }
 }
 
+impl From<DozerDecimal128> for Field {
+    fn from(value: DozerDecimal128) -> Self {
+        Field::Decimal128(value)
+    }
+}
+
 impl From<NaiveDateTime> for Field {
     fn from(value: NaiveDateTime) -> Self {
         Field::Timestamp(DateTime::from_utc(value, Utc.fix()))
@@ -6,11 +6,13 @@ pub mod types {
 pub mod common {
     #![allow(clippy::derive_partial_eq_without_eq)]
     tonic::include_proto!("dozer.common"); // The string specified here must match the proto package name
+    pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("common");
 }
 pub mod health {
     #![allow(non_camel_case_types)]
     #![allow(clippy::derive_partial_eq_without_eq)]
     tonic::include_proto!("dozer.health"); // The string specified here must match the proto package name
+    pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("health");
 }
 pub mod internal {
     #![allow(clippy::derive_partial_eq_without_eq)]
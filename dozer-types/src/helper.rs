@@ -1,62 +1,161 @@
 use crate::errors::types::{DeserializationError, TypeError};
-use crate::types::{DozerPoint, DATE_FORMAT};
+use crate::types::{
+    DozerDecimal128, DozerDuration, DozerGeometry, DozerPoint, Record, Schema, DATE_FORMAT,
+};
 use crate::types::{Field, FieldType};
-use chrono::{DateTime, NaiveDate};
+use base64::{engine, Engine};
+use chrono::{DateTime, NaiveDate, SecondsFormat};
+use indexmap::IndexMap;
 use ordered_float::OrderedFloat;
 use rust_decimal::Decimal;
 use serde_json::Value;
 use std::str::FromStr;
 
 /// Used in REST APIs and query expressions for converting JSON value to `Field`
+///
+/// Same as `Field::from_json`, but `null` is only accepted if `nullable`.
 pub fn json_value_to_field(
     value: Value,
     typ: FieldType,
     nullable: bool,
 ) -> Result<Field, TypeError> {
-    if nullable {
-        if let Value::Null = value {
-            return Ok(Field::Null);
+    if !nullable && value.is_null() {
+        return Err(TypeError::InvalidFieldValue {
+            field_type: typ,
+            nullable,
+            value: value.to_string(),
+        });
+    }
+    Field::from_json(value, typ)
+}
+
+impl Field {
+    /// Converts a JSON value written by `Field::to_json` to a field of type `typ`.
+    ///
+    /// `null` converts to `Null` for every type. Besides what `to_json` writes, floats also accept strings of numbers,
+    /// decimals also accept numbers, binaries and BSON also accept arrays of bytes and durations also accept nanoseconds.
+    pub fn from_json(value: Value, typ: FieldType) -> Result<Field, TypeError> {
+        let invalid = |value: &Value| TypeError::InvalidFieldValue {
+            field_type: typ,
+            nullable: true,
+            value: value.to_string(),
+        };
+
+        match (typ, &value) {
+            (_, Value::Null) => Ok(Field::Null),
+            (FieldType::String | FieldType::Text, Value::String(str)) => {
+                Field::from_str(str, typ, false)
+            }
+            (
+                FieldType::Float
+                | FieldType::Decimal
+                | FieldType::Timestamp
+                | FieldType::Date
+                | FieldType::Duration
+                | FieldType::Geometry
+                | FieldType::Decimal128,
+                Value::String(str),
+            ) => Field::from_str(str, typ, false),
+            // Parse the number as written, instead of going through `f64`.
+            (FieldType::Decimal | FieldType::Decimal128, Value::Number(number)) => {
+                Field::from_str(&number.to_string(), typ, false)
+            }
+            (FieldType::Binary | FieldType::Bson, Value::String(str)) => {
+                let bytes = engine::general_purpose::STANDARD
+                    .decode(str)
+                    .map_err(|_| invalid(&value))?;
+                Ok(if typ == FieldType::Binary {
+                    Field::Binary(bytes)
+                } else {
+                    Field::Bson(bytes)
+                })
+            }
+            (
+                FieldType::UInt
+                | FieldType::Int
+                | FieldType::Float
+                | FieldType::Boolean
+                | FieldType::Binary
+                | FieldType::Bson
+                | FieldType::Point
+                | FieldType::Duration,
+                _,
+            ) => {
+                let deserialize = |value| -> Result<Field, serde_json::Error> {
+                    Ok(match typ {
+                        FieldType::UInt => Field::UInt(serde_json::from_value(value)?),
+                        FieldType::Int => Field::Int(serde_json::from_value(value)?),
+                        FieldType::Float => Field::Float(serde_json::from_value(value)?),
+                        FieldType::Boolean => Field::Boolean(serde_json::from_value(value)?),
+                        FieldType::Binary => Field::Binary(serde_json::from_value(value)?),
+                        FieldType::Bson => Field::Bson(serde_json::from_value(value)?),
+                        FieldType::Point => Field::Point(serde_json::from_value(value)?),
+                        FieldType::Duration => Field::Duration(serde_json::from_value(value)?),
+                        _ => unreachable!("Matched above"),
+                    })
+                };
+                deserialize(value)
+                    .map_err(|e| TypeError::DeserializationError(DeserializationError::Json(e)))
+            }
+            _ => Err(invalid(&value)),
+        }
+    }
+
+    /// Converts the field to JSON, the inverse of `Field::from_json`.
+    ///
+    /// | Field | JSON |
+    /// |---|---|
+    /// | `UInt`, `Int` | Number |
+    /// | `Float` | Number, or `"NaN"`, `"inf"` or `"-inf"` which JSON numbers can't represent |
+    /// | `Boolean` | Boolean |
+    /// | `String`, `Text` | String |
+    /// | `Binary`, `Bson` | Base64 string |
+    /// | `Decimal`, `Decimal128` | String, so no precision is lost |
+    /// | `Timestamp` | RFC 3339 string with as many fractional digits as needed |
+    /// | `Date` | `%Y-%m-%d` string |
+    /// | `Point` | `{"x": x, "y": y}` |
+    /// | `Duration` | String, see `DozerDuration` |
+    /// | `Geometry` | Hexadecimal EWKB string, as PostGIS prints it |
+    /// | `Null` | `null` |
+    pub fn to_json(&self) -> Value {
+        match self {
+            Field::UInt(n) => Value::from(*n),
+            Field::Int(n) => Value::from(*n),
+            Field::Float(n) if n.is_finite() => Value::from(n.0),
+            Field::Float(n) => Value::String(n.to_string()),
+            Field::Boolean(b) => Value::from(*b),
+            Field::String(s) | Field::Text(s) => Value::from(s.as_str()),
+            Field::Binary(b) | Field::Bson(b) => {
+                Value::String(engine::general_purpose::STANDARD.encode(b))
+            }
+            Field::Decimal(n) => Value::String(n.to_string()),
+            Field::Decimal128(n) => Value::String(n.to_string()),
+            Field::Timestamp(ts) => Value::String(ts.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+            Field::Date(n) => Value::String(n.format(DATE_FORMAT).to_string()),
+            Field::Point(point) => {
+                let (x, y) = point.0.x_y();
+                let mut map = serde_json::Map::new();
+                map.insert("x".to_string(), Value::from(x.0));
+                map.insert("y".to_string(), Value::from(y.0));
+                Value::Object(map)
+            }
+            Field::Duration(d) => Value::String(d.to_string()),
+            Field::Geometry(g) => Value::String(g.to_string()),
+            Field::Null => Value::Null,
         }
     }
+}
 
-    match (typ, &value) {
-        (FieldType::UInt, _) => serde_json::from_value(value)
-            .map_err(DeserializationError::Json)
-            .map(Field::UInt),
-        (FieldType::Int, _) => serde_json::from_value(value)
-            .map_err(DeserializationError::Json)
-            .map(Field::Int),
-        (FieldType::Float, _) => serde_json::from_value(value)
-            .map_err(DeserializationError::Json)
-            .map(Field::Float),
-        (FieldType::Boolean, _) => serde_json::from_value(value)
-            .map_err(DeserializationError::Json)
-            .map(Field::Boolean),
-        (FieldType::String, _) => serde_json::from_value(value)
-            .map_err(DeserializationError::Json)
-            .map(Field::String),
-        (FieldType::Text, _) => serde_json::from_value(value)
-            .map_err(DeserializationError::Json)
-            .map(Field::Text),
-        (FieldType::Binary, _) => serde_json::from_value(value)
-            .map_err(DeserializationError::Json)
-            .map(Field::Binary),
-        (FieldType::Decimal, Value::String(str)) => return Field::from_str(str, typ, nullable),
-        (FieldType::Timestamp, Value::String(str)) => return Field::from_str(str, typ, nullable),
-        (FieldType::Date, Value::String(str)) => return Field::from_str(str, typ, nullable),
-        (FieldType::Bson, _) => serde_json::from_value(value)
-            .map_err(DeserializationError::Json)
-            .map(Field::Bson),
-        (FieldType::Point, _) => serde_json::from_value(value)
-            .map_err(DeserializationError::Json)
-            .map(Field::Point),
-        _ => Err(DeserializationError::Custom(
-            "Json value type does not match field type"
-                .to_string()
-                .into(),
-        )),
+impl Record {
+    /// Converts the record to a JSON object with the fields of `schema`, in order, see `Field::to_json`.
+    pub fn to_json(&self, schema: &Schema) -> IndexMap<String, Value> {
+        schema
+            .fields
+            .iter()
+            .zip(&self.values)
+            .map(|(field_def, field)| (field_def.name.clone(), field.to_json()))
+            .collect()
     }
-    .map_err(TypeError::DeserializationError)
 }
 
 impl Field {
@@ -133,13 +232,14 @@ impl Field {
                 if nullable && (value.is_empty() || value == "null") {
                     Ok(Field::Null)
                 } else {
-                    Decimal::from_str(value).map(Field::Decimal).map_err(|_| {
-                        TypeError::InvalidFieldValue {
+                    Decimal::from_str(value)
+                        .or_else(|_| Decimal::from_scientific(value))
+                        .map(Field::Decimal)
+                        .map_err(|_| TypeError::InvalidFieldValue {
                             field_type: typ,
                             nullable,
                             value: value.to_string(),
-                        }
-                    })
+                        })
                 }
             }
             FieldType::Timestamp => {
@@ -186,18 +286,165 @@ impl Field {
                     value.parse::<DozerPoint>().map(Field::Point)
                 }
             }
+            FieldType::Duration => {
+                if nullable && (value.is_empty() || value == "null") {
+                    Ok(Field::Null)
+                } else {
+                    value.parse::<DozerDuration>().map(Field::Duration)
+                }
+            }
+            FieldType::Geometry => {
+                if nullable && (value.is_empty() || value == "null") {
+                    Ok(Field::Null)
+                } else {
+                    value.parse::<DozerGeometry>().map(Field::Geometry)
+                }
+            }
+            FieldType::Decimal128 => {
+                if nullable && (value.is_empty() || value == "null") {
+                    Ok(Field::Null)
+                } else {
+                    value.parse::<DozerDecimal128>().map(Field::Decimal128)
+                }
+            }
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use chrono::FixedOffset;
+    use chrono::{FixedOffset, Offset, TimeZone, Utc};
     use geo::Point;
     use rust_decimal::prelude::FromPrimitive;
+    use serde_json::json;
+
+    use crate::types::{FieldDefinition, SourceDefinition};
 
     use super::*;
 
+    #[test]
+    fn test_field_types_json_conversion() {
+        let fields = vec![
+            (FieldType::Int, Field::Int(-1)),
+            (FieldType::UInt, Field::UInt(1)),
+            (FieldType::Float, Field::Float(OrderedFloat(1.1))),
+            (
+                FieldType::Float,
+                Field::Float(OrderedFloat(f64::NEG_INFINITY)),
+            ),
+            (FieldType::Boolean, Field::Boolean(true)),
+            (FieldType::String, Field::String("a".to_string())),
+            (FieldType::Binary, Field::Binary(b"asdf".to_vec())),
+            (FieldType::Decimal, Field::Decimal(Decimal::new(202, 2))),
+            (
+                FieldType::Timestamp,
+                Field::Timestamp(Utc.fix().with_ymd_and_hms(2001, 1, 1, 0, 4, 0).unwrap()),
+            ),
+            (
+                FieldType::Timestamp,
+                Field::Timestamp(
+                    DateTime::parse_from_rfc3339("2001-01-01T00:04:00.123456789+08:00").unwrap(),
+                ),
+            ),
+            (
+                FieldType::Date,
+                Field::Date(NaiveDate::from_ymd_opt(2022, 11, 24).unwrap()),
+            ),
+            (
+                FieldType::Bson,
+                Field::Bson(vec![
+                    // BSON representation of `{"abc":"foo"}`
+                    123, 34, 97, 98, 99, 34, 58, 34, 102, 111, 111, 34, 125,
+                ]),
+            ),
+            (FieldType::Text, Field::Text("lorem ipsum".to_string())),
+            (
+                FieldType::Point,
+                Field::Point(DozerPoint::from((3.234, 4.567))),
+            ),
+            (
+                FieldType::Duration,
+                Field::Duration(DozerDuration::from_nanos(-1_500_000)),
+            ),
+            (FieldType::Int, Field::Null),
+        ];
+        for (field_type, field) in fields {
+            let value = field.to_json();
+            assert_eq!(Field::from_json(value, field_type).unwrap(), field);
+        }
+    }
+
+    #[test]
+    fn test_field_to_json() {
+        assert_eq!(Field::Binary(b"asdf".to_vec()).to_json(), json!("YXNkZg=="));
+        assert_eq!(
+            Field::Decimal(Decimal::from_str("0.10").unwrap()).to_json(),
+            json!("0.10")
+        );
+        assert_eq!(
+            Field::Timestamp(
+                DateTime::parse_from_rfc3339("2001-01-01T00:04:00.120+08:00").unwrap()
+            )
+            .to_json(),
+            json!("2001-01-01T00:04:00.120+08:00")
+        );
+        assert_eq!(Field::Float(OrderedFloat(f64::NAN)).to_json(), json!("NaN"));
+        assert_eq!(
+            Field::Point(DozerPoint::from((1.0, 2.0))).to_json(),
+            json!({"x": 1.0, "y": 2.0})
+        );
+    }
+
+    #[test]
+    fn test_field_from_json() {
+        assert_eq!(
+            Field::from_json(json!([97, 98]), FieldType::Binary).unwrap(),
+            Field::Binary(b"ab".to_vec())
+        );
+        assert_eq!(
+            Field::from_json(json!(0.1), FieldType::Decimal).unwrap(),
+            Field::Decimal(Decimal::from_str("0.1").unwrap())
+        );
+        assert_eq!(
+            Field::from_json(json!(null), FieldType::String).unwrap(),
+            Field::Null
+        );
+        assert!(Field::from_json(json!("not base64!"), FieldType::Binary).is_err());
+        assert!(Field::from_json(json!(1), FieldType::String).is_err());
+        assert!(Field::from_json(json!(1), FieldType::Timestamp).is_err());
+        assert!(json_value_to_field(json!(null), FieldType::String, false).is_err());
+    }
+
+    #[test]
+    fn test_record_to_json() {
+        let schema = Schema::empty()
+            .field(
+                FieldDefinition::new(
+                    "b".to_string(),
+                    FieldType::Int,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                true,
+            )
+            .field(
+                FieldDefinition::new(
+                    "a".to_string(),
+                    FieldType::String,
+                    true,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            )
+            .clone();
+        let record = Record::new(None, vec![Field::Int(1), Field::Null], None);
+        let json = record.to_json(&schema);
+        assert_eq!(
+            json.into_iter().collect::<Vec<_>>(),
+            vec![("b".to_string(), json!(1)), ("a".to_string(), Value::Null)]
+        );
+    }
+
     #[test]
     fn test_field_from_str() {
         let ok_cases = [
@@ -246,6 +493,12 @@ mod tests {
                 false,
                 Field::Point(DozerPoint(Point::new(OrderedFloat(1.0), OrderedFloat(1.0)))),
             ),
+            (
+                "1h 30m",
+                FieldType::Duration,
+                false,
+                Field::Duration(DozerDuration::from_secs(90 * 60).unwrap()),
+            ),
             ("null", FieldType::UInt, true, Field::Null),
             ("null", FieldType::Int, true, Field::Null),
             ("null", FieldType::Float, true, Field::Null),
@@ -268,6 +521,7 @@ mod tests {
             ("null", FieldType::Date, true, Field::Null),
             ("null", FieldType::Bson, true, Field::Null),
             ("null", FieldType::Point, true, Field::Null),
+            ("null", FieldType::Duration, true, Field::Null),
             ("", FieldType::UInt, true, Field::Null),
             ("", FieldType::Int, true, Field::Null),
             ("", FieldType::Float, true, Field::Null),
@@ -280,6 +534,7 @@ mod tests {
             ("", FieldType::Date, true, Field::Null),
             ("", FieldType::Bson, true, Field::Null),
             ("", FieldType::Point, true, Field::Null),
+            ("", FieldType::Duration, true, Field::Null),
         ];
 
         for case in ok_cases {
@@ -297,6 +552,7 @@ mod tests {
             ("null", FieldType::Date, false),
             ("null", FieldType::Bson, false),
             ("null", FieldType::Point, false),
+            ("null", FieldType::Duration, false),
             ("", FieldType::UInt, false),
             ("", FieldType::Int, false),
             ("", FieldType::Float, false),
@@ -307,6 +563,7 @@ mod tests {
             ("", FieldType::Date, false),
             ("", FieldType::Bson, false),
             ("", FieldType::Point, false),
+            ("", FieldType::Duration, false),
         ];
         for err_case in err_cases {
             assert!(Field::from_str(err_case.0, err_case.1, err_case.2).is_err());
@@ -10,12 +10,15 @@ pub mod types;
 
 // Export Arrow functionality
 pub mod arrow_types;
+// Export Avro functionality
+pub mod avro_types;
 // Export grpc types
 pub mod grpc_types;
 
 pub use helper::json_value_to_field;
 
 // Re-exports
+pub use apache_avro;
 pub use arrow;
 pub use bincode;
 pub use bytes;
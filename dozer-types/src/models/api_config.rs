@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Message)]
 #[serde(default = "default_api_config")]
 pub struct ApiConfig {
-    #[prost(oneof = "ApiSecurity", tags = "1")]
+    #[prost(oneof = "ApiSecurity", tags = "1,5")]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// The security configuration for the API; Default: None
     pub api_security: Option<ApiSecurity>,
@@ -18,6 +18,26 @@ pub struct ApiConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default = "default_app_grpc")]
     pub app_grpc: Option<GrpcApiOptions>,
+
+    #[prost(message, tag = "6")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Rate limits and a cap on concurrent cache reads; Default: None
+    pub rate_limit: Option<RateLimitConfig>,
+}
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Message)]
+pub struct RateLimitConfig {
+    #[prost(uint32, optional, tag = "1")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Requests per second of every token; Default: None
+    pub per_token: Option<u32>,
+    #[prost(uint32, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Requests per second of every endpoint; Default: None
+    pub per_endpoint: Option<u32>,
+    #[prost(uint32, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Cache reads in progress at the same time, across endpoints, so clients can't use up the LMDB reader slots; Default: None
+    pub max_concurrent_reads: Option<u32>,
 }
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, prost::Message)]
 pub struct RestApiOptions {
@@ -99,5 +119,6 @@ pub fn default_api_config() -> ApiConfig {
         grpc: default_api_grpc(),
         app_grpc: default_app_grpc(),
         api_security: None,
+        rate_limit: None,
     }
 }
@@ -5,4 +5,41 @@ pub enum ApiSecurity {
     /// Initialize with a JWT_SECRET
     #[prost(string, tag = "1")]
     Jwt(String),
+    /// Static API keys, sent as bearer tokens
+    #[prost(message, tag = "5")]
+    ApiKeys(ApiKeys),
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+pub struct ApiKeys {
+    #[prost(message, repeated, tag = "1")]
+    pub keys: Vec<ApiKey>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+pub struct ApiKey {
+    #[prost(string, tag = "1")]
+    pub key: String,
+    #[prost(message, repeated, tag = "2")]
+    #[serde(default)]
+    /// The endpoints that the key may query; Default: all of them
+    pub endpoints: Vec<ApiKeyEndpoint>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+pub struct ApiKeyEndpoint {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, repeated, tag = "2")]
+    #[serde(default)]
+    /// The fields that queries of the key may filter on; Default: all of them
+    pub filter_fields: Vec<String>,
+    #[prost(string, optional, tag = "3")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// A filter expression in JSON, like `$filter`, that every query of the key must satisfy; Default: None
+    pub filter: Option<String>,
+    #[prost(string, repeated, tag = "4")]
+    #[serde(default)]
+    /// The fields whose values the key can't see, returned as null; Default: None
+    pub masked_fields: Vec<String>,
 }
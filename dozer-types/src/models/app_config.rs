@@ -1,6 +1,6 @@
 use super::{
     api_config::ApiConfig, api_endpoint::ApiEndpoint, connection::Connection, flags::Flags,
-    source::Source,
+    source::Source, telemetry::TelemetryConfig,
 };
 use crate::{constants::DEFAULT_HOME_DIR, models::api_config::default_api_config};
 use serde::{
@@ -71,6 +71,11 @@ pub struct Config {
     #[prost(uint64, optional, tag = "14")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub commit_timeout: Option<u64>,
+
+    #[prost(message, tag = "15")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// tracing and logs: service name, exporter, sampling, log format, etc
+    pub telemetry: Option<TelemetryConfig>,
 }
 
 pub fn default_home_dir() -> String {
@@ -130,6 +135,7 @@ impl<'de> Deserialize<'de> for Config {
                 let mut app_buffer_size: Option<u32> = Some(default_app_buffer_size());
                 let mut commit_size: Option<u32> = Some(default_commit_size());
                 let mut commit_timeout: Option<u64> = Some(default_commit_timeout());
+                let mut telemetry: Option<TelemetryConfig> = None;
 
                 while let Some(key) = access.next_key()? {
                     match key {
@@ -172,6 +178,9 @@ impl<'de> Deserialize<'de> for Config {
                         "commit_timeout" => {
                             commit_timeout = access.next_value::<Option<u64>>()?;
                         }
+                        "telemetry" => {
+                            telemetry = access.next_value::<Option<TelemetryConfig>>()?;
+                        }
                         _ => {
                             access.next_value::<IgnoredAny>()?;
                         }
@@ -242,6 +251,7 @@ impl<'de> Deserialize<'de> for Config {
                     app_buffer_size,
                     commit_size,
                     commit_timeout,
+                    telemetry,
                 })
             }
         }
@@ -0,0 +1,308 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, ::prost::Message)]
+#[serde(default)]
+/// The configuration for tracing and logs
+pub struct TelemetryConfig {
+    #[prost(string, tag = "1", default = "dozer")]
+    /// name of the service in traces; Default: dozer
+    pub service_name: String,
+
+    #[prost(oneof = "TelemetryExporter", tags = "2,3,20")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// where to export traces, `exporters` adds more; Default: None, traces are not exported
+    pub exporter: Option<TelemetryExporter>,
+
+    #[prost(double, tag = "4", default = 1.0)]
+    /// ratio of traces to sample, between 0 and 1, when `sampler` is not set; Default: 1
+    pub sampling_ratio: f64,
+
+    #[prost(btree_map = "string, string", tag = "5")]
+    /// attributes of the resource in traces, such as `deployment.environment`; Default: none
+    pub resource_attributes: BTreeMap<String, String>,
+
+    #[prost(oneof = "LogFormat", tags = "6,7,9")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// format of the logs written to stdout; Default: Full
+    pub log_format: Option<LogFormat>,
+
+    #[prost(message, tag = "8")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// how spans are batched for export to Jaeger or Datadog; Default: see `BatchExportConfig`
+    pub batch_export: Option<BatchExportConfig>,
+
+    #[prost(message, tag = "10")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// also write the logs to rotated files; Default: None, logs are only written to stdout
+    pub file: Option<FileLogConfig>,
+
+    #[prost(string, tag = "11", default = "info")]
+    /// levels of the logs, in the format of `RUST_LOG` such as `info,dozer_storage=warn,dozer_cache::query=debug`;
+    /// `RUST_LOG` is applied on top; Default: info
+    pub log_filter: String,
+
+    #[prost(oneof = "TelemetrySampler", tags = "12,13,14,15")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// which traces to sample; Default: None, a ratio of `sampling_ratio`
+    pub sampler: Option<TelemetrySampler>,
+
+    #[prost(bool, tag = "16", default = true)]
+    /// follow the sampling decision of the parent span, such as the span of an API caller, instead of `sampler`;
+    /// Default: true
+    pub parent_based: bool,
+
+    #[prost(uint64, tag = "17", default = 30)]
+    /// seconds between two reads of the LMDB gauges, such as how full the cache is, 0 disables them; Default: 30
+    pub lmdb_gauges_interval_secs: u64,
+
+    #[prost(message, tag = "18")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// serve tokio-console, if dozer was built with the `tokio-console` feature; Default: None
+    pub tokio_console: Option<TokioConsoleConfig>,
+
+    #[prost(string, repeated, tag = "19")]
+    /// names of the fields of spans and logs whose values are masked, such as `password` or `ssn`, in any case;
+    /// Default: none
+    pub redacted_fields: Vec<String>,
+
+    #[prost(message, tag = "21")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// send error logs, with the warning and info logs before them, and panics to Sentry; Default: None
+    pub sentry: Option<SentryConfig>,
+
+    #[prost(message, repeated, tag = "22")]
+    /// more places to export the same traces to, such as both Jaeger and stdout; Default: none
+    pub exporters: Vec<ExporterConfig>,
+
+    #[prost(string, optional, tag = "23")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// levels of the logs written to stdout, replacing `log_filter`; Default: None, `log_filter`
+    pub stdout_log_filter: Option<String>,
+
+    #[prost(string, optional, tag = "24")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// levels of the spans and events exported as traces, replacing `log_filter`; Default: None, `log_filter`
+    pub trace_filter: Option<String>,
+
+    #[prost(uint64, optional, tag = "25")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// log the cache reads and commits that take longer than this, in milliseconds, at warn with their query, if
+    /// dozer was built with the `instrument` feature; Default: None
+    pub slow_query_threshold_ms: Option<u64>,
+}
+
+// `sampling_ratio` is never NaN in a valid config.
+impl Eq for TelemetryConfig {}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Oneof)]
+pub enum TelemetryExporter {
+    #[prost(message, tag = "2")]
+    /// In yaml, present as tag: `!Jaeger`
+    Jaeger(JaegerConfig),
+    #[prost(message, tag = "3")]
+    /// In yaml, present as tag: `!Stdout`
+    Stdout(StdoutExporterConfig),
+    #[prost(message, tag = "20")]
+    /// In yaml, present as tag: `!Datadog`
+    Datadog(DatadogConfig),
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct ExporterConfig {
+    #[prost(oneof = "TelemetryExporter", tags = "2,3,20")]
+    /// where to export traces
+    pub exporter: Option<TelemetryExporter>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct JaegerConfig {
+    #[prost(string, optional, tag = "1")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// address of the Jaeger agent; Default: 127.0.0.1:6831
+    pub endpoint: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct StdoutExporterConfig {}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct DatadogConfig {
+    #[prost(string, optional, tag = "1")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// address of the Datadog agent; Default: http://127.0.0.1:8126
+    pub agent_endpoint: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// `env` tag of the spans, such as `production`; Default: None
+    pub env: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// `version` tag of the spans, such as the version of the deployment; Default: None
+    pub version: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Oneof)]
+pub enum TelemetrySampler {
+    #[prost(message, tag = "12")]
+    /// Samples every trace. In yaml, present as tag: `!AlwaysOn`
+    AlwaysOn(AlwaysOnSamplerConfig),
+    #[prost(message, tag = "13")]
+    /// Samples no trace. In yaml, present as tag: `!AlwaysOff`
+    AlwaysOff(AlwaysOffSamplerConfig),
+    #[prost(message, tag = "14")]
+    /// Samples a ratio of the traces by their trace id. In yaml, present as tag: `!Ratio`
+    Ratio(RatioSamplerConfig),
+    #[prost(message, tag = "15")]
+    /// Samples up to a number of traces per second for every root span name, such as every API endpoint. In yaml,
+    /// present as tag: `!RateLimited`
+    RateLimited(RateLimitedSamplerConfig),
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct AlwaysOnSamplerConfig {}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct AlwaysOffSamplerConfig {}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, ::prost::Message)]
+pub struct RatioSamplerConfig {
+    #[prost(double, tag = "1")]
+    /// ratio of traces to sample, between 0 and 1
+    pub ratio: f64,
+}
+
+// `ratio` is never NaN in a valid config.
+impl Eq for RatioSamplerConfig {}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, ::prost::Message)]
+pub struct RateLimitedSamplerConfig {
+    #[prost(double, tag = "1")]
+    /// traces to sample per second for every root span name
+    pub traces_per_second: f64,
+}
+
+// `traces_per_second` is never NaN in a valid config.
+impl Eq for RateLimitedSamplerConfig {}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+#[serde(default)]
+pub struct BatchExportConfig {
+    #[prost(oneof = "BatchExportRuntime", tags = "1,2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// runtime that exports the batches; Default: TokioCurrentThread
+    pub runtime: Option<BatchExportRuntime>,
+    #[prost(uint32, tag = "3", default = 2048)]
+    /// maximum number of spans waiting for export, after which spans are dropped; Default: 2048
+    pub max_queue_size: u32,
+    #[prost(uint64, tag = "4", default = 5000)]
+    /// delay between two exports, in milliseconds; Default: 5000
+    pub scheduled_delay_ms: u64,
+    #[prost(uint32, tag = "5", default = 512)]
+    /// maximum number of spans in an export; Default: 512
+    pub max_export_batch_size: u32,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Oneof)]
+pub enum BatchExportRuntime {
+    #[prost(message, tag = "1")]
+    /// The Tokio runtime that dozer initializes telemetry on. In yaml, present as tag: `!Tokio`
+    Tokio(TokioRuntime),
+    #[prost(message, tag = "2")]
+    /// A Tokio runtime on a thread of its own. In yaml, present as tag: `!TokioCurrentThread`
+    TokioCurrentThread(TokioCurrentThreadRuntime),
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct TokioRuntime {}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct TokioCurrentThreadRuntime {}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct SentryConfig {
+    #[prost(string, tag = "1")]
+    /// DSN of the Sentry project
+    pub dsn: String,
+    #[prost(string, optional, tag = "2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// environment of the events, such as `production`; Default: None
+    pub environment: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// release of the events; Default: the version of dozer
+    pub release: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct TokioConsoleConfig {
+    #[prost(string, optional, tag = "1")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// address that tokio-console connects to; Default: 127.0.0.1:6669
+    pub server_addr: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+#[serde(default)]
+pub struct FileLogConfig {
+    #[prost(string, tag = "1", default = "./log")]
+    /// directory of the log files; Default: ./log
+    pub directory: String,
+    #[prost(string, tag = "2", default = "dozer.log")]
+    /// name of the current log file, rotated files get a timestamp suffix; Default: dozer.log
+    pub file_name: String,
+    #[prost(oneof = "LogRotationPeriod", tags = "3,4")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// rotate the file at the start of every period, in UTC; Default: None
+    pub rotation_period: Option<LogRotationPeriod>,
+    #[prost(uint64, optional, tag = "5")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// rotate the file before it grows over this size, in megabytes; Default: None
+    pub max_file_size_mb: Option<u64>,
+    #[prost(uint32, tag = "6", default = 7)]
+    /// number of rotated files to keep, older files are deleted; Default: 7
+    pub max_files: u32,
+    #[prost(string, optional, tag = "7")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// levels of the logs written to the files, replacing `log_filter`; Default: None, `log_filter`
+    pub log_filter: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Oneof)]
+pub enum LogRotationPeriod {
+    #[prost(message, tag = "3")]
+    /// In yaml, present as tag: `!Hourly`
+    Hourly(HourlyRotation),
+    #[prost(message, tag = "4")]
+    /// In yaml, present as tag: `!Daily`
+    Daily(DailyRotation),
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct HourlyRotation {}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct DailyRotation {}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Oneof)]
+pub enum LogFormat {
+    #[prost(message, tag = "6")]
+    /// In yaml, present as tag: `!Full`
+    Full(FullLogFormat),
+    #[prost(message, tag = "7")]
+    /// In yaml, present as tag: `!Compact`
+    Compact(CompactLogFormat),
+    #[prost(message, tag = "9")]
+    /// JSON lines, with the fields of the event and its spans, for log pipelines. In yaml, present as tag: `!Json`
+    Json(JsonLogFormat),
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct FullLogFormat {}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct CompactLogFormat {}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct JsonLogFormat {}
@@ -4,6 +4,8 @@ use std::{
     str::from_utf8,
 };
 
+use crate::errors::types::DeserializationError;
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct NodeHandle {
     pub ns: Option<u16>,
@@ -59,17 +61,43 @@ impl Display for NodeHandle {
     }
 }
 
+/// What the source reports about the transaction of an operation, so a checkpoint can resume the source exactly.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct SourceTransaction {
+    /// Id of the transaction in the source, such as the `xid` of PostgreSQL.
+    pub id: Option<u64>,
+    /// Log sequence number of the commit of the transaction.
+    pub commit_lsn: Option<u64>,
+    /// Commit time, in microseconds since the Unix epoch.
+    pub commit_timestamp: Option<i64>,
+}
+
 #[derive(Clone, Debug, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct OpIdentifier {
     pub txid: u64,
     pub seq_in_tx: u64,
+    pub transaction: Option<SourceTransaction>,
 }
 
+const HAS_TRANSACTION_ID: u8 = 1;
+const HAS_COMMIT_LSN: u8 = 1 << 1;
+const HAS_COMMIT_TIMESTAMP: u8 = 1 << 2;
+
 impl OpIdentifier {
     pub fn new(txid: u64, seq_in_tx: u64) -> Self {
-        Self { txid, seq_in_tx }
+        Self {
+            txid,
+            seq_in_tx,
+            transaction: None,
+        }
+    }
+
+    pub fn with_transaction(mut self, transaction: SourceTransaction) -> Self {
+        self.transaction = Some(transaction);
+        self
     }
 
+    /// `txid` and `seq_in_tx` in big-endian, without `transaction`.
     pub fn to_bytes(&self) -> [u8; 16] {
         let mut result = [0_u8; 16];
         result[0..8].copy_from_slice(&self.txid.to_be_bytes());
@@ -82,6 +110,64 @@ impl OpIdentifier {
         let seq_in_tx = u64::from_be_bytes(bytes[8..16].try_into().unwrap());
         Self::new(txid, seq_in_tx)
     }
+
+    /// `to_bytes`, followed by `transaction` if there is one: a byte of which of its values are present, then those
+    /// values in big-endian.
+    pub fn to_bytes_with_transaction(&self) -> Vec<u8> {
+        let mut result = self.to_bytes().to_vec();
+        if let Some(transaction) = self.transaction {
+            let mut flags = 0;
+            let mut values = vec![];
+            if let Some(id) = transaction.id {
+                flags |= HAS_TRANSACTION_ID;
+                values.extend_from_slice(&id.to_be_bytes());
+            }
+            if let Some(commit_lsn) = transaction.commit_lsn {
+                flags |= HAS_COMMIT_LSN;
+                values.extend_from_slice(&commit_lsn.to_be_bytes());
+            }
+            if let Some(commit_timestamp) = transaction.commit_timestamp {
+                flags |= HAS_COMMIT_TIMESTAMP;
+                values.extend_from_slice(&commit_timestamp.to_be_bytes());
+            }
+            result.push(flags);
+            result.extend_from_slice(&values);
+        }
+        result
+    }
+
+    /// Decodes `to_bytes_with_transaction`, or `to_bytes` as stored before `transaction` was added.
+    pub fn from_bytes_with_transaction(bytes: &[u8]) -> Result<Self, DeserializationError> {
+        if bytes.len() < 16 {
+            return Err(DeserializationError::BadDataLength);
+        }
+        let (position, rest) = bytes.split_at(16);
+        let op_id = Self::from_bytes(position.try_into().expect("Split 16 bytes"));
+        let Some((&flags, mut rest)) = rest.split_first() else {
+            return Ok(op_id);
+        };
+
+        let mut take = |flag: u8| -> Result<Option<[u8; 8]>, DeserializationError> {
+            if flags & flag == 0 {
+                return Ok(None);
+            }
+            if rest.len() < 8 {
+                return Err(DeserializationError::BadDataLength);
+            }
+            let (value, remaining) = rest.split_at(8);
+            rest = remaining;
+            Ok(Some(value.try_into().expect("Split 8 bytes")))
+        };
+        let transaction = SourceTransaction {
+            id: take(HAS_TRANSACTION_ID)?.map(u64::from_be_bytes),
+            commit_lsn: take(HAS_COMMIT_LSN)?.map(u64::from_be_bytes),
+            commit_timestamp: take(HAS_COMMIT_TIMESTAMP)?.map(i64::from_be_bytes),
+        };
+        if !rest.is_empty() {
+            return Err(DeserializationError::BadDataLength);
+        }
+        Ok(op_id.with_transaction(transaction))
+    }
 }
 
 pub type SourceStates = HashMap<NodeHandle, OpIdentifier>;
@@ -98,3 +184,42 @@ fn test_handle_to_from_bytes() {
 
     assert_eq!(original, decoded)
 }
+
+#[test]
+fn test_op_identifier_to_from_bytes_with_transaction() {
+    let op_id = OpIdentifier::new(10, 2);
+    assert_eq!(op_id.to_bytes_with_transaction(), op_id.to_bytes());
+    assert_eq!(
+        OpIdentifier::from_bytes_with_transaction(&op_id.to_bytes()).unwrap(),
+        op_id
+    );
+
+    for transaction in [
+        SourceTransaction::default(),
+        SourceTransaction {
+            id: Some(42),
+            commit_lsn: None,
+            commit_timestamp: Some(-1),
+        },
+        SourceTransaction {
+            id: Some(42),
+            commit_lsn: Some(u64::MAX),
+            commit_timestamp: Some(1_680_000_000_000_000),
+        },
+    ] {
+        let op_id = op_id.with_transaction(transaction);
+        let bytes = op_id.to_bytes_with_transaction();
+        assert_eq!(bytes[..16], op_id.to_bytes());
+        let decoded = OpIdentifier::from_bytes_with_transaction(&bytes).unwrap();
+        assert_eq!(decoded.transaction, Some(transaction));
+    }
+
+    let bytes = op_id
+        .with_transaction(SourceTransaction {
+            id: Some(42),
+            ..Default::default()
+        })
+        .to_bytes_with_transaction();
+    assert!(OpIdentifier::from_bytes_with_transaction(&bytes[..bytes.len() - 1]).is_err());
+    assert!(OpIdentifier::from_bytes_with_transaction(&bytes[..15]).is_err());
+}
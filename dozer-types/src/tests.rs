@@ -1,12 +1,50 @@
 #[cfg(test)]
 mod api_config_yaml_deserialize;
 #[cfg(test)]
+mod arithmetic_test;
+#[cfg(test)]
+mod bson_canonical_test;
+#[cfg(test)]
+mod cast_test;
+#[cfg(test)]
+mod collation_test;
+#[cfg(test)]
+mod decimal128_test;
+#[cfg(test)]
 mod dozer_yaml_deserialize;
 #[cfg(test)]
+mod duration_test;
+#[cfg(test)]
 mod eth_yaml_deserialize;
 #[cfg(test)]
 mod field_serialize_test;
 #[cfg(test)]
+mod field_text_test;
+#[cfg(test)]
 mod flags_config_yaml_deserialize;
 #[cfg(test)]
+mod geometry_test;
+#[cfg(test)]
+mod heap_size_test;
+#[cfg(test)]
+mod legacy_schema_test;
+#[cfg(test)]
+mod point_test;
+#[cfg(test)]
 mod postgres_yaml_deserialize;
+#[cfg(test)]
+mod record_builder_test;
+#[cfg(test)]
+mod record_encoding_test;
+#[cfg(test)]
+mod record_hash_test;
+#[cfg(test)]
+mod schema_diff_test;
+#[cfg(test)]
+mod schema_export_test;
+#[cfg(test)]
+mod sql_literal_test;
+#[cfg(test)]
+mod telemetry_config_yaml_deserialize;
+#[cfg(test)]
+mod update_delta_test;
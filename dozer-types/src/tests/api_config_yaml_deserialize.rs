@@ -1,8 +1,9 @@
 use crate::models::{
     api_config::{
-        default_api_grpc, default_api_rest, default_app_grpc, GrpcApiOptions, RestApiOptions,
+        default_api_grpc, default_api_rest, default_app_grpc, GrpcApiOptions, RateLimitConfig,
+        RestApiOptions,
     },
-    api_security::ApiSecurity,
+    api_security::{ApiKey, ApiKeyEndpoint, ApiKeys, ApiSecurity},
     app_config::Config,
 };
 
@@ -207,3 +208,64 @@ fn override_grpc_and_rest_port_jwt_pipeline_home_dir() {
     assert_eq!(app_grpc.port, 3993);
     assert_eq!(app_grpc.host, default_app_grpc.host);
 }
+
+#[test]
+fn api_keys_security() {
+    let input_config = r#"
+  app_name: working_app
+  api:
+    api_security: !ApiKeys
+      keys:
+        - key: admin-key
+        - key: films-key
+          endpoints:
+            - name: films
+              filter_fields: [film_id, release_year]
+              filter: '{"tenant_id": 1}'
+              masked_fields: [rental_rate]
+  home_dir: './.dozer'
+"#;
+    let config = serde_yaml::from_str::<Config>(input_config).unwrap();
+    let expected_api_security = ApiSecurity::ApiKeys(ApiKeys {
+        keys: vec![
+            ApiKey {
+                key: "admin-key".to_owned(),
+                endpoints: vec![],
+            },
+            ApiKey {
+                key: "films-key".to_owned(),
+                endpoints: vec![ApiKeyEndpoint {
+                    name: "films".to_owned(),
+                    filter_fields: vec!["film_id".to_owned(), "release_year".to_owned()],
+                    filter: Some(r#"{"tenant_id": 1}"#.to_owned()),
+                    masked_fields: vec!["rental_rate".to_owned()],
+                }],
+            },
+        ],
+    });
+    assert_eq!(
+        config.api.unwrap().api_security,
+        Some(expected_api_security)
+    );
+}
+
+#[test]
+fn rate_limit() {
+    let input_config = r#"
+  app_name: working_app
+  api:
+    rate_limit:
+      per_token: 10
+      max_concurrent_reads: 64
+  home_dir: './.dozer'
+"#;
+    let config = serde_yaml::from_str::<Config>(input_config).unwrap();
+    assert_eq!(
+        config.api.unwrap().rate_limit,
+        Some(RateLimitConfig {
+            per_token: Some(10),
+            per_endpoint: None,
+            max_concurrent_reads: Some(64),
+        })
+    );
+}
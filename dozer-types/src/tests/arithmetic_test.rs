@@ -0,0 +1,258 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use chrono::DateTime;
+use ordered_float::OrderedFloat;
+use rust_decimal::Decimal;
+
+use crate::errors::types::ArithmeticError;
+use crate::types::{DozerDuration, Field, FieldType};
+
+fn float(f: f64) -> Field {
+    Field::Float(OrderedFloat(f))
+}
+
+fn decimal(s: &str) -> Field {
+    Field::Decimal(Decimal::from_str(s).unwrap())
+}
+
+#[test]
+fn test_arithmetic_type_promotion() {
+    assert_eq!(
+        Field::UInt(2).checked_add(&Field::UInt(3)).unwrap(),
+        Field::UInt(5)
+    );
+    assert_eq!(
+        Field::UInt(2).checked_sub(&Field::Int(3)).unwrap(),
+        Field::Int(-1)
+    );
+    assert_eq!(Field::Int(2).checked_mul(&float(1.5)).unwrap(), float(3.0));
+    assert_eq!(
+        decimal("0.1").checked_add(&float(0.2)).unwrap(),
+        decimal("0.3")
+    );
+    assert_eq!(
+        Field::UInt(u64::MAX).checked_add(&decimal("1")).unwrap(),
+        decimal("18446744073709551616")
+    );
+    assert_eq!(
+        Field::Int(7).checked_rem(&Field::Int(-4)).unwrap(),
+        Field::Int(3)
+    );
+    assert_eq!(
+        Field::Null.checked_add(&Field::Int(1)).unwrap(),
+        Field::Null
+    );
+    assert_eq!(
+        Field::Int(1).checked_div(&Field::Null).unwrap(),
+        Field::Null
+    );
+}
+
+#[test]
+fn test_integer_division_gives_float() {
+    assert_eq!(
+        Field::Int(7).checked_div(&Field::Int(2)).unwrap(),
+        float(3.5)
+    );
+    assert_eq!(
+        Field::UInt(1).checked_div(&Field::UInt(0)).unwrap(),
+        float(f64::INFINITY)
+    );
+    assert_eq!(
+        decimal("1").checked_div(&Field::Int(4)).unwrap(),
+        decimal("0.25")
+    );
+}
+
+#[test]
+fn test_arithmetic_errors() {
+    assert!(matches!(
+        Field::Int(i64::MAX).checked_add(&Field::Int(1)),
+        Err(ArithmeticError::Overflow { .. })
+    ));
+    assert!(matches!(
+        Field::UInt(1).checked_sub(&Field::UInt(2)),
+        Err(ArithmeticError::Overflow { .. })
+    ));
+    assert!(matches!(
+        Field::Decimal(Decimal::MAX).checked_mul(&Field::Int(2)),
+        Err(ArithmeticError::Overflow { .. })
+    ));
+    assert!(matches!(
+        Field::Int(1).checked_rem(&Field::Int(0)),
+        Err(ArithmeticError::DivisionByZero { .. })
+    ));
+    assert!(matches!(
+        decimal("1").checked_div(&Field::UInt(0)),
+        Err(ArithmeticError::DivisionByZero { .. })
+    ));
+    assert!(matches!(
+        Field::Int(1).checked_add(&Field::UInt(u64::MAX)),
+        Err(ArithmeticError::PrecisionLoss {
+            to: FieldType::Int,
+            ..
+        })
+    ));
+    assert!(matches!(
+        decimal("1").checked_add(&float(f64::NAN)),
+        Err(ArithmeticError::PrecisionLoss {
+            to: FieldType::Decimal,
+            ..
+        })
+    ));
+    assert!(matches!(
+        Field::String("1".to_string()).checked_add(&Field::Int(1)),
+        Err(ArithmeticError::UnsupportedTypes { .. })
+    ));
+}
+
+#[test]
+fn test_timestamp_and_duration_arithmetic() {
+    let earlier = DateTime::parse_from_rfc3339("2023-01-01T00:00:00Z").unwrap();
+    let later = DateTime::parse_from_rfc3339("2023-01-01T00:00:01.5Z").unwrap();
+    let duration = DozerDuration::from_millis(1500).unwrap();
+
+    assert_eq!(
+        Field::Timestamp(later)
+            .checked_sub(&Field::Timestamp(earlier))
+            .unwrap(),
+        Field::Int(1500)
+    );
+    assert_eq!(
+        Field::Timestamp(earlier)
+            .checked_add(&Field::Duration(duration))
+            .unwrap(),
+        Field::Timestamp(later)
+    );
+    assert_eq!(
+        Field::Duration(duration)
+            .checked_add(&Field::Timestamp(earlier))
+            .unwrap(),
+        Field::Timestamp(later)
+    );
+    assert_eq!(
+        Field::Duration(duration)
+            .checked_sub(&Field::Duration(duration))
+            .unwrap(),
+        Field::Duration(DozerDuration(0))
+    );
+    assert!(matches!(
+        Field::Duration(duration).checked_sub(&Field::Timestamp(earlier)),
+        Err(ArithmeticError::UnsupportedTypes { .. })
+    ));
+    assert!(matches!(
+        Field::Duration(DozerDuration(i64::MAX)).checked_add(&Field::Duration(duration)),
+        Err(ArithmeticError::Overflow { .. })
+    ));
+}
+
+#[test]
+fn test_checked_neg() {
+    assert_eq!(Field::Int(1).checked_neg().unwrap(), Field::Int(-1));
+    assert_eq!(float(1.5).checked_neg().unwrap(), float(-1.5));
+    assert_eq!(decimal("1.5").checked_neg().unwrap(), decimal("-1.5"));
+    assert_eq!(Field::UInt(0).checked_neg().unwrap(), Field::UInt(0));
+    assert_eq!(Field::Null.checked_neg().unwrap(), Field::Null);
+    assert!(matches!(
+        Field::UInt(1).checked_neg(),
+        Err(ArithmeticError::UnaryOverflow { .. })
+    ));
+    assert!(matches!(
+        Field::Int(i64::MIN).checked_neg(),
+        Err(ArithmeticError::UnaryOverflow { .. })
+    ));
+    assert!(matches!(
+        Field::Boolean(true).checked_neg(),
+        Err(ArithmeticError::UnsupportedType { .. })
+    ));
+}
+
+#[test]
+fn test_checked_cmp() {
+    let cmp = |l: Field, r: Field| l.checked_cmp(&r).unwrap();
+
+    assert_eq!(
+        cmp(Field::UInt(u64::MAX), Field::Int(-1)),
+        Some(Ordering::Greater)
+    );
+    assert_eq!(cmp(Field::Int(1), float(1.0)), Some(Ordering::Equal));
+    assert_eq!(cmp(float(0.1), decimal("0.1")), Some(Ordering::Equal));
+    assert_eq!(
+        cmp(decimal("1"), float(f64::INFINITY)),
+        Some(Ordering::Less)
+    );
+    assert_eq!(cmp(Field::UInt(2), decimal("2.5")), Some(Ordering::Less));
+    assert_eq!(
+        cmp(Field::String("a".to_string()), Field::Text("b".to_string())),
+        Some(Ordering::Less)
+    );
+    assert_eq!(cmp(Field::Null, Field::Int(1)), None);
+    assert!(matches!(
+        Field::Int(1).checked_cmp(&Field::String("1".to_string())),
+        Err(ArithmeticError::Incomparable { .. })
+    ));
+    assert!(matches!(
+        Field::Bson(vec![]).checked_cmp(&Field::Bson(vec![])),
+        Err(ArithmeticError::Incomparable { .. })
+    ));
+}
+
+#[test]
+fn test_cmp_numeric() {
+    let cmp = |l: Field, r: Field| l.cmp_numeric(&r).unwrap();
+
+    assert_eq!(cmp(Field::UInt(2), Field::Int(1)), Ordering::Greater);
+    assert_eq!(cmp(Field::Int(-1), Field::UInt(0)), Ordering::Less);
+    assert_eq!(
+        cmp(Field::Int(i64::MAX), float(9_223_372_036_854_775_807.0)),
+        Ordering::Less
+    );
+    assert_eq!(
+        cmp(Field::Int(i64::MAX - 1), float(9.223372036854775e18)),
+        Ordering::Greater
+    );
+    // 0.1 as a float is slightly more than 0.1.
+    assert_eq!(cmp(float(0.1), decimal("0.1")), Ordering::Greater);
+    assert_eq!(cmp(decimal("-2.5"), Field::Int(-2)), Ordering::Less);
+    assert_eq!(
+        cmp(float(f64::NAN), float(f64::INFINITY)),
+        Ordering::Greater
+    );
+    assert_eq!(
+        cmp(float(f64::NEG_INFINITY), decimal("-100000000000000000000")),
+        Ordering::Less
+    );
+    assert_eq!(cmp(float(1e-320), Field::UInt(0)), Ordering::Greater);
+
+    // Equal values order by type.
+    assert_eq!(cmp(Field::UInt(1), Field::Int(1)), Ordering::Less);
+    assert_eq!(cmp(Field::Int(1), float(1.0)), Ordering::Less);
+    assert_eq!(cmp(float(-0.0), float(0.0)), Ordering::Equal);
+    assert_eq!(cmp(decimal("1.0"), decimal("1.00")), Ordering::Equal);
+
+    let mut fields = vec![
+        float(2.5),
+        Field::UInt(2),
+        decimal("-1"),
+        Field::Int(3),
+        float(-0.5),
+    ];
+    fields.sort_by(|l, r| l.cmp_numeric(r).unwrap());
+    assert_eq!(
+        fields,
+        vec![
+            decimal("-1"),
+            float(-0.5),
+            Field::UInt(2),
+            float(2.5),
+            Field::Int(3)
+        ]
+    );
+
+    assert_eq!(
+        Field::Int(1).cmp_numeric(&Field::String("1".to_string())),
+        None
+    );
+    assert_eq!(Field::Null.cmp_numeric(&Field::Int(1)), None);
+}
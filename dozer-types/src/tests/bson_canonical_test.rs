@@ -0,0 +1,57 @@
+use bson::doc;
+
+use crate::types::{Field, Record};
+
+fn key(bytes: &[u8]) -> Vec<u8> {
+    Field::Bson(bytes.to_vec()).encode()
+}
+
+#[test]
+fn test_bson_key_ignores_key_order_and_whitespace() {
+    assert_eq!(
+        key(br#"{"b": {"d": 1, "c": 2}, "a": [2]}"#),
+        key(br#"{"a":[2],"b":{"c":2,"d":1}}"#)
+    );
+    assert_eq!(key(br#"{"b":1,"a":2}"#)[1..], *br#"{"a":2,"b":1}"#);
+    assert_ne!(key(br#"{"a":[1,2]}"#), key(br#"{"a":[2,1]}"#));
+}
+
+#[test]
+fn test_bson_key_normalizes_numbers() {
+    assert_eq!(key(br#"{"a":1.0}"#), key(br#"{"a":1}"#));
+    assert_eq!(key(br#"{"a":1e2}"#), key(br#"{"a":100}"#));
+    assert_eq!(key(br#"{"a":-0.0}"#), key(br#"{"a":0}"#));
+    assert_ne!(key(br#"{"a":1.5}"#), key(br#"{"a":1}"#));
+}
+
+#[test]
+fn test_bson_document_and_json_have_same_key() {
+    let mut document = vec![];
+    doc! { "b": 1, "a": "x" }.to_writer(&mut document).unwrap();
+    assert_eq!(key(&document), key(br#"{"a":"x","b":1}"#));
+}
+
+#[test]
+fn test_bson_key_keeps_other_bytes() {
+    let cases: [&[u8]; 3] = [b"", b"\x00\xff", b"{\"a\":"];
+    for bytes in cases {
+        assert_eq!(key(bytes)[1..], *bytes);
+    }
+}
+
+#[test]
+fn test_bson_encoding_len() {
+    let cases: [&[u8]; 3] = [br#"{ "b": 1.0, "a": 2 }"#, b"[1, 2]", b"\x00\xff"];
+    for bytes in cases {
+        let field = Field::Bson(bytes.to_vec());
+        assert_eq!(field.encoding_len(), field.encode().len());
+    }
+}
+
+#[test]
+fn test_bson_record_keeps_raw_bytes() {
+    let bytes = br#"{ "b": 1.0, "a": 2 }"#.to_vec();
+    let record = Record::new(None, vec![Field::Bson(bytes.clone())], None);
+    let decoded = Record::decode_compact(&record.encode_compact()).unwrap();
+    assert_eq!(decoded.values, vec![Field::Bson(bytes)]);
+}
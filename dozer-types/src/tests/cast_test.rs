@@ -0,0 +1,139 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDate};
+use ordered_float::OrderedFloat;
+use rust_decimal::Decimal;
+
+use crate::errors::types::CastError;
+use crate::types::{DozerDuration, Field, FieldType};
+
+#[test]
+fn test_cast_null_and_same_type() {
+    for typ in [FieldType::Int, FieldType::Bson, FieldType::Timestamp] {
+        assert_eq!(Field::Null.cast_to(typ).unwrap(), Field::Null);
+    }
+    let bson = Field::Bson(vec![1]);
+    assert_eq!(bson.cast_to(FieldType::Bson).unwrap(), bson);
+}
+
+#[test]
+fn test_cast_numbers() {
+    assert_eq!(
+        Field::Int(-1).cast_to(FieldType::Float).unwrap(),
+        Field::Float(OrderedFloat(-1.0))
+    );
+    assert_eq!(
+        Field::Float(OrderedFloat(-2.7))
+            .cast_to(FieldType::Int)
+            .unwrap(),
+        Field::Int(-2)
+    );
+    assert_eq!(
+        Field::Float(OrderedFloat(0.1))
+            .cast_to(FieldType::Decimal)
+            .unwrap(),
+        Field::Decimal(Decimal::from_str("0.1").unwrap())
+    );
+    assert_eq!(
+        Field::Decimal(Decimal::from_str("3.9").unwrap())
+            .cast_to(FieldType::UInt)
+            .unwrap(),
+        Field::UInt(3)
+    );
+    assert_eq!(
+        Field::Boolean(true).cast_to(FieldType::UInt).unwrap(),
+        Field::UInt(1)
+    );
+    assert!(matches!(
+        Field::Int(-1).cast_to(FieldType::UInt),
+        Err(CastError::OutOfRange { .. })
+    ));
+    assert!(matches!(
+        Field::UInt(u64::MAX).cast_to(FieldType::Int),
+        Err(CastError::OutOfRange { .. })
+    ));
+    assert!(matches!(
+        Field::Float(OrderedFloat(f64::NAN)).cast_to(FieldType::Decimal),
+        Err(CastError::InvalidValue { .. })
+    ));
+}
+
+#[test]
+fn test_cast_strings() {
+    let string = |s: &str| Field::String(s.to_string());
+    assert_eq!(
+        string(" 42 ").cast_to(FieldType::UInt).unwrap(),
+        Field::UInt(42)
+    );
+    assert_eq!(
+        string("1.5e3").cast_to(FieldType::Decimal).unwrap(),
+        Field::Decimal(Decimal::from(1500))
+    );
+    assert_eq!(
+        string("TRUE").cast_to(FieldType::Boolean).unwrap(),
+        Field::Boolean(true)
+    );
+    assert_eq!(
+        string("1h 30m").cast_to(FieldType::Duration).unwrap(),
+        Field::Duration(DozerDuration::from_secs(5400).unwrap())
+    );
+    assert_eq!(
+        string("(1,2)").cast_to(FieldType::Point).unwrap(),
+        Field::Point((1.0, 2.0).into())
+    );
+    assert!(matches!(
+        string("abc").cast_to(FieldType::Int),
+        Err(CastError::InvalidValue { .. })
+    ));
+
+    assert_eq!(
+        Field::Boolean(false).cast_to(FieldType::Text).unwrap(),
+        Field::Text("false".to_string())
+    );
+    assert_eq!(
+        Field::Point((1.0, 2.0).into())
+            .cast_to(FieldType::String)
+            .unwrap(),
+        string("(1,2)")
+    );
+    assert_eq!(
+        Field::Binary(b"abc".to_vec())
+            .cast_to(FieldType::String)
+            .unwrap(),
+        string("abc")
+    );
+    assert!(matches!(
+        Field::Bson(vec![]).cast_to(FieldType::String),
+        Err(CastError::Unsupported {
+            from: FieldType::Bson,
+            to: FieldType::String
+        })
+    ));
+}
+
+#[test]
+fn test_cast_timestamps_and_dates() {
+    let timestamp = DateTime::parse_from_rfc3339("2023-03-01T23:00:00-05:00").unwrap();
+    assert_eq!(
+        Field::String("2023-03-01T23:00:00-05:00".to_string())
+            .cast_to(FieldType::Timestamp)
+            .unwrap(),
+        Field::Timestamp(timestamp)
+    );
+    assert_eq!(
+        Field::Timestamp(timestamp)
+            .cast_to(FieldType::Date)
+            .unwrap(),
+        Field::Date(NaiveDate::from_ymd_opt(2023, 3, 1).unwrap())
+    );
+    assert_eq!(
+        Field::Date(NaiveDate::from_ymd_opt(2023, 3, 1).unwrap())
+            .cast_to(FieldType::Timestamp)
+            .unwrap(),
+        Field::Timestamp(DateTime::parse_from_rfc3339("2023-03-01T00:00:00Z").unwrap())
+    );
+    assert!(matches!(
+        Field::Int(0).cast_to(FieldType::Timestamp),
+        Err(CastError::Unsupported { .. })
+    ));
+}
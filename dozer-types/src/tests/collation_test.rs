@@ -0,0 +1,61 @@
+use crate::types::collation::{collation_key, normalize};
+use crate::types::Field;
+
+fn assert_sorted(locale: &str, sorted: &[&str]) {
+    for pair in sorted.windows(2) {
+        assert!(
+            collation_key(pair[0], locale) < collation_key(pair[1], locale),
+            "{} < {} in {locale}",
+            pair[0],
+            pair[1]
+        );
+    }
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(normalize("e\u{301}"), "\u{e9}");
+    assert_eq!(normalize("\u{e9}"), "\u{e9}");
+    assert_eq!(
+        collation_key("e\u{301}", "en"),
+        collation_key("\u{e9}", "en")
+    );
+}
+
+#[test]
+fn test_root_collation() {
+    assert_sorted(
+        "en",
+        &["", "a", "A", "ab", "b", "resume", "Resume", "résumé", "rf"],
+    );
+    assert_sorted("de", &["Apfel", "Äpfel", "Bär", "Zug"]);
+    assert_sorted("und", &["cote", "coté", "côte", "côté"]);
+}
+
+#[test]
+fn test_tailored_collation() {
+    assert_sorted("sv-SE", &["apa", "zebra", "år", "ägg", "öl"]);
+    assert_sorted("fi", &["zeta", "åke", "äiti", "öljy"]);
+    assert_sorted("da", &["zoo", "æble", "øl", "ål"]);
+    assert_sorted("es_ES", &["nube", "ñu", "oso"]);
+    assert_sorted("pl", &["lato", "łza", "mama"]);
+    assert_sorted(
+        "tr",
+        &["hal", "ılık", "ılıK", "Irmak", "inek", "İnek", "jale"],
+    );
+    assert_sorted("en", &["ilik", "Irmak", "ılık"]);
+}
+
+#[test]
+fn test_field_collation_key() {
+    assert_eq!(
+        Field::String("Äpfel".to_string()).collation_key("de"),
+        Some(collation_key("Äpfel", "de"))
+    );
+    assert_eq!(
+        Field::Text("Äpfel".to_string()).collation_key("sv"),
+        Some(collation_key("Äpfel", "sv"))
+    );
+    assert_eq!(Field::Int(1).collation_key("en"), None);
+    assert_eq!(Field::Null.collation_key("en"), None);
+}
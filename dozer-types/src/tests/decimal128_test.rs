@@ -0,0 +1,152 @@
+use std::cmp::Ordering;
+
+use rust_decimal::Decimal;
+
+use crate::types::{DozerDecimal128, Field, FieldType};
+
+fn decimal128(s: &str) -> DozerDecimal128 {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_decimal128_text() {
+    for s in [
+        "0",
+        "1.50",
+        "-0.001",
+        "12345678901234567890123456789012345678",
+        "-1234567890.1234567890123456789012345678",
+        "0.00000000000000000000000000000000000001",
+    ] {
+        assert_eq!(decimal128(s).to_string(), s);
+    }
+    assert_eq!(decimal128("+1.5e2").to_string(), "150");
+    assert_eq!(decimal128("15e-3").to_string(), "0.015");
+    assert_eq!(decimal128("007.5"), DozerDecimal128::new(75, 1).unwrap());
+
+    for s in [
+        "",
+        "-",
+        ".",
+        "1.2.3",
+        "1e",
+        "abc",
+        "123456789012345678901234567890123456789",
+        "0.000000000000000000000000000000000000001",
+        "1e39",
+    ] {
+        assert!(s.parse::<DozerDecimal128>().is_err(), "{s}");
+    }
+    assert!(DozerDecimal128::new(1, 39).is_err());
+    assert!(DozerDecimal128::new(10i128.pow(38), 0).is_err());
+}
+
+#[test]
+fn test_decimal128_ordered_bytes() {
+    let sorted = [
+        "-99999999999999999999999999999999999999",
+        "-1.5",
+        "-1",
+        "-0.00000000000000000000000000000000000001",
+        "0",
+        "0.00",
+        "0.00000000000000000000000000000000000001",
+        "1",
+        "1.0",
+        "1.5",
+        "99999999999999999999999999999999999999",
+    ]
+    .map(decimal128);
+    for pair in sorted.windows(2) {
+        assert!(
+            pair[0].to_ordered_bytes() < pair[1].to_ordered_bytes(),
+            "{} < {}",
+            pair[0],
+            pair[1]
+        );
+        assert!(pair[0] < pair[1]);
+    }
+    for decimal in sorted {
+        let bytes = decimal.to_ordered_bytes();
+        assert_eq!(
+            DozerDecimal128::from_ordered_bytes(&bytes).unwrap(),
+            decimal
+        );
+    }
+
+    assert_eq!(
+        decimal128("1").cmp_value(&decimal128("1.00")),
+        Ordering::Equal
+    );
+    assert!(DozerDecimal128::from_ordered_bytes(&[0; 3]).is_err());
+    let mut bytes = decimal128("1.5").to_ordered_bytes();
+    bytes[32] = 0;
+    assert!(DozerDecimal128::from_ordered_bytes(&bytes).is_err());
+}
+
+#[test]
+fn test_decimal128_field_encoding() {
+    let field = Field::Decimal128(decimal128("-1234567890.1234567890123456789012345678"));
+    assert_eq!(Field::decode(&field.encode()).unwrap(), field);
+    assert_eq!(format!("{field}").parse::<Field>().unwrap(), field);
+    assert!(
+        Field::Decimal128(decimal128("-1")).encode() < Field::Decimal128(decimal128("1")).encode()
+    );
+}
+
+#[test]
+fn test_decimal128_cast() {
+    let field = Field::Decimal128(decimal128("-2.75"));
+    assert_eq!(field.cast_to(FieldType::Int).unwrap(), Field::Int(-2));
+    assert!(field.cast_to(FieldType::UInt).is_err());
+    assert_eq!(
+        field.cast_to(FieldType::Decimal).unwrap(),
+        Field::Decimal(Decimal::new(-275, 2))
+    );
+    assert!(
+        Field::Decimal128(decimal128("12345678901234567890123456789012345678"))
+            .cast_to(FieldType::Decimal)
+            .is_err()
+    );
+    assert_eq!(
+        Field::Decimal(Decimal::new(150, 2))
+            .cast_to(FieldType::Decimal128)
+            .unwrap(),
+        Field::Decimal128(decimal128("1.50"))
+    );
+    assert_eq!(
+        Field::String("1e2".to_string())
+            .cast_to(FieldType::Decimal128)
+            .unwrap(),
+        Field::Decimal128(decimal128("100"))
+    );
+}
+
+#[test]
+fn test_decimal128_arithmetic() {
+    let large = Field::Decimal128(decimal128("10000000000000000000000000000000.5"));
+    assert_eq!(
+        large.checked_add(&Field::Int(2)).unwrap(),
+        Field::Decimal128(decimal128("10000000000000000000000000000002.5"))
+    );
+    assert_eq!(
+        Field::Decimal128(decimal128("1.5"))
+            .checked_mul(&Field::Decimal(Decimal::new(2, 1)))
+            .unwrap(),
+        Field::Decimal128(decimal128("0.30"))
+    );
+    assert!(large.checked_mul(&large).is_err());
+    assert!(large
+        .checked_div(&Field::Decimal128(decimal128("0")))
+        .is_err());
+    assert_eq!(
+        large.checked_cmp(&Field::Int(1)).unwrap(),
+        Some(Ordering::Greater)
+    );
+    assert_eq!(
+        Field::Decimal128(decimal128("2.0"))
+            .checked_cmp(&Field::UInt(2))
+            .unwrap(),
+        Some(Ordering::Equal)
+    );
+}
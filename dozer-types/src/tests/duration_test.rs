@@ -0,0 +1,91 @@
+use chrono::DateTime;
+
+use crate::types::{DozerDuration, Field};
+
+#[test]
+fn test_duration_conversion() {
+    let duration = DozerDuration::from_secs(90).unwrap();
+    let field = Field::Duration(duration);
+    assert_eq!(field.as_duration(), Some(duration));
+    assert!(field.as_timestamp().is_none());
+    assert_eq!(field.to_duration().unwrap(), Some(duration));
+    assert_eq!(field.to_string(), Some("90s".to_string()));
+
+    assert_eq!(
+        Field::String("1m 30s".to_string()).to_duration().unwrap(),
+        Some(duration)
+    );
+    assert_eq!(
+        Field::Null.to_duration().unwrap(),
+        Some(DozerDuration::ZERO)
+    );
+    assert!(Field::Int(1).to_duration().unwrap().is_none());
+}
+
+#[test]
+fn test_duration_from_str_and_display() {
+    let cases = [
+        ("0s", 0),
+        ("1ns", 1),
+        ("-1.5s", -1_500_000_000),
+        ("1h 30m", 90 * 60 * 1_000_000_000),
+        ("2 days", 2 * 24 * 60 * 60 * 1_000_000_000),
+        ("1 week, 1 hour", (7 * 24 + 1) * 60 * 60 * 1_000_000_000),
+        (".25ms", 250_000),
+    ];
+    for (str, nanos) in cases {
+        assert_eq!(
+            str.parse::<DozerDuration>().unwrap(),
+            DozerDuration::from_nanos(nanos)
+        );
+    }
+
+    for str in [
+        "",
+        "-",
+        "1",
+        "1 fortnight",
+        "1.2.3s",
+        "1h -5m",
+        "20000 weeks",
+    ] {
+        assert!(str.parse::<DozerDuration>().is_err(), "{str}");
+    }
+
+    for (nanos, str) in [
+        (0, "0s"),
+        (90 * 1_000_000_000, "90s"),
+        (-2 * 24 * 60 * 60 * 1_000_000_000, "-2d"),
+        (1_500_000, "1500us"),
+    ] {
+        let duration = DozerDuration::from_nanos(nanos);
+        assert_eq!(duration.to_string(), str);
+        assert_eq!(str.parse::<DozerDuration>().unwrap(), duration);
+    }
+}
+
+#[test]
+fn test_duration_encoding_is_sortable() {
+    let durations = [i64::MIN, -1, 0, 1, i64::MAX].map(DozerDuration::from_nanos);
+    for pair in durations.windows(2) {
+        assert!(pair[0].to_bytes() < pair[1].to_bytes());
+        assert_eq!(
+            DozerDuration::from_bytes(&pair[0].to_bytes()).unwrap(),
+            pair[0]
+        );
+    }
+}
+
+#[test]
+fn test_duration_timestamp_arithmetic() {
+    let from = DateTime::parse_from_rfc3339("2020-01-01T00:00:00Z").unwrap();
+    let to = DateTime::parse_from_rfc3339("2020-01-01T00:01:30Z").unwrap();
+    let duration = DozerDuration::between(&from, &to).unwrap();
+    assert_eq!(duration, DozerDuration::from_secs(90).unwrap());
+    assert_eq!(
+        DozerDuration::between(&to, &from).unwrap(),
+        duration.checked_neg().unwrap()
+    );
+    assert_eq!(duration.add_to_timestamp(from).unwrap(), to);
+    assert_eq!(duration.sub_from_timestamp(to).unwrap(), from);
+}
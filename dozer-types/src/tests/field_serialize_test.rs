@@ -1,4 +1,12 @@
-use crate::types::{field_test_cases, Field};
+use std::str::FromStr;
+
+use chrono::{DateTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+
+use crate::helper::json_value_to_field;
+use crate::types::{
+    field_test_cases, Field, FieldBorrow, FieldType, Record, RecordBorrow, SchemaIdentifier,
+};
 
 #[test]
 fn test_field_serialize_roundtrip() {
@@ -20,6 +28,18 @@ fn test_field_bincode_serialize_roundtrip() {
     }
 }
 
+#[test]
+fn test_field_borrow_bincode_matches_field() {
+    for field in field_test_cases() {
+        let bytes = bincode::serialize(&field).unwrap();
+        assert_eq!(bincode::serialize(&field.borrow()).unwrap(), bytes);
+        assert_eq!(
+            bincode::deserialize::<FieldBorrow>(&bytes).unwrap(),
+            field.borrow()
+        );
+    }
+}
+
 #[test]
 fn field_serialization_should_never_be_empty() {
     for field in field_test_cases() {
@@ -42,3 +62,170 @@ fn encoding_len_must_agree_with_encode() {
         assert_eq!(bytes.len(), field.encoding_len());
     }
 }
+
+#[test]
+fn test_timestamp_encoding_preserves_nanos_and_offset() {
+    let timestamp = DateTime::parse_from_rfc3339("2023-03-01T12:34:56.123456789-07:00").unwrap();
+    let decoded = Field::decode(&Field::Timestamp(timestamp).encode()).unwrap();
+    let decoded = decoded.as_timestamp().unwrap();
+    assert_eq!(decoded.timestamp_subsec_nanos(), 123_456_789);
+    assert_eq!(decoded.offset(), timestamp.offset());
+}
+
+#[test]
+fn test_timestamp_encoding_is_sortable() {
+    let timestamps = [
+        "1969-12-31T23:59:58.5Z",
+        "1969-12-31T23:59:59.999999999Z",
+        "1970-01-01T00:00:00Z",
+        "1970-01-01T00:00:00.000000001Z",
+        "2023-03-01T12:34:56+00:00",
+        "2023-03-01T12:34:56-07:00",
+    ]
+    .map(|timestamp| Field::Timestamp(DateTime::parse_from_rfc3339(timestamp).unwrap()).encode());
+    for pair in timestamps.windows(2) {
+        assert!(pair[0] < pair[1]);
+    }
+}
+
+#[test]
+fn test_legacy_timestamp_decode() {
+    let mut bytes = vec![8];
+    bytes.extend_from_slice(&1_500_i64.to_be_bytes());
+    assert_eq!(
+        Field::decode(&bytes).unwrap(),
+        Field::Timestamp(DateTime::from(Utc.timestamp_millis_opt(1_500).unwrap()))
+    );
+}
+
+#[test]
+fn test_decimal_serde_is_lossless() {
+    let decimal = Field::Decimal(Decimal::from_str("79228162514264337593543950.335").unwrap());
+
+    let bytes = bincode::serialize(&decimal).unwrap();
+    assert_eq!(bincode::deserialize::<Field>(&bytes).unwrap(), decimal);
+
+    let json = serde_json::to_string(&decimal).unwrap();
+    assert_eq!(json, r#"{"Decimal":"79228162514264337593543950.335"}"#);
+    assert_eq!(serde_json::from_str::<Field>(&json).unwrap(), decimal);
+}
+
+#[test]
+fn test_decimal_deserialize_from_string_bincode() {
+    // Decimals used to be serialized as strings in binary formats too. `Decimal` is variant 7.
+    let mut legacy = bincode::serialize(&7_u32).unwrap();
+    legacy.extend(bincode::serialize("0.1").unwrap());
+    assert_eq!(
+        bincode::deserialize::<Field>(&legacy).unwrap(),
+        Field::Decimal(Decimal::from_str("0.1").unwrap())
+    );
+}
+
+#[test]
+fn test_decimal_json_value_to_field() {
+    let field = json_value_to_field(
+        serde_json::json!(12345678901234567890_u64),
+        FieldType::Decimal,
+        false,
+    )
+    .unwrap();
+    assert_eq!(
+        field,
+        Field::Decimal(Decimal::from_str("12345678901234567890").unwrap())
+    );
+}
+
+#[test]
+fn test_field_json_serde_roundtrip() {
+    for field in field_test_cases() {
+        let json = serde_json::to_string(&field).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Field>(&json).unwrap(),
+            field,
+            "{json}"
+        );
+    }
+}
+
+#[test]
+fn test_field_json_is_tagged() {
+    let cases = [
+        (Field::Binary(vec![0xde, 0xad]), r#"{"Binary":"3q0="}"#),
+        (
+            Field::Timestamp(DateTime::parse_from_rfc3339("2020-01-01T00:00:00.5Z").unwrap()),
+            r#"{"Timestamp":"2020-01-01T00:00:00.500Z"}"#,
+        ),
+        (Field::Int(-1), r#"{"Int":-1}"#),
+        (Field::Null, r#""Null""#),
+    ];
+    for (field, json) in cases {
+        assert_eq!(serde_json::to_string(&field).unwrap(), json);
+    }
+    // The derived implementation wrote binaries as arrays of bytes.
+    assert_eq!(
+        serde_json::from_str::<Field>(r#"{"Binary":[222,173]}"#).unwrap(),
+        Field::Binary(vec![0xde, 0xad])
+    );
+    assert!(serde_json::from_str::<Field>(r#"{"Int":"abc"}"#).is_err());
+}
+
+#[test]
+fn test_field_bincode_is_positional() {
+    // The variant index followed by the value, as the derived implementation wrote.
+    let mut expected = bincode::serialize(&6_u32).unwrap();
+    expected.extend(bincode::serialize(&vec![1_u8, 2]).unwrap());
+    assert_eq!(
+        bincode::serialize(&Field::Binary(vec![1, 2])).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_record_bincode_decodes_baseline_bytes() {
+    // Written by the derived serde of `Field` before `Duration`, `Geometry` and `Decimal128`, where `Null` was variant 12.
+    let mut bytes = vec![1];
+    bytes.extend(1_u32.to_le_bytes());
+    bytes.extend(2_u16.to_le_bytes());
+    bytes.extend(3_u64.to_le_bytes());
+    bytes.extend(1_u32.to_le_bytes());
+    bytes.extend((-1_i64).to_le_bytes());
+    bytes.extend(12_u32.to_le_bytes());
+    bytes.extend(4_u32.to_le_bytes());
+    bytes.extend(1_u64.to_le_bytes());
+    bytes.push(b'a');
+    bytes.push(1);
+    bytes.extend(3_u32.to_le_bytes());
+
+    let record = Record::new(
+        Some(SchemaIdentifier { id: 1, version: 2 }),
+        vec![Field::Int(-1), Field::Null, Field::String("a".to_string())],
+        Some(3),
+    );
+    assert_eq!(bincode::deserialize::<Record>(&bytes).unwrap(), record);
+    assert_eq!(
+        bincode::deserialize::<RecordBorrow>(&bytes).unwrap(),
+        record.borrow()
+    );
+    assert_eq!(Record::decode_compact(&bytes).unwrap(), record);
+    assert_eq!(bincode::serialize(&record).unwrap(), bytes);
+    assert_eq!(bincode::serialize(&record.borrow()).unwrap(), bytes);
+}
+
+#[test]
+fn test_record_json_serde_roundtrip() {
+    let record = Record::new(
+        None,
+        vec![
+            Field::Binary(vec![1]),
+            Field::String("a".to_string()),
+            Field::Null,
+        ],
+        Some(1),
+    );
+    let json = serde_json::to_value(&record).unwrap();
+    assert_eq!(
+        json["values"],
+        serde_json::json!([{ "Binary": "AQ==" }, { "String": "a" }, "Null"])
+    );
+    assert_eq!(serde_json::from_value::<Record>(json).unwrap(), record);
+}
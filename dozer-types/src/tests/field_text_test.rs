@@ -0,0 +1,102 @@
+use chrono::{DateTime, NaiveDate};
+use ordered_float::OrderedFloat;
+use rust_decimal::Decimal;
+
+use crate::types::{field_test_cases, DozerDuration, DozerPoint, Field};
+
+#[test]
+fn test_field_text_round_trip() {
+    let extra_cases = [
+        Field::Int(i64::MIN),
+        Field::UInt(u64::MAX),
+        Field::Float(OrderedFloat(0.1)),
+        Field::Float(OrderedFloat(-0.0)),
+        Field::Float(OrderedFloat(f64::NAN)),
+        Field::Float(OrderedFloat(f64::NEG_INFINITY)),
+        Field::Float(OrderedFloat(1e300)),
+        Field::String("a:b\nnull".to_string()),
+        Field::Text("null".to_string()),
+        Field::Decimal(Decimal::new(150, 2)),
+        Field::Decimal(Decimal::new(-1, 28)),
+        Field::Binary(vec![0, 15, 255]),
+        Field::Point(DozerPoint::from((0.1, -2.5))),
+        Field::Duration(DozerDuration::from_nanos(1_500_000_000)),
+    ];
+    for field in field_test_cases().chain(extra_cases) {
+        let text = format!("{field}");
+        let parsed: Field = text.parse().unwrap();
+        assert_eq!(parsed, field, "{text}");
+        assert_eq!(parsed.get_type(), field.get_type(), "{text}");
+        if let (Field::Decimal(parsed), Field::Decimal(field)) = (&parsed, &field) {
+            assert_eq!(parsed.scale(), field.scale(), "{text}");
+        }
+        if let (Field::Timestamp(parsed), Field::Timestamp(field)) = (&parsed, &field) {
+            assert_eq!(parsed.offset(), field.offset(), "{text}");
+        }
+    }
+}
+
+#[test]
+fn test_field_text_format() {
+    let timestamp = DateTime::parse_from_rfc3339("2020-01-01T12:30:00.5+05:30").unwrap();
+    let cases = [
+        (Field::Null, "null"),
+        (Field::UInt(1), "uint:1"),
+        (Field::Int(-1), "int:-1"),
+        (Field::Float(OrderedFloat(1.0)), "float:1"),
+        (Field::Boolean(true), "boolean:true"),
+        (Field::String("s".to_string()), "string:s"),
+        (Field::Text("".to_string()), "text:"),
+        (Field::Binary(vec![0xde, 0xad]), "binary:DEAD"),
+        (Field::Decimal(Decimal::new(150, 2)), "decimal:1.50"),
+        (
+            Field::Timestamp(timestamp),
+            "timestamp:2020-01-01T12:30:00.500+05:30",
+        ),
+        (
+            Field::Date(NaiveDate::from_ymd_opt(2020, 1, 31).unwrap()),
+            "date:2020-01-31",
+        ),
+        (Field::Bson(vec![1]), "bson:01"),
+        (Field::Point(DozerPoint::from((1.5, 2.0))), "point:(1.5,2)"),
+        (
+            Field::Duration(DozerDuration::from_nanos(90_000_000_000)),
+            "duration:90s",
+        ),
+    ];
+    for (field, text) in cases {
+        assert_eq!(format!("{field}"), text);
+        assert_eq!(text.parse::<Field>().unwrap(), field);
+    }
+
+    assert_eq!(
+        "binary:dead".parse::<Field>().unwrap(),
+        Field::Binary(vec![0xde, 0xad])
+    );
+}
+
+#[test]
+fn test_field_text_invalid() {
+    let cases = [
+        "",
+        "NULL",
+        "1",
+        "unknown:1",
+        "uint:-1",
+        "int:1.5",
+        "float:",
+        "boolean:yes",
+        "binary:0",
+        "binary:+1",
+        "binary:zz",
+        "decimal:1.2.3",
+        "timestamp:2020-01-01",
+        "date:2020-13-01",
+        "point:1",
+        "duration:1 fortnight",
+        "geometry:00",
+    ];
+    for case in cases {
+        assert!(case.parse::<Field>().is_err(), "{case}");
+    }
+}
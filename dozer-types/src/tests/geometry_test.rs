@@ -0,0 +1,131 @@
+use geo::{line_string, point, polygon, Geometry, MultiPolygon};
+
+use crate::types::{DozerGeometry, DozerPoint, Field, FieldType};
+
+fn square() -> Geometry<f64> {
+    Geometry::Polygon(polygon![
+        (x: 0., y: 0.),
+        (x: 10., y: 0.),
+        (x: 10., y: 10.),
+        (x: 0., y: 10.),
+        (x: 0., y: 0.),
+    ])
+}
+
+#[test]
+fn test_geometry_geo_round_trip() {
+    let geometries = [
+        Geometry::Point(point!(x: 1., y: 2.)),
+        Geometry::LineString(line_string![(x: 0., y: 0.), (x: 1., y: 1.), (x: 2., y: 0.)]),
+        square(),
+        Geometry::MultiPolygon(MultiPolygon::new(vec![
+            polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 0., y: 1.), (x: 0., y: 0.)],
+            polygon![(x: 5., y: 5.), (x: 6., y: 5.), (x: 5., y: 6.), (x: 5., y: 5.)],
+        ])),
+    ];
+    for geometry in geometries {
+        for srid in [None, Some(4326)] {
+            let value = DozerGeometry::from_geo(&geometry, srid);
+            assert_eq!(value.to_geo().unwrap(), geometry);
+            assert_eq!(value.srid().unwrap(), srid);
+            assert_eq!(
+                DozerGeometry::from_ewkb(value.as_bytes().to_vec()).unwrap(),
+                value
+            );
+        }
+    }
+}
+
+#[test]
+fn test_geometry_hex() {
+    // `SELECT 'SRID=4326;POINT(1 2)'::geometry` in PostGIS.
+    let hex = "0101000020E6100000000000000000F03F0000000000000040";
+    let value: DozerGeometry = hex.parse().unwrap();
+    assert_eq!(value.srid().unwrap(), Some(4326));
+    assert_eq!(
+        value.to_geo().unwrap(),
+        Geometry::Point(point!(x: 1., y: 2.))
+    );
+    assert_eq!(value.to_string(), hex);
+    assert_eq!(
+        value,
+        DozerGeometry::from_geo(&value.to_geo().unwrap(), Some(4326))
+    );
+
+    let lowercase: DozerGeometry = hex.to_lowercase().parse().unwrap();
+    assert_eq!(lowercase, value);
+}
+
+#[test]
+fn test_geometry_other_encodings() {
+    // Big-endian WKB POINT(1 2).
+    let big_endian: DozerGeometry = "00000000013FF00000000000004000000000000000"
+        .parse()
+        .unwrap();
+    assert_eq!(big_endian.srid().unwrap(), None);
+    assert_eq!(
+        big_endian.to_geo().unwrap(),
+        Geometry::Point(point!(x: 1., y: 2.))
+    );
+
+    // ISO WKB POINT Z(1 2 3), whose Z coordinate is dropped.
+    let iso_z: DozerGeometry = "01E9030000000000000000F03F00000000000000400000000000000840"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        iso_z.to_geo().unwrap(),
+        Geometry::Point(point!(x: 1., y: 2.))
+    );
+}
+
+#[test]
+fn test_geometry_invalid() {
+    assert!("".parse::<DozerGeometry>().is_err());
+    assert!("0".parse::<DozerGeometry>().is_err());
+    assert!("zz".parse::<DozerGeometry>().is_err());
+    // Unknown geometry type.
+    assert!("0109000000".parse::<DozerGeometry>().is_err());
+    // Truncated point.
+    assert!("0101000000000000000000F03F"
+        .parse::<DozerGeometry>()
+        .is_err());
+    // Trailing bytes.
+    assert!("00000000013FF0000000000000400000000000000000"
+        .parse::<DozerGeometry>()
+        .is_err());
+    // A huge count of points without the bytes for them.
+    assert!(DozerGeometry::from_ewkb(vec![1, 2, 0, 0, 0, 255, 255, 255, 255]).is_err());
+}
+
+#[test]
+fn test_geometry_contains() {
+    let value = DozerGeometry::from_geo(&square(), Some(4326));
+    assert!(value.contains(&DozerPoint::from((5., 5.))).unwrap());
+    assert!(!value.contains(&DozerPoint::from((15., 5.))).unwrap());
+    assert!(!value.contains(&DozerPoint::from((0., 5.))).unwrap());
+}
+
+#[test]
+fn test_geometry_field() {
+    let value = DozerGeometry::from_geo(&square(), Some(4326));
+    let field = Field::Geometry(value.clone());
+    assert_eq!(field.get_type(), Some(FieldType::Geometry));
+    assert_eq!(field.as_geometry(), Some(&value));
+    assert_eq!(field.to_string(), Some(value.to_string()));
+    assert_eq!(Field::decode(&field.encode()).unwrap(), field);
+    assert_eq!(
+        Field::String(value.to_string())
+            .cast_to(FieldType::Geometry)
+            .unwrap(),
+        field
+    );
+    assert_eq!(
+        Field::Binary(value.as_bytes().to_vec())
+            .cast_to(FieldType::Geometry)
+            .unwrap(),
+        field
+    );
+    assert!(Field::Binary(vec![1, 2, 3])
+        .cast_to(FieldType::Geometry)
+        .is_err());
+}
@@ -0,0 +1,36 @@
+use std::mem::size_of;
+
+use crate::types::{field_test_cases, DozerGeometry, Field, Record};
+
+#[test]
+fn test_field_estimated_heap_size() {
+    let mut string = String::with_capacity(16);
+    string.push_str("dozer");
+    assert_eq!(Field::String(string).estimated_heap_size(), 16);
+    assert_eq!(Field::Text("abc".to_string()).estimated_heap_size(), 3);
+    assert_eq!(Field::Binary(vec![0; 10]).estimated_heap_size(), 10);
+    assert_eq!(Field::Bson(vec![0; 5]).estimated_heap_size(), 5);
+    assert_eq!(
+        Field::Geometry(DozerGeometry(vec![0; 21])).estimated_heap_size(),
+        21
+    );
+    assert_eq!(Field::Int(1).estimated_heap_size(), 0);
+    assert_eq!(Field::Null.estimated_heap_size(), 0);
+}
+
+#[test]
+fn test_record_estimated_heap_size() {
+    let values = vec![
+        Field::Int(1),
+        Field::String("abc".to_string()),
+        Field::Binary(vec![1, 2]),
+    ];
+    let record = Record::new(None, values, None);
+    assert_eq!(
+        record.estimated_heap_size(),
+        record.values.capacity() * size_of::<Field>() + 5
+    );
+
+    let record = Record::new(None, field_test_cases().collect(), None);
+    assert!(record.estimated_heap_size() >= record.values.len() * size_of::<Field>());
+}
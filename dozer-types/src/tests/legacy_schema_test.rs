@@ -0,0 +1,128 @@
+use crate::types::legacy::{
+    deserialize_schema, FieldDefinitionV1, FieldDefinitionV2, FieldMetadataV1, FieldMetadataV2,
+    LegacyFieldDefinition, LegacySchema, SchemaV1, SchemaV2,
+};
+use crate::types::{
+    Field, FieldDefinition, FieldMetadata, FieldType, GeneratedValue, Schema, SchemaIdentifier,
+    SourceDefinition, TimestampPolicy,
+};
+
+#[test]
+fn test_deserialize_legacy_schema() {
+    let legacy = LegacySchema {
+        identifier: Some(SchemaIdentifier { id: 1, version: 1 }),
+        fields: vec![LegacyFieldDefinition {
+            name: "id".to_string(),
+            typ: FieldType::Int,
+            nullable: false,
+            source: SourceDefinition::Dynamic,
+        }],
+        primary_index: vec![0],
+    };
+    let bytes = bincode::serialize(&legacy).unwrap();
+    let schema = deserialize_schema(&bytes).unwrap();
+    assert_eq!(
+        schema,
+        Schema {
+            identifier: Some(SchemaIdentifier { id: 1, version: 1 }),
+            fields: vec![FieldDefinition::new(
+                "id".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic
+            )],
+            primary_index: vec![0],
+        }
+    );
+}
+
+#[test]
+fn test_deserialize_schema_with_metadata() {
+    let schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "price".to_string(),
+                FieldType::Decimal,
+                true,
+                SourceDefinition::Dynamic,
+            )
+            .with_metadata(FieldMetadata {
+                source_name: Some("PRICE".to_string()),
+                precision: Some(10),
+                scale: Some(2),
+                description: Some("Price in dollars".to_string()),
+                default_value: Some(Field::Null),
+                ..Default::default()
+            }),
+            false,
+        )
+        .clone();
+    let bytes = bincode::serialize(&schema).unwrap();
+    assert_eq!(deserialize_schema(&bytes).unwrap(), schema);
+    assert!(deserialize_schema(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[test]
+fn test_deserialize_schema_before_generated_values() {
+    let v1 = SchemaV1 {
+        identifier: None,
+        fields: vec![FieldDefinitionV1 {
+            name: "price".to_string(),
+            typ: FieldType::Decimal,
+            nullable: true,
+            source: SourceDefinition::Dynamic,
+            metadata: FieldMetadataV1 {
+                source_name: None,
+                precision: Some(10),
+                scale: Some(2),
+                max_length: None,
+                description: None,
+                default_value: Some(Field::Null),
+            },
+        }],
+        primary_index: vec![],
+    };
+    let bytes = bincode::serialize(&v1).unwrap();
+    let schema = deserialize_schema(&bytes).unwrap();
+    assert_eq!(schema.fields[0].metadata.precision, Some(10));
+    assert_eq!(schema.fields[0].metadata.default_value, Some(Field::Null));
+    assert_eq!(schema.fields[0].metadata.generated, None);
+}
+
+#[test]
+fn test_deserialize_schema_before_timestamp_policy() {
+    let v2 = SchemaV2 {
+        identifier: None,
+        fields: vec![FieldDefinitionV2 {
+            name: "created_at".to_string(),
+            typ: FieldType::Timestamp,
+            nullable: false,
+            source: SourceDefinition::Dynamic,
+            metadata: FieldMetadataV2 {
+                source_name: None,
+                precision: None,
+                scale: None,
+                max_length: None,
+                description: None,
+                default_value: None,
+                generated: Some(GeneratedValue::InsertTimestamp),
+            },
+        }],
+        primary_index: vec![],
+    };
+    let bytes = bincode::serialize(&v2).unwrap();
+    let schema = deserialize_schema(&bytes).unwrap();
+    assert_eq!(
+        schema.fields[0].metadata.generated,
+        Some(GeneratedValue::InsertTimestamp)
+    );
+    assert_eq!(
+        schema.fields[0].metadata.timestamp_policy,
+        TimestampPolicy::PreserveOffset
+    );
+
+    let mut schema = schema;
+    schema.fields[0].metadata.timestamp_policy = TimestampPolicy::NormalizeToUtc;
+    let bytes = bincode::serialize(&schema).unwrap();
+    assert_eq!(deserialize_schema(&bytes).unwrap(), schema);
+}
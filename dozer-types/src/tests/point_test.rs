@@ -0,0 +1,86 @@
+use geo::{HaversineDistance, Point};
+use serde_json::json;
+
+use crate::types::DozerPoint;
+
+fn point(x: f64, y: f64) -> DozerPoint {
+    DozerPoint::from((x, y))
+}
+
+fn assert_close(actual: DozerPoint, expected: DozerPoint) {
+    assert!(
+        (actual.longitude() - expected.longitude()).abs() < 1e-9
+            && (actual.latitude() - expected.latitude()).abs() < 1e-9,
+        "{actual} != {expected}"
+    );
+}
+
+#[test]
+fn test_haversine_distance() {
+    let london = point(-0.1278, 51.5074);
+    let paris = point(2.3522, 48.8566);
+    assert_eq!(
+        london.haversine_distance(&paris),
+        Point::new(-0.1278, 51.5074).haversine_distance(&Point::new(2.3522, 48.8566))
+    );
+    assert!((london.haversine_distance(&paris) - 343_560.0).abs() < 1_000.0);
+    assert_eq!(
+        london.haversine_distance(&paris),
+        paris.haversine_distance(&london)
+    );
+    assert_eq!(london.haversine_distance(&london), 0.0);
+}
+
+#[test]
+fn test_bearing() {
+    let origin = point(0.0, 0.0);
+    for (to, bearing) in [
+        (point(0.0, 10.0), 0.0),
+        (point(10.0, 0.0), 90.0),
+        (point(0.0, -10.0), 180.0),
+        (point(-10.0, 0.0), 270.0),
+    ] {
+        assert!((origin.bearing(&to) - bearing).abs() < 1e-9, "{to}");
+    }
+}
+
+#[test]
+fn test_bounding_box() {
+    let radius = 6_371_008.8 * 1f64.to_radians();
+
+    let (south_west, north_east) = point(0.0, 0.0).bounding_box(radius);
+    assert_close(south_west, point(-1.0, -1.0));
+    assert_close(north_east, point(1.0, 1.0));
+
+    let (south_west, north_east) = point(179.5, 0.0).bounding_box(radius);
+    assert_close(south_west, point(178.5, -1.0));
+    assert_close(north_east, point(-179.5, 1.0));
+
+    let (south_west, north_east) = point(30.0, 89.5).bounding_box(radius);
+    assert_close(south_west, point(-180.0, 88.5));
+    assert_close(north_east, point(180.0, 90.0));
+}
+
+#[test]
+fn test_point_json() {
+    let p = point(1.5, -2.0);
+    let geojson = json!({ "type": "Point", "coordinates": [1.5, -2.0] });
+    assert_eq!(serde_json::to_value(p).unwrap(), geojson);
+    assert_eq!(serde_json::from_value::<DozerPoint>(geojson).unwrap(), p);
+    assert_eq!(
+        serde_json::from_value::<DozerPoint>(json!({ "x": 1.5, "y": -2.0 })).unwrap(),
+        p
+    );
+    assert!(serde_json::from_value::<DozerPoint>(
+        json!({ "type": "LineString", "coordinates": [1.5, -2.0] })
+    )
+    .is_err());
+}
+
+#[test]
+fn test_point_bincode() {
+    let p = point(1.5, -2.0);
+    let bytes = bincode::serialize(&p).unwrap();
+    assert_eq!(bytes, bincode::serialize(&(1.5f64, -2.0f64)).unwrap());
+    assert_eq!(bincode::deserialize::<DozerPoint>(&bytes).unwrap(), p);
+}
@@ -0,0 +1,195 @@
+use chrono::DateTime;
+
+use crate::errors::types::TypeError;
+use crate::types::{
+    Field, FieldDefinition, FieldMetadata, FieldType, GeneratedValue, Record, Schema,
+    SchemaIdentifier, SourceDefinition, TimestampPolicy,
+};
+
+fn schema() -> Schema {
+    let mut schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::UInt,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                "name".to_string(),
+                FieldType::String,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "active".to_string(),
+                FieldType::Boolean,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+    schema.identifier = Some(SchemaIdentifier { id: 1, version: 1 });
+    schema
+}
+
+#[test]
+fn record_builder_orders_values_by_schema() {
+    let schema = schema();
+    let record = Record::builder(&schema)
+        .set("active", true)
+        .unwrap()
+        .set("id", 1_u64)
+        .unwrap()
+        .version(2)
+        .build()
+        .unwrap();
+    assert_eq!(
+        record,
+        Record::new(
+            schema.identifier,
+            vec![Field::UInt(1), Field::Null, Field::Boolean(true)],
+            Some(2)
+        )
+    );
+}
+
+#[test]
+fn record_builder_validates_values() {
+    let schema = schema();
+    let mut builder = Record::builder(&schema);
+    assert!(matches!(
+        builder.set("unknown", 1_u64),
+        Err(TypeError::InvalidFieldName(_))
+    ));
+    assert!(matches!(
+        builder.set("id", 1_i64),
+        Err(TypeError::InvalidFieldValue { .. })
+    ));
+    assert!(matches!(
+        builder.set("id", Field::Null),
+        Err(TypeError::InvalidFieldValue { .. })
+    ));
+    assert!(matches!(
+        builder.set_index(3, true),
+        Err(TypeError::InvalidFieldIndex(3))
+    ));
+    builder.set("name", Field::Null).unwrap();
+    builder.set("id", 1_u64).unwrap();
+    assert!(matches!(
+        builder.build(),
+        Err(TypeError::MissingFieldValue(name)) if name == "active"
+    ));
+}
+
+fn schema_with_omitted_values() -> Schema {
+    let mut schema = schema();
+    schema.fields[1].metadata.default_value = Some(Field::String("unknown".to_string()));
+    schema.fields[2].metadata.default_value = Some(Field::Boolean(true));
+    schema
+        .field(
+            FieldDefinition::new(
+                "ingested_at".to_string(),
+                FieldType::Timestamp,
+                false,
+                SourceDefinition::Dynamic,
+            )
+            .with_metadata(FieldMetadata {
+                generated: Some(GeneratedValue::InsertTimestamp),
+                ..Default::default()
+            }),
+            false,
+        )
+        .clone()
+}
+
+#[test]
+fn record_builder_fills_omitted_values() {
+    let schema = schema_with_omitted_values();
+    let record = Record::builder(&schema)
+        .set("id", 1_u64)
+        .unwrap()
+        .set("active", false)
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(record.values[1], Field::String("unknown".to_string()));
+    assert_eq!(record.values[2], Field::Boolean(false));
+    assert!(record.values[3].as_timestamp().is_some());
+}
+
+#[test]
+fn record_fill_omitted_replaces_nulls() {
+    let schema = schema_with_omitted_values();
+    let mut record = Record::new(
+        schema.identifier,
+        vec![Field::UInt(1), Field::Null, Field::Null, Field::Null],
+        None,
+    );
+    record.fill_omitted(&schema);
+    assert_eq!(record.values[1], Field::String("unknown".to_string()));
+    assert_eq!(record.values[2], Field::Boolean(true));
+    assert!(record.values[3].as_timestamp().is_some());
+
+    assert_eq!(
+        GeneratedValue::InsertDate.generate().get_type(),
+        Some(FieldType::Date)
+    );
+}
+
+#[test]
+fn record_timestamp_policies() {
+    let timestamp = DateTime::parse_from_rfc3339("2023-03-01T10:00:00+07:00").unwrap();
+    let field = |name: &str, timestamp_policy| {
+        FieldDefinition::new(
+            name.to_string(),
+            FieldType::Timestamp,
+            false,
+            SourceDefinition::Dynamic,
+        )
+        .with_metadata(FieldMetadata {
+            timestamp_policy,
+            ..Default::default()
+        })
+    };
+    let schema = Schema::empty()
+        .field(field("preserved", TimestampPolicy::PreserveOffset), false)
+        .field(field("normalized", TimestampPolicy::NormalizeToUtc), false)
+        .clone();
+
+    let offsets = |record: &Record| {
+        record
+            .values
+            .iter()
+            .map(|value| value.as_timestamp().unwrap().to_rfc3339())
+            .collect::<Vec<_>>()
+    };
+    let expected = vec![
+        "2023-03-01T10:00:00+07:00".to_string(),
+        "2023-03-01T03:00:00+00:00".to_string(),
+    ];
+
+    let mut record = Record::new(
+        None,
+        vec![Field::Timestamp(timestamp), Field::Timestamp(timestamp)],
+        None,
+    );
+    record.apply_timestamp_policies(&schema);
+    assert_eq!(offsets(&record), expected);
+
+    let record = Record::builder(&schema)
+        .set("preserved", timestamp)
+        .unwrap()
+        .set("normalized", timestamp)
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(offsets(&record), expected);
+}
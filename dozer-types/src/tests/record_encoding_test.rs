@@ -0,0 +1,111 @@
+use crate::errors::types::DeserializationError;
+use crate::types::{
+    field_test_cases, Field, Record, RecordEnvelope, SchemaIdentifier, FORMAT_VERSION_COMPACT,
+};
+
+fn test_records() -> Vec<Record> {
+    let values: Vec<Field> = field_test_cases().collect();
+    vec![
+        Record::new(None, vec![], None),
+        Record::new(
+            Some(SchemaIdentifier { id: 1, version: 2 }),
+            values.clone(),
+            Some(3),
+        ),
+        Record::new(None, values.iter().rev().cloned().collect(), None),
+        Record::new(None, vec![Field::Null; 9], Some(1)),
+    ]
+}
+
+#[test]
+fn test_record_compact_encoding_roundtrip() {
+    for record in test_records() {
+        let bytes = record.encode_compact();
+        assert_eq!(Record::decode_compact(&bytes).unwrap(), record);
+        assert_eq!(
+            Record::decode_compact_borrow(&bytes).unwrap(),
+            record.borrow()
+        );
+    }
+}
+
+#[test]
+fn test_record_compact_encoding_decodes_bincode() {
+    for record in test_records() {
+        let bytes = bincode::serialize(&record).unwrap();
+        assert_eq!(Record::decode_compact(&bytes).unwrap(), record);
+    }
+}
+
+#[test]
+fn test_record_compact_encoding_is_smaller_than_bincode() {
+    let record = Record::new(
+        None,
+        vec![
+            Field::Int(1),
+            Field::String("name".to_string()),
+            Field::Null,
+            Field::Boolean(true),
+        ],
+        Some(1),
+    );
+    assert!(record.encode_compact().len() < bincode::serialize(&record).unwrap().len());
+}
+
+#[test]
+fn test_record_compact_encoding_rejects_truncated_input() {
+    let record = test_records().swap_remove(1);
+    let bytes = record.encode_compact();
+    assert!(Record::decode_compact(&[]).is_err());
+    for len in 1..bytes.len() {
+        assert!(Record::decode_compact(&bytes[..len]).is_err());
+    }
+}
+
+#[test]
+fn test_record_stored_encoding_roundtrip() {
+    for record in test_records() {
+        let bytes = record.encode_stored();
+        let (envelope, payload) = RecordEnvelope::read(&bytes).unwrap().unwrap();
+        assert_eq!(
+            envelope,
+            RecordEnvelope {
+                format_version: FORMAT_VERSION_COMPACT,
+                schema_version: record
+                    .schema_id
+                    .map_or(0, |schema_id| schema_id.version as u8),
+            }
+        );
+        assert_eq!(payload, record.encode_compact().as_slice());
+        assert_eq!(Record::decode_stored(&bytes).unwrap(), record);
+        assert_eq!(
+            Record::decode_stored_borrow(&bytes).unwrap(),
+            record.borrow()
+        );
+    }
+}
+
+#[test]
+fn test_record_stored_encoding_decodes_unversioned() {
+    for record in test_records() {
+        let compact = record.encode_compact();
+        assert_eq!(RecordEnvelope::read(&compact).unwrap(), None);
+        assert_eq!(Record::decode_stored(&compact).unwrap(), record);
+
+        let bincode = bincode::serialize(&record).unwrap();
+        assert_eq!(RecordEnvelope::read(&bincode).unwrap(), None);
+        assert_eq!(Record::decode_stored(&bincode).unwrap(), record);
+    }
+}
+
+#[test]
+fn test_record_stored_encoding_rejects_unknown_format_version() {
+    let mut bytes = test_records().swap_remove(1).encode_stored();
+    bytes[0] += 1;
+    assert!(matches!(
+        Record::decode_stored(&bytes),
+        Err(DeserializationError::UnsupportedFormatVersion(2))
+    ));
+    assert!(Record::decode_stored(&bytes[..1]).is_err());
+    assert!(Record::decode_stored(&[]).is_err());
+}
@@ -0,0 +1,112 @@
+use crate::types::{
+    Compatibility, FieldDefinition, FieldType, Schema, SchemaChange, SourceDefinition,
+};
+
+fn field(name: &str, typ: FieldType, nullable: bool) -> FieldDefinition {
+    FieldDefinition::new(name.to_string(), typ, nullable, SourceDefinition::Dynamic)
+}
+
+fn schema() -> Schema {
+    Schema::empty()
+        .field(field("id", FieldType::Int, false), true)
+        .field(field("name", FieldType::String, true), false)
+        .clone()
+}
+
+#[test]
+fn identical_schemas_are_fully_compatible() {
+    let mut other = schema();
+    other.identifier = Some(crate::types::SchemaIdentifier { id: 1, version: 2 });
+    let diff = schema().diff(&other);
+    assert!(diff.is_empty());
+    assert_eq!(diff.compatibility(), Compatibility::Full);
+}
+
+#[test]
+fn adding_fields() {
+    let new = schema()
+        .field(field("age", FieldType::UInt, true), false)
+        .clone();
+    let diff = schema().diff(&new);
+    assert_eq!(
+        diff.changes,
+        vec![SchemaChange::FieldAdded {
+            index: 2,
+            field: field("age", FieldType::UInt, true)
+        }]
+    );
+    assert_eq!(diff.compatibility(), Compatibility::Full);
+
+    let new = schema()
+        .field(field("age", FieldType::UInt, false), false)
+        .clone();
+    assert_eq!(schema().diff(&new).compatibility(), Compatibility::Forward);
+}
+
+#[test]
+fn removing_fields() {
+    let new = Schema::empty()
+        .field(field("id", FieldType::Int, false), true)
+        .clone();
+    assert_eq!(schema().diff(&new).compatibility(), Compatibility::Full);
+    assert_eq!(new.diff(&schema()).compatibility(), Compatibility::Full);
+
+    let new = Schema::empty()
+        .field(field("name", FieldType::String, true), true)
+        .clone();
+    let diff = schema().diff(&new);
+    assert!(diff.changes.contains(&SchemaChange::FieldRemoved {
+        index: 0,
+        field: field("id", FieldType::Int, false)
+    }));
+    assert!(diff.changes.contains(&SchemaChange::FieldMoved {
+        name: "name".to_string(),
+        from: 1,
+        to: 0
+    }));
+    assert_eq!(diff.compatibility(), Compatibility::Breaking);
+}
+
+#[test]
+fn changing_types_and_nullability() {
+    let mut new = schema();
+    new.fields[0].typ = FieldType::Decimal;
+    let diff = schema().diff(&new);
+    assert_eq!(
+        diff.changes,
+        vec![SchemaChange::TypeChanged {
+            name: "id".to_string(),
+            from: FieldType::Int,
+            to: FieldType::Decimal
+        }]
+    );
+    assert_eq!(diff.compatibility(), Compatibility::Backward);
+    assert_eq!(new.diff(&schema()).compatibility(), Compatibility::Forward);
+
+    new.fields[1].typ = FieldType::Binary;
+    assert_eq!(schema().diff(&new).compatibility(), Compatibility::Breaking);
+
+    let mut new = schema();
+    new.fields[1].typ = FieldType::Text;
+    assert_eq!(schema().diff(&new).compatibility(), Compatibility::Full);
+
+    let mut new = schema();
+    new.fields[0].nullable = true;
+    assert_eq!(schema().diff(&new).compatibility(), Compatibility::Backward);
+    assert_eq!(new.diff(&schema()).compatibility(), Compatibility::Forward);
+}
+
+#[test]
+fn changing_primary_key_is_breaking() {
+    let mut new = schema();
+    new.primary_index = vec![0, 1];
+    let diff = schema().diff(&new);
+    assert_eq!(
+        diff.changes,
+        vec![SchemaChange::PrimaryKeyChanged {
+            from: vec!["id".to_string()],
+            to: vec!["id".to_string(), "name".to_string()]
+        }]
+    );
+    assert_eq!(diff.compatibility(), Compatibility::Breaking);
+}
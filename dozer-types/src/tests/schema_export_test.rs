@@ -0,0 +1,84 @@
+use serde_json::json;
+
+use crate::types::{Field, FieldDefinition, FieldMetadata, FieldType, Schema, SourceDefinition};
+
+fn field(name: &str, typ: FieldType, nullable: bool) -> FieldDefinition {
+    FieldDefinition::new(name.to_string(), typ, nullable, SourceDefinition::Dynamic)
+}
+
+fn schema() -> Schema {
+    Schema::empty()
+        .field(field("id", FieldType::UInt, false), true)
+        .field(
+            field("name", FieldType::String, true).with_metadata(FieldMetadata {
+                max_length: Some(64),
+                description: Some("Display name".to_string()),
+                ..Default::default()
+            }),
+            false,
+        )
+        .field(
+            field("price", FieldType::Decimal, false).with_metadata(FieldMetadata {
+                precision: Some(10),
+                scale: Some(2),
+                default_value: Some(Field::Int(0)),
+                ..Default::default()
+            }),
+            false,
+        )
+        .field(field("created \"at\"", FieldType::Timestamp, false), true)
+        .clone()
+}
+
+#[test]
+fn test_to_json_schema() {
+    let json_schema = schema().to_json_schema("products");
+    assert_eq!(
+        json_schema,
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "products",
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "minimum": 0 },
+                "name": {
+                    "anyOf": [{ "type": "string", "maxLength": 64 }, { "type": "null" }],
+                    "description": "Display name",
+                },
+                "price": {
+                    "type": "string",
+                    "pattern": "^-?[0-9]+(\\.[0-9]+)?$",
+                    "default": 0,
+                },
+                "created \"at\"": { "type": "string", "format": "date-time" },
+            },
+            "required": ["id", "name", "price", "created \"at\""],
+            "additionalProperties": false,
+        })
+    );
+}
+
+#[test]
+fn test_to_create_table() {
+    assert_eq!(
+        schema().to_create_table("products"),
+        "CREATE TABLE \"products\" (\n    \
+            \"id\" NUMERIC(20) NOT NULL,\n    \
+            \"name\" VARCHAR(64),\n    \
+            \"price\" NUMERIC(10, 2) NOT NULL,\n    \
+            \"created \"\"at\"\"\" TIMESTAMPTZ NOT NULL,\n    \
+            PRIMARY KEY (\"id\", \"created \"\"at\"\"\")\n\
+        )"
+    );
+}
+
+#[test]
+fn test_to_create_table_without_primary_key() {
+    let schema = Schema::empty()
+        .field(field("doc", FieldType::Bson, true), false)
+        .clone();
+    assert_eq!(
+        schema.to_create_table("docs"),
+        "CREATE TABLE \"docs\" (\n    \"doc\" JSONB\n)"
+    );
+}
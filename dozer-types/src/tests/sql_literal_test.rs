@@ -0,0 +1,124 @@
+use chrono::{DateTime, NaiveDate};
+use ordered_float::OrderedFloat;
+use rust_decimal::Decimal;
+
+use crate::types::{DozerDuration, DozerPoint, Field, FieldType};
+
+#[test]
+fn test_sql_literals() {
+    let timestamp = |s: &str| Field::Timestamp(DateTime::parse_from_rfc3339(s).unwrap());
+    let cases = [
+        ("NULL", FieldType::Int, Field::Null),
+        (" null ", FieldType::String, Field::Null),
+        ("42", FieldType::UInt, Field::UInt(42)),
+        ("-42", FieldType::Int, Field::Int(-42)),
+        ("'7'", FieldType::Int, Field::Int(7)),
+        ("1.5", FieldType::Float, Field::Float(OrderedFloat(1.5))),
+        ("1e3", FieldType::Float, Field::Float(OrderedFloat(1000.0))),
+        (
+            "12.50",
+            FieldType::Decimal,
+            Field::Decimal(Decimal::new(1250, 2)),
+        ),
+        (
+            "1.5e2",
+            FieldType::Decimal,
+            Field::Decimal(Decimal::new(150, 0)),
+        ),
+        ("TRUE", FieldType::Boolean, Field::Boolean(true)),
+        ("false", FieldType::Boolean, Field::Boolean(false)),
+        ("'t'", FieldType::Boolean, Field::Boolean(true)),
+        (
+            "'dozer'",
+            FieldType::String,
+            Field::String("dozer".to_string()),
+        ),
+        ("'it''s'", FieldType::Text, Field::Text("it's".to_string())),
+        ("''", FieldType::String, Field::String("".to_string())),
+        (
+            "X'DEAD'",
+            FieldType::Binary,
+            Field::Binary(vec![0xde, 0xad]),
+        ),
+        ("x'00ff'", FieldType::Bson, Field::Bson(vec![0, 255])),
+        (
+            "'\\xbeef'",
+            FieldType::Binary,
+            Field::Binary(vec![0xbe, 0xef]),
+        ),
+        (
+            "'2020-01-01T12:00:00+05:30'",
+            FieldType::Timestamp,
+            timestamp("2020-01-01T12:00:00+05:30"),
+        ),
+        (
+            "TIMESTAMP '2020-01-01 12:00:00.25'",
+            FieldType::Timestamp,
+            timestamp("2020-01-01T12:00:00.25Z"),
+        ),
+        (
+            "timestamptz '2020-01-01 12:00:00+02'",
+            FieldType::Timestamp,
+            timestamp("2020-01-01T12:00:00+02:00"),
+        ),
+        (
+            "'2020-01-01'",
+            FieldType::Timestamp,
+            timestamp("2020-01-01T00:00:00Z"),
+        ),
+        (
+            "DATE '2020-02-29'",
+            FieldType::Date,
+            Field::Date(NaiveDate::from_ymd_opt(2020, 2, 29).unwrap()),
+        ),
+        (
+            "'(1.5,2)'",
+            FieldType::Point,
+            Field::Point(DozerPoint::from((1.5, 2.0))),
+        ),
+        (
+            "INTERVAL '1 hour 30 minutes'",
+            FieldType::Duration,
+            Field::Duration(DozerDuration::from_secs(5400).unwrap()),
+        ),
+    ];
+    for (literal, typ, expected) in cases {
+        assert_eq!(
+            Field::from_sql_literal(literal, typ).unwrap(),
+            expected,
+            "{literal}"
+        );
+    }
+}
+
+#[test]
+fn test_invalid_sql_literals() {
+    let cases = [
+        ("", FieldType::Int),
+        ("1.5", FieldType::Int),
+        ("-1", FieldType::UInt),
+        ("DATE '1'", FieldType::Int),
+        ("abc", FieldType::Float),
+        ("yes", FieldType::Boolean),
+        ("1", FieldType::Boolean),
+        ("dozer", FieldType::String),
+        ("'dozer", FieldType::String),
+        ("'it's'", FieldType::String),
+        ("DATE 'dozer'", FieldType::String),
+        ("'DEAD'", FieldType::Binary),
+        ("X'DEA'", FieldType::Binary),
+        ("X'ZZ'", FieldType::Binary),
+        ("'2020-13-01'", FieldType::Date),
+        ("TIMESTAMP '2020-01-01'", FieldType::Date),
+        ("'noon'", FieldType::Timestamp),
+        ("2020-01-01", FieldType::Date),
+        ("'1 fortnight'", FieldType::Duration),
+        ("'00'", FieldType::Geometry),
+    ];
+    for (literal, typ) in cases {
+        assert!(
+            Field::from_sql_literal(literal, typ).is_err(),
+            "{literal} as {typ}"
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+
+use crate::models::{
+    app_config::Config,
+    telemetry::{
+        BatchExportConfig, BatchExportRuntime, CompactLogFormat, DailyRotation, DatadogConfig,
+        ExporterConfig, FileLogConfig, JaegerConfig, JsonLogFormat, LogFormat, LogRotationPeriod,
+        RateLimitedSamplerConfig, RatioSamplerConfig, SentryConfig, StdoutExporterConfig,
+        TelemetryConfig, TelemetryExporter, TelemetrySampler, TokioConsoleConfig, TokioRuntime,
+    },
+};
+
+#[test]
+fn test_telemetry_config() {
+    let input_config = r#"
+  app_name: working_app
+  telemetry:
+    service_name: orders
+    exporter: !Jaeger
+      endpoint: jaeger:6831
+    sampling_ratio: 0.25
+    resource_attributes:
+      deployment.environment: staging
+    log_format: !Compact {}
+    log_filter: info,dozer_storage=warn,dozer_cache::query=debug
+    sampler: !RateLimited
+      traces_per_second: 10
+    parent_based: false
+    lmdb_gauges_interval_secs: 0
+    redacted_fields: [password, ssn]
+    slow_query_threshold_ms: 500
+    batch_export:
+      runtime: !Tokio {}
+      max_queue_size: 8192
+      scheduled_delay_ms: 1000
+"#;
+    let config = serde_yaml::from_str::<Config>(input_config).unwrap();
+    assert_eq!(
+        config.telemetry,
+        Some(TelemetryConfig {
+            service_name: "orders".to_string(),
+            exporter: Some(TelemetryExporter::Jaeger(JaegerConfig {
+                endpoint: Some("jaeger:6831".to_string()),
+            })),
+            sampling_ratio: 0.25,
+            resource_attributes: BTreeMap::from([(
+                "deployment.environment".to_string(),
+                "staging".to_string()
+            )]),
+            log_format: Some(LogFormat::Compact(CompactLogFormat {})),
+            batch_export: Some(BatchExportConfig {
+                runtime: Some(BatchExportRuntime::Tokio(TokioRuntime {})),
+                max_queue_size: 8192,
+                scheduled_delay_ms: 1000,
+                max_export_batch_size: 512,
+            }),
+            file: None,
+            log_filter: "info,dozer_storage=warn,dozer_cache::query=debug".to_string(),
+            sampler: Some(TelemetrySampler::RateLimited(RateLimitedSamplerConfig {
+                traces_per_second: 10.0,
+            })),
+            parent_based: false,
+            lmdb_gauges_interval_secs: 0,
+            tokio_console: None,
+            redacted_fields: vec!["password".to_string(), "ssn".to_string()],
+            sentry: None,
+            exporters: vec![],
+            stdout_log_filter: None,
+            trace_filter: None,
+            slow_query_threshold_ms: Some(500),
+        })
+    );
+}
+
+#[test]
+fn test_partial_telemetry_config() {
+    let input_config = r#"
+  app_name: working_app
+  telemetry:
+    exporter: !Stdout {}
+"#;
+    let telemetry = serde_yaml::from_str::<Config>(input_config)
+        .unwrap()
+        .telemetry
+        .unwrap();
+    assert_eq!(telemetry.service_name, "dozer");
+    assert_eq!(telemetry.sampling_ratio, 1.0);
+    assert!(telemetry.resource_attributes.is_empty());
+    assert_eq!(telemetry.log_format, None);
+    assert_eq!(telemetry.batch_export, None);
+    assert_eq!(telemetry.log_filter, "info");
+    assert_eq!(telemetry.sampler, None);
+    assert!(telemetry.parent_based);
+    assert_eq!(telemetry.lmdb_gauges_interval_secs, 30);
+    assert!(matches!(
+        telemetry.exporter,
+        Some(TelemetryExporter::Stdout(_))
+    ));
+}
+
+#[test]
+fn test_json_log_format() {
+    let input_config = r#"
+  app_name: working_app
+  telemetry:
+    log_format: !Json {}
+"#;
+    let telemetry = serde_yaml::from_str::<Config>(input_config)
+        .unwrap()
+        .telemetry
+        .unwrap();
+    assert_eq!(
+        telemetry.log_format,
+        Some(LogFormat::Json(JsonLogFormat {}))
+    );
+    assert_eq!(telemetry.exporter, None);
+}
+
+#[test]
+fn test_file_log_config() {
+    let input_config = r#"
+  app_name: working_app
+  telemetry:
+    file:
+      directory: /var/log/dozer
+      rotation_period: !Daily {}
+      max_file_size_mb: 100
+      log_filter: debug
+"#;
+    let telemetry = serde_yaml::from_str::<Config>(input_config)
+        .unwrap()
+        .telemetry
+        .unwrap();
+    assert_eq!(
+        telemetry.file,
+        Some(FileLogConfig {
+            directory: "/var/log/dozer".to_string(),
+            file_name: "dozer.log".to_string(),
+            rotation_period: Some(LogRotationPeriod::Daily(DailyRotation {})),
+            max_file_size_mb: Some(100),
+            max_files: 7,
+            log_filter: Some("debug".to_string()),
+        })
+    );
+}
+
+#[test]
+fn test_ratio_sampler_config() {
+    let telemetry =
+        serde_yaml::from_str::<TelemetryConfig>("sampler: !Ratio\n  ratio: 0.01").unwrap();
+    assert_eq!(
+        telemetry.sampler,
+        Some(TelemetrySampler::Ratio(RatioSamplerConfig { ratio: 0.01 }))
+    );
+    assert!(telemetry.parent_based);
+}
+
+#[test]
+fn test_tokio_console_config() {
+    let telemetry =
+        serde_yaml::from_str::<TelemetryConfig>("tokio_console:\n  server_addr: 0.0.0.0:6669")
+            .unwrap();
+    assert_eq!(
+        telemetry.tokio_console,
+        Some(TokioConsoleConfig {
+            server_addr: Some("0.0.0.0:6669".to_string()),
+        })
+    );
+    let telemetry = serde_yaml::from_str::<TelemetryConfig>("tokio_console: {}").unwrap();
+    assert_eq!(
+        telemetry.tokio_console,
+        Some(TokioConsoleConfig { server_addr: None })
+    );
+}
+
+#[test]
+fn test_datadog_exporter_config() {
+    let input_config = r#"
+  exporter: !Datadog
+    agent_endpoint: http://datadog-agent:8126
+    env: production
+"#;
+    let telemetry = serde_yaml::from_str::<TelemetryConfig>(input_config).unwrap();
+    assert_eq!(
+        telemetry.exporter,
+        Some(TelemetryExporter::Datadog(DatadogConfig {
+            agent_endpoint: Some("http://datadog-agent:8126".to_string()),
+            env: Some("production".to_string()),
+            version: None,
+        }))
+    );
+}
+
+#[test]
+fn test_sentry_config() {
+    let input_config = r#"
+  sentry:
+    dsn: https://key@sentry.example.com/1
+    environment: staging
+"#;
+    let telemetry = serde_yaml::from_str::<TelemetryConfig>(input_config).unwrap();
+    assert_eq!(
+        telemetry.sentry,
+        Some(SentryConfig {
+            dsn: "https://key@sentry.example.com/1".to_string(),
+            environment: Some("staging".to_string()),
+            release: None,
+        })
+    );
+}
+
+#[test]
+fn test_multiple_exporters_config() {
+    let input_config = r#"
+  exporter: !Stdout {}
+  exporters:
+    - exporter: !Jaeger {}
+    - exporter: !Datadog {}
+  stdout_log_filter: warn
+  trace_filter: info,dozer_cache=debug
+"#;
+    let telemetry = serde_yaml::from_str::<TelemetryConfig>(input_config).unwrap();
+    assert_eq!(
+        telemetry.exporter,
+        Some(TelemetryExporter::Stdout(StdoutExporterConfig {}))
+    );
+    assert_eq!(
+        telemetry.exporters,
+        vec![
+            ExporterConfig {
+                exporter: Some(TelemetryExporter::Jaeger(JaegerConfig { endpoint: None })),
+            },
+            ExporterConfig {
+                exporter: Some(TelemetryExporter::Datadog(DatadogConfig::default())),
+            },
+        ]
+    );
+    assert_eq!(telemetry.stdout_log_filter, Some("warn".to_string()));
+    assert_eq!(
+        telemetry.trace_filter,
+        Some("info,dozer_cache=debug".to_string())
+    );
+}
+
+#[test]
+fn test_config_without_telemetry_config() {
+    let config = serde_yaml::from_str::<Config>("app_name: working_app").unwrap();
+    assert_eq!(config.telemetry, None);
+    assert_eq!(
+        TelemetryConfig::default(),
+        serde_yaml::from_str::<TelemetryConfig>("{}").unwrap()
+    );
+}
+
+#[test]
+fn test_default_batch_export_config() {
+    let batch_export = serde_yaml::from_str::<BatchExportConfig>("{}").unwrap();
+    assert_eq!(batch_export, BatchExportConfig::default());
+    assert_eq!(batch_export.runtime, None);
+    assert_eq!(batch_export.max_queue_size, 2048);
+    assert_eq!(batch_export.scheduled_delay_ms, 5000);
+    assert_eq!(batch_export.max_export_batch_size, 512);
+}
@@ -0,0 +1,61 @@
+use crate::errors::types::TypeError;
+use crate::types::{Field, Record, UpdateDelta};
+
+fn record(values: Vec<Field>) -> Record {
+    Record::new(None, values, None)
+}
+
+#[test]
+fn update_delta_contains_changed_fields() {
+    let old = record(vec![
+        Field::Int(1),
+        Field::String("a".to_string()),
+        Field::Null,
+        Field::Boolean(true),
+    ]);
+    let new = record(vec![
+        Field::Int(1),
+        Field::String("b".to_string()),
+        Field::Float(1.5.into()),
+        Field::Boolean(true),
+    ]);
+
+    let delta = UpdateDelta::new(&old, &new);
+    assert_eq!(delta.indexes, vec![1, 2]);
+    assert_eq!(
+        delta.values,
+        vec![Field::String("b".to_string()), Field::Float(1.5.into())]
+    );
+
+    let mut record = old.clone();
+    delta.apply(&mut record).unwrap();
+    assert_eq!(record, new);
+
+    assert!(UpdateDelta::new(&old, &old).is_empty());
+}
+
+#[test]
+fn update_delta_serde_roundtrip() {
+    let delta = UpdateDelta {
+        indexes: vec![0, 3],
+        values: vec![Field::UInt(1), Field::Null],
+    };
+    let bytes = bincode::serialize(&delta).unwrap();
+    assert_eq!(bincode::deserialize::<UpdateDelta>(&bytes).unwrap(), delta);
+    let json = serde_json::to_string(&delta).unwrap();
+    assert_eq!(serde_json::from_str::<UpdateDelta>(&json).unwrap(), delta);
+}
+
+#[test]
+fn update_delta_out_of_range_is_not_applied() {
+    let delta = UpdateDelta {
+        indexes: vec![0, 2],
+        values: vec![Field::Int(2), Field::Int(3)],
+    };
+    let mut record = record(vec![Field::Int(1), Field::Int(1)]);
+    assert!(matches!(
+        delta.apply(&mut record),
+        Err(TypeError::InvalidFieldIndex(2))
+    ));
+    assert_eq!(record.values, vec![Field::Int(1), Field::Int(1)]);
+}
@@ -0,0 +1,466 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+use num_bigint::BigInt;
+use ordered_float::OrderedFloat;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+
+use crate::errors::types::ArithmeticError;
+
+use super::{DozerDecimal128, Field, FieldType};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArithmeticOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Neg,
+}
+
+impl Display for ArithmeticOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ArithmeticOperator::Add => "+",
+            ArithmeticOperator::Sub => "-",
+            ArithmeticOperator::Mul => "*",
+            ArithmeticOperator::Div => "/",
+            ArithmeticOperator::Rem => "%",
+            ArithmeticOperator::Neg => "-",
+        })
+    }
+}
+
+impl Field {
+    /// Adds `rhs` to the field, see `checked_div` for how the operands are converted.
+    ///
+    /// Durations can also be added to timestamps and durations.
+    pub fn checked_add(&self, rhs: &Field) -> Result<Field, ArithmeticError> {
+        self.checked_binary(ArithmeticOperator::Add, rhs)
+    }
+
+    /// Subtracts `rhs` from the field, see `checked_div` for how the operands are converted.
+    ///
+    /// Durations can also be subtracted from timestamps and durations. Subtracting two timestamps gives the
+    /// milliseconds between them as an `Int`.
+    pub fn checked_sub(&self, rhs: &Field) -> Result<Field, ArithmeticError> {
+        self.checked_binary(ArithmeticOperator::Sub, rhs)
+    }
+
+    /// Multiplies the field by `rhs`, see `checked_div` for how the operands are converted.
+    pub fn checked_mul(&self, rhs: &Field) -> Result<Field, ArithmeticError> {
+        self.checked_binary(ArithmeticOperator::Mul, rhs)
+    }
+
+    /// Divides the field by `rhs`.
+    ///
+    /// `Null` operands give `Null`. Numbers of different types are converted to a common type first:
+    ///
+    /// | Operands | Type |
+    /// |---|---|
+    /// | A `Decimal128` and any number | `Decimal128`, `Float`s that can't be represented fail with `PrecisionLoss` |
+    /// | A `Decimal` and any number | `Decimal`, `Float`s that can't be represented fail with `PrecisionLoss` |
+    /// | A `Float` and any other number | `Float` |
+    /// | Two integers in a division | `Float` |
+    /// | `UInt` and `UInt` otherwise | `UInt` |
+    /// | `Int` and `Int` or `UInt` otherwise | `Int`, `UInt`s above `i64::MAX` fail with `PrecisionLoss` |
+    ///
+    /// `Float`s follow IEEE 754. Integer and decimal results that don't fit fail with `Overflow`, and integer remainders
+    /// and decimal divisions by zero fail with `DivisionByZero`. `Decimal128`s are divided as `Decimal`s, so dividing
+    /// those that don't fit in `Decimal` fails with `Overflow`.
+    pub fn checked_div(&self, rhs: &Field) -> Result<Field, ArithmeticError> {
+        self.checked_binary(ArithmeticOperator::Div, rhs)
+    }
+
+    /// The remainder of dividing the field by `rhs`, see `checked_div` for how the operands are converted.
+    pub fn checked_rem(&self, rhs: &Field) -> Result<Field, ArithmeticError> {
+        self.checked_binary(ArithmeticOperator::Rem, rhs)
+    }
+
+    /// Negates numbers and durations. `UInt`s other than zero fail with `UnaryOverflow`.
+    pub fn checked_neg(&self) -> Result<Field, ArithmeticError> {
+        let op = ArithmeticOperator::Neg;
+        let overflow = || ArithmeticError::UnaryOverflow {
+            op,
+            operand: self.clone(),
+        };
+        match self {
+            Field::Null => Ok(Field::Null),
+            Field::UInt(0) => Ok(Field::UInt(0)),
+            Field::UInt(_) => Err(overflow()),
+            Field::Int(i) => i.checked_neg().map(Field::Int).ok_or_else(overflow),
+            Field::Float(f) => Ok(Field::Float(-*f)),
+            Field::Decimal(d) => Ok(Field::Decimal(-*d)),
+            Field::Decimal128(d) => Ok(Field::Decimal128(-*d)),
+            Field::Duration(d) => d.checked_neg().map(Field::Duration).ok_or_else(overflow),
+            _ => Err(ArithmeticError::UnsupportedType {
+                op,
+                operand: self.clone(),
+            }),
+        }
+    }
+
+    /// Compares the field with `other`, or returns `None` if either is `Null`.
+    ///
+    /// Numbers of any type compare by their values, and so do strings and texts. Other fields only compare with
+    /// fields of their own type, and `Bson` and `Geometry` don't compare at all.
+    pub fn checked_cmp(&self, other: &Field) -> Result<Option<Ordering>, ArithmeticError> {
+        let ordering = match (self, other) {
+            (Field::Null, _) | (_, Field::Null) => return Ok(None),
+            (Field::String(l) | Field::Text(l), Field::String(r) | Field::Text(r)) => l.cmp(r),
+            (Field::Boolean(l), Field::Boolean(r)) => l.cmp(r),
+            (Field::Binary(l), Field::Binary(r)) => l.cmp(r),
+            (Field::Timestamp(l), Field::Timestamp(r)) => l.cmp(r),
+            (Field::Date(l), Field::Date(r)) => l.cmp(r),
+            (Field::Point(l), Field::Point(r)) => l.cmp(r),
+            (Field::Duration(l), Field::Duration(r)) => l.cmp(r),
+            _ => match (Number::from_field(self), Number::from_field(other)) {
+                (Some(l), Some(r)) => l.cmp(r),
+                _ => {
+                    return Err(ArithmeticError::Incomparable {
+                        left: self.clone(),
+                        right: other.clone(),
+                    })
+                }
+            },
+        };
+        Ok(Some(ordering))
+    }
+
+    /// Compares numeric fields by their exact values, or returns `None` if either is not a number.
+    ///
+    /// Unlike the derived `Ord`, which sorts `UInt(2)` before `Int(1)`, `UInt`, `Int`, `Float`, `Decimal` and
+    /// `Decimal128` compare by value without converting to a common type, so `Int(i64::MAX)` is greater than
+    /// `Float(9.223372036854775e18)`. `NaN` is greater than every number and `-0.0` equals `0.0`, as in `OrderedFloat`.
+    ///
+    /// Equal values then compare as the derived `Ord` does, by type and then by scale, so the order is total and agrees
+    /// with `Eq`: `Int(1) < Float(1.0)`. Use it for sort keys that mix numeric types.
+    pub fn cmp_numeric(&self, other: &Field) -> Option<Ordering> {
+        let (l, r) = (Number::from_field(self)?, Number::from_field(other)?);
+        Some(l.cmp_exact(r).then_with(|| self.cmp(other)))
+    }
+
+    fn checked_binary(
+        &self,
+        op: ArithmeticOperator,
+        rhs: &Field,
+    ) -> Result<Field, ArithmeticError> {
+        use ArithmeticOperator::{Add, Div, Mul, Neg, Rem, Sub};
+
+        let unsupported = || ArithmeticError::UnsupportedTypes {
+            op,
+            left: self.clone(),
+            right: rhs.clone(),
+        };
+        let overflow = || ArithmeticError::Overflow {
+            op,
+            left: self.clone(),
+            right: rhs.clone(),
+        };
+        let division_by_zero = || ArithmeticError::DivisionByZero {
+            op,
+            left: self.clone(),
+            right: rhs.clone(),
+        };
+
+        match (self, rhs) {
+            (Field::Null, _) | (_, Field::Null) => return Ok(Field::Null),
+            (Field::Timestamp(l), Field::Timestamp(r)) => {
+                return match op {
+                    Sub => Ok(Field::Int((*l - *r).num_milliseconds())),
+                    _ => Err(unsupported()),
+                }
+            }
+            (Field::Timestamp(l), Field::Duration(r)) => {
+                let result = match op {
+                    Add => r.add_to_timestamp(*l),
+                    Sub => r.sub_from_timestamp(*l),
+                    _ => return Err(unsupported()),
+                };
+                return result.map(Field::Timestamp).ok_or_else(overflow);
+            }
+            (Field::Duration(l), Field::Duration(r)) => {
+                let result = match op {
+                    Add => l.checked_add(r),
+                    Sub => l.checked_sub(r),
+                    _ => return Err(unsupported()),
+                };
+                return result.map(Field::Duration).ok_or_else(overflow);
+            }
+            (Field::Duration(l), Field::Timestamp(r)) if op == Add => {
+                return l
+                    .add_to_timestamp(*r)
+                    .map(Field::Timestamp)
+                    .ok_or_else(overflow);
+            }
+            _ => (),
+        }
+
+        let (Some(l), Some(r)) = (Number::from_field(self), Number::from_field(rhs)) else {
+            return Err(unsupported());
+        };
+        let to_int = |value: u64, field: &Field| {
+            i64::try_from(value).map_err(|_| ArithmeticError::PrecisionLoss {
+                value: field.clone(),
+                to: FieldType::Int,
+            })
+        };
+        let operands = match (l, r) {
+            (Number::Decimal128(_), _) | (_, Number::Decimal128(_)) => {
+                let to_decimal128 = |number: Number, field: &Field| {
+                    number
+                        .to_decimal128()
+                        .ok_or_else(|| ArithmeticError::PrecisionLoss {
+                            value: field.clone(),
+                            to: FieldType::Decimal128,
+                        })
+                };
+                Operands::Decimal128(to_decimal128(l, self)?, to_decimal128(r, rhs)?)
+            }
+            (Number::Decimal(_), _) | (_, Number::Decimal(_)) => {
+                let to_decimal = |number: Number, field: &Field| {
+                    number
+                        .to_decimal()
+                        .ok_or_else(|| ArithmeticError::PrecisionLoss {
+                            value: field.clone(),
+                            to: FieldType::Decimal,
+                        })
+                };
+                Operands::Decimal(to_decimal(l, self)?, to_decimal(r, rhs)?)
+            }
+            (Number::Float(_), _) | (_, Number::Float(_)) => {
+                Operands::Float(l.to_f64(), r.to_f64())
+            }
+            _ if op == Div => Operands::Float(l.to_f64(), r.to_f64()),
+            (Number::UInt(l), Number::UInt(r)) => Operands::UInt(l, r),
+            (Number::UInt(l), Number::Int(r)) => Operands::Int(to_int(l, self)?, r),
+            (Number::Int(l), Number::UInt(r)) => Operands::Int(l, to_int(r, rhs)?),
+            (Number::Int(l), Number::Int(r)) => Operands::Int(l, r),
+        };
+
+        match operands {
+            Operands::UInt(l, r) => match op {
+                Add => l.checked_add(r),
+                Sub => l.checked_sub(r),
+                Mul => l.checked_mul(r),
+                Rem if r == 0 => return Err(division_by_zero()),
+                Rem => l.checked_rem(r),
+                Div | Neg => unreachable!("Integers are divided as floats and {op} is unary"),
+            }
+            .map(Field::UInt)
+            .ok_or_else(overflow),
+            Operands::Int(l, r) => match op {
+                Add => l.checked_add(r),
+                Sub => l.checked_sub(r),
+                Mul => l.checked_mul(r),
+                Rem if r == 0 => return Err(division_by_zero()),
+                Rem => l.checked_rem(r),
+                Div | Neg => unreachable!("Integers are divided as floats and {op} is unary"),
+            }
+            .map(Field::Int)
+            .ok_or_else(overflow),
+            Operands::Float(l, r) => Ok(Field::Float(OrderedFloat(match op {
+                Add => l + r,
+                Sub => l - r,
+                Mul => l * r,
+                Div => l / r,
+                Rem => l % r,
+                Neg => unreachable!("{op} is unary"),
+            }))),
+            Operands::Decimal(l, r) => match op {
+                Add => l.checked_add(r),
+                Sub => l.checked_sub(r),
+                Mul => l.checked_mul(r),
+                Div | Rem if r.is_zero() => return Err(division_by_zero()),
+                Div => l.checked_div(r),
+                Rem => l.checked_rem(r),
+                Neg => unreachable!("{op} is unary"),
+            }
+            .map(Field::Decimal)
+            .ok_or_else(overflow),
+            Operands::Decimal128(l, r) => match op {
+                Add => l.checked_add(r),
+                Sub => l.checked_sub(r),
+                Mul => l.checked_mul(r),
+                Div | Rem if r.mantissa() == 0 => return Err(division_by_zero()),
+                Div => l.checked_div(r),
+                Rem => l.checked_rem(r),
+                Neg => unreachable!("{op} is unary"),
+            }
+            .map(Field::Decimal128)
+            .ok_or_else(overflow),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Number {
+    UInt(u64),
+    Int(i64),
+    Float(f64),
+    Decimal(Decimal),
+    Decimal128(DozerDecimal128),
+}
+
+impl Number {
+    fn from_field(field: &Field) -> Option<Self> {
+        match field {
+            Field::UInt(u) => Some(Number::UInt(*u)),
+            Field::Int(i) => Some(Number::Int(*i)),
+            Field::Float(f) => Some(Number::Float(f.0)),
+            Field::Decimal(d) => Some(Number::Decimal(*d)),
+            Field::Decimal128(d) => Some(Number::Decimal128(*d)),
+            _ => None,
+        }
+    }
+
+    fn to_f64(self) -> f64 {
+        match self {
+            Number::UInt(u) => u as f64,
+            Number::Int(i) => i as f64,
+            Number::Float(f) => f,
+            Number::Decimal(d) => d.to_f64().unwrap_or(f64::NAN),
+            Number::Decimal128(d) => d.to_f64(),
+        }
+    }
+
+    fn to_decimal(self) -> Option<Decimal> {
+        match self {
+            Number::UInt(u) => Some(Decimal::from(u)),
+            Number::Int(i) => Some(Decimal::from(i)),
+            Number::Float(f) => Decimal::from_f64(f),
+            Number::Decimal(d) => Some(d),
+            Number::Decimal128(d) => d.to_decimal(),
+        }
+    }
+
+    fn to_decimal128(self) -> Option<DozerDecimal128> {
+        match self {
+            Number::UInt(u) => DozerDecimal128::new(u as i128, 0).ok(),
+            Number::Int(i) => DozerDecimal128::new(i as i128, 0).ok(),
+            Number::Float(f) if f.is_finite() => f.to_string().parse().ok(),
+            Number::Float(_) => None,
+            Number::Decimal(d) => Some(DozerDecimal128::from(d)),
+            Number::Decimal128(d) => Some(d),
+        }
+    }
+
+    fn cmp(self, other: Number) -> Ordering {
+        let cmp_f64 = || OrderedFloat(self.to_f64()).cmp(&OrderedFloat(other.to_f64()));
+        match (self, other) {
+            // Floats that aren't decimals, such as infinities, still compare as floats.
+            (Number::Decimal128(_), _) | (_, Number::Decimal128(_)) => {
+                match (self.to_decimal128(), other.to_decimal128()) {
+                    (Some(l), Some(r)) => l.cmp_value(&r),
+                    _ => cmp_f64(),
+                }
+            }
+            (Number::Decimal(_), _) | (_, Number::Decimal(_)) => {
+                match (self.to_decimal(), other.to_decimal()) {
+                    (Some(l), Some(r)) => l.cmp(&r),
+                    _ => cmp_f64(),
+                }
+            }
+            (Number::Float(_), _) | (_, Number::Float(_)) => cmp_f64(),
+            (Number::UInt(l), Number::UInt(r)) => l.cmp(&r),
+            (Number::Int(l), Number::Int(r)) => l.cmp(&r),
+            (Number::UInt(l), Number::Int(r)) => (l as i128).cmp(&(r as i128)),
+            (Number::Int(l), Number::UInt(r)) => (l as i128).cmp(&(r as i128)),
+        }
+    }
+
+    /// Compares the exact values, unlike `cmp` which compares in the common type of `checked_binary`.
+    fn cmp_exact(self, other: Number) -> Ordering {
+        match (self, other) {
+            (Number::UInt(l), Number::UInt(r)) => l.cmp(&r),
+            (Number::Int(l), Number::Int(r)) => l.cmp(&r),
+            (Number::UInt(l), Number::Int(r)) => (l as i128).cmp(&(r as i128)),
+            (Number::Int(l), Number::UInt(r)) => (l as i128).cmp(&(r as i128)),
+            (Number::Float(l), Number::Float(r)) => OrderedFloat(l).cmp(&OrderedFloat(r)),
+            (Number::Decimal(l), Number::Decimal(r)) => l.cmp(&r),
+            (Number::Decimal128(l), Number::Decimal128(r)) => l.cmp_value(&r),
+            _ => match (self.to_fraction(), other.to_fraction()) {
+                (Some(l), Some(r)) => l.compare(&r),
+                // Infinities and `NaN` order the same against every finite number.
+                (l, r) => {
+                    let non_finite = |number: Number, fraction: &Option<Fraction>| {
+                        OrderedFloat(if fraction.is_some() {
+                            0.0
+                        } else {
+                            number.to_f64()
+                        })
+                    };
+                    non_finite(self, &l).cmp(&non_finite(other, &r))
+                }
+            },
+        }
+    }
+
+    /// The exact value, or `None` if it is an infinity or `NaN`.
+    fn to_fraction(self) -> Option<Fraction> {
+        Some(match self {
+            Number::UInt(u) => Fraction::new(BigInt::from(u), 0, 0),
+            Number::Int(i) => Fraction::new(BigInt::from(i), 0, 0),
+            Number::Float(f) if !f.is_finite() => return None,
+            Number::Float(f) => {
+                // `f` is `mantissa * 2^exponent`, see IEEE 754.
+                let bits = f.to_bits();
+                let biased_exponent = ((bits >> 52) & 0x7ff) as i32;
+                let fraction = bits & ((1 << 52) - 1);
+                let (mantissa, exponent) = if biased_exponent == 0 {
+                    (fraction, -1074)
+                } else {
+                    (fraction | 1 << 52, biased_exponent - 1075)
+                };
+                let mantissa = if f.is_sign_negative() {
+                    -BigInt::from(mantissa)
+                } else {
+                    BigInt::from(mantissa)
+                };
+                if exponent >= 0 {
+                    Fraction::new(mantissa << exponent as usize, 0, 0)
+                } else {
+                    Fraction::new(mantissa, exponent.unsigned_abs(), 0)
+                }
+            }
+            Number::Decimal(d) => Fraction::new(BigInt::from(d.mantissa()), 0, d.scale()),
+            Number::Decimal128(d) => Fraction::new(BigInt::from(d.mantissa()), 0, d.scale() as u32),
+        })
+    }
+}
+
+/// `numerator / (2^pow2 * 10^pow10)`, which represents every finite number exactly.
+struct Fraction {
+    numerator: BigInt,
+    pow2: u32,
+    pow10: u32,
+}
+
+impl Fraction {
+    fn new(numerator: BigInt, pow2: u32, pow10: u32) -> Self {
+        Self {
+            numerator,
+            pow2,
+            pow10,
+        }
+    }
+
+    fn compare(&self, other: &Fraction) -> Ordering {
+        // Both denominators are positive, so cross multiplying keeps the order.
+        let scale = |fraction: &Fraction, by: &Fraction| {
+            (&fraction.numerator << by.pow2 as usize) * BigInt::from(10).pow(by.pow10)
+        };
+        scale(self, other).cmp(&scale(other, self))
+    }
+}
+
+/// The operands of a binary operation, converted to a common type.
+enum Operands {
+    UInt(u64, u64),
+    Int(i64, i64),
+    Float(f64, f64),
+    Decimal(Decimal, Decimal),
+    Decimal128(DozerDecimal128, DozerDecimal128),
+}
@@ -0,0 +1,205 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use ordered_float::OrderedFloat;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+
+use crate::errors::types::CastError;
+
+use super::{
+    DozerDecimal128, DozerDuration, DozerGeometry, DozerPoint, Field, FieldType, DATE_FORMAT,
+};
+
+impl Field {
+    /// Converts the field to `typ`.
+    ///
+    /// `Null` casts to `Null` and every type casts to itself. Otherwise:
+    ///
+    /// | To | From |
+    /// |---|---|
+    /// | `UInt`, `Int` | The other integer, `Float`, `Decimal` and `Decimal128` truncated toward zero, `Boolean` as 0 or 1, and strings of integers. |
+    /// | `Float` | `UInt`, `Int`, `Decimal`, `Decimal128`, `Boolean` as 0 or 1, and strings of numbers. |
+    /// | `Decimal` | `UInt`, `Int`, `Decimal128`s that fit, finite `Float`s by their shortest representation, `Boolean` as 0 or 1, and strings of decimals, including scientific notation. |
+    /// | `Decimal128` | Like `Decimal`, and `Decimal`s. |
+    /// | `Boolean` | Numbers, true if not zero, and `true`, `false`, `t`, `f`, `1` or `0` in any case. |
+    /// | `String`, `Text` | Every type but `Bson`. Booleans are `true` or `false`, timestamps RFC 3339, dates `%Y-%m-%d`, points `(x,y)`, geometries hexadecimal EWKB and binaries must be UTF-8. |
+    /// | `Binary` | Strings, as their UTF-8 bytes, and `Geometry`, as its EWKB. |
+    /// | `Timestamp` | RFC 3339 strings and `Date`s, at midnight UTC. |
+    /// | `Date` | `%Y-%m-%d` strings and `Timestamp`s, the date in their own offset. |
+    /// | `Point` | `(x,y)` strings. |
+    /// | `Duration` | Strings such as `1h 30m`, see `DozerDuration`. |
+    /// | `Geometry` | Hexadecimal EWKB strings and EWKB binaries. |
+    /// | `Bson` | Nothing else. |
+    ///
+    /// Strings and texts are interchangeable as sources, and are trimmed before parsing.
+    pub fn cast_to(&self, typ: FieldType) -> Result<Field, CastError> {
+        if self == &Field::Null || self.get_type() == Some(typ) {
+            return Ok(self.clone());
+        }
+
+        let unsupported = || CastError::Unsupported {
+            from: self.get_type().expect("Null is handled above"),
+            to: typ,
+        };
+        let invalid = || CastError::InvalidValue {
+            value: self.to_string().unwrap_or_default(),
+            to: typ,
+        };
+        let out_of_range = || CastError::OutOfRange {
+            value: self.to_string().unwrap_or_default(),
+            to: typ,
+        };
+        let string = match self {
+            Field::String(s) | Field::Text(s) => Some(s.trim()),
+            _ => None,
+        };
+
+        match typ {
+            FieldType::UInt => match self {
+                Field::Int(i) => u64::try_from(*i).map_err(|_| out_of_range()),
+                Field::Float(f) => f.0.trunc().to_u64().ok_or_else(out_of_range),
+                Field::Decimal(d) => d.trunc().to_u64().ok_or_else(out_of_range),
+                Field::Decimal128(d) => u64::try_from(d.trunc()).map_err(|_| out_of_range()),
+                Field::Boolean(b) => Ok(*b as u64),
+                Field::String(_) | Field::Text(_) => parse(string, invalid),
+                _ => Err(unsupported()),
+            }
+            .map(Field::UInt),
+            FieldType::Int => match self {
+                Field::UInt(u) => i64::try_from(*u).map_err(|_| out_of_range()),
+                Field::Float(f) => f.0.trunc().to_i64().ok_or_else(out_of_range),
+                Field::Decimal(d) => d.trunc().to_i64().ok_or_else(out_of_range),
+                Field::Decimal128(d) => i64::try_from(d.trunc()).map_err(|_| out_of_range()),
+                Field::Boolean(b) => Ok(*b as i64),
+                Field::String(_) | Field::Text(_) => parse(string, invalid),
+                _ => Err(unsupported()),
+            }
+            .map(Field::Int),
+            FieldType::Float => match self {
+                Field::UInt(u) => Ok(*u as f64),
+                Field::Int(i) => Ok(*i as f64),
+                Field::Decimal(d) => d.to_f64().ok_or_else(out_of_range),
+                Field::Decimal128(d) => Ok(d.to_f64()),
+                Field::Boolean(b) => Ok(*b as u8 as f64),
+                Field::String(_) | Field::Text(_) => parse(string, invalid),
+                _ => Err(unsupported()),
+            }
+            .map(|f| Field::Float(OrderedFloat(f))),
+            FieldType::Decimal => match self {
+                Field::UInt(u) => Ok(Decimal::from(*u)),
+                Field::Int(i) => Ok(Decimal::from(*i)),
+                Field::Float(f) if !f.0.is_finite() => Err(invalid()),
+                Field::Float(f) => Decimal::from_str(&f.0.to_string())
+                    .ok()
+                    .or_else(|| Decimal::from_f64(f.0))
+                    .ok_or_else(out_of_range),
+                Field::Decimal128(d) => d.to_decimal().ok_or_else(out_of_range),
+                Field::Boolean(b) => Ok(Decimal::from(*b as u8)),
+                Field::String(_) | Field::Text(_) => {
+                    let s = string.expect("Matched a string");
+                    Decimal::from_str(s)
+                        .or_else(|_| Decimal::from_scientific(s))
+                        .map_err(|_| invalid())
+                }
+                _ => Err(unsupported()),
+            }
+            .map(Field::Decimal),
+            FieldType::Decimal128 => match self {
+                Field::UInt(u) => Ok(DozerDecimal128::new(*u as i128, 0).expect("Fits")),
+                Field::Int(i) => Ok(DozerDecimal128::new(*i as i128, 0).expect("Fits")),
+                Field::Decimal(d) => Ok(DozerDecimal128::from(*d)),
+                Field::Float(f) if !f.0.is_finite() => Err(invalid()),
+                Field::Float(f) => f.0.to_string().parse().map_err(|_| out_of_range()),
+                Field::Boolean(b) => Ok(DozerDecimal128::new(*b as i128, 0).expect("Fits")),
+                Field::String(_) | Field::Text(_) => parse(string, invalid),
+                _ => Err(unsupported()),
+            }
+            .map(Field::Decimal128),
+            FieldType::Boolean => match self {
+                Field::UInt(u) => Ok(*u != 0),
+                Field::Int(i) => Ok(*i != 0),
+                Field::Float(f) => Ok(f.0 != 0.0),
+                Field::Decimal(d) => Ok(!d.is_zero()),
+                Field::Decimal128(d) => Ok(d.mantissa() != 0),
+                Field::String(_) | Field::Text(_) => {
+                    match string.expect("Matched a string").to_lowercase().as_str() {
+                        "true" | "t" | "1" => Ok(true),
+                        "false" | "f" | "0" => Ok(false),
+                        _ => Err(invalid()),
+                    }
+                }
+                _ => Err(unsupported()),
+            }
+            .map(Field::Boolean),
+            FieldType::String | FieldType::Text => {
+                let s = match self {
+                    Field::String(s) | Field::Text(s) => s.clone(),
+                    Field::Boolean(b) => b.to_string(),
+                    Field::Binary(b) => String::from_utf8(b.clone()).map_err(|_| invalid())?,
+                    Field::Point(p) => format!("({},{})", p.0.x(), p.0.y()),
+                    Field::Bson(_) => return Err(unsupported()),
+                    other => other.to_string().ok_or_else(unsupported)?,
+                };
+                Ok(if typ == FieldType::String {
+                    Field::String(s)
+                } else {
+                    Field::Text(s)
+                })
+            }
+            FieldType::Binary => match self {
+                Field::String(s) | Field::Text(s) => Ok(Field::Binary(s.as_bytes().to_vec())),
+                Field::Geometry(g) => Ok(Field::Binary(g.as_bytes().to_vec())),
+                _ => Err(unsupported()),
+            },
+            FieldType::Timestamp => match self {
+                Field::Date(d) => {
+                    let midnight = d.and_hms_opt(0, 0, 0).expect("Midnight is valid");
+                    Ok(Utc.from_utc_datetime(&midnight).into())
+                }
+                Field::String(_) | Field::Text(_) => {
+                    DateTime::parse_from_rfc3339(string.expect("Matched a string"))
+                        .map_err(|_| invalid())
+                }
+                _ => Err(unsupported()),
+            }
+            .map(Field::Timestamp),
+            FieldType::Date => match self {
+                Field::Timestamp(t) => Ok(t.naive_local().date()),
+                Field::String(_) | Field::Text(_) => {
+                    NaiveDate::parse_from_str(string.expect("Matched a string"), DATE_FORMAT)
+                        .map_err(|_| invalid())
+                }
+                _ => Err(unsupported()),
+            }
+            .map(Field::Date),
+            FieldType::Point => match self {
+                Field::String(_) | Field::Text(_) => parse::<DozerPoint>(string, invalid),
+                _ => Err(unsupported()),
+            }
+            .map(Field::Point),
+            FieldType::Duration => match self {
+                Field::String(_) | Field::Text(_) => parse::<DozerDuration>(string, invalid),
+                _ => Err(unsupported()),
+            }
+            .map(Field::Duration),
+            FieldType::Geometry => match self {
+                Field::String(_) | Field::Text(_) => parse::<DozerGeometry>(string, invalid),
+                Field::Binary(b) => DozerGeometry::from_ewkb(b.clone()).map_err(|_| invalid()),
+                _ => Err(unsupported()),
+            }
+            .map(Field::Geometry),
+            FieldType::Bson => Err(unsupported()),
+        }
+    }
+}
+
+fn parse<T: FromStr>(
+    string: Option<&str>,
+    invalid: impl FnOnce() -> CastError,
+) -> Result<T, CastError> {
+    string
+        .expect("Matched a string")
+        .parse()
+        .map_err(|_| invalid())
+}
@@ -0,0 +1,159 @@
+//! Unicode normalization and locale-aware collation keys, for collated cache indexes and `ORDER BY` on text.
+//!
+//! Collation keys compare byte-wise in the order of the locale. They compare letters by their base letter first, then
+//! by their accents, then by their case, lowercase first, so `"resume" < "Resume" < "résumé" < "rf"`. Locales tailor
+//! the order of the letters in `TAILORINGS`, such as `ä` after `z` in Swedish. This is a simplification of the Unicode
+//! Collation Algorithm, without contractions or ignorable characters.
+//!
+//! | Part | Content |
+//! |---|---|
+//! | Primary | The weight of every base letter, lowercase. |
+//! | Secondary | `BASE_WEIGHT` for every base letter, followed by the code points of its combining marks. |
+//! | Tertiary | `LOWERCASE_WEIGHT` or `UPPERCASE_WEIGHT` for every base letter. |
+//!
+//! Weights are `u32` in big-endian and parts are separated by `LEVEL_SEPARATOR`, which is smaller than every weight.
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use super::Field;
+
+const LEVEL_SEPARATOR: u32 = 0;
+const BASE_WEIGHT: u32 = 1;
+const LOWERCASE_WEIGHT: u32 = 1;
+const UPPERCASE_WEIGHT: u32 = 2;
+
+/// Letters that languages sort after another letter, as `(language, letter, after)`. Letters after the same letter
+/// sort in the order they are listed.
+const TAILORINGS: &[(&str, char, char)] = &[
+    ("da", 'æ', 'z'),
+    ("da", 'ø', 'z'),
+    ("da", 'å', 'z'),
+    ("es", 'ñ', 'n'),
+    ("fi", 'å', 'z'),
+    ("fi", 'ä', 'z'),
+    ("fi", 'ö', 'z'),
+    ("nb", 'æ', 'z'),
+    ("nb", 'ø', 'z'),
+    ("nb", 'å', 'z'),
+    ("nn", 'æ', 'z'),
+    ("nn", 'ø', 'z'),
+    ("nn", 'å', 'z'),
+    ("no", 'æ', 'z'),
+    ("no", 'ø', 'z'),
+    ("no", 'å', 'z'),
+    ("pl", 'ą', 'a'),
+    ("pl", 'ć', 'c'),
+    ("pl", 'ę', 'e'),
+    ("pl", 'ł', 'l'),
+    ("pl", 'ń', 'n'),
+    ("pl", 'ó', 'o'),
+    ("pl", 'ś', 's'),
+    ("pl", 'ź', 'z'),
+    ("pl", 'ż', 'z'),
+    ("sv", 'å', 'z'),
+    ("sv", 'ä', 'z'),
+    ("sv", 'ö', 'z'),
+    ("tr", 'ç', 'c'),
+    ("tr", 'ğ', 'g'),
+    ("tr", 'ı', 'h'),
+    ("tr", 'ö', 'o'),
+    ("tr", 'ş', 's'),
+    ("tr", 'ü', 'u'),
+];
+
+/// `text` in Unicode Normalization Form C, so canonically equivalent texts are equal.
+pub fn normalize(text: &str) -> String {
+    text.nfc().collect()
+}
+
+/// The collation key of `text` in `locale`, a BCP 47 tag such as `sv-SE`, see `collation`.
+///
+/// Only the language of `locale` matters. Languages without tailorings get the root order.
+pub fn collation_key(text: &str, locale: &str) -> Vec<u8> {
+    let language = locale
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let language = match language.as_str() {
+        // Azerbaijani has the dotted and dotless i of Turkish.
+        "az" => "tr",
+        other => other,
+    };
+
+    let mut primary = vec![];
+    let mut secondary = vec![];
+    let mut tertiary = vec![];
+    for c in text.nfc() {
+        let case_weight = if c.is_uppercase() {
+            UPPERCASE_WEIGHT
+        } else {
+            LOWERCASE_WEIGHT
+        };
+        for lower in to_lowercase(c, language) {
+            if let Some(weight) = tailored_weight(language, lower) {
+                primary.push(weight);
+                secondary.push(BASE_WEIGHT);
+                tertiary.push(case_weight);
+                continue;
+            }
+            for d in std::iter::once(lower).nfd() {
+                if is_combining_mark(d) {
+                    secondary.push(d as u32);
+                } else {
+                    primary.push(letter_weight(d));
+                    secondary.push(BASE_WEIGHT);
+                    tertiary.push(case_weight);
+                }
+            }
+        }
+    }
+
+    let mut key = Vec::with_capacity((primary.len() + secondary.len() + tertiary.len() + 2) * 4);
+    for (index, level) in [primary, secondary, tertiary].iter().enumerate() {
+        if index > 0 {
+            key.extend_from_slice(&LEVEL_SEPARATOR.to_be_bytes());
+        }
+        for weight in level {
+            key.extend_from_slice(&weight.to_be_bytes());
+        }
+    }
+    key
+}
+
+impl Field {
+    /// The collation key of a string or text in `locale`, see `collation::collation_key`. `None` for other fields.
+    pub fn collation_key(&self, locale: &str) -> Option<Vec<u8>> {
+        match self {
+            Field::String(s) | Field::Text(s) => Some(collation_key(s, locale)),
+            _ => None,
+        }
+    }
+}
+
+/// Leaves room after every letter for the letters tailored after it.
+fn letter_weight(c: char) -> u32 {
+    (c as u32) << 8
+}
+
+fn tailored_weight(language: &str, c: char) -> Option<u32> {
+    let (_, _, after) = TAILORINGS
+        .iter()
+        .find(|(tailored_language, letter, _)| *tailored_language == language && *letter == c)?;
+    let position = TAILORINGS
+        .iter()
+        .filter(|(tailored_language, _, tailored_after)| {
+            *tailored_language == language && tailored_after == after
+        })
+        .position(|(_, letter, _)| *letter == c)
+        .expect("Found the letter");
+    Some(letter_weight(*after) + position as u32 + 1)
+}
+
+fn to_lowercase(c: char, language: &str) -> Vec<char> {
+    match (language, c) {
+        ("tr", 'I') => vec!['ı'],
+        ("tr", 'İ') => vec!['i'],
+        _ => c.to_lowercase().collect(),
+    }
+}
@@ -0,0 +1,321 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::Neg;
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::types::TypeError;
+
+/// A 256-bit unsigned integer, as little-endian 64-bit limbs.
+type U256 = [u64; 4];
+
+/// A decimal of up to 38 digits, for numbers beyond the 96-bit mantissa of `Decimal`, such as Snowflake `NUMBER(38, 10)`.
+///
+/// It is `mantissa / 10^scale`, and keeps its scale like `Decimal`, so `1.50` is displayed as it is. Decimals with the
+/// same value and different scales are not equal, and sort by their scale after their value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DozerDecimal128 {
+    mantissa: i128,
+    scale: u8,
+}
+
+impl DozerDecimal128 {
+    /// The most digits of the mantissa, and the largest scale.
+    pub const MAX_PRECISION: u8 = 38;
+
+    /// Length of `to_ordered_bytes`.
+    pub const ORDERED_BYTES_LEN: usize = 33;
+
+    pub fn new(mantissa: i128, scale: u8) -> Result<Self, TypeError> {
+        if scale > Self::MAX_PRECISION {
+            return Err(TypeError::InvalidDecimal128(format!(
+                "Scale {scale} is larger than {}",
+                Self::MAX_PRECISION
+            )));
+        }
+        if mantissa.unsigned_abs() >= pow10(Self::MAX_PRECISION) {
+            return Err(TypeError::InvalidDecimal128(format!(
+                "{mantissa} has more than {} digits",
+                Self::MAX_PRECISION
+            )));
+        }
+        Ok(Self { mantissa, scale })
+    }
+
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// The integer part, truncated toward zero.
+    pub fn trunc(&self) -> i128 {
+        self.mantissa / pow10(self.scale) as i128
+    }
+
+    /// Compares the values, ignoring the scales.
+    pub fn cmp_value(&self, other: &Self) -> Ordering {
+        self.to_ordered_bytes()[..32].cmp(&other.to_ordered_bytes()[..32])
+    }
+
+    /// The sum at the larger scale of the two, or `None` if it has more than `MAX_PRECISION` digits.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let (l, r, scale) = self.rescale(other)?;
+        Self::new(l.checked_add(r)?, scale).ok()
+    }
+
+    /// The difference at the larger scale of the two, or `None` if it has more than `MAX_PRECISION` digits.
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        let (l, r, scale) = self.rescale(other)?;
+        Self::new(l.checked_sub(r)?, scale).ok()
+    }
+
+    /// The exact product, or `None` if it has more than `MAX_PRECISION` digits or a larger scale.
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        Self::new(
+            self.mantissa.checked_mul(other.mantissa)?,
+            self.scale.checked_add(other.scale)?,
+        )
+        .ok()
+    }
+
+    /// The quotient as `Decimal` divides, or `None` if either operand doesn't fit in `Decimal` or `other` is zero.
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        self.to_decimal()?
+            .checked_div(other.to_decimal()?)
+            .map(Self::from)
+    }
+
+    /// The remainder at the larger scale of the two, or `None` if `other` is zero.
+    pub fn checked_rem(self, other: Self) -> Option<Self> {
+        let (l, r, scale) = self.rescale(other)?;
+        Self::new(l.checked_rem(r)?, scale).ok()
+    }
+
+    /// Both mantissas at the larger scale of the two, or `None` if either overflows.
+    fn rescale(self, other: Self) -> Option<(i128, i128, u8)> {
+        let scale = self.scale.max(other.scale);
+        let l = self
+            .mantissa
+            .checked_mul(pow10(scale - self.scale) as i128)?;
+        let r = other
+            .mantissa
+            .checked_mul(pow10(scale - other.scale) as i128)?;
+        Some((l, r, scale))
+    }
+
+    /// The `Decimal` of the same value and scale, or `None` if it doesn't fit.
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        Decimal::try_from_i128_with_scale(self.mantissa, self.scale as u32).ok()
+    }
+
+    /// The nearest `f64`.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().expect("Decimals are valid floats")
+    }
+
+    /// The value as a 256-bit fixed-point number with `MAX_PRECISION` fractional digits, followed by the scale.
+    ///
+    /// The fixed-point number is big-endian with its sign bit flipped, so the bytes sort like the decimals.
+    pub fn to_ordered_bytes(&self) -> [u8; Self::ORDERED_BYTES_LEN] {
+        let magnitude = self.mantissa.unsigned_abs();
+        let mut value = [magnitude as u64, (magnitude >> 64) as u64, 0, 0];
+        for _ in self.scale..Self::MAX_PRECISION {
+            value = mul10(value);
+        }
+        if self.mantissa < 0 {
+            value = negate(value);
+        }
+
+        let mut result = [0; Self::ORDERED_BYTES_LEN];
+        for (index, limb) in value.iter().rev().enumerate() {
+            result[index * 8..index * 8 + 8].copy_from_slice(&limb.to_be_bytes());
+        }
+        result[0] ^= 0x80;
+        result[32] = self.scale;
+        result
+    }
+
+    pub fn from_ordered_bytes(bytes: &[u8]) -> Result<Self, TypeError> {
+        let invalid = || TypeError::InvalidDecimal128("Invalid ordered bytes".to_string());
+        if bytes.len() != Self::ORDERED_BYTES_LEN {
+            return Err(invalid());
+        }
+        let scale = bytes[32];
+        if scale > Self::MAX_PRECISION {
+            return Err(invalid());
+        }
+
+        let mut value: U256 = [0; 4];
+        for (index, limb) in value.iter_mut().rev().enumerate() {
+            *limb = u64::from_be_bytes(
+                bytes[index * 8..index * 8 + 8]
+                    .try_into()
+                    .expect("Checked the length"),
+            );
+        }
+        value[3] ^= 1 << 63;
+        let is_negative = value[3] >> 63 == 1;
+        if is_negative {
+            value = negate(value);
+        }
+        for _ in scale..Self::MAX_PRECISION {
+            value = div10_exact(value).ok_or_else(invalid)?;
+        }
+        if value[2] != 0 || value[3] != 0 {
+            return Err(invalid());
+        }
+
+        let magnitude = (value[1] as u128) << 64 | value[0] as u128;
+        let magnitude = i128::try_from(magnitude).map_err(|_| invalid())?;
+        Self::new(if is_negative { -magnitude } else { magnitude }, scale).map_err(|_| invalid())
+    }
+}
+
+impl From<Decimal> for DozerDecimal128 {
+    fn from(value: Decimal) -> Self {
+        // `Decimal` has a 96-bit mantissa and a scale of at most 28, which always fit.
+        Self {
+            mantissa: value.mantissa(),
+            scale: value.scale() as u8,
+        }
+    }
+}
+
+impl Neg for DozerDecimal128 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            mantissa: -self.mantissa,
+            scale: self.scale,
+        }
+    }
+}
+
+impl PartialOrd for DozerDecimal128 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DozerDecimal128 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_ordered_bytes().cmp(&other.to_ordered_bytes())
+    }
+}
+
+impl Display for DozerDecimal128 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        if self.mantissa < 0 {
+            f.write_str("-")?;
+        }
+        f.write_str(integer)?;
+        if !fraction.is_empty() {
+            write!(f, ".{fraction}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses decimals such as `-1.50`, with an optional exponent such as `1.5e2`.
+impl FromStr for DozerDecimal128 {
+    type Err = TypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || TypeError::InvalidDecimal128(s.to_string());
+
+        let (is_negative, unsigned) = match s.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (number, exponent) = match unsigned.find(['e', 'E']) {
+            Some(index) => (
+                &unsigned[..index],
+                unsigned[index + 1..]
+                    .parse::<i32>()
+                    .map_err(|_| invalid())?,
+            ),
+            None => (unsigned, 0),
+        };
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+        let is_digits = |s: &str| s.bytes().all(|byte| byte.is_ascii_digit());
+        if (integer.is_empty() && fraction.is_empty())
+            || !is_digits(integer)
+            || !is_digits(fraction)
+        {
+            return Err(invalid());
+        }
+
+        let mut digits = format!("{integer}{fraction}");
+        let mut scale = (fraction.len() as i32).saturating_sub(exponent);
+        if scale < -(Self::MAX_PRECISION as i32) {
+            return Err(invalid());
+        } else if scale < 0 {
+            digits.extend(std::iter::repeat('0').take(-scale as usize));
+            scale = 0;
+        }
+        let digits = digits.trim_start_matches('0');
+        if digits.len() > Self::MAX_PRECISION as usize || scale > Self::MAX_PRECISION as i32 {
+            return Err(invalid());
+        }
+
+        let magnitude = if digits.is_empty() {
+            0
+        } else {
+            digits.parse::<i128>().map_err(|_| invalid())?
+        };
+        Self::new(
+            if is_negative { -magnitude } else { magnitude },
+            scale as u8,
+        )
+    }
+}
+
+fn pow10(exponent: u8) -> u128 {
+    10u128.pow(exponent as u32)
+}
+
+fn mul10(value: U256) -> U256 {
+    let mut result = [0; 4];
+    let mut carry = 0;
+    for (result, limb) in result.iter_mut().zip(value) {
+        let product = limb as u128 * 10 + carry;
+        *result = product as u64;
+        carry = product >> 64;
+    }
+    result
+}
+
+/// `value / 10`, or `None` if it is not a multiple of 10.
+fn div10_exact(value: U256) -> Option<U256> {
+    let mut result = [0; 4];
+    let mut remainder = 0;
+    for (result, limb) in result.iter_mut().zip(value).rev() {
+        let dividend = remainder << 64 | limb as u128;
+        *result = (dividend / 10) as u64;
+        remainder = dividend % 10;
+    }
+    (remainder == 0).then_some(result)
+}
+
+/// Two's complement of `value`.
+fn negate(value: U256) -> U256 {
+    let mut result = value.map(|limb| !limb);
+    for limb in result.iter_mut() {
+        let (sum, overflow) = limb.overflowing_add(1);
+        *limb = sum;
+        if !overflow {
+            break;
+        }
+    }
+    result
+}
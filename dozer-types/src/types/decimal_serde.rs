@@ -0,0 +1,68 @@
+//! Lossless serde for `Decimal`, to be used with `#[serde(with = "dozer_types::types::decimal_serde")]`.
+//!
+//! Human readable formats, such as JSON, get the decimal string. Binary formats get the 16 bytes of `Decimal::serialize`,
+//! the same bytes as `Field::encode`.
+//!
+//! Binary data serialized as a string before this module existed still deserializes. Serialized decimals always start with
+//! a zero byte and strings of decimals never do, so the two can be told apart.
+
+use std::{fmt, str::FromStr};
+
+use rust_decimal::Decimal;
+use serde::{de, Deserializer, Serializer};
+
+pub fn serialize<S: Serializer>(decimal: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.collect_str(decimal)
+    } else {
+        serializer.serialize_bytes(&decimal.serialize())
+    }
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(DecimalVisitor)
+    } else {
+        deserializer.deserialize_bytes(DecimalVisitor)
+    }
+}
+
+struct DecimalVisitor;
+
+impl<'de> de::Visitor<'de> for DecimalVisitor {
+    type Value = Decimal;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a decimal string, number or 16 serialized bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Decimal::from_str(v)
+            .or_else(|_| Decimal::from_scientific(v))
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        match <[u8; 16]>::try_from(v) {
+            Ok(bytes) if bytes[0] == 0 => Ok(Decimal::deserialize(bytes)),
+            _ => {
+                let v = std::str::from_utf8(v)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Bytes(v), &self))?;
+                self.visit_str(v)
+            }
+        }
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(Decimal::from(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Decimal::from(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        // The shortest representation that round-trips, so `0.1` stays `0.1`.
+        self.visit_str(&v.to_string())
+    }
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+use crate::errors::types::TypeError;
+
+use super::{Field, Record};
+
+/// The fields an update changed and their new values, so updates of wide records don't need both full records.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+pub struct UpdateDelta {
+    /// Indexes of the changed fields, in increasing order.
+    pub indexes: Vec<usize>,
+    /// New values of the changed fields, following `indexes`.
+    pub values: Vec<Field>,
+}
+
+impl UpdateDelta {
+    /// The delta that turns the values of `old` into the values of `new`, which must have as many values.
+    pub fn new(old: &Record, new: &Record) -> UpdateDelta {
+        debug_assert_eq!(
+            old.values.len(),
+            new.values.len(),
+            "Records of an update must have the same number of values"
+        );
+
+        let mut delta = UpdateDelta::default();
+        for (index, (old, new)) in old.values.iter().zip(&new.values).enumerate() {
+            if old != new {
+                delta.indexes.push(index);
+                delta.values.push(new.clone());
+            }
+        }
+        delta
+    }
+
+    /// Returns true if no field changed.
+    pub fn is_empty(&self) -> bool {
+        self.indexes.is_empty()
+    }
+
+    /// Iterates over the changed indexes and their new values.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &Field)> {
+        self.indexes.iter().copied().zip(&self.values)
+    }
+
+    /// Sets the changed fields of `record` to their new values.
+    ///
+    /// `record` is left untouched if an index is out of its range.
+    pub fn apply(&self, record: &mut Record) -> Result<(), TypeError> {
+        if let Some(index) = self.indexes.iter().find(|i| **i >= record.values.len()) {
+            return Err(TypeError::InvalidFieldIndex(*index));
+        }
+        for (index, value) in self.iter() {
+            record.values[index] = value.clone();
+        }
+        Ok(())
+    }
+}
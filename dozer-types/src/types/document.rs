@@ -0,0 +1,85 @@
+//! The canonical form of `Bson` fields in `Field::encode`, so equal documents have equal keys.
+//!
+//! `Bson` fields hold either a BSON document or JSON text, depending on the source. Both become compact JSON text,
+//! with the keys of every object sorted and numbers normalized, so `{"b":1.0,"a":[2]}` and `{"a": [2], "b": 1}` encode
+//! to the same `{"a":[2],"b":1}`. Fields that are neither encode as they are.
+
+use std::borrow::Cow;
+
+use bson::{Bson, Document};
+use serde_json::{Number, Value};
+
+/// Integral floats below this magnitude are written as integers.
+const MAX_INTEGRAL_FLOAT: f64 = 9_223_372_036_854_775_808.0;
+
+/// The canonical form of a `Bson` field, or `bytes` if they are neither BSON nor JSON.
+pub(super) fn canonical_bson(bytes: &[u8]) -> Cow<[u8]> {
+    match parse(bytes) {
+        Some(value) => {
+            let mut result = String::with_capacity(bytes.len());
+            write_value(&mut result, &value);
+            Cow::Owned(result.into_bytes())
+        }
+        None => Cow::Borrowed(bytes),
+    }
+}
+
+fn parse(bytes: &[u8]) -> Option<Value> {
+    // A BSON document starts with its length, which JSON text is very unlikely to match.
+    let is_bson = bytes.len() >= 5
+        && i32::from_le_bytes(bytes[..4].try_into().expect("Checked the length")) as usize
+            == bytes.len();
+    if is_bson {
+        let mut reader = bytes;
+        if let Ok(document) = Document::from_reader(&mut reader) {
+            return Some(Bson::Document(document).into_relaxed_extjson());
+        }
+    }
+    serde_json::from_slice(bytes).ok()
+}
+
+fn write_value(result: &mut String, value: &Value) {
+    match value {
+        Value::Null | Value::Bool(_) | Value::String(_) => result.push_str(&value.to_string()),
+        Value::Number(number) => write_number(result, number),
+        Value::Array(values) => {
+            result.push('[');
+            for (index, value) in values.iter().enumerate() {
+                if index > 0 {
+                    result.push(',');
+                }
+                write_value(result, value);
+            }
+            result.push(']');
+        }
+        Value::Object(object) => {
+            let mut entries = object.iter().collect::<Vec<_>>();
+            entries.sort_unstable_by(|(left, _), (right, _)| left.cmp(right));
+            result.push('{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    result.push(',');
+                }
+                result.push_str(&Value::from(key.as_str()).to_string());
+                result.push(':');
+                write_value(result, value);
+            }
+            result.push('}');
+        }
+    }
+}
+
+/// Integers as they are, integral floats as integers, and other floats in their shortest form.
+fn write_number(result: &mut String, number: &Number) {
+    if number.is_i64() || number.is_u64() {
+        result.push_str(&number.to_string());
+        return;
+    }
+    let float = number.as_f64().expect("Numbers are integers or floats");
+    if float.fract() == 0.0 && float.abs() < MAX_INTEGRAL_FLOAT {
+        result.push_str(&(float as i64).to_string());
+    } else {
+        let number = Number::from_f64(float).expect("JSON numbers are finite");
+        result.push_str(&number.to_string());
+    }
+}
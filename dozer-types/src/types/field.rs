@@ -1,17 +1,21 @@
 use crate::errors::types::{DeserializationError, TypeError};
 #[allow(unused_imports)]
-use chrono::{DateTime, Datelike, FixedOffset, LocalResult, NaiveDate, TimeZone, Utc};
+use chrono::{
+    DateTime, Datelike, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Utc,
+};
 use ordered_float::OrderedFloat;
 use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::Decimal;
 use serde::{self, Deserialize, Serialize};
 use std::borrow::Cow;
 
-use crate::types::DozerPoint;
+use super::document::canonical_bson;
+use crate::types::{DozerDecimal128, DozerDuration, DozerGeometry, DozerPoint};
 use std::fmt::{Display, Formatter};
 
 pub const DATE_FORMAT: &str = "%Y-%m-%d";
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord, Hash)]
+/// Serde depends on whether the format is human readable, see `field_serde`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Field {
     UInt(u64),
     Int(i64),
@@ -25,10 +29,14 @@ pub enum Field {
     Date(NaiveDate),
     Bson(Vec<u8>),
     Point(DozerPoint),
+    Duration(DozerDuration),
+    Geometry(DozerGeometry),
+    Decimal128(DozerDecimal128),
     Null,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord)]
+/// Serde follows the binary representation of `Field`, see `field_serde`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum FieldBorrow<'a> {
     UInt(u64),
     Int(i64),
@@ -42,9 +50,56 @@ pub enum FieldBorrow<'a> {
     Date(NaiveDate),
     Bson(&'a [u8]),
     Point(DozerPoint),
+    Duration(DozerDuration),
+    /// The EWKB of a `DozerGeometry`.
+    Geometry(&'a [u8]),
+    Decimal128(DozerDecimal128),
     Null,
 }
 
+const TIMESTAMP_ENCODING_LEN: usize = 16;
+/// Before storage layout version 1, timestamps were encoded as milliseconds since the epoch, dropping the offset.
+const LEGACY_TIMESTAMP_ENCODING_LEN: usize = 8;
+
+/// Encodes the seconds and nanoseconds since the epoch, followed by the offset in seconds east of UTC.
+///
+/// The signed parts have their sign bit flipped, so encodings sort by instant, then by offset.
+fn encode_timestamp(t: &DateTime<FixedOffset>) -> [u8; TIMESTAMP_ENCODING_LEN] {
+    let mut result = [0; TIMESTAMP_ENCODING_LEN];
+    result[0..8].copy_from_slice(&((t.timestamp() as u64) ^ (1 << 63)).to_be_bytes());
+    result[8..12].copy_from_slice(&t.timestamp_subsec_nanos().to_be_bytes());
+    let offset = t.offset().local_minus_utc();
+    result[12..16].copy_from_slice(&((offset as u32) ^ (1 << 31)).to_be_bytes());
+    result
+}
+
+/// Decodes `encode_timestamp` and the legacy encoding, which the cache comparators still meet while migrating.
+fn decode_timestamp(bytes: &[u8]) -> Result<DateTime<FixedOffset>, DeserializationError> {
+    let invalid = || DeserializationError::Custom(Box::new(TypeError::InvalidTimestamp));
+    match bytes.len() {
+        TIMESTAMP_ENCODING_LEN => {
+            let secs = u64::from_be_bytes(bytes[0..8].try_into().expect("Checked the length"));
+            let nanos = u32::from_be_bytes(bytes[8..12].try_into().expect("Checked the length"));
+            let offset = u32::from_be_bytes(bytes[12..16].try_into().expect("Checked the length"));
+            let utc = NaiveDateTime::from_timestamp_opt((secs ^ (1 << 63)) as i64, nanos)
+                .ok_or_else(invalid)?;
+            let offset = FixedOffset::east_opt((offset ^ (1 << 31)) as i32).ok_or_else(invalid)?;
+            Ok(offset.from_utc_datetime(&utc))
+        }
+        LEGACY_TIMESTAMP_ENCODING_LEN => {
+            let millis = i64::from_be_bytes(bytes.try_into().expect("Checked the length"));
+            match Utc.timestamp_millis_opt(millis) {
+                LocalResult::Single(v) => Ok(DateTime::from(v)),
+                LocalResult::Ambiguous(_, _) => Err(DeserializationError::Custom(Box::new(
+                    TypeError::AmbiguousTimestamp,
+                ))),
+                LocalResult::None => Err(invalid()),
+            }
+        }
+        _ => Err(DeserializationError::BadDataLength),
+    }
+}
+
 impl Field {
     fn data_encoding_len(&self) -> usize {
         match self {
@@ -56,15 +111,18 @@ impl Field {
             Field::Text(s) => s.len(),
             Field::Binary(b) => b.len(),
             Field::Decimal(_) => 16,
-            Field::Timestamp(_) => 8,
+            Field::Timestamp(_) => TIMESTAMP_ENCODING_LEN,
             Field::Date(_) => 10,
-            Field::Bson(b) => b.len(),
+            Field::Bson(b) => canonical_bson(b).len(),
             Field::Point(_p) => 16,
+            Field::Duration(_) => 8,
+            Field::Geometry(g) => g.0.len(),
+            Field::Decimal128(_) => DozerDecimal128::ORDERED_BYTES_LEN,
             Field::Null => 0,
         }
     }
 
-    fn encode_data(&self) -> Cow<[u8]> {
+    pub(super) fn encode_data(&self) -> Cow<[u8]> {
         match self {
             Field::UInt(i) => Cow::Owned(i.to_be_bytes().into()),
             Field::Int(i) => Cow::Owned(i.to_be_bytes().into()),
@@ -74,11 +132,14 @@ impl Field {
             Field::Text(s) => Cow::Borrowed(s.as_bytes()),
             Field::Binary(b) => Cow::Borrowed(b.as_slice()),
             Field::Decimal(d) => Cow::Owned(d.serialize().into()),
-            Field::Timestamp(t) => Cow::Owned(t.timestamp_millis().to_be_bytes().into()),
+            Field::Timestamp(t) => Cow::Owned(encode_timestamp(t).into()),
             Field::Date(t) => Cow::Owned(t.to_string().into()),
-            Field::Bson(b) => Cow::Borrowed(b),
+            Field::Bson(b) => canonical_bson(b),
             Field::Null => Cow::Owned([].into()),
             Field::Point(p) => Cow::Owned(p.to_bytes().into()),
+            Field::Duration(d) => Cow::Owned(d.to_bytes().into()),
+            Field::Geometry(g) => Cow::Borrowed(g.as_bytes()),
+            Field::Decimal128(d) => Cow::Owned(d.to_ordered_bytes().into()),
         }
     }
 
@@ -99,6 +160,28 @@ impl Field {
         result
     }
 
+    /// The bytes the field allocates on the heap, which are the capacities of strings, binaries, BSON and geometries.
+    ///
+    /// It excludes the `Field` itself, so the memory of a field is `size_of::<Field>()` plus this.
+    pub fn estimated_heap_size(&self) -> usize {
+        match self {
+            Field::String(s) | Field::Text(s) => s.capacity(),
+            Field::Binary(b) | Field::Bson(b) => b.capacity(),
+            Field::Geometry(g) => g.0.capacity(),
+            Field::UInt(_)
+            | Field::Int(_)
+            | Field::Float(_)
+            | Field::Boolean(_)
+            | Field::Decimal(_)
+            | Field::Timestamp(_)
+            | Field::Date(_)
+            | Field::Point(_)
+            | Field::Duration(_)
+            | Field::Decimal128(_)
+            | Field::Null => 0,
+        }
+    }
+
     pub fn borrow(&self) -> FieldBorrow {
         match self {
             Field::UInt(i) => FieldBorrow::UInt(*i),
@@ -113,6 +196,9 @@ impl Field {
             Field::Date(t) => FieldBorrow::Date(*t),
             Field::Bson(b) => FieldBorrow::Bson(b),
             Field::Point(p) => FieldBorrow::Point(*p),
+            Field::Duration(d) => FieldBorrow::Duration(*d),
+            Field::Geometry(g) => FieldBorrow::Geometry(g.as_bytes()),
+            Field::Decimal128(d) => FieldBorrow::Decimal128(*d),
             Field::Null => FieldBorrow::Null,
         }
     }
@@ -123,8 +209,15 @@ impl Field {
 
     pub fn decode_borrow(buf: &[u8]) -> Result<FieldBorrow, DeserializationError> {
         let first_byte = *buf.first().ok_or(DeserializationError::EmptyInput)?;
-        let val = &buf[1..];
-        match first_byte {
+        Self::decode_data_borrow(first_byte, &buf[1..])
+    }
+
+    /// Decodes the data of `encode_data` of the field with type prefix `prefix`.
+    pub(super) fn decode_data_borrow(
+        prefix: u8,
+        val: &[u8],
+    ) -> Result<FieldBorrow, DeserializationError> {
+        match prefix {
             0 => Ok(FieldBorrow::UInt(u64::from_be_bytes(
                 val.try_into()
                     .map_err(|_| DeserializationError::BadDataLength)?,
@@ -145,22 +238,7 @@ impl Field {
                 val.try_into()
                     .map_err(|_| DeserializationError::BadDataLength)?,
             ))),
-            8 => {
-                let timestamp = Utc.timestamp_millis_opt(i64::from_be_bytes(
-                    val.try_into()
-                        .map_err(|_| DeserializationError::BadDataLength)?,
-                ));
-
-                match timestamp {
-                    LocalResult::Single(v) => Ok(FieldBorrow::Timestamp(DateTime::from(v))),
-                    LocalResult::Ambiguous(_, _) => Err(DeserializationError::Custom(Box::new(
-                        TypeError::AmbiguousTimestamp,
-                    ))),
-                    LocalResult::None => Err(DeserializationError::Custom(Box::new(
-                        TypeError::InvalidTimestamp,
-                    ))),
-                }
-            }
+            8 => Ok(FieldBorrow::Timestamp(decode_timestamp(val)?)),
             9 => Ok(FieldBorrow::Date(NaiveDate::parse_from_str(
                 std::str::from_utf8(val)?,
                 DATE_FORMAT,
@@ -170,11 +248,19 @@ impl Field {
                 DozerPoint::from_bytes(val).map_err(|_| DeserializationError::BadDataLength)?,
             )),
             12 => Ok(FieldBorrow::Null),
+            13 => Ok(FieldBorrow::Duration(
+                DozerDuration::from_bytes(val).map_err(|_| DeserializationError::BadDataLength)?,
+            )),
+            14 => Ok(FieldBorrow::Geometry(val)),
+            15 => Ok(FieldBorrow::Decimal128(
+                DozerDecimal128::from_ordered_bytes(val)
+                    .map_err(|e| DeserializationError::Custom(Box::new(e)))?,
+            )),
             other => Err(DeserializationError::UnrecognisedFieldType(other)),
         }
     }
 
-    fn get_type_prefix(&self) -> u8 {
+    pub(super) fn get_type_prefix(&self) -> u8 {
         match self {
             Field::UInt(_) => 0,
             Field::Int(_) => 1,
@@ -189,6 +275,31 @@ impl Field {
             Field::Bson(_) => 10,
             Field::Point(_) => 11,
             Field::Null => 12,
+            Field::Duration(_) => 13,
+            Field::Geometry(_) => 14,
+            Field::Decimal128(_) => 15,
+        }
+    }
+
+    /// Returns the type of the field, or `None` for `Null`.
+    pub fn get_type(&self) -> Option<FieldType> {
+        match self {
+            Field::UInt(_) => Some(FieldType::UInt),
+            Field::Int(_) => Some(FieldType::Int),
+            Field::Float(_) => Some(FieldType::Float),
+            Field::Boolean(_) => Some(FieldType::Boolean),
+            Field::String(_) => Some(FieldType::String),
+            Field::Text(_) => Some(FieldType::Text),
+            Field::Binary(_) => Some(FieldType::Binary),
+            Field::Decimal(_) => Some(FieldType::Decimal),
+            Field::Timestamp(_) => Some(FieldType::Timestamp),
+            Field::Date(_) => Some(FieldType::Date),
+            Field::Bson(_) => Some(FieldType::Bson),
+            Field::Point(_) => Some(FieldType::Point),
+            Field::Duration(_) => Some(FieldType::Duration),
+            Field::Geometry(_) => Some(FieldType::Geometry),
+            Field::Decimal128(_) => Some(FieldType::Decimal128),
+            Field::Null => None,
         }
     }
 
@@ -276,6 +387,27 @@ impl Field {
         }
     }
 
+    pub fn as_duration(&self) -> Option<DozerDuration> {
+        match self {
+            Field::Duration(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    pub fn as_geometry(&self) -> Option<&DozerGeometry> {
+        match self {
+            Field::Geometry(g) => Some(g),
+            _ => None,
+        }
+    }
+
+    pub fn as_decimal128(&self) -> Option<DozerDecimal128> {
+        match self {
+            Field::Decimal128(d) => Some(*d),
+            _ => None,
+        }
+    }
+
     pub fn as_null(&self) -> Option<()> {
         match self {
             Field::Null => Some(()),
@@ -307,6 +439,7 @@ impl Field {
         match self {
             Field::Float(f) => Some(f.0),
             Field::Decimal(d) => d.to_f64(),
+            Field::Decimal128(d) => Some(d.to_f64()),
             Field::UInt(u) => f64::from_u64(*u),
             Field::Int(i) => f64::from_i64(*i),
             Field::Null => Some(0_f64),
@@ -335,6 +468,7 @@ impl Field {
             Field::UInt(i) => Some(format!("{i}")),
             Field::Float(i) => Some(format!("{i}")),
             Field::Decimal(i) => Some(format!("{i}")),
+            Field::Decimal128(i) => Some(format!("{i}")),
             Field::Boolean(i) => Some(if *i {
                 "TRUE".to_string()
             } else {
@@ -342,6 +476,8 @@ impl Field {
             }),
             Field::Date(d) => Some(d.format("%Y-%m-%d").to_string()),
             Field::Timestamp(t) => Some(t.to_rfc3339()),
+            Field::Duration(d) => Some(d.to_string()),
+            Field::Geometry(g) => Some(g.to_string()),
             Field::Binary(b) => Some(format!("{b:X?}")),
             Field::Null => Some("".to_string()),
             _ => None,
@@ -356,6 +492,7 @@ impl Field {
             Field::UInt(i) => Some(format!("{i}")),
             Field::Float(i) => Some(format!("{i}")),
             Field::Decimal(i) => Some(format!("{i}")),
+            Field::Decimal128(i) => Some(format!("{i}")),
             Field::Boolean(i) => Some(if *i {
                 "TRUE".to_string()
             } else {
@@ -363,6 +500,8 @@ impl Field {
             }),
             Field::Date(d) => Some(d.format("%Y-%m-%d").to_string()),
             Field::Timestamp(t) => Some(t.to_rfc3339()),
+            Field::Duration(d) => Some(d.to_string()),
+            Field::Geometry(g) => Some(g.to_string()),
             Field::Binary(b) => Some(format!("{b:X?}")),
             Field::Null => Some("".to_string()),
             _ => None,
@@ -379,6 +518,7 @@ impl Field {
     pub fn to_decimal(&self) -> Option<Decimal> {
         match self {
             Field::Decimal(d) => Some(*d),
+            Field::Decimal128(d) => d.to_decimal(),
             Field::Float(f) => Decimal::from_f64_retain(f.0),
             Field::Int(i) => Decimal::from_i64(*i),
             Field::UInt(u) => Decimal::from_u64(*u),
@@ -428,30 +568,19 @@ impl Field {
         }
     }
 
-    pub fn to_null(&self) -> Option<()> {
+    pub fn to_duration(&self) -> Result<Option<DozerDuration>, TypeError> {
         match self {
-            Field::Null => Some(()),
-            _ => None,
+            Field::Duration(d) => Ok(Some(*d)),
+            Field::String(s) => Ok(s.parse::<DozerDuration>().ok()),
+            Field::Null => Ok(Some(DozerDuration::ZERO)),
+            _ => Ok(None),
         }
     }
-}
 
-impl Display for Field {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    pub fn to_null(&self) -> Option<()> {
         match self {
-            Field::UInt(v) => f.write_str(&format!("{v} (unsigned int)")),
-            Field::Int(v) => f.write_str(&format!("{v} (signed int)")),
-            Field::Float(v) => f.write_str(&format!("{v} (Float)")),
-            Field::Boolean(v) => f.write_str(&format!("{v}")),
-            Field::String(v) => f.write_str(&v.to_string()),
-            Field::Text(v) => f.write_str(&v.to_string()),
-            Field::Binary(v) => f.write_str(&format!("{v:x?}")),
-            Field::Decimal(v) => f.write_str(&format!("{v} (Decimal)")),
-            Field::Timestamp(v) => f.write_str(&format!("{v}")),
-            Field::Date(v) => f.write_str(&format!("{v}")),
-            Field::Bson(v) => f.write_str(&format!("{v:x?}")),
-            Field::Null => f.write_str("NULL"),
-            Field::Point(v) => f.write_str(&format!("{v} (Point)")),
+            Field::Null => Some(()),
+            _ => None,
         }
     }
 }
@@ -471,6 +600,9 @@ impl<'a> FieldBorrow<'a> {
             FieldBorrow::Date(d) => Field::Date(d),
             FieldBorrow::Bson(b) => Field::Bson(b.to_owned()),
             FieldBorrow::Point(p) => Field::Point(p),
+            FieldBorrow::Duration(d) => Field::Duration(d),
+            FieldBorrow::Geometry(g) => Field::Geometry(DozerGeometry(g.to_owned())),
+            FieldBorrow::Decimal128(d) => Field::Decimal128(d),
             FieldBorrow::Null => Field::Null,
         }
     }
@@ -490,6 +622,9 @@ pub enum FieldType {
     Date,
     Bson,
     Point,
+    Duration,
+    Geometry,
+    Decimal128,
 }
 
 impl TryFrom<&str> for FieldType {
@@ -508,6 +643,10 @@ impl TryFrom<&str> for FieldType {
             "timestamp" => FieldType::Timestamp,
             "date" => FieldType::Date,
             "bson" => FieldType::Bson,
+            "point" => FieldType::Point,
+            "duration" => FieldType::Duration,
+            "geometry" => FieldType::Geometry,
+            "decimal128" => FieldType::Decimal128,
             _ => return Err(format!("Unsupported '{value}' type")),
         };
 
@@ -530,6 +669,9 @@ impl Display for FieldType {
             FieldType::Date => f.write_str("date"),
             FieldType::Bson => f.write_str("bson"),
             FieldType::Point => f.write_str("point"),
+            FieldType::Duration => f.write_str("duration"),
+            FieldType::Geometry => f.write_str("geometry"),
+            FieldType::Decimal128 => f.write_str("decimal128"),
         }
     }
 }
@@ -556,12 +698,23 @@ pub fn field_test_cases() -> impl Iterator<Item = Field> {
         Field::Decimal(Decimal::new(1, 0)),
         Field::Timestamp(DateTime::from(Utc.timestamp_millis_opt(0).unwrap())),
         Field::Timestamp(DateTime::parse_from_rfc3339("2020-01-01T00:00:00Z").unwrap()),
+        Field::Timestamp(
+            DateTime::parse_from_rfc3339("1969-12-31T23:59:59.999999999+05:30").unwrap(),
+        ),
         Field::Date(NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()),
         Field::Date(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap()),
         Field::Bson(vec![
             // BSON representation of `{"abc":"foo"}`
             123, 34, 97, 98, 99, 34, 58, 34, 102, 111, 111, 34, 125,
         ]),
+        Field::Duration(DozerDuration::ZERO),
+        Field::Duration(DozerDuration::from_nanos(-1)),
+        Field::Geometry(DozerGeometry::from_geo(
+            &geo::Geometry::Polygon(geo::polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 0., y: 1.)]),
+            Some(4326),
+        )),
+        Field::Decimal128(DozerDecimal128::new(0, 0).unwrap()),
+        Field::Decimal128(DozerDecimal128::new(-10i128.pow(37), 2).unwrap()),
         Field::Null,
     ]
     .into_iter()
@@ -579,6 +732,7 @@ impl pyo3::ToPyObject for Field {
             Field::Text(val) => val.to_object(py),
             Field::Binary(val) => val.to_object(py),
             Field::Decimal(val) => val.to_f64().unwrap().to_object(py),
+            Field::Decimal128(val) => val.to_f64().to_object(py),
             Field::Timestamp(val) => val.timestamp().to_object(py),
             Field::Date(val) => {
                 pyo3::types::PyDate::new(py, val.year(), val.month() as u8, val.day() as u8)
@@ -588,6 +742,22 @@ impl pyo3::ToPyObject for Field {
             Field::Bson(val) => val.to_object(py),
             Field::Null => unreachable!(),
             Field::Point(_val) => todo!(),
+            Field::Geometry(val) => val.as_bytes().to_object(py),
+            Field::Duration(val) => {
+                const NANOS_PER_DAY: i64 = 24 * 60 * 60 * 1_000_000_000;
+                let nanos = val.as_nanos();
+                let days = nanos.div_euclid(NANOS_PER_DAY);
+                let nanos = nanos.rem_euclid(NANOS_PER_DAY);
+                pyo3::types::PyDelta::new(
+                    py,
+                    days as i32,
+                    (nanos / 1_000_000_000) as i32,
+                    (nanos % 1_000_000_000 / 1_000) as i32,
+                    false,
+                )
+                .unwrap()
+                .to_object(py)
+            }
         }
     }
 }
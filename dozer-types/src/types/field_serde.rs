@@ -0,0 +1,252 @@
+//! Serde for `Field`, which depends on whether the format is human readable.
+//!
+//! Human readable formats, such as JSON, get an object of the variant and `Field::to_json`, such as `{"Binary":"3q0="}`
+//! instead of an array of bytes, and `"Null"` for `Null`. They deserialize with `Field::from_json`, which also accepts
+//! what the derived implementation used to write.
+//!
+//! Binary formats, such as bincode, get the variant index and the value, as the derived implementation did. The indices
+//! are those of the variants before `Duration`, `Geometry` and `Decimal128` were added in front of `Null`, so `Null` keeps
+//! index 12 and stored records still deserialize. The new variants take the indices after it. `Record` derives its
+//! serde, so its values follow the same rules. `FieldBorrow` uses the binary representation in every format.
+
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use ordered_float::OrderedFloat;
+use rust_decimal::Decimal;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use super::{
+    decimal_serde, DozerDecimal128, DozerDuration, DozerGeometry, DozerPoint, Field, FieldBorrow,
+    FieldType,
+};
+
+impl Serialize for Field {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return TaggedField::from(self).serialize(serializer);
+        }
+
+        const NAME: &str = "Field";
+        match self {
+            Field::UInt(v) => serializer.serialize_newtype_variant(NAME, 0, "UInt", v),
+            Field::Int(v) => serializer.serialize_newtype_variant(NAME, 1, "Int", v),
+            Field::Float(v) => serializer.serialize_newtype_variant(NAME, 2, "Float", v),
+            Field::Boolean(v) => serializer.serialize_newtype_variant(NAME, 3, "Boolean", v),
+            Field::String(v) => serializer.serialize_newtype_variant(NAME, 4, "String", v),
+            Field::Text(v) => serializer.serialize_newtype_variant(NAME, 5, "Text", v),
+            Field::Binary(v) => serializer.serialize_newtype_variant(NAME, 6, "Binary", v),
+            Field::Decimal(v) => {
+                serializer.serialize_newtype_variant(NAME, 7, "Decimal", &SerializeDecimal(v))
+            }
+            Field::Timestamp(v) => serializer.serialize_newtype_variant(NAME, 8, "Timestamp", v),
+            Field::Date(v) => serializer.serialize_newtype_variant(NAME, 9, "Date", v),
+            Field::Bson(v) => serializer.serialize_newtype_variant(NAME, 10, "Bson", v),
+            Field::Point(v) => serializer.serialize_newtype_variant(NAME, 11, "Point", v),
+            Field::Null => serializer.serialize_unit_variant(NAME, 12, "Null"),
+            Field::Duration(v) => serializer.serialize_newtype_variant(NAME, 13, "Duration", v),
+            Field::Geometry(v) => serializer.serialize_newtype_variant(NAME, 14, "Geometry", v),
+            Field::Decimal128(v) => serializer.serialize_newtype_variant(NAME, 15, "Decimal128", v),
+        }
+    }
+}
+
+impl Serialize for FieldBorrow<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        const NAME: &str = "Field";
+        match self {
+            FieldBorrow::UInt(v) => serializer.serialize_newtype_variant(NAME, 0, "UInt", v),
+            FieldBorrow::Int(v) => serializer.serialize_newtype_variant(NAME, 1, "Int", v),
+            FieldBorrow::Float(v) => serializer.serialize_newtype_variant(NAME, 2, "Float", v),
+            FieldBorrow::Boolean(v) => serializer.serialize_newtype_variant(NAME, 3, "Boolean", v),
+            FieldBorrow::String(v) => serializer.serialize_newtype_variant(NAME, 4, "String", v),
+            FieldBorrow::Text(v) => serializer.serialize_newtype_variant(NAME, 5, "Text", v),
+            FieldBorrow::Binary(v) => serializer.serialize_newtype_variant(NAME, 6, "Binary", v),
+            FieldBorrow::Decimal(v) => {
+                serializer.serialize_newtype_variant(NAME, 7, "Decimal", &SerializeDecimal(v))
+            }
+            FieldBorrow::Timestamp(v) => {
+                serializer.serialize_newtype_variant(NAME, 8, "Timestamp", v)
+            }
+            FieldBorrow::Date(v) => serializer.serialize_newtype_variant(NAME, 9, "Date", v),
+            FieldBorrow::Bson(v) => serializer.serialize_newtype_variant(NAME, 10, "Bson", v),
+            FieldBorrow::Point(v) => serializer.serialize_newtype_variant(NAME, 11, "Point", v),
+            FieldBorrow::Null => serializer.serialize_unit_variant(NAME, 12, "Null"),
+            FieldBorrow::Duration(v) => {
+                serializer.serialize_newtype_variant(NAME, 13, "Duration", v)
+            }
+            FieldBorrow::Geometry(v) => {
+                serializer.serialize_newtype_variant(NAME, 14, "Geometry", v)
+            }
+            FieldBorrow::Decimal128(v) => {
+                serializer.serialize_newtype_variant(NAME, 15, "Decimal128", v)
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let (typ, value) = match TaggedField::deserialize(deserializer)? {
+                TaggedField::UInt(v) => (FieldType::UInt, v),
+                TaggedField::Int(v) => (FieldType::Int, v),
+                TaggedField::Float(v) => (FieldType::Float, v),
+                TaggedField::Boolean(v) => (FieldType::Boolean, v),
+                TaggedField::String(v) => (FieldType::String, v),
+                TaggedField::Text(v) => (FieldType::Text, v),
+                TaggedField::Binary(v) => (FieldType::Binary, v),
+                TaggedField::Decimal(v) => (FieldType::Decimal, v),
+                TaggedField::Timestamp(v) => (FieldType::Timestamp, v),
+                TaggedField::Date(v) => (FieldType::Date, v),
+                TaggedField::Bson(v) => (FieldType::Bson, v),
+                TaggedField::Point(v) => (FieldType::Point, v),
+                TaggedField::Duration(v) => (FieldType::Duration, v),
+                TaggedField::Geometry(v) => (FieldType::Geometry, v),
+                TaggedField::Decimal128(v) => (FieldType::Decimal128, v),
+                TaggedField::Null => return Ok(Field::Null),
+            };
+            return Field::from_json(value, typ).map_err(de::Error::custom);
+        }
+
+        Ok(match CompactField::deserialize(deserializer)? {
+            CompactField::UInt(v) => Field::UInt(v),
+            CompactField::Int(v) => Field::Int(v),
+            CompactField::Float(v) => Field::Float(v),
+            CompactField::Boolean(v) => Field::Boolean(v),
+            CompactField::String(v) => Field::String(v),
+            CompactField::Text(v) => Field::Text(v),
+            CompactField::Binary(v) => Field::Binary(v),
+            CompactField::Decimal(v) => Field::Decimal(v),
+            CompactField::Timestamp(v) => Field::Timestamp(v),
+            CompactField::Date(v) => Field::Date(v),
+            CompactField::Bson(v) => Field::Bson(v),
+            CompactField::Point(v) => Field::Point(v),
+            CompactField::Duration(v) => Field::Duration(v),
+            CompactField::Geometry(v) => Field::Geometry(v),
+            CompactField::Decimal128(v) => Field::Decimal128(v),
+            CompactField::Null => Field::Null,
+        })
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for FieldBorrow<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match CompactFieldBorrow::deserialize(deserializer)? {
+            CompactFieldBorrow::UInt(v) => FieldBorrow::UInt(v),
+            CompactFieldBorrow::Int(v) => FieldBorrow::Int(v),
+            CompactFieldBorrow::Float(v) => FieldBorrow::Float(v),
+            CompactFieldBorrow::Boolean(v) => FieldBorrow::Boolean(v),
+            CompactFieldBorrow::String(v) => FieldBorrow::String(v),
+            CompactFieldBorrow::Text(v) => FieldBorrow::Text(v),
+            CompactFieldBorrow::Binary(v) => FieldBorrow::Binary(v),
+            CompactFieldBorrow::Decimal(v) => FieldBorrow::Decimal(v),
+            CompactFieldBorrow::Timestamp(v) => FieldBorrow::Timestamp(v),
+            CompactFieldBorrow::Date(v) => FieldBorrow::Date(v),
+            CompactFieldBorrow::Bson(v) => FieldBorrow::Bson(v),
+            CompactFieldBorrow::Point(v) => FieldBorrow::Point(v),
+            CompactFieldBorrow::Null => FieldBorrow::Null,
+            CompactFieldBorrow::Duration(v) => FieldBorrow::Duration(v),
+            CompactFieldBorrow::Geometry(v) => FieldBorrow::Geometry(v),
+            CompactFieldBorrow::Decimal128(v) => FieldBorrow::Decimal128(v),
+        })
+    }
+}
+
+struct SerializeDecimal<'a>(&'a Decimal);
+
+impl Serialize for SerializeDecimal<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        decimal_serde::serialize(self.0, serializer)
+    }
+}
+
+/// The human readable representation, with the JSON of the field in its variant.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Field")]
+enum TaggedField {
+    UInt(Value),
+    Int(Value),
+    Float(Value),
+    Boolean(Value),
+    String(Value),
+    Text(Value),
+    Binary(Value),
+    Decimal(Value),
+    Timestamp(Value),
+    Date(Value),
+    Bson(Value),
+    Point(Value),
+    Duration(Value),
+    Geometry(Value),
+    Decimal128(Value),
+    Null,
+}
+
+impl From<&Field> for TaggedField {
+    fn from(field: &Field) -> Self {
+        let value = field.to_json();
+        match field {
+            Field::UInt(_) => TaggedField::UInt(value),
+            Field::Int(_) => TaggedField::Int(value),
+            Field::Float(_) => TaggedField::Float(value),
+            Field::Boolean(_) => TaggedField::Boolean(value),
+            Field::String(_) => TaggedField::String(value),
+            Field::Text(_) => TaggedField::Text(value),
+            Field::Binary(_) => TaggedField::Binary(value),
+            Field::Decimal(_) => TaggedField::Decimal(value),
+            Field::Timestamp(_) => TaggedField::Timestamp(value),
+            Field::Date(_) => TaggedField::Date(value),
+            Field::Bson(_) => TaggedField::Bson(value),
+            Field::Point(_) => TaggedField::Point(value),
+            Field::Duration(_) => TaggedField::Duration(value),
+            Field::Geometry(_) => TaggedField::Geometry(value),
+            Field::Decimal128(_) => TaggedField::Decimal128(value),
+            Field::Null => TaggedField::Null,
+        }
+    }
+}
+
+/// The binary representation. The variants must stay in the order of the `Serialize` indices, which isn't the order of
+/// `Field`.
+#[derive(Deserialize)]
+#[serde(rename = "Field")]
+enum CompactField {
+    UInt(u64),
+    Int(i64),
+    Float(OrderedFloat<f64>),
+    Boolean(bool),
+    String(String),
+    Text(String),
+    Binary(Vec<u8>),
+    Decimal(#[serde(with = "decimal_serde")] Decimal),
+    Timestamp(DateTime<FixedOffset>),
+    Date(NaiveDate),
+    Bson(Vec<u8>),
+    Point(DozerPoint),
+    Null,
+    Duration(DozerDuration),
+    Geometry(DozerGeometry),
+    Decimal128(DozerDecimal128),
+}
+
+/// The binary representation of `FieldBorrow`, in the order of `CompactField`.
+#[derive(Deserialize)]
+#[serde(rename = "Field")]
+enum CompactFieldBorrow<'a> {
+    UInt(u64),
+    Int(i64),
+    Float(OrderedFloat<f64>),
+    Boolean(bool),
+    String(&'a str),
+    Text(&'a str),
+    Binary(&'a [u8]),
+    Decimal(#[serde(with = "decimal_serde")] Decimal),
+    Timestamp(DateTime<FixedOffset>),
+    Date(NaiveDate),
+    Bson(&'a [u8]),
+    Point(DozerPoint),
+    Null,
+    Duration(DozerDuration),
+    Geometry(&'a [u8]),
+    Decimal128(DozerDecimal128),
+}
@@ -0,0 +1,103 @@
+//! The canonical text of `Field`, which `Display` writes and `FromStr` parses back to the same field.
+//!
+//! `Null` is `null`. Other fields are their type, a colon and their value:
+//!
+//! | Field | Value |
+//! |---|---|
+//! | `UInt`, `Int`, `Float` | The number. Floats are the shortest text that parses back, or `NaN`, `inf` and `-inf`. |
+//! | `Boolean` | `true` or `false`. |
+//! | `String`, `Text` | The string as it is, so `string:a:b` is `a:b`. |
+//! | `Binary`, `Bson` | Hexadecimal bytes. |
+//! | `Decimal`, `Decimal128` | The decimal with its scale, so `decimal:1.50` keeps its trailing zero. |
+//! | `Timestamp` | RFC 3339 with the offset and as many fractional digits as needed. |
+//! | `Date` | ISO 8601 calendar date. |
+//! | `Point` | `(x,y)`. |
+//! | `Duration` | See `DozerDuration`, such as `1500ms`. |
+//! | `Geometry` | Hexadecimal EWKB, see `DozerGeometry`. |
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDate};
+use ordered_float::OrderedFloat;
+use rust_decimal::Decimal;
+
+use crate::errors::types::TypeError;
+
+use super::{DozerDecimal128, DozerDuration, DozerGeometry, DozerPoint, Field, FieldType};
+
+impl Display for Field {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Field::UInt(v) => write!(f, "uint:{v}"),
+            Field::Int(v) => write!(f, "int:{v}"),
+            Field::Float(v) => write!(f, "float:{}", v.0),
+            Field::Boolean(v) => write!(f, "boolean:{v}"),
+            Field::String(v) => write!(f, "string:{v}"),
+            Field::Text(v) => write!(f, "text:{v}"),
+            Field::Binary(v) => write!(f, "binary:{}", to_hex(v)),
+            Field::Decimal(v) => write!(f, "decimal:{v}"),
+            Field::Timestamp(v) => write!(f, "timestamp:{}", v.to_rfc3339()),
+            Field::Date(v) => write!(f, "date:{v}"),
+            Field::Bson(v) => write!(f, "bson:{}", to_hex(v)),
+            Field::Point(v) => write!(f, "point:({},{})", v.0.x().0, v.0.y().0),
+            Field::Duration(v) => write!(f, "duration:{v}"),
+            Field::Geometry(v) => write!(f, "geometry:{v}"),
+            Field::Decimal128(v) => write!(f, "decimal128:{v}"),
+            Field::Null => f.write_str("null"),
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = TypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "null" {
+            return Ok(Field::Null);
+        }
+        let (typ, value) = s.split_once(':').ok_or(TypeError::InvalidFieldType)?;
+        let typ = FieldType::try_from(typ).map_err(|_| TypeError::InvalidFieldType)?;
+        let invalid = || TypeError::InvalidFieldValue {
+            field_type: typ,
+            nullable: false,
+            value: value.to_string(),
+        };
+
+        Ok(match typ {
+            FieldType::UInt => Field::UInt(value.parse().map_err(|_| invalid())?),
+            FieldType::Int => Field::Int(value.parse().map_err(|_| invalid())?),
+            FieldType::Float => Field::Float(OrderedFloat(value.parse().map_err(|_| invalid())?)),
+            FieldType::Boolean => Field::Boolean(value.parse().map_err(|_| invalid())?),
+            FieldType::String => Field::String(value.to_string()),
+            FieldType::Text => Field::Text(value.to_string()),
+            FieldType::Binary => Field::Binary(from_hex(value).ok_or_else(invalid)?),
+            FieldType::Decimal => Field::Decimal(Decimal::from_str(value).map_err(|_| invalid())?),
+            FieldType::Timestamp => {
+                Field::Timestamp(DateTime::parse_from_rfc3339(value).map_err(|_| invalid())?)
+            }
+            FieldType::Date => Field::Date(NaiveDate::from_str(value).map_err(|_| invalid())?),
+            FieldType::Bson => Field::Bson(from_hex(value).ok_or_else(invalid)?),
+            FieldType::Point => Field::Point(value.parse::<DozerPoint>()?),
+            FieldType::Duration => Field::Duration(value.parse::<DozerDuration>()?),
+            FieldType::Geometry => Field::Geometry(value.parse::<DozerGeometry>()?),
+            FieldType::Decimal128 => Field::Decimal128(value.parse::<DozerDecimal128>()?),
+        })
+    }
+}
+
+/// Uppercase hexadecimal `bytes`.
+pub(super) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02X}")).collect()
+}
+
+/// Parses hexadecimal bytes in either case.
+pub(super) fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 || !s.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect()
+}
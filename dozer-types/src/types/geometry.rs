@@ -0,0 +1,343 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use geo::{
+    Contains, Coord, Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint,
+    MultiPolygon, Point, Polygon,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::types::TypeError;
+
+use super::field_text::{from_hex, to_hex};
+use super::DozerPoint;
+
+const SRID_FLAG: u32 = 0x2000_0000;
+const M_FLAG: u32 = 0x4000_0000;
+const Z_FLAG: u32 = 0x8000_0000;
+
+const POINT: u32 = 1;
+const LINE_STRING: u32 = 2;
+const POLYGON: u32 = 3;
+const MULTI_POINT: u32 = 4;
+const MULTI_LINE_STRING: u32 = 5;
+const MULTI_POLYGON: u32 = 6;
+const GEOMETRY_COLLECTION: u32 = 7;
+
+/// A geometry of any kind, as the EWKB that PostGIS uses: WKB with an optional SRID.
+///
+/// The bytes are kept as they are, so geometries with Z or M coordinates are not altered. Use `from_ewkb` to check them.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct DozerGeometry(pub Vec<u8>);
+
+impl DozerGeometry {
+    /// Checks that `ewkb` is a valid EWKB or WKB geometry.
+    pub fn from_ewkb(ewkb: Vec<u8>) -> Result<Self, TypeError> {
+        let mut reader = WkbReader::new(&ewkb);
+        reader.read_geometry()?;
+        if !reader.bytes.is_empty() {
+            return Err(invalid("Trailing bytes"));
+        }
+        Ok(Self(ewkb))
+    }
+
+    /// Encodes `geometry` as little-endian EWKB. Lines, rectangles and triangles become line strings and polygons.
+    pub fn from_geo(geometry: &Geometry<f64>, srid: Option<u32>) -> Self {
+        let mut result = vec![];
+        write_geometry(&mut result, geometry, srid);
+        Self(result)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn srid(&self) -> Result<Option<u32>, TypeError> {
+        WkbReader::new(&self.0)
+            .read_header()
+            .map(|header| header.srid)
+    }
+
+    /// The 2D geometry, without Z and M coordinates.
+    pub fn to_geo(&self) -> Result<Geometry<f64>, TypeError> {
+        WkbReader::new(&self.0).read_geometry()
+    }
+
+    /// Returns true if `point` is inside the geometry, not on its boundary.
+    pub fn contains(&self, point: &DozerPoint) -> Result<bool, TypeError> {
+        let point = Point::new(point.0.x().0, point.0.y().0);
+        Ok(self.to_geo()?.contains(&point))
+    }
+}
+
+/// Hexadecimal EWKB, as PostGIS prints geometries.
+impl Display for DozerGeometry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&to_hex(&self.0))
+    }
+}
+
+impl FromStr for DozerGeometry {
+    type Err = TypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_ewkb(from_hex(s).ok_or_else(|| invalid("Not hexadecimal"))?)
+    }
+}
+
+fn invalid(reason: &str) -> TypeError {
+    TypeError::InvalidGeometry(reason.to_string())
+}
+
+struct Header {
+    little_endian: bool,
+    kind: u32,
+    /// Number of ordinates of every coordinate, 2 to 4.
+    dimensions: usize,
+    srid: Option<u32>,
+}
+
+struct WkbReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> WkbReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], TypeError> {
+        if self.bytes.len() < N {
+            return Err(invalid("Unexpected end"));
+        }
+        let (result, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        Ok(result.try_into().expect("Took N bytes"))
+    }
+
+    fn read_u32(&mut self, little_endian: bool) -> Result<u32, TypeError> {
+        let bytes = self.take()?;
+        Ok(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_f64(&mut self, little_endian: bool) -> Result<f64, TypeError> {
+        let bytes = self.take()?;
+        Ok(if little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_header(&mut self) -> Result<Header, TypeError> {
+        let little_endian = match self.take::<1>()?[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(invalid("Invalid byte order")),
+        };
+        let typ = self.read_u32(little_endian)?;
+        let srid = if typ & SRID_FLAG != 0 {
+            Some(self.read_u32(little_endian)?)
+        } else {
+            None
+        };
+        // EWKB flags the dimensions, ISO WKB adds 1000 for Z, 2000 for M and 3000 for both.
+        let flags = typ & 0xF000_0000;
+        let (kind, iso_dimensions) = ((typ & 0x0FFF_FFFF) % 1000, (typ & 0x0FFF_FFFF) / 1000);
+        let extra_dimensions = match iso_dimensions {
+            0 => (flags & Z_FLAG != 0) as usize + (flags & M_FLAG != 0) as usize,
+            1 | 2 => 1,
+            3 => 2,
+            _ => return Err(invalid("Invalid geometry type")),
+        };
+        Ok(Header {
+            little_endian,
+            kind,
+            dimensions: 2 + extra_dimensions,
+            srid,
+        })
+    }
+
+    fn read_coord(&mut self, header: &Header) -> Result<Coord<f64>, TypeError> {
+        let x = self.read_f64(header.little_endian)?;
+        let y = self.read_f64(header.little_endian)?;
+        for _ in 2..header.dimensions {
+            self.read_f64(header.little_endian)?;
+        }
+        Ok(Coord { x, y })
+    }
+
+    fn read_count(&mut self, header: &Header) -> Result<usize, TypeError> {
+        let count = self.read_u32(header.little_endian)? as usize;
+        // Every item takes at least 4 bytes, so a corrupted count can't allocate much.
+        if count > self.bytes.len() / 4 {
+            return Err(invalid("Unexpected end"));
+        }
+        Ok(count)
+    }
+
+    fn read_line_string(&mut self, header: &Header) -> Result<LineString<f64>, TypeError> {
+        let count = self.read_count(header)?;
+        let coords = (0..count)
+            .map(|_| self.read_coord(header))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(LineString::new(coords))
+    }
+
+    fn read_polygon(&mut self, header: &Header) -> Result<Polygon<f64>, TypeError> {
+        let count = self.read_count(header)?;
+        let mut rings = (0..count)
+            .map(|_| self.read_line_string(header))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter();
+        let exterior = rings.next().unwrap_or_else(|| LineString::new(vec![]));
+        Ok(Polygon::new(exterior, rings.collect()))
+    }
+
+    /// Reads a geometry nested in a multi geometry or collection, which has its own header.
+    fn read_nested<T>(
+        &mut self,
+        kind: u32,
+        read: impl Fn(&mut Self, &Header) -> Result<T, TypeError>,
+    ) -> Result<T, TypeError> {
+        let header = self.read_header()?;
+        if header.kind != kind {
+            return Err(invalid("Unexpected geometry type"));
+        }
+        read(self, &header)
+    }
+
+    fn read_geometry(&mut self) -> Result<Geometry<f64>, TypeError> {
+        let header = self.read_header()?;
+        Ok(match header.kind {
+            POINT => Geometry::Point(Point(self.read_coord(&header)?)),
+            LINE_STRING => Geometry::LineString(self.read_line_string(&header)?),
+            POLYGON => Geometry::Polygon(self.read_polygon(&header)?),
+            MULTI_POINT => {
+                let count = self.read_count(&header)?;
+                let points = (0..count)
+                    .map(|_| self.read_nested(POINT, |r, h| r.read_coord(h).map(Point)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Geometry::MultiPoint(MultiPoint(points))
+            }
+            MULTI_LINE_STRING => {
+                let count = self.read_count(&header)?;
+                let line_strings = (0..count)
+                    .map(|_| self.read_nested(LINE_STRING, Self::read_line_string))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Geometry::MultiLineString(MultiLineString(line_strings))
+            }
+            MULTI_POLYGON => {
+                let count = self.read_count(&header)?;
+                let polygons = (0..count)
+                    .map(|_| self.read_nested(POLYGON, Self::read_polygon))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Geometry::MultiPolygon(MultiPolygon(polygons))
+            }
+            GEOMETRY_COLLECTION => {
+                let count = self.read_count(&header)?;
+                let geometries = (0..count)
+                    .map(|_| self.read_geometry())
+                    .collect::<Result<Vec<_>, _>>()?;
+                Geometry::GeometryCollection(GeometryCollection(geometries))
+            }
+            _ => return Err(invalid("Unsupported geometry type")),
+        })
+    }
+}
+
+fn write_header(result: &mut Vec<u8>, kind: u32, srid: Option<u32>) {
+    result.push(1);
+    match srid {
+        Some(srid) => {
+            result.extend_from_slice(&(kind | SRID_FLAG).to_le_bytes());
+            result.extend_from_slice(&srid.to_le_bytes());
+        }
+        None => result.extend_from_slice(&kind.to_le_bytes()),
+    }
+}
+
+fn write_coord(result: &mut Vec<u8>, coord: Coord<f64>) {
+    result.extend_from_slice(&coord.x.to_le_bytes());
+    result.extend_from_slice(&coord.y.to_le_bytes());
+}
+
+fn write_count(result: &mut Vec<u8>, count: usize) {
+    result.extend_from_slice(&(count as u32).to_le_bytes());
+}
+
+fn write_line_string(result: &mut Vec<u8>, line_string: &LineString<f64>) {
+    write_count(result, line_string.0.len());
+    for coord in &line_string.0 {
+        write_coord(result, *coord);
+    }
+}
+
+fn write_polygon(result: &mut Vec<u8>, polygon: &Polygon<f64>) {
+    write_count(result, polygon.interiors().len() + 1);
+    write_line_string(result, polygon.exterior());
+    for interior in polygon.interiors() {
+        write_line_string(result, interior);
+    }
+}
+
+/// Writes `geometry`, with `srid` in its header. Nested geometries have no SRID.
+fn write_geometry(result: &mut Vec<u8>, geometry: &Geometry<f64>, srid: Option<u32>) {
+    match geometry {
+        Geometry::Point(point) => {
+            write_header(result, POINT, srid);
+            write_coord(result, point.0);
+        }
+        Geometry::Line(line) => {
+            write_header(result, LINE_STRING, srid);
+            write_line_string(result, &LineString::new(vec![line.start, line.end]));
+        }
+        Geometry::LineString(line_string) => {
+            write_header(result, LINE_STRING, srid);
+            write_line_string(result, line_string);
+        }
+        Geometry::Polygon(polygon) => {
+            write_header(result, POLYGON, srid);
+            write_polygon(result, polygon);
+        }
+        Geometry::MultiPoint(multi_point) => {
+            write_header(result, MULTI_POINT, srid);
+            write_count(result, multi_point.0.len());
+            for point in &multi_point.0 {
+                write_header(result, POINT, None);
+                write_coord(result, point.0);
+            }
+        }
+        Geometry::MultiLineString(multi_line_string) => {
+            write_header(result, MULTI_LINE_STRING, srid);
+            write_count(result, multi_line_string.0.len());
+            for line_string in &multi_line_string.0 {
+                write_header(result, LINE_STRING, None);
+                write_line_string(result, line_string);
+            }
+        }
+        Geometry::MultiPolygon(multi_polygon) => {
+            write_header(result, MULTI_POLYGON, srid);
+            write_count(result, multi_polygon.0.len());
+            for polygon in &multi_polygon.0 {
+                write_header(result, POLYGON, None);
+                write_polygon(result, polygon);
+            }
+        }
+        Geometry::GeometryCollection(collection) => {
+            write_header(result, GEOMETRY_COLLECTION, srid);
+            write_count(result, collection.0.len());
+            for geometry in &collection.0 {
+                write_geometry(result, geometry, None);
+            }
+        }
+        Geometry::Rect(rect) => write_geometry(result, &Geometry::Polygon(rect.to_polygon()), srid),
+        Geometry::Triangle(triangle) => {
+            write_geometry(result, &Geometry::Polygon(triangle.to_polygon()), srid)
+        }
+    }
+}
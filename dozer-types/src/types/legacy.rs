@@ -0,0 +1,194 @@
+//! Types as serialized by earlier versions, to read data they persisted.
+
+use bincode::Options;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{
+    Field, FieldDefinition, FieldMetadata, FieldType, GeneratedValue, Schema, SchemaIdentifier,
+    SourceDefinition, TimestampPolicy,
+};
+
+/// `FieldDefinition` before `metadata` was added.
+#[derive(Serialize, Deserialize)]
+pub struct LegacyFieldDefinition {
+    pub name: String,
+    pub typ: FieldType,
+    pub nullable: bool,
+    #[serde(default)]
+    pub source: SourceDefinition,
+}
+
+impl From<LegacyFieldDefinition> for FieldDefinition {
+    fn from(field: LegacyFieldDefinition) -> Self {
+        FieldDefinition::new(field.name, field.typ, field.nullable, field.source)
+    }
+}
+
+/// `Schema` before `FieldDefinition::metadata` was added.
+#[derive(Serialize, Deserialize)]
+pub struct LegacySchema {
+    pub identifier: Option<SchemaIdentifier>,
+    pub fields: Vec<LegacyFieldDefinition>,
+    #[serde(default)]
+    pub primary_index: Vec<usize>,
+}
+
+impl From<LegacySchema> for Schema {
+    fn from(schema: LegacySchema) -> Self {
+        Schema {
+            identifier: schema.identifier,
+            fields: schema.fields.into_iter().map(Into::into).collect(),
+            primary_index: schema.primary_index,
+        }
+    }
+}
+
+/// `FieldMetadata` before `generated` was added.
+#[derive(Serialize, Deserialize)]
+pub struct FieldMetadataV1 {
+    pub source_name: Option<String>,
+    pub precision: Option<u32>,
+    pub scale: Option<u32>,
+    pub max_length: Option<u32>,
+    pub description: Option<String>,
+    pub default_value: Option<Field>,
+}
+
+impl From<FieldMetadataV1> for FieldMetadata {
+    fn from(metadata: FieldMetadataV1) -> Self {
+        FieldMetadata {
+            source_name: metadata.source_name,
+            precision: metadata.precision,
+            scale: metadata.scale,
+            max_length: metadata.max_length,
+            description: metadata.description,
+            default_value: metadata.default_value,
+            generated: None,
+            timestamp_policy: TimestampPolicy::default(),
+        }
+    }
+}
+
+/// `FieldDefinition` before `FieldMetadata::generated` was added.
+#[derive(Serialize, Deserialize)]
+pub struct FieldDefinitionV1 {
+    pub name: String,
+    pub typ: FieldType,
+    pub nullable: bool,
+    pub source: SourceDefinition,
+    pub metadata: FieldMetadataV1,
+}
+
+impl From<FieldDefinitionV1> for FieldDefinition {
+    fn from(field: FieldDefinitionV1) -> Self {
+        FieldDefinition::new(field.name, field.typ, field.nullable, field.source)
+            .with_metadata(field.metadata.into())
+    }
+}
+
+/// `Schema` before `FieldMetadata::generated` was added.
+#[derive(Serialize, Deserialize)]
+pub struct SchemaV1 {
+    pub identifier: Option<SchemaIdentifier>,
+    pub fields: Vec<FieldDefinitionV1>,
+    pub primary_index: Vec<usize>,
+}
+
+impl From<SchemaV1> for Schema {
+    fn from(schema: SchemaV1) -> Self {
+        Schema {
+            identifier: schema.identifier,
+            fields: schema.fields.into_iter().map(Into::into).collect(),
+            primary_index: schema.primary_index,
+        }
+    }
+}
+
+/// `FieldMetadata` before `timestamp_policy` was added.
+#[derive(Serialize, Deserialize)]
+pub struct FieldMetadataV2 {
+    pub source_name: Option<String>,
+    pub precision: Option<u32>,
+    pub scale: Option<u32>,
+    pub max_length: Option<u32>,
+    pub description: Option<String>,
+    pub default_value: Option<Field>,
+    pub generated: Option<GeneratedValue>,
+}
+
+impl From<FieldMetadataV2> for FieldMetadata {
+    fn from(metadata: FieldMetadataV2) -> Self {
+        FieldMetadata {
+            source_name: metadata.source_name,
+            precision: metadata.precision,
+            scale: metadata.scale,
+            max_length: metadata.max_length,
+            description: metadata.description,
+            default_value: metadata.default_value,
+            generated: metadata.generated,
+            timestamp_policy: TimestampPolicy::default(),
+        }
+    }
+}
+
+/// `FieldDefinition` before `FieldMetadata::timestamp_policy` was added.
+#[derive(Serialize, Deserialize)]
+pub struct FieldDefinitionV2 {
+    pub name: String,
+    pub typ: FieldType,
+    pub nullable: bool,
+    pub source: SourceDefinition,
+    pub metadata: FieldMetadataV2,
+}
+
+impl From<FieldDefinitionV2> for FieldDefinition {
+    fn from(field: FieldDefinitionV2) -> Self {
+        FieldDefinition::new(field.name, field.typ, field.nullable, field.source)
+            .with_metadata(field.metadata.into())
+    }
+}
+
+/// `Schema` before `FieldMetadata::timestamp_policy` was added.
+#[derive(Serialize, Deserialize)]
+pub struct SchemaV2 {
+    pub identifier: Option<SchemaIdentifier>,
+    pub fields: Vec<FieldDefinitionV2>,
+    pub primary_index: Vec<usize>,
+}
+
+impl From<SchemaV2> for Schema {
+    fn from(schema: SchemaV2) -> Self {
+        Schema {
+            identifier: schema.identifier,
+            fields: schema.fields.into_iter().map(Into::into).collect(),
+            primary_index: schema.primary_index,
+        }
+    }
+}
+
+/// `bincode::deserialize`, but failing if `bytes` has more than `T`.
+///
+/// Layouts can then be told apart by trying each of them, newest first.
+pub fn deserialize_bincode_exact<T: DeserializeOwned>(bytes: &[u8]) -> bincode::Result<T> {
+    bincode::options()
+        .with_fixint_encoding()
+        .reject_trailing_bytes()
+        .deserialize(bytes)
+}
+
+/// Deserializes a bincode-serialized `Schema`, in its current or legacy layout.
+pub fn deserialize_schema(bytes: &[u8]) -> bincode::Result<Schema> {
+    deserialize_schema_with::<()>(bytes).map(|(schema, ())| schema)
+}
+
+/// Deserializes a bincode-serialized `(Schema, T)`, with the schema in its current or legacy layout.
+pub fn deserialize_schema_with<T: DeserializeOwned>(bytes: &[u8]) -> bincode::Result<(Schema, T)> {
+    fn convert<S: Into<Schema>, T>((schema, value): (S, T)) -> (Schema, T) {
+        (schema.into(), value)
+    }
+
+    deserialize_bincode_exact::<(Schema, T)>(bytes)
+        .or_else(|_| deserialize_bincode_exact::<(SchemaV2, T)>(bytes).map(convert))
+        .or_else(|_| deserialize_bincode_exact::<(SchemaV1, T)>(bytes).map(convert))
+        .or_else(|_| deserialize_bincode_exact::<(LegacySchema, T)>(bytes).map(convert))
+}
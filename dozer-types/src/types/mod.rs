@@ -3,16 +3,42 @@ use ordered_float::OrderedFloat;
 use std::array::TryFromSliceError;
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
+use std::hash::Hasher;
 use std::str::FromStr;
 
 use crate::errors::types::TypeError;
 use prettytable::{Cell, Row, Table};
 use serde::{self, Deserialize, Serialize};
-
+use twox_hash::XxHash64;
+
+mod arithmetic;
+mod cast;
+pub mod collation;
+mod decimal128;
+pub mod decimal_serde;
+mod delta;
+mod document;
 mod field;
+mod field_serde;
+mod field_text;
+mod geometry;
+pub mod legacy;
+mod point;
+mod record_builder;
+mod record_encoding;
+mod schema_diff;
+mod schema_export;
+mod sql_literal;
 
 use crate::errors::types::TypeError::InvalidFieldValue;
+pub use arithmetic::ArithmeticOperator;
+pub use decimal128::DozerDecimal128;
+pub use delta::UpdateDelta;
 pub use field::{field_test_cases, Field, FieldBorrow, FieldType, DATE_FORMAT};
+pub use geometry::DozerGeometry;
+pub use record_builder::RecordBuilder;
+pub use record_encoding::{RecordEnvelope, FORMAT_VERSION_COMPACT};
+pub use schema_diff::{is_widening, Compatibility, SchemaChange, SchemaDiff};
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub enum SourceDefinition {
@@ -27,6 +53,74 @@ pub enum SourceDefinition {
     Dynamic,
 }
 
+/// What the source knows about a field, beyond its type.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct FieldMetadata {
+    /// Name of the column in the source, if it differs from the field name.
+    pub source_name: Option<String>,
+    /// Total number of digits of a decimal.
+    pub precision: Option<u32>,
+    /// Number of digits after the decimal point of a decimal.
+    pub scale: Option<u32>,
+    /// Maximum length of a string, text or binary.
+    pub max_length: Option<u32>,
+    pub description: Option<String>,
+    /// Value of the field when the source doesn't provide one.
+    pub default_value: Option<Field>,
+    /// Value generated when the record is inserted, if the source doesn't provide one. Takes precedence over
+    /// `default_value`.
+    pub generated: Option<GeneratedValue>,
+    /// How the offsets of timestamps are stored.
+    #[serde(default)]
+    pub timestamp_policy: TimestampPolicy,
+}
+
+/// A value generated when a record is inserted.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GeneratedValue {
+    /// The time of the insert, as a `Timestamp` in UTC.
+    InsertTimestamp,
+    /// The date of the insert in UTC, as a `Date`.
+    InsertDate,
+}
+
+impl GeneratedValue {
+    pub fn generate(&self) -> Field {
+        let now = chrono::Utc::now();
+        match self {
+            GeneratedValue::InsertTimestamp => Field::Timestamp(now.into()),
+            GeneratedValue::InsertDate => Field::Date(now.date_naive()),
+        }
+    }
+}
+
+/// How the offset of a `Timestamp` is stored.
+///
+/// Timestamps compare by instant either way, but sources disagree on offsets, so only normalized timestamps are
+/// stored and written the same way by every source.
+#[derive(
+    Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default,
+)]
+pub enum TimestampPolicy {
+    /// Keep the offset that the source reports.
+    #[default]
+    PreserveOffset,
+    /// Convert to UTC.
+    NormalizeToUtc,
+}
+
+impl TimestampPolicy {
+    pub fn apply(
+        &self,
+        timestamp: chrono::DateTime<chrono::FixedOffset>,
+    ) -> chrono::DateTime<chrono::FixedOffset> {
+        match self {
+            TimestampPolicy::PreserveOffset => timestamp,
+            TimestampPolicy::NormalizeToUtc => timestamp.with_timezone(&chrono::Utc).into(),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct FieldDefinition {
     pub name: String,
@@ -34,6 +128,8 @@ pub struct FieldDefinition {
     pub nullable: bool,
     #[serde(default)]
     pub source: SourceDefinition,
+    #[serde(default)]
+    pub metadata: FieldMetadata,
 }
 
 impl FieldDefinition {
@@ -43,6 +139,27 @@ impl FieldDefinition {
             typ,
             nullable,
             source,
+            metadata: FieldMetadata::default(),
+        }
+    }
+
+    pub fn with_metadata(mut self, metadata: FieldMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Applies `metadata.timestamp_policy` to `value`, if it's a timestamp.
+    pub fn apply_timestamp_policy(&self, value: &mut Field) {
+        if let Field::Timestamp(timestamp) = value {
+            *timestamp = self.metadata.timestamp_policy.apply(*timestamp);
+        }
+    }
+
+    /// The value of the field when the source omits it, from `metadata.generated` or `metadata.default_value`.
+    pub fn omitted_value(&self) -> Option<Field> {
+        match (&self.metadata.generated, &self.metadata.default_value) {
+            (Some(generated), _) => Some(generated.generate()),
+            (None, default_value) => default_value.clone(),
         }
     }
 }
@@ -207,6 +324,14 @@ impl Record {
         self.values.iter()
     }
 
+    pub fn borrow(&self) -> RecordBorrow {
+        RecordBorrow {
+            schema_id: self.schema_id,
+            values: self.values.iter().map(Field::borrow).collect(),
+            version: self.version,
+        }
+    }
+
     pub fn set_value(&mut self, idx: usize, value: Field) {
         self.values[idx] = value;
     }
@@ -222,6 +347,41 @@ impl Record {
         }
     }
 
+    /// Replaces the `Null` values of the fields of `schema` that have an omitted value, see
+    /// `FieldDefinition::omitted_value`.
+    ///
+    /// Omitted values can't be told apart from explicit nulls, so fields with a default or generated value can't be
+    /// `Null` once filled.
+    pub fn fill_omitted(&mut self, schema: &Schema) {
+        for (value, definition) in self.values.iter_mut().zip(&schema.fields) {
+            if *value == Field::Null {
+                if let Some(omitted) = definition.omitted_value() {
+                    *value = omitted;
+                }
+            }
+        }
+    }
+
+    /// Applies the timestamp policies of the fields of `schema` to the values, see `TimestampPolicy`.
+    pub fn apply_timestamp_policies(&mut self, schema: &Schema) {
+        for (value, definition) in self.values.iter_mut().zip(&schema.fields) {
+            definition.apply_timestamp_policy(value);
+        }
+    }
+
+    /// The bytes the record allocates on the heap, which are its values and what they allocate, see
+    /// `Field::estimated_heap_size`.
+    ///
+    /// Use it to bound batches and caches by memory instead of by the number of records.
+    pub fn estimated_heap_size(&self) -> usize {
+        self.values.capacity() * std::mem::size_of::<Field>()
+            + self
+                .values
+                .iter()
+                .map(Field::estimated_heap_size)
+                .sum::<usize>()
+    }
+
     pub fn get_key_fields(&self, schema: &Schema) -> Vec<Field> {
         self.get_fields_by_indexes(&schema.primary_index)
     }
@@ -253,6 +413,45 @@ impl Record {
         }
         res_buffer
     }
+
+    /// A hash of the values at `fields`, which is the same across platforms and versions.
+    ///
+    /// It is the 64-bit xxHash, with seed 0, of each encoded field prefixed by its length.
+    /// Unlike the `Hash` implementation, it can be persisted or used to partition records between processes.
+    pub fn hash(&self, fields: &[usize]) -> u64 {
+        let mut hasher = XxHash64::with_seed(0);
+        for i in fields {
+            let bytes = self.values[*i].encode();
+            hasher.write(&(bytes.len() as u64).to_le_bytes());
+            hasher.write(&bytes);
+        }
+        hasher.finish()
+    }
+
+    /// The partition, out of `num_partitions`, of the values at `fields`.
+    pub fn partition(&self, fields: &[usize], num_partitions: usize) -> usize {
+        debug_assert!(num_partitions > 0, "Number of partitions cannot be zero");
+        (self.hash(fields) % num_partitions as u64) as usize
+    }
+}
+
+/// A view of a `Record` whose string and binary values borrow from the bincode-serialized `Record`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecordBorrow<'a> {
+    pub schema_id: Option<SchemaIdentifier>,
+    #[serde(borrow)]
+    pub values: Vec<FieldBorrow<'a>>,
+    pub version: Option<u32>,
+}
+
+impl<'a> RecordBorrow<'a> {
+    pub fn into_owned(self) -> Record {
+        Record {
+            schema_id: self.schema_id,
+            values: self.values.into_iter().map(FieldBorrow::to_owned).collect(),
+            version: self.version,
+        }
+    }
 }
 
 impl Display for Record {
@@ -276,7 +475,8 @@ pub enum Operation {
     Update { old: Record, new: Record },
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, Hash)]
+/// Serde follows whether the format is human readable, see `point`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub struct DozerPoint(pub Point<OrderedFloat<f64>>);
 
 impl GeodesicDistance<OrderedFloat<f64>> for DozerPoint {
@@ -361,3 +561,180 @@ impl DozerPoint {
         Ok(DozerPoint::from((x, y)))
     }
 }
+
+/// A signed span of time with nanosecond precision, covering about 292 years in both directions.
+///
+/// Strings are a sequence of numbers with units, for example `1h 30m`, `-1.5s` or `2 days`.
+/// Durations are displayed in the largest unit that represents them exactly, for example `90s` or `-2d`.
+#[derive(
+    Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Default,
+)]
+pub struct DozerDuration(pub i64);
+
+const DURATION_UNITS: &[(&[&str], i64)] = &[
+    (&["w", "week", "weeks"], 7 * 24 * 60 * 60 * 1_000_000_000),
+    (&["d", "day", "days"], 24 * 60 * 60 * 1_000_000_000),
+    (&["h", "hour", "hours"], 60 * 60 * 1_000_000_000),
+    (&["m", "min", "minute", "minutes"], 60 * 1_000_000_000),
+    (&["s", "sec", "second", "seconds"], 1_000_000_000),
+    (&["ms", "millisecond", "milliseconds"], 1_000_000),
+    (&["us", "microsecond", "microseconds"], 1_000),
+    (&["ns", "nanosecond", "nanoseconds"], 1),
+];
+
+impl DozerDuration {
+    pub const ZERO: DozerDuration = DozerDuration(0);
+
+    pub fn from_nanos(nanos: i64) -> Self {
+        Self(nanos)
+    }
+
+    pub fn from_millis(millis: i64) -> Option<Self> {
+        millis.checked_mul(1_000_000).map(Self)
+    }
+
+    pub fn from_secs(secs: i64) -> Option<Self> {
+        secs.checked_mul(1_000_000_000).map(Self)
+    }
+
+    pub fn as_nanos(&self) -> i64 {
+        self.0
+    }
+
+    pub fn to_chrono(&self) -> chrono::Duration {
+        chrono::Duration::nanoseconds(self.0)
+    }
+
+    /// Returns `None` if the duration doesn't fit in 64 bits of nanoseconds.
+    pub fn from_chrono(duration: chrono::Duration) -> Option<Self> {
+        duration.num_nanoseconds().map(Self)
+    }
+
+    /// Returns the duration from `from` to `to`, which is negative if `to` is earlier.
+    pub fn between<Tz: chrono::TimeZone>(
+        from: &chrono::DateTime<Tz>,
+        to: &chrono::DateTime<Tz>,
+    ) -> Option<Self> {
+        Self::from_chrono(to.clone().signed_duration_since(from.clone()))
+    }
+
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Self)
+    }
+
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Self)
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        self.0.checked_neg().map(Self)
+    }
+
+    pub fn add_to_timestamp<Tz: chrono::TimeZone>(
+        &self,
+        timestamp: chrono::DateTime<Tz>,
+    ) -> Option<chrono::DateTime<Tz>> {
+        timestamp.checked_add_signed(self.to_chrono())
+    }
+
+    pub fn sub_from_timestamp<Tz: chrono::TimeZone>(
+        &self,
+        timestamp: chrono::DateTime<Tz>,
+    ) -> Option<chrono::DateTime<Tz>> {
+        timestamp.checked_sub_signed(self.to_chrono())
+    }
+
+    /// Big-endian with the sign bit flipped, so encoded durations sort like the durations themselves.
+    pub fn to_bytes(&self) -> [u8; 8] {
+        ((self.0 as u64) ^ (1 << 63)).to_be_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TryFromSliceError> {
+        let value = u64::from_be_bytes(bytes.try_into()?);
+        Ok(Self((value ^ (1 << 63)) as i64))
+    }
+}
+
+impl FromStr for DozerDuration {
+    type Err = TypeError;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let error = || InvalidFieldValue {
+            field_type: FieldType::Duration,
+            nullable: false,
+            value: str.to_string(),
+        };
+
+        let mut rest = str.trim();
+        let negative = match rest.strip_prefix('-') {
+            Some(stripped) => {
+                rest = stripped.trim_start();
+                true
+            }
+            None => false,
+        };
+        if rest.is_empty() {
+            return Err(error());
+        }
+
+        let mut nanos: i128 = 0;
+        while !rest.is_empty() {
+            let number_len = rest
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(rest.len());
+            let (number, tail) = rest.split_at(number_len);
+            let tail = tail.trim_start();
+            let unit_len = tail
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(tail.len());
+            let (unit, tail) = tail.split_at(unit_len);
+            rest = tail.trim_start().trim_start_matches(',').trim_start();
+
+            let unit_nanos = DURATION_UNITS
+                .iter()
+                .find(|(names, _)| names.contains(&unit.to_ascii_lowercase().as_str()))
+                .map(|(_, nanos)| *nanos as i128)
+                .ok_or_else(error)?;
+            let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+            if whole.is_empty() && fraction.is_empty() {
+                return Err(error());
+            }
+            let whole = if whole.is_empty() {
+                0
+            } else {
+                whole.parse::<i128>().map_err(|_| error())?
+            };
+            let mut fraction_nanos = 0;
+            let mut scale = unit_nanos;
+            for digit in fraction.chars() {
+                let digit = digit.to_digit(10).ok_or_else(error)? as i128;
+                scale /= 10;
+                fraction_nanos += digit * scale;
+            }
+            nanos = whole
+                .checked_mul(unit_nanos)
+                .and_then(|nanos| nanos.checked_add(fraction_nanos))
+                .and_then(|value| nanos.checked_add(value))
+                .ok_or_else(error)?;
+        }
+
+        if negative {
+            nanos = -nanos;
+        }
+        i64::try_from(nanos).map(Self).map_err(|_| error())
+    }
+}
+
+impl Display for DozerDuration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.0 == 0 {
+            return f.write_str("0s");
+        }
+        let nanos = self.0 as i128;
+        let (names, unit_nanos) = DURATION_UNITS
+            .iter()
+            .find(|(_, unit_nanos)| nanos % *unit_nanos as i128 == 0)
+            .expect("Every duration is a multiple of 1ns");
+        write!(f, "{}{}", nanos / *unit_nanos as i128, names[0])
+    }
+}
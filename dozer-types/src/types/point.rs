@@ -0,0 +1,119 @@
+//! Geodesic operations and serde of `DozerPoint`, whose `x` is the longitude and `y` the latitude, in degrees.
+//!
+//! Human readable formats, such as JSON, get a GeoJSON point, `{"type":"Point","coordinates":[x,y]}`, and also accept
+//! `{"x":x,"y":y}` as derived before. Binary formats get `x` and `y`, as derived before.
+
+use geo::Point;
+use ordered_float::OrderedFloat;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::DozerPoint;
+
+/// Mean radius of the Earth in meters, as `geo` uses for haversine distances.
+const MEAN_EARTH_RADIUS: f64 = 6_371_008.8;
+
+const GEOJSON_POINT_TYPE: &str = "Point";
+
+impl DozerPoint {
+    pub fn longitude(&self) -> f64 {
+        self.0.x().0
+    }
+
+    pub fn latitude(&self) -> f64 {
+        self.0.y().0
+    }
+
+    /// The great-circle distance to `other` in meters, by the haversine formula on a sphere.
+    pub fn haversine_distance(&self, other: &DozerPoint) -> f64 {
+        let (lat1, lat2) = (self.latitude().to_radians(), other.latitude().to_radians());
+        let d_lat = (other.latitude() - self.latitude()).to_radians();
+        let d_lon = (other.longitude() - self.longitude()).to_radians();
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+        2.0 * MEAN_EARTH_RADIUS * a.sqrt().asin()
+    }
+
+    /// The initial bearing of the great circle to `other` in degrees, clockwise from north, in `[0, 360)`.
+    pub fn bearing(&self, other: &DozerPoint) -> f64 {
+        let (lat1, lat2) = (self.latitude().to_radians(), other.latitude().to_radians());
+        let d_lon = (other.longitude() - self.longitude()).to_radians();
+        let y = d_lon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * d_lon.cos();
+        y.atan2(x).to_degrees().rem_euclid(360.0)
+    }
+
+    /// The south-west and north-east corners of a box that contains every point within `radius` meters.
+    ///
+    /// The box may contain more. It spans every longitude if it reaches a pole, and its longitudes may wrap around
+    /// the antimeridian, so the west may be greater than the east.
+    pub fn bounding_box(&self, radius: f64) -> (DozerPoint, DozerPoint) {
+        let d_lat = (radius / MEAN_EARTH_RADIUS).to_degrees();
+        let south = self.latitude() - d_lat;
+        let north = self.latitude() + d_lat;
+        if south <= -90.0 || north >= 90.0 {
+            return (
+                DozerPoint::from((-180.0, south.max(-90.0))),
+                DozerPoint::from((180.0, north.min(90.0))),
+            );
+        }
+
+        let d_lon =
+            (radius / (MEAN_EARTH_RADIUS * self.latitude().to_radians().cos())).to_degrees();
+        if d_lon >= 180.0 {
+            return (
+                DozerPoint::from((-180.0, south)),
+                DozerPoint::from((180.0, north)),
+            );
+        }
+        let wrap = |longitude: f64| (longitude + 180.0).rem_euclid(360.0) - 180.0;
+        (
+            DozerPoint::from((wrap(self.longitude() - d_lon), south)),
+            DozerPoint::from((wrap(self.longitude() + d_lon), north)),
+        )
+    }
+}
+
+impl Serialize for DozerPoint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            HumanReadablePoint::GeoJson {
+                typ: GEOJSON_POINT_TYPE.to_string(),
+                coordinates: (self.longitude(), self.latitude()),
+            }
+            .serialize(serializer)
+        } else {
+            self.0.serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for DozerPoint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            return Point::<OrderedFloat<f64>>::deserialize(deserializer).map(DozerPoint);
+        }
+        match HumanReadablePoint::deserialize(deserializer)? {
+            HumanReadablePoint::GeoJson { typ, coordinates } if typ == GEOJSON_POINT_TYPE => {
+                Ok(DozerPoint::from(coordinates))
+            }
+            HumanReadablePoint::GeoJson { typ, .. } => Err(de::Error::invalid_value(
+                de::Unexpected::Str(&typ),
+                &GEOJSON_POINT_TYPE,
+            )),
+            HumanReadablePoint::Coordinates { x, y } => Ok(DozerPoint::from((x, y))),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum HumanReadablePoint {
+    GeoJson {
+        #[serde(rename = "type")]
+        typ: String,
+        coordinates: (f64, f64),
+    },
+    Coordinates {
+        x: f64,
+        y: f64,
+    },
+}
@@ -0,0 +1,106 @@
+use crate::errors::types::TypeError;
+
+use super::{Field, Record, Schema};
+
+/// Builds a `Record` of a schema by field name, checking every value against its field definition.
+///
+/// ```
+/// use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+///
+/// let schema = Schema::empty()
+///     .field(
+///         FieldDefinition::new("id".to_string(), FieldType::Int, false, SourceDefinition::Dynamic),
+///         true,
+///     )
+///     .field(
+///         FieldDefinition::new("name".to_string(), FieldType::String, true, SourceDefinition::Dynamic),
+///         false,
+///     )
+///     .clone();
+/// let record = Record::builder(&schema).set("id", 1_i64).unwrap().build().unwrap();
+/// assert_eq!(record.values, vec![Field::Int(1), Field::Null]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecordBuilder<'a> {
+    schema: &'a Schema,
+    values: Vec<Option<Field>>,
+    version: Option<u32>,
+}
+
+impl<'a> RecordBuilder<'a> {
+    pub fn new(schema: &'a Schema) -> Self {
+        Self {
+            schema,
+            values: vec![None; schema.fields.len()],
+            version: None,
+        }
+    }
+
+    /// Sets the value of the field named `name`.
+    pub fn set(&mut self, name: &str, value: impl Into<Field>) -> Result<&mut Self, TypeError> {
+        let (index, _) = self.schema.get_field_index(name)?;
+        self.set_index(index, value)
+    }
+
+    /// Sets the value of the field at `index`.
+    ///
+    /// The value must be of the type of the field, or `Null` if the field is nullable. Timestamps are stored by the
+    /// timestamp policy of the field.
+    pub fn set_index(
+        &mut self,
+        index: usize,
+        value: impl Into<Field>,
+    ) -> Result<&mut Self, TypeError> {
+        let definition = self
+            .schema
+            .fields
+            .get(index)
+            .ok_or(TypeError::InvalidFieldIndex(index))?;
+        let mut value = value.into();
+        let valid = match value.get_type() {
+            Some(typ) => typ == definition.typ,
+            None => definition.nullable,
+        };
+        if !valid {
+            return Err(TypeError::InvalidFieldValue {
+                field_type: definition.typ,
+                nullable: definition.nullable,
+                value: format!("{value}"),
+            });
+        }
+
+        definition.apply_timestamp_policy(&mut value);
+        self.values[index] = Some(value);
+        Ok(self)
+    }
+
+    pub fn version(&mut self, version: u32) -> &mut Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Builds the record. Fields that were not set get their generated or default value, see
+    /// `FieldDefinition::omitted_value`, or `Null` if they have neither and are nullable.
+    ///
+    /// Fails if a field that is not nullable was not set and has neither.
+    pub fn build(&self) -> Result<Record, TypeError> {
+        let mut values = Vec::with_capacity(self.values.len());
+        for (value, definition) in self.values.iter().zip(&self.schema.fields) {
+            match value {
+                Some(value) => values.push(value.clone()),
+                None => match definition.omitted_value() {
+                    Some(omitted) => values.push(omitted),
+                    None if definition.nullable => values.push(Field::Null),
+                    None => return Err(TypeError::MissingFieldValue(definition.name.clone())),
+                },
+            }
+        }
+        Ok(Record::new(self.schema.identifier, values, self.version))
+    }
+}
+
+impl Record {
+    pub fn builder(schema: &Schema) -> RecordBuilder {
+        RecordBuilder::new(schema)
+    }
+}
@@ -0,0 +1,296 @@
+//! The compact encoding of `Record`, replacing bincode in storage.
+//!
+//! | Part | Content |
+//! |---|---|
+//! | Header | 1 byte, `HEADER_TAG` with `HAS_SCHEMA_ID` and `HAS_VERSION` set if present. |
+//! | Schema id | If present, `id` as `u32` and `version` as `u16`. |
+//! | Version | If present, as `u32`. |
+//! | Length | Number of values, as `u32`. |
+//! | Null bitmap | One bit per value, least significant bit first, set for `Null`. |
+//! | Types | The type prefix of `Field::encode` of every value that is not `Null`, one byte each. |
+//! | Fixed-width section | The data of `Field::encode` of every value that is not `Null`. Dates are days since the common era as `i32`. Strings, texts, binaries, BSON and geometries are their lengths as `u32`. |
+//! | Var-length tail | The bytes of every string, text, binary, BSON and geometry. |
+//!
+//! Integers are little-endian, except in the data of `Field::encode`.
+//!
+//! Bincode-encoded records always start with 0 or 1, so they are still decoded.
+//!
+//! Stored records are wrapped in an envelope by `encode_stored`, so the encoding can change without breaking existing
+//! cache directories:
+//!
+//! | Part | Content |
+//! |---|---|
+//! | Format version | 1 byte, `ENVELOPE_TAG` with the version of the payload encoding in the low bits. |
+//! | Schema version | 1 byte, the low byte of the version of `schema_id`, or 0 without one. |
+//! | Payload | The record in that format, `encode_compact` for `FORMAT_VERSION_COMPACT`. |
+//!
+//! `decode_stored` dispatches on the format version, and still decodes records stored without an envelope.
+
+use std::borrow::Cow;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::errors::types::DeserializationError;
+
+use super::{DozerDecimal128, Field, FieldBorrow, Record, RecordBorrow, SchemaIdentifier};
+
+const HEADER_TAG: u8 = 0x80;
+const HAS_SCHEMA_ID: u8 = 1;
+const HAS_VERSION: u8 = 1 << 1;
+
+/// Set in the first byte of an envelope, and never in the first byte of bincode or of `encode_compact`.
+const ENVELOPE_TAG: u8 = 0xC0;
+const FORMAT_VERSION_MASK: u8 = !ENVELOPE_TAG;
+/// The format version of `encode_compact` payloads.
+pub const FORMAT_VERSION_COMPACT: u8 = 1;
+
+const STRING_PREFIX: u8 = 4;
+const TEXT_PREFIX: u8 = 5;
+const BINARY_PREFIX: u8 = 6;
+const DATE_PREFIX: u8 = 9;
+const BSON_PREFIX: u8 = 10;
+const GEOMETRY_PREFIX: u8 = 14;
+
+/// Width of the value of type `prefix` in the fixed-width section.
+fn fixed_width(prefix: u8) -> Result<usize, DeserializationError> {
+    match prefix {
+        // UInt, Int, Float and Duration.
+        0 | 1 | 2 | 13 => Ok(8),
+        // Boolean.
+        3 => Ok(1),
+        STRING_PREFIX | TEXT_PREFIX | BINARY_PREFIX | BSON_PREFIX | GEOMETRY_PREFIX => Ok(4),
+        // Decimal, Timestamp and Point.
+        7 | 8 | 11 => Ok(16),
+        DATE_PREFIX => Ok(4),
+        // Decimal128.
+        15 => Ok(DozerDecimal128::ORDERED_BYTES_LEN),
+        other => Err(DeserializationError::UnrecognisedFieldType(other)),
+    }
+}
+
+fn is_var_length(prefix: u8) -> bool {
+    matches!(
+        prefix,
+        STRING_PREFIX | TEXT_PREFIX | BINARY_PREFIX | BSON_PREFIX | GEOMETRY_PREFIX
+    )
+}
+
+impl Record {
+    /// Encodes the record compactly, see `record_encoding`.
+    pub fn encode_compact(&self) -> Vec<u8> {
+        let num_values = self.values.len();
+        let mut header = HEADER_TAG;
+        if self.schema_id.is_some() {
+            header |= HAS_SCHEMA_ID;
+        }
+        if self.version.is_some() {
+            header |= HAS_VERSION;
+        }
+
+        let mut result = Vec::with_capacity(1 + 6 + 4 + 4 + num_values * 10);
+        result.push(header);
+        if let Some(schema_id) = self.schema_id {
+            result.extend_from_slice(&schema_id.id.to_le_bytes());
+            result.extend_from_slice(&schema_id.version.to_le_bytes());
+        }
+        if let Some(version) = self.version {
+            result.extend_from_slice(&version.to_le_bytes());
+        }
+        result.extend_from_slice(&(num_values as u32).to_le_bytes());
+
+        let mut bitmap = vec![0_u8; (num_values + 7) / 8];
+        for (index, value) in self.values.iter().enumerate() {
+            if value == &Field::Null {
+                bitmap[index / 8] |= 1 << (index % 8);
+            }
+        }
+        result.extend_from_slice(&bitmap);
+
+        let values = || self.values.iter().filter(|value| **value != Field::Null);
+        result.extend(values().map(Field::get_type_prefix));
+
+        let mut tail = vec![];
+        for value in values() {
+            match value {
+                Field::String(_)
+                | Field::Text(_)
+                | Field::Binary(_)
+                | Field::Bson(_)
+                | Field::Geometry(_) => {
+                    // Documents are stored as they are, not in their canonical form.
+                    let data = match value {
+                        Field::Bson(bytes) => Cow::Borrowed(bytes.as_slice()),
+                        _ => value.encode_data(),
+                    };
+                    result.extend_from_slice(&(data.len() as u32).to_le_bytes());
+                    tail.extend_from_slice(&data);
+                }
+                Field::Date(date) => {
+                    result.extend_from_slice(&date.num_days_from_ce().to_le_bytes())
+                }
+                _ => result.extend_from_slice(&value.encode_data()),
+            }
+        }
+        result.extend_from_slice(&tail);
+        result
+    }
+
+    /// Decodes `encode_compact`, or a bincode-encoded record.
+    pub fn decode_compact(bytes: &[u8]) -> Result<Record, DeserializationError> {
+        if is_bincode(bytes)? {
+            return Ok(bincode::deserialize(bytes)?);
+        }
+        Record::decode_compact_borrow(bytes).map(RecordBorrow::into_owned)
+    }
+
+    /// Decodes `encode_compact`, or a bincode-encoded record, borrowing strings and binaries from `bytes`.
+    pub fn decode_compact_borrow(bytes: &[u8]) -> Result<RecordBorrow, DeserializationError> {
+        if is_bincode(bytes)? {
+            return Ok(bincode::deserialize(bytes)?);
+        }
+
+        let mut reader = Reader(bytes);
+        let header = reader.take(1)?[0];
+        let schema_id = if header & HAS_SCHEMA_ID != 0 {
+            Some(SchemaIdentifier {
+                id: u32::from_le_bytes(reader.take_array()?),
+                version: u16::from_le_bytes(reader.take_array()?),
+            })
+        } else {
+            None
+        };
+        let version = if header & HAS_VERSION != 0 {
+            Some(u32::from_le_bytes(reader.take_array()?))
+        } else {
+            None
+        };
+
+        let num_values = u32::from_le_bytes(reader.take_array()?) as usize;
+        let bitmap = reader.take((num_values + 7) / 8)?;
+        let is_null = |index: usize| bitmap[index / 8] & (1 << (index % 8)) != 0;
+        let num_non_null = (0..num_values).filter(|index| !is_null(*index)).count();
+        let prefixes = reader.take(num_non_null)?;
+
+        let mut fixed_len = 0;
+        for prefix in prefixes {
+            fixed_len += fixed_width(*prefix)?;
+        }
+        let mut fixed = Reader(reader.take(fixed_len)?);
+        let mut tail = reader;
+
+        let mut prefixes = prefixes.iter();
+        let mut values = Vec::with_capacity(num_values);
+        for index in 0..num_values {
+            if is_null(index) {
+                values.push(FieldBorrow::Null);
+                continue;
+            }
+            let prefix = *prefixes.next().expect("Counted the non-null values");
+            let data = fixed.take(fixed_width(prefix)?)?;
+            let value = if is_var_length(prefix) {
+                let len = u32::from_le_bytes(data.try_into().expect("Checked the width"));
+                Field::decode_data_borrow(prefix, tail.take(len as usize)?)?
+            } else if prefix == DATE_PREFIX {
+                let days = i32::from_le_bytes(data.try_into().expect("Checked the width"));
+                FieldBorrow::Date(
+                    NaiveDate::from_num_days_from_ce_opt(days)
+                        .ok_or(DeserializationError::BadDataLength)?,
+                )
+            } else {
+                Field::decode_data_borrow(prefix, data)?
+            };
+            values.push(value);
+        }
+
+        if !tail.0.is_empty() {
+            return Err(DeserializationError::BadDataLength);
+        }
+        Ok(RecordBorrow {
+            schema_id,
+            values,
+            version,
+        })
+    }
+}
+
+/// The envelope of a stored record, see `record_encoding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordEnvelope {
+    pub format_version: u8,
+    pub schema_version: u8,
+}
+
+impl RecordEnvelope {
+    /// The envelope of `bytes`, or `None` if the record was stored without one.
+    pub fn read(bytes: &[u8]) -> Result<Option<(RecordEnvelope, &[u8])>, DeserializationError> {
+        let first_byte = *bytes.first().ok_or(DeserializationError::EmptyInput)?;
+        if first_byte & ENVELOPE_TAG != ENVELOPE_TAG {
+            return Ok(None);
+        }
+        let schema_version = *bytes.get(1).ok_or(DeserializationError::BadDataLength)?;
+        let envelope = RecordEnvelope {
+            format_version: first_byte & FORMAT_VERSION_MASK,
+            schema_version,
+        };
+        Ok(Some((envelope, &bytes[2..])))
+    }
+}
+
+impl Record {
+    /// Encodes the record for storage, in the latest format and with its envelope.
+    pub fn encode_stored(&self) -> Vec<u8> {
+        let schema_version = self
+            .schema_id
+            .map_or(0, |schema_id| schema_id.version as u8);
+        let payload = self.encode_compact();
+        let mut result = Vec::with_capacity(2 + payload.len());
+        result.push(ENVELOPE_TAG | FORMAT_VERSION_COMPACT);
+        result.push(schema_version);
+        result.extend_from_slice(&payload);
+        result
+    }
+
+    /// Decodes `encode_stored`, or a record stored without an envelope.
+    pub fn decode_stored(bytes: &[u8]) -> Result<Record, DeserializationError> {
+        match RecordEnvelope::read(bytes)? {
+            Some((envelope, payload)) => match envelope.format_version {
+                FORMAT_VERSION_COMPACT => Record::decode_compact(payload),
+                other => Err(DeserializationError::UnsupportedFormatVersion(other)),
+            },
+            None => Record::decode_compact(bytes),
+        }
+    }
+
+    /// Decodes `encode_stored`, or a record stored without an envelope, borrowing strings and binaries from `bytes`.
+    pub fn decode_stored_borrow(bytes: &[u8]) -> Result<RecordBorrow, DeserializationError> {
+        match RecordEnvelope::read(bytes)? {
+            Some((envelope, payload)) => match envelope.format_version {
+                FORMAT_VERSION_COMPACT => Record::decode_compact_borrow(payload),
+                other => Err(DeserializationError::UnsupportedFormatVersion(other)),
+            },
+            None => Record::decode_compact_borrow(bytes),
+        }
+    }
+}
+
+fn is_bincode(bytes: &[u8]) -> Result<bool, DeserializationError> {
+    let first_byte = *bytes.first().ok_or(DeserializationError::EmptyInput)?;
+    Ok(first_byte & HEADER_TAG == 0)
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DeserializationError> {
+        if self.0.len() < len {
+            return Err(DeserializationError::BadDataLength);
+        }
+        let (result, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(result)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], DeserializationError> {
+        Ok(self.take(N)?.try_into().expect("Took N bytes"))
+    }
+}
@@ -0,0 +1,236 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use super::{FieldDefinition, FieldType, Schema};
+
+/// A change from one version of a schema to the next. Fields are matched by name, so a renamed field is removed and added.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum SchemaChange {
+    /// A field of the new schema, at `index`, is not in the old one.
+    FieldAdded {
+        index: usize,
+        field: FieldDefinition,
+    },
+    /// A field of the old schema, at `index`, is not in the new one.
+    FieldRemoved {
+        index: usize,
+        field: FieldDefinition,
+    },
+    /// A field moved from `from` in the old schema to `to` in the new one.
+    FieldMoved {
+        name: String,
+        from: usize,
+        to: usize,
+    },
+    TypeChanged {
+        name: String,
+        from: FieldType,
+        to: FieldType,
+    },
+    NullabilityChanged {
+        name: String,
+        nullable: bool,
+    },
+    /// The primary key fields, by name, changed.
+    PrimaryKeyChanged {
+        from: Vec<String>,
+        to: Vec<String>,
+    },
+}
+
+/// Whether data written with one schema can be read with the other.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum Compatibility {
+    /// Both backward and forward compatible.
+    Full,
+    /// Readers of the new schema can read data written with the old one.
+    Backward,
+    /// Readers of the old schema can read data written with the new one.
+    Forward,
+    /// Neither backward nor forward compatible.
+    Breaking,
+}
+
+impl Compatibility {
+    fn new(backward: bool, forward: bool) -> Self {
+        match (backward, forward) {
+            (true, true) => Compatibility::Full,
+            (true, false) => Compatibility::Backward,
+            (false, true) => Compatibility::Forward,
+            (false, false) => Compatibility::Breaking,
+        }
+    }
+
+    pub fn is_backward(&self) -> bool {
+        matches!(self, Compatibility::Full | Compatibility::Backward)
+    }
+
+    pub fn is_forward(&self) -> bool {
+        matches!(self, Compatibility::Full | Compatibility::Forward)
+    }
+
+    /// The compatibility of applying both `self` and `other`.
+    pub fn and(self, other: Compatibility) -> Self {
+        Compatibility::new(
+            self.is_backward() && other.is_backward(),
+            self.is_forward() && other.is_forward(),
+        )
+    }
+}
+
+impl SchemaChange {
+    /// The compatibility of this change on its own.
+    ///
+    /// Readers fill fields missing from the data with `Null`, ignore fields they don't know, map fields by name,
+    /// and can read a type as any of its widenings, see `is_widening`.
+    /// Primary key changes are breaking, because records are identified by their primary key.
+    pub fn compatibility(&self) -> Compatibility {
+        match self {
+            SchemaChange::FieldAdded { field, .. } => Compatibility::new(field.nullable, true),
+            SchemaChange::FieldRemoved { field, .. } => Compatibility::new(true, field.nullable),
+            SchemaChange::FieldMoved { .. } => Compatibility::Full,
+            SchemaChange::TypeChanged { from, to, .. } => {
+                Compatibility::new(is_widening(*from, *to), is_widening(*to, *from))
+            }
+            SchemaChange::NullabilityChanged { nullable, .. } => {
+                Compatibility::new(*nullable, !*nullable)
+            }
+            SchemaChange::PrimaryKeyChanged { .. } => Compatibility::Breaking,
+        }
+    }
+}
+
+impl Display for SchemaChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaChange::FieldAdded { index, field } => {
+                write!(
+                    f,
+                    "Field {} of type {} added at {index}",
+                    field.name, field.typ
+                )
+            }
+            SchemaChange::FieldRemoved { index, field } => {
+                write!(f, "Field {} removed from {index}", field.name)
+            }
+            SchemaChange::FieldMoved { name, from, to } => {
+                write!(f, "Field {name} moved from {from} to {to}")
+            }
+            SchemaChange::TypeChanged { name, from, to } => {
+                write!(f, "Type of field {name} changed from {from} to {to}")
+            }
+            SchemaChange::NullabilityChanged { name, nullable } => {
+                let nullable = if *nullable {
+                    "nullable"
+                } else {
+                    "not nullable"
+                };
+                write!(f, "Field {name} became {nullable}")
+            }
+            SchemaChange::PrimaryKeyChanged { from, to } => {
+                write!(f, "Primary key changed from {from:?} to {to:?}")
+            }
+        }
+    }
+}
+
+/// Returns true if every value of `from` converts to `to` without loss.
+pub fn is_widening(from: FieldType, to: FieldType) -> bool {
+    matches!(
+        (from, to),
+        (FieldType::UInt, FieldType::Decimal)
+            | (FieldType::Int, FieldType::Decimal)
+            | (FieldType::UInt, FieldType::Decimal128)
+            | (FieldType::Int, FieldType::Decimal128)
+            | (FieldType::Decimal, FieldType::Decimal128)
+            | (FieldType::String, FieldType::Text)
+            | (FieldType::Text, FieldType::String)
+            | (FieldType::Date, FieldType::Timestamp)
+    )
+}
+
+/// The changes from an old schema to a new one.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub struct SchemaDiff {
+    pub changes: Vec<SchemaChange>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The compatibility of all changes together. Identical schemas are fully compatible.
+    pub fn compatibility(&self) -> Compatibility {
+        self.changes
+            .iter()
+            .fold(Compatibility::Full, |compatibility, change| {
+                compatibility.and(change.compatibility())
+            })
+    }
+}
+
+impl Schema {
+    /// The changes from `self` to `new`. Identifiers are not compared.
+    pub fn diff(&self, new: &Schema) -> SchemaDiff {
+        let mut changes = vec![];
+
+        for (index, old_field) in self.fields.iter().enumerate() {
+            let Ok((new_index, new_field)) = new.get_field_index(&old_field.name) else {
+                changes.push(SchemaChange::FieldRemoved {
+                    index,
+                    field: old_field.clone(),
+                });
+                continue;
+            };
+            if index != new_index {
+                changes.push(SchemaChange::FieldMoved {
+                    name: old_field.name.clone(),
+                    from: index,
+                    to: new_index,
+                });
+            }
+            if old_field.typ != new_field.typ {
+                changes.push(SchemaChange::TypeChanged {
+                    name: old_field.name.clone(),
+                    from: old_field.typ,
+                    to: new_field.typ,
+                });
+            }
+            if old_field.nullable != new_field.nullable {
+                changes.push(SchemaChange::NullabilityChanged {
+                    name: old_field.name.clone(),
+                    nullable: new_field.nullable,
+                });
+            }
+        }
+
+        for (index, new_field) in new.fields.iter().enumerate() {
+            if self.get_field_index(&new_field.name).is_err() {
+                changes.push(SchemaChange::FieldAdded {
+                    index,
+                    field: new_field.clone(),
+                });
+            }
+        }
+
+        let old_key = self.primary_key_names();
+        let new_key = new.primary_key_names();
+        if old_key != new_key {
+            changes.push(SchemaChange::PrimaryKeyChanged {
+                from: old_key,
+                to: new_key,
+            });
+        }
+
+        SchemaDiff { changes }
+    }
+
+    fn primary_key_names(&self) -> Vec<String> {
+        self.primary_index
+            .iter()
+            .map(|i| self.fields[*i].name.clone())
+            .collect()
+    }
+}
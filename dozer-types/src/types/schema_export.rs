@@ -0,0 +1,144 @@
+//! Exports of `Schema` for API documentation and for sinks that create their target tables.
+
+use serde_json::{json, Map, Value};
+
+use super::{FieldDefinition, FieldType, Schema};
+
+const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+impl Schema {
+    /// A JSON Schema document titled `title` for the records that `Record::to_json` writes.
+    ///
+    /// Every field is required, because `to_json` writes nulls too. Nullable fields also accept `null`. Field metadata
+    /// adds the description, default and maximum length that the source reports.
+    pub fn to_json_schema(&self, title: &str) -> Value {
+        let properties = self
+            .fields
+            .iter()
+            .map(|field| (field.name.clone(), field_json_schema(field)))
+            .collect::<Map<_, _>>();
+        let required = self
+            .fields
+            .iter()
+            .map(|field| Value::from(field.name.as_str()))
+            .collect::<Vec<_>>();
+        json!({
+            "$schema": JSON_SCHEMA_DIALECT,
+            "title": title,
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        })
+    }
+
+    /// A `CREATE TABLE` statement for `table_name`, in the PostgreSQL dialect.
+    ///
+    /// Identifiers are always quoted. Non-nullable fields are `NOT NULL`, and the primary index becomes the
+    /// `PRIMARY KEY`. Precision, scale and maximum length come from the field metadata, if the source reports them.
+    pub fn to_create_table(&self, table_name: &str) -> String {
+        let mut columns = self
+            .fields
+            .iter()
+            .map(|field| {
+                let mut column = format!("{} {}", quote_identifier(&field.name), sql_type(field));
+                if !field.nullable {
+                    column.push_str(" NOT NULL");
+                }
+                column
+            })
+            .collect::<Vec<_>>();
+        if !self.primary_index.is_empty() {
+            let keys = self
+                .primary_index
+                .iter()
+                .map(|index| quote_identifier(&self.fields[*index].name))
+                .collect::<Vec<_>>();
+            columns.push(format!("PRIMARY KEY ({})", keys.join(", ")));
+        }
+        format!(
+            "CREATE TABLE {} (\n    {}\n)",
+            quote_identifier(table_name),
+            columns.join(",\n    ")
+        )
+    }
+}
+
+/// The JSON Schema of the JSON that `Field::to_json` writes for `field`.
+fn field_json_schema(field: &FieldDefinition) -> Value {
+    let mut schema = match field.typ {
+        FieldType::UInt => json!({ "type": "integer", "minimum": 0 }),
+        FieldType::Int => json!({ "type": "integer" }),
+        FieldType::Float => json!({
+            "oneOf": [{ "type": "number" }, { "enum": ["NaN", "inf", "-inf"] }],
+        }),
+        FieldType::Boolean => json!({ "type": "boolean" }),
+        FieldType::String | FieldType::Text => match field.metadata.max_length {
+            Some(max_length) => json!({ "type": "string", "maxLength": max_length }),
+            None => json!({ "type": "string" }),
+        },
+        FieldType::Binary | FieldType::Bson => {
+            json!({ "type": "string", "contentEncoding": "base64" })
+        }
+        FieldType::Decimal | FieldType::Decimal128 => {
+            json!({ "type": "string", "pattern": "^-?[0-9]+(\\.[0-9]+)?$" })
+        }
+        FieldType::Timestamp => json!({ "type": "string", "format": "date-time" }),
+        FieldType::Date => json!({ "type": "string", "format": "date" }),
+        FieldType::Point => json!({
+            "type": "object",
+            "properties": { "x": { "type": "number" }, "y": { "type": "number" } },
+            "required": ["x", "y"],
+            "additionalProperties": false,
+        }),
+        FieldType::Duration => json!({ "type": "string" }),
+        FieldType::Geometry => json!({ "type": "string", "contentEncoding": "base16" }),
+    };
+    if field.nullable {
+        schema = json!({ "anyOf": [schema, { "type": "null" }] });
+    }
+
+    let object = schema.as_object_mut().expect("Schemas are objects");
+    if let Some(description) = &field.metadata.description {
+        object.insert("description".to_string(), Value::from(description.as_str()));
+    }
+    if let Some(default) = &field.metadata.default_value {
+        object.insert("default".to_string(), default.to_json());
+    }
+    schema
+}
+
+/// The PostgreSQL type of `field`.
+///
+/// `UInt` is `NUMERIC(20)`, because `BIGINT` can't hold every `u64`, and `Bson` is `JSONB`.
+fn sql_type(field: &FieldDefinition) -> String {
+    let metadata = &field.metadata;
+    match field.typ {
+        FieldType::UInt => "NUMERIC(20)".to_string(),
+        FieldType::Int => "BIGINT".to_string(),
+        FieldType::Float => "DOUBLE PRECISION".to_string(),
+        FieldType::Boolean => "BOOLEAN".to_string(),
+        FieldType::String => match metadata.max_length {
+            Some(max_length) => format!("VARCHAR({max_length})"),
+            None => "VARCHAR".to_string(),
+        },
+        FieldType::Text => "TEXT".to_string(),
+        FieldType::Binary => "BYTEA".to_string(),
+        FieldType::Decimal | FieldType::Decimal128 => match (metadata.precision, metadata.scale) {
+            (Some(precision), Some(scale)) => format!("NUMERIC({precision}, {scale})"),
+            (Some(precision), None) => format!("NUMERIC({precision})"),
+            _ => "NUMERIC".to_string(),
+        },
+        FieldType::Timestamp => "TIMESTAMPTZ".to_string(),
+        FieldType::Date => "DATE".to_string(),
+        FieldType::Bson => "JSONB".to_string(),
+        FieldType::Point => "POINT".to_string(),
+        FieldType::Duration => "INTERVAL".to_string(),
+        FieldType::Geometry => "GEOMETRY".to_string(),
+    }
+}
+
+/// `identifier` in double quotes, with its double quotes doubled.
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
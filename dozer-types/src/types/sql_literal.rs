@@ -0,0 +1,164 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use ordered_float::OrderedFloat;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+use crate::errors::types::TypeError;
+
+use super::field_text::from_hex;
+use super::{
+    DozerDecimal128, DozerDuration, DozerGeometry, DozerPoint, Field, FieldType, DATE_FORMAT,
+};
+
+/// Formats of timestamps without an offset, which are in UTC.
+const NAIVE_TIMESTAMP_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"];
+/// Formats of timestamps with an offset such as `+05:30`, `+0530` or `+05`.
+const TIMESTAMP_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S%.f%#z", "%Y-%m-%dT%H:%M:%S%.f%#z"];
+
+impl Field {
+    /// Parses a SQL literal of type `typ`.
+    ///
+    /// `NULL` in any case is `Null` for every type. Strings are in single quotes, with `''` for a quote, and may
+    /// follow the name of their type, as in `DATE '2020-01-01'`:
+    ///
+    /// | Type | Literal |
+    /// |---|---|
+    /// | `UInt`, `Int`, `Float`, `Decimal`, `Decimal128` | Numbers such as `-1`, `1.5` or `1e3`, quoted or not. Integers can't have fractions. |
+    /// | `Boolean` | `TRUE` or `FALSE` in any case, or quoted `true`, `false`, `t`, `f`, `1` or `0`. |
+    /// | `String`, `Text` | Strings. |
+    /// | `Binary`, `Bson` | Hexadecimal strings after `X`, as in `X'DEAD'`, or after `\x`, as in `'\xDEAD'`. |
+    /// | `Timestamp` | ISO 8601 date and time strings after nothing, `TIMESTAMP` or `TIMESTAMPTZ`. Without an offset, they are in UTC, and dates alone are at midnight. |
+    /// | `Date` | `%Y-%m-%d` strings after nothing or `DATE`. |
+    /// | `Point` | `(x,y)` strings after nothing or `POINT`. |
+    /// | `Duration` | Strings such as `1 hour 30 minutes` after nothing or `INTERVAL`, see `DozerDuration`. |
+    /// | `Geometry` | Hexadecimal EWKB strings after nothing or `GEOMETRY`. |
+    pub fn from_sql_literal(literal: &str, typ: FieldType) -> Result<Field, TypeError> {
+        let literal = literal.trim();
+        if literal.eq_ignore_ascii_case("null") {
+            return Ok(Field::Null);
+        }
+        let invalid = || TypeError::InvalidFieldValue {
+            field_type: typ,
+            nullable: true,
+            value: literal.to_string(),
+        };
+
+        let (prefix, string) = split_literal(literal).ok_or_else(invalid)?;
+        let prefix = prefix.map(str::to_ascii_uppercase);
+        // The string of a literal with one of `prefixes`, or without a prefix.
+        let quoted = |prefixes: &[&str]| match (&prefix, &string) {
+            (None, Some(string)) => Ok(string.as_str()),
+            (Some(prefix), Some(string)) if prefixes.iter().any(|p| *p == prefix.as_str()) => {
+                Ok(string.as_str())
+            }
+            _ => Err(invalid()),
+        };
+        // A number, quoted or not.
+        let number = || match (&prefix, &string) {
+            (None, Some(string)) => Ok(string.trim()),
+            (None, None) => Ok(literal),
+            _ => Err(invalid()),
+        };
+
+        match typ {
+            FieldType::UInt => number()?.parse().map(Field::UInt).map_err(|_| invalid()),
+            FieldType::Int => number()?.parse().map(Field::Int).map_err(|_| invalid()),
+            FieldType::Float => number()?
+                .parse()
+                .map(|f| Field::Float(OrderedFloat(f)))
+                .map_err(|_| invalid()),
+            FieldType::Decimal => {
+                let number = number()?;
+                Decimal::from_str(number)
+                    .or_else(|_| Decimal::from_scientific(number))
+                    .map(Field::Decimal)
+                    .map_err(|_| invalid())
+            }
+            FieldType::Decimal128 => number()?
+                .parse::<DozerDecimal128>()
+                .map(Field::Decimal128)
+                .map_err(|_| invalid()),
+            FieldType::Boolean => match (&prefix, &string) {
+                (None, None) if literal.eq_ignore_ascii_case("true") => Ok(true),
+                (None, None) if literal.eq_ignore_ascii_case("false") => Ok(false),
+                (None, Some(string)) => match string.trim().to_ascii_lowercase().as_str() {
+                    "true" | "t" | "1" => Ok(true),
+                    "false" | "f" | "0" => Ok(false),
+                    _ => Err(invalid()),
+                },
+                _ => Err(invalid()),
+            }
+            .map(Field::Boolean),
+            FieldType::String => quoted(&[]).map(|s| Field::String(s.to_string())),
+            FieldType::Text => quoted(&[]).map(|s| Field::Text(s.to_string())),
+            FieldType::Binary | FieldType::Bson => {
+                let hex = match (prefix.as_deref(), &string) {
+                    (Some("X"), Some(string)) => string.as_str(),
+                    (None, Some(string)) => string.strip_prefix("\\x").ok_or_else(invalid)?,
+                    _ => return Err(invalid()),
+                };
+                let bytes = from_hex(hex).ok_or_else(invalid)?;
+                Ok(if typ == FieldType::Binary {
+                    Field::Binary(bytes)
+                } else {
+                    Field::Bson(bytes)
+                })
+            }
+            FieldType::Timestamp => parse_timestamp(quoted(&["TIMESTAMP", "TIMESTAMPTZ"])?.trim())
+                .map(Field::Timestamp)
+                .ok_or_else(invalid),
+            FieldType::Date => NaiveDate::parse_from_str(quoted(&["DATE"])?.trim(), DATE_FORMAT)
+                .map(Field::Date)
+                .map_err(|_| invalid()),
+            FieldType::Point => quoted(&["POINT"])?.parse::<DozerPoint>().map(Field::Point),
+            FieldType::Duration => quoted(&["INTERVAL"])?
+                .parse::<DozerDuration>()
+                .map(Field::Duration),
+            FieldType::Geometry => quoted(&["GEOMETRY"])?
+                .trim()
+                .parse::<DozerGeometry>()
+                .map(Field::Geometry),
+        }
+    }
+}
+
+/// Splits `literal` into its prefix and unquoted string, if it has them.
+///
+/// Returns `None` if the quotes are unbalanced.
+fn split_literal(literal: &str) -> Option<(Option<&str>, Option<String>)> {
+    let Some(start) = literal.find('\'') else {
+        return Some((None, None));
+    };
+    let prefix = literal[..start].trim();
+    let quoted = &literal[start..];
+    if quoted.len() < 2 || !quoted.ends_with('\'') {
+        return None;
+    }
+    let inner = &quoted[1..quoted.len() - 1];
+    // Every quote inside must be doubled.
+    if inner.replace("''", "").contains('\'') {
+        return None;
+    }
+    let prefix = (!prefix.is_empty()).then_some(prefix);
+    Some((prefix, Some(inner.replace("''", "'"))))
+}
+
+fn parse_timestamp(s: &str) -> Option<DateTime<FixedOffset>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(s) {
+        return Some(timestamp);
+    }
+    for format in TIMESTAMP_FORMATS {
+        if let Ok(timestamp) = DateTime::parse_from_str(s, format) {
+            return Some(timestamp);
+        }
+    }
+    let naive = NAIVE_TIMESTAMP_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, DATE_FORMAT)
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+    Some(Utc.from_utc_datetime(&naive).into())
+}